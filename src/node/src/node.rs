@@ -13,13 +13,19 @@
 // limitations under the License.
 
 use crate::query_listener::SmolQueryListener;
+use kernel::SystemResult;
+use protocol::results::QueryResults;
 use protocol::{listener::Secure, Command, QueryListener};
 use smol::Task;
 use sql_engine::Handler;
-use std::sync::{
-    atomic::{AtomicU8, Ordering},
-    Arc, Mutex,
+use std::{
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Arc,
+    },
+    thread,
 };
+use storage::backend::BackendStorage;
 
 const PORT: usize = 5432;
 const HOST: &str = "0.0.0.0";
@@ -53,20 +59,37 @@ impl Node {
         let local_address = format!("{}:{}", HOST, PORT);
         log::debug!("Starting server on {}", local_address);
 
+        // connections are served on every executor thread so that queries on
+        // different tables are not serialized by a single thread
+        for _ in 1..num_cpus::get().max(1) {
+            thread::spawn(|| smol::run(std::future::pending::<()>()));
+        }
+
         smol::run(async {
             let listener = SmolQueryListener::bind(local_address, Secure::none())
                 .await
                 .expect("open server connection");
             self.state.store(RUNNING, Ordering::SeqCst);
 
-            let storage = Arc::new(Mutex::new(storage::frontend::FrontendStorage::default().unwrap()));
+            let storage = Arc::new(storage::frontend::FrontendStorage::default().unwrap());
 
             log::debug!("waiting for connections");
-            while let Ok(mut connection) = listener.accept().await.expect("no io errors") {
+            loop {
+                // client that failed to connect does not stop the server
+                let mut connection = match listener.accept().await {
+                    Ok(Ok(connection)) => connection,
+                    Ok(Err(error)) => {
+                        log::error!("client can't connect: {:?}", error);
+                        continue;
+                    }
+                    Err(error) => {
+                        log::error!("client can't connect: {:?}", error);
+                        continue;
+                    }
+                };
                 if self.state() == STOPPED {
                     return;
                 }
-                let state = self.state.clone();
                 let storage = storage.clone();
                 Task::spawn(async move {
                     let mut sql_handler = Handler::new(storage);
@@ -75,33 +98,159 @@ impl Node {
                     loop {
                         match connection.receive().await {
                             Err(e) => {
-                                log::debug!("SHOULD STOP");
                                 log::error!("UNEXPECTED ERROR: {:?}", e);
-                                state.store(STOPPED, Ordering::SeqCst);
-                                return;
+                                break;
                             }
+                            // client is already told about protocol violation,
+                            // only its connection is closed
                             Ok(Err(e)) => {
-                                log::debug!("SHOULD STOP");
-                                log::error!("UNEXPECTED ERROR: {:?}", e);
-                                state.store(STOPPED, Ordering::SeqCst);
-                                return;
+                                log::error!("protocol violation: {:?}", e);
+                                break;
                             }
                             Ok(Ok(Command::Terminate)) => {
                                 log::debug!("Closing connection with client");
                                 break;
                             }
-                            Ok(Ok(Command::Query(sql_query))) => {
-                                let response = sql_handler.execute(sql_query.as_str()).expect("no system error");
+                            Ok(Ok(Command::Sync)) => sql_handler.sync(),
+                            Ok(Ok(command)) => {
+                                let (handler, response) = execute(sql_handler, command).await;
+                                sql_handler = handler;
+                                let response = response.expect("no system error");
                                 match connection.send(response).await {
                                     Ok(()) => {}
                                     Err(error) => eprintln!("{:?}", error), // break Err(SystemError::io(error)),
                                 }
                             }
                         }
+                        // transaction fails when its results are sent
+                        connection.set_transaction_status(sql_handler.transaction_status());
                     }
+                    close(sql_handler).await;
                 })
                 .detach();
             }
         });
     }
 }
+
+// statements wait for locks that are held by other sessions, so they are
+// executed on the blocking thread pool not to occupy executor threads that
+// serve the session holding the lock
+async fn execute<P: BackendStorage + 'static>(
+    mut handler: Handler<P>,
+    command: Command,
+) -> (Handler<P>, SystemResult<QueryResults>) {
+    smol::blocking!({
+        let response = match command {
+            Command::Query(sql_query) => handler.execute(sql_query.as_str()),
+            Command::Parse {
+                statement_name,
+                sql,
+                param_types,
+            } => handler.parse(statement_name, sql, param_types),
+            Command::Bind {
+                portal_name,
+                statement_name,
+                param_formats,
+                raw_params,
+                result_formats,
+            } => handler.bind(portal_name, statement_name, param_formats, raw_params, result_formats),
+            Command::DescribeStatement { name } => handler.describe_statement(&name),
+            Command::DescribePortal { name } => handler.describe_portal(&name),
+            Command::Execute { portal_name, max_rows } => handler.execute_portal(&portal_name, max_rows),
+            Command::CloseStatement { name } => handler.close_statement(&name),
+            Command::ClosePortal { name } => handler.close_portal(&name),
+            Command::Sync | Command::Terminate => unreachable!("handled by connection loop"),
+        };
+        (handler, response)
+    })
+}
+
+// transaction of the closed connection is rolled back when its handler is
+// dropped, rollback waits for readers of changed tables
+async fn close<P: BackendStorage + 'static>(handler: Handler<P>) {
+    smol::blocking!(drop(handler))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::results::{QueryEvent, QueryResult};
+    use std::time::Duration;
+    use storage::{
+        frontend::FrontendStorage,
+        lock::{LockDuration, LockMode, Resource},
+    };
+
+    async fn query<P: BackendStorage + 'static>(handler: Handler<P>, sql: &str) -> (Handler<P>, Vec<QueryResult>) {
+        let (handler, response) = execute(handler, Command::Query(sql.to_owned())).await;
+        (handler, response.expect("no system error").collect())
+    }
+
+    #[test]
+    fn sessions_waiting_for_lock_do_not_block_executor() {
+        // `smol::run` on the test thread is the only executor thread
+        smol::run(async {
+            let storage = Arc::new(FrontendStorage::default().expect("storage is created"));
+            let (holder, _) = query(
+                Handler::new(storage.clone()),
+                "create schema schema_name; create table schema_name.table_name (column_test smallint);",
+            )
+            .await;
+            let (holder, _) = query(
+                holder,
+                "begin; lock table schema_name.table_name in access exclusive mode;",
+            )
+            .await;
+
+            let waiters = (0..4)
+                .map(|_| {
+                    let storage = storage.clone();
+                    Task::spawn(async move {
+                        query(Handler::new(storage), "select * from schema_name.table_name;")
+                            .await
+                            .1
+                    })
+                })
+                .collect::<Vec<Task<Vec<QueryResult>>>>();
+            smol::Timer::after(Duration::from_millis(100)).await;
+
+            let (_holder, results) = query(holder, "commit;").await;
+            assert_eq!(results, vec![Ok(QueryEvent::TransactionCommitted)]);
+            for waiter in waiters {
+                assert_eq!(waiter.await.last(), Some(&Ok(QueryEvent::RecordsSelected(0))));
+            }
+        })
+    }
+
+    #[test]
+    fn rollback_of_closed_connection_does_not_block_executor() {
+        smol::run(async {
+            let storage = Arc::new(FrontendStorage::default().expect("storage is created"));
+            let (writer, _) = query(
+                Handler::new(storage.clone()),
+                "create schema schema_name; create table schema_name.table_name (column_test smallint);",
+            )
+            .await;
+            let (writer, _) = query(writer, "begin; insert into schema_name.table_name values (1);").await;
+            let reader = storage.lock_manager().new_session();
+            storage
+                .lock_manager()
+                .lock(
+                    reader,
+                    Resource::rows("schema_name", "table_name"),
+                    LockMode::AccessShare,
+                    LockDuration::Transaction,
+                )
+                .expect("rows are locked");
+
+            let closed = Task::spawn(close(writer));
+            smol::Timer::after(Duration::from_millis(100)).await;
+            storage.lock_manager().unlock_all(reader);
+            closed.await;
+
+            let (_handler, results) = query(Handler::new(storage), "select * from schema_name.table_name;").await;
+            assert_eq!(results.last(), Some(&Ok(QueryEvent::RecordsSelected(0))));
+        })
+    }
+}
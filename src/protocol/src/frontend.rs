@@ -0,0 +1,255 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{sql_types::PostgreSqlFormat, Command, Error, Result};
+use byteorder::{ByteOrder, NetworkEndian};
+
+pub(crate) const BIND: u8 = b'B';
+pub(crate) const CLOSE: u8 = b'C';
+pub(crate) const DESCRIBE: u8 = b'D';
+pub(crate) const EXECUTE: u8 = b'E';
+pub(crate) const FLUSH: u8 = b'H';
+pub(crate) const PARSE: u8 = b'P';
+pub(crate) const QUERY: u8 = b'Q';
+pub(crate) const SYNC: u8 = b'S';
+pub(crate) const TERMINATE: u8 = b'X';
+
+// target of Describe and Close messages
+const STATEMENT: u8 = b'S';
+const PORTAL: u8 = b'P';
+
+/// decodes command from the message body, messages that are not commands
+/// (e.g. Flush) have to be handled before
+pub(crate) fn decode(tag: u8, body: &[u8]) -> Result<Command> {
+    let mut cursor = Cursor::new(body);
+    let command = match tag {
+        QUERY => Command::Query(cursor.read_str()?),
+        PARSE => {
+            let statement_name = cursor.read_str()?;
+            let sql = cursor.read_str()?;
+            let param_types = (0..cursor.read_i16()?)
+                .map(|_| cursor.read_i32())
+                .collect::<Result<Vec<i32>>>()?;
+            Command::Parse {
+                statement_name,
+                sql,
+                param_types,
+            }
+        }
+        BIND => {
+            let portal_name = cursor.read_str()?;
+            let statement_name = cursor.read_str()?;
+            let param_formats = cursor.read_formats()?;
+            let raw_params = (0..cursor.read_i16()?)
+                .map(|_| match cursor.read_i32()? {
+                    -1 => Ok(None),
+                    len if len >= 0 => cursor.read_bytes(len as usize).map(|bytes| Some(bytes.to_vec())),
+                    _ => Err(Error::MalformedMessage),
+                })
+                .collect::<Result<Vec<Option<Vec<u8>>>>>()?;
+            let result_formats = cursor.read_formats()?;
+            Command::Bind {
+                portal_name,
+                statement_name,
+                param_formats,
+                raw_params,
+                result_formats,
+            }
+        }
+        DESCRIBE => match cursor.read_byte()? {
+            STATEMENT => Command::DescribeStatement {
+                name: cursor.read_str()?,
+            },
+            PORTAL => Command::DescribePortal {
+                name: cursor.read_str()?,
+            },
+            _ => return Err(Error::MalformedMessage),
+        },
+        EXECUTE => Command::Execute {
+            portal_name: cursor.read_str()?,
+            max_rows: cursor.read_i32()?,
+        },
+        CLOSE => match cursor.read_byte()? {
+            STATEMENT => Command::CloseStatement {
+                name: cursor.read_str()?,
+            },
+            PORTAL => Command::ClosePortal {
+                name: cursor.read_str()?,
+            },
+            _ => return Err(Error::MalformedMessage),
+        },
+        SYNC => Command::Sync,
+        TERMINATE => Command::Terminate,
+        _ => return Err(Error::UnsupportedRequest),
+    };
+    if cursor.is_empty() {
+        Ok(command)
+    } else {
+        Err(Error::MalformedMessage)
+    }
+}
+
+struct Cursor<'b> {
+    buf: &'b [u8],
+}
+
+impl<'b> Cursor<'b> {
+    fn new(buf: &'b [u8]) -> Self {
+        Self { buf }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'b [u8]> {
+        if self.buf.len() < len {
+            return Err(Error::MalformedMessage);
+        }
+        let (bytes, rest) = self.buf.split_at(len);
+        self.buf = rest;
+        Ok(bytes)
+    }
+
+    fn read_byte(&mut self) -> Result<u8> {
+        self.read_bytes(1).map(|bytes| bytes[0])
+    }
+
+    fn read_i16(&mut self) -> Result<i16> {
+        self.read_bytes(2).map(NetworkEndian::read_i16)
+    }
+
+    fn read_i32(&mut self) -> Result<i32> {
+        self.read_bytes(4).map(NetworkEndian::read_i32)
+    }
+
+    // null terminated string
+    fn read_str(&mut self) -> Result<String> {
+        let end = match self.buf.iter().position(|byte| *byte == 0) {
+            Some(end) => end,
+            None => return Err(Error::MalformedMessage),
+        };
+        let bytes = self.read_bytes(end + 1)?;
+        String::from_utf8(bytes[..end].to_vec()).map_err(|_| Error::QueryIsNotValidUtfString)
+    }
+
+    fn read_formats(&mut self) -> Result<Vec<PostgreSqlFormat>> {
+        (0..self.read_i16()?)
+            .map(|_| PostgreSqlFormat::from_code(self.read_i16()?).ok_or(Error::MalformedMessage))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query() {
+        assert_eq!(
+            decode(QUERY, b"select 1;\0"),
+            Ok(Command::Query("select 1;".to_owned()))
+        );
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            decode(PARSE, b"stmt\0select $1;\0\0\x01\0\0\0\x17"),
+            Ok(Command::Parse {
+                statement_name: "stmt".to_owned(),
+                sql: "select $1;".to_owned(),
+                param_types: vec![23],
+            })
+        );
+    }
+
+    #[test]
+    fn bind() {
+        assert_eq!(
+            decode(
+                BIND,
+                b"portal\0stmt\0\0\x01\0\x01\0\x02\0\0\0\x02\0\x01\xff\xff\xff\xff\0\x01\0\x01"
+            ),
+            Ok(Command::Bind {
+                portal_name: "portal".to_owned(),
+                statement_name: "stmt".to_owned(),
+                param_formats: vec![PostgreSqlFormat::Binary],
+                raw_params: vec![Some(vec![0, 1]), None],
+                result_formats: vec![PostgreSqlFormat::Binary],
+            })
+        );
+    }
+
+    #[test]
+    fn bind_with_unknown_format() {
+        assert_eq!(decode(BIND, b"\0\0\0\x01\0\x02\0\0\0\0"), Err(Error::MalformedMessage));
+    }
+
+    #[test]
+    fn describe() {
+        assert_eq!(
+            decode(DESCRIBE, b"Sstmt\0"),
+            Ok(Command::DescribeStatement {
+                name: "stmt".to_owned()
+            })
+        );
+        assert_eq!(
+            decode(DESCRIBE, b"P\0"),
+            Ok(Command::DescribePortal { name: "".to_owned() })
+        );
+    }
+
+    #[test]
+    fn execute() {
+        assert_eq!(
+            decode(EXECUTE, b"portal\0\0\0\0\x0a"),
+            Ok(Command::Execute {
+                portal_name: "portal".to_owned(),
+                max_rows: 10,
+            })
+        );
+    }
+
+    #[test]
+    fn close() {
+        assert_eq!(
+            decode(CLOSE, b"Sstmt\0"),
+            Ok(Command::CloseStatement {
+                name: "stmt".to_owned()
+            })
+        );
+        assert_eq!(
+            decode(CLOSE, b"Pportal\0"),
+            Ok(Command::ClosePortal {
+                name: "portal".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn sync() {
+        assert_eq!(decode(SYNC, b""), Ok(Command::Sync));
+    }
+
+    #[test]
+    fn truncated_message() {
+        assert_eq!(decode(EXECUTE, b"portal\0\0\0"), Err(Error::MalformedMessage));
+    }
+
+    #[test]
+    fn unknown_message() {
+        assert_eq!(decode(b'?', b""), Err(Error::UnsupportedRequest));
+    }
+}
@@ -16,15 +16,24 @@
 //! API for backend implementation of PostgreSQL Wire Protocol
 extern crate log;
 
-use crate::messages::Message;
+use crate::{
+    frontend::FLUSH,
+    messages::{ErrorFields, Message},
+};
 use byteorder::{ByteOrder, NetworkEndian};
 use bytes::BytesMut;
 use futures_util::io::{AsyncReadExt, AsyncWriteExt};
 use std::io;
 
-use crate::results::{QueryEvent, QueryResult};
+use crate::{
+    results::{QueryError, QueryEvent, QueryResult, QueryResults},
+    sql_types::PostgreSqlFormat,
+};
 pub use listener::{QueryListener, ServerListener};
 
+// Frontend messages that client sends after start-up to execute queries
+// see https://www.postgresql.org/docs/12/protocol-message-formats.html
+mod frontend;
 /// Module contains functionality to listen to incoming client connections and
 /// queries
 pub mod listener;
@@ -33,6 +42,8 @@ pub mod listener;
 pub mod messages;
 /// Module contains functionality to represent query result
 pub mod results;
+/// Module contains prepared statements and portals of extended query protocol
+pub mod session;
 /// Module contains functionality to represent SQL type system
 pub mod sql_types;
 
@@ -56,6 +67,10 @@ pub const VERSION_SSL: Version = (1234 << 16) + 5679;
 /// Client initiate `gss` encrypted connection
 pub const VERSION_GSSENC: Version = (1234 << 16) + 5680;
 
+// messages that are longer than 1 GB are rejected as in PostgreSQL, length
+// includes itself
+const MAX_MESSAGE_LENGTH: u32 = 0x3fff_ffff;
+
 /// `Error` type in protocol `Result`. Indicates that something went not well
 #[derive(Debug, PartialEq)]
 pub enum Error {
@@ -67,6 +82,8 @@ pub enum Error {
     UnsupportedRequest,
     /// Indicates that during handshake client sent unrecognized protocol version
     UnrecognizedVersion,
+    /// Indicates that incoming message does not follow its format
+    MalformedMessage,
 }
 
 /// Result of handling incoming bytes from a client
@@ -74,20 +91,98 @@ pub enum Error {
 pub enum Command {
     /// Client commands to execute a `Query`
     Query(String),
+    /// Client commands to prepare a statement with OIDs of its parameter
+    /// types, types that are not specified or specified as 0 are inferred
+    Parse {
+        /// name of the statement, empty name is unnamed statement
+        statement_name: String,
+        /// text of the statement with `$n` parameters
+        sql: String,
+        /// OIDs of parameter types
+        param_types: Vec<i32>,
+    },
+    /// Client commands to create a portal from a prepared statement and values
+    /// of its parameters
+    Bind {
+        /// name of the portal, empty name is unnamed portal
+        portal_name: String,
+        /// name of the prepared statement
+        statement_name: String,
+        /// formats of parameter values: none if all of them are in text, a
+        /// single one is applied to all parameters
+        param_formats: Vec<PostgreSqlFormat>,
+        /// parameter values, `None` is NULL
+        raw_params: Vec<Option<Vec<u8>>>,
+        /// formats of result columns with the same rules as for parameters
+        result_formats: Vec<PostgreSqlFormat>,
+    },
+    /// Client commands to describe parameters and rows of a prepared statement
+    DescribeStatement {
+        /// name of the prepared statement
+        name: String,
+    },
+    /// Client commands to describe rows of a portal
+    DescribePortal {
+        /// name of the portal
+        name: String,
+    },
+    /// Client commands to execute a portal
+    Execute {
+        /// name of the portal
+        portal_name: String,
+        /// maximum number of rows to return, 0 is no limit
+        max_rows: i32,
+    },
+    /// Client commands to close a prepared statement
+    CloseStatement {
+        /// name of the prepared statement
+        name: String,
+    },
+    /// Client commands to close a portal
+    ClosePortal {
+        /// name of the portal
+        name: String,
+    },
+    /// Client commands to finish extended query
+    Sync,
     /// Client commands to terminate current connection
     Terminate,
 }
 
+/// Status of transaction block the backend is in when it is ready for the
+/// next query
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransactionStatus {
+    /// Not in a transaction block
+    Idle,
+    /// In a transaction block
+    InTransaction,
+    /// In a failed transaction block, queries are rejected until the block is ended
+    Failed,
+}
+
 /// Structure to handle client-server PostgreSQL Wire Protocol connection
 pub struct Connection<RW: AsyncReadExt + AsyncWriteExt + Unpin> {
     properties: (Version, Params, SslMode),
     socket: RW,
+    // client waits for ReadyForQuery only after simple query and Sync,
+    // messages of extended query are sent without waiting for responses
+    ready_for_query: bool,
+    // after an error of extended query its messages are skipped until Sync
+    skip_until_sync: bool,
+    transaction_status: TransactionStatus,
 }
 
 impl<RW: AsyncReadExt + AsyncWriteExt + Unpin> Connection<RW> {
     /// Creates new Connection with properties and read-write socket
     pub fn new(properties: (Version, Params, SslMode), socket: RW) -> Connection<RW> {
-        Connection { properties, socket }
+        Connection {
+            properties,
+            socket,
+            ready_for_query: true,
+            skip_until_sync: false,
+            transaction_status: TransactionStatus::Idle,
+        }
     }
 
     /// connection properties tuple
@@ -95,48 +190,95 @@ impl<RW: AsyncReadExt + AsyncWriteExt + Unpin> Connection<RW> {
         &(self.properties)
     }
 
+    /// Sets transaction status that is sent when the connection is ready for
+    /// the next query
+    pub fn set_transaction_status(&mut self, transaction_status: TransactionStatus) {
+        self.transaction_status = transaction_status;
+    }
+
     async fn send_ready_for_query(&mut self) -> io::Result<Result<()>> {
         log::debug!("send ready for query message");
         self.socket
-            .write_all(Message::ReadyForQuery.as_vec().as_slice())
+            .write_all(Message::ReadyForQuery(self.transaction_status).as_vec().as_slice())
             .await?;
         Ok(Ok(()))
     }
 
     /// receives and decodes a command from remote client
     pub async fn receive(&mut self) -> io::Result<Result<Command>> {
-        self.send_ready_for_query().await?.expect("to send ready for query");
-        let mut buffer = [0u8; 1];
-        let tag = self.socket.read_exact(&mut buffer).await.map(|_| buffer[0])?;
-        if b'X' == tag {
-            Ok(Ok(Command::Terminate))
-        } else {
+        if self.ready_for_query {
+            self.send_ready_for_query().await?.expect("to send ready for query");
+            self.ready_for_query = false;
+        }
+        loop {
+            let mut buffer = [0u8; 1];
+            let tag = self.socket.read_exact(&mut buffer).await.map(|_| buffer[0])?;
             let mut buffer = [0u8; 4];
             let len = self
                 .socket
                 .read_exact(&mut buffer)
                 .await
                 .map(|_| NetworkEndian::read_u32(&buffer))?;
+            if !(4..=MAX_MESSAGE_LENGTH).contains(&len) {
+                self.send_protocol_violation(format!("invalid message length {}", len))
+                    .await?;
+                return Ok(Err(Error::MalformedMessage));
+            }
             let mut buffer = BytesMut::with_capacity(len as usize - 4);
             buffer.resize(len as usize - 4, b'0');
-            let sql_buff = self.socket.read_exact(&mut buffer).await.map(|_| buffer)?;
-            log::debug!("FOR TEST sql = {:?}", sql_buff);
-            let sql = match String::from_utf8(sql_buff[..sql_buff.len() - 1].to_vec()) {
-                Ok(sql) => sql,
-                Err(_e) => return Ok(Err(Error::QueryIsNotValidUtfString)),
+            let body = self.socket.read_exact(&mut buffer).await.map(|_| buffer)?;
+            log::debug!("message {:?} {:?}", tag as char, body);
+            let command = match tag {
+                // responses are written as soon as they are produced
+                FLUSH => continue,
+                _ => match frontend::decode(tag, &body) {
+                    Ok(command) => command,
+                    Err(error) => {
+                        let message = match error {
+                            Error::UnsupportedRequest => format!("invalid frontend message type {}", tag),
+                            Error::QueryIsNotValidUtfString => "invalid byte sequence for encoding \"UTF8\"".to_owned(),
+                            _ => "invalid message format".to_owned(),
+                        };
+                        self.send_protocol_violation(message).await?;
+                        return Ok(Err(error));
+                    }
+                },
             };
-            log::debug!("SQL = {}", sql);
-            Ok(Ok(Command::Query(sql)))
+            match command {
+                Command::Sync => {
+                    self.skip_until_sync = false;
+                    self.ready_for_query = true;
+                }
+                Command::Query(_) => self.ready_for_query = true,
+                Command::Terminate => {}
+                _ if self.skip_until_sync => continue,
+                _ => {}
+            }
+            log::debug!("COMMAND = {:?}", command);
+            return Ok(Ok(command));
         }
     }
 
-    /// Sends response messages to client. Most of the time it is a single
-    /// message, select result one of the exceptional situation
-    pub async fn send(&mut self, query_result: QueryResult) -> io::Result<()> {
-        for message in QueryResultMapper::map(query_result) {
-            log::debug!("{:?}", message);
+    // client is told why the connection is going to be closed
+    async fn send_protocol_violation(&mut self, message: String) -> io::Result<()> {
+        for message in QueryResultMapper::map(Err(QueryError::fatal_protocol_violation(message))) {
             self.socket.write_all(message.as_vec().as_slice()).await?;
         }
+        Ok(())
+    }
+
+    /// Sends response messages to client. Most of the time it is a single
+    /// message, select results are sent one by one as they are produced
+    pub async fn send(&mut self, query_results: QueryResults) -> io::Result<()> {
+        for query_result in query_results {
+            if query_result.is_err() && !self.ready_for_query {
+                self.skip_until_sync = true;
+            }
+            for message in QueryResultMapper::map(query_result) {
+                log::debug!("{:?}", message);
+                self.socket.write_all(message.as_vec().as_slice()).await?;
+            }
+        }
         log::debug!("end of the command is sent");
         Ok(())
     }
@@ -158,15 +300,21 @@ pub(crate) struct ColumnMetadata {
     pub type_id: i32,
     /// PostgreSQL data type size
     pub type_size: i16,
+    /// PostgreSQL data type modifier
+    pub type_modifier: i32,
+    /// format of the column values
+    pub format: PostgreSqlFormat,
 }
 
 impl ColumnMetadata {
     /// Creates new column metadata
-    pub fn new(name: String, type_id: i32, type_size: i16) -> Self {
+    pub fn new(name: String, type_id: i32, type_size: i16, type_modifier: i32, format: PostgreSqlFormat) -> Self {
         Self {
             name,
             type_id,
             type_size,
+            type_modifier,
+            format,
         }
     }
 }
@@ -190,35 +338,73 @@ impl QueryResultMapper {
             Ok(QueryEvent::SchemaDropped) => vec![Message::CommandComplete("DROP SCHEMA".to_owned())],
             Ok(QueryEvent::TableCreated) => vec![Message::CommandComplete("CREATE TABLE".to_owned())],
             Ok(QueryEvent::TableDropped) => vec![Message::CommandComplete("DROP TABLE".to_owned())],
+            Ok(QueryEvent::TypeCreated) => vec![Message::CommandComplete("CREATE TYPE".to_owned())],
+            Ok(QueryEvent::TypeDropped) => vec![Message::CommandComplete("DROP TYPE".to_owned())],
+            Ok(QueryEvent::SequenceCreated) => vec![Message::CommandComplete("CREATE SEQUENCE".to_owned())],
+            Ok(QueryEvent::SequenceDropped) => vec![Message::CommandComplete("DROP SEQUENCE".to_owned())],
             Ok(QueryEvent::VariableSet) => vec![Message::CommandComplete("SET".to_owned())],
             Ok(QueryEvent::TransactionStarted) => vec![Message::CommandComplete("BEGIN".to_owned())],
+            Ok(QueryEvent::TransactionCommitted) => vec![Message::CommandComplete("COMMIT".to_owned())],
+            Ok(QueryEvent::TransactionRolledBack) => vec![Message::CommandComplete("ROLLBACK".to_owned())],
+            Ok(QueryEvent::TableLocked) => vec![Message::CommandComplete("LOCK TABLE".to_owned())],
+            Ok(QueryEvent::StatementPrepared) => vec![Message::CommandComplete("PREPARE".to_owned())],
+            Ok(QueryEvent::StatementDeallocated) => vec![Message::CommandComplete("DEALLOCATE".to_owned())],
+            Ok(QueryEvent::AllStatementsDeallocated) => vec![Message::CommandComplete("DEALLOCATE ALL".to_owned())],
             Ok(QueryEvent::RecordsInserted(records)) => vec![Message::CommandComplete(format!("INSERT 0 {}", records))],
-            Ok(QueryEvent::RecordsSelected(projection)) => {
-                let definition = projection.0;
-                let description: Vec<ColumnMetadata> = definition
+            Ok(QueryEvent::RowDescription(description, formats)) => vec![Message::RowDescription(
+                description
                     .into_iter()
-                    .map(|(name, sql_type)| ColumnMetadata::new(name, sql_type.pg_oid(), sql_type.pg_len()))
-                    .collect();
-                let records = projection.1;
-                let len = records.len();
-                let mut messages = vec![Message::RowDescription(description)];
-                for record in records {
-                    messages.push(Message::DataRow(record));
-                }
-                messages.push(Message::CommandComplete(format!("SELECT {}", len)));
-                messages
-            }
+                    .enumerate()
+                    .map(|(index, (name, sql_type))| {
+                        ColumnMetadata::new(
+                            name,
+                            sql_type.pg_oid(),
+                            sql_type.pg_len(),
+                            sql_type.pg_typmod(),
+                            formats.get(index).copied().unwrap_or(PostgreSqlFormat::Text),
+                        )
+                    })
+                    .collect(),
+            )],
+            Ok(QueryEvent::DataRow(record)) => vec![Message::DataRow(record)],
+            Ok(QueryEvent::RecordsSelected(records)) => vec![Message::CommandComplete(format!("SELECT {}", records))],
             Ok(QueryEvent::RecordsUpdated(records)) => vec![Message::CommandComplete(format!("UPDATE {}", records))],
             Ok(QueryEvent::RecordsDeleted(records)) => vec![Message::CommandComplete(format!("DELETE {}", records))],
-            Err(query_error) => vec![Message::ErrorResponse(
-                query_error.severity(),
-                query_error.code(),
-                query_error.message(),
+            Ok(QueryEvent::EmptyQuery) => vec![Message::EmptyQueryResponse],
+            Ok(QueryEvent::ParseComplete) => vec![Message::ParseComplete],
+            Ok(QueryEvent::BindComplete) => vec![Message::BindComplete],
+            Ok(QueryEvent::CloseComplete) => vec![Message::CloseComplete],
+            Ok(QueryEvent::ParameterDescription(param_types)) => vec![Message::ParameterDescription(
+                param_types.iter().map(|sql_type| sql_type.pg_oid()).collect(),
             )],
+            Ok(QueryEvent::NoData) => vec![Message::NoData],
+            Ok(QueryEvent::PortalSuspended) => vec![Message::PortalSuspended],
+            Ok(QueryEvent::Notice(notice)) => vec![Message::NoticeResponse(error_fields(&notice))],
+            Err(query_error) => vec![Message::ErrorResponse(error_fields(&query_error))],
         }
     }
 }
 
+fn error_fields(error: &QueryError) -> ErrorFields {
+    ErrorFields {
+        severity: error.severity(),
+        code: error.code(),
+        message: error.message(),
+        detail: error.detail(),
+        hint: error.hint(),
+        position: error.position(),
+        where_: error.context(),
+        schema: error.schema(),
+        table: error.table(),
+        column: error.column(),
+        data_type: error.data_type(),
+        constraint: error.constraint(),
+        file: error.file(),
+        line: error.line(),
+        routine: error.routine(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -226,7 +412,7 @@ mod tests {
     #[cfg(test)]
     mod mapper {
         use super::*;
-        use crate::{results::QueryError, sql_types::PostgreSqlType};
+        use crate::sql_types::PostgreSqlType;
 
         #[test]
         fn create_schema() {
@@ -260,6 +446,86 @@ mod tests {
             );
         }
 
+        #[test]
+        fn create_type() {
+            assert_eq!(
+                QueryResultMapper::map(Ok(QueryEvent::TypeCreated)),
+                vec![Message::CommandComplete("CREATE TYPE".to_owned())]
+            );
+        }
+
+        #[test]
+        fn drop_type() {
+            assert_eq!(
+                QueryResultMapper::map(Ok(QueryEvent::TypeDropped)),
+                vec![Message::CommandComplete("DROP TYPE".to_owned())]
+            );
+        }
+
+        #[test]
+        fn create_sequence() {
+            assert_eq!(
+                QueryResultMapper::map(Ok(QueryEvent::SequenceCreated)),
+                vec![Message::CommandComplete("CREATE SEQUENCE".to_owned())]
+            );
+        }
+
+        #[test]
+        fn drop_sequence() {
+            assert_eq!(
+                QueryResultMapper::map(Ok(QueryEvent::SequenceDropped)),
+                vec![Message::CommandComplete("DROP SEQUENCE".to_owned())]
+            );
+        }
+
+        #[test]
+        fn commit_transaction() {
+            assert_eq!(
+                QueryResultMapper::map(Ok(QueryEvent::TransactionCommitted)),
+                vec![Message::CommandComplete("COMMIT".to_owned())]
+            );
+        }
+
+        #[test]
+        fn rollback_transaction() {
+            assert_eq!(
+                QueryResultMapper::map(Ok(QueryEvent::TransactionRolledBack)),
+                vec![Message::CommandComplete("ROLLBACK".to_owned())]
+            );
+        }
+
+        #[test]
+        fn lock_table() {
+            assert_eq!(
+                QueryResultMapper::map(Ok(QueryEvent::TableLocked)),
+                vec![Message::CommandComplete("LOCK TABLE".to_owned())]
+            );
+        }
+
+        #[test]
+        fn prepare_statement() {
+            assert_eq!(
+                QueryResultMapper::map(Ok(QueryEvent::StatementPrepared)),
+                vec![Message::CommandComplete("PREPARE".to_owned())]
+            )
+        }
+
+        #[test]
+        fn deallocate_statement() {
+            assert_eq!(
+                QueryResultMapper::map(Ok(QueryEvent::StatementDeallocated)),
+                vec![Message::CommandComplete("DEALLOCATE".to_owned())]
+            )
+        }
+
+        #[test]
+        fn deallocate_all_statements() {
+            assert_eq!(
+                QueryResultMapper::map(Ok(QueryEvent::AllStatementsDeallocated)),
+                vec![Message::CommandComplete("DEALLOCATE ALL".to_owned())]
+            )
+        }
+
         #[test]
         fn insert_record() {
             let records_number = 3;
@@ -270,28 +536,50 @@ mod tests {
         }
 
         #[test]
-        fn select_records() {
-            let projection = (
-                vec![
-                    ("column_name_1".to_owned(), PostgreSqlType::SmallInt),
-                    ("column_name_2".to_owned(), PostgreSqlType::SmallInt),
-                ],
-                vec![
-                    vec!["1".to_owned(), "2".to_owned()],
-                    vec!["3".to_owned(), "4".to_owned()],
-                ],
+        fn row_description() {
+            let description = vec![
+                ("column_name_1".to_owned(), PostgreSqlType::SmallInt),
+                ("column_name_2".to_owned(), PostgreSqlType::SmallInt),
+            ];
+            assert_eq!(
+                QueryResultMapper::map(Ok(QueryEvent::RowDescription(description, vec![]))),
+                vec![Message::RowDescription(vec![
+                    ColumnMetadata::new("column_name_1".to_owned(), 21, 2, -1, PostgreSqlFormat::Text),
+                    ColumnMetadata::new("column_name_2".to_owned(), 21, 2, -1, PostgreSqlFormat::Text)
+                ])]
+            );
+        }
+
+        #[test]
+        fn row_description_with_type_modifiers() {
+            let description = vec![
+                ("column_c".to_owned(), PostgreSqlType::Char(3)),
+                ("column_v".to_owned(), PostgreSqlType::VarChar(Some(5))),
+                ("column_t".to_owned(), PostgreSqlType::VarChar(None)),
+            ];
+            assert_eq!(
+                QueryResultMapper::map(Ok(QueryEvent::RowDescription(description, vec![]))),
+                vec![Message::RowDescription(vec![
+                    ColumnMetadata::new("column_c".to_owned(), 1042, -1, 7, PostgreSqlFormat::Text),
+                    ColumnMetadata::new("column_v".to_owned(), 1043, -1, 9, PostgreSqlFormat::Text),
+                    ColumnMetadata::new("column_t".to_owned(), 1043, -1, -1, PostgreSqlFormat::Text)
+                ])]
+            );
+        }
+
+        #[test]
+        fn data_row() {
+            assert_eq!(
+                QueryResultMapper::map(Ok(QueryEvent::DataRow(vec![Some(b"1".to_vec()), None]))),
+                vec![Message::DataRow(vec![Some(b"1".to_vec()), None])]
             );
+        }
+
+        #[test]
+        fn select_records() {
             assert_eq!(
-                QueryResultMapper::map(Ok(QueryEvent::RecordsSelected(projection))),
-                vec![
-                    Message::RowDescription(vec![
-                        ColumnMetadata::new("column_name_1".to_owned(), 21, 2),
-                        ColumnMetadata::new("column_name_2".to_owned(), 21, 2)
-                    ]),
-                    Message::DataRow(vec!["1".to_owned(), "2".to_owned()]),
-                    Message::DataRow(vec!["3".to_owned(), "4".to_owned()]),
-                    Message::CommandComplete("SELECT 2".to_owned())
-                ]
+                QueryResultMapper::map(Ok(QueryEvent::RecordsSelected(2))),
+                vec![Message::CommandComplete("SELECT 2".to_owned())]
             );
         }
 
@@ -318,11 +606,12 @@ mod tests {
             let schema_name = "some_table_name".to_owned();
             assert_eq!(
                 QueryResultMapper::map(Err(QueryError::schema_already_exists(schema_name.clone()))),
-                vec![Message::ErrorResponse(
-                    Some("ERROR".to_owned()),
-                    Some("42P06".to_owned()),
-                    Some(format!("schema \"{}\" already exists", schema_name)),
-                )]
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("42P06".to_owned()),
+                    message: Some(format!("schema \"{}\" already exists", schema_name)),
+                    ..ErrorFields::default()
+                })]
             )
         }
 
@@ -331,11 +620,12 @@ mod tests {
             let schema_name = "some_table_name".to_owned();
             assert_eq!(
                 QueryResultMapper::map(Err(QueryError::schema_does_not_exist(schema_name.clone()))),
-                vec![Message::ErrorResponse(
-                    Some("ERROR".to_owned()),
-                    Some("3F000".to_owned()),
-                    Some(format!("schema \"{}\" does not exist", schema_name)),
-                )]
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("3F000".to_owned()),
+                    message: Some(format!("schema \"{}\" does not exist", schema_name)),
+                    ..ErrorFields::default()
+                })]
             )
         }
 
@@ -344,11 +634,12 @@ mod tests {
             let table_name = "some_table_name".to_owned();
             assert_eq!(
                 QueryResultMapper::map(Err(QueryError::table_already_exists(table_name.clone()))),
-                vec![Message::ErrorResponse(
-                    Some("ERROR".to_owned()),
-                    Some("42P07".to_owned()),
-                    Some(format!("table \"{}\" already exists", table_name)),
-                )]
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("42P07".to_owned()),
+                    message: Some(format!("table \"{}\" already exists", table_name)),
+                    ..ErrorFields::default()
+                })]
             )
         }
 
@@ -357,11 +648,12 @@ mod tests {
             let table_name = "some_table_name".to_owned();
             assert_eq!(
                 QueryResultMapper::map(Err(QueryError::table_does_not_exist(table_name.clone()))),
-                vec![Message::ErrorResponse(
-                    Some("ERROR".to_owned()),
-                    Some("42P01".to_owned()),
-                    Some(format!("table \"{}\" does not exist", table_name)),
-                )]
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("42P01".to_owned()),
+                    message: Some(format!("table \"{}\" does not exist", table_name)),
+                    ..ErrorFields::default()
+                })]
             )
         }
 
@@ -371,11 +663,12 @@ mod tests {
                 QueryResultMapper::map(Err(QueryError::column_does_not_exist(vec![
                     "column_not_in_table".to_owned()
                 ]))),
-                vec![Message::ErrorResponse(
-                    Some("ERROR".to_owned()),
-                    Some("42703".to_owned()),
-                    Some("column column_not_in_table does not exist".to_owned()),
-                )]
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("42703".to_owned()),
+                    message: Some("column column_not_in_table does not exist".to_owned()),
+                    ..ErrorFields::default()
+                })]
             )
         }
 
@@ -386,11 +679,726 @@ mod tests {
                     "column_not_in_table1".to_owned(),
                     "column_not_in_table2".to_owned()
                 ]))),
-                vec![Message::ErrorResponse(
-                    Some("ERROR".to_owned()),
-                    Some("42703".to_owned()),
-                    Some("columns column_not_in_table1, column_not_in_table2 do not exist".to_owned()),
-                )]
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("42703".to_owned()),
+                    message: Some("columns column_not_in_table1, column_not_in_table2 do not exist".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn deadlock_detected() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::deadlock_detected())),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("40P01".to_owned()),
+                    message: Some("deadlock detected".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn lock_not_available() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::lock_not_available("table_name".to_owned()))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("55P03".to_owned()),
+                    message: Some("could not obtain lock on relation \"table_name\"".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn lock_timeout() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::lock_timeout())),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("55P03".to_owned()),
+                    message: Some("canceling statement due to lock timeout".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn no_active_transaction() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::no_active_transaction("LOCK TABLE".to_owned()))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("25P01".to_owned()),
+                    message: Some("LOCK TABLE can only be used in transaction blocks".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn invalid_parameter_value() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::invalid_parameter_value(
+                    "lock_timeout".to_owned(),
+                    "abc".to_owned()
+                ))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("22023".to_owned()),
+                    message: Some("invalid value for parameter \"lock_timeout\": \"abc\"".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn transaction_isolation_after_query() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::transaction_isolation_after_query())),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("25001".to_owned()),
+                    message: Some("SET TRANSACTION ISOLATION LEVEL must be called before any query".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn in_failed_transaction() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::in_failed_transaction())),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("25P02".to_owned()),
+                    message: Some(
+                        "current transaction is aborted, commands ignored until end of transaction block".to_owned()
+                    ),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn fatal_protocol_violation() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::fatal_protocol_violation(
+                    "invalid message format".to_owned()
+                ))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("FATAL".to_owned()),
+                    code: Some("08P01".to_owned()),
+                    message: Some("invalid message format".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn division_by_zero() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::division_by_zero())),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("22012".to_owned()),
+                    message: Some("division by zero".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn out_of_range() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::out_of_range("smallint".to_owned()))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("22003".to_owned()),
+                    message: Some("smallint out of range".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn float_overflow() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::float_overflow())),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("22003".to_owned()),
+                    message: Some("value out of range: overflow".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn undefined_operator() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::undefined_operator("boolean + integer".to_owned()))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("42883".to_owned()),
+                    message: Some("operator does not exist: boolean + integer".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn undefined_function() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::undefined_function("now(integer)".to_owned()))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("42883".to_owned()),
+                    message: Some("function now(integer) does not exist".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn not_boolean_argument() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::not_boolean_argument(
+                    "AND".to_owned(),
+                    "integer".to_owned()
+                ))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("42804".to_owned()),
+                    message: Some("argument of AND must be type boolean, not type integer".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn numeric_field_overflow() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::numeric_field_overflow())),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("22003".to_owned()),
+                    message: Some("numeric field overflow".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn invalid_numeric_precision() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::invalid_numeric_precision(0))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("22023".to_owned()),
+                    message: Some("NUMERIC precision 0 must be between 1 and 1000".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn invalid_numeric_scale() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::invalid_numeric_scale(6, 5))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("22023".to_owned()),
+                    message: Some("NUMERIC scale 6 must be between 0 and precision 5".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn invalid_datetime_syntax() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::invalid_datetime_syntax(
+                    "interval".to_owned(),
+                    "soon".to_owned()
+                ))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("22007".to_owned()),
+                    message: Some("invalid input syntax for type interval: \"soon\"".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn datetime_field_overflow() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::datetime_field_overflow("2020-02-30".to_owned()))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("22008".to_owned()),
+                    message: Some("date/time field value out of range: \"2020-02-30\"".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn datetime_out_of_range() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::datetime_out_of_range("timestamp".to_owned()))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("22008".to_owned()),
+                    message: Some("timestamp out of range".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn invalid_text_representation() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::invalid_text_representation(
+                    "json".to_owned(),
+                    "{".to_owned()
+                ))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("22P02".to_owned()),
+                    message: Some("invalid input syntax for type json: \"{\"".to_owned()),
+                    data_type: Some("json".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn odd_number_of_arguments() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::odd_number_of_arguments())),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("22023".to_owned()),
+                    message: Some("argument list must have even number of elements".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn null_object_key() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::null_object_key())),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("22004".to_owned()),
+                    message: Some("null value not allowed for object key".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn not_an_array() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::not_an_array("integer".to_owned()))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("42804".to_owned()),
+                    message: Some("cannot subscript type integer because it is not an array".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn array_subscript_not_integer() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::array_subscript_not_integer())),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("42804".to_owned()),
+                    message: Some("array subscript must have type integer".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn array_required() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::array_required("=".to_owned()))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("42809".to_owned()),
+                    message: Some("op = ANY/ALL (array) requires array on right side".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn type_already_exists() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::type_already_exists("mood".to_owned()))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("42710".to_owned()),
+                    message: Some("type \"mood\" already exists".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn type_does_not_exist() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::type_does_not_exist("mood".to_owned()))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("42704".to_owned()),
+                    message: Some("type \"mood\" does not exist".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn invalid_enum_value() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::invalid_enum_value(
+                    "mood".to_owned(),
+                    "angry".to_owned()
+                ))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("22P02".to_owned()),
+                    message: Some("invalid input value for enum mood: \"angry\"".to_owned()),
+                    data_type: Some("mood".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn dependent_objects_exist() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::dependent_objects_exist("type mood".to_owned()))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("2BP01".to_owned()),
+                    message: Some("cannot drop type mood because other objects depend on it".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn generated_always() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::generated_always("id".to_owned()))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("428C9".to_owned()),
+                    message: Some("cannot insert a non-DEFAULT value into column \"id\"".to_owned()),
+                    detail: Some("Column \"id\" is an identity column defined as GENERATED ALWAYS.".to_owned()),
+                    hint: Some("Use OVERRIDING SYSTEM VALUE to override.".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn sequence_already_exists() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::sequence_already_exists("ids".to_owned()))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("42P07".to_owned()),
+                    message: Some("relation \"ids\" already exists".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn sequence_does_not_exist() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::sequence_does_not_exist("ids".to_owned()))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("42P01".to_owned()),
+                    message: Some("relation \"ids\" does not exist".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn sequence_max_value_reached() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::sequence_max_value_reached("ids".to_owned(), 3))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("2200H".to_owned()),
+                    message: Some("nextval: reached maximum value of sequence \"ids\" (3)".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn sequence_min_value_reached() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::sequence_min_value_reached("ids".to_owned(), -3))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("2200H".to_owned()),
+                    message: Some("nextval: reached minimum value of sequence \"ids\" (-3)".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn sequence_current_value_not_defined() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::sequence_current_value_not_defined("ids".to_owned()))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("55000".to_owned()),
+                    message: Some("currval of sequence \"ids\" is not yet defined in this session".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn sequence_value_out_of_bounds() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::sequence_value_out_of_bounds(
+                    "ids".to_owned(),
+                    0,
+                    1,
+                    10
+                ))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("22003".to_owned()),
+                    message: Some("setval: value 0 is out of bounds for sequence \"ids\" (1..10)".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn cannot_cast() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::cannot_cast("boolean".to_owned(), "date".to_owned()))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("42846".to_owned()),
+                    message: Some("cannot cast type boolean to date".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn datatype_mismatch() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::datatype_mismatch(
+                    "column_b".to_owned(),
+                    "boolean".to_owned(),
+                    "integer".to_owned()
+                ))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("42804".to_owned()),
+                    message: Some(
+                        "column \"column_b\" is of type boolean but expression is of type integer".to_owned()
+                    ),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn value_too_long() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::value_too_long("character varying(5)".to_owned()))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("22001".to_owned()),
+                    message: Some("value too long for type character varying(5)".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn invalid_sequence_parameter() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::invalid_sequence_parameter(
+                    "INCREMENT must not be zero".to_owned()
+                ))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("22023".to_owned()),
+                    message: Some("INCREMENT must not be zero".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn empty_query() {
+            assert_eq!(
+                QueryResultMapper::map(Ok(QueryEvent::EmptyQuery)),
+                vec![Message::EmptyQueryResponse]
+            )
+        }
+
+        #[test]
+        fn parameter_description() {
+            assert_eq!(
+                QueryResultMapper::map(Ok(QueryEvent::ParameterDescription(vec![
+                    PostgreSqlType::Integer,
+                    PostgreSqlType::Text
+                ]))),
+                vec![Message::ParameterDescription(vec![23, 25])]
+            )
+        }
+
+        #[test]
+        fn portal_suspended() {
+            assert_eq!(
+                QueryResultMapper::map(Ok(QueryEvent::PortalSuspended)),
+                vec![Message::PortalSuspended]
+            )
+        }
+
+        #[test]
+        fn duplicate_prepared_statement() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::duplicate_prepared_statement("stmt".to_owned()))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("42P05".to_owned()),
+                    message: Some("prepared statement \"stmt\" already exists".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn portal_does_not_exist() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::portal_does_not_exist("portal".to_owned()))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("34000".to_owned()),
+                    message: Some("portal \"portal\" does not exist".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn wrong_number_of_parameters() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::wrong_number_of_parameters("stmt".to_owned()))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("42601".to_owned()),
+                    message: Some("wrong number of parameters for prepared statement \"stmt\"".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn protocol_violation() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::protocol_violation("invalid message".to_owned()))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("08P01".to_owned()),
+                    message: Some("invalid message".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn syntax_error() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::syntax_error(
+                    "Expected an SQL statement, found: selec".to_owned()
+                )
+                .with_position(1))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("42601".to_owned()),
+                    message: Some("Expected an SQL statement, found: selec".to_owned()),
+                    position: Some("1".to_owned()),
+                    ..ErrorFields::default()
+                })]
+            )
+        }
+
+        #[test]
+        fn error_with_all_fields() {
+            assert_eq!(
+                QueryResultMapper::map(Err(QueryError::value_too_long("character varying(5)".to_owned())
+                    .with_detail("value has 6 characters".to_owned())
+                    .with_hint("truncate the value".to_owned())
+                    .with_position(8)
+                    .with_context("insert".to_owned())
+                    .with_schema("schema_name".to_owned())
+                    .with_table("table_name".to_owned())
+                    .with_column("column_name".to_owned())
+                    .with_data_type("character varying".to_owned())
+                    .with_constraint("constraint_name".to_owned())
+                    .with_location("insert.rs", 10, "execute"))),
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("22001".to_owned()),
+                    message: Some("value too long for type character varying(5)".to_owned()),
+                    detail: Some("value has 6 characters".to_owned()),
+                    hint: Some("truncate the value".to_owned()),
+                    position: Some("8".to_owned()),
+                    where_: Some("insert".to_owned()),
+                    schema: Some("schema_name".to_owned()),
+                    table: Some("table_name".to_owned()),
+                    column: Some("column_name".to_owned()),
+                    data_type: Some("character varying".to_owned()),
+                    constraint: Some("constraint_name".to_owned()),
+                    file: Some("insert.rs".to_owned()),
+                    line: Some("10".to_owned()),
+                    routine: Some("execute".to_owned()),
+                })]
+            )
+        }
+
+        #[test]
+        fn notice() {
+            assert_eq!(
+                QueryResultMapper::map(Ok(QueryEvent::Notice(QueryError::type_does_not_exist_skipping(
+                    "schema_name.mood".to_owned()
+                )))),
+                vec![Message::NoticeResponse(ErrorFields {
+                    severity: Some("NOTICE".to_owned()),
+                    code: Some("00000".to_owned()),
+                    message: Some("type \"schema_name.mood\" does not exist, skipping".to_owned()),
+                    ..ErrorFields::default()
+                })]
             )
         }
 
@@ -399,11 +1407,12 @@ mod tests {
             let raw_sql_query = "some SQL query".to_owned();
             assert_eq!(
                 QueryResultMapper::map(Err(QueryError::not_supported_operation(raw_sql_query.clone()))),
-                vec![Message::ErrorResponse(
-                    Some("ERROR".to_owned()),
-                    Some("42601".to_owned()),
-                    Some(format!("Currently, Query '{}' can't be executed", raw_sql_query)),
-                )]
+                vec![Message::ErrorResponse(ErrorFields {
+                    severity: Some("ERROR".to_owned()),
+                    code: Some("42601".to_owned()),
+                    message: Some(format!("Currently, Query '{}' can't be executed", raw_sql_query)),
+                    ..ErrorFields::default()
+                })]
             )
         }
     }
@@ -440,7 +1449,26 @@ mod tests {
 
                 let actual_content = test_case.read_result().await;
                 let mut expected_content = BytesMut::new();
-                expected_content.extend_from_slice(Message::ReadyForQuery.as_vec().as_slice());
+                expected_content.extend_from_slice(Message::ReadyForQuery(TransactionStatus::Idle).as_vec().as_slice());
+                assert_eq!(actual_content, expected_content);
+
+                Ok(())
+            }
+
+            #[async_std::test]
+            async fn ready_for_query_with_transaction_status() -> io::Result<()> {
+                let test_case = async_io::TestCase::with_content(vec![&[81], &[0, 0, 0, 14], b"select 1;\0"]).await;
+                let mut connection = Connection::new((VERSION_3, vec![], SslMode::Disable), test_case.clone());
+                connection.set_transaction_status(TransactionStatus::Failed);
+
+                let query = connection.receive().await?;
+
+                assert_eq!(query, Ok(Command::Query("select 1;".to_owned())));
+
+                let actual_content = test_case.read_result().await;
+                let mut expected_content = BytesMut::new();
+                expected_content
+                    .extend_from_slice(Message::ReadyForQuery(TransactionStatus::Failed).as_vec().as_slice());
                 assert_eq!(actual_content, expected_content);
 
                 Ok(())
@@ -466,6 +1494,157 @@ mod tests {
                 assert!(query.is_err());
             }
 
+            #[async_std::test]
+            async fn ready_for_query_is_sent_after_sync() -> io::Result<()> {
+                let test_case = async_io::TestCase::with_content(vec![
+                    &[80],
+                    &[0, 0, 0, 16],
+                    b"\0select 1\0\0\0",
+                    &[72],
+                    &[0, 0, 0, 4],
+                    &[83],
+                    &[0, 0, 0, 4],
+                    &[81],
+                    &[0, 0, 0, 14],
+                    b"select 1;\0",
+                ])
+                .await;
+                let mut connection = Connection::new((VERSION_3, vec![], SslMode::Disable), test_case.clone());
+
+                assert_eq!(
+                    connection.receive().await?,
+                    Ok(Command::Parse {
+                        statement_name: "".to_owned(),
+                        sql: "select 1".to_owned(),
+                        param_types: vec![],
+                    })
+                );
+                assert_eq!(connection.receive().await?, Ok(Command::Sync));
+                assert_eq!(connection.receive().await?, Ok(Command::Query("select 1;".to_owned())));
+
+                let actual_content = test_case.read_result().await;
+                let mut expected_content = BytesMut::new();
+                expected_content.extend_from_slice(Message::ReadyForQuery(TransactionStatus::Idle).as_vec().as_slice());
+                expected_content.extend_from_slice(Message::ReadyForQuery(TransactionStatus::Idle).as_vec().as_slice());
+                assert_eq!(actual_content, expected_content);
+
+                Ok(())
+            }
+
+            #[async_std::test]
+            async fn protocol_violation_is_sent_for_unsupported_message() -> io::Result<()> {
+                let test_case = async_io::TestCase::with_content(vec![&[63], &[0, 0, 0, 4]]).await;
+                let mut connection = Connection::new((VERSION_3, vec![], SslMode::Disable), test_case.clone());
+
+                assert_eq!(connection.receive().await?, Err(Error::UnsupportedRequest));
+
+                let actual_content = test_case.read_result().await;
+                let mut expected_content = BytesMut::new();
+                expected_content.extend_from_slice(Message::ReadyForQuery(TransactionStatus::Idle).as_vec().as_slice());
+                expected_content.extend_from_slice(
+                    Message::ErrorResponse(ErrorFields {
+                        severity: Some("FATAL".to_owned()),
+                        code: Some("08P01".to_owned()),
+                        message: Some("invalid frontend message type 63".to_owned()),
+                        ..ErrorFields::default()
+                    })
+                    .as_vec()
+                    .as_slice(),
+                );
+                assert_eq!(actual_content, expected_content);
+
+                Ok(())
+            }
+
+            #[async_std::test]
+            async fn protocol_violation_is_sent_for_malformed_message() -> io::Result<()> {
+                let test_case = async_io::TestCase::with_content(vec![&[69], &[0, 0, 0, 7], b"p\0\0"]).await;
+                let mut connection = Connection::new((VERSION_3, vec![], SslMode::Disable), test_case.clone());
+
+                assert_eq!(connection.receive().await?, Err(Error::MalformedMessage));
+
+                let actual_content = test_case.read_result().await;
+                let mut expected_content = BytesMut::new();
+                expected_content.extend_from_slice(Message::ReadyForQuery(TransactionStatus::Idle).as_vec().as_slice());
+                expected_content.extend_from_slice(
+                    Message::ErrorResponse(ErrorFields {
+                        severity: Some("FATAL".to_owned()),
+                        code: Some("08P01".to_owned()),
+                        message: Some("invalid message format".to_owned()),
+                        ..ErrorFields::default()
+                    })
+                    .as_vec()
+                    .as_slice(),
+                );
+                assert_eq!(actual_content, expected_content);
+
+                Ok(())
+            }
+
+            #[async_std::test]
+            async fn messages_are_skipped_after_error_until_sync() -> io::Result<()> {
+                let test_case = async_io::TestCase::with_content(vec![
+                    &[80],
+                    &[0, 0, 0, 16],
+                    b"\0select 1\0\0\0",
+                    &[69],
+                    &[0, 0, 0, 9],
+                    b"\0\0\0\0\0",
+                    &[83],
+                    &[0, 0, 0, 4],
+                ])
+                .await;
+                let mut connection = Connection::new((VERSION_3, vec![], SslMode::Disable), test_case);
+
+                assert!(matches!(connection.receive().await?, Ok(Command::Parse { .. })));
+                connection
+                    .send(Box::new(std::iter::once(Err(results::QueryError::protocol_violation(
+                        "invalid message".to_owned(),
+                    )))))
+                    .await?;
+                assert_eq!(connection.receive().await?, Ok(Command::Sync));
+
+                Ok(())
+            }
+
+            #[async_std::test]
+            async fn protocol_violation_is_sent_for_invalid_message_length() -> io::Result<()> {
+                for len in [[0, 0, 0, 3], [0x40, 0, 0, 0], [0xff, 0xff, 0xff, 0xff]].iter() {
+                    let test_case = async_io::TestCase::with_content(vec![&[81], len]).await;
+                    let mut connection = Connection::new((VERSION_3, vec![], SslMode::Disable), test_case.clone());
+
+                    assert_eq!(connection.receive().await?, Err(Error::MalformedMessage));
+
+                    let actual_content = test_case.read_result().await;
+                    let mut expected_content = BytesMut::new();
+                    expected_content
+                        .extend_from_slice(Message::ReadyForQuery(TransactionStatus::Idle).as_vec().as_slice());
+                    expected_content.extend_from_slice(
+                        Message::ErrorResponse(ErrorFields {
+                            severity: Some("FATAL".to_owned()),
+                            code: Some("08P01".to_owned()),
+                            message: Some(format!("invalid message length {}", NetworkEndian::read_u32(len))),
+                            ..ErrorFields::default()
+                        })
+                        .as_vec()
+                        .as_slice(),
+                    );
+                    assert_eq!(actual_content, expected_content);
+                }
+
+                Ok(())
+            }
+
+            #[async_std::test]
+            async fn malformed_message() -> io::Result<()> {
+                let test_case = async_io::TestCase::with_content(vec![&[69], &[0, 0, 0, 6], b"\0\0"]).await;
+                let mut connection = Connection::new((VERSION_3, vec![], SslMode::Disable), test_case);
+
+                assert_eq!(connection.receive().await?, Err(Error::MalformedMessage));
+
+                Ok(())
+            }
+
             #[async_std::test]
             async fn unexpected_eof_when_query_string() {
                 let test_case = async_io::TestCase::with_content(vec![&[81], &[0, 0, 0, 14], b"sel;\0"]).await;
@@ -476,5 +1655,47 @@ mod tests {
                 assert!(query.is_err());
             }
         }
+
+        #[cfg(test)]
+        mod send_results {
+            use super::*;
+            use crate::sql_types::PostgreSqlType;
+            use test_helpers::async_io;
+
+            #[async_std::test]
+            async fn send_selected_records_one_by_one() -> io::Result<()> {
+                let test_case = async_io::TestCase::with_content(vec![]).await;
+                let mut connection = Connection::new((VERSION_3, vec![], SslMode::Disable), test_case.clone());
+
+                let records = (1..=2).map(|i| Ok(QueryEvent::DataRow(vec![Some(i.to_string().into_bytes())])));
+                let results = std::iter::once(Ok(QueryEvent::RowDescription(
+                    vec![("column_name".to_owned(), PostgreSqlType::SmallInt)],
+                    vec![],
+                )))
+                .chain(records)
+                .chain(std::iter::once(Ok(QueryEvent::RecordsSelected(2))));
+                connection.send(Box::new(results)).await?;
+
+                let actual_content = test_case.read_result().await;
+                let mut expected_content = BytesMut::new();
+                expected_content.extend_from_slice(
+                    Message::RowDescription(vec![ColumnMetadata::new(
+                        "column_name".to_owned(),
+                        21,
+                        2,
+                        -1,
+                        PostgreSqlFormat::Text,
+                    )])
+                    .as_vec()
+                    .as_slice(),
+                );
+                expected_content.extend_from_slice(Message::DataRow(vec![Some(b"1".to_vec())]).as_vec().as_slice());
+                expected_content.extend_from_slice(Message::DataRow(vec![Some(b"2".to_vec())]).as_vec().as_slice());
+                expected_content.extend_from_slice(Message::CommandComplete("SELECT 2".to_owned()).as_vec().as_slice());
+                assert_eq!(actual_content, expected_content);
+
+                Ok(())
+            }
+        }
     }
 }
@@ -60,7 +60,7 @@ pub trait QueryListener {
             if self.secure().ssl_support() {
                 unimplemented!()
             } else {
-                socket.write_all(Message::NoticeResponse.as_vec().as_slice()).await?;
+                socket.write_all(Message::SslDisallowed.as_vec().as_slice()).await?;
                 let len = read_len(&mut socket).await?;
                 log::debug!("LEN = {:?}", len);
                 let mut message = read_message(len, &mut socket).await?;
@@ -105,6 +105,14 @@ pub trait QueryListener {
                     )
                     .await?;
 
+                socket
+                    .write_all(
+                        Message::ParameterStatus("TimeZone".to_owned(), "UTC".to_owned())
+                            .as_vec()
+                            .as_slice(),
+                    )
+                    .await?;
+
                 Ok(Ok(Connection::new((version, parsed, SslMode::Require), socket)))
             }
         } else if version == VERSION_GSSENC {
@@ -194,6 +202,10 @@ impl Secure {
     }
 }
 
+// startup packets are limited as in PostgreSQL, they have at least length
+// and protocol version
+const MAX_STARTUP_PACKET_LENGTH: usize = 10_000;
+
 async fn read_len<RW>(socket: &mut RW) -> io::Result<usize>
 where
     RW: AsyncReadExt + AsyncWriteExt + Unpin,
@@ -203,6 +215,12 @@ where
         .read_exact(&mut buffer)
         .await
         .map(|_| NetworkEndian::read_u32(&buffer) as usize)?;
+    if !(8..=MAX_STARTUP_PACKET_LENGTH).contains(&len) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid length of startup packet {}", len),
+        ));
+    }
     Ok(len - 4)
 }
 
@@ -344,6 +362,17 @@ mod tests {
         mod psql_client {
             use super::*;
 
+            #[async_std::test]
+            async fn invalid_length_of_startup_packet() {
+                for len in [[0, 0, 0, 3], [0, 0, 0, 7], [0xff, 0xff, 0xff, 0xff]].iter() {
+                    let test_case = async_io::TestCase::with_content(vec![len]).await;
+
+                    let error = MockQueryListener::new(test_case, Secure::none()).accept().await;
+
+                    assert!(matches!(error, Err(error) if error.kind() == io::ErrorKind::InvalidData));
+                }
+            }
+
             #[async_std::test]
             async fn trying_read_only_length_of_ssl_message() {
                 let test_case = async_io::TestCase::with_content(vec![&[0, 0, 0, 8]]).await;
@@ -354,7 +383,7 @@ mod tests {
             }
 
             #[async_std::test]
-            async fn sending_notice_after_reading_ssl_message() {
+            async fn sending_ssl_disallowed_after_reading_ssl_message() {
                 let test_case =
                     async_io::TestCase::with_content(vec![pg_frontend::Message::SslRequired.as_vec().as_slice()]).await;
 
@@ -364,7 +393,7 @@ mod tests {
 
                 let actual_content = test_case.read_result().await;
                 let mut expected_content = BytesMut::new();
-                expected_content.extend_from_slice(Message::NoticeResponse.as_vec().as_slice());
+                expected_content.extend_from_slice(Message::SslDisallowed.as_vec().as_slice());
 
                 assert_eq!(actual_content, expected_content);
             }
@@ -394,7 +423,7 @@ mod tests {
 
                 let actual_content = test_case.read_result().await;
                 let mut expected_content = BytesMut::new();
-                expected_content.extend_from_slice(Message::NoticeResponse.as_vec().as_slice());
+                expected_content.extend_from_slice(Message::SslDisallowed.as_vec().as_slice());
                 expected_content.extend_from_slice(Message::AuthenticationCleartextPassword.as_vec().as_slice());
                 expected_content.extend_from_slice(Message::AuthenticationOk.as_vec().as_slice());
                 expected_content.extend_from_slice(
@@ -407,6 +436,11 @@ mod tests {
                         .as_vec()
                         .as_slice(),
                 );
+                expected_content.extend_from_slice(
+                    Message::ParameterStatus("TimeZone".to_owned(), "UTC".to_owned())
+                        .as_vec()
+                        .as_slice(),
+                );
 
                 assert_eq!(actual_content, expected_content);
 
@@ -12,41 +12,123 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::ColumnMetadata;
+use crate::{ColumnMetadata, TransactionStatus};
 use bytes::{Buf, BufMut, BytesMut};
 
-// const PARSE_COMPLETE: u8 = b'1';
-// const BIND_COMPLETE: u8 = b'2';
-// const CLOSE_COMPLETE: u8 = b'3';
+const PARSE_COMPLETE: u8 = b'1';
+const BIND_COMPLETE: u8 = b'2';
+const CLOSE_COMPLETE: u8 = b'3';
 // const NOTIFICATION_RESPONSE: u8 = b'A';
 // const COPY_DONE: u8 = b'c';
 const COMMAND_COMPLETE: u8 = b'C';
 // const COPY_DATA: u8 = b'd';
 const DATA_ROW: u8 = b'D';
 const ERROR_RESPONSE: u8 = b'E';
-const SEVERITY: u8 = b'S';
-const CODE: u8 = b'C';
-const MESSAGE: u8 = b'M';
 // const COPY_IN_RESPONSE: u8 = b'G';
 // const COPY_OUT_RESPONSE: u8 = b'H';
 const EMPTY_QUERY_RESPONSE: u8 = b'I';
 // const BACKEND_KEY_DATA: u8 = b'K';
-// const NO_DATA: u8 = b'n';
+const NO_DATA: u8 = b'n';
 const NOTICE_RESPONSE: u8 = b'N';
 const AUTHENTICATION: u8 = b'R';
-// const PORTAL_SUSPENDED: u8 = b's';
+const PORTAL_SUSPENDED: u8 = b's';
 const PARAMETER_STATUS: u8 = b'S';
-// const PARAMETER_DESCRIPTION: u8 = b't';
+const PARAMETER_DESCRIPTION: u8 = b't';
 const ROW_DESCRIPTION: u8 = b'T';
 const READY_FOR_QUERY: u8 = b'Z';
+// transaction status of ReadyForQuery
+const IDLE: u8 = b'I';
+const IN_TRANSACTION: u8 = b'T';
+const FAILED_TRANSACTION: u8 = b'E';
+// response to SSLRequest when SSL is not supported
+const SSL_DISALLOWED: u8 = b'N';
+
+// fields of ErrorResponse and NoticeResponse
+const SEVERITY: u8 = b'S';
+const CODE: u8 = b'C';
+const MESSAGE: u8 = b'M';
+const DETAIL: u8 = b'D';
+const HINT: u8 = b'H';
+const POSITION: u8 = b'P';
+const WHERE: u8 = b'W';
+const SCHEMA: u8 = b's';
+const TABLE: u8 = b't';
+const COLUMN: u8 = b'c';
+const DATA_TYPE: u8 = b'd';
+const CONSTRAINT: u8 = b'n';
+const FILE: u8 = b'F';
+const LINE: u8 = b'L';
+const ROUTINE: u8 = b'R';
+
+/// Fields of ErrorResponse and NoticeResponse messages, fields that are
+/// `None` are not sent
+/// see https://www.postgresql.org/docs/12/protocol-error-fields.html
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct ErrorFields {
+    pub(crate) severity: Option<String>,
+    pub(crate) code: Option<String>,
+    pub(crate) message: Option<String>,
+    pub(crate) detail: Option<String>,
+    pub(crate) hint: Option<String>,
+    pub(crate) position: Option<String>,
+    pub(crate) where_: Option<String>,
+    pub(crate) schema: Option<String>,
+    pub(crate) table: Option<String>,
+    pub(crate) column: Option<String>,
+    pub(crate) data_type: Option<String>,
+    pub(crate) constraint: Option<String>,
+    pub(crate) file: Option<String>,
+    pub(crate) line: Option<String>,
+    pub(crate) routine: Option<String>,
+}
+
+impl ErrorFields {
+    fn encode(&self, tag: u8) -> Vec<u8> {
+        let mut fields_buff = BytesMut::with_capacity(256);
+        for (field_type, value) in [
+            (SEVERITY, &self.severity),
+            (CODE, &self.code),
+            (MESSAGE, &self.message),
+            (DETAIL, &self.detail),
+            (HINT, &self.hint),
+            (POSITION, &self.position),
+            (WHERE, &self.where_),
+            (SCHEMA, &self.schema),
+            (TABLE, &self.table),
+            (COLUMN, &self.column),
+            (DATA_TYPE, &self.data_type),
+            (CONSTRAINT, &self.constraint),
+            (FILE, &self.file),
+            (LINE, &self.line),
+            (ROUTINE, &self.routine),
+        ]
+        .iter()
+        {
+            if let Some(value) = value {
+                fields_buff.put_u8(*field_type);
+                fields_buff.extend_from_slice(value.as_bytes());
+                fields_buff.put_u8(0);
+            }
+        }
+        let mut message_buff = BytesMut::with_capacity(fields_buff.len() + 6);
+        message_buff.put_u8(tag);
+        message_buff.put_i32(fields_buff.len() as i32 + 4 + 1);
+        message_buff.extend_from_slice(fields_buff.as_ref());
+        message_buff.put_u8(0);
+        message_buff.to_vec()
+    }
+}
 
 /// Backend PostgreSQL Wire Protocol messages
 /// see https://www.postgresql.org/docs/12/protocol-flow.html
 #[derive(Debug, PartialEq)]
 pub(crate) enum Message {
+    /// Response to SSLRequest, the server does not support SSL and the
+    /// frontend continues with the startup message without encryption.
+    SslDisallowed,
     /// A warning message has been issued. The frontend should display the message
     /// but continue listening for ReadyForQuery or ErrorResponse.
-    NoticeResponse,
+    NoticeResponse(ErrorFields),
     /// The frontend must now send a PasswordMessage containing the password in
     /// clear-text form. If this is the correct password, the server responds
     /// with an AuthenticationOk, otherwise it responds with an ErrorResponse.
@@ -64,9 +146,11 @@ pub(crate) enum Message {
     /// The authentication exchange is successfully completed.
     AuthenticationOk,
     /// Start-up is completed. The frontend can now issue commands.
-    ReadyForQuery,
+    /// Status tells whether the backend is in a transaction block
+    ReadyForQuery(TransactionStatus),
     /// One of the set of rows returned by a SELECT, FETCH, etc query.
-    DataRow(Vec<String>),
+    /// `None` is sent as NULL column value
+    DataRow(Vec<Option<Vec<u8>>>),
     /// Indicates that rows are about to be returned in response to a SELECT, FETCH,
     /// etc query. The contents of this message describe the column layout of
     /// the rows. This will be followed by a DataRow message for each row being
@@ -77,32 +161,56 @@ pub(crate) enum Message {
     /// An empty query string was recognized.
     #[allow(dead_code)]
     EmptyQueryResponse,
-    /// An error has occurred. Contains (`Severity`, `Error Code`, `Error Message`)
-    /// all of them are optional
-    ErrorResponse(Option<String>, Option<String>, Option<String>),
+    /// An error has occurred.
+    ErrorResponse(ErrorFields),
     /// This message informs the frontend about the current (initial) setting of
     /// backend parameters, such as client_encoding or DateStyle
     ///
     /// see https://www.postgresql.org/docs/12/protocol-flow.html#PROTOCOL-ASYNC
     /// 3rd and 4th paragraph
     ParameterStatus(String, String),
+    /// Parse of a prepared statement is completed.
+    ParseComplete,
+    /// Bind of a portal is completed.
+    BindComplete,
+    /// Close of a prepared statement or a portal is completed.
+    CloseComplete,
+    /// Describes OIDs of parameter types of a prepared statement.
+    ParameterDescription(Vec<i32>),
+    /// Prepared statement or portal does not return rows.
+    NoData,
+    /// Execution of a portal is stopped because it reached the row limit of
+    /// Execute message, the rest of rows are sent on the next Execute.
+    PortalSuspended,
 }
 
 impl Message {
     /// returns binary representation of a backend message
     pub fn as_vec(&self) -> Vec<u8> {
         match self {
-            Message::NoticeResponse => vec![NOTICE_RESPONSE],
+            Message::SslDisallowed => vec![SSL_DISALLOWED],
+            Message::NoticeResponse(fields) => fields.encode(NOTICE_RESPONSE),
             Message::AuthenticationCleartextPassword => vec![AUTHENTICATION, 0, 0, 0, 8, 0, 0, 0, 3],
             Message::AuthenticationMD5Password => vec![AUTHENTICATION, 0, 0, 0, 12, 0, 0, 0, 5, 1, 1, 1, 1],
             Message::AuthenticationOk => vec![AUTHENTICATION, 0, 0, 0, 8, 0, 0, 0, 0],
-            Message::ReadyForQuery => vec![READY_FOR_QUERY, 0, 0, 0, 5, EMPTY_QUERY_RESPONSE],
+            Message::ReadyForQuery(status) => {
+                let status = match status {
+                    TransactionStatus::Idle => IDLE,
+                    TransactionStatus::InTransaction => IN_TRANSACTION,
+                    TransactionStatus::Failed => FAILED_TRANSACTION,
+                };
+                vec![READY_FOR_QUERY, 0, 0, 0, 5, status]
+            }
             Message::DataRow(row) => {
                 let mut row_buff = BytesMut::with_capacity(256);
                 for field in row.iter() {
-                    let as_string = field;
-                    row_buff.put_i32(as_string.len() as i32);
-                    row_buff.extend_from_slice(as_string.as_str().as_bytes());
+                    match field {
+                        Some(value) => {
+                            row_buff.put_i32(value.len() as i32);
+                            row_buff.extend_from_slice(value);
+                        }
+                        None => row_buff.put_i32(-1),
+                    }
                 }
                 let mut len_buff = BytesMut::new();
                 len_buff.put_u8(DATA_ROW);
@@ -120,8 +228,8 @@ impl Message {
                     buff.put_i16(0); // column id
                     buff.put_i32(field.type_id);
                     buff.put_i16(field.type_size);
-                    buff.put_i32(-1); // type modifier
-                    buff.put_i16(0);
+                    buff.put_i32(field.type_modifier);
+                    buff.put_i16(field.format.code());
                 }
                 let mut len_buff = BytesMut::new();
                 len_buff.put_u8(ROW_DESCRIPTION);
@@ -139,30 +247,7 @@ impl Message {
                 command_buff.to_vec()
             }
             Message::EmptyQueryResponse => vec![EMPTY_QUERY_RESPONSE, 0, 0, 0, 4],
-            Message::ErrorResponse(severity, code, message) => {
-                let mut error_response_buff = BytesMut::with_capacity(256);
-                error_response_buff.put_u8(ERROR_RESPONSE);
-                let mut message_buff = BytesMut::with_capacity(256);
-                if let Some(severity) = severity.as_ref() {
-                    message_buff.put_u8(SEVERITY);
-                    message_buff.extend_from_slice(severity.as_bytes());
-                    message_buff.put_u8(0);
-                }
-                if let Some(code) = code.as_ref() {
-                    message_buff.put_u8(CODE);
-                    message_buff.extend_from_slice(code.as_bytes());
-                    message_buff.put_u8(0);
-                }
-                if let Some(message) = message.as_ref() {
-                    message_buff.put_u8(MESSAGE);
-                    message_buff.extend_from_slice(message.as_bytes());
-                    message_buff.put_u8(0);
-                }
-                error_response_buff.put_i32(message_buff.len() as i32 + 4 + 1);
-                error_response_buff.extend_from_slice(message_buff.as_ref());
-                error_response_buff.put_u8(0);
-                error_response_buff.to_vec()
-            }
+            Message::ErrorResponse(fields) => fields.encode(ERROR_RESPONSE),
             Message::ParameterStatus(name, value) => {
                 let mut parameter_status_buff = BytesMut::with_capacity(256);
                 parameter_status_buff.put_u8(PARAMETER_STATUS);
@@ -175,6 +260,21 @@ impl Message {
                 parameter_status_buff.extend_from_slice(parameters.as_ref());
                 parameter_status_buff.to_vec()
             }
+            Message::ParseComplete => vec![PARSE_COMPLETE, 0, 0, 0, 4],
+            Message::BindComplete => vec![BIND_COMPLETE, 0, 0, 0, 4],
+            Message::CloseComplete => vec![CLOSE_COMPLETE, 0, 0, 0, 4],
+            Message::ParameterDescription(type_ids) => {
+                let mut buff = BytesMut::with_capacity(256);
+                buff.put_u8(PARAMETER_DESCRIPTION);
+                buff.put_i32(6 + 4 * type_ids.len() as i32);
+                buff.put_i16(type_ids.len() as i16);
+                for type_id in type_ids.iter() {
+                    buff.put_i32(*type_id);
+                }
+                buff.to_vec()
+            }
+            Message::NoData => vec![NO_DATA, 0, 0, 0, 4],
+            Message::PortalSuspended => vec![PORTAL_SUSPENDED, 0, 0, 0, 4],
         }
     }
 }
@@ -182,10 +282,27 @@ impl Message {
 #[cfg(test)]
 mod serialized_messages {
     use super::*;
+    use crate::sql_types::PostgreSqlFormat;
+
+    #[test]
+    fn ssl_disallowed() {
+        assert_eq!(Message::SslDisallowed.as_vec(), vec![SSL_DISALLOWED]);
+    }
 
     #[test]
-    fn notice() {
-        assert_eq!(Message::NoticeResponse.as_vec(), vec![NOTICE_RESPONSE]);
+    fn notice_response() {
+        let mut expected = vec![NOTICE_RESPONSE, 0, 0, 0, 30];
+        expected.extend_from_slice(b"SNOTICE\0C00000\0Mskipping\0\0");
+        assert_eq!(
+            Message::NoticeResponse(ErrorFields {
+                severity: Some("NOTICE".to_owned()),
+                code: Some("00000".to_owned()),
+                message: Some("skipping".to_owned()),
+                ..ErrorFields::default()
+            })
+            .as_vec(),
+            expected
+        );
     }
 
     #[test]
@@ -250,23 +367,54 @@ mod serialized_messages {
     #[test]
     fn ready_for_query() {
         assert_eq!(
-            Message::ReadyForQuery.as_vec(),
-            vec![READY_FOR_QUERY, 0, 0, 0, 5, EMPTY_QUERY_RESPONSE]
+            Message::ReadyForQuery(TransactionStatus::Idle).as_vec(),
+            vec![READY_FOR_QUERY, 0, 0, 0, 5, IDLE]
+        )
+    }
+
+    #[test]
+    fn ready_for_query_in_transaction() {
+        assert_eq!(
+            Message::ReadyForQuery(TransactionStatus::InTransaction).as_vec(),
+            vec![READY_FOR_QUERY, 0, 0, 0, 5, IN_TRANSACTION]
+        )
+    }
+
+    #[test]
+    fn ready_for_query_in_failed_transaction() {
+        assert_eq!(
+            Message::ReadyForQuery(TransactionStatus::Failed).as_vec(),
+            vec![READY_FOR_QUERY, 0, 0, 0, 5, FAILED_TRANSACTION]
         )
     }
 
     #[test]
     fn data_row() {
         assert_eq!(
-            Message::DataRow(vec!["1".to_owned(), "2".to_owned(), "3".to_owned()]).as_vec(),
+            Message::DataRow(vec![Some(b"1".to_vec()), Some(b"2".to_vec()), Some(b"3".to_vec())]).as_vec(),
             vec![DATA_ROW, 0, 0, 0, 21, 0, 3, 0, 0, 0, 1, 49, 0, 0, 0, 1, 50, 0, 0, 0, 1, 51]
         )
     }
 
+    #[test]
+    fn data_row_with_null() {
+        assert_eq!(
+            Message::DataRow(vec![Some(b"1".to_vec()), None]).as_vec(),
+            vec![DATA_ROW, 0, 0, 0, 15, 0, 2, 0, 0, 0, 1, 49, 255, 255, 255, 255]
+        )
+    }
+
     #[test]
     fn row_description() {
         assert_eq!(
-            Message::RowDescription(vec![ColumnMetadata::new("c1".to_owned(), 23, 4)]).as_vec(),
+            Message::RowDescription(vec![ColumnMetadata::new(
+                "c1".to_owned(),
+                23,
+                4,
+                -1,
+                PostgreSqlFormat::Text
+            )])
+            .as_vec(),
             vec![
                 ROW_DESCRIPTION,
                 0,
@@ -319,8 +467,72 @@ mod serialized_messages {
     #[test]
     fn error_response() {
         assert_eq!(
-            Message::ErrorResponse(None, None, None).as_vec(),
+            Message::ErrorResponse(ErrorFields::default()).as_vec(),
             vec![ERROR_RESPONSE, 0, 0, 0, 5, 0]
         )
     }
+
+    #[test]
+    fn error_response_with_position() {
+        assert_eq!(
+            Message::ErrorResponse(ErrorFields {
+                code: Some("42601".to_owned()),
+                position: Some("12".to_owned()),
+                ..ErrorFields::default()
+            })
+            .as_vec(),
+            vec![
+                ERROR_RESPONSE,
+                0,
+                0,
+                0,
+                16,
+                CODE,
+                b'4',
+                b'2',
+                b'6',
+                b'0',
+                b'1',
+                0,
+                POSITION,
+                b'1',
+                b'2',
+                0,
+                0
+            ]
+        )
+    }
+
+    #[test]
+    fn parse_complete() {
+        assert_eq!(Message::ParseComplete.as_vec(), vec![PARSE_COMPLETE, 0, 0, 0, 4])
+    }
+
+    #[test]
+    fn bind_complete() {
+        assert_eq!(Message::BindComplete.as_vec(), vec![BIND_COMPLETE, 0, 0, 0, 4])
+    }
+
+    #[test]
+    fn close_complete() {
+        assert_eq!(Message::CloseComplete.as_vec(), vec![CLOSE_COMPLETE, 0, 0, 0, 4])
+    }
+
+    #[test]
+    fn parameter_description() {
+        assert_eq!(
+            Message::ParameterDescription(vec![23, 25]).as_vec(),
+            vec![PARAMETER_DESCRIPTION, 0, 0, 0, 14, 0, 2, 0, 0, 0, 23, 0, 0, 0, 25]
+        )
+    }
+
+    #[test]
+    fn no_data() {
+        assert_eq!(Message::NoData.as_vec(), vec![NO_DATA, 0, 0, 0, 4])
+    }
+
+    #[test]
+    fn portal_suspended() {
+        assert_eq!(Message::PortalSuspended.as_vec(), vec![PORTAL_SUSPENDED, 0, 0, 0, 4])
+    }
 }
@@ -13,12 +13,17 @@
 // limitations under the License.
 
 use crate::sql_types;
-use std::fmt::{self, Display, Formatter};
+use std::{
+    fmt::{self, Display, Formatter},
+    num::NonZeroU32,
+};
 
 /// Represents result of SQL query execution
 pub type QueryResult = std::result::Result<QueryEvent, QueryError>;
-/// Represents selected data from tables
-pub type Projection = (Vec<(String, sql_types::PostgreSqlType)>, Vec<Vec<String>>);
+/// Represents results of SQL query execution that are produced one by one
+pub type QueryResults = Box<dyn Iterator<Item = QueryResult> + Send>;
+/// Represents names and types of selected columns
+pub type Description = Vec<(String, sql_types::PostgreSqlType)>;
 
 /// Represents successful events that can happen in server backend
 #[derive(Debug, PartialEq)]
@@ -31,18 +36,61 @@ pub enum QueryEvent {
     TableCreated,
     /// Table successfully dropped
     TableDropped,
+    /// Type successfully created
+    TypeCreated,
+    /// Type successfully dropped
+    TypeDropped,
+    /// Sequence successfully created
+    SequenceCreated,
+    /// Sequence successfully dropped
+    SequenceDropped,
     /// Variable successfully set
     VariableSet,
     /// Transaction is started
     TransactionStarted,
+    /// Transaction is committed
+    TransactionCommitted,
+    /// Transaction is rolled back
+    TransactionRolledBack,
+    /// Table successfully locked
+    TableLocked,
+    /// Statement successfully prepared by `PREPARE`
+    StatementPrepared,
+    /// Prepared statement successfully deallocated
+    StatementDeallocated,
+    /// All prepared statements successfully deallocated
+    AllStatementsDeallocated,
     /// Number of records inserted into a table
     RecordsInserted(usize),
-    /// Records selected from database
-    RecordsSelected(Projection),
+    /// Description of columns that are going to be selected with formats
+    /// their values are sent in
+    RowDescription(Description, Vec<sql_types::PostgreSqlFormat>),
+    /// Single record selected from database with values encoded in formats
+    /// of their columns, `None` is NULL
+    DataRow(Vec<Option<Vec<u8>>>),
+    /// Number of records selected from database
+    RecordsSelected(usize),
     /// Number of records updated into a table
     RecordsUpdated(usize),
     /// Number of records deleted into a table
     RecordsDeleted(usize),
+    /// Query does not contain statements
+    EmptyQuery,
+    /// Prepared statement successfully created
+    ParseComplete,
+    /// Portal successfully created
+    BindComplete,
+    /// Prepared statement or portal successfully closed
+    CloseComplete,
+    /// Types of parameters of a prepared statement
+    ParameterDescription(Vec<sql_types::PostgreSqlType>),
+    /// Prepared statement or portal does not return rows
+    NoData,
+    /// Portal execution is stopped because it reached its limit of rows
+    PortalSuspended,
+    /// Notice that is sent to client while the query is executed, it does not
+    /// stop the query
+    Notice(QueryError),
 }
 
 /// Message severities
@@ -84,20 +132,89 @@ pub(crate) enum QueryErrorKind {
     ColumnDoesNotExist(Vec<String>),
     NotSupportedOperation(String),
     TooManyInsertExpressions,
+    DeadlockDetected,
+    LockNotAvailable(String),
+    LockTimeout,
+    NoActiveTransaction(String),
+    InvalidParameterValue(String, String),
+    TransactionIsolationAfterQuery,
+    InFailedTransaction,
+    DivisionByZero,
+    OutOfRange(String),
+    FloatOverflow,
+    UndefinedOperator(String),
+    UndefinedFunction(String),
+    NotBooleanArgument(String, String),
+    NumericFieldOverflow,
+    InvalidNumericPrecision(u64),
+    InvalidNumericScale(u64, u64),
+    InvalidDateTimeSyntax(String, String),
+    DateTimeFieldOverflow(String),
+    DateTimeOutOfRange(String),
+    InvalidTextRepresentation(String, String),
+    OddNumberOfArguments,
+    NullObjectKey,
+    NotAnArray(String),
+    ArraySubscriptNotInteger,
+    ArrayRequired(String),
+    TypeAlreadyExists(String),
+    TypeDoesNotExist(String),
+    InvalidEnumValue(String, String),
+    DependentObjectsExist(String),
+    SequenceAlreadyExists(String),
+    SequenceDoesNotExist(String),
+    SequenceMaxValueReached(String, i64),
+    SequenceMinValueReached(String, i64),
+    SequenceCurrentValueNotDefined(String),
+    SequenceValueOutOfBounds(String, i64, i64, i64),
+    InvalidSequenceParameter(String),
+    GeneratedAlways(String),
+    CannotCast(String, String),
+    DatatypeMismatch(String, String, String),
+    ValueTooLong(String),
+    DuplicatePreparedStatement(String),
+    PreparedStatementDoesNotExist(String),
+    DuplicateCursor(String),
+    PortalDoesNotExist(String),
+    MultipleCommandsInPreparedStatement,
+    WrongNumberOfParameters(String),
+    ProtocolViolation(String),
+    InvalidBinaryRepresentation(usize),
+    SyntaxError(String),
+    Skipping(Box<QueryErrorKind>),
 }
 
-/// Represents error during query execution
+/// Represents error during query execution, notices that are sent while the
+/// query is executed have the same fields
 #[derive(Debug, PartialEq)]
 pub struct QueryError {
     severity: Severity,
-    code: String,
+    code: &'static str,
     kind: QueryErrorKind,
+    details: Box<ErrorDetails>,
+}
+
+/// Optional fields that are set by the builders of `QueryError`
+#[derive(Debug, Default, PartialEq)]
+struct ErrorDetails {
+    detail: Option<String>,
+    hint: Option<String>,
+    position: Option<NonZeroU32>,
+    context: Option<String>,
+    schema: Option<String>,
+    table: Option<String>,
+    column: Option<String>,
+    data_type: Option<String>,
+    constraint: Option<String>,
+    file: Option<String>,
+    line: Option<u32>,
+    routine: Option<String>,
 }
 
 impl QueryError {
     /// error code
     pub fn code(&self) -> Option<String> {
-        Some(self.code.clone())
+        Some(self.code.to_owned())
     }
 
     /// error severity
@@ -110,12 +227,136 @@ impl QueryError {
         Some(format!("{}", self.kind))
     }
 
+    /// secondary message with more details about the error
+    pub fn detail(&self) -> Option<String> {
+        self.details.detail.clone()
+    }
+
+    /// adds secondary message with more details about the error
+    pub fn with_detail(mut self, detail: String) -> Self {
+        self.details.detail = Some(detail);
+        self
+    }
+
+    /// suggestion what to do about the error
+    pub fn hint(&self) -> Option<String> {
+        self.details.hint.clone()
+    }
+
+    /// adds suggestion what to do about the error
+    pub fn with_hint(mut self, hint: String) -> Self {
+        self.details.hint = Some(hint);
+        self
+    }
+
+    /// position of the character in the query that caused the error,
+    /// counting from 1
+    pub fn position(&self) -> Option<String> {
+        self.details.position.map(|position| position.to_string())
+    }
+
+    /// relates the error to the character of the query at the position
+    pub fn with_position(mut self, position: u32) -> Self {
+        self.details.position = NonZeroU32::new(position);
+        self
+    }
+
+    /// context in which the error occurred, e.g. statement of a function
+    pub fn context(&self) -> Option<String> {
+        self.details.context.clone()
+    }
+
+    /// adds context in which the error occurred
+    pub fn with_context(mut self, context: String) -> Self {
+        self.details.context = Some(context);
+        self
+    }
+
+    /// name of the schema the error is related to
+    pub fn schema(&self) -> Option<String> {
+        self.details.schema.clone()
+    }
+
+    /// relates the error to the schema
+    pub fn with_schema(mut self, schema_name: String) -> Self {
+        self.details.schema = Some(schema_name);
+        self
+    }
+
+    /// name of the table the error is related to
+    pub fn table(&self) -> Option<String> {
+        self.details.table.clone()
+    }
+
+    /// relates the error to the table, its schema is set separately
+    pub fn with_table(mut self, table_name: String) -> Self {
+        self.details.table = Some(table_name);
+        self
+    }
+
+    /// name of the column the error is related to
+    pub fn column(&self) -> Option<String> {
+        self.details.column.clone()
+    }
+
+    /// relates the error to the column whose value caused it
+    pub fn with_column(mut self, column_name: String) -> Self {
+        self.details.column = Some(column_name);
+        self
+    }
+
+    /// name of the data type the error is related to
+    pub fn data_type(&self) -> Option<String> {
+        self.details.data_type.clone()
+    }
+
+    /// relates the error to the data type
+    pub fn with_data_type(mut self, type_name: String) -> Self {
+        self.details.data_type = Some(type_name);
+        self
+    }
+
+    /// name of the constraint the error is related to
+    pub fn constraint(&self) -> Option<String> {
+        self.details.constraint.clone()
+    }
+
+    /// relates the error to the constraint that is violated
+    pub fn with_constraint(mut self, constraint_name: String) -> Self {
+        self.details.constraint = Some(constraint_name);
+        self
+    }
+
+    /// source file where the error is reported
+    pub fn file(&self) -> Option<String> {
+        self.details.file.clone()
+    }
+
+    /// line of the source file where the error is reported
+    pub fn line(&self) -> Option<String> {
+        self.details.line.map(|line| line.to_string())
+    }
+
+    /// name of the routine that reported the error
+    pub fn routine(&self) -> Option<String> {
+        self.details.routine.clone()
+    }
+
+    /// adds location in the source code where the error is reported
+    pub fn with_location(mut self, file: &str, line: u32, routine: &str) -> Self {
+        self.details.file = Some(file.to_owned());
+        self.details.line = Some(line);
+        self.details.routine = Some(routine.to_owned());
+        self
+    }
+
     /// schema already exists error constructor
     pub fn schema_already_exists(schema_name: String) -> Self {
         Self {
             severity: Severity::Error,
-            code: "42P06".to_owned(),
+            code: "42P06",
             kind: QueryErrorKind::SchemaAlreadyExists(schema_name),
+            details: Box::default(),
         }
     }
 
@@ -123,8 +364,9 @@ impl QueryError {
     pub fn schema_does_not_exist(schema_name: String) -> Self {
         Self {
             severity: Severity::Error,
-            code: "3F000".to_owned(),
+            code: "3F000",
             kind: QueryErrorKind::SchemaDoesNotExist(schema_name),
+            details: Box::default(),
         }
     }
 
@@ -132,8 +374,9 @@ impl QueryError {
     pub fn table_already_exists(table_name: String) -> Self {
         Self {
             severity: Severity::Error,
-            code: "42P07".to_owned(),
+            code: "42P07",
             kind: QueryErrorKind::TableAlreadyExists(table_name),
+            details: Box::default(),
         }
     }
 
@@ -141,8 +384,9 @@ impl QueryError {
     pub fn table_does_not_exist(table_name: String) -> Self {
         Self {
             severity: Severity::Error,
-            code: "42P01".to_owned(),
+            code: "42P01",
             kind: QueryErrorKind::TableDoesNotExist(table_name),
+            details: Box::default(),
         }
     }
 
@@ -150,8 +394,9 @@ impl QueryError {
     pub fn column_does_not_exist(non_existing_columns: Vec<String>) -> Self {
         Self {
             severity: Severity::Error,
-            code: "42703".to_owned(),
+            code: "42703",
             kind: QueryErrorKind::ColumnDoesNotExist(non_existing_columns),
+            details: Box::default(),
         }
     }
 
@@ -159,8 +404,9 @@ impl QueryError {
     pub fn not_supported_operation(raw_sql_query: String) -> Self {
         Self {
             severity: Severity::Error,
-            code: "42601".to_owned(),
+            code: "42601",
             kind: QueryErrorKind::NotSupportedOperation(raw_sql_query),
+            details: Box::default(),
         }
     }
 
@@ -168,8 +414,551 @@ impl QueryError {
     pub fn too_many_insert_expressions() -> Self {
         Self {
             severity: Severity::Error,
-            code: "42601".to_owned(),
+            code: "42601",
             kind: QueryErrorKind::TooManyInsertExpressions,
+            details: Box::default(),
+        }
+    }
+
+    /// deadlock detected error constructor
+    pub fn deadlock_detected() -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "40P01",
+            kind: QueryErrorKind::DeadlockDetected,
+            details: Box::default(),
+        }
+    }
+
+    /// lock not available error constructor
+    pub fn lock_not_available(relation_name: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "55P03",
+            kind: QueryErrorKind::LockNotAvailable(relation_name),
+            details: Box::default(),
+        }
+    }
+
+    /// lock timeout error constructor
+    pub fn lock_timeout() -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "55P03",
+            kind: QueryErrorKind::LockTimeout,
+            details: Box::default(),
+        }
+    }
+
+    /// no active transaction error constructor
+    pub fn no_active_transaction(command: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "25P01",
+            kind: QueryErrorKind::NoActiveTransaction(command),
+            details: Box::default(),
+        }
+    }
+
+    /// invalid parameter value error constructor
+    pub fn invalid_parameter_value(parameter_name: String, value: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "22023",
+            kind: QueryErrorKind::InvalidParameterValue(parameter_name, value),
+            details: Box::default(),
+        }
+    }
+
+    /// transaction isolation level is set after first query in transaction error constructor
+    pub fn transaction_isolation_after_query() -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "25001",
+            kind: QueryErrorKind::TransactionIsolationAfterQuery,
+            details: Box::default(),
+        }
+    }
+
+    /// statement is executed in a transaction that has already failed error constructor
+    pub fn in_failed_transaction() -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "25P02",
+            kind: QueryErrorKind::InFailedTransaction,
+            details: Box::default(),
+        }
+    }
+
+    /// division by zero error constructor
+    pub fn division_by_zero() -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "22012",
+            kind: QueryErrorKind::DivisionByZero,
+            details: Box::default(),
+        }
+    }
+
+    /// result of integer operation is out of type range error constructor
+    pub fn out_of_range(type_name: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "22003",
+            kind: QueryErrorKind::OutOfRange(type_name),
+            details: Box::default(),
+        }
+    }
+
+    /// result of floating point operation is too big error constructor
+    pub fn float_overflow() -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "22003",
+            kind: QueryErrorKind::FloatOverflow,
+            details: Box::default(),
+        }
+    }
+
+    /// operator is not defined for types of operands error constructor
+    pub fn undefined_operator(operator: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "42883",
+            kind: QueryErrorKind::UndefinedOperator(operator),
+            details: Box::default(),
+        }
+    }
+
+    /// function with given argument types is not defined error constructor
+    pub fn undefined_function(function: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "42883",
+            kind: QueryErrorKind::UndefinedFunction(function),
+            details: Box::default(),
+        }
+    }
+
+    /// argument of logical operator is not boolean error constructor
+    pub fn not_boolean_argument(operator: String, type_name: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "42804",
+            kind: QueryErrorKind::NotBooleanArgument(operator, type_name),
+            details: Box::default(),
+        }
+    }
+
+    /// numeric value does not fit into precision of a column error constructor
+    pub fn numeric_field_overflow() -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "22003",
+            kind: QueryErrorKind::NumericFieldOverflow,
+            details: Box::default(),
+        }
+    }
+
+    /// precision of numeric type is out of range error constructor
+    pub fn invalid_numeric_precision(precision: u64) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "22023",
+            kind: QueryErrorKind::InvalidNumericPrecision(precision),
+            details: Box::default(),
+        }
+    }
+
+    /// scale of numeric type is greater than its precision error constructor
+    pub fn invalid_numeric_scale(scale: u64, precision: u64) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "22023",
+            kind: QueryErrorKind::InvalidNumericScale(scale, precision),
+            details: Box::default(),
+        }
+    }
+
+    /// literal can not be parsed as a value of date/time type error constructor
+    pub fn invalid_datetime_syntax(type_name: String, value: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "22007",
+            kind: QueryErrorKind::InvalidDateTimeSyntax(type_name, value),
+            details: Box::default(),
+        }
+    }
+
+    /// field of date/time literal is out of its range error constructor
+    pub fn datetime_field_overflow(value: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "22008",
+            kind: QueryErrorKind::DateTimeFieldOverflow(value),
+            details: Box::default(),
+        }
+    }
+
+    /// result of date/time operation is out of type range error constructor
+    pub fn datetime_out_of_range(type_name: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "22008",
+            kind: QueryErrorKind::DateTimeOutOfRange(type_name),
+            details: Box::default(),
+        }
+    }
+
+    /// literal can not be parsed as a value of the type error constructor, the
+    /// error is related to the type
+    pub fn invalid_text_representation(type_name: String, value: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "22P02",
+            kind: QueryErrorKind::InvalidTextRepresentation(type_name.clone(), value),
+            details: Box::default(),
+        }
+        .with_data_type(type_name)
+    }
+
+    /// function expects pairs of arguments error constructor
+    pub fn odd_number_of_arguments() -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "22023",
+            kind: QueryErrorKind::OddNumberOfArguments,
+            details: Box::default(),
+        }
+    }
+
+    /// key of JSON object is null error constructor
+    pub fn null_object_key() -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "22004",
+            kind: QueryErrorKind::NullObjectKey,
+            details: Box::default(),
+        }
+    }
+
+    /// subscripted value is not an array error constructor
+    pub fn not_an_array(type_name: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "42804",
+            kind: QueryErrorKind::NotAnArray(type_name),
+            details: Box::default(),
+        }
+    }
+
+    /// array subscript is not an integer error constructor
+    pub fn array_subscript_not_integer() -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "42804",
+            kind: QueryErrorKind::ArraySubscriptNotInteger,
+            details: Box::default(),
+        }
+    }
+
+    /// right operand of ANY/ALL comparison is not an array error constructor
+    pub fn array_required(operator: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "42809",
+            kind: QueryErrorKind::ArrayRequired(operator),
+            details: Box::default(),
+        }
+    }
+
+    /// type already exists error constructor
+    pub fn type_already_exists(type_name: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "42710",
+            kind: QueryErrorKind::TypeAlreadyExists(type_name),
+            details: Box::default(),
+        }
+    }
+
+    /// type does not exist error constructor
+    pub fn type_does_not_exist(type_name: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "42704",
+            kind: QueryErrorKind::TypeDoesNotExist(type_name),
+            details: Box::default(),
+        }
+    }
+
+    /// value is not a label of enum type error constructor, the error is
+    /// related to the type
+    pub fn invalid_enum_value(type_name: String, value: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "22P02",
+            kind: QueryErrorKind::InvalidEnumValue(type_name.clone(), value),
+            details: Box::default(),
+        }
+        .with_data_type(type_name)
+    }
+
+    /// dropped object is used by other objects error constructor
+    pub fn dependent_objects_exist(object: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "2BP01",
+            kind: QueryErrorKind::DependentObjectsExist(object),
+            details: Box::default(),
+        }
+    }
+
+    /// sequence already exists error constructor
+    pub fn sequence_already_exists(sequence_name: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "42P07",
+            kind: QueryErrorKind::SequenceAlreadyExists(sequence_name),
+            details: Box::default(),
+        }
+    }
+
+    /// sequence does not exist error constructor
+    pub fn sequence_does_not_exist(sequence_name: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "42P01",
+            kind: QueryErrorKind::SequenceDoesNotExist(sequence_name),
+            details: Box::default(),
+        }
+    }
+
+    /// sequence can't go beyond its maximum value error constructor
+    pub fn sequence_max_value_reached(sequence_name: String, max_value: i64) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "2200H",
+            kind: QueryErrorKind::SequenceMaxValueReached(sequence_name, max_value),
+            details: Box::default(),
+        }
+    }
+
+    /// sequence can't go beyond its minimum value error constructor
+    pub fn sequence_min_value_reached(sequence_name: String, min_value: i64) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "2200H",
+            kind: QueryErrorKind::SequenceMinValueReached(sequence_name, min_value),
+            details: Box::default(),
+        }
+    }
+
+    /// `currval` is called before `nextval` in the session error constructor
+    pub fn sequence_current_value_not_defined(sequence_name: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "55000",
+            kind: QueryErrorKind::SequenceCurrentValueNotDefined(sequence_name),
+            details: Box::default(),
+        }
+    }
+
+    /// `setval` value is out of sequence range error constructor
+    pub fn sequence_value_out_of_bounds(sequence_name: String, value: i64, min_value: i64, max_value: i64) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "22003",
+            kind: QueryErrorKind::SequenceValueOutOfBounds(sequence_name, value, min_value, max_value),
+            details: Box::default(),
+        }
+    }
+
+    /// inconsistent options of sequence error constructor
+    pub fn invalid_sequence_parameter(message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "22023",
+            kind: QueryErrorKind::InvalidSequenceParameter(message),
+            details: Box::default(),
+        }
+    }
+
+    /// explicit value is given to `GENERATED ALWAYS` identity column error constructor
+    pub fn generated_always(column_name: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "428C9",
+            kind: QueryErrorKind::GeneratedAlways(column_name.clone()),
+            details: Box::default(),
+        }
+        .with_detail(format!(
+            "Column \"{}\" is an identity column defined as GENERATED ALWAYS.",
+            column_name
+        ))
+        .with_hint("Use OVERRIDING SYSTEM VALUE to override.".to_owned())
+    }
+
+    /// there is no cast between types error constructor
+    pub fn cannot_cast(from_type: String, to_type: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "42846",
+            kind: QueryErrorKind::CannotCast(from_type, to_type),
+            details: Box::default(),
+        }
+    }
+
+    /// value of expression can't be assigned to column error constructor
+    pub fn datatype_mismatch(column_name: String, column_type: String, expression_type: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "42804",
+            kind: QueryErrorKind::DatatypeMismatch(column_name, column_type, expression_type),
+            details: Box::default(),
+        }
+    }
+
+    /// string is longer than type allows error constructor
+    pub fn value_too_long(type_name: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "22001",
+            kind: QueryErrorKind::ValueTooLong(type_name),
+            details: Box::default(),
+        }
+    }
+
+    /// prepared statement with the name already exists error constructor
+    pub fn duplicate_prepared_statement(statement_name: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "42P05",
+            kind: QueryErrorKind::DuplicatePreparedStatement(statement_name),
+            details: Box::default(),
+        }
+    }
+
+    /// prepared statement does not exist error constructor
+    pub fn prepared_statement_does_not_exist(statement_name: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "26000",
+            kind: QueryErrorKind::PreparedStatementDoesNotExist(statement_name),
+            details: Box::default(),
+        }
+    }
+
+    /// portal with the name already exists error constructor
+    pub fn duplicate_cursor(portal_name: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "42P03",
+            kind: QueryErrorKind::DuplicateCursor(portal_name),
+            details: Box::default(),
+        }
+    }
+
+    /// portal does not exist error constructor
+    pub fn portal_does_not_exist(portal_name: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "34000",
+            kind: QueryErrorKind::PortalDoesNotExist(portal_name),
+            details: Box::default(),
+        }
+    }
+
+    /// more than one statement is sent to be prepared error constructor
+    pub fn multiple_commands_in_prepared_statement() -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "42601",
+            kind: QueryErrorKind::MultipleCommandsInPreparedStatement,
+            details: Box::default(),
+        }
+    }
+
+    /// `EXECUTE` gives more or less parameters than prepared statement has
+    /// error constructor
+    pub fn wrong_number_of_parameters(statement_name: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "42601",
+            kind: QueryErrorKind::WrongNumberOfParameters(statement_name),
+            details: Box::default(),
+        }
+    }
+
+    /// client message contradicts the state of the session error constructor
+    pub fn protocol_violation(message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "08P01",
+            kind: QueryErrorKind::ProtocolViolation(message),
+            details: Box::default(),
+        }
+    }
+
+    /// bind parameter in binary format can't be decoded error constructor,
+    /// parameters are numbered from 1
+    pub fn invalid_binary_representation(parameter_number: usize) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "22P03",
+            kind: QueryErrorKind::InvalidBinaryRepresentation(parameter_number),
+            details: Box::default(),
+        }
+    }
+
+    /// query can't be parsed error constructor
+    pub fn syntax_error(message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: "42601",
+            kind: QueryErrorKind::SyntaxError(message),
+            details: Box::default(),
+        }
+    }
+
+    /// message of a client that does not follow the protocol fatal error
+    /// constructor, connection is closed after it is sent
+    pub fn fatal_protocol_violation(message: String) -> Self {
+        Self {
+            severity: Severity::Fatal,
+            code: "08P01",
+            kind: QueryErrorKind::ProtocolViolation(message),
+            details: Box::default(),
+        }
+    }
+
+    /// `CREATE SEQUENCE IF NOT EXISTS` of existing sequence notice constructor
+    pub fn sequence_already_exists_skipping(sequence_name: String) -> Self {
+        Self {
+            severity: Severity::Notice,
+            code: "42P07",
+            kind: QueryErrorKind::Skipping(Box::new(QueryErrorKind::SequenceAlreadyExists(sequence_name))),
+            details: Box::default(),
+        }
+    }
+
+    /// `DROP SEQUENCE IF EXISTS` of non existent sequence notice constructor
+    pub fn sequence_does_not_exist_skipping(sequence_name: String) -> Self {
+        Self {
+            severity: Severity::Notice,
+            code: "00000",
+            kind: QueryErrorKind::Skipping(Box::new(QueryErrorKind::SequenceDoesNotExist(sequence_name))),
+            details: Box::default(),
+        }
+    }
+
+    /// `DROP TYPE IF EXISTS` of non existent type notice constructor
+    pub fn type_does_not_exist_skipping(type_name: String) -> Self {
+        Self {
+            severity: Severity::Notice,
+            code: "00000",
+            kind: QueryErrorKind::Skipping(Box::new(QueryErrorKind::TypeDoesNotExist(type_name))),
+            details: Box::default(),
         }
     }
 }
@@ -192,6 +981,119 @@ impl Display for QueryErrorKind {
                 write!(f, "Currently, Query '{}' can't be executed", raw_sql_query)
             }
             Self::TooManyInsertExpressions => write!(f, "INSERT has more epxressions then target columns"),
+            Self::DeadlockDetected => write!(f, "deadlock detected"),
+            Self::LockNotAvailable(relation_name) => {
+                write!(f, "could not obtain lock on relation \"{}\"", relation_name)
+            }
+            Self::LockTimeout => write!(f, "canceling statement due to lock timeout"),
+            Self::NoActiveTransaction(command) => write!(f, "{} can only be used in transaction blocks", command),
+            Self::InvalidParameterValue(parameter_name, value) => {
+                write!(f, "invalid value for parameter \"{}\": \"{}\"", parameter_name, value)
+            }
+            Self::TransactionIsolationAfterQuery => {
+                write!(f, "SET TRANSACTION ISOLATION LEVEL must be called before any query")
+            }
+            Self::InFailedTransaction => write!(
+                f,
+                "current transaction is aborted, commands ignored until end of transaction block"
+            ),
+            Self::DivisionByZero => write!(f, "division by zero"),
+            Self::OutOfRange(type_name) => write!(f, "{} out of range", type_name),
+            Self::FloatOverflow => write!(f, "value out of range: overflow"),
+            Self::UndefinedOperator(operator) => write!(f, "operator does not exist: {}", operator),
+            Self::UndefinedFunction(function) => write!(f, "function {} does not exist", function),
+            Self::NotBooleanArgument(operator, type_name) => write!(
+                f,
+                "argument of {} must be type boolean, not type {}",
+                operator, type_name
+            ),
+            Self::NumericFieldOverflow => write!(f, "numeric field overflow"),
+            Self::InvalidNumericPrecision(precision) => {
+                write!(f, "NUMERIC precision {} must be between 1 and 1000", precision)
+            }
+            Self::InvalidNumericScale(scale, precision) => write!(
+                f,
+                "NUMERIC scale {} must be between 0 and precision {}",
+                scale, precision
+            ),
+            Self::InvalidDateTimeSyntax(type_name, value) => {
+                write!(f, "invalid input syntax for type {}: \"{}\"", type_name, value)
+            }
+            Self::DateTimeFieldOverflow(value) => write!(f, "date/time field value out of range: \"{}\"", value),
+            Self::DateTimeOutOfRange(type_name) => write!(f, "{} out of range", type_name),
+            Self::InvalidTextRepresentation(type_name, value) => {
+                write!(f, "invalid input syntax for type {}: \"{}\"", type_name, value)
+            }
+            Self::OddNumberOfArguments => write!(f, "argument list must have even number of elements"),
+            Self::NullObjectKey => write!(f, "null value not allowed for object key"),
+            Self::NotAnArray(type_name) => {
+                write!(f, "cannot subscript type {} because it is not an array", type_name)
+            }
+            Self::ArraySubscriptNotInteger => write!(f, "array subscript must have type integer"),
+            Self::ArrayRequired(operator) => write!(f, "op {} ANY/ALL (array) requires array on right side", operator),
+            Self::TypeAlreadyExists(type_name) => write!(f, "type \"{}\" already exists", type_name),
+            Self::TypeDoesNotExist(type_name) => write!(f, "type \"{}\" does not exist", type_name),
+            Self::InvalidEnumValue(type_name, value) => {
+                write!(f, "invalid input value for enum {}: \"{}\"", type_name, value)
+            }
+            Self::DependentObjectsExist(object) => {
+                write!(f, "cannot drop {} because other objects depend on it", object)
+            }
+            Self::SequenceAlreadyExists(sequence_name) => write!(f, "relation \"{}\" already exists", sequence_name),
+            Self::SequenceDoesNotExist(sequence_name) => write!(f, "relation \"{}\" does not exist", sequence_name),
+            Self::SequenceMaxValueReached(sequence_name, max_value) => write!(
+                f,
+                "nextval: reached maximum value of sequence \"{}\" ({})",
+                sequence_name, max_value
+            ),
+            Self::SequenceMinValueReached(sequence_name, min_value) => write!(
+                f,
+                "nextval: reached minimum value of sequence \"{}\" ({})",
+                sequence_name, min_value
+            ),
+            Self::SequenceCurrentValueNotDefined(sequence_name) => write!(
+                f,
+                "currval of sequence \"{}\" is not yet defined in this session",
+                sequence_name
+            ),
+            Self::SequenceValueOutOfBounds(sequence_name, value, min_value, max_value) => write!(
+                f,
+                "setval: value {} is out of bounds for sequence \"{}\" ({}..{})",
+                value, sequence_name, min_value, max_value
+            ),
+            Self::InvalidSequenceParameter(message) => write!(f, "{}", message),
+            Self::GeneratedAlways(column_name) => {
+                write!(f, "cannot insert a non-DEFAULT value into column \"{}\"", column_name)
+            }
+            Self::CannotCast(from_type, to_type) => write!(f, "cannot cast type {} to {}", from_type, to_type),
+            Self::DatatypeMismatch(column_name, column_type, expression_type) => write!(
+                f,
+                "column \"{}\" is of type {} but expression is of type {}",
+                column_name, column_type, expression_type
+            ),
+            Self::ValueTooLong(type_name) => write!(f, "value too long for type {}", type_name),
+            Self::DuplicatePreparedStatement(statement_name) => {
+                write!(f, "prepared statement \"{}\" already exists", statement_name)
+            }
+            Self::PreparedStatementDoesNotExist(statement_name) => {
+                write!(f, "prepared statement \"{}\" does not exist", statement_name)
+            }
+            Self::DuplicateCursor(portal_name) => write!(f, "cursor \"{}\" already exists", portal_name),
+            Self::PortalDoesNotExist(portal_name) => write!(f, "portal \"{}\" does not exist", portal_name),
+            Self::MultipleCommandsInPreparedStatement => {
+                write!(f, "cannot insert multiple commands into a prepared statement")
+            }
+            Self::WrongNumberOfParameters(statement_name) => write!(
+                f,
+                "wrong number of parameters for prepared statement \"{}\"",
+                statement_name
+            ),
+            Self::ProtocolViolation(message) => write!(f, "{}", message),
+            Self::InvalidBinaryRepresentation(parameter_number) => {
+                write!(f, "incorrect binary data format in bind parameter {}", parameter_number)
+            }
+            Self::SyntaxError(message) => write!(f, "{}", message),
+            Self::Skipping(kind) => write!(f, "{}, skipping", kind),
         }
     }
 }
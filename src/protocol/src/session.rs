@@ -0,0 +1,323 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    results::{QueryError, QueryEvent, QueryResult, QueryResults},
+    sql_types::PostgreSqlFormat,
+};
+use std::{collections::HashMap, iter::Peekable};
+
+/// Prepared statements and portals that client creates with extended query
+/// messages. Statements are kept in form `S` that server implementation
+/// executes. Unnamed statement and portal are replaced by new ones, named
+/// ones have to be closed before their names are reused
+pub struct Session<S> {
+    statements: HashMap<String, S>,
+    portals: HashMap<String, Portal<S>>,
+}
+
+impl<S> Default for Session<S> {
+    fn default() -> Self {
+        Self {
+            statements: HashMap::new(),
+            portals: HashMap::new(),
+        }
+    }
+}
+
+impl<S> Session<S> {
+    /// saves prepared statement under the name
+    pub fn save_statement(&mut self, name: String, statement: S) -> Result<(), QueryError> {
+        if !name.is_empty() && self.statements.contains_key(&name) {
+            return Err(QueryError::duplicate_prepared_statement(name));
+        }
+        self.statements.insert(name, statement);
+        Ok(())
+    }
+
+    /// prepared statement with the name
+    pub fn statement(&self, name: &str) -> Option<&S> {
+        self.statements.get(name)
+    }
+
+    /// closes prepared statement and portals that are created from it, it is
+    /// not an error to close statement that does not exist
+    pub fn close_statement(&mut self, name: &str) {
+        self.statements.remove(name);
+        self.portals.retain(|_, portal| portal.statement_name != name);
+    }
+
+    /// closes all prepared statements and their portals
+    pub fn close_statements(&mut self) {
+        self.statements.clear();
+        self.portals.clear();
+    }
+
+    /// saves portal under the name
+    pub fn save_portal(&mut self, name: String, portal: Portal<S>) -> Result<(), QueryError> {
+        if !name.is_empty() && self.portals.contains_key(&name) {
+            return Err(QueryError::duplicate_cursor(name));
+        }
+        self.portals.insert(name, portal);
+        Ok(())
+    }
+
+    /// portal with the name
+    pub fn portal_mut(&mut self, name: &str) -> Option<&mut Portal<S>> {
+        self.portals.get_mut(name)
+    }
+
+    /// closes portal, it is not an error to close portal that does not exist
+    pub fn close_portal(&mut self, name: &str) {
+        self.portals.remove(name);
+    }
+
+    /// closes all portals, they exist only until the end of transaction
+    pub fn close_portals(&mut self) {
+        self.portals.clear();
+    }
+}
+
+/// Prepared statement with bound parameters that is ready to be executed
+pub struct Portal<S> {
+    statement_name: String,
+    statement: S,
+    result_formats: Vec<PostgreSqlFormat>,
+    // results are produced when portal is described or executed for the first
+    // time, rows that are not sent yet are kept while portal is suspended
+    results: Option<Peekable<QueryResults>>,
+}
+
+impl<S> Portal<S> {
+    /// Creates portal from the statement with formats of result columns
+    pub fn new(statement_name: String, statement: S, result_formats: Vec<PostgreSqlFormat>) -> Self {
+        Self {
+            statement_name,
+            statement,
+            result_formats,
+            results: None,
+        }
+    }
+
+    /// statement with bound parameters
+    pub fn statement(&self) -> &S {
+        &self.statement
+    }
+
+    /// formats of result columns
+    pub fn result_formats(&self) -> &[PostgreSqlFormat] {
+        &self.result_formats
+    }
+
+    /// whether the portal is already executed
+    pub fn is_started(&self) -> bool {
+        self.results.is_some()
+    }
+
+    /// saves results of portal execution to be sent by `describe` and `fetch`
+    pub fn start(&mut self, results: QueryResults) {
+        self.results = Some(results.peekable());
+    }
+
+    /// description of rows that started portal returns, `NoData` if it
+    /// returns no rows or an error if its execution failed
+    pub fn describe(&mut self) -> QueryResult {
+        let results = match self.results.as_mut() {
+            Some(results) => results,
+            None => return Ok(QueryEvent::NoData),
+        };
+        match results.peek() {
+            Some(Ok(QueryEvent::RowDescription(description, formats))) => {
+                Ok(QueryEvent::RowDescription(description.clone(), formats.clone()))
+            }
+            Some(Err(_)) => results.next().unwrap_or(Ok(QueryEvent::NoData)),
+            _ => Ok(QueryEvent::NoData),
+        }
+    }
+
+    /// takes results of started portal, at most `max_rows` rows if it is
+    /// positive. If there are more rows the portal is suspended until the
+    /// next fetch. Rows are described only by `describe`
+    pub fn fetch(&mut self, max_rows: i32) -> Vec<QueryResult> {
+        let results = match self.results.as_mut() {
+            Some(results) => results,
+            None => return vec![],
+        };
+        let mut fetched = vec![];
+        let mut rows = 0;
+        while let Some(result) = results.next() {
+            match result {
+                Ok(QueryEvent::RowDescription(_, _)) => {}
+                Ok(QueryEvent::DataRow(row)) => {
+                    fetched.push(Ok(QueryEvent::DataRow(row)));
+                    rows += 1;
+                    if rows == max_rows && matches!(results.peek(), Some(Ok(QueryEvent::DataRow(_)))) {
+                        fetched.push(Ok(QueryEvent::PortalSuspended));
+                        break;
+                    }
+                }
+                result => fetched.push(result),
+            }
+        }
+        fetched
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql_types::PostgreSqlType;
+
+    fn selected(rows: i32) -> QueryResults {
+        Box::new(
+            std::iter::once(Ok(QueryEvent::RowDescription(
+                vec![("column".to_owned(), PostgreSqlType::Integer)],
+                vec![PostgreSqlFormat::Text],
+            )))
+            .chain((1..=rows).map(|row| Ok(QueryEvent::DataRow(vec![Some(row.to_string().into_bytes())]))))
+            .chain(std::iter::once(Ok(QueryEvent::RecordsSelected(rows as usize)))),
+        )
+    }
+
+    #[test]
+    fn named_statement_is_not_replaced() {
+        let mut session = Session::default();
+        assert_eq!(session.save_statement("stmt".to_owned(), 1), Ok(()));
+        assert_eq!(
+            session.save_statement("stmt".to_owned(), 2),
+            Err(QueryError::duplicate_prepared_statement("stmt".to_owned()))
+        );
+        assert_eq!(session.statement("stmt"), Some(&1));
+    }
+
+    #[test]
+    fn unnamed_statement_is_replaced() {
+        let mut session = Session::default();
+        assert_eq!(session.save_statement("".to_owned(), 1), Ok(()));
+        assert_eq!(session.save_statement("".to_owned(), 2), Ok(()));
+        assert_eq!(session.statement(""), Some(&2));
+    }
+
+    #[test]
+    fn named_portal_is_not_replaced() {
+        let mut session = Session::default();
+        assert_eq!(
+            session.save_portal("portal".to_owned(), Portal::new("".to_owned(), 1, vec![])),
+            Ok(())
+        );
+        assert_eq!(
+            session.save_portal("portal".to_owned(), Portal::new("".to_owned(), 2, vec![])),
+            Err(QueryError::duplicate_cursor("portal".to_owned()))
+        );
+    }
+
+    #[test]
+    fn closing_statement_closes_its_portals() {
+        let mut session = Session::default();
+        session.save_statement("stmt".to_owned(), 1).unwrap();
+        session
+            .save_portal("portal".to_owned(), Portal::new("stmt".to_owned(), 1, vec![]))
+            .unwrap();
+        session
+            .save_portal("other".to_owned(), Portal::new("".to_owned(), 2, vec![]))
+            .unwrap();
+
+        session.close_statement("stmt");
+
+        assert_eq!(session.statement("stmt"), None);
+        assert!(session.portal_mut("portal").is_none());
+        assert!(session.portal_mut("other").is_some());
+    }
+
+    #[test]
+    fn describe_started_portal() {
+        let mut portal = Portal::new("".to_owned(), (), vec![]);
+        portal.start(selected(1));
+
+        assert_eq!(
+            portal.describe(),
+            Ok(QueryEvent::RowDescription(
+                vec![("column".to_owned(), PostgreSqlType::Integer)],
+                vec![PostgreSqlFormat::Text],
+            ))
+        );
+        assert_eq!(
+            portal.fetch(0),
+            vec![
+                Ok(QueryEvent::DataRow(vec![Some(b"1".to_vec())])),
+                Ok(QueryEvent::RecordsSelected(1))
+            ]
+        );
+    }
+
+    #[test]
+    fn describe_failed_portal() {
+        let mut portal = Portal::new("".to_owned(), (), vec![]);
+        portal.start(Box::new(std::iter::once(Err(QueryError::division_by_zero()))));
+
+        assert_eq!(portal.describe(), Err(QueryError::division_by_zero()));
+    }
+
+    #[test]
+    fn fetch_all_rows() {
+        let mut portal = Portal::new("".to_owned(), (), vec![]);
+        portal.start(selected(2));
+
+        assert_eq!(
+            portal.fetch(0),
+            vec![
+                Ok(QueryEvent::DataRow(vec![Some(b"1".to_vec())])),
+                Ok(QueryEvent::DataRow(vec![Some(b"2".to_vec())])),
+                Ok(QueryEvent::RecordsSelected(2))
+            ]
+        );
+    }
+
+    #[test]
+    fn fetch_rows_with_limit() {
+        let mut portal = Portal::new("".to_owned(), (), vec![]);
+        portal.start(selected(3));
+
+        assert_eq!(
+            portal.fetch(2),
+            vec![
+                Ok(QueryEvent::DataRow(vec![Some(b"1".to_vec())])),
+                Ok(QueryEvent::DataRow(vec![Some(b"2".to_vec())])),
+                Ok(QueryEvent::PortalSuspended)
+            ]
+        );
+        assert_eq!(
+            portal.fetch(2),
+            vec![
+                Ok(QueryEvent::DataRow(vec![Some(b"3".to_vec())])),
+                Ok(QueryEvent::RecordsSelected(3))
+            ]
+        );
+    }
+
+    #[test]
+    fn limit_equal_to_number_of_rows() {
+        let mut portal = Portal::new("".to_owned(), (), vec![]);
+        portal.start(selected(2));
+
+        assert_eq!(
+            portal.fetch(2),
+            vec![
+                Ok(QueryEvent::DataRow(vec![Some(b"1".to_vec())])),
+                Ok(QueryEvent::DataRow(vec![Some(b"2".to_vec())])),
+                Ok(QueryEvent::RecordsSelected(2))
+            ]
+        );
+    }
+}
@@ -14,11 +14,18 @@
 
 /// Represents PostgreSQL data type and methods to send over wire
 #[allow(missing_docs)]
-#[derive(PartialEq, Debug, Copy, Clone)]
+#[derive(PartialEq, Debug, Clone)]
 pub enum PostgreSqlType {
     Bool,
-    Char,
-    VarChar,
+    // blank padded string of the length
+    Char(u64),
+    // string of limited or unlimited length
+    VarChar(Option<u64>),
+    Text,
+    Bytea,
+    Uuid,
+    Json,
+    Jsonb,
     Decimal,
     SmallInt,
     Integer,
@@ -31,6 +38,9 @@ pub enum PostgreSqlType {
     TimestampWithTimeZone,
     Date,
     Interval,
+    Array(Box<PostgreSqlType>),
+    // user defined enum type with its OID
+    Enum(u32),
 }
 
 impl PostgreSqlType {
@@ -38,13 +48,15 @@ impl PostgreSqlType {
     pub fn pg_oid(&self) -> i32 {
         match self {
             Self::Bool => 16,
-            Self::Char => 18,
-            Self::BigInt => 20,           // PG int8
-            Self::SmallInt => 21,         // PG int2
-            Self::Integer => 23,          // PG int4
+            Self::Bytea => 17,
+            Self::Char(_) => 1042, // PG bpchar
+            Self::BigInt => 20,    // PG int8
+            Self::SmallInt => 21,  // PG int2
+            Self::Integer => 23,   // PG int4
+            Self::Text => 25,
             Self::Real => 700,            // PG float4
             Self::DoublePrecision => 701, // PG float8
-            Self::VarChar => 1043,
+            Self::VarChar(_) => 1043,
             Self::Date => 1082,
             Self::Time => 1083,
             Self::Timestamp => 1114,
@@ -52,6 +64,36 @@ impl PostgreSqlType {
             Self::Interval => 1186,
             Self::TimeWithTimeZone => 1266, // PG Timetz
             Self::Decimal => 1700,          // PG Numeric & Decimal
+            Self::Uuid => 2950,
+            Self::Json => 114,
+            Self::Jsonb => 3802,
+            Self::Enum(oid) => *oid as i32,
+            Self::Array(element) => match **element {
+                Self::Bool => 1000,
+                Self::Bytea => 1001,
+                Self::Char(_) => 1014,
+                Self::SmallInt => 1005,
+                Self::Integer => 1007,
+                Self::Text => 1009,
+                Self::VarChar(_) => 1015,
+                Self::BigInt => 1016,
+                Self::Real => 1021,
+                Self::DoublePrecision => 1022,
+                Self::Timestamp => 1115,
+                Self::Date => 1182,
+                Self::Time => 1183,
+                Self::TimestampWithTimeZone => 1185,
+                Self::Interval => 1187,
+                Self::Decimal => 1231,
+                Self::TimeWithTimeZone => 1270,
+                Self::Uuid => 2951,
+                Self::Json => 199,
+                Self::Jsonb => 3807,
+                // OID that follows OID of enum type is reserved for its array type
+                Self::Enum(oid) => oid as i32 + 1,
+                // multidimensional arrays have the same type as one dimensional
+                Self::Array(_) => element.pg_oid(),
+            },
         }
     }
 
@@ -59,13 +101,18 @@ impl PostgreSqlType {
     pub fn pg_len(&self) -> i16 {
         match self {
             Self::Bool => 1,
-            Self::Char => 1,
+            Self::Char(_) => -1,
             Self::BigInt => 8,
             Self::SmallInt => 2,
             Self::Integer => 4,
             Self::Real => 4,
             Self::DoublePrecision => 8,
-            Self::VarChar => -1,
+            Self::VarChar(_) => -1,
+            Self::Text => -1,
+            Self::Bytea => -1,
+            Self::Uuid => 16,
+            Self::Json => -1,
+            Self::Jsonb => -1,
             Self::Date => 4,
             Self::Time => 8,
             Self::Timestamp => 8,
@@ -73,6 +120,47 @@ impl PostgreSqlType {
             Self::Interval => 16,
             Self::TimeWithTimeZone => 12,
             Self::Decimal => -1,
+            Self::Array(_) => -1,
+            Self::Enum(_) => 4,
+        }
+    }
+
+    /// PostgreSQL type modifier, e.g. the length of `varchar(5)` with size of
+    /// its header or -1 if the type has no modifier
+    pub fn pg_typmod(&self) -> i32 {
+        match self {
+            Self::Char(length) | Self::VarChar(Some(length)) => *length as i32 + 4,
+            Self::Array(element) => element.pg_typmod(),
+            _ => -1,
+        }
+    }
+}
+
+/// Format of values sent over wire, clients choose it for every result column
+/// of a portal
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum PostgreSqlFormat {
+    /// values in their text representation
+    Text,
+    /// values in type specific binary representation
+    Binary,
+}
+
+impl PostgreSqlFormat {
+    /// Format of the code sent over wire, `None` if the code is unknown
+    pub fn from_code(code: i16) -> Option<Self> {
+        match code {
+            0 => Some(Self::Text),
+            1 => Some(Self::Binary),
+            _ => None,
+        }
+    }
+
+    /// Code of the format sent over wire
+    pub fn code(&self) -> i16 {
+        match self {
+            Self::Text => 0,
+            Self::Binary => 1,
         }
     }
 }
@@ -15,22 +15,45 @@
 use kernel::SystemResult;
 use protocol::results::{QueryError, QueryEvent, QueryResult};
 use sqlparser::ast::ObjectName;
-use std::sync::{Arc, Mutex};
-use storage::{backend::BackendStorage, frontend::FrontendStorage, SchemaAlreadyExists};
+use std::sync::Arc;
+use storage::{
+    backend::BackendStorage,
+    frontend::FrontendStorage,
+    lock::{LockDuration, LockMode, Resource, SessionId},
+    SchemaAlreadyExists,
+};
 
 pub(crate) struct CreateSchemaCommand<P: BackendStorage> {
     schema_name: ObjectName,
-    storage: Arc<Mutex<FrontendStorage<P>>>,
+    storage: Arc<FrontendStorage<P>>,
+    session_id: SessionId,
 }
 
 impl<P: BackendStorage> CreateSchemaCommand<P> {
-    pub(crate) fn new(schema_name: ObjectName, storage: Arc<Mutex<FrontendStorage<P>>>) -> CreateSchemaCommand<P> {
-        CreateSchemaCommand { schema_name, storage }
+    pub(crate) fn new(
+        schema_name: ObjectName,
+        storage: Arc<FrontendStorage<P>>,
+        session_id: SessionId,
+    ) -> CreateSchemaCommand<P> {
+        CreateSchemaCommand {
+            schema_name,
+            storage,
+            session_id,
+        }
     }
 
     pub(crate) fn execute(&mut self) -> SystemResult<QueryResult> {
         let schema_name = self.schema_name.to_string();
-        match (self.storage.lock().unwrap()).create_schema(&schema_name)? {
+        if let Err(error) = crate::lock_resources(
+            &self.storage,
+            self.session_id,
+            vec![(Resource::schema(&schema_name), LockMode::AccessExclusive)],
+            false,
+            LockDuration::Transaction,
+        ) {
+            return Ok(Err(error));
+        }
+        match self.storage.create_schema(&schema_name, self.session_id)? {
             Ok(()) => Ok(Ok(QueryEvent::SchemaCreated)),
             Err(SchemaAlreadyExists) => Ok(Err(QueryError::schema_already_exists(schema_name))),
         }
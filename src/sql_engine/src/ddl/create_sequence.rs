@@ -0,0 +1,138 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::parser::SequenceParameters;
+use kernel::SystemResult;
+use protocol::results::{QueryError, QueryEvent, QueryResult, QueryResults};
+use sqlparser::ast::ObjectName;
+use std::sync::Arc;
+use storage::{
+    backend::BackendStorage,
+    frontend::FrontendStorage,
+    lock::{LockDuration, LockMode, Resource, SessionId},
+    CreateSequenceError, SequenceOptions,
+};
+
+pub(crate) struct CreateSequenceCommand<P: BackendStorage> {
+    name: ObjectName,
+    if_not_exists: bool,
+    parameters: SequenceParameters,
+    storage: Arc<FrontendStorage<P>>,
+    session_id: SessionId,
+}
+
+impl<P: BackendStorage> CreateSequenceCommand<P> {
+    pub(crate) fn new(
+        name: ObjectName,
+        if_not_exists: bool,
+        parameters: SequenceParameters,
+        storage: Arc<FrontendStorage<P>>,
+        session_id: SessionId,
+    ) -> CreateSequenceCommand<P> {
+        CreateSequenceCommand {
+            name,
+            if_not_exists,
+            parameters,
+            storage,
+            session_id,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<QueryResults> {
+        let mut notices = vec![];
+        let result = self.create_sequence(&mut notices)?;
+        Ok(crate::with_notices(notices, result))
+    }
+
+    // existing sequence is skipped with notice when `IF NOT EXISTS` is given
+    fn create_sequence(&mut self, notices: &mut Vec<QueryError>) -> SystemResult<QueryResult> {
+        let (schema_name, sequence_name) = match crate::qualified_name(&self.name) {
+            Ok(name) => name,
+            Err(error) => return Ok(Err(error)),
+        };
+        if let Err(error) = crate::lock_resources(
+            &self.storage,
+            self.session_id,
+            vec![(Resource::schema(&schema_name), LockMode::AccessShare)],
+            false,
+            LockDuration::Transaction,
+        ) {
+            return Ok(Err(error));
+        }
+        let options = match sequence_options(&self.parameters, i64::MIN, i64::MAX) {
+            Ok(options) => options,
+            Err(error) => return Ok(Err(error)),
+        };
+        match self
+            .storage
+            .create_sequence(&schema_name, &sequence_name, options, None, self.session_id)?
+        {
+            Ok(()) => Ok(Ok(QueryEvent::SequenceCreated)),
+            Err(CreateSequenceError::SchemaDoesNotExist) => Ok(Err(QueryError::schema_does_not_exist(schema_name))),
+            Err(CreateSequenceError::SequenceAlreadyExists) if self.if_not_exists => {
+                notices.push(QueryError::sequence_already_exists_skipping(sequence_name));
+                Ok(Ok(QueryEvent::SequenceCreated))
+            }
+            Err(CreateSequenceError::SequenceAlreadyExists) => {
+                Ok(Err(QueryError::sequence_already_exists(sequence_name)))
+            }
+        }
+    }
+}
+
+/// Options that are not given are defaults of ascending or descending
+/// sequence of a type with the given range of values
+pub(crate) fn sequence_options(
+    parameters: &SequenceParameters,
+    type_min: i64,
+    type_max: i64,
+) -> Result<SequenceOptions, QueryError> {
+    let increment = parameters.increment.unwrap_or(1);
+    if increment == 0 {
+        return Err(QueryError::invalid_sequence_parameter(
+            "INCREMENT must not be zero".to_owned(),
+        ));
+    }
+    let min_value = parameters.min_value.unwrap_or(if increment > 0 { 1 } else { type_min });
+    let max_value = parameters
+        .max_value
+        .unwrap_or(if increment > 0 { type_max } else { -1 });
+    if min_value >= max_value {
+        return Err(QueryError::invalid_sequence_parameter(format!(
+            "MINVALUE ({}) must be less than MAXVALUE ({})",
+            min_value, max_value
+        )));
+    }
+    let start = parameters
+        .start
+        .unwrap_or(if increment > 0 { min_value } else { max_value });
+    if start < min_value {
+        return Err(QueryError::invalid_sequence_parameter(format!(
+            "START value ({}) cannot be less than MINVALUE ({})",
+            start, min_value
+        )));
+    }
+    if start > max_value {
+        return Err(QueryError::invalid_sequence_parameter(format!(
+            "START value ({}) cannot be greater than MAXVALUE ({})",
+            start, max_value
+        )));
+    }
+    Ok(SequenceOptions {
+        start,
+        increment,
+        min_value,
+        max_value,
+    })
+}
@@ -12,54 +12,295 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::{ddl::create_sequence::sequence_options, parser::SequenceParameters};
 use kernel::SystemResult;
 use protocol::results::{QueryError, QueryEvent, QueryResult};
 use sql_types::SqlType;
-use sqlparser::ast::{ColumnDef, ObjectName};
-use std::sync::{Arc, Mutex};
-use storage::{backend::BackendStorage, frontend::FrontendStorage, CreateTableError};
+use sqlparser::ast::{ColumnDef, ColumnOption, DataType, Expr, ObjectName, Value};
+use std::sync::Arc;
+use storage::{
+    backend::BackendStorage,
+    frontend::FrontendStorage,
+    lock::{LockDuration, LockMode, Resource, SessionId},
+    CreateSequenceError, CreateTableError,
+};
 
 pub(crate) struct CreateTableCommand<P: BackendStorage> {
     name: ObjectName,
     columns: Vec<ColumnDef>,
-    storage: Arc<Mutex<FrontendStorage<P>>>,
+    storage: Arc<FrontendStorage<P>>,
+    session_id: SessionId,
 }
 
 impl<P: BackendStorage> CreateTableCommand<P> {
     pub(crate) fn new(
         name: ObjectName,
         columns: Vec<ColumnDef>,
-        storage: Arc<Mutex<FrontendStorage<P>>>,
+        storage: Arc<FrontendStorage<P>>,
+        session_id: SessionId,
     ) -> CreateTableCommand<P> {
-        CreateTableCommand { name, columns, storage }
+        CreateTableCommand {
+            name,
+            columns,
+            storage,
+            session_id,
+        }
     }
 
     pub(crate) fn execute(&mut self) -> SystemResult<QueryResult> {
-        let table_name = self.name.0.pop().unwrap().to_string();
-        let schema_name = self.name.0.pop().unwrap().to_string();
-        match (self.storage.lock().unwrap()).create_table(
+        let (schema_name, table_name) = match crate::qualified_name(&self.name) {
+            Ok(name) => name,
+            Err(error) => return Ok(Err(error)),
+        };
+        if let Err(error) = crate::lock_resources(
+            &self.storage,
+            self.session_id,
+            vec![
+                (Resource::schema(&schema_name), LockMode::AccessShare),
+                (Resource::table(&schema_name, &table_name), LockMode::AccessExclusive),
+            ],
+            false,
+            LockDuration::Transaction,
+        ) {
+            return Ok(Err(error));
+        }
+        let mut columns = vec![];
+        let mut generated_always = vec![];
+        let mut sequences = vec![];
+        for column in self.columns.iter() {
+            let name = column.name.to_string();
+            let serial = serial_type(&column.data_type);
+            let sql_type = match serial.clone() {
+                Some(sql_type) => sql_type,
+                None => match sql_type(&column.data_type, &mut |name| {
+                    user_type(&self.storage, &schema_name, name)
+                })? {
+                    Ok(sql_type) => sql_type,
+                    Err(error) => return Ok(Err(error)),
+                },
+            };
+            let parameters = match identity_parameters(column) {
+                Some((raw_parameters, always)) => match crate::parser::parse_sequence_parameters(raw_parameters) {
+                    Ok(parameters) => {
+                        if always {
+                            generated_always.push(name.clone());
+                        }
+                        Some(parameters)
+                    }
+                    Err(_) => return Ok(Err(QueryError::not_supported_operation(raw_parameters.to_owned()))),
+                },
+                None if serial.is_some() => Some(SequenceParameters::default()),
+                None => None,
+            };
+            let default = match parameters {
+                // values of SERIAL and IDENTITY columns are taken from sequences
+                // that are named and dropped as PostgreSQL does
+                Some(parameters) => {
+                    let (type_min, type_max) = match sql_type {
+                        SqlType::SmallInt => (i16::MIN as i64, i16::MAX as i64),
+                        SqlType::Integer => (i32::MIN as i64, i32::MAX as i64),
+                        SqlType::BigInt => (i64::MIN, i64::MAX),
+                        _ => {
+                            return Ok(Err(QueryError::invalid_sequence_parameter(
+                                "identity column type must be smallint, integer, or bigint".to_owned(),
+                            )))
+                        }
+                    };
+                    let options = match sequence_options(&parameters, type_min, type_max) {
+                        Ok(options) => options,
+                        Err(error) => return Ok(Err(error)),
+                    };
+                    let sequence_name = format!("{}_{}_seq", table_name, name);
+                    let default = format!("nextval('{}.{}')", schema_name, sequence_name);
+                    sequences.push((sequence_name, options));
+                    Some(default)
+                }
+                // default is evaluated on every insert, e.g. `DEFAULT gen_random_uuid()`
+                None => column.options.iter().find_map(|option| match &option.option {
+                    ColumnOption::Default(expr) => Some(expr.to_string()),
+                    _ => None,
+                }),
+            };
+            columns.push((name, sql_type, default));
+        }
+        match self.storage.create_table_with_defaults(
             &schema_name,
             &table_name,
-            self.columns
-                .iter()
-                .cloned()
-                .map(|c| {
-                    let name = c.name.to_string();
-                    let sql_type = match c.data_type {
-                        sqlparser::ast::DataType::SmallInt => SqlType::SmallInt,
-                        sqlparser::ast::DataType::Int => SqlType::Integer,
-                        sqlparser::ast::DataType::BigInt => SqlType::BigInt,
-                        sqlparser::ast::DataType::Char(len) => SqlType::Char(len.unwrap_or(255)),
-                        sqlparser::ast::DataType::Varchar(len) => SqlType::VarChar(len.unwrap_or(255)),
-                        _ => unimplemented!(),
-                    };
-                    (name, sql_type)
-                })
-                .collect(),
+            columns,
+            &generated_always,
+            self.session_id,
         )? {
-            Ok(()) => Ok(Ok(QueryEvent::TableCreated)),
+            Ok(()) => {
+                for (sequence_name, options) in sequences {
+                    match self.storage.create_sequence(
+                        &schema_name,
+                        &sequence_name,
+                        options,
+                        Some(&table_name),
+                        self.session_id,
+                    )? {
+                        Ok(()) => {}
+                        Err(CreateSequenceError::SchemaDoesNotExist) => {
+                            return Ok(Err(QueryError::schema_does_not_exist(schema_name)))
+                        }
+                        Err(CreateSequenceError::SequenceAlreadyExists) => {
+                            return Ok(Err(QueryError::sequence_already_exists(sequence_name)))
+                        }
+                    }
+                }
+                Ok(Ok(QueryEvent::TableCreated))
+            }
             Err(CreateTableError::SchemaDoesNotExist) => Ok(Err(QueryError::schema_does_not_exist(schema_name))),
-            Err(CreateTableError::TableAlreadyExists) => Ok(Err(QueryError::table_already_exists(table_name))),
+            Err(CreateTableError::TableAlreadyExists) => Ok(Err(QueryError::table_already_exists(table_name.clone())
+                .with_schema(schema_name)
+                .with_table(table_name))),
         }
     }
 }
+
+/// User defined type that is looked up in the given schema unless its name
+/// is qualified
+pub(crate) fn user_type<P: BackendStorage>(
+    storage: &FrontendStorage<P>,
+    schema_name: &str,
+    name: &ObjectName,
+) -> SystemResult<Result<SqlType, QueryError>> {
+    let (type_schema, type_name) = match name.0.as_slice() {
+        [type_name] => (schema_name.to_owned(), type_name.to_string()),
+        [type_schema, type_name] => (type_schema.to_string(), type_name.to_string()),
+        _ => return Ok(Err(QueryError::not_supported_operation(name.to_string()))),
+    };
+    match storage.user_type(&type_schema, &type_name)? {
+        Some(sql_type) => Ok(Ok(sql_type)),
+        None => Ok(Err(QueryError::type_does_not_exist(name.to_string()))),
+    }
+}
+
+/// Type of column or cast target, user defined types are looked up by the
+/// given function
+pub(crate) fn sql_type(
+    data_type: &DataType,
+    user_type: &mut dyn FnMut(&ObjectName) -> SystemResult<Result<SqlType, QueryError>>,
+) -> SystemResult<Result<SqlType, QueryError>> {
+    let sql_type = match data_type {
+        DataType::Boolean => SqlType::Bool,
+        DataType::SmallInt => SqlType::SmallInt,
+        DataType::Int => SqlType::Integer,
+        DataType::BigInt => SqlType::BigInt,
+        DataType::Real => SqlType::Real,
+        DataType::Double => SqlType::DoublePrecision,
+        // FLOAT(1) to FLOAT(24) is REAL, FLOAT(25) to FLOAT(53) and FLOAT are DOUBLE PRECISION
+        DataType::Float(Some(precision)) if *precision <= 24 => SqlType::Real,
+        DataType::Float(_) => SqlType::DoublePrecision,
+        DataType::Decimal(None, _) => SqlType::Decimal(None),
+        // NUMERIC(p) is the same as NUMERIC(p, 0)
+        DataType::Decimal(Some(precision), scale) => {
+            let scale = scale.unwrap_or(0);
+            if !(1..=1000).contains(precision) {
+                return Ok(Err(QueryError::invalid_numeric_precision(*precision)));
+            }
+            if scale > *precision {
+                return Ok(Err(QueryError::invalid_numeric_scale(scale, *precision)));
+            }
+            SqlType::Decimal(Some((*precision, scale)))
+        }
+        DataType::Date => SqlType::Date,
+        DataType::Time => SqlType::Time,
+        DataType::Timestamp => SqlType::Timestamp,
+        DataType::Interval => SqlType::Interval,
+        // CHAR is CHAR(1) and VARCHAR is of any length as in PostgreSQL
+        DataType::Char(len) => SqlType::Char(len.unwrap_or(1)),
+        DataType::Varchar(len) => SqlType::VarChar(*len),
+        DataType::Text => SqlType::Text,
+        DataType::Bytea => SqlType::Bytea,
+        DataType::Uuid => SqlType::Uuid,
+        DataType::Array(element) => match sql_type(element, user_type)? {
+            Ok(element) => SqlType::Array(Box::new(element)),
+            Err(error) => return Ok(Err(error)),
+        },
+        // array types are parsed as custom types, see `parser::rewrite_array_types`
+        DataType::Custom(name) if name.0.last().map(|ident| ident.value.ends_with("[]")).unwrap_or(false) => {
+            let mut element = name.clone();
+            if let Some(ident) = element.0.last_mut() {
+                ident.value.truncate(ident.value.len() - 2);
+                ident.quote_style = None;
+            }
+            match crate::parser::parse_data_type(&element.to_string()) {
+                Ok(element) => match sql_type(&element, user_type)? {
+                    Ok(element) => SqlType::Array(Box::new(element)),
+                    Err(error) => return Ok(Err(error)),
+                },
+                Err(_) => return Ok(Err(QueryError::not_supported_operation(name.to_string()))),
+            }
+        }
+        DataType::Custom(name) => match builtin_type(name) {
+            Some(sql_type) => sql_type,
+            None => match user_type(name)? {
+                Ok(sql_type) => sql_type,
+                Err(error) => return Ok(Err(error)),
+            },
+        },
+        // REGCLASS, other types that are not supported aren't parsed
+        data_type => {
+            return Ok(Err(QueryError::type_does_not_exist(
+                data_type.to_string().to_lowercase(),
+            )))
+        }
+    };
+    Ok(Ok(sql_type))
+}
+
+// types and aliases of PostgreSQL that are parsed as custom types
+fn builtin_type(name: &ObjectName) -> Option<SqlType> {
+    match name.to_string().to_lowercase().as_str() {
+        "bool" => Some(SqlType::Bool),
+        "int2" => Some(SqlType::SmallInt),
+        "int4" => Some(SqlType::Integer),
+        "int8" => Some(SqlType::BigInt),
+        "float4" => Some(SqlType::Real),
+        "float8" => Some(SqlType::DoublePrecision),
+        "timetz" => Some(SqlType::TimeWithTimeZone),
+        "timestamptz" => Some(SqlType::TimestampWithTimeZone),
+        "json" => Some(SqlType::Json),
+        "jsonb" => Some(SqlType::Jsonb),
+        _ => None,
+    }
+}
+
+// SERIAL types are integer types which values are generated by sequences
+fn serial_type(data_type: &DataType) -> Option<SqlType> {
+    match data_type {
+        DataType::Custom(name) => match name.to_string().to_lowercase().as_str() {
+            "smallserial" | "serial2" => Some(SqlType::SmallInt),
+            "serial" | "serial4" => Some(SqlType::Integer),
+            "bigserial" | "serial8" => Some(SqlType::BigInt),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// `GENERATED ... AS IDENTITY` is parsed as `DEFAULT "identity"('sequence options')`
+// with `'always'` second argument for `GENERATED ALWAYS` column, see
+// `parser::rewrite_identity_columns`. Returns sequence options and whether the
+// column is `GENERATED ALWAYS`
+fn identity_parameters(column: &ColumnDef) -> Option<(&str, bool)> {
+    column.options.iter().find_map(|option| match &option.option {
+        ColumnOption::Default(Expr::Function(function))
+            if function.name.0.len() == 1
+                && function.name.0[0].value == "identity"
+                && function.name.0[0].quote_style == Some('"') =>
+        {
+            match function.args.as_slice() {
+                [Expr::Value(Value::SingleQuotedString(parameters))] => Some((parameters.as_str(), false)),
+                [Expr::Value(Value::SingleQuotedString(parameters)), Expr::Value(Value::SingleQuotedString(always))]
+                    if always == "always" =>
+                {
+                    Some((parameters.as_str(), true))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    })
+}
@@ -0,0 +1,71 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use kernel::SystemResult;
+use protocol::results::{QueryError, QueryEvent, QueryResult};
+use sqlparser::ast::ObjectName;
+use std::sync::Arc;
+use storage::{
+    backend::BackendStorage,
+    frontend::FrontendStorage,
+    lock::{LockDuration, LockMode, Resource, SessionId},
+    CreateTypeError,
+};
+
+pub(crate) struct CreateTypeCommand<P: BackendStorage> {
+    name: ObjectName,
+    labels: Vec<String>,
+    storage: Arc<FrontendStorage<P>>,
+    session_id: SessionId,
+}
+
+impl<P: BackendStorage> CreateTypeCommand<P> {
+    pub(crate) fn new(
+        name: ObjectName,
+        labels: Vec<String>,
+        storage: Arc<FrontendStorage<P>>,
+        session_id: SessionId,
+    ) -> CreateTypeCommand<P> {
+        CreateTypeCommand {
+            name,
+            labels,
+            storage,
+            session_id,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<QueryResult> {
+        let (schema_name, type_name) = match crate::qualified_name(&self.name) {
+            Ok(name) => name,
+            Err(error) => return Ok(Err(error)),
+        };
+        if let Err(error) = crate::lock_resources(
+            &self.storage,
+            self.session_id,
+            vec![(Resource::schema(&schema_name), LockMode::AccessShare)],
+            false,
+            LockDuration::Transaction,
+        ) {
+            return Ok(Err(error));
+        }
+        match self
+            .storage
+            .create_enum_type(&schema_name, &type_name, self.labels.clone(), self.session_id)?
+        {
+            Ok(()) => Ok(Ok(QueryEvent::TypeCreated)),
+            Err(CreateTypeError::SchemaDoesNotExist) => Ok(Err(QueryError::schema_does_not_exist(schema_name))),
+            Err(CreateTypeError::TypeAlreadyExists) => Ok(Err(QueryError::type_already_exists(type_name))),
+        }
+    }
+}
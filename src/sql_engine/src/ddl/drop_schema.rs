@@ -15,22 +15,45 @@
 use kernel::SystemResult;
 use protocol::results::{QueryError, QueryEvent, QueryResult};
 use sqlparser::ast::ObjectName;
-use std::sync::{Arc, Mutex};
-use storage::{backend::BackendStorage, frontend::FrontendStorage, SchemaDoesNotExist};
+use std::sync::Arc;
+use storage::{
+    backend::BackendStorage,
+    frontend::FrontendStorage,
+    lock::{LockDuration, LockMode, Resource, SessionId},
+    SchemaDoesNotExist,
+};
 
 pub(crate) struct DropSchemaCommand<P: BackendStorage> {
     name: ObjectName,
-    storage: Arc<Mutex<FrontendStorage<P>>>,
+    storage: Arc<FrontendStorage<P>>,
+    session_id: SessionId,
 }
 
 impl<P: BackendStorage> DropSchemaCommand<P> {
-    pub(crate) fn new(name: ObjectName, storage: Arc<Mutex<FrontendStorage<P>>>) -> DropSchemaCommand<P> {
-        DropSchemaCommand { name, storage }
+    pub(crate) fn new(
+        name: ObjectName,
+        storage: Arc<FrontendStorage<P>>,
+        session_id: SessionId,
+    ) -> DropSchemaCommand<P> {
+        DropSchemaCommand {
+            name,
+            storage,
+            session_id,
+        }
     }
 
     pub(crate) fn execute(&mut self) -> SystemResult<QueryResult> {
         let schema_name = self.name.0[0].to_string();
-        match (self.storage.lock().unwrap()).drop_schema(&schema_name)? {
+        if let Err(error) = crate::lock_resources(
+            &self.storage,
+            self.session_id,
+            vec![(Resource::schema(&schema_name), LockMode::AccessExclusive)],
+            false,
+            LockDuration::Transaction,
+        ) {
+            return Ok(Err(error));
+        }
+        match self.storage.drop_schema(&schema_name, self.session_id)? {
             Ok(()) => Ok(Ok(QueryEvent::SchemaDropped)),
             Err(SchemaDoesNotExist) => Ok(Err(QueryError::schema_does_not_exist(schema_name))),
         }
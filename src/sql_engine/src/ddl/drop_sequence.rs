@@ -0,0 +1,88 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use kernel::SystemResult;
+use protocol::results::{QueryError, QueryEvent, QueryResult, QueryResults};
+use sqlparser::ast::ObjectName;
+use std::sync::Arc;
+use storage::{
+    backend::BackendStorage,
+    frontend::FrontendStorage,
+    lock::{LockDuration, LockMode, Resource, SessionId},
+    DropSequenceError,
+};
+
+pub(crate) struct DropSequenceCommand<P: BackendStorage> {
+    names: Vec<ObjectName>,
+    if_exists: bool,
+    storage: Arc<FrontendStorage<P>>,
+    session_id: SessionId,
+}
+
+impl<P: BackendStorage> DropSequenceCommand<P> {
+    pub(crate) fn new(
+        names: Vec<ObjectName>,
+        if_exists: bool,
+        storage: Arc<FrontendStorage<P>>,
+        session_id: SessionId,
+    ) -> DropSequenceCommand<P> {
+        DropSequenceCommand {
+            names,
+            if_exists,
+            storage,
+            session_id,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<QueryResults> {
+        let mut notices = vec![];
+        let result = self.drop_sequences(&mut notices)?;
+        Ok(crate::with_notices(notices, result))
+    }
+
+    // sequences that do not exist are skipped with notice when `IF EXISTS` is given
+    fn drop_sequences(&mut self, notices: &mut Vec<QueryError>) -> SystemResult<QueryResult> {
+        for name in self.names.iter() {
+            let (schema_name, sequence_name) = match crate::qualified_name(name) {
+                Ok(name) => name,
+                Err(error) => return Ok(Err(error)),
+            };
+            if let Err(error) = crate::lock_resources(
+                &self.storage,
+                self.session_id,
+                vec![(Resource::schema(&schema_name), LockMode::AccessShare)],
+                false,
+                LockDuration::Transaction,
+            ) {
+                return Ok(Err(error));
+            }
+            match self
+                .storage
+                .drop_sequence(&schema_name, &sequence_name, self.session_id)?
+            {
+                Ok(()) => {}
+                Err(DropSequenceError::SchemaDoesNotExist) => {
+                    return Ok(Err(QueryError::schema_does_not_exist(schema_name)))
+                }
+                Err(DropSequenceError::SequenceDoesNotExist) if self.if_exists => {
+                    notices.push(QueryError::sequence_does_not_exist_skipping(name.to_string()))
+                }
+                Err(DropSequenceError::SequenceDoesNotExist) => {
+                    return Ok(Err(QueryError::sequence_does_not_exist(name.to_string())))
+                }
+            }
+        }
+        Ok(Ok(QueryEvent::SequenceDropped))
+    }
+}
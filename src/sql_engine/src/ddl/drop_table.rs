@@ -15,27 +15,53 @@
 use kernel::SystemResult;
 use protocol::results::{QueryError, QueryEvent, QueryResult};
 use sqlparser::ast::ObjectName;
-use std::sync::{Arc, Mutex};
-use storage::{backend::BackendStorage, frontend::FrontendStorage, DropTableError};
+use std::sync::Arc;
+use storage::{
+    backend::BackendStorage,
+    frontend::FrontendStorage,
+    lock::{LockDuration, LockMode, Resource, SessionId},
+    DropTableError,
+};
 
 pub(crate) struct DropTableCommand<P: BackendStorage> {
     name: ObjectName,
-    storage: Arc<Mutex<FrontendStorage<P>>>,
+    storage: Arc<FrontendStorage<P>>,
+    session_id: SessionId,
 }
 
 impl<P: BackendStorage> DropTableCommand<P> {
-    pub(crate) fn new(name: ObjectName, storage: Arc<Mutex<FrontendStorage<P>>>) -> DropTableCommand<P> {
-        DropTableCommand { name, storage }
+    pub(crate) fn new(
+        name: ObjectName,
+        storage: Arc<FrontendStorage<P>>,
+        session_id: SessionId,
+    ) -> DropTableCommand<P> {
+        DropTableCommand {
+            name,
+            storage,
+            session_id,
+        }
     }
 
     pub(crate) fn execute(&mut self) -> SystemResult<QueryResult> {
-        let table_name = self.name.0[1].to_string();
-        let schema_name = self.name.0[0].to_string();
-        match (self.storage.lock().unwrap()).drop_table(&schema_name, &table_name)? {
+        let (schema_name, table_name) = match crate::qualified_name(&self.name) {
+            Ok(name) => name,
+            Err(error) => return Ok(Err(error)),
+        };
+        if let Err(error) = crate::lock_resources(
+            &self.storage,
+            self.session_id,
+            vec![
+                (Resource::schema(&schema_name), LockMode::AccessShare),
+                (Resource::table(&schema_name, &table_name), LockMode::AccessExclusive),
+            ],
+            false,
+            LockDuration::Transaction,
+        ) {
+            return Ok(Err(error));
+        }
+        match self.storage.drop_table(&schema_name, &table_name, self.session_id)? {
             Ok(()) => Ok(Ok(QueryEvent::TableDropped)),
-            Err(DropTableError::TableDoesNotExist) => Ok(Err(QueryError::table_does_not_exist(
-                schema_name + "." + table_name.as_str(),
-            ))),
+            Err(DropTableError::TableDoesNotExist) => Ok(Err(crate::table_does_not_exist(&schema_name, &table_name))),
             Err(DropTableError::SchemaDoesNotExist) => Ok(Err(QueryError::schema_does_not_exist(schema_name))),
         }
     }
@@ -13,6 +13,10 @@
 // limitations under the License.
 
 pub(crate) mod create_schema;
+pub(crate) mod create_sequence;
 pub(crate) mod create_table;
+pub(crate) mod create_type;
 pub(crate) mod drop_schema;
+pub(crate) mod drop_sequence;
 pub(crate) mod drop_table;
+pub(crate) mod drop_type;
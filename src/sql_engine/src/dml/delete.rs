@@ -15,37 +15,63 @@
 use kernel::SystemResult;
 use protocol::results::{QueryError, QueryEvent, QueryResult};
 use sqlparser::ast::ObjectName;
-use std::sync::{Arc, Mutex};
-use storage::{backend::BackendStorage, frontend::FrontendStorage, OperationOnTableError};
+use std::sync::Arc;
+use storage::{
+    backend::BackendStorage,
+    frontend::FrontendStorage,
+    lock::{LockDuration, LockMode, Resource, SessionId},
+    OperationOnTableError,
+};
 
 pub(crate) struct DeleteCommand<'q, P: BackendStorage> {
     raw_sql_query: &'q str,
     name: ObjectName,
-    storage: Arc<Mutex<FrontendStorage<P>>>,
+    storage: Arc<FrontendStorage<P>>,
+    session_id: SessionId,
 }
 
 impl<P: BackendStorage> DeleteCommand<'_, P> {
     pub(crate) fn new(
         raw_sql_query: &'_ str,
         name: ObjectName,
-        storage: Arc<Mutex<FrontendStorage<P>>>,
+        storage: Arc<FrontendStorage<P>>,
+        session_id: SessionId,
     ) -> DeleteCommand<P> {
         DeleteCommand {
             raw_sql_query,
             name,
             storage,
+            session_id,
         }
     }
 
     pub(crate) fn execute(&mut self) -> SystemResult<QueryResult> {
-        let schema_name = self.name.0[0].to_string();
-        let table_name = self.name.0[1].to_string();
-        match (self.storage.lock().unwrap()).delete_all_from(&schema_name, &table_name)? {
+        let (schema_name, table_name) = match crate::qualified_name(&self.name) {
+            Ok(name) => name,
+            Err(error) => return Ok(Err(error)),
+        };
+        if let Err(error) = crate::lock_resources(
+            &self.storage,
+            self.session_id,
+            vec![
+                (Resource::schema(&schema_name), LockMode::AccessShare),
+                (Resource::table(&schema_name, &table_name), LockMode::RowExclusive),
+                (Resource::rows(&schema_name, &table_name), LockMode::Exclusive),
+            ],
+            false,
+            LockDuration::Transaction,
+        ) {
+            return Ok(Err(error));
+        }
+        match self
+            .storage
+            .delete_all_from(&schema_name, &table_name, self.session_id)?
+        {
             Ok(records_number) => Ok(Ok(QueryEvent::RecordsDeleted(records_number))),
             Err(OperationOnTableError::SchemaDoesNotExist) => Ok(Err(QueryError::schema_does_not_exist(schema_name))),
-            Err(OperationOnTableError::TableDoesNotExist) => Ok(Err(QueryError::table_does_not_exist(
-                schema_name + "." + table_name.as_str(),
-            ))),
+            Err(OperationOnTableError::TableDoesNotExist) => {
+                Ok(Err(crate::table_does_not_exist(&schema_name, &table_name)))
+            }
             Err(OperationOnTableError::ColumnDoesNotExist(non_existing_columns)) => {
                 Ok(Err(QueryError::column_does_not_exist(non_existing_columns)))
             }
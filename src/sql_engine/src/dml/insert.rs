@@ -14,43 +14,75 @@
 
 use kernel::SystemResult;
 use protocol::results::{QueryError, QueryEvent, QueryResult};
+use sql_types::{Datum, SqlType, TimeZone};
 use sqlparser::ast::{Ident, ObjectName, Query};
-use std::sync::{Arc, Mutex};
-use storage::{backend::BackendStorage, frontend::FrontendStorage, OperationOnTableError};
+use std::sync::Arc;
+use storage::{
+    backend::BackendStorage,
+    frontend::FrontendStorage,
+    lock::{LockDuration, LockMode, Resource, SessionId},
+    OperationOnTableError,
+};
 
 pub(crate) struct InsertCommand<'q, P: BackendStorage> {
     raw_sql_query: &'q str,
     name: ObjectName,
     columns: Vec<Ident>,
     source: Box<Query>,
-    storage: Arc<Mutex<FrontendStorage<P>>>,
+    // explicit values are allowed for `GENERATED ALWAYS` columns
+    overriding_system_value: bool,
+    time_zone: TimeZone,
+    storage: Arc<FrontendStorage<P>>,
+    session_id: SessionId,
 }
 
 impl<P: BackendStorage> InsertCommand<'_, P> {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         raw_sql_query: &'_ str,
         name: ObjectName,
         columns: Vec<Ident>,
         source: Box<Query>,
-        storage: Arc<Mutex<FrontendStorage<P>>>,
+        overriding_system_value: bool,
+        time_zone: TimeZone,
+        storage: Arc<FrontendStorage<P>>,
+        session_id: SessionId,
     ) -> InsertCommand<P> {
         InsertCommand {
             raw_sql_query,
             name,
             columns,
             source,
+            overriding_system_value,
+            time_zone,
             storage,
+            session_id,
         }
     }
 
     pub(crate) fn execute(&mut self) -> SystemResult<QueryResult> {
-        let table_name = self.name.0.pop().unwrap().to_string();
-        let schema_name = self.name.0.pop().unwrap().to_string();
+        let (schema_name, table_name) = match crate::qualified_name(&self.name) {
+            Ok(name) => name,
+            Err(error) => return Ok(Err(error)),
+        };
+        if let Err(error) = crate::lock_resources(
+            &self.storage,
+            self.session_id,
+            vec![
+                (Resource::schema(&schema_name), LockMode::AccessShare),
+                (Resource::table(&schema_name, &table_name), LockMode::RowExclusive),
+                (Resource::rows(&schema_name, &table_name), LockMode::Exclusive),
+            ],
+            false,
+            LockDuration::Transaction,
+        ) {
+            return Ok(Err(error));
+        }
         let sqlparser::ast::Query { body, .. } = &*self.source;
         if let sqlparser::ast::SetExpr::Values(values) = &body {
             let values = &values.0;
 
-            let columns = if self.columns.is_empty() {
+            let mut columns = if self.columns.is_empty() {
                 vec![]
             } else {
                 self.columns
@@ -63,41 +95,155 @@ impl<P: BackendStorage> InsertCommand<'_, P> {
                     .collect()
             };
 
-            let rows: Vec<Vec<String>> = values
-                .iter()
-                .map(|v| {
-                    v.iter()
-                        .map(|v| match v {
-                            sqlparser::ast::Expr::Value(sqlparser::ast::Value::Number(v)) => v.to_string(),
-                            sqlparser::ast::Expr::Value(sqlparser::ast::Value::SingleQuotedString(v)) => v.to_string(),
-                            sqlparser::ast::Expr::UnaryOp { op, expr } => match (op, &**expr) {
-                                (
-                                    sqlparser::ast::UnaryOperator::Minus,
-                                    sqlparser::ast::Expr::Value(sqlparser::ast::Value::Number(v)),
-                                ) => "-".to_owned() + v.as_str(),
-                                (op, expr) => unimplemented!("{:?} {:?} is not currently supported", op, expr),
-                            },
-                            expr => unimplemented!("{:?} is not currently supported", expr),
-                        })
+            // string literals are of unknown type and validated by storage,
+            // values of other expressions are cast into types of columns
+            let mut rows: Vec<Vec<Datum>> = vec![];
+            for row in values {
+                let mut record = vec![];
+                for value in row {
+                    let value = match value {
+                        sqlparser::ast::Expr::Value(sqlparser::ast::Value::SingleQuotedString(v)) => {
+                            Datum::String(v.to_string())
+                        }
+                        expr => match crate::sequence::evaluate(expr, &self.storage, self.session_id)? {
+                            Ok(datum) => datum,
+                            Err(error) => return Ok(Err(error)),
+                        },
+                    };
+                    record.push(value);
+                }
+                rows.push(record);
+            }
+
+            let table_columns = self.storage.table_columns(&schema_name, &table_name)?;
+
+            if !self.overriding_system_value {
+                let width = rows.iter().map(Vec::len).max().unwrap_or_default();
+                let explicit = if columns.is_empty() {
+                    table_columns
+                        .iter()
+                        .take(width)
+                        .map(|(name, _sql_type)| name.clone())
                         .collect()
-                })
-                .collect();
+                } else {
+                    columns.clone()
+                };
+                let generated_always = self.storage.generated_always_columns(&schema_name, &table_name)?;
+                if let Some(column) = explicit.into_iter().find(|column| generated_always.contains(column)) {
+                    return Ok(Err(QueryError::generated_always(column)));
+                }
+            }
+
+            // columns that are not given values get their defaults that are
+            // evaluated for every row, e.g. `gen_random_uuid()`
+            let defaults = self.storage.column_defaults(&schema_name, &table_name)?;
+            if !defaults.is_empty() {
+                let width = rows.iter().map(Vec::len).max().unwrap_or_default();
+                if columns.is_empty() && width < table_columns.len() {
+                    columns = table_columns
+                        .iter()
+                        .take(width)
+                        .map(|(name, _sql_type)| name.clone())
+                        .collect();
+                }
+                if !columns.is_empty() {
+                    for (name, default) in defaults {
+                        if columns.contains(&name) {
+                            continue;
+                        }
+                        let default = match crate::parser::parse_expr(&default) {
+                            Ok(expr) => expr,
+                            Err(_) => return Ok(Err(QueryError::not_supported_operation(default))),
+                        };
+                        let mut values = vec![];
+                        for _row in rows.iter() {
+                            match crate::sequence::evaluate(&default, &self.storage, self.session_id)? {
+                                Ok(datum) => values.push(datum),
+                                Err(error) => return Ok(Err(error)),
+                            }
+                        }
+                        // NULL default is the same as no default
+                        if values.iter().all(|value| *value != Datum::Null) {
+                            columns.push(name);
+                            for (row, value) in rows.iter_mut().zip(values) {
+                                row.push(value);
+                            }
+                        }
+                    }
+                }
+            }
+
+            let columns_with_types = if columns.is_empty() {
+                table_columns
+                    .iter()
+                    .map(|(name, sql_type)| (name.clone(), Some(sql_type.clone())))
+                    .collect()
+            } else {
+                columns
+                    .iter()
+                    .map(|column| {
+                        let sql_type = table_columns
+                            .iter()
+                            .find(|(name, _sql_type)| name == column)
+                            .map(|(_name, sql_type)| sql_type.clone());
+                        (column.clone(), sql_type)
+                    })
+                    .collect::<Vec<(String, Option<SqlType>)>>()
+            };
+            let mut records = vec![];
+            for row in rows {
+                let mut record = vec![];
+                for (index, datum) in row.into_iter().enumerate() {
+                    let value = match columns_with_types.get(index) {
+                        Some((column_name, Some(sql_type))) => {
+                            match crate::dml::column_value(
+                                datum,
+                                &schema_name,
+                                &table_name,
+                                column_name,
+                                sql_type,
+                                &self.time_zone,
+                            ) {
+                                Ok(value) => value,
+                                Err(error) => return Ok(Err(error)),
+                            }
+                        }
+                        _ => datum,
+                    };
+                    record.push(value);
+                }
+                records.push(record);
+            }
 
-            let len = rows.len();
-            match (self.storage.lock().unwrap()).insert_into(&schema_name, &table_name, columns, rows)? {
+            // values are kept to report the one that violates type of its column
+            let values = records.clone();
+            let len = records.len();
+            match self
+                .storage
+                .insert_into(&schema_name, &table_name, columns, records, self.session_id)?
+            {
                 Ok(_) => Ok(Ok(QueryEvent::RecordsInserted(len))),
                 Err(OperationOnTableError::SchemaDoesNotExist) => {
                     Ok(Err(QueryError::schema_does_not_exist(schema_name)))
                 }
-                Err(OperationOnTableError::TableDoesNotExist) => Ok(Err(QueryError::table_does_not_exist(
-                    schema_name + "." + table_name.as_str(),
-                ))),
+                Err(OperationOnTableError::TableDoesNotExist) => {
+                    Ok(Err(crate::table_does_not_exist(&schema_name, &table_name)))
+                }
                 Err(OperationOnTableError::ColumnDoesNotExist(non_existing_columns)) => {
                     Ok(Err(QueryError::column_does_not_exist(non_existing_columns)))
                 }
-                Err(e) => {
-                    eprintln!("{:?}", e);
-                    unimplemented!()
+                Err(OperationOnTableError::ConstraintViolation(violations)) => {
+                    let names = columns_with_types.iter().map(|(name, _sql_type)| name.as_str());
+                    let values = values.iter().flat_map(|record| names.clone().zip(record.iter()));
+                    Ok(Err(crate::dml::constraint_violation(
+                        &schema_name,
+                        &table_name,
+                        &violations,
+                        values,
+                    )))
+                }
+                Err(OperationOnTableError::InsertTooManyExpressions) => {
+                    Ok(Err(QueryError::too_many_insert_expressions()))
                 }
             }
         } else {
@@ -0,0 +1,74 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use kernel::SystemResult;
+use protocol::results::{QueryEvent, QueryResult};
+use sqlparser::ast::ObjectName;
+use std::sync::Arc;
+use storage::{
+    backend::BackendStorage,
+    frontend::FrontendStorage,
+    lock::{LockDuration, LockMode, Resource, SessionId},
+};
+
+pub(crate) struct LockTableCommand<P: BackendStorage> {
+    tables: Vec<ObjectName>,
+    mode: LockMode,
+    nowait: bool,
+    storage: Arc<FrontendStorage<P>>,
+    session_id: SessionId,
+}
+
+impl<P: BackendStorage> LockTableCommand<P> {
+    pub(crate) fn new(
+        tables: Vec<ObjectName>,
+        mode: LockMode,
+        nowait: bool,
+        storage: Arc<FrontendStorage<P>>,
+        session_id: SessionId,
+    ) -> LockTableCommand<P> {
+        LockTableCommand {
+            tables,
+            mode,
+            nowait,
+            storage,
+            session_id,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<QueryResult> {
+        for name in self.tables.iter() {
+            let (schema_name, table_name) = match crate::qualified_name(name) {
+                Ok(name) => name,
+                Err(error) => return Ok(Err(error)),
+            };
+            if let Err(error) = crate::lock_resources(
+                &self.storage,
+                self.session_id,
+                vec![
+                    (Resource::schema(&schema_name), LockMode::AccessShare),
+                    (Resource::table(&schema_name, &table_name), self.mode),
+                ],
+                self.nowait,
+                LockDuration::Transaction,
+            ) {
+                return Ok(Err(error));
+            }
+            if !self.storage.table_exists(&schema_name, &table_name) {
+                return Ok(Err(crate::table_does_not_exist(&schema_name, &table_name)));
+            }
+        }
+        Ok(Ok(QueryEvent::TableLocked))
+    }
+}
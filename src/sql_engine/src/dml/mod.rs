@@ -14,5 +14,89 @@
 
 pub(crate) mod delete;
 pub(crate) mod insert;
+pub(crate) mod lock_table;
 pub(crate) mod select;
 pub(crate) mod update;
+
+use protocol::results::QueryError;
+use sql_types::{ConstraintError, Datum, SqlType, TimeZone};
+use std::collections::HashMap;
+
+// PostgreSQL reports the first value in order of rows and columns that can't
+// be represented by the type of its column together with the table
+pub(crate) fn constraint_violation<'v>(
+    schema_name: &str,
+    table_name: &str,
+    violations: &HashMap<ConstraintError, Vec<Vec<(String, SqlType)>>>,
+    values: impl IntoIterator<Item = (&'v str, &'v Datum)>,
+) -> QueryError {
+    of_table(violated_value(violations, values), schema_name, table_name)
+}
+
+fn violated_value<'v>(
+    violations: &HashMap<ConstraintError, Vec<Vec<(String, SqlType)>>>,
+    values: impl IntoIterator<Item = (&'v str, &'v Datum)>,
+) -> QueryError {
+    let violated = violations
+        .values()
+        .flatten()
+        .flatten()
+        .collect::<Vec<&(String, SqlType)>>();
+    for (column_name, value) in values {
+        let value = match value {
+            Datum::String(value) => value,
+            _ => continue,
+        };
+        if let Some((_name, sql_type)) = violated.iter().find(|(name, _sql_type)| name == column_name) {
+            if let Err(error) = sql_type.constraint().validate(value) {
+                return crate::expr::constraint_error(error, sql_type, value).with_column(column_name.to_owned());
+            }
+        }
+    }
+    let (error, (column_name, sql_type)) = violations
+        .iter()
+        .flat_map(|(error, rows)| rows.iter().flatten().map(move |column| (error, column)))
+        .next()
+        .expect("at least one violation");
+    crate::expr::constraint_error(*error, sql_type, "").with_column(column_name.clone())
+}
+
+// string values are of unknown type and validated by storage, literals of
+// time zone aware columns without explicit offset are in session time zone.
+// Values of other types are cast into type of the column, NULL is stored as
+// it is
+pub(crate) fn column_value(
+    datum: Datum,
+    schema_name: &str,
+    table_name: &str,
+    column_name: &str,
+    sql_type: &SqlType,
+    time_zone: &TimeZone,
+) -> Result<Datum, QueryError> {
+    match datum {
+        Datum::String(value) => match invalid_enum_value(sql_type, &value) {
+            Some(error) => Err(error),
+            None => Ok(Datum::String(time_zone.qualify(sql_type, value))),
+        },
+        datum => crate::expr::assign(datum, column_name, sql_type),
+    }
+    .map_err(|error| of_table(error, schema_name, table_name))
+}
+
+// errors of column values are related to the table of the column
+fn of_table(error: QueryError, schema_name: &str, table_name: &str) -> QueryError {
+    error
+        .with_schema(schema_name.to_owned())
+        .with_table(table_name.to_owned())
+}
+
+// PostgreSQL reports value that is not a label of ENUM type together with the
+// name of the type
+pub(crate) fn invalid_enum_value(sql_type: &SqlType, value: &str) -> Option<QueryError> {
+    match sql_type {
+        SqlType::Enum { name, labels, .. } if !labels.iter().any(|label| label == value) => {
+            Some(QueryError::invalid_enum_value(name.clone(), value.to_owned()))
+        }
+        _ => None,
+    }
+}
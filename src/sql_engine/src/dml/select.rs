@@ -13,54 +13,149 @@
 // limitations under the License.
 
 use kernel::SystemResult;
-use protocol::results::{QueryError, QueryEvent, QueryResult};
-use sqlparser::ast::Query;
-use std::{
-    ops::Deref,
-    sync::{Arc, Mutex},
+use protocol::results::{Description, QueryError, QueryEvent, QueryResult, QueryResults};
+use protocol::sql_types::{PostgreSqlFormat, PostgreSqlType};
+use sql_types::{Datum, SqlType, TextFormat};
+use sqlparser::ast::{Expr, ObjectName, Query, SelectItem, TableAlias};
+use std::{iter, ops::Deref, sync::Arc};
+use storage::{
+    backend::BackendStorage,
+    frontend::FrontendStorage,
+    lock::{LockDuration, LockMode, Resource, SessionId},
+    OperationOnTableError, Records,
 };
-use storage::{backend::BackendStorage, frontend::FrontendStorage, OperationOnTableError};
+
+// records whose values are computed can't be selected if the computation of
+// any of them fails
+type Rows = Box<dyn Iterator<Item = Result<Vec<Datum>, QueryError>> + Send>;
 
 pub(crate) struct SelectCommand<'q, P: BackendStorage> {
     raw_sql_query: &'q str,
     query: Box<Query>,
-    storage: Arc<Mutex<FrontendStorage<P>>>,
+    lock_mode: LockMode,
+    nowait: bool,
+    lock_duration: LockDuration,
+    text_format: TextFormat,
+    result_formats: Vec<PostgreSqlFormat>,
+    storage: Arc<FrontendStorage<P>>,
+    session_id: SessionId,
 }
 
 impl<P: BackendStorage> SelectCommand<'_, P> {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         raw_sql_query: &'_ str,
         query: Box<Query>,
-        storage: Arc<Mutex<FrontendStorage<P>>>,
+        lock_mode: LockMode,
+        nowait: bool,
+        lock_duration: LockDuration,
+        text_format: TextFormat,
+        result_formats: Vec<PostgreSqlFormat>,
+        storage: Arc<FrontendStorage<P>>,
+        session_id: SessionId,
     ) -> SelectCommand<P> {
         SelectCommand {
             raw_sql_query,
             query,
+            lock_mode,
+            nowait,
+            lock_duration,
+            text_format,
+            result_formats,
             storage,
+            session_id,
+        }
+    }
+
+    // records that are read until the end of transaction can't be changed by
+    // other sessions, otherwise only committed records are read without waiting
+    fn rows_lock_mode(&self) -> LockMode {
+        match self.lock_duration {
+            LockDuration::Transaction => LockMode::Share,
+            LockDuration::Statement => LockMode::AccessShare,
         }
     }
 
-    pub(crate) fn execute(&mut self) -> SystemResult<QueryResult> {
+    pub(crate) fn execute(&mut self) -> SystemResult<QueryResults> {
+        Ok(match self.select()? {
+            Ok((description, records)) => Box::new(SelectedRecords::new(
+                description,
+                records,
+                self.text_format.clone(),
+                &self.result_formats,
+            )),
+            Err(error) => Box::new(iter::once(Err(error))),
+        })
+    }
+
+    /// Describes selected columns without locking the table and reading its
+    /// records. Values of `SELECT expr` are not computed
+    pub(crate) fn describe(&mut self) -> SystemResult<Result<Description, QueryError>> {
+        Ok(self.projection(true)?.map(|(description, _records)| description))
+    }
+
+    fn select(&mut self) -> SystemResult<Result<(Description, Rows), QueryError>> {
+        self.projection(false)
+    }
+
+    // records of a table are read lazily, so only description is used when
+    // the query is described
+    fn projection(&mut self, describe: bool) -> SystemResult<Result<(Description, Rows), QueryError>> {
         let sqlparser::ast::Query { body, .. } = &*self.query;
         if let sqlparser::ast::SetExpr::Select(select) = body {
             let sqlparser::ast::Select { projection, from, .. } = select.deref();
+            if from.is_empty() && describe {
+                let records: Rows = Box::new(iter::empty());
+                return Ok(self
+                    .describe_values(projection)?
+                    .map(|description| (description, records)));
+            }
+            if from.is_empty() {
+                return self.values(projection);
+            }
             let sqlparser::ast::TableWithJoins { relation, .. } = &from[0];
             let (schema_name, table_name) = match relation {
-                sqlparser::ast::TableFactor::Table { name, .. } => {
-                    let table_name = name.0[1].to_string();
-                    let schema_name = name.0[0].to_string();
-                    (schema_name, table_name)
+                sqlparser::ast::TableFactor::Table { name, args, alias, .. } if !args.is_empty() => {
+                    return Ok(self
+                        .table_function(name, args, alias.as_ref(), projection)
+                        .map(|(description, records)| (description, rows(records))));
                 }
+                sqlparser::ast::TableFactor::Table { name, .. } => match crate::qualified_name(name) {
+                    Ok(name) => name,
+                    Err(error) => return Ok(Err(error)),
+                },
                 _ => return Ok(Err(QueryError::not_supported_operation(self.raw_sql_query.to_owned()))),
             };
+            if !describe {
+                if let Err(error) = crate::lock_resources(
+                    &self.storage,
+                    self.session_id,
+                    vec![
+                        (Resource::schema(&schema_name), LockMode::AccessShare),
+                        (Resource::table(&schema_name, &table_name), self.lock_mode),
+                        (Resource::rows(&schema_name, &table_name), self.rows_lock_mode()),
+                    ],
+                    self.nowait,
+                    self.lock_duration,
+                ) {
+                    return Ok(Err(error));
+                }
+            }
+            if projection.iter().any(|item| {
+                !matches!(
+                    item,
+                    SelectItem::Wildcard | SelectItem::UnnamedExpr(Expr::Identifier(_))
+                )
+            }) {
+                return self.expressions(&schema_name, &table_name, projection, describe);
+            }
             let table_columns = {
                 let projection = projection.clone();
                 let mut columns: Vec<String> = vec![];
                 for item in projection {
                     match item {
                         sqlparser::ast::SelectItem::Wildcard => {
-                            let all_columns =
-                                (self.storage.lock().unwrap()).table_columns(&schema_name, &table_name)?;
+                            let all_columns = self.storage.table_columns(&schema_name, &table_name)?;
                             columns.extend(
                                 all_columns
                                     .into_iter()
@@ -76,28 +171,411 @@ impl<P: BackendStorage> SelectCommand<'_, P> {
                 }
                 columns
             };
-            match (self.storage.lock().unwrap()).select_all_from(&schema_name, &table_name, table_columns)? {
-                Ok(records) => Ok(Ok(QueryEvent::RecordsSelected((
-                    records
-                        .0
+            match self
+                .storage
+                .select_all_from(&schema_name, &table_name, table_columns, self.session_id)?
+            {
+                Ok((description, records)) => Ok(Ok((
+                    description
                         .into_iter()
                         .map(|(name, sql_type)| (name, sql_type.to_pg_types()))
                         .collect(),
-                    records.1,
-                )))),
+                    rows(records),
+                ))),
                 Err(OperationOnTableError::ColumnDoesNotExist(non_existing_columns)) => {
                     Ok(Err(QueryError::column_does_not_exist(non_existing_columns)))
                 }
                 Err(OperationOnTableError::SchemaDoesNotExist) => {
                     Ok(Err(QueryError::schema_does_not_exist(schema_name.to_owned())))
                 }
-                Err(OperationOnTableError::TableDoesNotExist) => Ok(Err(QueryError::table_does_not_exist(
-                    schema_name.to_owned() + "." + table_name.as_str(),
-                ))),
+                Err(OperationOnTableError::TableDoesNotExist) => {
+                    Ok(Err(crate::table_does_not_exist(&schema_name, &table_name)))
+                }
                 _ => Ok(Err(QueryError::not_supported_operation(self.raw_sql_query.to_owned()))),
             }
         } else {
             Ok(Err(QueryError::not_supported_operation(self.raw_sql_query.to_owned())))
         }
     }
+
+    // values of expressions, e.g. `SELECT id + 1 FROM t`, are computed for
+    // every record of the table, their types are known from types of columns
+    fn expressions(
+        &self,
+        schema_name: &str,
+        table_name: &str,
+        projection: &[SelectItem],
+        describe: bool,
+    ) -> SystemResult<Result<(Description, Rows), QueryError>> {
+        let table_columns = self.storage.table_columns(schema_name, table_name)?;
+        let mut columns = vec![];
+        for item in projection {
+            match item {
+                SelectItem::Wildcard => columns.extend(
+                    table_columns
+                        .iter()
+                        .map(|(name, _sql_type)| (name.clone(), Expr::Identifier(name.as_str().into()))),
+                ),
+                item => match self.value_column(item) {
+                    Ok(column) => columns.push(column),
+                    Err(error) => return Ok(Err(error)),
+                },
+            }
+        }
+        let (names, records) = match self.storage.select_all_from(
+            schema_name,
+            table_name,
+            table_columns.iter().map(|(name, _sql_type)| name.clone()).collect(),
+            self.session_id,
+        )? {
+            Ok((names, records)) => (names, records),
+            Err(OperationOnTableError::SchemaDoesNotExist) => {
+                return Ok(Err(QueryError::schema_does_not_exist(schema_name.to_owned())))
+            }
+            Err(OperationOnTableError::TableDoesNotExist) => {
+                return Ok(Err(crate::table_does_not_exist(schema_name, table_name)))
+            }
+            _ => return Ok(Err(QueryError::not_supported_operation(self.raw_sql_query.to_owned()))),
+        };
+        let non_existing_columns = columns
+            .iter()
+            .flat_map(|(_name, expr)| crate::expr::columns(expr))
+            .filter(|column| !table_columns.iter().any(|(name, _sql_type)| name == column))
+            .collect::<Vec<String>>();
+        if !non_existing_columns.is_empty() {
+            return Ok(Err(QueryError::column_does_not_exist(non_existing_columns)));
+        }
+
+        let mut resolved = vec![];
+        for (name, expr) in columns {
+            match self.resolve(&expr, schema_name, &table_columns)? {
+                Ok(expr) => resolved.push((name, expr)),
+                Err(error) => return Ok(Err(error)),
+            }
+        }
+        let columns = resolved;
+        let description = columns
+            .iter()
+            .map(|(name, expr)| (name.clone(), result_type(expr, &table_columns)))
+            .collect();
+        // records that are evaluated at once are not read when the query is
+        // described
+        if describe {
+            let records: Rows = Box::new(iter::empty());
+            return Ok(Ok((description, records)));
+        }
+        let names = names.into_iter().map(|(name, _sql_type)| name).collect::<Vec<String>>();
+        // there is no GROUP BY clause, so aggregate functions are computed
+        // over all records and other values can't be selected with them
+        if columns
+            .iter()
+            .any(|(_name, expr)| crate::expr::aggregate_argument(expr).is_some())
+        {
+            let mut arguments = vec![];
+            for (_name, expr) in columns.iter() {
+                match crate::expr::aggregate_argument(expr) {
+                    Some(argument) => arguments.push(argument),
+                    None => return Ok(Err(QueryError::not_supported_operation(self.raw_sql_query.to_owned()))),
+                }
+            }
+            let mut values = vec![vec![]; arguments.len()];
+            for record in records {
+                let record = names.iter().cloned().zip(record).collect::<Vec<(String, Datum)>>();
+                for (argument, values) in arguments.iter().zip(values.iter_mut()) {
+                    match crate::expr::evaluate_in(argument, &record) {
+                        Ok(datum) => values.push(datum),
+                        Err(error) => return Ok(Err(error)),
+                    }
+                }
+            }
+            let record = columns
+                .iter()
+                .zip(values)
+                .map(|((_name, expr), values)| crate::expr::aggregate(expr, &values))
+                .collect();
+            let records: Rows = Box::new(iter::once(Ok(record)));
+            return Ok(Ok((description, records)));
+        }
+        // sequences are changed while the statement is executed, not while
+        // its records are read, thus the records are evaluated at once
+        if columns
+            .iter()
+            .any(|(_name, expr)| crate::sequence::calls_sequence_functions(expr))
+        {
+            let mut rows = vec![];
+            'records: for record in records {
+                let record = names.iter().cloned().zip(record).collect::<Vec<(String, Datum)>>();
+                let mut row = vec![];
+                for (_name, expr) in columns.iter() {
+                    match crate::sequence::evaluate_in(expr, &record, &self.storage, self.session_id)? {
+                        Ok(datum) => row.push(datum),
+                        Err(error) => {
+                            rows.push(Err(error));
+                            break 'records;
+                        }
+                    }
+                }
+                rows.push(Ok(row));
+            }
+            let records: Rows = Box::new(rows.into_iter());
+            return Ok(Ok((description, records)));
+        }
+        let records: Rows = Box::new(records.map(move |record| {
+            let record = names.iter().cloned().zip(record).collect::<Vec<(String, Datum)>>();
+            columns
+                .iter()
+                .map(|(_name, expr)| crate::expr::evaluate_in(expr, &record))
+                .collect()
+        }));
+        Ok(Ok((description, records)))
+    }
+
+    // `SELECT expr [, ...]` without FROM clause is a single record of the
+    // expression values, e.g. `SELECT nextval('schema_name.ids')`
+    fn values(&self, projection: &[SelectItem]) -> SystemResult<Result<(Description, Rows), QueryError>> {
+        let mut description = vec![];
+        let mut record = vec![];
+        for item in projection {
+            let (name, expr) = match self.value_column(item) {
+                Ok(column) => column,
+                Err(error) => return Ok(Err(error)),
+            };
+            let expr = match self.resolve(&expr, crate::DEFAULT_SCHEMA, &[])? {
+                Ok(expr) => expr,
+                Err(error) => return Ok(Err(error)),
+            };
+            let datum = match crate::sequence::evaluate(&expr, &self.storage, self.session_id)? {
+                Ok(datum) => datum,
+                Err(error) => return Ok(Err(error)),
+            };
+            description.push((name, result_type(&expr, &[])));
+            record.push(datum);
+        }
+        let records: Rows = Box::new(iter::once(Ok(record)));
+        Ok(Ok((description, records)))
+    }
+
+    // types of expressions are known without computing their values, that
+    // could change sequences
+    fn describe_values(&self, projection: &[SelectItem]) -> SystemResult<Result<Description, QueryError>> {
+        let mut description = vec![];
+        for item in projection {
+            let (name, expr) = match self.value_column(item) {
+                Ok(column) => column,
+                Err(error) => return Ok(Err(error)),
+            };
+            match self.resolve(&expr, crate::DEFAULT_SCHEMA, &[])? {
+                Ok(expr) => description.push((name, result_type(&expr, &[]))),
+                Err(error) => return Ok(Err(error)),
+            }
+        }
+        Ok(Ok(description))
+    }
+
+    // casts to user defined types and literals that are compared with values
+    // of enum and CHAR(n) types are resolved before expression is evaluated,
+    // unqualified names of types are looked up in the given schema
+    fn resolve(
+        &self,
+        expr: &Expr,
+        schema_name: &str,
+        columns: &[(String, SqlType)],
+    ) -> SystemResult<Result<Expr, QueryError>> {
+        let user_type = &mut |name: &ObjectName| crate::ddl::create_table::user_type(&self.storage, schema_name, name);
+        Ok(crate::expr::with_enum_values(expr, columns, user_type)?
+            .map(|expr| crate::expr::with_char_literals(&expr, columns)))
+    }
+
+    fn value_column(&self, item: &SelectItem) -> Result<(String, Expr), QueryError> {
+        match item {
+            SelectItem::UnnamedExpr(expr) => Ok((column_name(expr), expr.clone())),
+            SelectItem::ExprWithAlias { expr, alias } => Ok((alias.value.clone(), expr.clone())),
+            _ => Err(QueryError::not_supported_operation(self.raw_sql_query.to_owned())),
+        }
+    }
+
+    // `unnest(array)` is a table of array elements in the column that is named
+    // as the function or its alias
+    fn table_function(
+        &self,
+        name: &ObjectName,
+        args: &[Expr],
+        alias: Option<&TableAlias>,
+        projection: &[SelectItem],
+    ) -> Result<(Description, Records), QueryError> {
+        let name = name.to_string().to_lowercase();
+        let args = args
+            .iter()
+            .map(crate::expr::evaluate)
+            .collect::<Result<Vec<Datum>, QueryError>>()?;
+        let elements = match (name.as_str(), args.as_slice()) {
+            ("unnest", [Datum::Array(elements)]) => elements.clone(),
+            _ => {
+                return Err(QueryError::undefined_function(format!(
+                    "{}({})",
+                    name,
+                    args.iter()
+                        .map(crate::expr::type_name)
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )))
+            }
+        };
+        let column_name = match alias {
+            Some(TableAlias { columns, .. }) if !columns.is_empty() => columns[0].value.clone(),
+            Some(TableAlias { name, .. }) => name.value.clone(),
+            None => name,
+        };
+        let mut description = vec![];
+        for item in projection {
+            match item {
+                SelectItem::Wildcard => {}
+                SelectItem::UnnamedExpr(Expr::Identifier(ident)) if ident.value == column_name => {}
+                SelectItem::UnnamedExpr(Expr::Identifier(ident)) => {
+                    return Err(QueryError::column_does_not_exist(vec![ident.value.clone()]))
+                }
+                _ => return Err(QueryError::not_supported_operation(self.raw_sql_query.to_owned())),
+            }
+            let pg_type = elements.iter().find_map(Datum::pg_type).unwrap_or(PostgreSqlType::Text);
+            description.push((column_name.clone(), pg_type));
+        }
+        let columns = description.len();
+        let records: Records = Box::new(elements.into_iter().map(move |element| vec![element; columns]));
+        Ok((description, records))
+    }
+}
+
+// name of unnamed column as in PostgreSQL: name of the column or function,
+// subscript is named as the array, other expressions and operators that are
+// parsed as functions are `?column?`
+fn column_name(expr: &Expr) -> String {
+    match expr {
+        Expr::Identifier(ident) => ident.value.clone(),
+        Expr::Nested(expr) => column_name(expr),
+        Expr::CompoundIdentifier(idents) => idents
+            .last()
+            .map(|ident| ident.value.clone())
+            .unwrap_or_else(|| "?column?".to_owned()),
+        // operators are functions with quoted names, see `parser::rewrite_subscripts` and
+        // `parser::rewrite_json_operators`
+        Expr::Function(function) => match function.name.0.as_slice() {
+            [name] if name.quote_style.is_some() && name.value == "[]" => function
+                .args
+                .first()
+                .map(column_name)
+                .unwrap_or_else(|| "?column?".to_owned()),
+            [name] if name.quote_style.is_some() && is_json_operator(&name.value) => "?column?".to_owned(),
+            _ => function.name.to_string().to_lowercase(),
+        },
+        _ => "?column?".to_owned(),
+    }
+}
+
+fn is_json_operator(name: &str) -> bool {
+    matches!(name, "->" | "->>" | "#>" | "#>>" | "@>" | "?")
+}
+
+fn rows(records: Records) -> Rows {
+    Box::new(records.map(Ok))
+}
+
+// non null value of the type that expressions are computed with to know their
+// types
+// values keep no length of CHAR(n) and VARCHAR(n) and precision of NUMERIC,
+// so they are taken from types of columns and casts, e.g. `'a'::char(3)` is
+// `bpchar(3)`. Values of unknown type are sent as text
+fn result_type(expr: &Expr, columns: &[(String, SqlType)]) -> PostgreSqlType {
+    crate::expr::expr_type(expr, columns)
+        .map(|sql_type| sql_type.to_pg_types())
+        .unwrap_or(PostgreSqlType::Text)
+}
+
+// produces row description, then records as they are read from storage and
+// number of selected records at the end
+struct SelectedRecords {
+    description: Option<Description>,
+    formats: Vec<PostgreSqlFormat>,
+    // lengths of CHAR(n) columns whose values are blank padded on output
+    padding: Vec<Option<usize>>,
+    records: Rows,
+    text_format: TextFormat,
+    selected: usize,
+    done: bool,
+}
+
+impl SelectedRecords {
+    fn new(
+        description: Description,
+        records: Rows,
+        text_format: TextFormat,
+        result_formats: &[PostgreSqlFormat],
+    ) -> Self {
+        let formats = match result_formats {
+            [format] => vec![*format; description.len()],
+            formats => (0..description.len())
+                .map(|index| formats.get(index).copied().unwrap_or(PostgreSqlFormat::Text))
+                .collect(),
+        };
+        let padding = description
+            .iter()
+            .map(|(_name, pg_type)| match pg_type {
+                PostgreSqlType::Char(length) => Some(*length as usize),
+                _ => None,
+            })
+            .collect();
+        Self {
+            description: Some(description),
+            formats,
+            padding,
+            records,
+            text_format,
+            selected: 0,
+            done: false,
+        }
+    }
+}
+
+impl Iterator for SelectedRecords {
+    type Item = QueryResult;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(description) = self.description.take() {
+            return Some(Ok(QueryEvent::RowDescription(description, self.formats.clone())));
+        }
+        if self.done {
+            return None;
+        }
+        match self.records.next() {
+            Some(Err(error)) => {
+                self.done = true;
+                Some(Err(error))
+            }
+            Some(Ok(record)) => {
+                self.selected += 1;
+                Some(Ok(QueryEvent::DataRow(
+                    record
+                        .into_iter()
+                        .zip(self.padding.iter().chain(iter::repeat(&None)))
+                        .zip(self.formats.iter().chain(iter::repeat(&PostgreSqlFormat::Text)))
+                        .map(|((datum, padding), format)| {
+                            let datum = match (datum, padding) {
+                                (Datum::String(value), Some(length)) => {
+                                    Datum::String(format!("{:<width$}", value, width = *length))
+                                }
+                                (datum, _) => datum,
+                            };
+                            match format {
+                                PostgreSqlFormat::Text => datum.to_text_in(&self.text_format).map(String::into_bytes),
+                                PostgreSqlFormat::Binary => datum.to_binary(),
+                            }
+                        })
+                        .collect(),
+                )))
+            }
+            None => {
+                self.done = true;
+                Some(Ok(QueryEvent::RecordsSelected(self.selected)))
+            }
+        }
+    }
 }
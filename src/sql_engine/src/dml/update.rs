@@ -14,15 +14,23 @@
 
 use kernel::SystemResult;
 use protocol::results::{QueryError, QueryEvent, QueryResult};
-use sqlparser::ast::{Assignment, ObjectName};
-use std::sync::{Arc, Mutex};
-use storage::{backend::BackendStorage, frontend::FrontendStorage, OperationOnTableError};
+use sql_types::{Datum, SqlType, TimeZone};
+use sqlparser::ast::{Assignment, Expr, ObjectName, Value};
+use std::sync::Arc;
+use storage::{
+    backend::BackendStorage,
+    frontend::FrontendStorage,
+    lock::{LockDuration, LockMode, Resource, SessionId},
+    OperationOnTableError,
+};
 
 pub(crate) struct UpdateCommand<'q, P: BackendStorage> {
     raw_sql_query: &'q str,
     name: ObjectName,
     assignments: Vec<Assignment>,
-    storage: Arc<Mutex<FrontendStorage<P>>>,
+    time_zone: TimeZone,
+    storage: Arc<FrontendStorage<P>>,
+    session_id: SessionId,
 }
 
 impl<P: BackendStorage> UpdateCommand<'_, P> {
@@ -30,54 +38,171 @@ impl<P: BackendStorage> UpdateCommand<'_, P> {
         raw_sql_query: &'_ str,
         name: ObjectName,
         assignments: Vec<Assignment>,
-        storage: Arc<Mutex<FrontendStorage<P>>>,
+        time_zone: TimeZone,
+        storage: Arc<FrontendStorage<P>>,
+        session_id: SessionId,
     ) -> UpdateCommand<P> {
         UpdateCommand {
             raw_sql_query,
             name,
             assignments,
+            time_zone,
             storage,
+            session_id,
         }
     }
 
     pub(crate) fn execute(&mut self) -> SystemResult<QueryResult> {
-        let schema_name = self.name.0[0].to_string();
-        let table_name = self.name.0[1].to_string();
+        let (schema_name, table_name) = match crate::qualified_name(&self.name) {
+            Ok(name) => name,
+            Err(error) => return Ok(Err(error)),
+        };
+        if let Err(error) = crate::lock_resources(
+            &self.storage,
+            self.session_id,
+            vec![
+                (Resource::schema(&schema_name), LockMode::AccessShare),
+                (Resource::table(&schema_name, &table_name), LockMode::RowExclusive),
+                (Resource::rows(&schema_name, &table_name), LockMode::Exclusive),
+            ],
+            false,
+            LockDuration::Transaction,
+        ) {
+            return Ok(Err(error));
+        }
 
-        let to_update: Vec<(String, String)> = self
+        let table_columns = self.storage.table_columns(&schema_name, &table_name)?;
+        let referred = self
             .assignments
             .iter()
-            .map(|item| {
-                let sqlparser::ast::Assignment { id, value } = &item;
-                let sqlparser::ast::Ident { value: column, .. } = id;
+            .flat_map(|Assignment { value, .. }| crate::expr::columns(value))
+            .collect::<Vec<String>>();
+        if !referred.is_empty() {
+            return self.update_with_columns(schema_name, table_name, &table_columns, referred);
+        }
+
+        let mut to_update: Vec<(String, Datum)> = vec![];
+        for Assignment { id, value } in self.assignments.iter() {
+            let column = &id.value;
+            let sql_type = column_type(&table_columns, column);
+            let datum = match value {
+                Expr::Value(Value::SingleQuotedString(v)) => Ok(Datum::String(v.to_string())),
+                expr => crate::sequence::evaluate(expr, &self.storage, self.session_id)?,
+            };
+            match datum.and_then(|datum| value_of(&schema_name, &table_name, column, sql_type, datum, &self.time_zone))
+            {
+                Ok(value) => to_update.push((column.to_owned(), value)),
+                Err(error) => return Ok(Err(error)),
+            }
+        }
 
-                let value = match value {
-                    sqlparser::ast::Expr::Value(sqlparser::ast::Value::Number(val)) => val.to_owned(),
-                    sqlparser::ast::Expr::Value(sqlparser::ast::Value::SingleQuotedString(v)) => v.to_string(),
-                    sqlparser::ast::Expr::UnaryOp { op, expr } => match (op, &**expr) {
-                        (
-                            sqlparser::ast::UnaryOperator::Minus,
-                            sqlparser::ast::Expr::Value(sqlparser::ast::Value::Number(v)),
-                        ) => "-".to_owned() + v.as_str(),
-                        (op, expr) => unimplemented!("{:?} {:?} is not currently supported", op, expr),
-                    },
-                    expr => unimplemented!("{:?} is not currently supported", expr),
-                };
+        // values are kept to report the one that violates type of its column
+        let values = to_update.clone();
+        let updated = self
+            .storage
+            .update_all(&schema_name, &table_name, to_update, self.session_id)?;
+        Ok(self.updated(updated, schema_name, table_name, &values))
+    }
 
-                (column.to_owned(), value)
-            })
-            .collect();
+    // values of `SET id = id + 1` are computed for every record, sequence
+    // functions are evaluated only in assignments that don't refer to columns
+    fn update_with_columns(
+        &self,
+        schema_name: String,
+        table_name: String,
+        table_columns: &[(String, SqlType)],
+        referred: Vec<String>,
+    ) -> SystemResult<QueryResult> {
+        let non_existing_columns = referred
+            .into_iter()
+            .chain(self.assignments.iter().map(|Assignment { id, .. }| id.value.clone()))
+            .filter(|column| column_type(table_columns, column).is_none())
+            .collect::<Vec<String>>();
+        if !non_existing_columns.is_empty() {
+            return Ok(Err(QueryError::column_does_not_exist(non_existing_columns)));
+        }
 
-        match (self.storage.lock().unwrap()).update_all(&schema_name, &table_name, to_update)? {
-            Ok(records_number) => Ok(Ok(QueryEvent::RecordsUpdated(records_number))),
-            Err(OperationOnTableError::SchemaDoesNotExist) => Ok(Err(QueryError::schema_does_not_exist(schema_name))),
-            Err(OperationOnTableError::TableDoesNotExist) => Ok(Err(QueryError::table_does_not_exist(
-                schema_name + "." + table_name.as_str(),
-            ))),
+        // values of the last record are kept to report the one that violates
+        // type of its column
+        let mut values = vec![];
+        let updated = self.storage.update_all_with(
+            &schema_name,
+            &table_name,
+            |record| {
+                let mut to_update = vec![];
+                for Assignment { id, value } in self.assignments.iter() {
+                    let column = &id.value;
+                    let datum = match value {
+                        Expr::Value(Value::SingleQuotedString(v)) => Datum::String(v.to_string()),
+                        expr => crate::expr::evaluate_in(expr, record)?,
+                    };
+                    to_update.push((
+                        column.to_owned(),
+                        value_of(
+                            &schema_name,
+                            &table_name,
+                            column,
+                            column_type(table_columns, column),
+                            datum,
+                            &self.time_zone,
+                        )?,
+                    ));
+                }
+                values = to_update.clone();
+                Ok(to_update)
+            },
+            self.session_id,
+        )?;
+        Ok(match updated {
+            Ok(updated) => self.updated(updated, schema_name, table_name, &values),
+            Err(error) => Err(error),
+        })
+    }
+
+    fn updated(
+        &self,
+        updated: Result<usize, OperationOnTableError>,
+        schema_name: String,
+        table_name: String,
+        values: &[(String, Datum)],
+    ) -> QueryResult {
+        match updated {
+            Ok(records_number) => Ok(QueryEvent::RecordsUpdated(records_number)),
+            Err(OperationOnTableError::SchemaDoesNotExist) => Err(QueryError::schema_does_not_exist(schema_name)),
+            Err(OperationOnTableError::TableDoesNotExist) => {
+                Err(crate::table_does_not_exist(&schema_name, &table_name))
+            }
             Err(OperationOnTableError::ColumnDoesNotExist(non_existing_columns)) => {
-                Ok(Err(QueryError::column_does_not_exist(non_existing_columns)))
+                Err(QueryError::column_does_not_exist(non_existing_columns))
             }
-            _ => Ok(Err(QueryError::not_supported_operation(self.raw_sql_query.to_owned()))),
+            Err(OperationOnTableError::ConstraintViolation(violations)) => Err(crate::dml::constraint_violation(
+                &schema_name,
+                &table_name,
+                &violations,
+                values.iter().map(|(column, value)| (column.as_str(), value)),
+            )),
+            _ => Err(QueryError::not_supported_operation(self.raw_sql_query.to_owned())),
         }
     }
 }
+
+fn column_type<'c>(table_columns: &'c [(String, SqlType)], column: &str) -> Option<&'c SqlType> {
+    table_columns
+        .iter()
+        .find(|(name, _sql_type)| name == column)
+        .map(|(_name, sql_type)| sql_type)
+}
+
+fn value_of(
+    schema_name: &str,
+    table_name: &str,
+    column: &str,
+    sql_type: Option<&SqlType>,
+    datum: Datum,
+    time_zone: &TimeZone,
+) -> Result<Datum, QueryError> {
+    match sql_type {
+        Some(sql_type) => crate::dml::column_value(datum, schema_name, table_name, column, sql_type, time_zone),
+        None => Ok(datum),
+    }
+}
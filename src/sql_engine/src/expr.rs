@@ -0,0 +1,1574 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use kernel::SystemResult;
+use protocol::results::QueryError;
+use sql_types::{
+    cast, date_to_timestamp, CastContext, CastError, ConstraintError, Datum, Interval, IntervalField, Json, Numeric,
+    SqlType, Uuid,
+};
+use sqlparser::ast::{
+    BinaryOperator, DataType, DateTimeField, Expr, Function, Ident, ObjectName, UnaryOperator, Value,
+};
+use std::{cmp::Ordering, convert::TryFrom};
+
+/// Evaluates constant expression, e.g. `1.5 * 2` in `VALUES` or `SET` clause
+pub(crate) fn evaluate(expr: &Expr) -> Result<Datum, QueryError> {
+    evaluate_in(expr, &[])
+}
+
+/// Evaluates expression with values of columns of a record, e.g. `id + 1`
+/// in `SELECT` or `SET` clause. Columns are referred by their names, names
+/// of tables are not checked
+pub(crate) fn evaluate_in(expr: &Expr, record: &[(String, Datum)]) -> Result<Datum, QueryError> {
+    match expr {
+        Expr::Value(value) => literal(value),
+        Expr::Identifier(ident) => column(&ident.value, record),
+        Expr::CompoundIdentifier(idents) => match idents.last() {
+            Some(ident) => column(&ident.value, record),
+            None => Err(QueryError::not_supported_operation(expr.to_string())),
+        },
+        Expr::Nested(expr) => evaluate_in(expr, record),
+        Expr::UnaryOp { op, expr } => unary(op, evaluate_in(expr, record)?),
+        Expr::BinaryOp { left, op, right } => match quantifier(right) {
+            Some((all, array)) => quantified(op, all, evaluate_in(left, record)?, evaluate_in(array, record)?),
+            None => binary(op, evaluate_in(left, record)?, evaluate_in(right, record)?),
+        },
+        Expr::TypedString { data_type, value } => typed_literal(data_type, value),
+        Expr::Function(function) => call(function, record),
+        Expr::Cast { expr, data_type } => explicit_cast(evaluate_in(expr, record)?, data_type),
+        expr => Err(QueryError::not_supported_operation(expr.to_string())),
+    }
+}
+
+/// Names of columns that expression refers to, they are empty for constant
+/// expressions
+pub(crate) fn columns(expr: &Expr) -> Vec<String> {
+    match expr {
+        Expr::Identifier(ident) => vec![ident.value.clone()],
+        Expr::CompoundIdentifier(idents) => idents.last().map(|ident| ident.value.clone()).into_iter().collect(),
+        Expr::Nested(expr) | Expr::UnaryOp { expr, .. } | Expr::Cast { expr, .. } => columns(expr),
+        Expr::BinaryOp { left, right, .. } => {
+            let mut names = columns(left);
+            names.extend(columns(right));
+            names
+        }
+        Expr::Function(function) => function.args.iter().flat_map(columns).collect(),
+        _ => vec![],
+    }
+}
+
+/// Type of expression value that is known from types of columns without
+/// evaluating it. Type of NULL and string literals is unknown until they are
+/// used with values of known types, e.g. `id + NULL` is of type of `id`
+pub(crate) fn expr_type(expr: &Expr, columns: &[(String, SqlType)]) -> Option<SqlType> {
+    match expr {
+        Expr::Value(value) => literal_type(value),
+        Expr::Identifier(ident) => column_type(&ident.value, columns),
+        Expr::CompoundIdentifier(idents) => idents.last().and_then(|ident| column_type(&ident.value, columns)),
+        Expr::Nested(expr) => expr_type(expr, columns),
+        Expr::UnaryOp {
+            op: UnaryOperator::Not, ..
+        } => Some(SqlType::Bool),
+        Expr::UnaryOp { expr, .. } => expr_type(expr, columns),
+        Expr::BinaryOp { left, op, right } => match quantifier(right) {
+            Some(_) => Some(SqlType::Bool),
+            None => binary_type(op, expr_type(left, columns), expr_type(right, columns)),
+        },
+        Expr::TypedString { data_type, .. } | Expr::Cast { data_type, .. } => {
+            match crate::ddl::create_table::sql_type(data_type, &mut |name| {
+                Ok(Err(QueryError::type_does_not_exist(name.to_string())))
+            }) {
+                Ok(Ok(sql_type)) => Some(sql_type),
+                _ => None,
+            }
+        }
+        Expr::Function(function) => function_type(function, columns),
+        _ => None,
+    }
+}
+
+/// Argument of aggregate function, e.g. `id` of `jsonb_agg(id)`, that is
+/// evaluated for every record to compute value of the function once
+pub(crate) fn aggregate_argument(expr: &Expr) -> Option<&Expr> {
+    match expr {
+        Expr::Function(function) if function.args.len() == 1 => {
+            match function.name.to_string().to_lowercase().as_str() {
+                "jsonb_agg" | "json_agg" => Some(&function.args[0]),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Value of aggregate function of its argument values, it is NULL if there
+/// are no values as in PostgreSQL
+pub(crate) fn aggregate(expr: &Expr, values: &[Datum]) -> Datum {
+    let name = match expr {
+        Expr::Function(function) => function.name.to_string().to_lowercase(),
+        _ => String::new(),
+    };
+    if values.is_empty() {
+        Datum::Null
+    } else if name == "json_agg" {
+        Datum::Json(format!(
+            "[{}]",
+            values
+                .iter()
+                .map(|value| json_text(value, to_json(value)))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ))
+    } else {
+        Datum::Jsonb(Json::Array(values.iter().map(to_json).collect()))
+    }
+}
+
+/// Expression which string literals that are compared with CHAR(n) values are
+/// of the same type as `unknown` literals are in PostgreSQL, so their trailing
+/// spaces are insignificant, e.g. `code = 'ab  '` is `code = 'ab'`
+pub(crate) fn with_char_literals(expr: &Expr, columns: &[(String, SqlType)]) -> Expr {
+    let is_comparison = |op: &BinaryOperator| {
+        matches!(
+            op,
+            BinaryOperator::Gt
+                | BinaryOperator::Lt
+                | BinaryOperator::GtEq
+                | BinaryOperator::LtEq
+                | BinaryOperator::Eq
+                | BinaryOperator::NotEq
+        )
+    };
+    match expr {
+        Expr::Nested(expr) => Expr::Nested(Box::new(with_char_literals(expr, columns))),
+        Expr::UnaryOp { op, expr } => Expr::UnaryOp {
+            op: op.clone(),
+            expr: Box::new(with_char_literals(expr, columns)),
+        },
+        Expr::BinaryOp { left, op, right } => {
+            let operand = |operand: &Expr, other: &Expr| match operand {
+                Expr::Value(Value::SingleQuotedString(value))
+                    if is_comparison(op) && matches!(expr_type(other, columns), Some(SqlType::Char(_))) =>
+                {
+                    Expr::Value(Value::SingleQuotedString(value.trim_end().to_owned()))
+                }
+                operand => with_char_literals(operand, columns),
+            };
+            Expr::BinaryOp {
+                left: Box::new(operand(left, right)),
+                op: op.clone(),
+                right: Box::new(operand(right, left)),
+            }
+        }
+        Expr::Cast { expr, data_type } => Expr::Cast {
+            expr: Box::new(with_char_literals(expr, columns)),
+            data_type: data_type.clone(),
+        },
+        Expr::Function(function) => Expr::Function(Function {
+            args: function
+                .args
+                .iter()
+                .map(|arg| with_char_literals(arg, columns))
+                .collect(),
+            ..function.clone()
+        }),
+        expr => expr.clone(),
+    }
+}
+
+/// Expression which casts of string literals to enum types and string literals
+/// that are compared with enum values are replaced with values of the enum
+/// type, e.g. `mood < 'ok'`. Enum types are looked up by the given function
+pub(crate) fn with_enum_values(
+    expr: &Expr,
+    columns: &[(String, SqlType)],
+    user_type: &mut dyn FnMut(&ObjectName) -> SystemResult<Result<SqlType, QueryError>>,
+) -> SystemResult<Result<Expr, QueryError>> {
+    let resolved = match expr {
+        Expr::Nested(expr) => match with_enum_values(expr, columns, user_type)? {
+            Ok(expr) => Expr::Nested(Box::new(expr)),
+            Err(error) => return Ok(Err(error)),
+        },
+        Expr::UnaryOp { op, expr } => match with_enum_values(expr, columns, user_type)? {
+            Ok(expr) => Expr::UnaryOp {
+                op: op.clone(),
+                expr: Box::new(expr),
+            },
+            Err(error) => return Ok(Err(error)),
+        },
+        Expr::Cast { expr, data_type } => match (&**expr, enum_cast(data_type, user_type)?) {
+            (_, Err(error)) => return Ok(Err(error)),
+            (Expr::Value(Value::SingleQuotedString(label)), Ok(Some(enum_type))) => {
+                match enum_value(&enum_type, label) {
+                    Ok(value) => value,
+                    Err(error) => return Ok(Err(error)),
+                }
+            }
+            _ => match with_enum_values(expr, columns, user_type)? {
+                Ok(expr) => Expr::Cast {
+                    expr: Box::new(expr),
+                    data_type: data_type.clone(),
+                },
+                Err(error) => return Ok(Err(error)),
+            },
+        },
+        Expr::BinaryOp { left, op, right } => {
+            let is_comparison = matches!(
+                op,
+                BinaryOperator::Gt
+                    | BinaryOperator::Lt
+                    | BinaryOperator::GtEq
+                    | BinaryOperator::LtEq
+                    | BinaryOperator::Eq
+                    | BinaryOperator::NotEq
+            );
+            let left_type = operand_enum_type(left, columns, user_type)?;
+            let right_type = operand_enum_type(right, columns, user_type)?;
+            let mut operand = |operand: &Expr, other: Option<SqlType>| match (operand, other) {
+                (Expr::Value(Value::SingleQuotedString(label)), Some(enum_type)) if is_comparison => {
+                    Ok(enum_value(&enum_type, label))
+                }
+                (operand, _) => with_enum_values(operand, columns, user_type),
+            };
+            match (operand(left, right_type)?, operand(right, left_type)?) {
+                (Ok(left), Ok(right)) => Expr::BinaryOp {
+                    left: Box::new(left),
+                    op: op.clone(),
+                    right: Box::new(right),
+                },
+                (Err(error), _) | (_, Err(error)) => return Ok(Err(error)),
+            }
+        }
+        Expr::Function(function) => {
+            let mut args = vec![];
+            for arg in function.args.iter() {
+                match with_enum_values(arg, columns, user_type)? {
+                    Ok(arg) => args.push(arg),
+                    Err(error) => return Ok(Err(error)),
+                }
+            }
+            Expr::Function(Function {
+                args,
+                ..function.clone()
+            })
+        }
+        expr => expr.clone(),
+    };
+    Ok(Ok(resolved))
+}
+
+// only names of custom types are looked up, they are enum types or arrays of them
+fn enum_cast(
+    data_type: &DataType,
+    user_type: &mut dyn FnMut(&ObjectName) -> SystemResult<Result<SqlType, QueryError>>,
+) -> SystemResult<Result<Option<SqlType>, QueryError>> {
+    match data_type {
+        DataType::Custom(_) => Ok(
+            crate::ddl::create_table::sql_type(data_type, user_type)?.map(|sql_type| match sql_type {
+                SqlType::Enum { .. } => Some(sql_type),
+                _ => None,
+            }),
+        ),
+        _ => Ok(Ok(None)),
+    }
+}
+
+fn operand_enum_type(
+    operand: &Expr,
+    columns: &[(String, SqlType)],
+    user_type: &mut dyn FnMut(&ObjectName) -> SystemResult<Result<SqlType, QueryError>>,
+) -> SystemResult<Option<SqlType>> {
+    let sql_type = match operand {
+        Expr::Nested(operand) => return operand_enum_type(operand, columns, user_type),
+        Expr::Cast { data_type, .. } => enum_cast(data_type, user_type)?.ok().flatten(),
+        operand => expr_type(operand, columns),
+    };
+    Ok(sql_type.filter(|sql_type| matches!(sql_type, SqlType::Enum { .. })))
+}
+
+// enum value is called as `"enum"('type name', oid, position, 'label')`
+// function, as values of other types it is compared by the position of its label
+fn enum_value(enum_type: &SqlType, label: &str) -> Result<Expr, QueryError> {
+    match enum_type.constraint().validate(label) {
+        Ok(Datum::Enum(oid, position, label)) => Ok(Expr::Function(Function {
+            name: ObjectName(vec![Ident::with_quote('"', "enum")]),
+            args: vec![
+                Expr::Value(Value::SingleQuotedString(enum_type.to_string())),
+                Expr::Value(Value::Number(oid.to_string())),
+                Expr::Value(Value::Number(position.to_string())),
+                Expr::Value(Value::SingleQuotedString(label)),
+            ],
+            over: None,
+            distinct: false,
+        })),
+        _ => Err(QueryError::invalid_enum_value(enum_type.to_string(), label.to_owned())),
+    }
+}
+
+fn column_type(name: &str, columns: &[(String, SqlType)]) -> Option<SqlType> {
+    columns
+        .iter()
+        .find(|(column, _sql_type)| column == name)
+        .map(|(_column, sql_type)| sql_type.clone())
+}
+
+fn literal_type(value: &Value) -> Option<SqlType> {
+    match value {
+        Value::Number(number) => match number_literal(number) {
+            Ok(Datum::Int32(_)) => Some(SqlType::Integer),
+            Ok(Datum::Int64(_)) => Some(SqlType::BigInt),
+            _ => Some(SqlType::Decimal(None)),
+        },
+        Value::Boolean(_) => Some(SqlType::Bool),
+        Value::Interval { .. } => Some(SqlType::Interval),
+        _ => None,
+    }
+}
+
+fn function_type(function: &Function, columns: &[(String, SqlType)]) -> Option<SqlType> {
+    let name = match function.name.0.as_slice() {
+        [name] if name.quote_style.is_some() => name.value.clone(),
+        _ => function.name.to_string().to_lowercase(),
+    };
+    let arg_type = |index: usize| function.args.get(index).and_then(|arg| expr_type(arg, columns));
+    match name.as_str() {
+        "gen_random_uuid" => Some(SqlType::Uuid),
+        // labels are not needed to know the type of enum value
+        "enum" => match function.args.as_slice() {
+            [Expr::Value(Value::SingleQuotedString(name)), Expr::Value(Value::Number(oid)), ..] => {
+                oid.parse().ok().map(|oid| SqlType::Enum {
+                    name: name.clone(),
+                    oid,
+                    labels: vec![],
+                })
+            }
+            _ => None,
+        },
+        "nextval" | "currval" | "setval" => Some(SqlType::BigInt),
+        "array" => Some(SqlType::Array(Box::new(
+            (0..function.args.len()).find_map(arg_type).unwrap_or(SqlType::Text),
+        ))),
+        "[]" => match arg_type(0) {
+            Some(SqlType::Array(element)) => Some(*element),
+            _ => None,
+        },
+        // untyped literal operand is taken as JSONB
+        "->" | "#>" => match arg_type(0) {
+            Some(SqlType::Json) => Some(SqlType::Json),
+            _ => Some(SqlType::Jsonb),
+        },
+        "->>" | "#>>" => Some(SqlType::Text),
+        "@>" | "?" => Some(SqlType::Bool),
+        "jsonb_build_object" | "jsonb_build_array" | "jsonb_agg" => Some(SqlType::Jsonb),
+        "json_build_object" | "json_build_array" | "json_agg" => Some(SqlType::Json),
+        _ => None,
+    }
+}
+
+// operand of unknown type is taken to be of the type of the other operand as
+// `unknown` literals are in PostgreSQL
+fn binary_type(op: &BinaryOperator, left: Option<SqlType>, right: Option<SqlType>) -> Option<SqlType> {
+    match op {
+        BinaryOperator::And
+        | BinaryOperator::Or
+        | BinaryOperator::Gt
+        | BinaryOperator::Lt
+        | BinaryOperator::GtEq
+        | BinaryOperator::LtEq
+        | BinaryOperator::Eq
+        | BinaryOperator::NotEq => Some(SqlType::Bool),
+        BinaryOperator::Plus
+        | BinaryOperator::Minus
+        | BinaryOperator::Multiply
+        | BinaryOperator::Divide
+        | BinaryOperator::Modulus => match (left, right) {
+            (Some(left), Some(right)) => arithmetic_type(op, &left, &right),
+            (Some(known), None) | (None, Some(known)) => arithmetic_type(op, &known, &known).or(Some(known)),
+            (None, None) => None,
+        },
+        _ => None,
+    }
+}
+
+// follows `arithmetic` and `temporal_arithmetic` rules of result types
+fn arithmetic_type(op: &BinaryOperator, left: &SqlType, right: &SqlType) -> Option<SqlType> {
+    let rank = |sql_type: &SqlType| match sql_type {
+        SqlType::SmallInt => Some(0),
+        SqlType::Integer => Some(1),
+        SqlType::BigInt => Some(2),
+        SqlType::Decimal(_) => Some(3),
+        SqlType::Real => Some(4),
+        SqlType::DoublePrecision => Some(5),
+        _ => None,
+    };
+    let is_int = |sql_type: &SqlType| matches!(rank(sql_type), Some(0..=2));
+    if let (Some(left), Some(right)) = (rank(left), rank(right)) {
+        return Some(match (left.max(right), left.min(right)) {
+            (0, _) => SqlType::SmallInt,
+            (1, _) => SqlType::Integer,
+            (2, _) => SqlType::BigInt,
+            (3, _) => SqlType::Decimal(None),
+            // real operand is converted to double precision with numeric one
+            (4, 3) => SqlType::DoublePrecision,
+            (4, _) => SqlType::Real,
+            _ => SqlType::DoublePrecision,
+        });
+    }
+    match (op, left, right) {
+        (BinaryOperator::Plus, SqlType::Date, days)
+        | (BinaryOperator::Plus, days, SqlType::Date)
+        | (BinaryOperator::Minus, SqlType::Date, days)
+            if is_int(days) =>
+        {
+            Some(SqlType::Date)
+        }
+        (BinaryOperator::Minus, SqlType::Date, SqlType::Date) => Some(SqlType::Integer),
+        (BinaryOperator::Plus, SqlType::Date, SqlType::Time)
+        | (BinaryOperator::Plus, SqlType::Time, SqlType::Date)
+        | (BinaryOperator::Plus, SqlType::Date, SqlType::Interval)
+        | (BinaryOperator::Plus, SqlType::Interval, SqlType::Date)
+        | (BinaryOperator::Minus, SqlType::Date, SqlType::Interval)
+        | (BinaryOperator::Plus, SqlType::Timestamp, SqlType::Interval)
+        | (BinaryOperator::Plus, SqlType::Interval, SqlType::Timestamp)
+        | (BinaryOperator::Minus, SqlType::Timestamp, SqlType::Interval) => Some(SqlType::Timestamp),
+        (BinaryOperator::Plus, SqlType::TimestampWithTimeZone, SqlType::Interval)
+        | (BinaryOperator::Plus, SqlType::Interval, SqlType::TimestampWithTimeZone)
+        | (BinaryOperator::Minus, SqlType::TimestampWithTimeZone, SqlType::Interval) => {
+            Some(SqlType::TimestampWithTimeZone)
+        }
+        (BinaryOperator::Plus, SqlType::Time, SqlType::Interval)
+        | (BinaryOperator::Plus, SqlType::Interval, SqlType::Time)
+        | (BinaryOperator::Minus, SqlType::Time, SqlType::Interval) => Some(SqlType::Time),
+        (BinaryOperator::Minus, SqlType::Timestamp, SqlType::Timestamp)
+        | (BinaryOperator::Minus, SqlType::TimestampWithTimeZone, SqlType::TimestampWithTimeZone)
+        | (BinaryOperator::Minus, SqlType::Time, SqlType::Time)
+        | (BinaryOperator::Plus, SqlType::Interval, SqlType::Interval)
+        | (BinaryOperator::Minus, SqlType::Interval, SqlType::Interval) => Some(SqlType::Interval),
+        (BinaryOperator::Multiply, SqlType::Interval, factor)
+        | (BinaryOperator::Multiply, factor, SqlType::Interval)
+            if rank(factor).is_some() =>
+        {
+            Some(SqlType::Interval)
+        }
+        (BinaryOperator::Divide, SqlType::Interval, divisor) if rank(divisor).is_some() => Some(SqlType::Interval),
+        _ => None,
+    }
+}
+
+fn column(name: &str, record: &[(String, Datum)]) -> Result<Datum, QueryError> {
+    record
+        .iter()
+        .find(|(column, _datum)| column == name)
+        .map(|(_column, datum)| datum.clone())
+        .ok_or_else(|| QueryError::column_does_not_exist(vec![name.to_owned()]))
+}
+
+fn literal(value: &Value) -> Result<Datum, QueryError> {
+    match value {
+        Value::Number(number) => number_literal(number),
+        Value::SingleQuotedString(value) => Ok(Datum::String(value.clone())),
+        Value::Boolean(value) => Ok(Datum::Bool(*value)),
+        Value::Null => Ok(Datum::Null),
+        Value::Interval {
+            value,
+            leading_field,
+            last_field,
+            ..
+        } => {
+            // number without units is a number of the last field of qualifier
+            let parsed = match last_field.as_ref().or(leading_field.as_ref()) {
+                Some(field) => Interval::parse_with_field(value, interval_field(field)),
+                None => value.parse::<Interval>(),
+            };
+            parsed
+                .map(Datum::Interval)
+                .map_err(|error| datetime_error(error, "interval", value))
+        }
+        value => Err(QueryError::not_supported_operation(value.to_string())),
+    }
+}
+
+// integer literals that do not fit into bigint and literals with fraction or
+// exponent are numeric as in PostgreSQL
+fn number_literal(number: &str) -> Result<Datum, QueryError> {
+    if let Ok(value) = number.parse::<i32>() {
+        Ok(Datum::Int32(value))
+    } else if let Ok(value) = number.parse::<i64>() {
+        Ok(Datum::Int64(value))
+    } else {
+        number
+            .parse::<Numeric>()
+            .map(Datum::Numeric)
+            .map_err(|_| QueryError::not_supported_operation(number.to_owned()))
+    }
+}
+
+// `DATE '2020-01-01'`, `TIME '04:05'` or `TIMESTAMP '2020-01-01 04:05'`
+fn typed_literal(data_type: &DataType, value: &str) -> Result<Datum, QueryError> {
+    let (sql_type, type_name) = match data_type {
+        DataType::Date => (SqlType::Date, "date"),
+        DataType::Time => (SqlType::Time, "time"),
+        DataType::Timestamp => (SqlType::Timestamp, "timestamp"),
+        DataType::Interval => (SqlType::Interval, "interval"),
+        DataType::Uuid => (SqlType::Uuid, "uuid"),
+        data_type => {
+            return Err(QueryError::not_supported_operation(format!(
+                "{} '{}'",
+                data_type, value
+            )))
+        }
+    };
+    sql_type
+        .constraint()
+        .validate(value)
+        .map_err(|error| datetime_error(error, type_name, value))
+}
+
+// `CAST(value AS type)` or `value::type`, user defined types are not known
+// outside of a table context
+fn explicit_cast(datum: Datum, data_type: &DataType) -> Result<Datum, QueryError> {
+    let user_type =
+        &mut |name: &sqlparser::ast::ObjectName| Ok(Err(QueryError::not_supported_operation(name.to_string())));
+    let sql_type = match crate::ddl::create_table::sql_type(data_type, user_type) {
+        Ok(sql_type) => sql_type?,
+        Err(_) => unreachable!("types of casts are not looked up in storage"),
+    };
+    cast(&datum, &sql_type, CastContext::Explicit).map_err(|error| match error {
+        CastError::NotAllowed => QueryError::cannot_cast(type_name(&datum), sql_type.to_string()),
+        CastError::Constraint(error) => constraint_error(error, &sql_type, &datum.to_text().unwrap_or_default()),
+    })
+}
+
+/// Converts value of an expression into the type of the column it is stored in
+pub(crate) fn assign(datum: Datum, column_name: &str, sql_type: &SqlType) -> Result<Datum, QueryError> {
+    cast(&datum, sql_type, CastContext::Assignment).map_err(|error| match error {
+        CastError::NotAllowed => {
+            QueryError::datatype_mismatch(column_name.to_owned(), sql_type.to_string(), type_name(&datum))
+        }
+        CastError::Constraint(error) => {
+            constraint_error(error, sql_type, &datum.to_text().unwrap_or_default()).with_column(column_name.to_owned())
+        }
+    })
+}
+
+/// Error of value that can't be represented by the type
+pub(crate) fn constraint_error(error: ConstraintError, sql_type: &SqlType, value: &str) -> QueryError {
+    match error {
+        ConstraintError::OutOfRange => match sql_type {
+            SqlType::Decimal(_) => QueryError::numeric_field_overflow(),
+            sql_type => QueryError::out_of_range(sql_type.to_string()),
+        },
+        ConstraintError::ValueTooLong => QueryError::value_too_long(sql_type.to_string()),
+        ConstraintError::NotAnEnumLabel => QueryError::invalid_enum_value(sql_type.to_string(), value.to_owned()),
+        ConstraintError::NotADateTime | ConstraintError::DateTimeFieldOverflow => {
+            datetime_error(error, &sql_type.to_string(), value)
+        }
+        _ => QueryError::invalid_text_representation(sql_type.to_string(), value.to_owned()),
+    }
+}
+
+// `ANY(array)`, `SOME(array)` or `ALL(array)` on the right side of comparison
+// are parsed as function calls
+fn quantifier(expr: &Expr) -> Option<(bool, &Expr)> {
+    match expr {
+        Expr::Function(function) if function.args.len() == 1 && function.name.0.len() == 1 => {
+            let name = &function.name.0[0];
+            if name.quote_style.is_some() {
+                return None;
+            }
+            match name.value.to_lowercase().as_str() {
+                "any" | "some" => Some((false, &function.args[0])),
+                "all" => Some((true, &function.args[0])),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+// ANY is true if comparison is true for some element and ALL is false if it
+// is false for some element, otherwise result is NULL when there are NULL
+// comparisons as in three-valued logic
+fn quantified(op: &BinaryOperator, all: bool, left: Datum, right: Datum) -> Result<Datum, QueryError> {
+    let elements = match right {
+        Datum::Null => return Ok(Datum::Null),
+        Datum::Array(elements) => elements,
+        Datum::String(literal) => array_literal(&literal)?,
+        _ => return Err(QueryError::array_required(op.to_string())),
+    };
+    let mut has_null = false;
+    for element in elements {
+        match binary(op, left.clone(), element)? {
+            Datum::Bool(result) if result != all => return Ok(Datum::Bool(result)),
+            Datum::Bool(_) => {}
+            Datum::Null => has_null = true,
+            result => {
+                return Err(QueryError::not_boolean_argument(
+                    if all { "ALL" } else { "ANY" }.to_owned(),
+                    type_name(&result),
+                ))
+            }
+        }
+    }
+    Ok(if has_null { Datum::Null } else { Datum::Bool(all) })
+}
+
+// untyped array literal is an array of text, e.g. `'{a,b}'`
+fn array_literal(value: &str) -> Result<Vec<Datum>, QueryError> {
+    match SqlType::Array(Box::new(SqlType::Text)).constraint().validate(value) {
+        Ok(Datum::Array(elements)) => Ok(elements),
+        _ => Err(QueryError::invalid_text_representation(
+            "text[]".to_owned(),
+            value.to_owned(),
+        )),
+    }
+}
+
+// PostgreSQL arrays are indexed from 1 and out of bounds element is NULL
+fn subscript(array: &Datum, index: &Datum) -> Result<Datum, QueryError> {
+    match (array, index) {
+        (Datum::Null, _) | (_, Datum::Null) => Ok(Datum::Null),
+        (Datum::Array(elements), index) if is_int(index) => Ok(usize::try_from(as_int(index) - 1)
+            .ok()
+            .and_then(|index| elements.get(index))
+            .cloned()
+            .unwrap_or(Datum::Null)),
+        (Datum::Array(_), _) => Err(QueryError::array_subscript_not_integer()),
+        (other, _) => Err(QueryError::not_an_array(type_name(other))),
+    }
+}
+
+// JSON operators and array subscripts are called as functions with quoted
+// names, see `parser::rewrite_json_operators` and `parser::rewrite_subscripts`
+fn call(function: &Function, record: &[(String, Datum)]) -> Result<Datum, QueryError> {
+    let name = match function.name.0.as_slice() {
+        [name] if name.quote_style.is_some() => name.value.clone(),
+        _ => function.name.to_string().to_lowercase(),
+    };
+    let args = function
+        .args
+        .iter()
+        .map(|arg| evaluate_in(arg, record))
+        .collect::<Result<Vec<Datum>, QueryError>>()?;
+    match (name.as_str(), args.as_slice()) {
+        ("gen_random_uuid", []) => Ok(Datum::Uuid(Uuid::new_v4())),
+        ("enum", [_name, oid, position, Datum::String(label)]) if is_int(oid) && is_int(position) => {
+            Ok(Datum::Enum(as_int(oid) as u32, as_int(position) as u32, label.clone()))
+        }
+        ("array", args) => Ok(Datum::Array(args.to_vec())),
+        ("[]", [array, index]) => subscript(array, index),
+        ("->", [left, right])
+        | ("->>", [left, right])
+        | ("#>", [left, right])
+        | ("#>>", [left, right])
+        | ("@>", [left, right])
+        | ("?", [left, right]) => json_operator(&name, left, right),
+        ("jsonb_build_object", args) => build_json_object(args).map(|pairs| Datum::Jsonb(Json::object(pairs))),
+        ("json_build_object", args) => build_json_object(args).map(|pairs| {
+            let pairs = pairs
+                .into_iter()
+                .zip(args.iter().skip(1).step_by(2))
+                .map(|((key, value), arg)| format!("{} : {}", Json::String(key), json_text(arg, value)))
+                .collect::<Vec<String>>();
+            Datum::Json(format!("{{{}}}", pairs.join(", ")))
+        }),
+        ("jsonb_build_array", args) => Ok(Datum::Jsonb(Json::Array(args.iter().map(to_json).collect()))),
+        ("json_build_array", args) => Ok(Datum::Json(format!(
+            "[{}]",
+            args.iter()
+                .map(|arg| json_text(arg, to_json(arg)))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ))),
+        _ => Err(QueryError::undefined_function(format!(
+            "{}({})",
+            name,
+            args.iter().map(type_name).collect::<Vec<String>>().join(", ")
+        ))),
+    }
+}
+
+// untyped literal operand is taken as JSONB, operators on JSON return JSON
+fn json_operator(operator: &str, left: &Datum, right: &Datum) -> Result<Datum, QueryError> {
+    let undefined = || QueryError::undefined_operator(format!("{} {} {}", type_name(left), operator, type_name(right)));
+    let (document, is_json) = match left {
+        Datum::Null => return Ok(Datum::Null),
+        Datum::Json(text) => (json_literal(text, "json")?, true),
+        Datum::Jsonb(document) => (document.clone(), false),
+        Datum::String(text) => (json_literal(text, "jsonb")?, false),
+        _ => return Err(undefined()),
+    };
+    let value = |value: Option<&Json>| match value {
+        Some(value) if is_json => Datum::Json(value.to_string()),
+        Some(value) => Datum::Jsonb(value.clone()),
+        None => Datum::Null,
+    };
+    let text = |value: Option<&Json>| value.and_then(Json::to_text).map_or(Datum::Null, Datum::String);
+    match (operator, right) {
+        (_, Datum::Null) => Ok(Datum::Null),
+        ("->", Datum::String(key)) => Ok(value(document.field(key))),
+        ("->", index) if is_int(index) => Ok(value(document.element(as_int(index)))),
+        ("->>", Datum::String(key)) => Ok(text(document.field(key))),
+        ("->>", index) if is_int(index) => Ok(text(document.element(as_int(index)))),
+        ("#>", path) | ("#>>", path) if matches!(path, Datum::String(_) | Datum::Array(_)) => {
+            let path = match path {
+                Datum::String(literal) => array_literal(literal)?,
+                Datum::Array(elements) => elements.clone(),
+                _ => unreachable!(),
+            };
+            // NULL path element does not match anything
+            let found = path
+                .iter()
+                .map(Datum::to_text)
+                .collect::<Option<Vec<String>>>()
+                .and_then(|path| document.path(&path).cloned());
+            if operator == "#>" {
+                Ok(value(found.as_ref()))
+            } else {
+                Ok(text(found.as_ref()))
+            }
+        }
+        ("@>", Datum::Jsonb(other)) if !is_json => Ok(Datum::Bool(document.contains(other))),
+        ("@>", Datum::String(other)) if !is_json => Ok(Datum::Bool(document.contains(&json_literal(other, "jsonb")?))),
+        ("?", Datum::String(key)) if !is_json => Ok(Datum::Bool(document.exists(key))),
+        _ => Err(undefined()),
+    }
+}
+
+fn json_literal(value: &str, type_name: &str) -> Result<Json, QueryError> {
+    value
+        .parse()
+        .map_err(|_| QueryError::invalid_text_representation(type_name.to_owned(), value.to_owned()))
+}
+
+fn build_json_object(args: &[Datum]) -> Result<Vec<(String, Json)>, QueryError> {
+    if !args.len().is_multiple_of(2) {
+        return Err(QueryError::odd_number_of_arguments());
+    }
+    args.chunks(2)
+        .map(|pair| match pair[0].to_text() {
+            Some(key) => Ok((key, to_json(&pair[1]))),
+            None => Err(QueryError::null_object_key()),
+        })
+        .collect()
+}
+
+// JSON arguments of `json_build_*` functions are kept as they were given
+fn json_text(arg: &Datum, value: Json) -> String {
+    match arg {
+        Datum::Json(text) => text.clone(),
+        _ => value.to_string(),
+    }
+}
+
+fn to_json(datum: &Datum) -> Json {
+    match datum {
+        Datum::Null => Json::Null,
+        Datum::Bool(value) => Json::Bool(*value),
+        Datum::Numeric(value) => Json::Number(value.clone()),
+        Datum::Json(text) => text.parse().unwrap_or(Json::Null),
+        Datum::Jsonb(value) => value.clone(),
+        Datum::Array(elements) => Json::Array(elements.iter().map(to_json).collect()),
+        datum if is_int(datum) => Json::Number(Numeric::from(as_int(datum))),
+        // NaN and infinities are not JSON numbers
+        datum => {
+            let text = datum.to_text().unwrap_or_default();
+            match text.parse() {
+                Ok(number) if is_number(datum) => Json::Number(number),
+                _ => Json::String(text),
+            }
+        }
+    }
+}
+
+fn datetime_error(error: ConstraintError, type_name: &str, value: &str) -> QueryError {
+    match error {
+        ConstraintError::DateTimeFieldOverflow => QueryError::datetime_field_overflow(value.to_owned()),
+        _ => QueryError::invalid_datetime_syntax(type_name.to_owned(), value.to_owned()),
+    }
+}
+
+fn interval_field(field: &DateTimeField) -> IntervalField {
+    match field {
+        DateTimeField::Year => IntervalField::Year,
+        DateTimeField::Month => IntervalField::Month,
+        DateTimeField::Day => IntervalField::Day,
+        DateTimeField::Hour => IntervalField::Hour,
+        DateTimeField::Minute => IntervalField::Minute,
+        DateTimeField::Second => IntervalField::Second,
+    }
+}
+
+fn unary(op: &UnaryOperator, operand: Datum) -> Result<Datum, QueryError> {
+    match (op, operand) {
+        (_, Datum::Null) => Ok(Datum::Null),
+        (UnaryOperator::Not, Datum::Bool(value)) => Ok(Datum::Bool(!value)),
+        (UnaryOperator::Not, operand) => Err(QueryError::not_boolean_argument(
+            op.to_string(),
+            type_name(&operand).to_owned(),
+        )),
+        (UnaryOperator::Plus, operand) if is_number(&operand) => Ok(operand),
+        (UnaryOperator::Minus, Datum::Float32(value)) => Ok(Datum::Float32(-value)),
+        (UnaryOperator::Minus, Datum::Float64(value)) => Ok(Datum::Float64(-value)),
+        (UnaryOperator::Minus, Datum::Numeric(value)) => Ok(Datum::Numeric(value.neg())),
+        (UnaryOperator::Minus, Datum::Interval(value)) => value
+            .checked_neg()
+            .map(Datum::Interval)
+            .ok_or_else(|| QueryError::datetime_out_of_range("interval".to_owned())),
+        (UnaryOperator::Minus, operand) if is_number(&operand) => {
+            narrow(as_int(&operand).checked_neg(), int_rank(&operand))
+        }
+        (op, operand) => Err(QueryError::undefined_operator(format!(
+            "{} {}",
+            op,
+            type_name(&operand)
+        ))),
+    }
+}
+
+fn binary(op: &BinaryOperator, left: Datum, right: Datum) -> Result<Datum, QueryError> {
+    match op {
+        BinaryOperator::And | BinaryOperator::Or => logical(op, left, right),
+        BinaryOperator::Plus
+        | BinaryOperator::Minus
+        | BinaryOperator::Multiply
+        | BinaryOperator::Divide
+        | BinaryOperator::Modulus => arithmetic(op, left, right),
+        BinaryOperator::Gt
+        | BinaryOperator::Lt
+        | BinaryOperator::GtEq
+        | BinaryOperator::LtEq
+        | BinaryOperator::Eq
+        | BinaryOperator::NotEq => comparison(op, left, right),
+        op => Err(QueryError::undefined_operator(format!(
+            "{} {} {}",
+            type_name(&left),
+            op,
+            type_name(&right)
+        ))),
+    }
+}
+
+// three-valued logic: NULL AND false is false, NULL OR true is true
+fn logical(op: &BinaryOperator, left: Datum, right: Datum) -> Result<Datum, QueryError> {
+    let as_bool = |operand: &Datum| match operand {
+        Datum::Null => Ok(None),
+        Datum::Bool(value) => Ok(Some(*value)),
+        operand => Err(QueryError::not_boolean_argument(
+            op.to_string(),
+            type_name(operand).to_owned(),
+        )),
+    };
+    let (left, right) = (as_bool(&left)?, as_bool(&right)?);
+    let result = match op {
+        BinaryOperator::And => match (left, right) {
+            (Some(false), _) | (_, Some(false)) => Some(false),
+            (Some(true), Some(true)) => Some(true),
+            _ => None,
+        },
+        _ => match (left, right) {
+            (Some(true), _) | (_, Some(true)) => Some(true),
+            (Some(false), Some(false)) => Some(false),
+            _ => None,
+        },
+    };
+    Ok(result.map(Datum::Bool).unwrap_or(Datum::Null))
+}
+
+fn arithmetic(op: &BinaryOperator, left: Datum, right: Datum) -> Result<Datum, QueryError> {
+    match (&left, &right) {
+        (Datum::Null, _) | (_, Datum::Null) => Ok(Datum::Null),
+        _ if is_temporal(&left) || is_temporal(&right) => temporal_arithmetic(op, &left, &right),
+        // integers are converted to real, so that real stays real
+        (Datum::Float32(_), other) | (other, Datum::Float32(_))
+            if matches!(other, Datum::Float32(_)) || is_int(other) =>
+        {
+            let (l, r) = (as_float(&left) as f32, as_float(&right) as f32);
+            let result = float_arithmetic(op, l as f64, r as f64)? as f32;
+            if result.is_infinite() && l.is_finite() && r.is_finite() {
+                Err(QueryError::float_overflow())
+            } else {
+                Ok(Datum::Float32(result))
+            }
+        }
+        (Datum::Float32(_), _) | (Datum::Float64(_), _) | (_, Datum::Float32(_)) | (_, Datum::Float64(_))
+            if is_number(&left) && is_number(&right) =>
+        {
+            float_arithmetic(op, as_float(&left), as_float(&right)).map(Datum::Float64)
+        }
+        (Datum::Numeric(_), _) | (_, Datum::Numeric(_)) if is_number(&left) && is_number(&right) => {
+            numeric_arithmetic(op, &as_numeric(&left), &as_numeric(&right)).map(Datum::Numeric)
+        }
+        _ if is_number(&left) && is_number(&right) => {
+            let (l, r) = (as_int(&left), as_int(&right));
+            let result = match op {
+                BinaryOperator::Plus => l.checked_add(r),
+                BinaryOperator::Minus => l.checked_sub(r),
+                BinaryOperator::Multiply => l.checked_mul(r),
+                BinaryOperator::Divide if r == 0 => return Err(QueryError::division_by_zero()),
+                BinaryOperator::Divide => l.checked_div(r),
+                BinaryOperator::Modulus if r == 0 => return Err(QueryError::division_by_zero()),
+                // i64::MIN % -1 overflows in Rust but remainder is always zero
+                BinaryOperator::Modulus if r == -1 => Some(0),
+                _ => l.checked_rem(r),
+            };
+            narrow(result, int_rank(&left).max(int_rank(&right)))
+        }
+        _ => Err(QueryError::undefined_operator(format!(
+            "{} {} {}",
+            type_name(&left),
+            op,
+            type_name(&right)
+        ))),
+    }
+}
+
+// date/time arithmetic as PostgreSQL defines it, e.g. `timestamp + interval`
+// is timestamp and `timestamp - timestamp` is interval
+fn temporal_arithmetic(op: &BinaryOperator, left: &Datum, right: &Datum) -> Result<Datum, QueryError> {
+    let out_of_range = |type_name: &str| QueryError::datetime_out_of_range(type_name.to_owned());
+    let timestamp = |interval: Option<Interval>, timestamp: i64| {
+        interval
+            .and_then(|interval| interval.add_to_timestamp(timestamp))
+            .map(Datum::Timestamp)
+            .ok_or_else(|| out_of_range("timestamp"))
+    };
+    let timestamp_with_time_zone = |interval: Option<Interval>, timestamp: i64| {
+        interval
+            .and_then(|interval| interval.add_to_timestamp(timestamp))
+            .map(Datum::TimestampWithTimeZone)
+            .ok_or_else(|| out_of_range("timestamp with time zone"))
+    };
+    match (op, left, right) {
+        (BinaryOperator::Plus, Datum::Date(date), days) | (BinaryOperator::Plus, days, Datum::Date(date))
+            if is_int(days) =>
+        {
+            add_days(*date, as_int(days))
+        }
+        (BinaryOperator::Minus, Datum::Date(date), days) if is_int(days) => match as_int(days).checked_neg() {
+            Some(days) => add_days(*date, days),
+            None => Err(out_of_range("date")),
+        },
+        (BinaryOperator::Minus, Datum::Date(left), Datum::Date(right))
+            if !is_infinite_date(*left) && !is_infinite_date(*right) =>
+        {
+            Ok(Datum::Int32(left - right))
+        }
+        (BinaryOperator::Plus, Datum::Date(date), Datum::Time(time))
+        | (BinaryOperator::Plus, Datum::Time(time), Datum::Date(date)) => {
+            if is_infinite_date(*date) {
+                Ok(Datum::Timestamp(date_to_timestamp(*date)))
+            } else {
+                Ok(Datum::Timestamp(date_to_timestamp(*date) + time))
+            }
+        }
+        (BinaryOperator::Plus, Datum::Date(date), Datum::Interval(interval))
+        | (BinaryOperator::Plus, Datum::Interval(interval), Datum::Date(date)) => {
+            timestamp(Some(*interval), date_to_timestamp(*date))
+        }
+        (BinaryOperator::Minus, Datum::Date(date), Datum::Interval(interval)) => {
+            timestamp(interval.checked_neg(), date_to_timestamp(*date))
+        }
+        (BinaryOperator::Plus, Datum::Timestamp(value), Datum::Interval(interval))
+        | (BinaryOperator::Plus, Datum::Interval(interval), Datum::Timestamp(value)) => {
+            timestamp(Some(*interval), *value)
+        }
+        (BinaryOperator::Minus, Datum::Timestamp(value), Datum::Interval(interval)) => {
+            timestamp(interval.checked_neg(), *value)
+        }
+        // session time zone is a fixed offset, so days and months are added
+        // the same way as to timestamp in UTC
+        (BinaryOperator::Plus, Datum::TimestampWithTimeZone(value), Datum::Interval(interval))
+        | (BinaryOperator::Plus, Datum::Interval(interval), Datum::TimestampWithTimeZone(value)) => {
+            timestamp_with_time_zone(Some(*interval), *value)
+        }
+        (BinaryOperator::Minus, Datum::TimestampWithTimeZone(value), Datum::Interval(interval)) => {
+            timestamp_with_time_zone(interval.checked_neg(), *value)
+        }
+        (BinaryOperator::Minus, Datum::Timestamp(left), Datum::Timestamp(right))
+        | (BinaryOperator::Minus, Datum::TimestampWithTimeZone(left), Datum::TimestampWithTimeZone(right)) => {
+            Interval::between_timestamps(*left, *right)
+                .map(Datum::Interval)
+                .ok_or_else(|| out_of_range("interval"))
+        }
+        (BinaryOperator::Plus, Datum::Time(time), Datum::Interval(interval))
+        | (BinaryOperator::Plus, Datum::Interval(interval), Datum::Time(time)) => {
+            Ok(Datum::Time(interval.add_to_time(*time)))
+        }
+        (BinaryOperator::Minus, Datum::Time(time), Datum::Interval(interval)) => interval
+            .checked_neg()
+            .map(|interval| Datum::Time(interval.add_to_time(*time)))
+            .ok_or_else(|| out_of_range("time")),
+        (BinaryOperator::Minus, Datum::Time(left), Datum::Time(right)) => {
+            Ok(Datum::Interval(Interval::between_times(*left, *right)))
+        }
+        (BinaryOperator::Plus, Datum::Interval(left), Datum::Interval(right)) => left
+            .checked_add(right)
+            .map(Datum::Interval)
+            .ok_or_else(|| out_of_range("interval")),
+        (BinaryOperator::Minus, Datum::Interval(left), Datum::Interval(right)) => left
+            .checked_sub(right)
+            .map(Datum::Interval)
+            .ok_or_else(|| out_of_range("interval")),
+        (BinaryOperator::Multiply, Datum::Interval(interval), factor)
+        | (BinaryOperator::Multiply, factor, Datum::Interval(interval))
+            if is_number(factor) =>
+        {
+            interval
+                .mul(as_float(factor))
+                .map(Datum::Interval)
+                .ok_or_else(|| out_of_range("interval"))
+        }
+        (BinaryOperator::Divide, Datum::Interval(interval), divisor) if is_number(divisor) => {
+            if as_float(divisor) == 0.0 {
+                return Err(QueryError::division_by_zero());
+            }
+            interval
+                .div(as_float(divisor))
+                .map(Datum::Interval)
+                .ok_or_else(|| out_of_range("interval"))
+        }
+        _ => Err(QueryError::undefined_operator(format!(
+            "{} {} {}",
+            type_name(left),
+            op,
+            type_name(right)
+        ))),
+    }
+}
+
+// infinite dates stay infinite
+fn add_days(date: i32, days: i64) -> Result<Datum, QueryError> {
+    if is_infinite_date(date) {
+        return Ok(Datum::Date(date));
+    }
+    i32::try_from(days)
+        .ok()
+        .and_then(|days| date.checked_add(days))
+        .filter(|date| !is_infinite_date(*date))
+        .map(Datum::Date)
+        .ok_or_else(|| QueryError::datetime_out_of_range("date".to_owned()))
+}
+
+// `infinity` and `-infinity` dates are bounds of i32
+fn is_infinite_date(date: i32) -> bool {
+    date == i32::MAX || date == i32::MIN
+}
+
+fn float_arithmetic(op: &BinaryOperator, left: f64, right: f64) -> Result<f64, QueryError> {
+    let result = match op {
+        BinaryOperator::Plus => left + right,
+        BinaryOperator::Minus => left - right,
+        BinaryOperator::Multiply => left * right,
+        BinaryOperator::Divide if right == 0.0 => return Err(QueryError::division_by_zero()),
+        BinaryOperator::Divide => left / right,
+        // PostgreSQL does not define modulo for floating point types
+        _ => {
+            return Err(QueryError::undefined_operator(format!(
+                "double precision {} double precision",
+                op
+            )))
+        }
+    };
+    if result.is_infinite() && left.is_finite() && right.is_finite() {
+        Err(QueryError::float_overflow())
+    } else {
+        Ok(result)
+    }
+}
+
+fn numeric_arithmetic(op: &BinaryOperator, left: &Numeric, right: &Numeric) -> Result<Numeric, QueryError> {
+    match op {
+        BinaryOperator::Plus => Ok(left.add(right)),
+        BinaryOperator::Minus => Ok(left.sub(right)),
+        BinaryOperator::Multiply => Ok(left.mul(right)),
+        BinaryOperator::Divide => left.div(right).ok_or_else(QueryError::division_by_zero),
+        _ => left.rem(right).ok_or_else(QueryError::division_by_zero),
+    }
+}
+
+fn comparison(op: &BinaryOperator, left: Datum, right: Datum) -> Result<Datum, QueryError> {
+    if left == Datum::Null || right == Datum::Null {
+        return Ok(Datum::Null);
+    }
+    let ordering = compare(op, &left, &right)?;
+    let result = match op {
+        BinaryOperator::Gt => ordering == Ordering::Greater,
+        BinaryOperator::Lt => ordering == Ordering::Less,
+        BinaryOperator::GtEq => ordering != Ordering::Less,
+        BinaryOperator::LtEq => ordering != Ordering::Greater,
+        BinaryOperator::Eq => ordering == Ordering::Equal,
+        _ => ordering != Ordering::Equal,
+    };
+    Ok(Datum::Bool(result))
+}
+
+// arrays are compared element by element, NULL elements are equal to each
+// other and greater than any other value as in PostgreSQL
+fn compare(op: &BinaryOperator, left: &Datum, right: &Datum) -> Result<Ordering, QueryError> {
+    let ordering = match (left, right) {
+        (Datum::Array(left), Datum::Array(right)) => {
+            for pair in left.iter().zip(right.iter()) {
+                let ordering = match pair {
+                    (Datum::Null, Datum::Null) => Ordering::Equal,
+                    (Datum::Null, _) => Ordering::Greater,
+                    (_, Datum::Null) => Ordering::Less,
+                    (left, right) => compare(op, left, right)?,
+                };
+                if ordering != Ordering::Equal {
+                    return Ok(ordering);
+                }
+            }
+            left.len().cmp(&right.len())
+        }
+        (Datum::Bool(left), Datum::Bool(right)) => left.cmp(right),
+        (Datum::String(left), Datum::String(right)) => left.cmp(right),
+        (Datum::Bytea(left), Datum::Bytea(right)) => left.cmp(right),
+        (Datum::Uuid(left), Datum::Uuid(right)) => left.cmp(right),
+        (Datum::Jsonb(left), Datum::Jsonb(right)) => left.cmp(right),
+        // labels of enum are ordered as they were declared
+        (Datum::Enum(left_type, left, _), Datum::Enum(right_type, right, _)) if left_type == right_type => {
+            left.cmp(right)
+        }
+        (Datum::Date(left), Datum::Date(right)) => left.cmp(right),
+        (Datum::Time(left), Datum::Time(right)) => left.cmp(right),
+        (Datum::Timestamp(left), Datum::Timestamp(right)) => left.cmp(right),
+        (Datum::TimestampWithTimeZone(left), Datum::TimestampWithTimeZone(right)) => left.cmp(right),
+        (Datum::Date(left), Datum::Timestamp(right)) => date_to_timestamp(*left).cmp(right),
+        (Datum::Timestamp(left), Datum::Date(right)) => left.cmp(&date_to_timestamp(*right)),
+        (Datum::Interval(left), Datum::Interval(right)) => left.cmp(right),
+        (Datum::Float32(_), _) | (Datum::Float64(_), _) | (_, Datum::Float32(_)) | (_, Datum::Float64(_))
+            if is_number(left) && is_number(right) =>
+        {
+            float_cmp(as_float(left), as_float(right))
+        }
+        (Datum::Numeric(_), _) | (_, Datum::Numeric(_)) if is_number(left) && is_number(right) => {
+            as_numeric(left).cmp(&as_numeric(right))
+        }
+        _ if is_number(left) && is_number(right) => as_int(left).cmp(&as_int(right)),
+        _ => {
+            return Err(QueryError::undefined_operator(format!(
+                "{} {} {}",
+                type_name(left),
+                op,
+                type_name(right)
+            )))
+        }
+    };
+    Ok(ordering)
+}
+
+// PostgreSQL considers NaN equal to itself and greater than any other value
+// to have total order for sorting and indexing
+fn float_cmp(left: f64, right: f64) -> Ordering {
+    match (left.is_nan(), right.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => left.partial_cmp(&right).unwrap(),
+    }
+}
+
+fn narrow(value: Option<i64>, rank: u8) -> Result<Datum, QueryError> {
+    match rank {
+        0 => value
+            .and_then(|value| i16::try_from(value).ok())
+            .map(Datum::Int16)
+            .ok_or_else(|| QueryError::out_of_range("smallint".to_owned())),
+        1 => value
+            .and_then(|value| i32::try_from(value).ok())
+            .map(Datum::Int32)
+            .ok_or_else(|| QueryError::out_of_range("integer".to_owned())),
+        _ => value
+            .map(Datum::Int64)
+            .ok_or_else(|| QueryError::out_of_range("bigint".to_owned())),
+    }
+}
+
+fn is_number(datum: &Datum) -> bool {
+    matches!(
+        datum,
+        Datum::Int16(_) | Datum::Int32(_) | Datum::Int64(_) | Datum::Float32(_) | Datum::Float64(_) | Datum::Numeric(_)
+    )
+}
+
+fn is_int(datum: &Datum) -> bool {
+    matches!(datum, Datum::Int16(_) | Datum::Int32(_) | Datum::Int64(_))
+}
+
+fn is_temporal(datum: &Datum) -> bool {
+    matches!(
+        datum,
+        Datum::Date(_)
+            | Datum::Time(_)
+            | Datum::TimeWithTimeZone(_, _)
+            | Datum::Timestamp(_)
+            | Datum::TimestampWithTimeZone(_)
+            | Datum::Interval(_)
+    )
+}
+
+fn int_rank(datum: &Datum) -> u8 {
+    match datum {
+        Datum::Int16(_) => 0,
+        Datum::Int32(_) => 1,
+        _ => 2,
+    }
+}
+
+fn as_int(datum: &Datum) -> i64 {
+    match datum {
+        Datum::Int16(value) => *value as i64,
+        Datum::Int32(value) => *value as i64,
+        Datum::Int64(value) => *value,
+        _ => unreachable!(),
+    }
+}
+
+fn as_float(datum: &Datum) -> f64 {
+    match datum {
+        Datum::Float32(value) => *value as f64,
+        Datum::Float64(value) => *value,
+        Datum::Numeric(value) => value.to_f64(),
+        datum => as_int(datum) as f64,
+    }
+}
+
+fn as_numeric(datum: &Datum) -> Numeric {
+    match datum {
+        Datum::Numeric(value) => value.clone(),
+        datum => Numeric::from(as_int(datum)),
+    }
+}
+
+pub(crate) fn type_name(datum: &Datum) -> String {
+    let name = match datum {
+        Datum::Null => "unknown",
+        Datum::Bool(_) => "boolean",
+        Datum::Int16(_) => "smallint",
+        Datum::Int32(_) => "integer",
+        Datum::Int64(_) => "bigint",
+        Datum::Float32(_) => "real",
+        Datum::Float64(_) => "double precision",
+        Datum::Numeric(_) => "numeric",
+        Datum::Date(_) => "date",
+        Datum::Time(_) => "time without time zone",
+        Datum::TimeWithTimeZone(_, _) => "time with time zone",
+        Datum::Timestamp(_) => "timestamp without time zone",
+        Datum::TimestampWithTimeZone(_) => "timestamp with time zone",
+        Datum::Interval(_) => "interval",
+        Datum::String(_) => "text",
+        Datum::Bytea(_) => "bytea",
+        Datum::Uuid(_) => "uuid",
+        Datum::Json(_) => "json",
+        Datum::Jsonb(_) => "jsonb",
+        // values do not keep name of their type
+        Datum::Enum(_, _, _) => "anyenum",
+        Datum::Array(elements) => {
+            return match elements.iter().find(|element| **element != Datum::Null) {
+                Some(element) => type_name(element) + "[]",
+                None => "text[]".to_owned(),
+            }
+        }
+    };
+    name.to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(expr: &str) -> Result<Datum, QueryError> {
+        evaluate(&crate::parser::parse_expr(expr).unwrap())
+    }
+
+    #[rstest::rstest(
+        expr,
+        expected,
+        case::integer("1", Datum::Int32(1)),
+        case::bigint("2147483648", Datum::Int64(2147483648)),
+        case::numeric("1.5", Datum::Numeric("1.5".parse().unwrap())),
+        case::big_numeric("9223372036854775808", Datum::Numeric("9223372036854775808".parse().unwrap())),
+        case::nested("(2 + 3) * 4", Datum::Int32(20)),
+        case::negative("-(1 + 1)", Datum::Int32(-2)),
+        case::integer_division("7 / 2", Datum::Int32(3)),
+        case::modulo("7 % 3", Datum::Int32(1)),
+        case::int_and_numeric("1.5 * 2", Datum::Numeric("3.0".parse().unwrap())),
+        case::exact_numeric("0.1 + 0.2 = 0.3", Datum::Bool(true)),
+        case::numeric_modulo("7.5 % 2", Datum::Numeric("1.5".parse().unwrap())),
+        case::widen_to_bigint("2147483648 - 1", Datum::Int64(2147483647)),
+        case::comparison("1 < 1.5", Datum::Bool(true)),
+        case::string_comparison("'a' = 'a'", Datum::Bool(true)),
+        case::and("true AND false", Datum::Bool(false)),
+        case::or("false OR true", Datum::Bool(true)),
+        case::not("NOT false", Datum::Bool(true)),
+        case::null_arithmetic("NULL + 1", Datum::Null),
+        case::null_and_false("NULL AND false", Datum::Bool(false)),
+        case::null_or_false("NULL OR false", Datum::Null)
+    )]
+    fn evaluation(expr: &str, expected: Datum) {
+        assert_eq!(eval(expr), Ok(expected));
+    }
+
+    #[rstest::rstest(
+        expr,
+        expected,
+        case::timestamp_plus_interval(
+            "TIMESTAMP '2020-01-31 10:00' + INTERVAL '1 month 1 hour'",
+            "2020-02-29 11:00:00"
+        ),
+        case::interval_plus_timestamp("INTERVAL '1 day' + TIMESTAMP '2020-12-31'", "2021-01-01 00:00:00"),
+        case::timestamp_minus_interval("TIMESTAMP '2020-03-01' - INTERVAL 'P1D'", "2020-02-29 00:00:00"),
+        case::timestamp_difference("TIMESTAMP '2020-01-02 12:00' - TIMESTAMP '2020-01-01 10:30'", "1 day 01:30:00"),
+        case::date_plus_interval("DATE '2020-01-01' + INTERVAL '36 hours'", "2020-01-02 12:00:00"),
+        case::date_plus_days("DATE '2020-02-28' + 2", "2020-03-01"),
+        case::date_difference("DATE '2020-03-01' - DATE '2020-02-01'", "29"),
+        case::date_plus_time("DATE '2020-01-01' + TIME '10:00'", "2020-01-01 10:00:00"),
+        case::time_wraps_around("TIME '23:00' + INTERVAL '2 hours'", "01:00:00"),
+        case::time_difference("TIME '10:00' - TIME '12:30'", "-02:30:00"),
+        case::interval_sum("INTERVAL '1 day' + INTERVAL '2 hours'", "1 day 02:00:00"),
+        case::interval_multiplication("INTERVAL '1 month' * 1.5", "1 mon 15 days"),
+        case::interval_division("INTERVAL '1 day' / 4", "06:00:00"),
+        case::negative_interval("-INTERVAL '1 day'", "-1 days"),
+        case::qualified_interval("INTERVAL '3' DAY", "3 days"),
+        case::interval_comparison("INTERVAL '1 month' = INTERVAL '30 days'", "t"),
+        case::date_and_timestamp_comparison("DATE '2020-01-01' < TIMESTAMP '2020-01-01 00:00:01'", "t")
+    )]
+    fn date_time_evaluation(expr: &str, expected: &str) {
+        assert_eq!(eval(expr).map(|datum| datum.to_text()), Ok(Some(expected.to_owned())));
+    }
+
+    #[rstest::rstest(
+        expr,
+        error,
+        case::invalid_interval(
+            "INTERVAL 'soon'",
+            QueryError::invalid_datetime_syntax("interval".to_owned(), "soon".to_owned())
+        ),
+        case::invalid_date(
+            "DATE '2020-02-30'",
+            QueryError::datetime_field_overflow("2020-02-30".to_owned())
+        ),
+        case::timestamp_out_of_range(
+            "TIMESTAMP '294276-12-31' + INTERVAL '1 day'",
+            QueryError::datetime_out_of_range("timestamp".to_owned())
+        ),
+        case::interval_division_by_zero("INTERVAL '1 day' / 0", QueryError::division_by_zero()),
+        case::timestamp_sum(
+            "TIMESTAMP '2020-01-01' + TIMESTAMP '2020-01-01'",
+            QueryError::undefined_operator(
+                "timestamp without time zone + timestamp without time zone".to_owned()
+            )
+        )
+    )]
+    fn date_time_errors(expr: &str, error: QueryError) {
+        assert_eq!(eval(expr), Err(error));
+    }
+
+    #[rstest::rstest(
+        expr,
+        expected,
+        case::constructor("ARRAY[1, NULL]", Datum::Array(vec![Datum::Int32(1), Datum::Null])),
+        case::subscript("ARRAY['a', 'b'][2]", Datum::String("b".to_owned())),
+        case::out_of_bounds("ARRAY[1][0]", Datum::Null),
+        case::null_subscript("ARRAY[1][NULL]", Datum::Null),
+        case::any("3 > ANY(ARRAY[5, 2])", Datum::Bool(true)),
+        case::any_with_null("1 = ANY(ARRAY[2, NULL])", Datum::Null),
+        case::any_of_empty("NULL = ANY(ARRAY[])", Datum::Bool(false)),
+        case::all_with_null("1 <> ALL(ARRAY[2, NULL])", Datum::Null),
+        case::all_false_with_null("1 <> ALL(ARRAY[1, NULL])", Datum::Bool(false)),
+        case::array_equality("ARRAY[1, NULL] = ARRAY[1, NULL]", Datum::Bool(true)),
+        case::array_order("ARRAY[1, 2] < ARRAY[1, 2, 0]", Datum::Bool(true)),
+        case::null_element_is_greatest("ARRAY[NULL] > ARRAY[1]", Datum::Bool(true)),
+        case::json_path_of_array(
+            "'{\"a\": [1, 2]}' #>> ARRAY['a', '1']",
+            Datum::String("2".to_owned())
+        )
+    )]
+    fn array_evaluation(expr: &str, expected: Datum) {
+        assert_eq!(eval(expr), Ok(expected));
+    }
+
+    #[test]
+    fn nan_is_equal_to_itself_and_greater_than_numbers() {
+        assert_eq!(
+            comparison(&BinaryOperator::Eq, Datum::Float64(f64::NAN), Datum::Float64(f64::NAN)),
+            Ok(Datum::Bool(true))
+        );
+        assert_eq!(
+            comparison(
+                &BinaryOperator::Gt,
+                Datum::Float64(f64::NAN),
+                Datum::Float64(f64::INFINITY)
+            ),
+            Ok(Datum::Bool(true))
+        );
+    }
+
+    #[test]
+    fn enums_are_ordered_by_declaration() {
+        let sad = Datum::Enum(16384, 0, "sad".to_owned());
+        let happy = Datum::Enum(16384, 1, "happy".to_owned());
+        assert_eq!(
+            comparison(&BinaryOperator::Lt, sad.clone(), happy.clone()),
+            Ok(Datum::Bool(true))
+        );
+        assert_eq!(
+            comparison(&BinaryOperator::Eq, happy.clone(), happy),
+            Ok(Datum::Bool(true))
+        );
+        assert!(comparison(&BinaryOperator::Lt, sad, Datum::Enum(16386, 0, "other".to_owned())).is_err());
+    }
+
+    #[rstest::rstest(
+        expr,
+        expected,
+        case::string_to_bigint("CAST('42' AS bigint)", Datum::Int64(42)),
+        case::numeric_to_integer("1.5::integer", Datum::Int32(2)),
+        case::float_to_integer("CAST(2.5 AS double precision)::integer", Datum::Int32(2)),
+        case::integer_to_boolean("0::boolean", Datum::Bool(false)),
+        case::integer_to_text("12::text", Datum::String("12".to_owned())),
+        case::truncated_varchar("'abcdef'::varchar(3)", Datum::String("abc".to_owned())),
+        case::string_to_date("'2020-01-31'::date + 1", Datum::Date(7336)),
+        case::array_elements("ARRAY[1, 2]::bigint[]", Datum::Array(vec![Datum::Int64(1), Datum::Int64(2)])),
+        case::null("NULL::integer", Datum::Null)
+    )]
+    fn casts(expr: &str, expected: Datum) {
+        assert_eq!(eval(expr), Ok(expected));
+    }
+
+    #[test]
+    fn assignment_casts() {
+        assert_eq!(
+            assign(Datum::Numeric(Numeric::from(7)), "column_i", &SqlType::SmallInt),
+            Ok(Datum::Int16(7))
+        );
+        assert_eq!(
+            assign(Datum::Bool(true), "column_i", &SqlType::Integer),
+            Err(QueryError::datatype_mismatch(
+                "column_i".to_owned(),
+                "integer".to_owned(),
+                "boolean".to_owned()
+            ))
+        );
+        assert_eq!(
+            assign(Datum::Int32(40000), "column_s", &SqlType::SmallInt),
+            Err(QueryError::out_of_range("smallint".to_owned()).with_column("column_s".to_owned()))
+        );
+    }
+
+    #[test]
+    fn random_uuid() {
+        match (eval("gen_random_uuid()"), eval("gen_random_uuid()")) {
+            (Ok(Datum::Uuid(first)), Ok(Datum::Uuid(second))) => assert_ne!(first, second),
+            other => panic!("expected two uuids, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn float_overflow() {
+        assert_eq!(
+            arithmetic(
+                &BinaryOperator::Multiply,
+                Datum::Float64(f64::MAX),
+                Datum::Float64(10.0)
+            ),
+            Err(QueryError::float_overflow())
+        );
+        assert_eq!(
+            arithmetic(
+                &BinaryOperator::Plus,
+                Datum::Float32(f32::MAX),
+                Datum::Float32(f32::MAX)
+            ),
+            Err(QueryError::float_overflow())
+        );
+    }
+
+    #[test]
+    fn float_modulo() {
+        assert_eq!(
+            arithmetic(&BinaryOperator::Modulus, Datum::Float64(1.5), Datum::Int32(1)),
+            Err(QueryError::undefined_operator(
+                "double precision % double precision".to_owned()
+            ))
+        );
+    }
+
+    #[rstest::rstest(
+        expr,
+        error,
+        case::integer_division_by_zero("1 / 0", QueryError::division_by_zero()),
+        case::numeric_division_by_zero("1.5 / 0", QueryError::division_by_zero()),
+        case::modulo_by_zero("1 % 0", QueryError::division_by_zero()),
+        case::integer_overflow("2147483647 + 1", QueryError::out_of_range("integer".to_owned())),
+        case::bigint_overflow(
+            "9223372036854775807 * 2",
+            QueryError::out_of_range("bigint".to_owned())
+        ),
+        case::bool_arithmetic("true + 1", QueryError::undefined_operator("boolean + integer".to_owned())),
+        case::not_bool_and(
+            "1 AND true",
+            QueryError::not_boolean_argument("AND".to_owned(), "integer".to_owned())
+        ),
+        case::not_bool_not(
+            "NOT 1",
+            QueryError::not_boolean_argument("NOT".to_owned(), "integer".to_owned())
+        ),
+        case::undefined_function(
+            "gen_random_uuid(1)",
+            QueryError::undefined_function("gen_random_uuid(integer)".to_owned())
+        ),
+        case::cast_not_allowed(
+            "true::date",
+            QueryError::cannot_cast("boolean".to_owned(), "date".to_owned())
+        ),
+        case::cast_invalid_text(
+            "'abc'::integer",
+            QueryError::invalid_text_representation("integer".to_owned(), "abc".to_owned())
+        ),
+        case::cast_out_of_range("70000::smallint", QueryError::out_of_range("smallint".to_owned()))
+    )]
+    fn errors(expr: &str, error: QueryError) {
+        assert_eq!(eval(expr), Err(error));
+    }
+
+    #[rstest::rstest(
+        expr,
+        expected,
+        case::column_and_null("a + NULL", Some(SqlType::SmallInt)),
+        case::widened_integer("a * 2", Some(SqlType::Integer)),
+        case::real_and_numeric("2.5 * 1.5::real", Some(SqlType::DoublePrecision)),
+        case::array_element("i[5]", Some(SqlType::Integer)),
+        case::json_field("jb -> 'a'", Some(SqlType::Jsonb)),
+        case::json_text("jb ->> 'a'", Some(SqlType::Text)),
+        case::jsonb_aggregate("jsonb_agg(a)", Some(SqlType::Jsonb)),
+        case::json_aggregate("json_agg(jb)", Some(SqlType::Json)),
+        case::comparison("a < 1", Some(SqlType::Bool)),
+        case::date_difference("d - d", Some(SqlType::Integer)),
+        case::date_and_interval("d + interval '1 day'", Some(SqlType::Timestamp)),
+        case::null("NULL", None),
+        case::string_literal("'a'", None)
+    )]
+    fn types_of_expressions(expr: &str, expected: Option<SqlType>) {
+        let columns = vec![
+            ("a".to_owned(), SqlType::SmallInt),
+            ("i".to_owned(), SqlType::Array(Box::new(SqlType::Integer))),
+            ("jb".to_owned(), SqlType::Jsonb),
+            ("d".to_owned(), SqlType::Date),
+        ];
+        assert_eq!(expr_type(&crate::parser::parse_expr(expr).unwrap(), &columns), expected);
+    }
+}
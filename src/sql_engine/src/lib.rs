@@ -16,56 +16,633 @@ extern crate log;
 
 use crate::{
     ddl::{
-        create_schema::CreateSchemaCommand, create_table::CreateTableCommand, drop_schema::DropSchemaCommand,
-        drop_table::DropTableCommand,
+        create_schema::CreateSchemaCommand, create_sequence::CreateSequenceCommand, create_table::CreateTableCommand,
+        create_type::CreateTypeCommand, drop_schema::DropSchemaCommand, drop_sequence::DropSequenceCommand,
+        drop_table::DropTableCommand, drop_type::DropTypeCommand,
     },
-    dml::{delete::DeleteCommand, insert::InsertCommand, select::SelectCommand, update::UpdateCommand},
+    dml::{
+        delete::DeleteCommand, insert::InsertCommand, lock_table::LockTableCommand, select::SelectCommand,
+        update::UpdateCommand,
+    },
+    parser::ExtendedStatement,
+    prepared::PreparedStatement,
+    transaction::Transaction,
 };
 use kernel::SystemResult;
-use protocol::results::{QueryError, QueryEvent, QueryResult};
+use protocol::{
+    results::{QueryError, QueryEvent, QueryResult, QueryResults},
+    session::{Portal, Session},
+    sql_types::{PostgreSqlFormat, PostgreSqlType},
+    TransactionStatus,
+};
 
+use sql_types::{ByteaOutput, SqlType, TextFormat, TimeZone};
 use sqlparser::{
-    ast::{ObjectType, Statement},
-    dialect::PostgreSqlDialect,
-    parser::Parser,
+    ast::{DataType, ObjectName, ObjectType, SetVariableValue, Statement, Value},
+    parser::ParserError,
+    tokenizer::Token,
+};
+use std::{
+    iter,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use storage::{
+    backend::BackendStorage,
+    frontend::FrontendStorage,
+    lock::{IsolationLevel, LockDuration, LockError, LockMode, Resource, SessionId},
 };
-use std::sync::{Arc, Mutex};
-use storage::{backend::BackendStorage, frontend::FrontendStorage};
 
 mod ddl;
 mod dml;
+mod expr;
+mod parser;
+mod prepared;
+mod sequence;
+mod transaction;
+
+pub(crate) const DEFAULT_SCHEMA: &str = "public";
 
 pub struct Handler<P: BackendStorage> {
-    storage: Arc<Mutex<FrontendStorage<P>>>,
+    storage: Arc<FrontendStorage<P>>,
+    session_id: SessionId,
+    default_isolation: IsolationLevel,
+    // time zone and bytea_output settings
+    text_format: TextFormat,
+    // order of day, month and year in DateStyle, output is always ISO
+    date_order: &'static str,
+    // locks are held until the end of transaction or until the end of statement
+    // if it is executed outside of transaction block
+    transaction: Option<Transaction>,
+    // formats of result columns requested for the statement being executed
+    result_formats: Vec<PostgreSqlFormat>,
+    // prepared statements and portals of extended query protocol
+    session: Session<PreparedStatement>,
 }
 
-impl<P: BackendStorage> Handler<P> {
-    pub fn new(storage: Arc<Mutex<FrontendStorage<P>>>) -> Self {
-        Self { storage }
+impl<P: BackendStorage + 'static> Handler<P> {
+    pub fn new(storage: Arc<FrontendStorage<P>>) -> Self {
+        let session_id = storage.lock_manager().new_session();
+        Self {
+            storage,
+            session_id,
+            default_isolation: IsolationLevel::default(),
+            text_format: TextFormat::default(),
+            date_order: "MDY",
+            transaction: None,
+            result_formats: vec![],
+            session: Session::default(),
+        }
     }
 
-    #[allow(clippy::match_wild_err_arm)]
-    pub fn execute(&mut self, raw_sql_query: &str) -> SystemResult<QueryResult> {
-        let statement = match Parser::parse_sql(&PostgreSqlDialect {}, raw_sql_query) {
-            Ok(mut statements) => statements.pop().unwrap(),
-            Err(e) => {
-                log::error!("{:?} can't be parsed. Error: {:?}", raw_sql_query, e);
-                unimplemented!("PANIC!!! Ah-a-a-a")
+    pub fn execute(&mut self, raw_sql_query: &str) -> SystemResult<QueryResults> {
+        self.execute_with_formats(raw_sql_query, vec![])
+    }
+
+    /// Executes the query with values of result columns sent in the formats:
+    /// all columns are in text if there are no formats, a single format is
+    /// applied to all columns, otherwise every column has its own format
+    pub fn execute_with_formats(
+        &mut self,
+        raw_sql_query: &str,
+        result_formats: Vec<PostgreSqlFormat>,
+    ) -> SystemResult<QueryResults> {
+        let mut statements = match parser::parse(raw_sql_query) {
+            Ok(statements) => statements,
+            Err(error) => {
+                let position = parser::error_position(raw_sql_query, &error);
+                return Ok(single(Err(syntax_error(error, position))));
             }
         };
+        match statements.len() {
+            0 => return Ok(single(Ok(QueryEvent::EmptyQuery))),
+            1 => return self.execute_parsed(raw_sql_query, statements.remove(0), result_formats),
+            _ => {}
+        }
+        // statements are executed one after another in a transaction as in
+        // PostgreSQL, thus their results are collected before the next one is
+        // executed and the first error stops the query
+        let mut results = vec![];
+        for statement in statements {
+            if self.transaction.is_none() {
+                self.transaction = Some(Transaction::implicit(self.default_isolation));
+            }
+            let statement_results = self
+                .execute_parsed(raw_sql_query, statement, result_formats.clone())?
+                .collect::<Vec<QueryResult>>();
+            let failed = statement_results.iter().any(Result::is_err);
+            results.extend(statement_results);
+            if failed {
+                if self.in_implicit_transaction() {
+                    self.rollback()?;
+                    self.storage.lock_manager().unlock_all(self.session_id);
+                }
+                return Ok(Box::new(results.into_iter()));
+            }
+        }
+        if self.in_implicit_transaction() {
+            self.transaction = None;
+            release_locks(&self.storage, self.session_id, false);
+        }
+        Ok(Box::new(results.into_iter()))
+    }
+
+    /// Status of transaction block that is reported to client when the
+    /// server is ready for the next query
+    pub fn transaction_status(&self) -> TransactionStatus {
+        match &self.transaction {
+            None => TransactionStatus::Idle,
+            Some(transaction) if transaction.is_failed() => TransactionStatus::Failed,
+            Some(_) => TransactionStatus::InTransaction,
+        }
+    }
+
+    /// Prepares the statement with OIDs of its parameter types, types that
+    /// are not specified or specified as 0 are inferred from the statement
+    pub fn parse(
+        &mut self,
+        statement_name: String,
+        raw_sql_query: String,
+        param_types: Vec<i32>,
+    ) -> SystemResult<QueryResults> {
+        let statement = match PreparedStatement::parse(raw_sql_query, param_types, &self.storage)? {
+            Ok(statement) => statement,
+            Err(error) => return Ok(single(Err(error))),
+        };
+        Ok(single(
+            self.session
+                .save_statement(statement_name, statement)
+                .map(|()| QueryEvent::ParseComplete),
+        ))
+    }
+
+    /// Creates portal from the prepared statement and parameter values in the
+    /// formats, formats of parameters and result columns follow the same
+    /// rules as in `execute_with_formats`
+    pub fn bind(
+        &mut self,
+        portal_name: String,
+        statement_name: String,
+        param_formats: Vec<PostgreSqlFormat>,
+        raw_params: Vec<Option<Vec<u8>>>,
+        result_formats: Vec<PostgreSqlFormat>,
+    ) -> SystemResult<QueryResults> {
+        let statement = match self.session.statement(&statement_name) {
+            Some(statement) => match statement.bind(&statement_name, &param_formats, raw_params) {
+                Ok(statement) => statement,
+                Err(error) => return Ok(single(Err(error))),
+            },
+            None => {
+                return Ok(single(Err(QueryError::prepared_statement_does_not_exist(
+                    statement_name,
+                ))))
+            }
+        };
+        Ok(single(
+            self.session
+                .save_portal(portal_name, Portal::new(statement_name, statement, result_formats))
+                .map(|()| QueryEvent::BindComplete),
+        ))
+    }
+
+    /// Describes types of parameters and rows of the prepared statement
+    pub fn describe_statement(&mut self, name: &str) -> SystemResult<QueryResults> {
+        let statement = match self.session.statement(name) {
+            Some(statement) => match statement.with_nulls() {
+                Ok(statement) => statement,
+                Err(error) => return Ok(single(Err(error))),
+            },
+            None => {
+                return Ok(single(Err(QueryError::prepared_statement_does_not_exist(
+                    name.to_owned(),
+                ))))
+            }
+        };
+        let rows = match statement.statement() {
+            ExtendedStatement::Standard(Statement::Query(query)) | ExtendedStatement::LockingQuery { query, .. } => {
+                SelectCommand::new(
+                    statement.raw_sql_query(),
+                    query.clone(),
+                    LockMode::AccessShare,
+                    false,
+                    LockDuration::Statement,
+                    self.text_format.clone(),
+                    vec![],
+                    self.storage.clone(),
+                    self.session_id,
+                )
+                .describe()?
+                .map(|description| {
+                    let formats = vec![PostgreSqlFormat::Text; description.len()];
+                    QueryEvent::RowDescription(description, formats)
+                })
+            }
+            ExtendedStatement::Standard(Statement::ShowVariable { variable }) => self
+                .show_variable(statement.raw_sql_query(), variable.value.clone())
+                .next()
+                .unwrap_or(Ok(QueryEvent::NoData)),
+            _ => Ok(QueryEvent::NoData),
+        };
+        let parameters =
+            QueryEvent::ParameterDescription(statement.param_types().iter().map(SqlType::to_pg_types).collect());
+        Ok(Box::new(vec![Ok(parameters), rows].into_iter()))
+    }
+
+    /// Describes rows of the portal, statements that return rows are executed
+    /// to be described and their rows are sent when the portal is executed
+    pub fn describe_portal(&mut self, name: &str) -> SystemResult<QueryResults> {
+        let (statement, result_formats) = match self.session.portal_mut(name) {
+            Some(portal) if portal.is_started() => return Ok(single(portal.describe())),
+            Some(portal) => (portal.statement().clone(), portal.result_formats().to_vec()),
+            None => return Ok(single(Err(QueryError::portal_does_not_exist(name.to_owned())))),
+        };
+        match statement.statement() {
+            ExtendedStatement::Standard(Statement::Query(_))
+            | ExtendedStatement::Standard(Statement::ShowVariable { .. })
+            | ExtendedStatement::LockingQuery { .. } => {}
+            _ => return Ok(single(Ok(QueryEvent::NoData))),
+        }
+        let results = self.execute_prepared(&statement, result_formats)?;
+        let portal = self.session.portal_mut(name).expect("portal exists");
+        portal.start(results);
+        Ok(single(portal.describe()))
+    }
+
+    /// Executes the portal and returns at most `max_rows` rows if it is
+    /// positive, the portal is suspended if it has more rows
+    pub fn execute_portal(&mut self, name: &str, max_rows: i32) -> SystemResult<QueryResults> {
+        let (statement, result_formats) = match self.session.portal_mut(name) {
+            Some(portal) if portal.is_started() => return Ok(Box::new(portal.fetch(max_rows).into_iter())),
+            Some(portal) => (portal.statement().clone(), portal.result_formats().to_vec()),
+            None => return Ok(single(Err(QueryError::portal_does_not_exist(name.to_owned())))),
+        };
+        let results = self.execute_prepared(&statement, result_formats)?;
+        let portal = self.session.portal_mut(name).expect("portal exists");
+        portal.start(results);
+        Ok(Box::new(portal.fetch(max_rows).into_iter()))
+    }
+
+    /// Closes the prepared statement and its portals
+    pub fn close_statement(&mut self, name: &str) -> SystemResult<QueryResults> {
+        self.session.close_statement(name);
+        Ok(single(Ok(QueryEvent::CloseComplete)))
+    }
+
+    /// Closes the portal
+    pub fn close_portal(&mut self, name: &str) -> SystemResult<QueryResults> {
+        self.session.close_portal(name);
+        Ok(single(Ok(QueryEvent::CloseComplete)))
+    }
+
+    /// Finishes extended query, portals are closed if there is no
+    /// transaction in progress
+    pub fn sync(&mut self) {
+        if self.transaction.is_none() {
+            self.session.close_portals();
+        }
+    }
+
+    fn execute_prepared(
+        &mut self,
+        statement: &PreparedStatement,
+        result_formats: Vec<PostgreSqlFormat>,
+    ) -> SystemResult<QueryResults> {
+        self.execute_parsed(statement.raw_sql_query(), statement.statement().clone(), result_formats)
+    }
+
+    fn execute_parsed(
+        &mut self,
+        raw_sql_query: &str,
+        statement: ExtendedStatement,
+        result_formats: Vec<PostgreSqlFormat>,
+    ) -> SystemResult<QueryResults> {
+        self.result_formats = result_formats;
+        // only the end of failed transaction block is executed as in PostgreSQL
+        let failed = self.transaction.as_ref().map(Transaction::is_failed).unwrap_or(false);
+        if failed
+            && !matches!(
+                statement,
+                ExtendedStatement::Standard(Statement::Commit { .. })
+                    | ExtendedStatement::Standard(Statement::Rollback { .. })
+            )
+        {
+            return Ok(single(Err(QueryError::in_failed_transaction())));
+        }
+        let result = self.execute_extended(raw_sql_query, statement);
+        // records are read lazily while client consumes them, thus statement
+        // locks are released only when all results are dropped
+        let failure = self.transaction.as_ref().map(Transaction::failure);
+        let in_transaction = failure.is_some();
+        match result {
+            Ok(results) => Ok(Box::new(StatementResults {
+                results,
+                storage: self.storage.clone(),
+                session_id: self.session_id,
+                in_transaction,
+                failure,
+            })),
+            Err(error) => {
+                release_locks(&self.storage, self.session_id, in_transaction);
+                Err(error)
+            }
+        }
+    }
+
+    fn execute_extended(&mut self, raw_sql_query: &str, statement: ExtendedStatement) -> SystemResult<QueryResults> {
         log::debug!("STATEMENT = {:?}", statement);
         match statement {
-            Statement::StartTransaction { .. } => Ok(Ok(QueryEvent::TransactionStarted)),
-            Statement::SetVariable { .. } => Ok(Ok(QueryEvent::VariableSet)),
+            ExtendedStatement::Standard(statement) => self.execute_statement(raw_sql_query, statement),
+            ExtendedStatement::SetSessionTransaction { modes } => {
+                if let Some(isolation) = transaction::isolation_from_modes(&modes) {
+                    self.default_isolation = isolation;
+                }
+                Ok(single(Ok(QueryEvent::VariableSet)))
+            }
+            ExtendedStatement::Lock { tables, mode, nowait } => {
+                if self.transaction.is_some() {
+                    self.mark_queried();
+                    LockTableCommand::new(tables, mode, nowait, self.storage.clone(), self.session_id)
+                        .execute()
+                        .map(single)
+                } else {
+                    Ok(single(Err(QueryError::no_active_transaction("LOCK TABLE".to_owned()))))
+                }
+            }
+            ExtendedStatement::LockingQuery { query, mode, nowait } => {
+                self.mark_queried();
+                SelectCommand::new(
+                    raw_sql_query,
+                    query,
+                    mode,
+                    nowait,
+                    LockDuration::Transaction,
+                    self.text_format.clone(),
+                    self.result_formats.clone(),
+                    self.storage.clone(),
+                    self.session_id,
+                )
+                .execute()
+            }
+            ExtendedStatement::InsertOverridingSystemValue(Statement::Insert {
+                table_name,
+                columns,
+                source,
+                ..
+            }) => {
+                self.mark_queried();
+                InsertCommand::new(
+                    raw_sql_query,
+                    table_name,
+                    columns,
+                    source,
+                    true,
+                    self.text_format.time_zone.clone(),
+                    self.storage.clone(),
+                    self.session_id,
+                )
+                .execute()
+                .map(single)
+            }
+            ExtendedStatement::InsertOverridingSystemValue(_) => Ok(single(Err(QueryError::not_supported_operation(
+                raw_sql_query.to_owned(),
+            )))),
+            ExtendedStatement::CreateEnumType { name, labels } => {
+                self.mark_queried();
+                CreateTypeCommand::new(name, labels, self.storage.clone(), self.session_id)
+                    .execute()
+                    .map(single)
+            }
+            ExtendedStatement::DropType { names, if_exists } => {
+                self.mark_queried();
+                DropTypeCommand::new(names, if_exists, self.storage.clone(), self.session_id).execute()
+            }
+            ExtendedStatement::CreateSequence {
+                name,
+                if_not_exists,
+                parameters,
+            } => {
+                self.mark_queried();
+                CreateSequenceCommand::new(name, if_not_exists, parameters, self.storage.clone(), self.session_id)
+                    .execute()
+            }
+            ExtendedStatement::DropSequence { names, if_exists } => {
+                self.mark_queried();
+                DropSequenceCommand::new(names, if_exists, self.storage.clone(), self.session_id).execute()
+            }
+            ExtendedStatement::Prepare {
+                name,
+                param_types,
+                statement,
+            } => self
+                .prepare(raw_sql_query, name.value, param_types, statement)
+                .map(single),
+            ExtendedStatement::Execute { name, parameters } => {
+                let statement = match self.session.statement(&name.value) {
+                    Some(statement) => match statement.execute(&name.value, parameters) {
+                        Ok(statement) => statement,
+                        Err(error) => return Ok(single(Err(error))),
+                    },
+                    None => return Ok(single(Err(QueryError::prepared_statement_does_not_exist(name.value)))),
+                };
+                match statement.statement() {
+                    // prepared statement could execute itself
+                    ExtendedStatement::Execute { .. } => Ok(single(Err(QueryError::not_supported_operation(
+                        raw_sql_query.to_owned(),
+                    )))),
+                    parsed => self.execute_extended(statement.raw_sql_query(), parsed.clone()),
+                }
+            }
+            ExtendedStatement::Deallocate { name: Some(name) } => match self.session.statement(&name.value) {
+                Some(_) => {
+                    self.session.close_statement(&name.value);
+                    Ok(single(Ok(QueryEvent::StatementDeallocated)))
+                }
+                None => Ok(single(Err(QueryError::prepared_statement_does_not_exist(name.value)))),
+            },
+            ExtendedStatement::Deallocate { name: None } => {
+                self.session.close_statements();
+                Ok(single(Ok(QueryEvent::AllStatementsDeallocated)))
+            }
+            ExtendedStatement::Empty => Ok(single(Ok(QueryEvent::EmptyQuery))),
+        }
+    }
+
+    // only queries and statements that change data can be prepared as in
+    // PostgreSQL, types of parameters are looked up in storage
+    fn prepare(
+        &mut self,
+        raw_sql_query: &str,
+        name: String,
+        param_types: Vec<DataType>,
+        statement: Vec<Token>,
+    ) -> SystemResult<QueryResult> {
+        let mut types = vec![];
+        for data_type in param_types.iter() {
+            let sql_type = ddl::create_table::sql_type(data_type, &mut |name| match qualified_name(name) {
+                Ok((schema_name, type_name)) => match self.storage.user_type(&schema_name, &type_name)? {
+                    Some(sql_type) => Ok(Ok(sql_type)),
+                    None => Ok(Err(QueryError::type_does_not_exist(type_name))),
+                },
+                Err(_) => Ok(Err(QueryError::type_does_not_exist(name.to_string()))),
+            })?;
+            match sql_type {
+                Ok(sql_type) => types.push(Some(sql_type)),
+                Err(error) => return Ok(Err(error)),
+            }
+        }
+        let prepared = match PreparedStatement::prepare(raw_sql_query.to_owned(), statement, types, &self.storage)? {
+            Ok(prepared) => prepared,
+            Err(error) => return Ok(Err(error)),
+        };
+        match prepared.statement() {
+            ExtendedStatement::Standard(Statement::Query(_))
+            | ExtendedStatement::Standard(Statement::Insert { .. })
+            | ExtendedStatement::InsertOverridingSystemValue(Statement::Insert { .. })
+            | ExtendedStatement::Standard(Statement::Update { .. })
+            | ExtendedStatement::Standard(Statement::Delete { .. })
+            | ExtendedStatement::LockingQuery { .. } => {}
+            _ => return Ok(Err(QueryError::not_supported_operation(raw_sql_query.to_owned()))),
+        }
+        Ok(self
+            .session
+            .save_statement(name, prepared)
+            .map(|()| QueryEvent::StatementPrepared))
+    }
+
+    fn execute_statement(&mut self, raw_sql_query: &str, statement: Statement) -> SystemResult<QueryResults> {
+        match statement {
+            Statement::ShowVariable { variable } => Ok(self.show_variable(raw_sql_query, variable.value)),
+            Statement::Query(query) => {
+                self.mark_queried();
+                let duration = match &self.transaction {
+                    Some(transaction) => transaction.isolation.read_lock_duration(),
+                    None => LockDuration::Statement,
+                };
+                SelectCommand::new(
+                    raw_sql_query,
+                    query,
+                    LockMode::AccessShare,
+                    false,
+                    duration,
+                    self.text_format.clone(),
+                    self.result_formats.clone(),
+                    self.storage.clone(),
+                    self.session_id,
+                )
+                .execute()
+            }
+            statement => self.execute_single(raw_sql_query, statement).map(single),
+        }
+    }
+
+    fn execute_single(&mut self, raw_sql_query: &str, statement: Statement) -> SystemResult<QueryResult> {
+        match statement {
+            Statement::StartTransaction { modes } => {
+                match self.transaction.as_mut() {
+                    Some(transaction) => transaction.implicit = false,
+                    None => {
+                        let isolation = transaction::isolation_from_modes(&modes).unwrap_or(self.default_isolation);
+                        self.transaction = Some(Transaction::new(isolation));
+                    }
+                }
+                Ok(Ok(QueryEvent::TransactionStarted))
+            }
+            Statement::Commit { .. } => match self.transaction.as_ref() {
+                // changes of failed transaction are discarded on COMMIT
+                Some(transaction) if transaction.is_failed() => {
+                    self.rollback()?;
+                    Ok(Ok(QueryEvent::TransactionRolledBack))
+                }
+                _ => {
+                    self.transaction = None;
+                    Ok(Ok(QueryEvent::TransactionCommitted))
+                }
+            },
+            Statement::Rollback { .. } => {
+                self.rollback()?;
+                Ok(Ok(QueryEvent::TransactionRolledBack))
+            }
+            Statement::SetTransaction { modes } => match transaction::isolation_from_modes(&modes) {
+                Some(isolation) => Ok(self.set_transaction_isolation(isolation)),
+                None => Ok(Ok(QueryEvent::VariableSet)),
+            },
+            Statement::SetVariable { variable, value, .. } => {
+                let value = match value {
+                    SetVariableValue::Ident(ident) => ident.value,
+                    SetVariableValue::Literal(Value::SingleQuotedString(value)) => value,
+                    SetVariableValue::Literal(value) => value.to_string(),
+                };
+                let name = variable.value.to_lowercase();
+                match name.as_str() {
+                    "lock_timeout" => match lock_timeout(&value) {
+                        Some(timeout) => self.storage.lock_manager().set_lock_timeout(self.session_id, timeout),
+                        None => return Ok(Err(QueryError::invalid_parameter_value(name, value))),
+                    },
+                    "transaction_isolation" => match transaction::isolation_from_str(&value) {
+                        Some(isolation) => return Ok(self.set_transaction_isolation(isolation)),
+                        None => return Ok(Err(QueryError::invalid_parameter_value(name, value))),
+                    },
+                    "default_transaction_isolation" => {
+                        self.default_isolation = match value.to_lowercase().as_str() {
+                            "default" => IsolationLevel::default(),
+                            _ => match transaction::isolation_from_str(&value) {
+                                Some(isolation) => isolation,
+                                None => return Ok(Err(QueryError::invalid_parameter_value(name, value))),
+                            },
+                        }
+                    }
+                    "timezone" => {
+                        self.text_format.time_zone = match value.to_lowercase().as_str() {
+                            "default" | "local" => TimeZone::default(),
+                            // there is no time zone database, so named time
+                            // zones other than UTC are rejected
+                            _ => match TimeZone::parse(&value) {
+                                Some(time_zone) => time_zone,
+                                None => {
+                                    return Ok(Err(QueryError::invalid_parameter_value(name, value).with_hint(
+                                        "only UTC and offsets from it, e.g. '+03:00' or -8, are supported".to_owned(),
+                                    )))
+                                }
+                            },
+                        }
+                    }
+                    "bytea_output" => match ByteaOutput::parse(&value) {
+                        Some(output) => self.text_format.bytea_output = output,
+                        None => return Ok(Err(QueryError::invalid_parameter_value(name, value))),
+                    },
+                    "datestyle" => match date_order(&value) {
+                        Some(order) => self.date_order = order,
+                        None => return Ok(Err(QueryError::invalid_parameter_value(name, value))),
+                    },
+                    _ => {}
+                }
+                Ok(Ok(QueryEvent::VariableSet))
+            }
+            statement => {
+                self.mark_queried();
+                self.execute_query(raw_sql_query, statement)
+            }
+        }
+    }
+
+    fn execute_query(&mut self, raw_sql_query: &str, statement: Statement) -> SystemResult<QueryResult> {
+        match statement {
             Statement::CreateTable { name, columns, .. } => {
-                CreateTableCommand::new(name, columns, self.storage.clone()).execute()
+                CreateTableCommand::new(name, columns, self.storage.clone(), self.session_id).execute()
             }
             Statement::CreateSchema { schema_name, .. } => {
-                CreateSchemaCommand::new(schema_name, self.storage.clone()).execute()
+                CreateSchemaCommand::new(schema_name, self.storage.clone(), self.session_id).execute()
             }
             Statement::Drop { object_type, names, .. } => match object_type {
-                ObjectType::Table => DropTableCommand::new(names[0].clone(), self.storage.clone()).execute(),
-                ObjectType::Schema => DropSchemaCommand::new(names[0].clone(), self.storage.clone()).execute(),
+                ObjectType::Table => {
+                    DropTableCommand::new(names[0].clone(), self.storage.clone(), self.session_id).execute()
+                }
+                ObjectType::Schema => {
+                    DropSchemaCommand::new(names[0].clone(), self.storage.clone(), self.session_id).execute()
+                }
                 _ => Ok(Err(QueryError::not_supported_operation(raw_sql_query.to_owned()))),
             },
             Statement::Insert {
@@ -73,19 +650,256 @@ impl<P: BackendStorage> Handler<P> {
                 columns,
                 source,
                 ..
-            } => InsertCommand::new(raw_sql_query, table_name, columns, source, self.storage.clone()).execute(),
-            Statement::Query(query) => SelectCommand::new(raw_sql_query, query, self.storage.clone()).execute(),
+            } => InsertCommand::new(
+                raw_sql_query,
+                table_name,
+                columns,
+                source,
+                false,
+                self.text_format.time_zone.clone(),
+                self.storage.clone(),
+                self.session_id,
+            )
+            .execute(),
             Statement::Update {
                 table_name,
                 assignments,
                 ..
-            } => UpdateCommand::new(raw_sql_query, table_name, assignments, self.storage.clone()).execute(),
+            } => UpdateCommand::new(
+                raw_sql_query,
+                table_name,
+                assignments,
+                self.text_format.time_zone.clone(),
+                self.storage.clone(),
+                self.session_id,
+            )
+            .execute(),
             Statement::Delete { table_name, .. } => {
-                DeleteCommand::new(raw_sql_query, table_name, self.storage.clone()).execute()
+                DeleteCommand::new(raw_sql_query, table_name, self.storage.clone(), self.session_id).execute()
             }
             _ => Ok(Err(QueryError::not_supported_operation(raw_sql_query.to_owned()))),
         }
     }
+
+    fn show_variable(&self, raw_sql_query: &str, name: String) -> QueryResults {
+        let name = name.to_lowercase();
+        let value = match name.as_str() {
+            "transaction_isolation" => transaction::isolation_to_str(
+                self.transaction
+                    .as_ref()
+                    .map(|transaction| transaction.isolation)
+                    .unwrap_or(self.default_isolation),
+            )
+            .to_owned(),
+            "default_transaction_isolation" => transaction::isolation_to_str(self.default_isolation).to_owned(),
+            "timezone" => self.text_format.time_zone.name().to_owned(),
+            "bytea_output" => self.text_format.bytea_output.name().to_owned(),
+            "datestyle" => format!("ISO, {}", self.date_order),
+            _ => return single(Err(QueryError::not_supported_operation(raw_sql_query.to_owned()))),
+        };
+        Box::new(
+            vec![
+                Ok(QueryEvent::RowDescription(
+                    vec![(name, PostgreSqlType::VarChar(None))],
+                    vec![PostgreSqlFormat::Text],
+                )),
+                Ok(QueryEvent::DataRow(vec![Some(value.into_bytes())])),
+                Ok(QueryEvent::RecordsSelected(1)),
+            ]
+            .into_iter(),
+        )
+    }
+
+    fn in_implicit_transaction(&self) -> bool {
+        matches!(&self.transaction, Some(transaction) if transaction.implicit)
+    }
+
+    fn mark_queried(&mut self) {
+        if let Some(transaction) = self.transaction.as_mut() {
+            transaction.queried = true;
+        }
+    }
+
+    fn set_transaction_isolation(&mut self, isolation: IsolationLevel) -> QueryResult {
+        match self.transaction.as_mut() {
+            Some(transaction) if transaction.queried => Err(QueryError::transaction_isolation_after_query()),
+            Some(transaction) => {
+                transaction.isolation = isolation;
+                Ok(QueryEvent::VariableSet)
+            }
+            // PostgreSQL only warns that there is no transaction in progress
+            None => Ok(QueryEvent::VariableSet),
+        }
+    }
+}
+
+impl<P: BackendStorage> Handler<P> {
+    // storage undoes both changed records and definitions of the transaction
+    fn rollback(&mut self) -> SystemResult<()> {
+        if self.transaction.take().is_some() {
+            self.storage.rollback(self.session_id)?;
+        }
+        Ok(())
+    }
+}
+
+impl<P: BackendStorage> Drop for Handler<P> {
+    fn drop(&mut self) {
+        if let Err(error) = self.rollback() {
+            log::error!("session {} can't rollback its transaction {:?}", self.session_id, error);
+        }
+        self.storage.end_session(self.session_id);
+    }
+}
+
+fn single(result: QueryResult) -> QueryResults {
+    Box::new(iter::once(result))
+}
+
+// notices are sent before the result of the statement
+fn with_notices(notices: Vec<QueryError>, result: QueryResult) -> QueryResults {
+    Box::new(
+        notices
+            .into_iter()
+            .map(|notice| Ok(QueryEvent::Notice(notice)))
+            .chain(iter::once(result)),
+    )
+}
+
+// releases locks that were acquired by the statement when its results are
+// consumed or dropped
+struct StatementResults<P: BackendStorage> {
+    results: QueryResults,
+    storage: Arc<FrontendStorage<P>>,
+    session_id: SessionId,
+    in_transaction: bool,
+    // marks transaction as failed when an error is sent to client
+    failure: Option<Arc<AtomicBool>>,
+}
+
+impl<P: BackendStorage> Iterator for StatementResults<P> {
+    type Item = QueryResult;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.results.next();
+        if let (Some(Err(_)), Some(failure)) = (&result, &self.failure) {
+            failure.store(true, Ordering::SeqCst);
+        }
+        result
+    }
+}
+
+impl<P: BackendStorage> Drop for StatementResults<P> {
+    fn drop(&mut self) {
+        release_locks(&self.storage, self.session_id, self.in_transaction)
+    }
+}
+
+// changes are committed before locks are released, so that other writers
+// don't see them as uncommitted ones
+fn release_locks<P: BackendStorage>(storage: &FrontendStorage<P>, session_id: SessionId, in_transaction: bool) {
+    if in_transaction {
+        storage.lock_manager().unlock_statement_locks(session_id)
+    } else {
+        storage.commit(session_id);
+        storage.lock_manager().unlock_all(session_id)
+    }
+}
+
+/// Schema and name of an object, objects without schema are looked up and
+/// created in `public` schema as it is the only schema of PostgreSQL default
+/// `search_path`
+pub(crate) fn qualified_name(name: &ObjectName) -> Result<(String, String), QueryError> {
+    match name.0.as_slice() {
+        [object_name] => Ok((DEFAULT_SCHEMA.to_owned(), object_name.to_string())),
+        [schema_name, object_name] => Ok((schema_name.to_string(), object_name.to_string())),
+        _ => Err(QueryError::not_supported_operation(name.to_string())),
+    }
+}
+
+pub(crate) fn lock_resources<P: BackendStorage>(
+    storage: &FrontendStorage<P>,
+    session_id: SessionId,
+    resources: Vec<(Resource, LockMode)>,
+    nowait: bool,
+    duration: LockDuration,
+) -> Result<(), QueryError> {
+    for (resource, mode) in resources {
+        let relation_name = match &resource {
+            Resource::Schema(schema_name) => schema_name.clone(),
+            Resource::Table(_, table_name) | Resource::Rows(_, table_name) => table_name.clone(),
+        };
+        let locked = if nowait {
+            storage.lock_manager().try_lock(session_id, resource, mode, duration)
+        } else {
+            storage.lock_manager().lock(session_id, resource, mode, duration)
+        };
+        match locked {
+            Ok(()) => {}
+            Err(LockError::Deadlock) => return Err(QueryError::deadlock_detected()),
+            Err(LockError::NotAvailable) => return Err(QueryError::lock_not_available(relation_name)),
+            Err(LockError::Timeout) => return Err(QueryError::lock_timeout()),
+        }
+    }
+    Ok(())
+}
+
+/// Syntax error of the query with the message of the parser and position of
+/// the character that caused it
+pub(crate) fn syntax_error(error: ParserError, position: Option<usize>) -> QueryError {
+    let message = match error {
+        ParserError::TokenizerError(message) | ParserError::ParserError(message) => message,
+    };
+    match position {
+        Some(position) => QueryError::syntax_error(message).with_position(position as u32),
+        None => QueryError::syntax_error(message),
+    }
+}
+
+/// Table does not exist error of the qualified table name that is related
+/// to the schema and the table
+pub(crate) fn table_does_not_exist(schema_name: &str, table_name: &str) -> QueryError {
+    QueryError::table_does_not_exist(format!("{}.{}", schema_name, table_name))
+        .with_schema(schema_name.to_owned())
+        .with_table(table_name.to_owned())
+}
+
+// `lock_timeout` is set either in milliseconds or with a time unit,
+// zero or `DEFAULT` disables timeout
+fn lock_timeout(value: &str) -> Option<Option<Duration>> {
+    let value = value.trim();
+    if value.to_lowercase() == "default" {
+        return Some(None);
+    }
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let amount = value[..split].parse::<u64>().ok()?;
+    let millis = match value[split..].trim() {
+        "" | "ms" => amount,
+        "s" => amount * 1000,
+        "min" => amount * 60 * 1000,
+        _ => return None,
+    };
+    if millis == 0 {
+        Some(None)
+    } else {
+        Some(Some(Duration::from_millis(millis)))
+    }
+}
+
+// only ISO output style is supported, input order of day, month and year is
+// remembered to be shown back
+fn date_order(value: &str) -> Option<&'static str> {
+    let mut order = None;
+    for part in value.split(',').map(|part| part.trim().to_lowercase()) {
+        match part.as_str() {
+            "iso" => {}
+            "mdy" | "us" | "noneuropean" => order = Some("MDY"),
+            "dmy" | "european" => order = Some("DMY"),
+            "ymd" => order = Some("YMD"),
+            _ => return None,
+        }
+    }
+    Some(order.unwrap_or("MDY"))
 }
 
 #[cfg(test)]
@@ -0,0 +1,1561 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Extends `sqlparser` with PostgreSQL statements and clauses that it does not support
+use sqlparser::{
+    ast::{DataType, Expr, Ident, ObjectName, Query, SetVariableValue, Statement, TransactionMode, Value},
+    dialect::{keywords::Keyword, PostgreSqlDialect},
+    parser::{Parser, ParserError},
+    tokenizer::{Token, Tokenizer, Whitespace},
+};
+use storage::lock::LockMode;
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum ExtendedStatement {
+    Standard(Statement),
+    /// SET SESSION CHARACTERISTICS AS TRANSACTION transaction_mode [, ...]
+    SetSessionTransaction {
+        modes: Vec<TransactionMode>,
+    },
+    /// LOCK [ TABLE ] [ ONLY ] name [, ...] [ IN lockmode MODE ] [ NOWAIT ]
+    Lock {
+        tables: Vec<ObjectName>,
+        mode: LockMode,
+        nowait: bool,
+    },
+    /// SELECT ... FOR { UPDATE | NO KEY UPDATE | SHARE | KEY SHARE } [ OF table_name [, ...] ] [ NOWAIT ]
+    ///
+    /// There are no row level locks, so whole table is locked in a mode
+    /// that conflicts with the same set of locking clauses as PostgreSQL row locks do
+    LockingQuery {
+        query: Box<Query>,
+        mode: LockMode,
+        nowait: bool,
+    },
+    /// INSERT INTO table_name [ ( column_name [, ...] ) ] OVERRIDING SYSTEM VALUE query
+    ///
+    /// Explicit values are inserted into `GENERATED ALWAYS` identity columns
+    InsertOverridingSystemValue(Statement),
+    /// CREATE TYPE name AS ENUM ( [ 'label' [, ... ] ] )
+    CreateEnumType {
+        name: ObjectName,
+        labels: Vec<String>,
+    },
+    /// DROP TYPE [ IF EXISTS ] name [, ...] [ RESTRICT ]
+    DropType {
+        names: Vec<ObjectName>,
+        if_exists: bool,
+    },
+    /// CREATE SEQUENCE [ IF NOT EXISTS ] name [ INCREMENT [ BY ] increment ]
+    ///     [ MINVALUE minvalue | NO MINVALUE ] [ MAXVALUE maxvalue | NO MAXVALUE ]
+    ///     [ START [ WITH ] start ] [ NO CYCLE ]
+    CreateSequence {
+        name: ObjectName,
+        if_not_exists: bool,
+        parameters: SequenceParameters,
+    },
+    /// DROP SEQUENCE [ IF EXISTS ] name [, ...] [ RESTRICT ]
+    DropSequence {
+        names: Vec<ObjectName>,
+        if_exists: bool,
+    },
+    /// PREPARE name [ ( data_type [, ...] ) ] AS statement
+    ///
+    /// Statement is kept as tokens with `$n` parameters to be prepared the
+    /// same way as statement of extended query
+    Prepare {
+        name: Ident,
+        param_types: Vec<DataType>,
+        statement: Vec<Token>,
+    },
+    /// EXECUTE name [ ( parameter [, ...] ) ]
+    Execute {
+        name: Ident,
+        parameters: Vec<Expr>,
+    },
+    /// DEALLOCATE [ PREPARE ] { name | ALL }, `None` name is ALL
+    Deallocate {
+        name: Option<Ident>,
+    },
+    /// Statement of extended query without tokens
+    Empty,
+}
+
+/// Options of a sequence that are not given are defined by its data type and
+/// direction
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct SequenceParameters {
+    pub(crate) increment: Option<i64>,
+    pub(crate) min_value: Option<i64>,
+    pub(crate) max_value: Option<i64>,
+    pub(crate) start: Option<i64>,
+}
+
+pub(crate) fn parse(raw_sql_query: &str) -> Result<Vec<ExtendedStatement>, ParserError> {
+    tokenize(raw_sql_query)?.into_iter().map(parse_statement).collect()
+}
+
+/// Splits query into tokens of its statements
+pub(crate) fn tokenize(raw_sql_query: &str) -> Result<Vec<Vec<Token>>, ParserError> {
+    let tokens = Tokenizer::new(&PostgreSqlDialect {}, raw_sql_query).tokenize()?;
+    Ok(tokens
+        .split(|token| *token == Token::SemiColon)
+        .filter(|tokens| tokens.iter().any(|token| !matches!(token, Token::Whitespace(_))))
+        .map(<[Token]>::to_vec)
+        .collect())
+}
+
+/// Numbers of `$n` parameters in the order they are used in statement tokens
+pub(crate) fn parameters(tokens: &[Token]) -> Vec<usize> {
+    tokens
+        .windows(2)
+        .filter_map(|pair| parameter(&pair[0], &pair[1]))
+        .collect()
+}
+
+/// Replaces `$n` parameters in statement tokens with tokens that are given
+/// for the parameter number
+pub(crate) fn bind_parameters(tokens: &[Token], value: &mut dyn FnMut(usize) -> Vec<Token>) -> Vec<Token> {
+    let mut bound = vec![];
+    let mut index = 0;
+    while index < tokens.len() {
+        match tokens.get(index + 1).and_then(|next| parameter(&tokens[index], next)) {
+            Some(number) => {
+                bound.extend(value(number));
+                index += 2;
+            }
+            None => {
+                bound.push(tokens[index].clone());
+                index += 1;
+            }
+        }
+    }
+    bound
+}
+
+/// Replaces `$n` parameters in statement tokens with `$n` identifiers that
+/// are bound when the statement is executed
+pub(crate) fn parameters_as_identifiers(tokens: &[Token]) -> Vec<Token> {
+    bind_parameters(tokens, &mut |number| {
+        vec![Token::make_word(&format!("${}", number), None)]
+    })
+}
+
+// `sqlparser` tokenizes `$1` as `$` character followed by number
+fn parameter(dollar: &Token, number: &Token) -> Option<usize> {
+    match (dollar, number) {
+        (Token::Char('$'), Token::Number(number)) => number.parse().ok().filter(|number| *number > 0),
+        _ => None,
+    }
+}
+
+/// Position of the character that caused the syntax error of the query,
+/// counting from 1, errors at the end of a statement are positioned after
+/// its last character
+pub(crate) fn error_position(raw_sql_query: &str, error: &ParserError) -> Option<usize> {
+    position_of_error(raw_sql_query, error, parse_statement)
+}
+
+/// Position of the syntax error of the extended query statement, that has
+/// `$n` parameters which are parsed as identifiers
+pub(crate) fn extended_error_position(raw_sql_query: &str, error: &ParserError) -> Option<usize> {
+    position_of_error(raw_sql_query, error, |tokens| {
+        parse_statement(parameters_as_identifiers(&tokens))
+    })
+}
+
+fn position_of_error(
+    raw_sql_query: &str,
+    error: &ParserError,
+    parse: impl Fn(Vec<Token>) -> Result<ExtendedStatement, ParserError>,
+) -> Option<usize> {
+    let offsets = token_offsets(raw_sql_query);
+    let position = |index: usize| Some(raw_sql_query[..*offsets.get(index)?].chars().count() + 1);
+    if let ParserError::TokenizerError(_) = error {
+        // text of the token that the tokenizer failed on can't be tokenized on its own
+        return position(offsets.windows(2).position(|token| {
+            Tokenizer::new(&PostgreSqlDialect {}, &raw_sql_query[token[0]..token[1]])
+                .tokenize()
+                .is_err()
+        })?);
+    }
+    let tokens = Tokenizer::new(&PostgreSqlDialect {}, raw_sql_query).tokenize().ok()?;
+    let at_end = matches!(error, ParserError::ParserError(message) if message.ends_with("EOF"));
+    let mut start = 0;
+    for statement in tokens.split(|token| *token == Token::SemiColon) {
+        let significant = (0..statement.len())
+            .filter(|index| !matches!(statement[*index], Token::Whitespace(_)))
+            .collect::<Vec<usize>>();
+        if !significant.is_empty() && parse(statement.to_vec()).is_err() {
+            if at_end {
+                return position(start + statement.len());
+            }
+            // statement prefixes fail with the same error starting from the
+            // token that caused it, so the token is searched with bisection
+            let failed =
+                significant.partition_point(|index| parse(statement[..=*index].to_vec()).as_ref().err() != Some(error));
+            return match significant.get(failed) {
+                Some(index) => position(start + index),
+                None => position(start + statement.len()),
+            };
+        }
+        start += statement.len() + 1;
+    }
+    None
+}
+
+// `sqlparser` does not keep positions of tokens, thus byte offsets of tokens
+// are found with the same lexical rules that `sqlparser` tokenizer has, in a
+// single pass over the query. The last offset is the end of the query
+fn token_offsets(raw_sql_query: &str) -> Vec<usize> {
+    let mut offsets = vec![];
+    let mut chars = raw_sql_query.char_indices().peekable();
+    while let Some((offset, c)) = chars.next() {
+        offsets.push(offset);
+        match (c, chars.peek().map(|(_, next)| *next)) {
+            ('\r', Some('\n'))
+            | ('|', Some('|'))
+            | ('!', Some('='))
+            | ('<', Some('='))
+            | ('<', Some('>'))
+            | ('>', Some('='))
+            | (':', Some(':')) => {
+                chars.next();
+            }
+            ('N', Some('\'')) | ('x', Some('\'')) | ('X', Some('\'')) | ('\'', _) => {
+                if c != '\'' {
+                    chars.next();
+                }
+                // quotes are escaped by doubling them
+                while let Some((_, c)) = chars.next() {
+                    if c == '\'' && chars.next_if(|(_, next)| *next == '\'').is_none() {
+                        break;
+                    }
+                }
+            }
+            ('"', _) => while chars.next().filter(|(_, c)| *c != '"').is_some() {},
+            ('-', Some('-')) => while chars.next().filter(|(_, c)| *c != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut star = false;
+                for (_, c) in chars.by_ref() {
+                    if star && c == '/' {
+                        break;
+                    }
+                    star = c == '*';
+                }
+            }
+            ('a'..='z', _) | ('A'..='Z', _) | ('_', _) => {
+                while chars
+                    .next_if(|(_, next)| next.is_ascii_alphanumeric() || *next == '$' || *next == '_')
+                    .is_some()
+                {}
+            }
+            ('0'..='9', _) => {
+                while chars
+                    .next_if(|(_, next)| next.is_ascii_digit() || *next == '.')
+                    .is_some()
+                {}
+            }
+            _ => {}
+        }
+    }
+    offsets.push(raw_sql_query.len());
+    offsets
+}
+
+/// Parses expression that is kept as text, e.g. column default
+pub(crate) fn parse_expr(raw_expr: &str) -> Result<Expr, ParserError> {
+    let tokens = Tokenizer::new(&PostgreSqlDialect {}, raw_expr).tokenize()?;
+    let mut parser = Parser::new(rewrite(tokens));
+    let expr = parser.parse_expr()?;
+    expect_end(&parser)?;
+    Ok(expr)
+}
+
+/// Parses sequence options that are kept as text, e.g. options of identity
+/// column, see `rewrite_identity_columns`
+pub(crate) fn parse_sequence_parameters(raw_parameters: &str) -> Result<SequenceParameters, ParserError> {
+    let tokens = Tokenizer::new(&PostgreSqlDialect {}, raw_parameters).tokenize()?;
+    let mut parser = Parser::new(tokens);
+    let parameters = sequence_parameters(&mut parser)?;
+    expect_end(&parser)?;
+    Ok(parameters)
+}
+
+/// Parses type name that is kept as text, e.g. element type of array
+pub(crate) fn parse_data_type(raw_data_type: &str) -> Result<DataType, ParserError> {
+    let tokens = Tokenizer::new(&PostgreSqlDialect {}, raw_data_type).tokenize()?;
+    let mut parser = Parser::new(rewrite(tokens));
+    let data_type = parser.parse_data_type()?;
+    expect_end(&parser)?;
+    Ok(data_type)
+}
+
+/// Parses tokens of a single statement
+pub(crate) fn parse_statement(tokens: Vec<Token>) -> Result<ExtendedStatement, ParserError> {
+    if let Some(statement) = parse_prepare(&tokens)? {
+        return Ok(statement);
+    }
+    let tokens = rewrite(tokens);
+    let mut parser = Parser::new(tokens.clone());
+    if parse_word(&mut parser, "LOCK") {
+        return parse_lock(&mut parser);
+    }
+    if let Some(statement) = parse_execute_or_deallocate(tokens.clone())? {
+        return Ok(statement);
+    }
+    if let Some(statement) = parse_set_time_zone(tokens.clone())? {
+        return Ok(statement);
+    }
+    if let Some(statement) = parse_type_statement(tokens.clone())? {
+        return Ok(statement);
+    }
+    if let Some(statement) = parse_sequence_statement(tokens.clone())? {
+        return Ok(statement);
+    }
+    if let Some(statement) = parse_set_transaction(tokens.clone())? {
+        return Ok(statement);
+    }
+    if let Some(statement) = parse_insert_overriding_system_value(&tokens)? {
+        return Ok(statement);
+    }
+    let statement = match locking_clause_start(&tokens) {
+        Some(index) => {
+            let query = Parser::new(tokens[..index].to_vec()).parse_query()?;
+            let mut parser = Parser::new(tokens[index..].to_vec());
+            parser.expect_keyword(Keyword::FOR)?;
+            let mode = parse_row_lock_strength(&mut parser)?;
+            if parser.parse_keyword(Keyword::OF) {
+                parser.parse_comma_separated(Parser::parse_object_name)?;
+            }
+            let nowait = parse_word(&mut parser, "NOWAIT");
+            expect_end(&parser)?;
+            return Ok(ExtendedStatement::LockingQuery {
+                query: Box::new(query),
+                mode,
+                nowait,
+            });
+        }
+        None => parser.parse_statement()?,
+    };
+    expect_end(&parser)?;
+    Ok(ExtendedStatement::Standard(statement))
+}
+
+fn parse_lock(parser: &mut Parser) -> Result<ExtendedStatement, ParserError> {
+    let _ = parser.parse_keyword(Keyword::TABLE);
+    let _ = parser.parse_keyword(Keyword::ONLY);
+    let tables = parser.parse_comma_separated(Parser::parse_object_name)?;
+    let mode = if parser.parse_keyword(Keyword::IN) {
+        let mode = parse_lock_mode(parser)?;
+        if !parse_word(parser, "MODE") {
+            return expected("MODE", parser.peek_token());
+        }
+        mode
+    } else {
+        LockMode::AccessExclusive
+    };
+    let nowait = parse_word(parser, "NOWAIT");
+    expect_end(parser)?;
+    Ok(ExtendedStatement::Lock { tables, mode, nowait })
+}
+
+// `sqlparser` recognizes only upper case `SET TRANSACTION`
+fn parse_set_transaction(tokens: Vec<Token>) -> Result<Option<ExtendedStatement>, ParserError> {
+    let mut parser = Parser::new(tokens);
+    if !parser.parse_keyword(Keyword::SET) {
+        return Ok(None);
+    }
+    let statement = if parser.parse_keyword(Keyword::TRANSACTION) {
+        ExtendedStatement::Standard(Statement::SetTransaction {
+            modes: parser.parse_transaction_modes()?,
+        })
+    } else if parser.parse_keyword(Keyword::SESSION) && parse_word(&mut parser, "CHARACTERISTICS") {
+        parser.expect_keywords(&[Keyword::AS, Keyword::TRANSACTION])?;
+        ExtendedStatement::SetSessionTransaction {
+            modes: parser.parse_transaction_modes()?,
+        }
+    } else {
+        return Ok(None);
+    };
+    expect_end(&parser)?;
+    Ok(Some(statement))
+}
+
+/// SET [ SESSION | LOCAL ] TIME ZONE { value | LOCAL | DEFAULT }
+///
+/// is the same as setting `timezone` variable
+fn parse_set_time_zone(tokens: Vec<Token>) -> Result<Option<ExtendedStatement>, ParserError> {
+    let mut parser = Parser::new(tokens);
+    if !parser.parse_keyword(Keyword::SET) {
+        return Ok(None);
+    }
+    let local = parser.parse_one_of_keywords(&[Keyword::SESSION, Keyword::LOCAL]) == Some(Keyword::LOCAL);
+    if !parser.parse_keywords(&[Keyword::TIME, Keyword::ZONE]) {
+        return Ok(None);
+    }
+    let value = match parser.next_token() {
+        Token::SingleQuotedString(value) => SetVariableValue::Literal(Value::SingleQuotedString(value)),
+        Token::Number(value) => SetVariableValue::Literal(Value::Number(value)),
+        Token::Minus => match parser.next_token() {
+            Token::Number(value) => SetVariableValue::Literal(Value::Number("-".to_owned() + value.as_str())),
+            unexpected => return expected("number", unexpected),
+        },
+        Token::Word(word) => SetVariableValue::Ident(word.to_ident()),
+        unexpected => return expected("time zone", unexpected),
+    };
+    expect_end(&parser)?;
+    Ok(Some(ExtendedStatement::Standard(Statement::SetVariable {
+        local,
+        variable: Ident::new("timezone"),
+        value,
+    })))
+}
+
+fn rewrite(tokens: Vec<Token>) -> Vec<Token> {
+    rewrite_identity_columns(rewrite_json_operators(rewrite_subscripts(rewrite_array_types(
+        rewrite_array_constructors(rewrite_time_zone_types(tokens)),
+    ))))
+}
+
+// `sqlparser` does not know `OVERRIDING SYSTEM VALUE` clause of INSERT, so it
+// is removed and the rest is parsed as INSERT statement
+fn parse_insert_overriding_system_value(tokens: &[Token]) -> Result<Option<ExtendedStatement>, ParserError> {
+    let is_word = |index: Option<usize>, expected: &str| match index.map(|index| &tokens[index]) {
+        Some(Token::Word(word)) => word.quote_style.is_none() && word.value.to_uppercase() == expected,
+        _ => false,
+    };
+    if !is_word(next_significant(tokens, 0), "INSERT") {
+        return Ok(None);
+    }
+    let overriding = (0..tokens.len()).find(|index| {
+        let system = next_significant(tokens, index + 1);
+        is_word(Some(*index), "OVERRIDING")
+            && is_word(system, "SYSTEM")
+            && is_word(system.and_then(|system| next_significant(tokens, system + 1)), "VALUE")
+    });
+    let overriding = match overriding {
+        Some(overriding) => overriding,
+        None => return Ok(None),
+    };
+    let system = next_significant(tokens, overriding + 1).unwrap();
+    let value = next_significant(tokens, system + 1).unwrap();
+    let mut parser = Parser::new([&tokens[..overriding], &tokens[value + 1..]].concat());
+    let statement = parser.parse_statement()?;
+    expect_end(&parser)?;
+    Ok(Some(ExtendedStatement::InsertOverridingSystemValue(statement)))
+}
+
+// `sqlparser` does not know `CREATE TYPE` and `DROP TYPE` statements
+fn parse_type_statement(tokens: Vec<Token>) -> Result<Option<ExtendedStatement>, ParserError> {
+    let mut parser = Parser::new(tokens);
+    let statement = if parser.parse_keyword(Keyword::CREATE) && parse_word(&mut parser, "TYPE") {
+        let name = parser.parse_object_name()?;
+        parser.expect_keyword(Keyword::AS)?;
+        if !parse_word(&mut parser, "ENUM") {
+            return expected("ENUM", parser.peek_token());
+        }
+        parser.expect_token(&Token::LParen)?;
+        let labels = if parser.consume_token(&Token::RParen) {
+            vec![]
+        } else {
+            let labels = parser.parse_comma_separated(Parser::parse_literal_string)?;
+            parser.expect_token(&Token::RParen)?;
+            labels
+        };
+        ExtendedStatement::CreateEnumType { name, labels }
+    } else if parser.parse_keyword(Keyword::DROP) && parse_word(&mut parser, "TYPE") {
+        let if_exists = parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+        let names = parser.parse_comma_separated(Parser::parse_object_name)?;
+        let _ = parser.parse_keyword(Keyword::RESTRICT);
+        ExtendedStatement::DropType { names, if_exists }
+    } else {
+        return Ok(None);
+    };
+    expect_end(&parser)?;
+    Ok(Some(statement))
+}
+
+// `sqlparser` does not know `CREATE SEQUENCE` and `DROP SEQUENCE` statements
+fn parse_sequence_statement(tokens: Vec<Token>) -> Result<Option<ExtendedStatement>, ParserError> {
+    let mut parser = Parser::new(tokens);
+    let statement = if parser.parse_keyword(Keyword::CREATE) && parse_word(&mut parser, "SEQUENCE") {
+        let if_not_exists = parser.parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+        let name = parser.parse_object_name()?;
+        let parameters = sequence_parameters(&mut parser)?;
+        ExtendedStatement::CreateSequence {
+            name,
+            if_not_exists,
+            parameters,
+        }
+    } else if parser.parse_keyword(Keyword::DROP) && parse_word(&mut parser, "SEQUENCE") {
+        let if_exists = parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+        let names = parser.parse_comma_separated(Parser::parse_object_name)?;
+        let _ = parser.parse_keyword(Keyword::RESTRICT);
+        ExtendedStatement::DropSequence { names, if_exists }
+    } else {
+        return Ok(None);
+    };
+    expect_end(&parser)?;
+    Ok(Some(statement))
+}
+
+// prepared statement is not rewritten, it is parsed to report syntax errors
+// of the query and parsed again when it is prepared
+fn parse_prepare(tokens: &[Token]) -> Result<Option<ExtendedStatement>, ParserError> {
+    if !parse_word(&mut Parser::new(tokens.to_vec()), "PREPARE") {
+        return Ok(None);
+    }
+    let as_index = match tokens.iter().position(|token| match token {
+        Token::Word(word) => word.keyword == Keyword::AS && word.quote_style.is_none(),
+        _ => false,
+    }) {
+        Some(index) => index,
+        None => return expected("AS", Token::EOF),
+    };
+    let mut parser = Parser::new(rewrite(tokens[..as_index].to_vec()));
+    parse_word(&mut parser, "PREPARE");
+    let name = parser.parse_identifier()?;
+    let param_types = if parser.consume_token(&Token::LParen) {
+        let param_types = parser.parse_comma_separated(Parser::parse_data_type)?;
+        parser.expect_token(&Token::RParen)?;
+        param_types
+    } else {
+        vec![]
+    };
+    expect_end(&parser)?;
+    let statement = tokens[as_index + 1..].to_vec();
+    if statement.iter().all(|token| matches!(token, Token::Whitespace(_))) {
+        return expected("statement", Token::EOF);
+    }
+    parse_statement(parameters_as_identifiers(&statement))?;
+    Ok(Some(ExtendedStatement::Prepare {
+        name,
+        param_types,
+        statement,
+    }))
+}
+
+// `sqlparser` does not know `EXECUTE` and `DEALLOCATE` statements
+fn parse_execute_or_deallocate(tokens: Vec<Token>) -> Result<Option<ExtendedStatement>, ParserError> {
+    let mut parser = Parser::new(tokens);
+    let statement = if parser.parse_keyword(Keyword::EXECUTE) {
+        let name = parser.parse_identifier()?;
+        let parameters = if parser.consume_token(&Token::LParen) {
+            let parameters = parser.parse_comma_separated(Parser::parse_expr)?;
+            parser.expect_token(&Token::RParen)?;
+            parameters
+        } else {
+            vec![]
+        };
+        ExtendedStatement::Execute { name, parameters }
+    } else if parser.parse_keyword(Keyword::DEALLOCATE) {
+        let _ = parser.parse_keyword(Keyword::PREPARE);
+        let name = if parser.parse_keyword(Keyword::ALL) {
+            None
+        } else {
+            Some(parser.parse_identifier()?)
+        };
+        ExtendedStatement::Deallocate { name }
+    } else {
+        return Ok(None);
+    };
+    expect_end(&parser)?;
+    Ok(Some(statement))
+}
+
+fn sequence_parameters(parser: &mut Parser) -> Result<SequenceParameters, ParserError> {
+    let mut parameters = SequenceParameters::default();
+    loop {
+        if parse_word(parser, "INCREMENT") {
+            parse_word(parser, "BY");
+            parameters.increment = Some(parse_integer(parser)?);
+        } else if parse_word(parser, "MINVALUE") {
+            parameters.min_value = Some(parse_integer(parser)?);
+        } else if parse_word(parser, "MAXVALUE") {
+            parameters.max_value = Some(parse_integer(parser)?);
+        } else if parse_word(parser, "START") {
+            parse_word(parser, "WITH");
+            parameters.start = Some(parse_integer(parser)?);
+        } else if parse_word(parser, "NO") {
+            if parse_word(parser, "MINVALUE") {
+                parameters.min_value = None;
+            } else if parse_word(parser, "MAXVALUE") {
+                parameters.max_value = None;
+            } else if !parse_word(parser, "CYCLE") {
+                return expected("MINVALUE, MAXVALUE or CYCLE", parser.peek_token());
+            }
+        } else {
+            return Ok(parameters);
+        }
+    }
+}
+
+fn parse_integer(parser: &mut Parser) -> Result<i64, ParserError> {
+    let sign = if parser.consume_token(&Token::Minus) { "-" } else { "" };
+    match parser.next_token() {
+        Token::Number(number) => match format!("{}{}", sign, number).parse() {
+            Ok(value) => Ok(value),
+            Err(_) => expected("integer", Token::Number(number)),
+        },
+        unexpected => expected("integer", unexpected),
+    }
+}
+
+// `sqlparser` does not know `GENERATED { ALWAYS | BY DEFAULT } AS IDENTITY [ ( sequence_option ... ) ]`
+// column constraint, so it is replaced with `DEFAULT "identity"('sequence_option ...')`
+// that the engine replaces with a sequence, `ALWAYS` is kept as the second
+// argument: `DEFAULT "identity"('sequence_option ...', 'always')`
+fn rewrite_identity_columns(tokens: Vec<Token>) -> Vec<Token> {
+    let is_word = |index: Option<usize>, expected: &str| match index.map(|index| &tokens[index]) {
+        Some(Token::Word(word)) => word.quote_style.is_none() && word.value.to_uppercase() == expected,
+        _ => false,
+    };
+    let mut rewritten = vec![];
+    let mut index = 0;
+    while index < tokens.len() {
+        if !is_word(Some(index), "GENERATED") {
+            rewritten.push(tokens[index].clone());
+            index += 1;
+            continue;
+        }
+        let mut next = next_significant(&tokens, index + 1);
+        let always = is_word(next, "ALWAYS");
+        if is_word(next, "BY") && is_word(next.and_then(|by| next_significant(&tokens, by + 1)), "DEFAULT") {
+            next = next.and_then(|by| next_significant(&tokens, by + 1));
+        } else if !is_word(next, "ALWAYS") {
+            rewritten.push(tokens[index].clone());
+            index += 1;
+            continue;
+        }
+        let as_ = next.and_then(|next| next_significant(&tokens, next + 1));
+        let identity = as_.and_then(|as_| next_significant(&tokens, as_ + 1));
+        if !is_word(as_, "AS") || !is_word(identity, "IDENTITY") {
+            rewritten.push(tokens[index].clone());
+            index += 1;
+            continue;
+        }
+        let mut end = identity.unwrap() + 1;
+        let parameters = match next_significant(&tokens, end) {
+            Some(open) if tokens[open] == Token::LParen => match matching_bracket(&tokens, open) {
+                Some(close) => {
+                    end = close + 1;
+                    tokens[open + 1..close].iter().map(ToString::to_string).collect()
+                }
+                None => String::new(),
+            },
+            _ => String::new(),
+        };
+        rewritten.push(Token::make_keyword("DEFAULT"));
+        rewritten.push(Token::Whitespace(Whitespace::Space));
+        rewritten.push(Token::make_word("identity", Some('"')));
+        rewritten.push(Token::LParen);
+        rewritten.push(Token::SingleQuotedString(parameters));
+        if always {
+            rewritten.push(Token::Comma);
+            rewritten.push(Token::SingleQuotedString("always".to_owned()));
+        }
+        rewritten.push(Token::RParen);
+        index = end;
+    }
+    rewritten
+}
+
+// `sqlparser` drops `WITH TIME ZONE` of TIME and TIMESTAMP types, so they are
+// replaced with `timetz` and `timestamptz` that it parses as custom types
+fn rewrite_time_zone_types(tokens: Vec<Token>) -> Vec<Token> {
+    let is_word = |token: &Token, expected: Keyword| match token {
+        Token::Word(word) => word.keyword == expected && word.quote_style.is_none(),
+        _ => false,
+    };
+    let mut rewritten = vec![];
+    let mut index = 0;
+    while index < tokens.len() {
+        let significant = tokens[index..]
+            .iter()
+            .enumerate()
+            .filter(|(_, token)| !matches!(token, Token::Whitespace(_)))
+            .take(4)
+            .collect::<Vec<(usize, &Token)>>();
+        let replacement = match significant.as_slice() {
+            [(_, data_type), (_, with), (_, time), (_, zone)]
+                if (is_word(with, Keyword::WITH) || is_word(with, Keyword::WITHOUT))
+                    && is_word(time, Keyword::TIME)
+                    && is_word(zone, Keyword::ZONE) =>
+            {
+                let with = is_word(with, Keyword::WITH);
+                if is_word(data_type, Keyword::TIMESTAMP) {
+                    Some(if with { "timestamptz" } else { "timestamp" })
+                } else if is_word(data_type, Keyword::TIME) {
+                    Some(if with { "timetz" } else { "time" })
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+        match replacement {
+            Some(word) => {
+                rewritten.extend(tokens[index..index + significant[0].0].iter().cloned());
+                rewritten.push(Token::make_word(word, None));
+                index += significant[3].0 + 1;
+            }
+            None => {
+                rewritten.push(tokens[index].clone());
+                index += 1;
+            }
+        }
+    }
+    rewritten
+}
+
+// `ARRAY[1, 2]` is replaced with `ARRAY(1, 2)` that `sqlparser` parses as
+// function call
+fn rewrite_array_constructors(mut tokens: Vec<Token>) -> Vec<Token> {
+    for index in 0..tokens.len() {
+        match &tokens[index] {
+            Token::Word(word) if word.keyword == Keyword::ARRAY && word.quote_style.is_none() => {}
+            _ => continue,
+        }
+        let open = match next_significant(&tokens, index + 1) {
+            Some(open) if tokens[open] == Token::LBracket => open,
+            _ => continue,
+        };
+        if let Some(close) = matching_bracket(&tokens, open) {
+            tokens[open] = Token::LParen;
+            tokens[close] = Token::RParen;
+        }
+    }
+    tokens
+}
+
+// `sqlparser` knows only `text[]` array type, so type name followed by `[]`
+// is replaced with custom type that is named as the whole array type, e.g.
+// `"varchar(10)[]"`
+fn rewrite_array_types(mut tokens: Vec<Token>) -> Vec<Token> {
+    let mut index = 0;
+    while index < tokens.len() {
+        let close = next_significant(&tokens, index + 1);
+        let start = match close {
+            Some(close) if tokens[index] == Token::LBracket && tokens[close] == Token::RBracket => {
+                type_start(&tokens, index)
+            }
+            _ => None,
+        };
+        match (start, close) {
+            (Some(start), Some(close)) => {
+                let type_name = tokens[start..index]
+                    .iter()
+                    .map(Token::to_string)
+                    .collect::<String>()
+                    .trim_end()
+                    .to_owned()
+                    + "[]";
+                let mut rewritten = tokens[..start].to_vec();
+                rewritten.push(Token::make_word(&type_name, Some('"')));
+                rewritten.extend_from_slice(&tokens[close + 1..]);
+                tokens = rewritten;
+                index = start + 1;
+            }
+            _ => index += 1,
+        }
+    }
+    tokens
+}
+
+// type name with optional modifiers, e.g. `double precision` or
+// `character varying(10)`
+fn type_start(tokens: &[Token], before: usize) -> Option<usize> {
+    let mut start = previous_significant(tokens, before)?;
+    if tokens[start] == Token::RParen {
+        start = previous_significant(tokens, matching_bracket(tokens, start)?)?;
+    }
+    let word = match &tokens[start] {
+        Token::Word(word) => word,
+        _ => return None,
+    };
+    if word.quote_style.is_none() && ["PRECISION", "VARYING"].contains(&word.value.to_uppercase().as_str()) {
+        if let Some(first) =
+            previous_significant(tokens, start).filter(|first| matches!(tokens[*first], Token::Word(_)))
+        {
+            start = first;
+        }
+    }
+    Some(start)
+}
+
+// `sqlparser` does not know array subscripts, so `operand[index]` is replaced
+// with call of `"[]"(operand, index)` function
+fn rewrite_subscripts(mut tokens: Vec<Token>) -> Vec<Token> {
+    let mut index = 0;
+    while index < tokens.len() {
+        if tokens[index] != Token::LBracket {
+            index += 1;
+            continue;
+        }
+        match (operand_start(&tokens, index), matching_bracket(&tokens, index)) {
+            (Some(start), Some(close)) => {
+                let mut rewritten = tokens[..start].to_vec();
+                rewritten.push(Token::make_word("[]", Some('"')));
+                rewritten.push(Token::LParen);
+                rewritten.extend_from_slice(&tokens[start..index]);
+                rewritten.push(Token::Comma);
+                rewritten.extend_from_slice(&tokens[index + 1..close]);
+                rewritten.push(Token::RParen);
+                rewritten.extend_from_slice(&tokens[close + 1..]);
+                // subscripts of the index are rewritten as scanning goes on
+                index = start + 1;
+                tokens = rewritten;
+            }
+            _ => index += 1,
+        }
+    }
+    tokens
+}
+
+// `sqlparser` does not know JSON operators, so `left -> right` is replaced
+// with call of function that is named as the operator, `"->"(left, right)`.
+// Operands are literals, names, function calls and parenthesized expressions
+// with optional casts, thus JSON operators are left associative and bind
+// tighter than other operators.
+fn rewrite_json_operators(mut tokens: Vec<Token>) -> Vec<Token> {
+    let mut index = 0;
+    while index < tokens.len() {
+        let (operator, len) = match json_operator(&tokens[index..]) {
+            Some(operator) => operator,
+            None => {
+                index += 1;
+                continue;
+            }
+        };
+        match (operand_start(&tokens, index), operand_end(&tokens, index + len)) {
+            (Some(start), Some(end)) => {
+                let mut rewritten = tokens[..start].to_vec();
+                rewritten.push(Token::make_word(operator, Some('"')));
+                rewritten.push(Token::LParen);
+                rewritten.extend_from_slice(&tokens[start..index]);
+                rewritten.push(Token::Comma);
+                rewritten.extend_from_slice(&tokens[index + len..end]);
+                rewritten.push(Token::RParen);
+                rewritten.extend_from_slice(&tokens[end..]);
+                // function call becomes left operand of the next operator
+                index = end - len + 4;
+                tokens = rewritten;
+            }
+            _ => index += len,
+        }
+    }
+    tokens
+}
+
+fn json_operator(tokens: &[Token]) -> Option<(&'static str, usize)> {
+    match tokens {
+        [Token::Minus, Token::Gt, Token::Gt, ..] => Some(("->>", 3)),
+        [Token::Minus, Token::Gt, ..] => Some(("->", 2)),
+        [Token::Char('#'), Token::Gt, Token::Gt, ..] => Some(("#>>", 3)),
+        [Token::Char('#'), Token::Gt, ..] => Some(("#>", 2)),
+        [Token::Char('@'), Token::Gt, ..] => Some(("@>", 2)),
+        [Token::Char('?'), ..] => Some(("?", 1)),
+        _ => None,
+    }
+}
+
+fn operand_start(tokens: &[Token], operator: usize) -> Option<usize> {
+    let mut start = previous_significant(tokens, operator)?;
+    loop {
+        start = match &tokens[start] {
+            Token::RParen | Token::RBracket => {
+                let open = matching_bracket(tokens, start)?;
+                match previous_significant(tokens, open) {
+                    Some(name) if is_function_name(&tokens[name]) => name_start(tokens, name),
+                    _ => open,
+                }
+            }
+            Token::Word(_) => name_start(tokens, start),
+            token if is_literal(token) => start,
+            _ => return None,
+        };
+        match previous_significant(tokens, start) {
+            Some(cast) if tokens[cast] == Token::DoubleColon => start = previous_significant(tokens, cast)?,
+            _ => return Some(start),
+        }
+    }
+}
+
+fn operand_end(tokens: &[Token], operator_end: usize) -> Option<usize> {
+    let start = next_significant(tokens, operator_end)?;
+    let mut end = match &tokens[start] {
+        Token::Minus | Token::Plus => match next_significant(tokens, start + 1) {
+            Some(number) if matches!(tokens[number], Token::Number(_)) => number + 1,
+            _ => return None,
+        },
+        Token::LParen => matching_bracket(tokens, start)? + 1,
+        Token::Word(_) => {
+            let mut end = start;
+            while let (Some(period), Some(name)) =
+                (next_significant(tokens, end + 1), next_significant(tokens, end + 2))
+            {
+                if tokens[period] == Token::Period && matches!(tokens[name], Token::Word(_)) {
+                    end = name;
+                } else {
+                    break;
+                }
+            }
+            match next_significant(tokens, end + 1) {
+                Some(open) if tokens[open] == Token::LParen || tokens[open] == Token::LBracket => {
+                    matching_bracket(tokens, open)? + 1
+                }
+                _ => end + 1,
+            }
+        }
+        token if is_literal(token) => start + 1,
+        _ => return None,
+    };
+    while let Some(cast) = next_significant(tokens, end).filter(|cast| tokens[*cast] == Token::DoubleColon) {
+        let type_name = next_significant(tokens, cast + 1).filter(|name| matches!(tokens[*name], Token::Word(_)))?;
+        end = match next_significant(tokens, type_name + 1) {
+            Some(open) if tokens[open] == Token::LParen => matching_bracket(tokens, open)? + 1,
+            _ => type_name + 1,
+        };
+    }
+    Some(end)
+}
+
+// dotted name, e.g. `schema_name.function_name`
+fn name_start(tokens: &[Token], mut start: usize) -> usize {
+    while let Some(period) = previous_significant(tokens, start).filter(|period| tokens[*period] == Token::Period) {
+        match previous_significant(tokens, period) {
+            Some(name) if matches!(tokens[name], Token::Word(_)) => start = name,
+            _ => break,
+        }
+    }
+    start
+}
+
+fn is_function_name(token: &Token) -> bool {
+    match token {
+        Token::Word(word) => {
+            word.quote_style.is_some() || word.keyword == Keyword::NoKeyword || word.keyword == Keyword::ARRAY
+        }
+        _ => false,
+    }
+}
+
+fn is_literal(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Number(_) | Token::SingleQuotedString(_) | Token::NationalStringLiteral(_) | Token::HexStringLiteral(_)
+    )
+}
+
+fn previous_significant(tokens: &[Token], before: usize) -> Option<usize> {
+    (0..before)
+        .rev()
+        .find(|index| !matches!(tokens[*index], Token::Whitespace(_)))
+}
+
+fn next_significant(tokens: &[Token], from: usize) -> Option<usize> {
+    (from..tokens.len()).find(|index| !matches!(tokens[*index], Token::Whitespace(_)))
+}
+
+// index of closing bracket for opening one and vice versa
+fn matching_bracket(tokens: &[Token], index: usize) -> Option<usize> {
+    let (open, close, forward) = match tokens[index] {
+        Token::LParen => (Token::LParen, Token::RParen, true),
+        Token::RParen => (Token::RParen, Token::LParen, false),
+        Token::LBracket => (Token::LBracket, Token::RBracket, true),
+        Token::RBracket => (Token::RBracket, Token::LBracket, false),
+        _ => return None,
+    };
+    let indexes: Box<dyn Iterator<Item = usize>> = if forward {
+        Box::new(index..tokens.len())
+    } else {
+        Box::new((0..=index).rev())
+    };
+    let mut depth = 0;
+    for index in indexes {
+        if tokens[index] == open {
+            depth += 1;
+        } else if tokens[index] == close {
+            depth -= 1;
+            if depth == 0 {
+                return Some(index);
+            }
+        }
+    }
+    None
+}
+
+fn parse_lock_mode(parser: &mut Parser) -> Result<LockMode, ParserError> {
+    if parse_word(parser, "ACCESS") {
+        if parse_word(parser, "SHARE") {
+            Ok(LockMode::AccessShare)
+        } else if parse_word(parser, "EXCLUSIVE") {
+            Ok(LockMode::AccessExclusive)
+        } else {
+            expected("SHARE or EXCLUSIVE", parser.peek_token())
+        }
+    } else if parser.parse_keyword(Keyword::ROW) {
+        if parse_word(parser, "SHARE") {
+            Ok(LockMode::RowShare)
+        } else if parse_word(parser, "EXCLUSIVE") {
+            Ok(LockMode::RowExclusive)
+        } else {
+            expected("SHARE or EXCLUSIVE", parser.peek_token())
+        }
+    } else if parse_word(parser, "SHARE") {
+        if parser.parse_keyword(Keyword::UPDATE) {
+            if parse_word(parser, "EXCLUSIVE") {
+                Ok(LockMode::ShareUpdateExclusive)
+            } else {
+                expected("EXCLUSIVE", parser.peek_token())
+            }
+        } else if parser.parse_keyword(Keyword::ROW) {
+            if parse_word(parser, "EXCLUSIVE") {
+                Ok(LockMode::ShareRowExclusive)
+            } else {
+                expected("EXCLUSIVE", parser.peek_token())
+            }
+        } else {
+            Ok(LockMode::Share)
+        }
+    } else if parse_word(parser, "EXCLUSIVE") {
+        Ok(LockMode::Exclusive)
+    } else {
+        expected("lock mode", parser.peek_token())
+    }
+}
+
+fn parse_row_lock_strength(parser: &mut Parser) -> Result<LockMode, ParserError> {
+    if parser.parse_keyword(Keyword::UPDATE) {
+        Ok(LockMode::Exclusive)
+    } else if parser.parse_keywords(&[Keyword::NO, Keyword::KEY, Keyword::UPDATE]) {
+        Ok(LockMode::ShareRowExclusive)
+    } else if parse_word(parser, "SHARE") {
+        Ok(LockMode::Share)
+    } else if parser.parse_keyword(Keyword::KEY) {
+        if parse_word(parser, "SHARE") {
+            Ok(LockMode::RowShare)
+        } else {
+            expected("SHARE", parser.peek_token())
+        }
+    } else {
+        expected("UPDATE, NO KEY UPDATE, SHARE or KEY SHARE", parser.peek_token())
+    }
+}
+
+// `sqlparser` takes `FOR` after table name as its alias, so query tokens are
+// split before top level `FOR` that starts locking clause
+fn locking_clause_start(tokens: &[Token]) -> Option<usize> {
+    let mut depth = 0;
+    let significant = tokens
+        .iter()
+        .enumerate()
+        .filter(|(_, token)| !matches!(token, Token::Whitespace(_)))
+        .collect::<Vec<(usize, &Token)>>();
+    match significant.first() {
+        Some((_, Token::Word(word))) if [Keyword::SELECT, Keyword::WITH].contains(&word.keyword) => {}
+        Some((_, Token::LParen)) => {}
+        _ => return None,
+    }
+    for (position, (index, token)) in significant.iter().enumerate() {
+        match token {
+            Token::LParen => depth += 1,
+            Token::RParen => depth -= 1,
+            Token::Word(word) if depth == 0 && word.keyword == Keyword::FOR && word.quote_style.is_none() => {
+                match significant.get(position + 1) {
+                    Some((_, Token::Word(next)))
+                        if next.quote_style.is_none()
+                            && ["UPDATE", "NO", "SHARE", "KEY"].contains(&next.value.to_uppercase().as_str()) =>
+                    {
+                        return Some(*index)
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// words that are not keywords for `sqlparser` are compared by their value
+fn parse_word(parser: &mut Parser, expected: &str) -> bool {
+    match parser.peek_token() {
+        Token::Word(word) if word.quote_style.is_none() && word.value.to_uppercase() == expected => {
+            parser.next_token();
+            true
+        }
+        _ => false,
+    }
+}
+
+fn expect_end(parser: &Parser) -> Result<(), ParserError> {
+    match parser.peek_token() {
+        Token::EOF => Ok(()),
+        unexpected => expected("end of statement", unexpected),
+    }
+}
+
+fn expected<T>(expected: &str, found: Token) -> Result<T, ParserError> {
+    Err(ParserError::ParserError(format!(
+        "Expected {}, found: {}",
+        expected, found
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlparser::ast::{Ident, TransactionIsolationLevel};
+
+    fn table_name() -> ObjectName {
+        ObjectName(vec![Ident::new("schema_name"), Ident::new("table_name")])
+    }
+
+    #[rstest::rstest(
+        sql,
+        mode,
+        case::default_mode("lock table schema_name.table_name", LockMode::AccessExclusive),
+        case::access_share("lock schema_name.table_name in access share mode", LockMode::AccessShare),
+        case::row_share("lock table schema_name.table_name in row share mode", LockMode::RowShare),
+        case::row_exclusive("lock table schema_name.table_name in row exclusive mode", LockMode::RowExclusive),
+        case::share_update_exclusive(
+            "lock table schema_name.table_name in share update exclusive mode",
+            LockMode::ShareUpdateExclusive
+        ),
+        case::share("lock table schema_name.table_name in share mode", LockMode::Share),
+        case::share_row_exclusive(
+            "lock table schema_name.table_name in share row exclusive mode",
+            LockMode::ShareRowExclusive
+        ),
+        case::exclusive("lock table schema_name.table_name in exclusive mode", LockMode::Exclusive),
+        case::access_exclusive(
+            "lock table only schema_name.table_name in access exclusive mode",
+            LockMode::AccessExclusive
+        )
+    )]
+    fn lock_table(sql: &str, mode: LockMode) {
+        assert_eq!(
+            parse(sql),
+            Ok(vec![ExtendedStatement::Lock {
+                tables: vec![table_name()],
+                mode,
+                nowait: false
+            }])
+        );
+    }
+
+    #[rstest::rstest]
+    fn lock_table_nowait() {
+        assert_eq!(
+            parse("LOCK TABLE schema_name.table_name IN SHARE MODE NOWAIT;"),
+            Ok(vec![ExtendedStatement::Lock {
+                tables: vec![table_name()],
+                mode: LockMode::Share,
+                nowait: true
+            }])
+        );
+    }
+
+    #[rstest::rstest]
+    fn lock_table_with_unknown_mode() {
+        assert!(parse("lock table schema_name.table_name in some mode").is_err());
+    }
+
+    #[rstest::rstest(
+        sql,
+        mode,
+        case::update("select * from schema_name.table_name for update", LockMode::Exclusive),
+        case::no_key_update(
+            "select * from schema_name.table_name for no key update",
+            LockMode::ShareRowExclusive
+        ),
+        case::share("select * from schema_name.table_name for share", LockMode::Share),
+        case::key_share("select * from schema_name.table_name for key share", LockMode::RowShare)
+    )]
+    fn select_with_locking_clause(sql: &str, mode: LockMode) {
+        match parse(sql) {
+            Ok(statements) => match statements.as_slice() {
+                [ExtendedStatement::LockingQuery {
+                    mode: actual,
+                    nowait: false,
+                    ..
+                }] => assert_eq!(*actual, mode),
+                other => panic!("unexpected statements {:?}", other),
+            },
+            Err(error) => panic!("{:?}", error),
+        }
+    }
+
+    #[rstest::rstest]
+    fn select_for_update_of_table_nowait() {
+        match parse("select * from schema_name.table_name for update of schema_name.table_name nowait") {
+            Ok(statements) => match statements.as_slice() {
+                [ExtendedStatement::LockingQuery {
+                    mode: LockMode::Exclusive,
+                    nowait: true,
+                    ..
+                }] => {}
+                other => panic!("unexpected statements {:?}", other),
+            },
+            Err(error) => panic!("{:?}", error),
+        }
+    }
+
+    #[rstest::rstest]
+    fn set_transaction_in_lower_case() {
+        assert_eq!(
+            parse("set transaction isolation level serializable"),
+            Ok(vec![ExtendedStatement::Standard(Statement::SetTransaction {
+                modes: vec![TransactionMode::IsolationLevel(TransactionIsolationLevel::Serializable)]
+            })])
+        );
+    }
+
+    #[rstest::rstest]
+    fn set_session_characteristics() {
+        assert_eq!(
+            parse("SET SESSION CHARACTERISTICS AS TRANSACTION ISOLATION LEVEL REPEATABLE READ"),
+            Ok(vec![ExtendedStatement::SetSessionTransaction {
+                modes: vec![TransactionMode::IsolationLevel(
+                    TransactionIsolationLevel::RepeatableRead
+                )]
+            }])
+        );
+    }
+
+    #[rstest::rstest(
+        sql,
+        value,
+        case::string("SET TIME ZONE 'UTC'", SetVariableValue::Literal(Value::SingleQuotedString("UTC".to_owned()))),
+        case::negative_hours("set time zone -8", SetVariableValue::Literal(Value::Number("-8".to_owned()))),
+        case::session("SET SESSION TIME ZONE DEFAULT", SetVariableValue::Ident(Ident::new("DEFAULT")))
+    )]
+    fn set_time_zone(sql: &str, value: SetVariableValue) {
+        assert_eq!(
+            parse(sql),
+            Ok(vec![ExtendedStatement::Standard(Statement::SetVariable {
+                local: false,
+                variable: Ident::new("timezone"),
+                value
+            })])
+        );
+    }
+
+    #[rstest::rstest]
+    fn time_zone_aware_types() {
+        match parse(
+            "create table schema_name.table_name (\
+             col_1 timestamp with time zone, col_2 time with time zone, col_3 timestamp without time zone);",
+        ) {
+            Ok(statements) => match statements.as_slice() {
+                [ExtendedStatement::Standard(Statement::CreateTable { columns, .. })] => assert_eq!(
+                    columns
+                        .iter()
+                        .map(|column| column.data_type.to_string())
+                        .collect::<Vec<String>>(),
+                    vec!["timestamptz", "timetz", "TIMESTAMP"]
+                ),
+                other => panic!("unexpected statements {:?}", other),
+            },
+            Err(error) => panic!("{:?}", error),
+        }
+    }
+
+    #[rstest::rstest(
+        sql,
+        expected,
+        case::field("select data -> 'a'", r#"SELECT "->"(data, 'a')"#),
+        case::left_associative("select t.data -> 'a' ->> 0", r#"SELECT "->>"("->"(t.data, 'a'), 0)"#),
+        case::negative_index("select data->-1", r#"SELECT "->"(data, - 1)"#),
+        case::path(
+            "select data #> '{a,b}', data #>> '{a}'",
+            r##"SELECT "#>"(data, '{a,b}'), "#>>"(data, '{a}')"##
+        ),
+        case::containment(
+            "select jsonb_build_object('a', 1) @> '{}' = true",
+            r#"SELECT "@>"(jsonb_build_object('a', 1), '{}') = true"#
+        ),
+        case::key_exists("select ('{}') ? 'a'", r#"SELECT "?"(('{}'), 'a')"#),
+        case::casts(
+            "select '{}'::jsonb -> 'a'::text",
+            r#"SELECT "->"(CAST('{}' AS jsonb), CAST('a' AS TEXT))"#
+        )
+    )]
+    fn json_operators(sql: &str, expected: &str) {
+        match parse(sql) {
+            Ok(statements) => match statements.as_slice() {
+                [ExtendedStatement::Standard(statement)] => assert_eq!(statement.to_string(), expected),
+                other => panic!("unexpected statements {:?}", other),
+            },
+            Err(error) => panic!("{:?}", error),
+        }
+    }
+
+    #[rstest::rstest(
+        sql,
+        expected,
+        case::constructor("select ARRAY[1, 2]", "SELECT ARRAY(1, 2)"),
+        case::empty_constructor("select array[]", "SELECT array()"),
+        case::subscript("select data[1]", r#"SELECT "[]"(data, 1)"#),
+        case::constructor_subscript("select array[1, 2][t.i + 1]", r#"SELECT "[]"(array(1, 2), t.i + 1)"#),
+        case::nested_subscripts("select a[b[1]]", r#"SELECT "[]"(a, "[]"(b, 1))"#),
+        case::any("select 1 = ANY(ARRAY[1])", "SELECT 1 = ANY(ARRAY(1))"),
+        case::cast("select '{1}'::int[]", r#"SELECT CAST('{1}' AS "int[]")"#),
+        case::subscript_json("select data[1] -> 'a'", r#"SELECT "->"("[]"(data, 1), 'a')"#)
+    )]
+    fn arrays(sql: &str, expected: &str) {
+        match parse(sql) {
+            Ok(statements) => match statements.as_slice() {
+                [ExtendedStatement::Standard(statement)] => assert_eq!(statement.to_string(), expected),
+                other => panic!("unexpected statements {:?}", other),
+            },
+            Err(error) => panic!("{:?}", error),
+        }
+    }
+
+    #[rstest::rstest]
+    fn array_types() {
+        match parse(
+            "create table schema_name.table_name (             col_1 int[], col_2 varchar(10)[], col_3 double precision [ ], col_4 timestamp with time zone[]);",
+        ) {
+            Ok(statements) => match statements.as_slice() {
+                [ExtendedStatement::Standard(Statement::CreateTable { columns, .. })] => assert_eq!(
+                    columns
+                        .iter()
+                        .map(|column| column.data_type.to_string())
+                        .collect::<Vec<String>>(),
+                    vec![
+                        r#""int[]""#,
+                        r#""varchar(10)[]""#,
+                        r#""double precision[]""#,
+                        r#""timestamptz[]""#
+                    ]
+                ),
+                other => panic!("unexpected statements {:?}", other),
+            },
+            Err(error) => panic!("{:?}", error),
+        }
+    }
+
+    #[rstest::rstest]
+    fn create_enum_type() {
+        assert_eq!(
+            parse("create type schema_name.mood as enum ('sad', 'ok', 'happy')"),
+            Ok(vec![ExtendedStatement::CreateEnumType {
+                name: ObjectName(vec![Ident::new("schema_name"), Ident::new("mood")]),
+                labels: vec!["sad".to_owned(), "ok".to_owned(), "happy".to_owned()]
+            }])
+        );
+        assert_eq!(
+            parse("CREATE TYPE schema_name.empty AS ENUM ()"),
+            Ok(vec![ExtendedStatement::CreateEnumType {
+                name: ObjectName(vec![Ident::new("schema_name"), Ident::new("empty")]),
+                labels: vec![]
+            }])
+        );
+    }
+
+    #[rstest::rstest]
+    fn drop_type() {
+        assert_eq!(
+            parse("drop type if exists schema_name.mood, schema_name.other restrict"),
+            Ok(vec![ExtendedStatement::DropType {
+                names: vec![
+                    ObjectName(vec![Ident::new("schema_name"), Ident::new("mood")]),
+                    ObjectName(vec![Ident::new("schema_name"), Ident::new("other")])
+                ],
+                if_exists: true
+            }])
+        );
+    }
+
+    #[rstest::rstest]
+    fn create_sequence() {
+        assert_eq!(
+            parse("create sequence if not exists schema_name.ids increment by -2 minvalue -10 no maxvalue start with -3 no cycle"),
+            Ok(vec![ExtendedStatement::CreateSequence {
+                name: ObjectName(vec![Ident::new("schema_name"), Ident::new("ids")]),
+                if_not_exists: true,
+                parameters: SequenceParameters {
+                    increment: Some(-2),
+                    min_value: Some(-10),
+                    max_value: None,
+                    start: Some(-3)
+                }
+            }])
+        );
+        assert_eq!(
+            parse("CREATE SEQUENCE schema_name.ids"),
+            Ok(vec![ExtendedStatement::CreateSequence {
+                name: ObjectName(vec![Ident::new("schema_name"), Ident::new("ids")]),
+                if_not_exists: false,
+                parameters: SequenceParameters::default()
+            }])
+        );
+    }
+
+    #[rstest::rstest]
+    fn insert_overriding_system_value() {
+        let insert = match parse("insert into schema_name.table_name (id) values (1)") {
+            Ok(mut statements) => match statements.pop() {
+                Some(ExtendedStatement::Standard(insert)) => insert,
+                other => panic!("unexpected statement {:?}", other),
+            },
+            Err(error) => panic!("{:?}", error),
+        };
+        assert_eq!(
+            parse("insert into schema_name.table_name (id) overriding system value values (1)"),
+            Ok(vec![ExtendedStatement::InsertOverridingSystemValue(insert)])
+        );
+    }
+
+    #[rstest::rstest]
+    fn drop_sequence() {
+        assert_eq!(
+            parse("drop sequence if exists schema_name.ids"),
+            Ok(vec![ExtendedStatement::DropSequence {
+                names: vec![ObjectName(vec![Ident::new("schema_name"), Ident::new("ids")])],
+                if_exists: true
+            }])
+        );
+    }
+
+    #[rstest::rstest(
+        column,
+        default,
+        case::always("id integer generated always as identity", "\"identity\"('', 'always')"),
+        case::by_default(
+            "id integer generated by default as identity (start with 10 increment by 5)",
+            "\"identity\"('start with 10 increment by 5')"
+        )
+    )]
+    fn identity_columns(column: &str, default: &str) {
+        match parse(&format!("create table schema_name.table_name ({})", column)) {
+            Ok(statements) => match statements.as_slice() {
+                [ExtendedStatement::Standard(Statement::CreateTable { columns, .. })] => {
+                    assert_eq!(columns[0].options[0].option.to_string(), format!("DEFAULT {}", default))
+                }
+                other => panic!("unexpected statements {:?}", other),
+            },
+            Err(error) => panic!("{:?}", error),
+        }
+        assert_eq!(
+            parse_sequence_parameters("start with 10 increment by 5"),
+            Ok(SequenceParameters {
+                increment: Some(5),
+                min_value: None,
+                max_value: None,
+                start: Some(10)
+            })
+        );
+    }
+
+    #[rstest::rstest]
+    fn standard_statements() {
+        match parse("create schema schema_name; select * from schema_name.table_name;") {
+            Ok(statements) => match statements.as_slice() {
+                [ExtendedStatement::Standard(Statement::CreateSchema { .. }), ExtendedStatement::Standard(Statement::Query(_))] =>
+                    {}
+                other => panic!("unexpected statements {:?}", other),
+            },
+            Err(error) => panic!("{:?}", error),
+        }
+    }
+
+    #[rstest::rstest]
+    fn prepare() {
+        match parse("prepare insert_values (integer, timestamp with time zone) as insert into schema_name.table_name values ($1, $2)") {
+            Ok(statements) => match statements.as_slice() {
+                [ExtendedStatement::Prepare {
+                    name,
+                    param_types,
+                    statement,
+                }] => {
+                    assert_eq!(name, &Ident::new("insert_values"));
+                    assert_eq!(
+                        param_types,
+                        &vec![
+                            DataType::Int,
+                            DataType::Custom(ObjectName(vec![Ident::new("timestamptz")]))
+                        ]
+                    );
+                    assert_eq!(
+                        statement.iter().map(Token::to_string).collect::<String>(),
+                        " insert into schema_name.table_name values ($1, $2)"
+                    );
+                }
+                other => panic!("unexpected statements {:?}", other),
+            },
+            Err(error) => panic!("{:?}", error),
+        }
+        assert!(parse("prepare without_statement as").is_err());
+    }
+
+    #[rstest::rstest]
+    fn execute() {
+        assert_eq!(
+            parse("execute insert_values (1, 'a')"),
+            Ok(vec![ExtendedStatement::Execute {
+                name: Ident::new("insert_values"),
+                parameters: vec![
+                    Expr::Value(Value::Number("1".to_owned())),
+                    Expr::Value(Value::SingleQuotedString("a".to_owned()))
+                ]
+            }])
+        );
+        assert_eq!(
+            parse("EXECUTE select_values"),
+            Ok(vec![ExtendedStatement::Execute {
+                name: Ident::new("select_values"),
+                parameters: vec![]
+            }])
+        );
+    }
+
+    #[rstest::rstest]
+    fn deallocate() {
+        assert_eq!(
+            parse("deallocate prepare insert_values"),
+            Ok(vec![ExtendedStatement::Deallocate {
+                name: Some(Ident::new("insert_values"))
+            }])
+        );
+        assert_eq!(
+            parse("DEALLOCATE ALL"),
+            Ok(vec![ExtendedStatement::Deallocate { name: None }])
+        );
+    }
+
+    #[rstest::rstest]
+    fn statement_parameters() {
+        let tokens = tokenize("insert into schema_name.table_name values ($2, $1, $2, '$3');").unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(parameters(&tokens[0]), vec![2, 1, 2]);
+    }
+
+    #[rstest::rstest]
+    fn bound_parameters() {
+        let tokens = tokenize("select $1, $2").unwrap();
+        let bound = bind_parameters(&tokens[0], &mut |number| {
+            vec![Token::SingleQuotedString(format!("value {}", number))]
+        });
+        assert_eq!(
+            bound.iter().map(Token::to_string).collect::<String>(),
+            "select 'value 1', 'value 2'"
+        );
+    }
+}
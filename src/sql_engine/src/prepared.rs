@@ -0,0 +1,516 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::parser::{self, ExtendedStatement};
+use kernel::SystemResult;
+use protocol::{results::QueryError, sql_types::PostgreSqlFormat};
+use sql_types::{ConstraintError, SqlType};
+use sqlparser::{
+    ast::{
+        DataType, Expr, Ident, ObjectName, Query, SelectItem, SetExpr, Statement, TableFactor, TableWithJoins, Value,
+    },
+    tokenizer::Token,
+};
+use storage::{backend::BackendStorage, frontend::FrontendStorage};
+
+/// Statement of extended query protocol or `PREPARE`. It is parsed once with
+/// `$n` parameters that are replaced with values when the statement is bound
+#[derive(Clone)]
+pub(crate) struct PreparedStatement {
+    raw_sql_query: String,
+    statement: ExtendedStatement,
+    param_types: Vec<SqlType>,
+}
+
+impl PreparedStatement {
+    /// Prepares single statement of extended query with OIDs of its parameter
+    /// types, 0 is a type that is not specified
+    pub(crate) fn parse<P: BackendStorage>(
+        raw_sql_query: String,
+        param_types: Vec<i32>,
+        storage: &FrontendStorage<P>,
+    ) -> SystemResult<Result<Self, QueryError>> {
+        let tokens = match parser::tokenize(&raw_sql_query) {
+            Ok(mut statements) if statements.len() <= 1 => statements.pop().unwrap_or_default(),
+            Ok(_) => return Ok(Err(QueryError::multiple_commands_in_prepared_statement())),
+            Err(error) => {
+                let position = parser::error_position(&raw_sql_query, &error);
+                return Ok(Err(crate::syntax_error(error, position)));
+            }
+        };
+        let mut types = vec![];
+        for oid in param_types {
+            match oid {
+                0 => types.push(None),
+                oid => match SqlType::from_pg_oid(oid) {
+                    Some(sql_type) => types.push(Some(sql_type)),
+                    None => return Ok(Err(QueryError::type_does_not_exist(oid.to_string()))),
+                },
+            }
+        }
+        Self::prepare(raw_sql_query, tokens, types, storage)
+    }
+
+    /// Prepares statement tokens with types of its parameters, types that are
+    /// not specified are inferred from the statement: cast target or column
+    /// of inserted or updated value, otherwise `text`
+    pub(crate) fn prepare<P: BackendStorage>(
+        raw_sql_query: String,
+        tokens: Vec<Token>,
+        mut param_types: Vec<Option<SqlType>>,
+        storage: &FrontendStorage<P>,
+    ) -> SystemResult<Result<Self, QueryError>> {
+        if let Some(params) = parser::parameters(&tokens).into_iter().max() {
+            param_types.resize(param_types.len().max(params), None);
+        }
+        // parameters are parsed as identifiers to be bound later
+        let statement = if tokens.iter().all(|token| matches!(token, Token::Whitespace(_))) {
+            ExtendedStatement::Empty
+        } else {
+            let tokens = parser::parameters_as_identifiers(&tokens);
+            match parser::parse_statement(tokens.clone()) {
+                Ok(statement) => statement,
+                Err(error) => {
+                    let position = parser::extended_error_position(&raw_sql_query, &error);
+                    return Ok(Err(crate::syntax_error(error, position)));
+                }
+            }
+        };
+        infer_types(&statement, storage, &mut param_types)?;
+        Ok(Ok(Self {
+            raw_sql_query,
+            statement,
+            param_types: param_types
+                .into_iter()
+                .map(|sql_type| sql_type.unwrap_or(SqlType::Text))
+                .collect(),
+        }))
+    }
+
+    pub(crate) fn raw_sql_query(&self) -> &str {
+        &self.raw_sql_query
+    }
+
+    pub(crate) fn param_types(&self) -> &[SqlType] {
+        &self.param_types
+    }
+
+    pub(crate) fn statement(&self) -> &ExtendedStatement {
+        &self.statement
+    }
+
+    /// Statement with values of parameters in the formats: all of them are in
+    /// text if there are no formats, a single format is applied to all of them
+    pub(crate) fn bind(
+        &self,
+        statement_name: &str,
+        param_formats: &[PostgreSqlFormat],
+        raw_params: Vec<Option<Vec<u8>>>,
+    ) -> Result<Self, QueryError> {
+        if raw_params.len() != self.param_types.len() {
+            return Err(QueryError::protocol_violation(format!(
+                "bind message supplies {} parameters, but prepared statement \"{}\" requires {}",
+                raw_params.len(),
+                statement_name,
+                self.param_types.len()
+            )));
+        }
+        if param_formats.len() > 1 && param_formats.len() != raw_params.len() {
+            return Err(QueryError::protocol_violation(format!(
+                "bind message has {} parameter formats but {} parameters",
+                param_formats.len(),
+                raw_params.len()
+            )));
+        }
+        let mut values = vec![];
+        for (index, (raw_param, sql_type)) in raw_params.into_iter().zip(self.param_types.iter()).enumerate() {
+            let format = match param_formats {
+                [] => PostgreSqlFormat::Text,
+                [format] => *format,
+                formats => formats[index],
+            };
+            let value = match (raw_param, format) {
+                (None, _) => None,
+                (Some(bytes), PostgreSqlFormat::Text) => match String::from_utf8(bytes) {
+                    Ok(value) => Some(value),
+                    Err(error) => {
+                        return Err(QueryError::invalid_text_representation(
+                            sql_type.to_string(),
+                            String::from_utf8_lossy(error.as_bytes()).into_owned(),
+                        ))
+                    }
+                },
+                (Some(bytes), PostgreSqlFormat::Binary) => match sql_type.from_binary(&bytes) {
+                    Ok(datum) => datum.to_text(),
+                    Err(ConstraintError::InvalidBinaryFormat) => {
+                        return Err(QueryError::invalid_binary_representation(index + 1))
+                    }
+                    Err(error) => return Err(crate::expr::constraint_error(error, sql_type, "")),
+                },
+            };
+            values.push(Expr::Value(match value {
+                Some(value) => Value::SingleQuotedString(value),
+                None => Value::Null,
+            }));
+        }
+        self.with_parameters(values)
+    }
+
+    /// Statement with values of `EXECUTE` parameters
+    pub(crate) fn execute(&self, statement_name: &str, parameters: Vec<Expr>) -> Result<Self, QueryError> {
+        if parameters.len() != self.param_types.len() {
+            return Err(QueryError::wrong_number_of_parameters(statement_name.to_owned()));
+        }
+        self.with_parameters(parameters)
+    }
+
+    /// Statement with NULL parameters to be described before values are bound,
+    /// NULLs are cast to types of parameters that are types of expressions
+    /// they are used in
+    pub(crate) fn with_nulls(&self) -> Result<Self, QueryError> {
+        self.with_parameters(vec![Expr::Value(Value::Null); self.param_types.len()])
+    }
+
+    // values are cast to types of parameters, enum values are left as they
+    // are to be checked against enum labels when they are assigned
+    fn with_parameters(&self, parameters: Vec<Expr>) -> Result<Self, QueryError> {
+        let mut values = vec![];
+        for (expr, sql_type) in parameters.into_iter().zip(self.param_types.iter()) {
+            if has_enum(sql_type) {
+                values.push(expr);
+                continue;
+            }
+            match parser::parse_data_type(&without_modifiers(sql_type).to_string()) {
+                Ok(data_type) => values.push(Expr::Cast {
+                    expr: Box::new(expr),
+                    data_type,
+                }),
+                Err(_) => return Err(QueryError::type_does_not_exist(sql_type.to_string())),
+            }
+        }
+        let mut statement = self.statement.clone();
+        bind_statement(&mut statement, &values);
+        Ok(Self {
+            raw_sql_query: self.raw_sql_query.clone(),
+            statement,
+            param_types: self.param_types.clone(),
+        })
+    }
+}
+
+fn infer_types<P: BackendStorage>(
+    statement: &ExtendedStatement,
+    storage: &FrontendStorage<P>,
+    types: &mut [Option<SqlType>],
+) -> SystemResult<()> {
+    match statement {
+        ExtendedStatement::Standard(Statement::Insert {
+            table_name,
+            columns,
+            source,
+            ..
+        })
+        | ExtendedStatement::InsertOverridingSystemValue(Statement::Insert {
+            table_name,
+            columns,
+            source,
+            ..
+        }) => {
+            if let SetExpr::Values(values) = &source.body {
+                let table_columns = table_columns(table_name, storage)?;
+                let targets = if columns.is_empty() {
+                    table_columns
+                        .iter()
+                        .map(|(_name, sql_type)| Some(sql_type.clone()))
+                        .collect()
+                } else {
+                    columns
+                        .iter()
+                        .map(|column| column_type(&table_columns, &column.value))
+                        .collect::<Vec<Option<SqlType>>>()
+                };
+                for row in values.0.iter() {
+                    for (index, expr) in row.iter().enumerate() {
+                        infer_expr_type(expr, targets.get(index).cloned().flatten(), &[], types);
+                    }
+                }
+            }
+        }
+        ExtendedStatement::Standard(Statement::Update {
+            table_name,
+            assignments,
+            ..
+        }) => {
+            let table_columns = table_columns(table_name, storage)?;
+            for assignment in assignments {
+                infer_expr_type(
+                    &assignment.value,
+                    column_type(&table_columns, &assignment.id.value),
+                    &table_columns,
+                    types,
+                );
+            }
+        }
+        ExtendedStatement::Standard(Statement::Query(query)) | ExtendedStatement::LockingQuery { query, .. } => {
+            if let SetExpr::Select(select) = &query.body {
+                let table_columns = match select.from.as_slice() {
+                    [TableWithJoins {
+                        relation: TableFactor::Table { name, .. },
+                        joins,
+                    }] if joins.is_empty() => table_columns(name, storage)?,
+                    _ => vec![],
+                };
+                for item in select.projection.iter() {
+                    match item {
+                        SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                            infer_expr_type(expr, None, &table_columns, types)
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+// parameter gets type of the context where it is used first, operand of an
+// operator gets type of the other operand as `unknown` literal in PostgreSQL
+fn infer_expr_type(
+    expr: &Expr,
+    context: Option<SqlType>,
+    columns: &[(String, SqlType)],
+    types: &mut [Option<SqlType>],
+) {
+    match expr {
+        Expr::Identifier(ident) => {
+            if let Some(param_type) = parameter_number(ident).and_then(|number| types.get_mut(number - 1)) {
+                if param_type.is_none() {
+                    *param_type = context;
+                }
+            }
+        }
+        Expr::Cast { expr, data_type } => infer_expr_type(expr, cast_target(data_type), columns, types),
+        Expr::Nested(expr) | Expr::UnaryOp { expr, .. } => infer_expr_type(expr, context, columns, types),
+        Expr::BinaryOp { left, right, .. } => {
+            infer_expr_type(left, expr_type(right, columns, types), columns, types);
+            infer_expr_type(right, expr_type(left, columns, types), columns, types);
+        }
+        Expr::Function(function) => {
+            for arg in function.args.iter() {
+                infer_expr_type(arg, None, columns, types);
+            }
+        }
+        _ => {}
+    }
+}
+
+// type of expression that is known before it is evaluated, string literals
+// and parameters without type are of unknown type
+fn expr_type(expr: &Expr, columns: &[(String, SqlType)], types: &[Option<SqlType>]) -> Option<SqlType> {
+    match expr {
+        Expr::Identifier(ident) => match parameter_number(ident) {
+            Some(number) => types.get(number - 1).cloned().flatten(),
+            None => column_type(columns, &ident.value),
+        },
+        Expr::Nested(expr) | Expr::UnaryOp { expr, .. } => expr_type(expr, columns, types),
+        expr => crate::expr::expr_type(expr, columns),
+    }
+}
+
+fn cast_target(data_type: &DataType) -> Option<SqlType> {
+    match crate::ddl::create_table::sql_type(data_type, &mut |name| {
+        Ok(Err(QueryError::type_does_not_exist(name.to_string())))
+    }) {
+        Ok(Ok(sql_type)) => Some(sql_type),
+        _ => None,
+    }
+}
+
+fn table_columns<P: BackendStorage>(
+    table_name: &ObjectName,
+    storage: &FrontendStorage<P>,
+) -> SystemResult<Vec<(String, SqlType)>> {
+    match crate::qualified_name(table_name) {
+        Ok((schema_name, table_name)) => storage.table_columns(&schema_name, &table_name),
+        Err(_) => Ok(vec![]),
+    }
+}
+
+fn column_type(table_columns: &[(String, SqlType)], column_name: &str) -> Option<SqlType> {
+    table_columns
+        .iter()
+        .find(|(name, _sql_type)| name == column_name)
+        .map(|(_name, sql_type)| sql_type.clone())
+}
+
+// parameters have no length, precision and scale as in PostgreSQL, explicit
+// cast would silently truncate values that are checked when they are assigned
+fn without_modifiers(sql_type: &SqlType) -> SqlType {
+    match sql_type {
+        SqlType::Char(_) | SqlType::VarChar(_) => SqlType::VarChar(None),
+        SqlType::Decimal(_) => SqlType::Decimal(None),
+        SqlType::Array(element) => SqlType::Array(Box::new(without_modifiers(element))),
+        sql_type => sql_type.clone(),
+    }
+}
+
+fn has_enum(sql_type: &SqlType) -> bool {
+    match sql_type {
+        SqlType::Enum { .. } => true,
+        SqlType::Array(element) => has_enum(element),
+        _ => false,
+    }
+}
+
+// `$n` identifiers of the parsed statement
+fn parameter_number(ident: &Ident) -> Option<usize> {
+    if ident.quote_style.is_some() || !ident.value.starts_with('$') {
+        return None;
+    }
+    ident.value[1..].parse::<usize>().ok().filter(|number| *number > 0)
+}
+
+fn bind_statement(statement: &mut ExtendedStatement, parameters: &[Expr]) {
+    match statement {
+        ExtendedStatement::Standard(Statement::Query(query)) | ExtendedStatement::LockingQuery { query, .. } => {
+            bind_query(query, parameters)
+        }
+        ExtendedStatement::Standard(Statement::Insert { source, .. })
+        | ExtendedStatement::InsertOverridingSystemValue(Statement::Insert { source, .. }) => {
+            bind_query(source, parameters)
+        }
+        ExtendedStatement::Standard(Statement::Update {
+            assignments, selection, ..
+        }) => {
+            for assignment in assignments.iter_mut() {
+                bind_expr(&mut assignment.value, parameters);
+            }
+            if let Some(selection) = selection {
+                bind_expr(selection, parameters);
+            }
+        }
+        ExtendedStatement::Standard(Statement::Delete {
+            selection: Some(selection),
+            ..
+        }) => bind_expr(selection, parameters),
+        _ => {}
+    }
+}
+
+fn bind_query(query: &mut Query, parameters: &[Expr]) {
+    for cte in query.ctes.iter_mut() {
+        bind_query(&mut cte.query, parameters);
+    }
+    bind_set_expr(&mut query.body, parameters);
+    for order_by in query.order_by.iter_mut() {
+        bind_expr(&mut order_by.expr, parameters);
+    }
+    if let Some(limit) = query.limit.as_mut() {
+        bind_expr(limit, parameters);
+    }
+}
+
+fn bind_set_expr(set_expr: &mut SetExpr, parameters: &[Expr]) {
+    match set_expr {
+        SetExpr::Select(select) => {
+            for item in select.projection.iter_mut() {
+                match item {
+                    SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                        bind_expr(expr, parameters)
+                    }
+                    _ => {}
+                }
+            }
+            for expr in select
+                .selection
+                .iter_mut()
+                .chain(select.group_by.iter_mut())
+                .chain(select.having.iter_mut())
+            {
+                bind_expr(expr, parameters);
+            }
+        }
+        SetExpr::Query(query) => bind_query(query, parameters),
+        SetExpr::SetOperation { left, right, .. } => {
+            bind_set_expr(left, parameters);
+            bind_set_expr(right, parameters);
+        }
+        SetExpr::Values(values) => {
+            for expr in values.0.iter_mut().flatten() {
+                bind_expr(expr, parameters);
+            }
+        }
+    }
+}
+
+fn bind_expr(expr: &mut Expr, parameters: &[Expr]) {
+    match expr {
+        Expr::Identifier(ident) => {
+            if let Some(value) = parameter_number(ident).and_then(|number| parameters.get(number - 1)) {
+                *expr = value.clone();
+            }
+        }
+        Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::UnaryOp { expr, .. }
+        | Expr::Cast { expr, .. }
+        | Expr::Extract { expr, .. }
+        | Expr::Collate { expr, .. }
+        | Expr::Nested(expr) => bind_expr(expr, parameters),
+        Expr::InList { expr, list, .. } => {
+            bind_expr(expr, parameters);
+            for item in list.iter_mut() {
+                bind_expr(item, parameters);
+            }
+        }
+        Expr::InSubquery { expr, subquery, .. } => {
+            bind_expr(expr, parameters);
+            bind_query(subquery, parameters);
+        }
+        Expr::Between { expr, low, high, .. } => {
+            bind_expr(expr, parameters);
+            bind_expr(low, parameters);
+            bind_expr(high, parameters);
+        }
+        Expr::BinaryOp { left, right, .. } => {
+            bind_expr(left, parameters);
+            bind_expr(right, parameters);
+        }
+        Expr::Function(function) => {
+            for arg in function.args.iter_mut() {
+                bind_expr(arg, parameters);
+            }
+        }
+        Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+        } => {
+            for expr in operand
+                .iter_mut()
+                .chain(else_result.iter_mut())
+                .map(|expr| expr.as_mut())
+                .chain(conditions.iter_mut())
+                .chain(results.iter_mut())
+            {
+                bind_expr(expr, parameters);
+            }
+        }
+        Expr::Exists(query) | Expr::Subquery(query) => bind_query(query, parameters),
+        _ => {}
+    }
+}
@@ -0,0 +1,176 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Sequence functions change sequences in storage, so they are evaluated
+//! before the rest of expression
+use kernel::SystemResult;
+use protocol::results::QueryError;
+use sql_types::Datum;
+use sqlparser::ast::{DataType, Expr, Function, Value};
+use storage::{backend::BackendStorage, frontend::FrontendStorage, lock::SessionId, SequenceError};
+
+/// Evaluates expression that can call `nextval`, `currval` and `setval`
+pub(crate) fn evaluate<P: BackendStorage>(
+    expr: &Expr,
+    storage: &FrontendStorage<P>,
+    session_id: SessionId,
+) -> SystemResult<Result<Datum, QueryError>> {
+    evaluate_in(expr, &[], storage, session_id)
+}
+
+/// Evaluates expression of record columns that can call sequence functions,
+/// they are called once for every record
+pub(crate) fn evaluate_in<P: BackendStorage>(
+    expr: &Expr,
+    record: &[(String, Datum)],
+    storage: &FrontendStorage<P>,
+    session_id: SessionId,
+) -> SystemResult<Result<Datum, QueryError>> {
+    match resolve(expr, &mut |name, args| call(name, args, storage, session_id))? {
+        Ok(expr) => Ok(crate::expr::evaluate_in(&expr, record)),
+        Err(error) => Ok(Err(error)),
+    }
+}
+
+/// Whether expression calls any of sequence functions
+pub(crate) fn calls_sequence_functions(expr: &Expr) -> bool {
+    match expr {
+        Expr::Nested(expr) | Expr::UnaryOp { expr, .. } => calls_sequence_functions(expr),
+        Expr::BinaryOp { left, right, .. } => calls_sequence_functions(left) || calls_sequence_functions(right),
+        Expr::Function(function) => {
+            let name = function.name.to_string().to_lowercase();
+            matches!(name.as_str(), "nextval" | "currval" | "setval")
+                || function.args.iter().any(calls_sequence_functions)
+        }
+        _ => false,
+    }
+}
+
+// calls sequence function with evaluated arguments
+type Call<'c> = dyn FnMut(&str, &[Expr]) -> SystemResult<Result<i64, QueryError>> + 'c;
+
+// replaces calls of sequence functions with their results, that are `bigint`
+// whatever the value is
+fn resolve(expr: &Expr, call: &mut Call) -> SystemResult<Result<Expr, QueryError>> {
+    let resolved = match expr {
+        Expr::Nested(expr) => match resolve(expr, call)? {
+            Ok(expr) => Expr::Nested(Box::new(expr)),
+            Err(error) => return Ok(Err(error)),
+        },
+        Expr::UnaryOp { op, expr } => match resolve(expr, call)? {
+            Ok(expr) => Expr::UnaryOp {
+                op: op.clone(),
+                expr: Box::new(expr),
+            },
+            Err(error) => return Ok(Err(error)),
+        },
+        Expr::BinaryOp { left, op, right } => match (resolve(left, call)?, resolve(right, call)?) {
+            (Ok(left), Ok(right)) => Expr::BinaryOp {
+                left: Box::new(left),
+                op: op.clone(),
+                right: Box::new(right),
+            },
+            (Err(error), _) | (_, Err(error)) => return Ok(Err(error)),
+        },
+        Expr::Function(function) => {
+            let mut args = vec![];
+            for arg in function.args.iter() {
+                match resolve(arg, call)? {
+                    Ok(arg) => args.push(arg),
+                    Err(error) => return Ok(Err(error)),
+                }
+            }
+            let name = function.name.to_string().to_lowercase();
+            if let "nextval" | "currval" | "setval" = name.as_str() {
+                return Ok(call(&name, &args)?.map(|value| Expr::Cast {
+                    expr: Box::new(Expr::Value(Value::Number(value.to_string()))),
+                    data_type: DataType::BigInt,
+                }));
+            }
+            Expr::Function(Function {
+                args,
+                ..function.clone()
+            })
+        }
+        expr => expr.clone(),
+    };
+    Ok(Ok(resolved))
+}
+
+fn call<P: BackendStorage>(
+    name: &str,
+    args: &[Expr],
+    storage: &FrontendStorage<P>,
+    session_id: SessionId,
+) -> SystemResult<Result<i64, QueryError>> {
+    let args = match args
+        .iter()
+        .map(crate::expr::evaluate)
+        .collect::<Result<Vec<Datum>, QueryError>>()
+    {
+        Ok(args) => args,
+        Err(error) => return Ok(Err(error)),
+    };
+    let (sequence, value, is_called) = match (name, args.as_slice()) {
+        ("nextval", [Datum::String(sequence)]) | ("currval", [Datum::String(sequence)]) => (sequence, 0, true),
+        ("setval", [Datum::String(sequence), value]) => match as_bigint(value) {
+            Some(value) => (sequence, value, true),
+            None => return Ok(Err(undefined_function(name, &args))),
+        },
+        ("setval", [Datum::String(sequence), value, Datum::Bool(is_called)]) => match as_bigint(value) {
+            Some(value) => (sequence, value, *is_called),
+            None => return Ok(Err(undefined_function(name, &args))),
+        },
+        _ => return Ok(Err(undefined_function(name, &args))),
+    };
+    let (schema_name, sequence_name) = match sequence.split('.').collect::<Vec<&str>>().as_slice() {
+        [sequence_name] => (crate::DEFAULT_SCHEMA.to_owned(), sequence_name.to_string()),
+        [schema_name, sequence_name] => (schema_name.to_string(), sequence_name.to_string()),
+        _ => return Ok(Err(QueryError::sequence_does_not_exist(sequence.clone()))),
+    };
+    let result = match name {
+        "nextval" => storage.next_value(&schema_name, &sequence_name, session_id)?,
+        "currval" => storage.current_value(&schema_name, &sequence_name, session_id)?,
+        _ => storage.set_value(&schema_name, &sequence_name, value, is_called, session_id)?,
+    };
+    Ok(result.map_err(|error| match error {
+        SequenceError::SequenceDoesNotExist => QueryError::sequence_does_not_exist(sequence.clone()),
+        SequenceError::MaxValueReached(max_value) => QueryError::sequence_max_value_reached(sequence_name, max_value),
+        SequenceError::MinValueReached(min_value) => QueryError::sequence_min_value_reached(sequence_name, min_value),
+        SequenceError::CurrentValueNotDefined => QueryError::sequence_current_value_not_defined(sequence_name),
+        SequenceError::ValueOutOfBounds(min_value, max_value) => {
+            QueryError::sequence_value_out_of_bounds(sequence_name, value, min_value, max_value)
+        }
+    }))
+}
+
+fn as_bigint(datum: &Datum) -> Option<i64> {
+    match datum {
+        Datum::Int16(value) => Some(*value as i64),
+        Datum::Int32(value) => Some(*value as i64),
+        Datum::Int64(value) => Some(*value),
+        _ => None,
+    }
+}
+
+fn undefined_function(name: &str, args: &[Datum]) -> QueryError {
+    QueryError::undefined_function(format!(
+        "{}({})",
+        name,
+        args.iter()
+            .map(crate::expr::type_name)
+            .collect::<Vec<String>>()
+            .join(", ")
+    ))
+}
@@ -0,0 +1,165 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+#[rstest::rstest]
+fn insert_and_select_arrays(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_table(
+        &mut sql_engine_with_schema,
+        "column_i integer[], column_t text[], column_vc varchar(2)[]",
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute(
+                r#"insert into schema_name.table_name values
+                 ('{1, 2, 3}', '{"a b", NULL, "NULL"}', '{ab}'),
+                 (ARRAY[4, NULL], ARRAY['c', 'd,e'], ARRAY[]);"#
+            )
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(2))]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                (
+                    "column_i".to_owned(),
+                    PostgreSqlType::Array(Box::new(PostgreSqlType::Integer))
+                ),
+                (
+                    "column_t".to_owned(),
+                    PostgreSqlType::Array(Box::new(PostgreSqlType::Text))
+                ),
+                (
+                    "column_vc".to_owned(),
+                    PostgreSqlType::Array(Box::new(PostgreSqlType::VarChar(Some(2))))
+                ),
+            ],
+            vec![
+                vec![
+                    "{1,2,3}".to_owned(),
+                    r#"{"a b",NULL,"NULL"}"#.to_owned(),
+                    "{ab}".to_owned(),
+                ],
+                vec!["{4,NULL}".to_owned(), r#"{c,"d,e"}"#.to_owned(), "{}".to_owned()],
+            ]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn subscripts_and_quantified_comparisons(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_table(
+        &mut sql_engine_with_schema,
+        "element integer, any_equal boolean, all_less boolean",
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute(
+                "insert into schema_name.table_name values \
+                 (ARRAY[10, 20, 30][2], 2 = ANY(ARRAY[1, 2]), 1 < ALL(ARRAY[2, 3])), \
+                 ((ARRAY[10, 20])[1 + 1], 'b' = SOME('{a,b}'), 1 < ALL(ARRAY[0, 3]));"
+            )
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(2))]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select element, any_equal, all_less from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("element".to_owned(), PostgreSqlType::Integer),
+                ("any_equal".to_owned(), PostgreSqlType::Bool),
+                ("all_less".to_owned(), PostgreSqlType::Bool),
+            ],
+            vec![
+                vec!["20".to_owned(), "t".to_owned(), "t".to_owned()],
+                vec!["20".to_owned(), "t".to_owned(), "f".to_owned()],
+            ]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn subscript_is_named_as_array(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        sql_engine
+            .execute("select ARRAY[10, 20][2], (ARRAY['a', 'b'])[1];")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("array".to_owned(), PostgreSqlType::Integer),
+                ("array".to_owned(), PostgreSqlType::Text),
+            ],
+            vec![vec!["20".to_owned(), "a".to_owned()]]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn unnest(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        sql_engine
+            .execute("select * from unnest(ARRAY[1, 2, 3]);")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![("unnest".to_owned(), PostgreSqlType::Integer)],
+            vec![vec!["1".to_owned()], vec!["2".to_owned()], vec!["3".to_owned()]]
+        )
+    );
+
+    assert_eq!(
+        sql_engine
+            .execute("select element from unnest(ARRAY['a', 'b']) as element;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![("element".to_owned(), PostgreSqlType::Text)],
+            vec![vec!["a".to_owned()], vec!["b".to_owned()]]
+        )
+    );
+}
+
+#[rstest::rstest(
+    value,
+    error,
+    case::subscript_of_scalar("(1)[1]", QueryError::not_an_array("integer".to_owned())),
+    case::text_subscript("ARRAY[1]['a']", QueryError::array_subscript_not_integer()),
+    case::any_of_scalar("1 = ANY(1)", QueryError::array_required("=".to_owned()))
+)]
+fn array_errors(mut sql_engine_with_schema: InMemorySqlEngine, value: &str, error: QueryError) {
+    create_table(&mut sql_engine_with_schema, "column_i integer[]");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute(format!("insert into schema_name.table_name values ({});", value).as_str())
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(error)]
+    );
+}
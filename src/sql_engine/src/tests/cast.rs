@@ -0,0 +1,167 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+fn create_table(sql_engine: &mut InMemorySqlEngine) {
+    sql_engine
+        .execute("create table schema_name.table_name (column_i integer, column_s varchar(5));")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+}
+
+#[rstest::rstest]
+fn insert_explicit_casts(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_table(&mut sql_engine_with_schema);
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute(
+                "insert into schema_name.table_name values \
+                 (CAST('42' AS integer), 'abcdefgh'::varchar(5)), (2.5::integer, true::text);"
+            )
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(2))]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("column_i".to_owned(), PostgreSqlType::Integer),
+                ("column_s".to_owned(), PostgreSqlType::VarChar(Some(5))),
+            ],
+            vec![
+                vec!["42".to_owned(), "abcde".to_owned()],
+                vec!["3".to_owned(), "true".to_owned()],
+            ]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn insert_assignment_casts(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_table(&mut sql_engine_with_schema);
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("insert into schema_name.table_name values (3.5, 12345);")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(1))]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("column_i".to_owned(), PostgreSqlType::Integer),
+                ("column_s".to_owned(), PostgreSqlType::VarChar(Some(5))),
+            ],
+            vec![vec!["4".to_owned(), "12345".to_owned()]]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn insert_value_without_assignment_cast(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_table(&mut sql_engine_with_schema);
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("insert into schema_name.table_name values (true, 'a');")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::datatype_mismatch(
+            "column_i".to_owned(),
+            "integer".to_owned(),
+            "boolean".to_owned()
+        )
+        .with_schema("schema_name".to_owned())
+        .with_table("table_name".to_owned()))]
+    );
+}
+
+#[rstest::rstest]
+fn update_assignment_casts(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_table(&mut sql_engine_with_schema);
+    sql_engine_with_schema
+        .execute("insert into schema_name.table_name values (1, 'a');")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("row inserted");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("update schema_name.table_name set column_i = 7.4, column_s = 2 * 21;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsUpdated(1))]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("column_i".to_owned(), PostgreSqlType::Integer),
+                ("column_s".to_owned(), PostgreSqlType::VarChar(Some(5))),
+            ],
+            vec![vec!["7".to_owned(), "42".to_owned()]]
+        )
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("update schema_name.table_name set column_s = 123456;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::value_too_long("character varying(5)".to_owned())
+            .with_column("column_s".to_owned())
+            .with_schema("schema_name".to_owned())
+            .with_table("table_name".to_owned()))]
+    );
+}
+
+#[rstest::rstest]
+fn select_casts(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        sql_engine
+            .execute("select '2020-01-31'::date + 1 as next_day;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![("next_day".to_owned(), PostgreSqlType::Date)],
+            vec![vec!["2020-02-01".to_owned()]]
+        )
+    );
+
+    assert_eq!(
+        sql_engine
+            .execute("select CAST(true AS date);")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::cannot_cast("boolean".to_owned(), "date".to_owned()))]
+    );
+}
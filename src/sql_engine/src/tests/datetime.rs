@@ -0,0 +1,380 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+fn show(sql_engine: &mut InMemorySqlEngine, name: &str) -> Vec<QueryResult> {
+    sql_engine
+        .execute(format!("show {};", name).as_str())
+        .expect("no system errors")
+        .collect()
+}
+
+fn set(sql_engine: &mut InMemorySqlEngine, sql: &str) -> Vec<QueryResult> {
+    sql_engine.execute(sql).expect("no system errors").collect()
+}
+
+#[rstest::rstest]
+fn insert_and_select_dates_and_times(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute(
+            "create table schema_name.table_name (\
+             column_d date, column_t time, column_ts timestamp, column_tstz timestamp with time zone, column_ttz timetz);",
+        )
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute(
+                "insert into schema_name.table_name values \
+                 ('2020-02-29', '13:45:01.25', '2020-02-29T13:45:01', '2020-02-29 13:45:01+03', '13:45:01-09:30'), \
+                 ('0044-03-15 BC', '00:00', 'infinity', '2020-02-29 13:45', '13:45');"
+            )
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(2))]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("column_d".to_owned(), PostgreSqlType::Date),
+                ("column_t".to_owned(), PostgreSqlType::Time),
+                ("column_ts".to_owned(), PostgreSqlType::Timestamp),
+                ("column_tstz".to_owned(), PostgreSqlType::TimestampWithTimeZone),
+                ("column_ttz".to_owned(), PostgreSqlType::TimeWithTimeZone),
+            ],
+            vec![
+                vec![
+                    "2020-02-29".to_owned(),
+                    "13:45:01.25".to_owned(),
+                    "2020-02-29 13:45:01".to_owned(),
+                    "2020-02-29 10:45:01+00".to_owned(),
+                    "13:45:01-09:30".to_owned(),
+                ],
+                vec![
+                    "0044-03-15 BC".to_owned(),
+                    "00:00:00".to_owned(),
+                    "infinity".to_owned(),
+                    "2020-02-29 13:45:00+00".to_owned(),
+                    "13:45:00+00".to_owned(),
+                ],
+            ]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn insert_and_select_intervals(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (column_i interval, column_ts timestamp);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute(
+                "insert into schema_name.table_name values \
+                 ('1 year 2 mons 3 days 04:05:06', '2020-01-31 10:00'), \
+                 ('P1DT12H', timestamp '2020-01-31 10:00' + interval '1 month'), \
+                 (timestamp '2020-03-01' - timestamp '2020-02-28 12:00', date '2020-01-01' + 1);"
+            )
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(3))]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("column_i".to_owned(), PostgreSqlType::Interval),
+                ("column_ts".to_owned(), PostgreSqlType::Timestamp),
+            ],
+            vec![
+                vec![
+                    "1 year 2 mons 3 days 04:05:06".to_owned(),
+                    "2020-01-31 10:00:00".to_owned()
+                ],
+                vec!["1 day 12:00:00".to_owned(), "2020-02-29 10:00:00".to_owned()],
+                vec!["1 day 12:00:00".to_owned(), "2020-01-02 00:00:00".to_owned()],
+            ]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn select_date_and_time_arithmetic_of_columns(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (column_d date, column_t time, column_ts timestamp, column_i interval);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+    sql_engine_with_schema
+        .execute(
+            "insert into schema_name.table_name values \
+             ('2020-01-31', '10:00', '2020-01-31 10:00', '1 day 02:00:00'), \
+             ('2020-02-28', '23:30', '2020-02-28 12:00', '1 month');",
+        )
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("records inserted");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute(
+                "select column_d + 1, column_d - date '2020-01-01', column_d + column_t, column_ts + column_i, \
+                 column_ts - timestamp '2020-01-01', column_t - column_i, column_i * 2 from schema_name.table_name;"
+            )
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("?column?".to_owned(), PostgreSqlType::Date),
+                ("?column?".to_owned(), PostgreSqlType::Integer),
+                ("?column?".to_owned(), PostgreSqlType::Timestamp),
+                ("?column?".to_owned(), PostgreSqlType::Timestamp),
+                ("?column?".to_owned(), PostgreSqlType::Interval),
+                ("?column?".to_owned(), PostgreSqlType::Time),
+                ("?column?".to_owned(), PostgreSqlType::Interval),
+            ],
+            vec![
+                vec![
+                    "2020-02-01".to_owned(),
+                    "30".to_owned(),
+                    "2020-01-31 10:00:00".to_owned(),
+                    "2020-02-01 12:00:00".to_owned(),
+                    "30 days 10:00:00".to_owned(),
+                    "08:00:00".to_owned(),
+                    "2 days 04:00:00".to_owned(),
+                ],
+                vec![
+                    "2020-02-29".to_owned(),
+                    "58".to_owned(),
+                    "2020-02-28 23:30:00".to_owned(),
+                    "2020-03-28 12:00:00".to_owned(),
+                    "58 days 12:00:00".to_owned(),
+                    "23:30:00".to_owned(),
+                    "2 mons".to_owned(),
+                ],
+            ]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn update_dates_and_times_with_values_of_their_columns(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (column_d date, column_ts timestamp, column_i interval);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+    sql_engine_with_schema
+        .execute(
+            "insert into schema_name.table_name values \
+             ('2020-01-31', '2020-01-31 10:00', '1 month'), ('2020-12-31', '2020-12-31 23:00', '1 hour');",
+        )
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("records inserted");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute(
+                "update schema_name.table_name \
+                 set column_d = column_d + column_i, column_ts = column_ts - column_i, column_i = column_i / 2;"
+            )
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsUpdated(2))]
+    );
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("column_d".to_owned(), PostgreSqlType::Date),
+                ("column_ts".to_owned(), PostgreSqlType::Timestamp),
+                ("column_i".to_owned(), PostgreSqlType::Interval),
+            ],
+            vec![
+                vec![
+                    "2020-02-29".to_owned(),
+                    "2019-12-31 10:00:00".to_owned(),
+                    "15 days".to_owned()
+                ],
+                vec![
+                    "2020-12-31".to_owned(),
+                    "2020-12-31 22:00:00".to_owned(),
+                    "00:30:00".to_owned()
+                ],
+            ]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn timestamps_with_time_zone_are_shown_in_session_time_zone(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (column_tstz timestamptz);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+
+    assert_eq!(
+        set(&mut sql_engine_with_schema, "set time zone -8;"),
+        vec![Ok(QueryEvent::VariableSet)]
+    );
+    sql_engine_with_schema
+        .execute("insert into schema_name.table_name values ('2020-01-01 10:00'), ('2020-01-01 10:00Z');")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("records inserted");
+
+    assert_eq!(
+        set(&mut sql_engine_with_schema, "set timezone = '+05:30';"),
+        vec![Ok(QueryEvent::VariableSet)]
+    );
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![("column_tstz".to_owned(), PostgreSqlType::TimestampWithTimeZone)],
+            vec![
+                vec!["2020-01-01 23:30:00+05:30".to_owned()],
+                vec!["2020-01-01 15:30:00+05:30".to_owned()],
+            ]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn timestamp_with_time_zone_and_interval_arithmetic(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        sql_engine
+            .execute(
+                "select '2020-01-31 00:00+00'::timestamptz + interval '1 month', \
+                 interval '1 day' + '2020-01-01 00:00+00'::timestamptz, \
+                 '2020-01-01 00:00+00'::timestamptz - interval '2 hours';"
+            )
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("?column?".to_owned(), PostgreSqlType::TimestampWithTimeZone),
+                ("?column?".to_owned(), PostgreSqlType::TimestampWithTimeZone),
+                ("?column?".to_owned(), PostgreSqlType::TimestampWithTimeZone),
+            ],
+            vec![vec![
+                "2020-02-29 00:00:00+00".to_owned(),
+                "2020-01-02 00:00:00+00".to_owned(),
+                "2019-12-31 22:00:00+00".to_owned(),
+            ]]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn show_and_set_time_zone(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        show(&mut sql_engine, "timezone"),
+        selected(
+            vec![("timezone".to_owned(), PostgreSqlType::VarChar(None))],
+            vec![vec!["UTC".to_owned()]]
+        )
+    );
+
+    assert_eq!(
+        set(&mut sql_engine, "set time zone 'Mars/Olympus_Mons';"),
+        vec![Err(QueryError::invalid_parameter_value(
+            "timezone".to_owned(),
+            "Mars/Olympus_Mons".to_owned()
+        )
+        .with_hint(
+            "only UTC and offsets from it, e.g. '+03:00' or -8, are supported".to_owned()
+        ))]
+    );
+    assert_eq!(
+        set(&mut sql_engine, "set time zone 'Europe/Berlin';"),
+        vec![Err(QueryError::invalid_parameter_value(
+            "timezone".to_owned(),
+            "Europe/Berlin".to_owned()
+        )
+        .with_hint(
+            "only UTC and offsets from it, e.g. '+03:00' or -8, are supported".to_owned()
+        ))]
+    );
+    assert_eq!(
+        set(&mut sql_engine, "set time zone 'Etc/UTC';"),
+        vec![Ok(QueryEvent::VariableSet)]
+    );
+
+    assert_eq!(
+        set(&mut sql_engine, "set timezone to '-03:00';"),
+        vec![Ok(QueryEvent::VariableSet)]
+    );
+    assert_eq!(
+        show(&mut sql_engine, "TimeZone"),
+        selected(
+            vec![("timezone".to_owned(), PostgreSqlType::VarChar(None))],
+            vec![vec!["-03:00".to_owned()]]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn show_and_set_date_style(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        show(&mut sql_engine, "DateStyle"),
+        selected(
+            vec![("datestyle".to_owned(), PostgreSqlType::VarChar(None))],
+            vec![vec!["ISO, MDY".to_owned()]]
+        )
+    );
+
+    assert_eq!(
+        set(&mut sql_engine, "set datestyle = 'ISO, DMY';"),
+        vec![Ok(QueryEvent::VariableSet)]
+    );
+    assert_eq!(
+        show(&mut sql_engine, "datestyle"),
+        selected(
+            vec![("datestyle".to_owned(), PostgreSqlType::VarChar(None))],
+            vec![vec!["ISO, DMY".to_owned()]]
+        )
+    );
+
+    assert_eq!(
+        set(&mut sql_engine, "set datestyle = 'SQL';"),
+        vec![Err(QueryError::invalid_parameter_value(
+            "datestyle".to_owned(),
+            "SQL".to_owned()
+        ))]
+    );
+}
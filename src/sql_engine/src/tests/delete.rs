@@ -20,8 +20,13 @@ fn delete_from_nonexistent_table(mut sql_engine_with_schema: InMemorySqlEngine)
     assert_eq!(
         sql_engine_with_schema
             .execute("delete from schema_name.table_name;")
-            .expect("no system errors"),
-        Err(QueryError::table_does_not_exist("schema_name.table_name".to_owned()))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::table_does_not_exist(
+            "schema_name.table_name".to_owned()
+        )
+        .with_schema("schema_name".to_owned())
+        .with_table("table_name".to_owned()))]
     );
 }
 
@@ -30,37 +35,40 @@ fn delete_all_records(mut sql_engine_with_schema: InMemorySqlEngine) {
     sql_engine_with_schema
         .execute("create table schema_name.table_name (column_test smallint);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("table created");
     sql_engine_with_schema
         .execute("insert into schema_name.table_name values (123);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("row inserted");
     sql_engine_with_schema
         .execute("insert into schema_name.table_name values (456);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("row inserted");
     assert_eq!(
         sql_engine_with_schema
             .execute("select * from schema_name.table_name;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsSelected((
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
             vec![("column_test".to_owned(), PostgreSqlType::SmallInt)],
             vec![vec!["123".to_owned()], vec!["456".to_owned()]]
-        )))
+        )
     );
     assert_eq!(
         sql_engine_with_schema
             .execute("delete from schema_name.table_name;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsDeleted(2))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsDeleted(2))]
     );
     assert_eq!(
         sql_engine_with_schema
             .execute("select * from schema_name.table_name;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsSelected((
-            vec![("column_test".to_owned(), PostgreSqlType::SmallInt)],
-            vec![]
-        )))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(vec![("column_test".to_owned(), PostgreSqlType::SmallInt)], vec![])
     );
 }
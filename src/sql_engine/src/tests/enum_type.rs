@@ -0,0 +1,294 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+fn create_type(sql_engine: &mut InMemorySqlEngine) {
+    sql_engine
+        .execute("create type schema_name.mood as enum ('sad', 'ok', 'happy');")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("type created");
+}
+
+#[rstest::rstest]
+fn create_enum_type(mut sql_engine_with_schema: InMemorySqlEngine) {
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("create type schema_name.mood as enum ('sad', 'ok', 'happy');")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::TypeCreated)]
+    );
+}
+
+#[rstest::rstest]
+fn create_type_twice(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_type(&mut sql_engine_with_schema);
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("create type schema_name.mood as enum ('other');")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::type_already_exists("mood".to_owned()))]
+    );
+}
+
+#[rstest::rstest]
+fn create_type_in_non_existent_schema(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        sql_engine
+            .execute("create type non_existent.mood as enum ('sad');")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::schema_does_not_exist("non_existent".to_owned()))]
+    );
+}
+
+#[rstest::rstest]
+fn create_type_without_schema(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        sql_engine
+            .execute("create type mood as enum ('sad', 'ok', 'happy');")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::schema_does_not_exist("public".to_owned()))]
+    );
+
+    sql_engine
+        .execute("create schema public;")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("schema created");
+    assert_eq!(
+        sql_engine
+            .execute("create type mood as enum ('sad', 'ok', 'happy');")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::TypeCreated)]
+    );
+    assert_eq!(
+        sql_engine
+            .execute("create table table_name (column_m mood);")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::TableCreated)]
+    );
+    assert_eq!(
+        sql_engine
+            .execute("drop type mood;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::dependent_objects_exist("type mood".to_owned()))]
+    );
+    assert_eq!(
+        sql_engine
+            .execute("drop table table_name; drop type mood;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::TableDropped), Ok(QueryEvent::TypeDropped)]
+    );
+}
+
+#[rstest::rstest]
+fn insert_and_select_enum_values(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_type(&mut sql_engine_with_schema);
+    create_table(
+        &mut sql_engine_with_schema,
+        "column_m mood, column_a schema_name.mood[]",
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("insert into schema_name.table_name values ('happy', '{sad,ok}'), ('sad', '{}');")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(2))]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("column_m".to_owned(), PostgreSqlType::Enum(16384)),
+                (
+                    "column_a".to_owned(),
+                    PostgreSqlType::Array(Box::new(PostgreSqlType::Enum(16384)))
+                ),
+            ],
+            vec![
+                vec!["happy".to_owned(), "{sad,ok}".to_owned()],
+                vec!["sad".to_owned(), "{}".to_owned()],
+            ]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn enum_values_are_ordered_by_labels(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_type(&mut sql_engine_with_schema);
+    create_table(&mut sql_engine_with_schema, "column_m mood");
+    sql_engine_with_schema
+        .execute("insert into schema_name.table_name values ('sad'), ('happy');")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("records inserted");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select column_m < 'ok' as less, column_m = 'happy' as equal from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("less".to_owned(), PostgreSqlType::Bool),
+                ("equal".to_owned(), PostgreSqlType::Bool)
+            ],
+            vec![
+                vec!["t".to_owned(), "f".to_owned()],
+                vec!["f".to_owned(), "t".to_owned()],
+            ]
+        )
+    );
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select 'sad'::schema_name.mood < 'happy'::schema_name.mood as less, 'ok'::schema_name.mood as m;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("less".to_owned(), PostgreSqlType::Bool),
+                ("m".to_owned(), PostgreSqlType::Enum(16384))
+            ],
+            vec![vec!["t".to_owned(), "ok".to_owned()]]
+        )
+    );
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select column_m < 'angry' from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::invalid_enum_value(
+            "mood".to_owned(),
+            "angry".to_owned()
+        ))]
+    );
+}
+
+#[rstest::rstest]
+fn insert_and_update_invalid_enum_value(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_type(&mut sql_engine_with_schema);
+    create_table(&mut sql_engine_with_schema, "column_m mood");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("insert into schema_name.table_name values ('Happy');")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::invalid_enum_value(
+            "mood".to_owned(),
+            "Happy".to_owned()
+        )
+        .with_schema("schema_name".to_owned())
+        .with_table("table_name".to_owned()))]
+    );
+
+    sql_engine_with_schema
+        .execute("insert into schema_name.table_name values ('ok');")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("record inserted");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("update schema_name.table_name set column_m = 'great';")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::invalid_enum_value(
+            "mood".to_owned(),
+            "great".to_owned()
+        )
+        .with_schema("schema_name".to_owned())
+        .with_table("table_name".to_owned()))]
+    );
+}
+
+#[rstest::rstest]
+fn create_table_with_non_existent_type(mut sql_engine_with_schema: InMemorySqlEngine) {
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("create table schema_name.table_name (column_m mood);")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::type_does_not_exist("mood".to_owned()))]
+    );
+}
+
+#[rstest::rstest]
+fn drop_type_used_by_table(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_type(&mut sql_engine_with_schema);
+    create_table(&mut sql_engine_with_schema, "column_m mood");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("drop type schema_name.mood;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::dependent_objects_exist(
+            "type schema_name.mood".to_owned()
+        ))]
+    );
+
+    sql_engine_with_schema
+        .execute("drop table schema_name.table_name;")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table dropped");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("drop type schema_name.mood;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::TypeDropped)]
+    );
+}
+
+#[rstest::rstest]
+fn drop_non_existent_type(mut sql_engine_with_schema: InMemorySqlEngine) {
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("drop type schema_name.mood;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::type_does_not_exist("schema_name.mood".to_owned()))]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("drop type if exists schema_name.mood;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![
+            Ok(QueryEvent::Notice(QueryError::type_does_not_exist_skipping(
+                "schema_name.mood".to_owned()
+            ))),
+            Ok(QueryEvent::TypeDropped)
+        ]
+    );
+}
@@ -0,0 +1,504 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+fn execute(sql_engine: &mut InMemorySqlEngine, sql: &str) {
+    sql_engine
+        .execute(sql)
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("query executed");
+}
+
+fn create_table(sql_engine: &mut InMemorySqlEngine) {
+    execute(
+        sql_engine,
+        "create table schema_name.table_name (column_i integer, column_s varchar(5));",
+    );
+}
+
+fn results(results: SystemResult<QueryResults>) -> Vec<QueryResult> {
+    results.expect("no system errors").collect()
+}
+
+#[rstest::rstest]
+fn insert_with_parameters(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_table(&mut sql_engine_with_schema);
+
+    assert_eq!(
+        results(sql_engine_with_schema.parse(
+            "".to_owned(),
+            "insert into schema_name.table_name values ($1, $2);".to_owned(),
+            vec![]
+        )),
+        vec![Ok(QueryEvent::ParseComplete)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.describe_statement("")),
+        vec![
+            Ok(QueryEvent::ParameterDescription(vec![
+                PostgreSqlType::Integer,
+                PostgreSqlType::VarChar(Some(5))
+            ])),
+            Ok(QueryEvent::NoData)
+        ]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.bind(
+            "".to_owned(),
+            "".to_owned(),
+            vec![],
+            vec![Some(b"1".to_vec()), Some(b"a'b".to_vec())],
+            vec![]
+        )),
+        vec![Ok(QueryEvent::BindComplete)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.describe_portal("")),
+        vec![Ok(QueryEvent::NoData)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.execute_portal("", 0)),
+        vec![Ok(QueryEvent::RecordsInserted(1))]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.bind(
+            "".to_owned(),
+            "".to_owned(),
+            vec![PostgreSqlFormat::Binary],
+            vec![Some(vec![0, 0, 0, 2]), Some(b"xy".to_vec())],
+            vec![]
+        )),
+        vec![Ok(QueryEvent::BindComplete)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.execute_portal("", 0)),
+        vec![Ok(QueryEvent::RecordsInserted(1))]
+    );
+
+    assert_eq!(
+        results(sql_engine_with_schema.execute("select * from schema_name.table_name;")),
+        vec![
+            Ok(QueryEvent::RowDescription(
+                vec![
+                    ("column_i".to_owned(), PostgreSqlType::Integer),
+                    ("column_s".to_owned(), PostgreSqlType::VarChar(Some(5))),
+                ],
+                vec![PostgreSqlFormat::Text; 2]
+            )),
+            Ok(QueryEvent::DataRow(vec![Some(b"1".to_vec()), Some(b"a'b".to_vec())])),
+            Ok(QueryEvent::DataRow(vec![Some(b"2".to_vec()), Some(b"xy".to_vec())])),
+            Ok(QueryEvent::RecordsSelected(2)),
+        ]
+    );
+}
+
+#[rstest::rstest]
+fn update_with_parameters(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_table(&mut sql_engine_with_schema);
+    execute(
+        &mut sql_engine_with_schema,
+        "insert into schema_name.table_name values (1, 'a');",
+    );
+
+    assert_eq!(
+        results(sql_engine_with_schema.parse(
+            "update".to_owned(),
+            "update schema_name.table_name set column_s = $1, column_i = $2 + 1;".to_owned(),
+            vec![]
+        )),
+        vec![Ok(QueryEvent::ParseComplete)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.describe_statement("update")),
+        vec![
+            Ok(QueryEvent::ParameterDescription(vec![
+                PostgreSqlType::VarChar(Some(5)),
+                PostgreSqlType::Integer
+            ])),
+            Ok(QueryEvent::NoData)
+        ]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.parse(
+            "update_with_types".to_owned(),
+            "update schema_name.table_name set column_s = $1, column_i = $2 + 1;".to_owned(),
+            vec![0, 23]
+        )),
+        vec![Ok(QueryEvent::ParseComplete)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.bind(
+            "".to_owned(),
+            "update_with_types".to_owned(),
+            vec![PostgreSqlFormat::Text, PostgreSqlFormat::Binary],
+            vec![Some(b"b".to_vec()), Some(vec![0, 0, 0, 41])],
+            vec![]
+        )),
+        vec![Ok(QueryEvent::BindComplete)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.execute_portal("", 0)),
+        vec![Ok(QueryEvent::RecordsUpdated(1))]
+    );
+
+    assert_eq!(
+        results(sql_engine_with_schema.execute("select * from schema_name.table_name;")),
+        selected(
+            vec![
+                ("column_i".to_owned(), PostgreSqlType::Integer),
+                ("column_s".to_owned(), PostgreSqlType::VarChar(Some(5))),
+            ],
+            vec![vec!["42".to_owned(), "b".to_owned()]]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn describe_select_statement(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_table(&mut sql_engine_with_schema);
+
+    assert_eq!(
+        results(sql_engine_with_schema.parse(
+            "table".to_owned(),
+            "select column_s from schema_name.table_name".to_owned(),
+            vec![]
+        )),
+        vec![Ok(QueryEvent::ParseComplete)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.describe_statement("table")),
+        vec![
+            Ok(QueryEvent::ParameterDescription(vec![])),
+            Ok(QueryEvent::RowDescription(
+                vec![("column_s".to_owned(), PostgreSqlType::VarChar(Some(5)))],
+                vec![PostgreSqlFormat::Text]
+            ))
+        ]
+    );
+
+    assert_eq!(
+        results(sql_engine_with_schema.parse(
+            "values".to_owned(),
+            "select $1::smallint, $2 as value, 1".to_owned(),
+            vec![0, 20]
+        )),
+        vec![Ok(QueryEvent::ParseComplete)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.describe_statement("values")),
+        vec![
+            Ok(QueryEvent::ParameterDescription(vec![
+                PostgreSqlType::SmallInt,
+                PostgreSqlType::BigInt
+            ])),
+            Ok(QueryEvent::RowDescription(
+                vec![
+                    ("?column?".to_owned(), PostgreSqlType::SmallInt),
+                    ("value".to_owned(), PostgreSqlType::BigInt),
+                    ("?column?".to_owned(), PostgreSqlType::Integer),
+                ],
+                vec![PostgreSqlFormat::Text; 3]
+            ))
+        ]
+    );
+}
+
+#[rstest::rstest]
+fn describe_expressions_of_columns(mut sql_engine_with_schema: InMemorySqlEngine) {
+    execute(
+        &mut sql_engine_with_schema,
+        "create table schema_name.table_name (a smallint, i integer[], jb jsonb);",
+    );
+
+    assert_eq!(
+        results(
+            sql_engine_with_schema.parse(
+                "".to_owned(),
+                "select a + $1 as sum, i[5] as element, jb -> 'a' as field, jb ->> 'a' as text \
+            from schema_name.table_name"
+                    .to_owned(),
+                vec![]
+            )
+        ),
+        vec![Ok(QueryEvent::ParseComplete)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.describe_statement("")),
+        vec![
+            Ok(QueryEvent::ParameterDescription(vec![PostgreSqlType::SmallInt])),
+            Ok(QueryEvent::RowDescription(
+                vec![
+                    ("sum".to_owned(), PostgreSqlType::SmallInt),
+                    ("element".to_owned(), PostgreSqlType::Integer),
+                    ("field".to_owned(), PostgreSqlType::Jsonb),
+                    ("text".to_owned(), PostgreSqlType::Text),
+                ],
+                vec![PostgreSqlFormat::Text; 4]
+            ))
+        ]
+    );
+}
+
+#[rstest::rstest]
+fn describe_casts_to_char(mut sql_engine_with_schema: InMemorySqlEngine) {
+    assert_eq!(
+        results(sql_engine_with_schema.parse(
+            "".to_owned(),
+            "select $1::char(3) as param, 'ab'::char(3) as literal".to_owned(),
+            vec![]
+        )),
+        vec![Ok(QueryEvent::ParseComplete)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.describe_statement("")),
+        vec![
+            Ok(QueryEvent::ParameterDescription(vec![PostgreSqlType::Char(3)])),
+            Ok(QueryEvent::RowDescription(
+                vec![
+                    ("param".to_owned(), PostgreSqlType::Char(3)),
+                    ("literal".to_owned(), PostgreSqlType::Char(3)),
+                ],
+                vec![PostgreSqlFormat::Text; 2]
+            ))
+        ]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.bind("".to_owned(), "".to_owned(), vec![], vec![Some(b"a".to_vec())], vec![])),
+        vec![Ok(QueryEvent::BindComplete)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.describe_portal("")),
+        vec![Ok(QueryEvent::RowDescription(
+            vec![
+                ("param".to_owned(), PostgreSqlType::Char(3)),
+                ("literal".to_owned(), PostgreSqlType::Char(3)),
+            ],
+            vec![PostgreSqlFormat::Text; 2]
+        ))]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.execute_portal("", 0)),
+        vec![
+            Ok(QueryEvent::DataRow(vec![Some(b"a  ".to_vec()), Some(b"ab ".to_vec())])),
+            Ok(QueryEvent::RecordsSelected(1)),
+        ]
+    );
+}
+
+#[rstest::rstest]
+fn execute_portal_with_row_limit(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_table(&mut sql_engine_with_schema);
+    execute(
+        &mut sql_engine_with_schema,
+        "insert into schema_name.table_name values (1, 'a'), (2, 'b'), (3, 'c');",
+    );
+
+    assert_eq!(
+        results(sql_engine_with_schema.parse(
+            "".to_owned(),
+            "select column_i from schema_name.table_name".to_owned(),
+            vec![]
+        )),
+        vec![Ok(QueryEvent::ParseComplete)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.bind(
+            "portal".to_owned(),
+            "".to_owned(),
+            vec![],
+            vec![],
+            vec![PostgreSqlFormat::Binary]
+        )),
+        vec![Ok(QueryEvent::BindComplete)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.describe_portal("portal")),
+        vec![Ok(QueryEvent::RowDescription(
+            vec![("column_i".to_owned(), PostgreSqlType::Integer)],
+            vec![PostgreSqlFormat::Binary]
+        ))]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.execute_portal("portal", 2)),
+        vec![
+            Ok(QueryEvent::DataRow(vec![Some(vec![0, 0, 0, 1])])),
+            Ok(QueryEvent::DataRow(vec![Some(vec![0, 0, 0, 2])])),
+            Ok(QueryEvent::PortalSuspended),
+        ]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.execute_portal("portal", 2)),
+        vec![
+            Ok(QueryEvent::DataRow(vec![Some(vec![0, 0, 0, 3])])),
+            Ok(QueryEvent::RecordsSelected(3)),
+        ]
+    );
+}
+
+#[rstest::rstest]
+fn close_statement_and_its_portals(mut sql_engine_with_schema: InMemorySqlEngine) {
+    assert_eq!(
+        results(sql_engine_with_schema.parse("stmt".to_owned(), "select 1".to_owned(), vec![])),
+        vec![Ok(QueryEvent::ParseComplete)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.bind("portal".to_owned(), "stmt".to_owned(), vec![], vec![], vec![])),
+        vec![Ok(QueryEvent::BindComplete)]
+    );
+
+    assert_eq!(
+        results(sql_engine_with_schema.close_statement("stmt")),
+        vec![Ok(QueryEvent::CloseComplete)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.describe_statement("stmt")),
+        vec![Err(QueryError::prepared_statement_does_not_exist("stmt".to_owned()))]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.execute_portal("portal", 0)),
+        vec![Err(QueryError::portal_does_not_exist("portal".to_owned()))]
+    );
+}
+
+#[rstest::rstest]
+fn portals_are_closed_by_sync_outside_of_transaction(mut sql_engine_with_schema: InMemorySqlEngine) {
+    let bind = |sql_engine: &mut InMemorySqlEngine| {
+        assert_eq!(
+            results(sql_engine.bind("".to_owned(), "".to_owned(), vec![], vec![], vec![])),
+            vec![Ok(QueryEvent::BindComplete)]
+        );
+    };
+    assert_eq!(
+        results(sql_engine_with_schema.parse("".to_owned(), "select 1".to_owned(), vec![])),
+        vec![Ok(QueryEvent::ParseComplete)]
+    );
+
+    bind(&mut sql_engine_with_schema);
+    sql_engine_with_schema.sync();
+    assert_eq!(
+        results(sql_engine_with_schema.execute_portal("", 0)),
+        vec![Err(QueryError::portal_does_not_exist("".to_owned()))]
+    );
+
+    execute(&mut sql_engine_with_schema, "begin;");
+    bind(&mut sql_engine_with_schema);
+    sql_engine_with_schema.sync();
+    assert_eq!(
+        results(sql_engine_with_schema.execute_portal("", 0)),
+        vec![
+            Ok(QueryEvent::DataRow(vec![Some(b"1".to_vec())])),
+            Ok(QueryEvent::RecordsSelected(1)),
+        ]
+    );
+}
+
+#[rstest::rstest]
+fn named_statement_and_portal_are_not_replaced(mut sql_engine_with_schema: InMemorySqlEngine) {
+    assert_eq!(
+        results(sql_engine_with_schema.parse("stmt".to_owned(), "select 1".to_owned(), vec![])),
+        vec![Ok(QueryEvent::ParseComplete)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.parse("stmt".to_owned(), "select 2".to_owned(), vec![])),
+        vec![Err(QueryError::duplicate_prepared_statement("stmt".to_owned()))]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.bind("portal".to_owned(), "stmt".to_owned(), vec![], vec![], vec![])),
+        vec![Ok(QueryEvent::BindComplete)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.bind("portal".to_owned(), "stmt".to_owned(), vec![], vec![], vec![])),
+        vec![Err(QueryError::duplicate_cursor("portal".to_owned()))]
+    );
+}
+
+#[rstest::rstest]
+fn parse_errors(mut sql_engine_with_schema: InMemorySqlEngine) {
+    assert_eq!(
+        results(sql_engine_with_schema.parse("".to_owned(), "select 1; select 2".to_owned(), vec![])),
+        vec![Err(QueryError::multiple_commands_in_prepared_statement())]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.parse("".to_owned(), "select $1".to_owned(), vec![-5])),
+        vec![Err(QueryError::type_does_not_exist("-5".to_owned()))]
+    );
+}
+
+#[rstest::rstest]
+fn bind_errors(mut sql_engine_with_schema: InMemorySqlEngine) {
+    assert_eq!(
+        results(sql_engine_with_schema.bind("".to_owned(), "stmt".to_owned(), vec![], vec![], vec![])),
+        vec![Err(QueryError::prepared_statement_does_not_exist("stmt".to_owned()))]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.parse("stmt".to_owned(), "select $1::integer".to_owned(), vec![])),
+        vec![Ok(QueryEvent::ParseComplete)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.bind("".to_owned(), "stmt".to_owned(), vec![], vec![], vec![])),
+        vec![Err(QueryError::protocol_violation(
+            "bind message supplies 0 parameters, but prepared statement \"stmt\" requires 1".to_owned()
+        ))]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.bind(
+            "".to_owned(),
+            "stmt".to_owned(),
+            vec![PostgreSqlFormat::Binary],
+            vec![Some(vec![0, 1])],
+            vec![]
+        )),
+        vec![Err(QueryError::invalid_binary_representation(1))]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.bind(
+            "".to_owned(),
+            "stmt".to_owned(),
+            vec![],
+            vec![Some(b"abc".to_vec())],
+            vec![]
+        )),
+        vec![Ok(QueryEvent::BindComplete)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.execute_portal("", 0)),
+        vec![Err(QueryError::invalid_text_representation(
+            "integer".to_owned(),
+            "abc".to_owned()
+        ))]
+    );
+}
+
+#[rstest::rstest]
+fn empty_statement(mut sql_engine_with_schema: InMemorySqlEngine) {
+    assert_eq!(
+        results(sql_engine_with_schema.parse("".to_owned(), "".to_owned(), vec![])),
+        vec![Ok(QueryEvent::ParseComplete)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.describe_statement("")),
+        vec![Ok(QueryEvent::ParameterDescription(vec![])), Ok(QueryEvent::NoData)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.bind("".to_owned(), "".to_owned(), vec![], vec![], vec![])),
+        vec![Ok(QueryEvent::BindComplete)]
+    );
+    assert_eq!(
+        results(sql_engine_with_schema.execute_portal("", 0)),
+        vec![Ok(QueryEvent::EmptyQuery)]
+    );
+}
@@ -20,8 +20,13 @@ fn insert_into_nonexistent_table(mut sql_engine_with_schema: InMemorySqlEngine)
     assert_eq!(
         sql_engine_with_schema
             .execute("insert into schema_name.table_name values (123);")
-            .expect("no system errors"),
-        Err(QueryError::table_does_not_exist("schema_name.table_name".to_owned()))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::table_does_not_exist(
+            "schema_name.table_name".to_owned()
+        )
+        .with_schema("schema_name".to_owned())
+        .with_table("table_name".to_owned()))]
     );
 }
 
@@ -30,22 +35,25 @@ fn insert_and_select_single_row(mut sql_engine_with_schema: InMemorySqlEngine) {
     sql_engine_with_schema
         .execute("create table schema_name.table_name (column_test smallint);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("table created");
 
     assert_eq!(
         sql_engine_with_schema
             .execute("insert into schema_name.table_name values (123);")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsInserted(1))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(1))]
     );
     assert_eq!(
         sql_engine_with_schema
             .execute("select * from schema_name.table_name;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsSelected((
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
             vec![("column_test".to_owned(), PostgreSqlType::SmallInt)],
             vec![vec!["123".to_owned()]]
-        )))
+        )
     );
 }
 
@@ -54,35 +62,111 @@ fn insert_and_select_multiple_rows(mut sql_engine_with_schema: InMemorySqlEngine
     sql_engine_with_schema
         .execute("create table schema_name.table_name (column_test smallint);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("table created");
     sql_engine_with_schema
         .execute("insert into schema_name.table_name values (123);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("row inserted");
 
     assert_eq!(
         sql_engine_with_schema
             .execute("select * from schema_name.table_name;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsSelected((
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
             vec![("column_test".to_owned(), PostgreSqlType::SmallInt)],
             vec![vec!["123".to_owned()]]
-        )))
+        )
     );
 
     sql_engine_with_schema
         .execute("insert into schema_name.table_name values (456);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("row inserted");
 
     assert_eq!(
         sql_engine_with_schema
             .execute("select * from schema_name.table_name;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsSelected((
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
             vec![("column_test".to_owned(), PostgreSqlType::SmallInt)],
             vec![vec!["123".to_owned()], vec!["456".to_owned()]]
-        )))
+        )
+    );
+}
+
+#[rstest::rstest]
+fn select_null_for_columns_without_values(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (col1 smallint, col2 smallint);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+    sql_engine_with_schema
+        .execute("insert into schema_name.table_name (col2) values (1);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("row inserted");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![
+            Ok(QueryEvent::RowDescription(
+                vec![
+                    ("col1".to_owned(), PostgreSqlType::SmallInt),
+                    ("col2".to_owned(), PostgreSqlType::SmallInt),
+                ],
+                vec![PostgreSqlFormat::Text; 2]
+            )),
+            Ok(QueryEvent::DataRow(vec![None, Some(b"1".to_vec())])),
+            Ok(QueryEvent::RecordsSelected(1)),
+        ]
+    );
+}
+
+#[rstest::rstest]
+fn insert_null_values(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_table(
+        &mut sql_engine_with_schema,
+        "col1 smallint, col2 varchar(5), col3 timestamp with time zone",
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("insert into schema_name.table_name values (NULL, NULL, NULL), (1, 'a', NULL);")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(2))]
+    );
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select col1, col2, col3 from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![
+            Ok(QueryEvent::RowDescription(
+                vec![
+                    ("col1".to_owned(), PostgreSqlType::SmallInt),
+                    ("col2".to_owned(), PostgreSqlType::VarChar(Some(5))),
+                    ("col3".to_owned(), PostgreSqlType::TimestampWithTimeZone),
+                ],
+                vec![PostgreSqlFormat::Text; 3]
+            )),
+            Ok(QueryEvent::DataRow(vec![None, None, None])),
+            Ok(QueryEvent::DataRow(vec![
+                Some(b"1".to_vec()),
+                Some(b"a".to_vec()),
+                None
+            ])),
+            Ok(QueryEvent::RecordsSelected(2)),
+        ]
     );
 }
 
@@ -91,17 +175,20 @@ fn insert_and_select_named_columns(mut sql_engine_with_schema: InMemorySqlEngine
     sql_engine_with_schema
         .execute("create table schema_name.table_name (col1 smallint, col2 smallint, col3 smallint);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("table created");
     sql_engine_with_schema
         .execute("insert into schema_name.table_name (col2, col3, col1) values (1, 2, 3), (4, 5, 6);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("row inserted");
 
     assert_eq!(
         sql_engine_with_schema
             .execute("select * from schema_name.table_name;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsSelected((
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
             vec![
                 ("col1".to_owned(), PostgreSqlType::SmallInt),
                 ("col2".to_owned(), PostgreSqlType::SmallInt),
@@ -111,7 +198,7 @@ fn insert_and_select_named_columns(mut sql_engine_with_schema: InMemorySqlEngine
                 vec!["3".to_owned(), "1".to_owned(), "2".to_owned()],
                 vec!["6".to_owned(), "4".to_owned(), "5".to_owned()],
             ]
-        )))
+        )
     );
 }
 
@@ -120,19 +207,22 @@ fn insert_multiple_rows(mut sql_engine_with_schema: InMemorySqlEngine) {
     sql_engine_with_schema
         .execute("create table schema_name.table_name (column_1 smallint, column_2 smallint, column_3 smallint);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("table created");
 
     assert_eq!(
         sql_engine_with_schema
             .execute("insert into schema_name.table_name values (1, 4, 7), (2, 5, 8), (3, 6, 9);")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsInserted(3))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(3))]
     );
     assert_eq!(
         sql_engine_with_schema
             .execute("select * from schema_name.table_name;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsSelected((
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
             vec![
                 ("column_1".to_owned(), PostgreSqlType::SmallInt),
                 ("column_2".to_owned(), PostgreSqlType::SmallInt),
@@ -143,7 +233,7 @@ fn insert_multiple_rows(mut sql_engine_with_schema: InMemorySqlEngine) {
                 vec!["2".to_owned(), "5".to_owned(), "8".to_owned()],
                 vec!["3".to_owned(), "6".to_owned(), "9".to_owned()],
             ]
-        )))
+        )
     );
 }
 
@@ -152,27 +242,31 @@ fn insert_and_select_different_integer_types(mut sql_engine_with_schema: InMemor
     sql_engine_with_schema
         .execute("create table schema_name.table_name (column_si smallint, column_i integer, column_bi bigint);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("table created");
 
     assert_eq!(
         sql_engine_with_schema
             .execute("insert into schema_name.table_name values(-32768, -2147483648, -9223372036854775808);")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsInserted(1))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(1))]
     );
 
     assert_eq!(
         sql_engine_with_schema
             .execute("insert into schema_name.table_name values(32767, 2147483647, 9223372036854775807);")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsInserted(1))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(1))]
     );
 
     assert_eq!(
         sql_engine_with_schema
             .execute("select * from schema_name.table_name;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsSelected((
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
             vec![
                 ("column_si".to_owned(), PostgreSqlType::SmallInt),
                 ("column_i".to_owned(), PostgreSqlType::Integer),
@@ -190,7 +284,7 @@ fn insert_and_select_different_integer_types(mut sql_engine_with_schema: InMemor
                     "9223372036854775807".to_owned()
                 ],
             ]
-        )))
+        )
     )
 }
 
@@ -199,35 +293,475 @@ fn insert_and_select_different_character_types(mut sql_engine_with_schema: InMem
     sql_engine_with_schema
         .execute("create table schema_name.table_name (column_c char(10), column_vc varchar(10));")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("table created");
 
     assert_eq!(
         sql_engine_with_schema
             .execute("insert into schema_name.table_name values('12345abcde', '12345abcde');")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsInserted(1))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(1))]
     );
 
     assert_eq!(
         sql_engine_with_schema
             .execute("insert into schema_name.table_name values('12345abcde', 'abcde');")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsInserted(1))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(1))]
     );
 
     assert_eq!(
         sql_engine_with_schema
             .execute("select * from schema_name.table_name;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsSelected((
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
             vec![
-                ("column_c".to_owned(), PostgreSqlType::Char),
-                ("column_vc".to_owned(), PostgreSqlType::VarChar)
+                ("column_c".to_owned(), PostgreSqlType::Char(10)),
+                ("column_vc".to_owned(), PostgreSqlType::VarChar(Some(10)))
             ],
             vec![
                 vec!["12345abcde".to_owned(), "12345abcde".to_owned()],
                 vec!["12345abcde".to_owned(), "abcde".to_owned()],
             ]
-        )))
+        )
+    )
+}
+
+#[rstest::rstest]
+fn character_types_lengths_are_in_characters(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (column_c char(5), column_vc varchar(5));")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("insert into schema_name.table_name values ('мир', 'привіт'), ('ab   ', 'абвгд');")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::value_too_long("character varying(5)".to_owned())
+            .with_column("column_vc".to_owned())
+            .with_schema("schema_name".to_owned())
+            .with_table("table_name".to_owned()))]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("insert into schema_name.table_name values ('мир', 'приві'), ('ab   ', 'абвгд');")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(2))]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("column_c".to_owned(), PostgreSqlType::Char(5)),
+                ("column_vc".to_owned(), PostgreSqlType::VarChar(Some(5)))
+            ],
+            vec![
+                vec!["мир  ".to_owned(), "приві".to_owned()],
+                vec!["ab   ".to_owned(), "абвгд".to_owned()],
+            ]
+        )
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select 'ab '::char(3) = 'ab'::char(5) as equal;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![("equal".to_owned(), PostgreSqlType::Bool)],
+            vec![vec!["t".to_owned()]]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn char_comparisons_ignore_trailing_spaces(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (column_c char(3), column_t text);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+    sql_engine_with_schema
+        .execute("insert into schema_name.table_name values ('ab', 'ab');")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("record inserted");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute(
+                "select column_c = 'ab  ' as char_equal, 'ab  ' > column_c as char_greater, \
+                column_t = 'ab  ' as text_equal from schema_name.table_name;"
+            )
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("char_equal".to_owned(), PostgreSqlType::Bool),
+                ("char_greater".to_owned(), PostgreSqlType::Bool),
+                ("text_equal".to_owned(), PostgreSqlType::Bool)
+            ],
+            vec![vec!["t".to_owned(), "f".to_owned(), "f".to_owned()]]
+        )
+    );
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select 'ab'::char(3) = 'ab  ' as equal;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![("equal".to_owned(), PostgreSqlType::Bool)],
+            vec![vec!["t".to_owned()]]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn insert_and_select_unbounded_text(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (column_t text, column_vc varchar);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+
+    let large = "abc ".repeat(10_000);
+    assert_eq!(
+        sql_engine_with_schema
+            .execute(format!("insert into schema_name.table_name values ('{}', '{}');", large, large).as_str())
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(1))]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("column_t".to_owned(), PostgreSqlType::Text),
+                ("column_vc".to_owned(), PostgreSqlType::VarChar(None))
+            ],
+            vec![vec![large.clone(), large]]
+        )
     )
 }
+
+#[rstest::rstest]
+fn insert_and_select_bytea(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (column_b bytea);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+
+    let large = "\\x".to_owned() + "00ff".repeat(10_000).as_str();
+    assert_eq!(
+        sql_engine_with_schema
+            .execute(
+                format!(
+                    "insert into schema_name.table_name values ('\\xDEADbeef'), ('a\\\\b\\000'), ('{}');",
+                    large
+                )
+                .as_str()
+            )
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(3))]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![("column_b".to_owned(), PostgreSqlType::Bytea)],
+            vec![
+                vec!["\\xdeadbeef".to_owned()],
+                vec!["\\x615c6200".to_owned()],
+                vec![large],
+            ]
+        )
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("set bytea_output = 'escape';")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::VariableSet)]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("show bytea_output;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![("bytea_output".to_owned(), PostgreSqlType::VarChar(None))],
+            vec![vec!["escape".to_owned()]]
+        )
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("delete from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsDeleted(3))]
+    );
+
+    sql_engine_with_schema
+        .execute("insert into schema_name.table_name values ('\\xDEADbeef'), ('a\\\\b\\000');")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("records inserted");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![("column_b".to_owned(), PostgreSqlType::Bytea)],
+            vec![vec!["\\336\\255\\276\\357".to_owned()], vec!["a\\\\b\\000".to_owned()],]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn set_invalid_bytea_output(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        sql_engine
+            .execute("set bytea_output = 'base64';")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::invalid_parameter_value(
+            "bytea_output".to_owned(),
+            "base64".to_owned()
+        ))]
+    );
+}
+
+#[rstest::rstest]
+fn insert_and_select_booleans(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (column_b boolean);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("insert into schema_name.table_name values (true), (FALSE), ('yes'), ('off'), ('t');")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(5))]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![("column_b".to_owned(), PostgreSqlType::Bool)],
+            vec![
+                vec!["t".to_owned()],
+                vec!["f".to_owned()],
+                vec!["t".to_owned()],
+                vec!["f".to_owned()],
+                vec!["t".to_owned()],
+            ]
+        )
+    )
+}
+
+#[rstest::rstest]
+fn insert_and_select_floats(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (column_r real, column_d double precision, column_b boolean);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute(
+                "insert into schema_name.table_name values \
+                 (1.5, -0.1, 1.5 > 1), ('NaN', '1e300', 'NaN' = 'NaN'), ('-Infinity', 1.5 * 2, 1 > 2);"
+            )
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(3))]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("column_r".to_owned(), PostgreSqlType::Real),
+                ("column_d".to_owned(), PostgreSqlType::DoublePrecision),
+                ("column_b".to_owned(), PostgreSqlType::Bool),
+            ],
+            vec![
+                vec!["1.5".to_owned(), "-0.1".to_owned(), "t".to_owned()],
+                vec!["NaN".to_owned(), "1e+300".to_owned(), "t".to_owned()],
+                vec!["-Infinity".to_owned(), "3".to_owned(), "f".to_owned()],
+            ]
+        )
+    )
+}
+
+#[rstest::rstest]
+fn insert_expression_errors(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (column_d double precision);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("insert into schema_name.table_name values (1.5 / 0);")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::division_by_zero())]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("insert into schema_name.table_name values (2147483647 + 1);")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::out_of_range("integer".to_owned()))]
+    );
+}
+
+#[rstest::rstest]
+fn insert_and_select_numerics(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (column_p numeric(5, 2), column_i numeric(3), column_n numeric);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute(
+                "insert into schema_name.table_name values \
+                 (1.235, 12.5, 0.1 + 0.2), (-999.994, '-999', 12345678901234567890.123456789), (7, 0, 1 / 3.0);"
+            )
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(3))]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("column_p".to_owned(), PostgreSqlType::Decimal),
+                ("column_i".to_owned(), PostgreSqlType::Decimal),
+                ("column_n".to_owned(), PostgreSqlType::Decimal),
+            ],
+            vec![
+                vec!["1.24".to_owned(), "13".to_owned(), "0.3".to_owned()],
+                vec![
+                    "-999.99".to_owned(),
+                    "-999".to_owned(),
+                    "12345678901234567890.123456789".to_owned()
+                ],
+                vec!["7.00".to_owned(), "0".to_owned(), "0.33333333333333333333".to_owned()],
+            ]
+        )
+    )
+}
+
+#[rstest::rstest]
+fn insert_numeric_that_does_not_fit_into_precision(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (column_p numeric(5, 2));")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("insert into schema_name.table_name values (999.995);")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::numeric_field_overflow()
+            .with_column("column_p".to_owned())
+            .with_schema("schema_name".to_owned())
+            .with_table("table_name".to_owned()))]
+    );
+}
+
+#[rstest::rstest(
+    values,
+    error,
+    case::smallint_out_of_range(
+        "('1', '32768', 'a')",
+        QueryError::out_of_range("smallint".to_owned()).with_column("column_si".to_owned())
+.with_schema("schema_name".to_owned())
+.with_table("table_name".to_owned())
+    ),
+    case::not_an_integer(
+        "('abc', '1', 'a')",
+        QueryError::invalid_text_representation("integer".to_owned(), "abc".to_owned()).with_column("column_i".to_owned())
+.with_schema("schema_name".to_owned())
+.with_table("table_name".to_owned())
+    ),
+    case::value_too_long(
+        "('1', '1', 'abcdef')",
+        QueryError::value_too_long("character varying(5)".to_owned()).with_column("column_v".to_owned())
+.with_schema("schema_name".to_owned())
+.with_table("table_name".to_owned())
+    ),
+    case::first_violation_is_reported(
+        "('1', '1', 'a'), ('2', '40000', 'abcdef'), ('x', '1', 'a')",
+        QueryError::out_of_range("smallint".to_owned()).with_column("column_si".to_owned())
+.with_schema("schema_name".to_owned())
+.with_table("table_name".to_owned())
+    )
+)]
+fn insert_values_violating_column_types(
+    mut sql_engine_with_schema: InMemorySqlEngine,
+    values: &str,
+    error: QueryError,
+) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (column_i integer, column_si smallint, column_v varchar(5));")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute(format!("insert into schema_name.table_name values {};", values).as_str())
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(error)]
+    );
+}
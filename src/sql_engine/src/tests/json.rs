@@ -0,0 +1,226 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+#[rstest::rstest]
+fn insert_and_select_json_and_jsonb(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_table(&mut sql_engine_with_schema, "column_j json, column_jb jsonb");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute(
+                r#"insert into schema_name.table_name values ('{"b": 1,  "a": [true, null]}', '{"b": 1,  "a": [true, null], "b": 2.50}');"#
+            )
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(1))]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("column_j".to_owned(), PostgreSqlType::Json),
+                ("column_jb".to_owned(), PostgreSqlType::Jsonb),
+            ],
+            vec![vec![
+                r#"{"b": 1,  "a": [true, null]}"#.to_owned(),
+                r#"{"a": [true, null], "b": 2.50}"#.to_owned(),
+            ]]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn json_operators(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_table(
+        &mut sql_engine_with_schema,
+        "field jsonb, element text, path jsonb, path_text text, contains boolean, key_exists boolean",
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute(
+                r#"insert into schema_name.table_name values (
+                 '{"a": {"b": [1, 2]}}' -> 'a',
+                 '["x", "y", "z"]' ->> -1,
+                 '{"a": {"b": [1, 2]}}' #> '{a,b,0}',
+                 '{"a": {"b": "c"}}' #>> '{a,b}',
+                 '{"a": 1, "b": [1, 2]}' @> '{"b": [2]}',
+                 jsonb_build_array('k', 'l') ? 'l'
+                 );"#
+            )
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(1))]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("field".to_owned(), PostgreSqlType::Jsonb),
+                ("element".to_owned(), PostgreSqlType::Text),
+                ("path".to_owned(), PostgreSqlType::Jsonb),
+                ("path_text".to_owned(), PostgreSqlType::Text),
+                ("contains".to_owned(), PostgreSqlType::Bool),
+                ("key_exists".to_owned(), PostgreSqlType::Bool),
+            ],
+            vec![vec![
+                r#"{"b": [1, 2]}"#.to_owned(),
+                "z".to_owned(),
+                "1".to_owned(),
+                "c".to_owned(),
+                "t".to_owned(),
+                "t".to_owned(),
+            ]]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn build_json(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_table(&mut sql_engine_with_schema, "column_j json, column_jb jsonb");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute(
+                "insert into schema_name.table_name values \
+                 (json_build_object('b', 1, 'a', 'x'), jsonb_build_object('b', 1, 'a', 'x', 'c', jsonb_build_array(1.5, true))), \
+                 (json_build_array(1, 'x'), jsonb_build_object('a', jsonb_build_object('b', 2)) -> 'a');"
+            )
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(2))]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("column_j".to_owned(), PostgreSqlType::Json),
+                ("column_jb".to_owned(), PostgreSqlType::Jsonb),
+            ],
+            vec![
+                vec![
+                    r#"{"b" : 1, "a" : "x"}"#.to_owned(),
+                    r#"{"a": "x", "b": 1, "c": [1.5, true]}"#.to_owned(),
+                ],
+                vec![r#"[1, "x"]"#.to_owned(), r#"{"b": 2}"#.to_owned()],
+            ]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn json_operators_are_unnamed_columns(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        sql_engine
+            .execute(r#"select '{"a": 1}'::jsonb -> 'a', '{"a": {"b": "x"}}'::jsonb #>> '{a,b}';"#)
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("?column?".to_owned(), PostgreSqlType::Jsonb),
+                ("?column?".to_owned(), PostgreSqlType::Text),
+            ],
+            vec![vec!["1".to_owned(), "x".to_owned()]]
+        )
+    );
+}
+
+#[rstest::rstest(
+    value,
+    error,
+    case::invalid_json("'{' -> 'a'", QueryError::invalid_text_representation("jsonb".to_owned(), "{".to_owned())),
+    case::odd_number_of_arguments("jsonb_build_object('a')", QueryError::odd_number_of_arguments()),
+    case::null_key("jsonb_build_object(NULL, 1)", QueryError::null_object_key()),
+    case::undefined_operator(
+        "jsonb_build_array() ? 1",
+        QueryError::undefined_operator("jsonb ? integer".to_owned())
+    )
+)]
+fn json_errors(mut sql_engine_with_schema: InMemorySqlEngine, value: &str, error: QueryError) {
+    create_table(&mut sql_engine_with_schema, "column_jb jsonb");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute(format!("insert into schema_name.table_name values ({});", value).as_str())
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(error)]
+    );
+}
+
+#[rstest::rstest]
+fn aggregate_json(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_table(&mut sql_engine_with_schema, "id integer, name varchar(10)");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select jsonb_agg(id), json_agg(name) from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![
+            Ok(QueryEvent::RowDescription(
+                vec![
+                    ("jsonb_agg".to_owned(), PostgreSqlType::Jsonb),
+                    ("json_agg".to_owned(), PostgreSqlType::Json),
+                ],
+                vec![PostgreSqlFormat::Text; 2]
+            )),
+            Ok(QueryEvent::DataRow(vec![None, None])),
+            Ok(QueryEvent::RecordsSelected(1)),
+        ]
+    );
+
+    sql_engine_with_schema
+        .execute("insert into schema_name.table_name values (1, 'first'), (2, NULL);")
+        .expect("no system errors")
+        .for_each(drop);
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select jsonb_agg(id * 10), json_agg(name) from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("jsonb_agg".to_owned(), PostgreSqlType::Jsonb),
+                ("json_agg".to_owned(), PostgreSqlType::Json),
+            ],
+            vec![vec!["[10, 20]".to_owned(), r#"["first", null]"#.to_owned()]]
+        )
+    );
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select jsonb_agg(id), name from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::not_supported_operation(
+            "select jsonb_agg(id), name from schema_name.table_name;".to_owned()
+        ))]
+    );
+}
@@ -0,0 +1,228 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+use test_helpers::in_memory_backend_storage::InMemoryStorage;
+
+#[rstest::rstest]
+fn lock_table_outside_of_transaction(storage_with_table: Arc<FrontendStorage<InMemoryStorage>>) {
+    let mut sql_engine = Handler::new(storage_with_table);
+
+    assert_eq!(
+        sql_engine
+            .execute("lock table schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::no_active_transaction("LOCK TABLE".to_owned()))]
+    );
+}
+
+#[rstest::rstest]
+fn lock_nonexistent_table(storage_with_table: Arc<FrontendStorage<InMemoryStorage>>) {
+    let mut sql_engine = Handler::new(storage_with_table);
+    sql_engine
+        .execute("begin;")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("transaction started");
+
+    assert_eq!(
+        sql_engine
+            .execute("lock table schema_name.non_existent;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::table_does_not_exist(
+            "schema_name.non_existent".to_owned()
+        )
+        .with_schema("schema_name".to_owned())
+        .with_table("non_existent".to_owned()))]
+    );
+}
+
+#[rstest::rstest]
+fn lock_is_held_until_end_of_transaction(storage_with_table: Arc<FrontendStorage<InMemoryStorage>>) {
+    let mut first = Handler::new(storage_with_table.clone());
+    let mut second = Handler::new(storage_with_table);
+
+    first
+        .execute("begin;")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("transaction started");
+    assert_eq!(
+        first
+            .execute("lock table schema_name.table_name in share mode;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::TableLocked)]
+    );
+
+    assert_eq!(
+        second
+            .execute("select * from schema_name.table_name for update nowait;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::lock_not_available("table_name".to_owned()))]
+    );
+    assert_eq!(
+        second
+            .execute("select * from schema_name.table_name for share nowait;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(vec![("column_test".to_owned(), PostgreSqlType::SmallInt)], vec![])
+    );
+
+    assert_eq!(
+        first
+            .execute("commit;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::TransactionCommitted)]
+    );
+    assert_eq!(
+        second
+            .execute("select * from schema_name.table_name for update nowait;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(vec![("column_test".to_owned(), PostgreSqlType::SmallInt)], vec![])
+    );
+}
+
+#[rstest::rstest]
+fn select_for_update_blocks_writers_until_rollback(storage_with_table: Arc<FrontendStorage<InMemoryStorage>>) {
+    let mut first = Handler::new(storage_with_table.clone());
+    let mut second = Handler::new(storage_with_table);
+
+    first
+        .execute("begin;")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("transaction started");
+    first
+        .execute("select * from schema_name.table_name for update;")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("records selected");
+
+    second
+        .execute("set lock_timeout = '50ms';")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("variable set");
+    assert_eq!(
+        second
+            .execute("insert into schema_name.table_name values (123);")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::lock_timeout())]
+    );
+
+    assert_eq!(
+        first
+            .execute("rollback;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::TransactionRolledBack)]
+    );
+    assert_eq!(
+        second
+            .execute("insert into schema_name.table_name values (123);")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(1))]
+    );
+}
+
+#[rstest::rstest]
+fn locks_are_released_after_statement_outside_of_transaction(
+    storage_with_table: Arc<FrontendStorage<InMemoryStorage>>,
+) {
+    let mut first = Handler::new(storage_with_table.clone());
+    let mut second = Handler::new(storage_with_table);
+
+    first
+        .execute("select * from schema_name.table_name for update;")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("records selected");
+
+    assert_eq!(
+        second
+            .execute("select * from schema_name.table_name for update nowait;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(vec![("column_test".to_owned(), PostgreSqlType::SmallInt)], vec![])
+    );
+}
+
+#[rstest::rstest]
+fn locks_are_released_after_query_with_multiple_statements(storage_with_table: Arc<FrontendStorage<InMemoryStorage>>) {
+    let mut first = Handler::new(storage_with_table.clone());
+    let mut second = Handler::new(storage_with_table);
+
+    first
+        .execute("insert into schema_name.table_name values (1); select * from schema_name.table_name for update;")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("records inserted and selected");
+
+    assert_eq!(
+        second
+            .execute("select * from schema_name.table_name for update nowait;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![("column_test".to_owned(), PostgreSqlType::SmallInt)],
+            vec![vec!["1".to_owned()]]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn locks_are_held_until_selected_records_are_consumed(storage_with_table: Arc<FrontendStorage<InMemoryStorage>>) {
+    let mut first = Handler::new(storage_with_table.clone());
+    let mut second = Handler::new(storage_with_table);
+
+    let mut results = first
+        .execute("select * from schema_name.table_name for update;")
+        .expect("no system errors");
+    assert_eq!(
+        results.next(),
+        Some(Ok(QueryEvent::RowDescription(
+            vec![("column_test".to_owned(), PostgreSqlType::SmallInt)],
+            vec![PostgreSqlFormat::Text]
+        )))
+    );
+
+    assert_eq!(
+        second
+            .execute("select * from schema_name.table_name for update nowait;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::lock_not_available("table_name".to_owned()))]
+    );
+
+    assert_eq!(
+        results.collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsSelected(0))]
+    );
+    assert_eq!(
+        second
+            .execute("select * from schema_name.table_name for update nowait;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(vec![("column_test".to_owned(), PostgreSqlType::SmallInt)], vec![])
+    );
+}
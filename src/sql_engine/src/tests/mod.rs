@@ -12,27 +12,54 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(test)]
+mod array;
+#[cfg(test)]
+mod cast;
+#[cfg(test)]
+mod datetime;
 #[cfg(test)]
 mod delete;
 #[cfg(test)]
+mod enum_type;
+#[cfg(test)]
+mod extended_query;
+#[cfg(test)]
 mod insert;
 #[cfg(test)]
+mod json;
+#[cfg(test)]
+mod lock;
+#[cfg(test)]
+mod multiple_statements;
+#[cfg(test)]
+mod prepared_statement;
+#[cfg(test)]
 mod schema;
 #[cfg(test)]
 mod select;
 #[cfg(test)]
+mod sequence;
+#[cfg(test)]
+mod syntax_error;
+#[cfg(test)]
 mod table;
 #[cfg(test)]
+mod transaction;
+#[cfg(test)]
 mod update;
+#[cfg(test)]
+mod uuid;
 
 use super::*;
 use crate::Handler;
-use std::sync::{Arc, Mutex};
+use protocol::{results::Description, sql_types::PostgreSqlFormat};
+use std::sync::Arc;
 use storage::frontend::FrontendStorage;
 use test_helpers::in_memory_backend_storage::InMemoryStorage;
 
-fn in_memory_storage() -> Arc<Mutex<FrontendStorage<InMemoryStorage>>> {
-    Arc::new(Mutex::new(FrontendStorage::new(InMemoryStorage::default()).unwrap()))
+fn in_memory_storage() -> Arc<FrontendStorage<InMemoryStorage>> {
+    Arc::new(FrontendStorage::new(InMemoryStorage::default()).unwrap())
 }
 
 type InMemorySqlEngine = Handler<InMemoryStorage>;
@@ -47,7 +74,46 @@ fn sql_engine_with_schema(mut sql_engine: InMemorySqlEngine) -> InMemorySqlEngin
     sql_engine
         .execute("create schema schema_name;")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("schema created");
+
+    sql_engine
+}
+
+#[rstest::fixture]
+fn storage_with_table() -> Arc<FrontendStorage<InMemoryStorage>> {
+    let storage = in_memory_storage();
+    let mut sql_engine = Handler::new(storage.clone());
+    sql_engine
+        .execute("create schema schema_name;")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("schema created");
+    sql_engine
+        .execute("create table schema_name.table_name (column_test smallint);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+    storage
+}
 
+fn create_table(sql_engine: &mut InMemorySqlEngine, columns: &str) {
     sql_engine
+        .execute(format!("create table schema_name.table_name ({});", columns).as_str())
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+}
+
+fn selected(description: Description, records: Vec<Vec<String>>) -> Vec<QueryResult> {
+    let selected = records.len();
+    let formats = vec![PostgreSqlFormat::Text; description.len()];
+    let mut results = vec![Ok(QueryEvent::RowDescription(description, formats))];
+    results.extend(records.into_iter().map(|record| {
+        Ok(QueryEvent::DataRow(
+            record.into_iter().map(|value| Some(value.into_bytes())).collect(),
+        ))
+    }));
+    results.push(Ok(QueryEvent::RecordsSelected(selected)));
+    results
 }
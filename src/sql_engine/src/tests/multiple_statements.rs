@@ -0,0 +1,197 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+fn execute(sql_engine: &mut InMemorySqlEngine, sql: &str) -> Vec<QueryResult> {
+    sql_engine.execute(sql).expect("no system errors").collect()
+}
+
+#[rstest::fixture]
+fn with_table(mut sql_engine_with_schema: InMemorySqlEngine) -> InMemorySqlEngine {
+    execute(
+        &mut sql_engine_with_schema,
+        "create table schema_name.table_name (column_test smallint);",
+    );
+    sql_engine_with_schema
+}
+
+fn selected_values(values: Vec<&str>) -> Vec<QueryResult> {
+    selected(
+        vec![("column_test".to_owned(), PostgreSqlType::SmallInt)],
+        values.into_iter().map(|value| vec![value.to_owned()]).collect(),
+    )
+}
+
+#[rstest::rstest]
+fn statements_are_executed_in_order(mut sql_engine: InMemorySqlEngine) {
+    let mut expected = vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(2)),
+    ];
+    expected.extend(selected_values(vec!["1", "2"]));
+
+    assert_eq!(
+        execute(
+            &mut sql_engine,
+            "create schema schema_name; \
+             create table schema_name.table_name (column_test smallint); \
+             insert into schema_name.table_name values (1), (2); \
+             select * from schema_name.table_name;"
+        ),
+        expected
+    );
+}
+
+#[rstest::rstest]
+fn execution_stops_at_first_error(mut with_table: InMemorySqlEngine) {
+    assert_eq!(
+        execute(
+            &mut with_table,
+            "insert into schema_name.table_name values (1); \
+             insert into schema_name.non_existent values (2); \
+             insert into schema_name.table_name values (3);"
+        ),
+        vec![
+            Ok(QueryEvent::RecordsInserted(1)),
+            Err(QueryError::table_does_not_exist("schema_name.non_existent".to_owned())
+                .with_schema("schema_name".to_owned())
+                .with_table("non_existent".to_owned()))
+        ]
+    );
+
+    assert_eq!(
+        execute(&mut with_table, "select * from schema_name.table_name;"),
+        selected_values(vec![])
+    );
+}
+
+#[rstest::rstest]
+fn statements_are_committed_together(mut with_table: InMemorySqlEngine) {
+    assert_eq!(
+        execute(
+            &mut with_table,
+            "insert into schema_name.table_name values (1); insert into schema_name.table_name values (2);"
+        ),
+        vec![Ok(QueryEvent::RecordsInserted(1)), Ok(QueryEvent::RecordsInserted(1))]
+    );
+    assert_eq!(
+        execute(&mut with_table, "rollback;"),
+        vec![Ok(QueryEvent::TransactionRolledBack)]
+    );
+
+    assert_eq!(
+        execute(&mut with_table, "select * from schema_name.table_name;"),
+        selected_values(vec!["1", "2"])
+    );
+}
+
+#[rstest::rstest]
+fn definitions_are_undone_with_failed_query(mut with_table: InMemorySqlEngine) {
+    assert_eq!(
+        execute(
+            &mut with_table,
+            "create schema other_schema; \
+             create table other_schema.table_name (column_test smallint); \
+             drop table schema_name.table_name; \
+             select 1 / 0;"
+        ),
+        vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::TableDropped),
+            Err(QueryError::division_by_zero())
+        ]
+    );
+
+    assert_eq!(
+        execute(&mut with_table, "create schema other_schema;"),
+        vec![Ok(QueryEvent::SchemaCreated)]
+    );
+    assert_eq!(
+        execute(&mut with_table, "select * from other_schema.table_name;"),
+        vec![Err(QueryError::table_does_not_exist(
+            "other_schema.table_name".to_owned()
+        )
+        .with_schema("other_schema".to_owned())
+        .with_table("table_name".to_owned()))]
+    );
+    assert_eq!(
+        execute(&mut with_table, "select * from schema_name.table_name;"),
+        selected_values(vec![])
+    );
+}
+
+#[rstest::rstest]
+fn explicit_transaction_in_query(mut with_table: InMemorySqlEngine) {
+    assert_eq!(
+        execute(
+            &mut with_table,
+            "insert into schema_name.table_name values (1); \
+             begin; \
+             insert into schema_name.table_name values (2); \
+             commit; \
+             insert into schema_name.table_name values (3); \
+             insert into schema_name.table_name values ('abc');"
+        ),
+        vec![
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::TransactionStarted),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::TransactionCommitted),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Err(
+                QueryError::invalid_text_representation("smallint".to_owned(), "abc".to_owned())
+                    .with_column("column_test".to_owned())
+                    .with_schema("schema_name".to_owned())
+                    .with_table("table_name".to_owned())
+            )
+        ]
+    );
+
+    assert_eq!(
+        execute(&mut with_table, "select * from schema_name.table_name;"),
+        selected_values(vec!["1", "2"])
+    );
+}
+
+#[rstest::rstest]
+fn transaction_started_by_query_continues(mut with_table: InMemorySqlEngine) {
+    assert_eq!(
+        execute(&mut with_table, "begin; insert into schema_name.table_name values (1);"),
+        vec![Ok(QueryEvent::TransactionStarted), Ok(QueryEvent::RecordsInserted(1))]
+    );
+    assert_eq!(
+        execute(&mut with_table, "rollback;"),
+        vec![Ok(QueryEvent::TransactionRolledBack)]
+    );
+
+    assert_eq!(
+        execute(&mut with_table, "select * from schema_name.table_name;"),
+        selected_values(vec![])
+    );
+}
+
+#[rstest::rstest(
+    sql,
+    case::empty(""),
+    case::whitespaces("  \n "),
+    case::semicolons(" ; ;"),
+    case::comment("-- nothing to execute")
+)]
+fn empty_query(mut sql_engine: InMemorySqlEngine, sql: &str) {
+    assert_eq!(execute(&mut sql_engine, sql), vec![Ok(QueryEvent::EmptyQuery)]);
+}
@@ -0,0 +1,327 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+fn execute(sql_engine: &mut InMemorySqlEngine, sql: &str) -> Vec<QueryResult> {
+    sql_engine.execute(sql).expect("no system errors").collect()
+}
+
+#[rstest::fixture]
+fn with_table(mut sql_engine_with_schema: InMemorySqlEngine) -> InMemorySqlEngine {
+    execute(
+        &mut sql_engine_with_schema,
+        "create table schema_name.table_name (column_i integer, column_s varchar(5));",
+    );
+    sql_engine_with_schema
+}
+
+#[rstest::rstest]
+fn prepare_and_execute_insert(mut with_table: InMemorySqlEngine) {
+    assert_eq!(
+        execute(
+            &mut with_table,
+            "prepare insert_values as insert into schema_name.table_name values ($1, $2);"
+        ),
+        vec![Ok(QueryEvent::StatementPrepared)]
+    );
+    assert_eq!(
+        execute(&mut with_table, "execute insert_values (1, 'a');"),
+        vec![Ok(QueryEvent::RecordsInserted(1))]
+    );
+    assert_eq!(
+        execute(&mut with_table, "execute insert_values (2 + 1, 'b');"),
+        vec![Ok(QueryEvent::RecordsInserted(1))]
+    );
+
+    assert_eq!(
+        execute(&mut with_table, "select * from schema_name.table_name;"),
+        selected(
+            vec![
+                ("column_i".to_owned(), PostgreSqlType::Integer),
+                ("column_s".to_owned(), PostgreSqlType::VarChar(Some(5))),
+            ],
+            vec![
+                vec!["1".to_owned(), "a".to_owned()],
+                vec!["3".to_owned(), "b".to_owned()]
+            ]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn prepare_and_execute_update(mut with_table: InMemorySqlEngine) {
+    execute(&mut with_table, "insert into schema_name.table_name values (1, 'a');");
+
+    assert_eq!(
+        execute(
+            &mut with_table,
+            "prepare update_values as update schema_name.table_name set column_s = $1;"
+        ),
+        vec![Ok(QueryEvent::StatementPrepared)]
+    );
+    assert_eq!(
+        execute(&mut with_table, "execute update_values ('abcdef');"),
+        vec![Err(QueryError::value_too_long("character varying(5)".to_owned())
+            .with_column("column_s".to_owned())
+            .with_schema("schema_name".to_owned())
+            .with_table("table_name".to_owned()))]
+    );
+    assert_eq!(
+        execute(&mut with_table, "execute update_values ('b');"),
+        vec![Ok(QueryEvent::RecordsUpdated(1))]
+    );
+
+    assert_eq!(
+        execute(&mut with_table, "select * from schema_name.table_name;"),
+        selected(
+            vec![
+                ("column_i".to_owned(), PostgreSqlType::Integer),
+                ("column_s".to_owned(), PostgreSqlType::VarChar(Some(5))),
+            ],
+            vec![vec!["1".to_owned(), "b".to_owned()]]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn prepare_with_parameter_types(mut sql_engine_with_schema: InMemorySqlEngine) {
+    assert_eq!(
+        execute(
+            &mut sql_engine_with_schema,
+            "prepare select_values (smallint, text) as select $1, $2;"
+        ),
+        vec![Ok(QueryEvent::StatementPrepared)]
+    );
+
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "execute select_values (1, 2);"),
+        selected(
+            vec![
+                ("?column?".to_owned(), PostgreSqlType::SmallInt),
+                ("?column?".to_owned(), PostgreSqlType::Text),
+            ],
+            vec![vec!["1".to_owned(), "2".to_owned()]]
+        )
+    );
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "execute select_values ('abc', 'abc');"),
+        vec![Err(QueryError::invalid_text_representation(
+            "smallint".to_owned(),
+            "abc".to_owned()
+        ))]
+    );
+}
+
+#[rstest::rstest]
+fn prepare_with_enum_parameter(mut sql_engine_with_schema: InMemorySqlEngine) {
+    execute(
+        &mut sql_engine_with_schema,
+        "create type schema_name.mood as enum ('sad', 'happy');",
+    );
+    execute(
+        &mut sql_engine_with_schema,
+        "create table schema_name.table_name (column_m schema_name.mood);",
+    );
+
+    assert_eq!(
+        execute(
+            &mut sql_engine_with_schema,
+            "prepare insert_mood (schema_name.mood) as insert into schema_name.table_name values ($1);"
+        ),
+        vec![Ok(QueryEvent::StatementPrepared)]
+    );
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "execute insert_mood ('happy');"),
+        vec![Ok(QueryEvent::RecordsInserted(1))]
+    );
+    assert_eq!(
+        execute(
+            &mut sql_engine_with_schema,
+            "prepare insert_unknown (schema_name.unknown) as insert into schema_name.table_name values ($1);"
+        ),
+        vec![Err(QueryError::type_does_not_exist("unknown".to_owned()))]
+    );
+}
+
+#[rstest::rstest]
+fn execute_with_wrong_number_of_parameters(mut with_table: InMemorySqlEngine) {
+    execute(
+        &mut with_table,
+        "prepare insert_values as insert into schema_name.table_name values ($1, $2);",
+    );
+
+    assert_eq!(
+        execute(&mut with_table, "execute insert_values (1);"),
+        vec![Err(QueryError::wrong_number_of_parameters("insert_values".to_owned()))]
+    );
+    assert_eq!(
+        execute(&mut with_table, "execute insert_values;"),
+        vec![Err(QueryError::wrong_number_of_parameters("insert_values".to_owned()))]
+    );
+}
+
+#[rstest::rstest]
+fn statement_name_is_unique(mut sql_engine_with_schema: InMemorySqlEngine) {
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "prepare select_one as select 1;"),
+        vec![Ok(QueryEvent::StatementPrepared)]
+    );
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "prepare select_one as select 2;"),
+        vec![Err(QueryError::duplicate_prepared_statement("select_one".to_owned()))]
+    );
+    assert_eq!(
+        sql_engine_with_schema
+            .parse("select_one".to_owned(), "select 3".to_owned(), vec![])
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::duplicate_prepared_statement("select_one".to_owned()))]
+    );
+
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "execute select_one;"),
+        selected(
+            vec![("?column?".to_owned(), PostgreSqlType::Integer)],
+            vec![vec!["1".to_owned()]]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn statement_of_extended_query_is_executed(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .parse("select_value".to_owned(), "select $1::integer".to_owned(), vec![])
+        .expect("no system errors")
+        .for_each(drop);
+
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "execute select_value (42);"),
+        selected(
+            vec![("?column?".to_owned(), PostgreSqlType::Integer)],
+            vec![vec!["42".to_owned()]]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn only_queries_and_data_changes_are_prepared(mut sql_engine_with_schema: InMemorySqlEngine) {
+    assert_eq!(
+        execute(
+            &mut sql_engine_with_schema,
+            "prepare create_schema as create schema other_schema;"
+        ),
+        vec![Err(QueryError::not_supported_operation(
+            "prepare create_schema as create schema other_schema;".to_owned()
+        ))]
+    );
+}
+
+#[rstest::rstest]
+fn deallocate_statement(mut sql_engine_with_schema: InMemorySqlEngine) {
+    execute(&mut sql_engine_with_schema, "prepare select_one as select 1;");
+
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "deallocate select_one;"),
+        vec![Ok(QueryEvent::StatementDeallocated)]
+    );
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "execute select_one;"),
+        vec![Err(QueryError::prepared_statement_does_not_exist(
+            "select_one".to_owned()
+        ))]
+    );
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "deallocate prepare select_one;"),
+        vec![Err(QueryError::prepared_statement_does_not_exist(
+            "select_one".to_owned()
+        ))]
+    );
+}
+
+#[rstest::rstest]
+fn deallocate_all_statements(mut sql_engine_with_schema: InMemorySqlEngine) {
+    execute(&mut sql_engine_with_schema, "prepare select_one as select 1;");
+    execute(&mut sql_engine_with_schema, "prepare select_two as select 2;");
+
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "deallocate all;"),
+        vec![Ok(QueryEvent::AllStatementsDeallocated)]
+    );
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "execute select_one;"),
+        vec![Err(QueryError::prepared_statement_does_not_exist(
+            "select_one".to_owned()
+        ))]
+    );
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "execute select_two;"),
+        vec![Err(QueryError::prepared_statement_does_not_exist(
+            "select_two".to_owned()
+        ))]
+    );
+}
+
+#[rstest::rstest]
+fn parameter_type_is_inferred_from_other_operand(mut sql_engine_with_schema: InMemorySqlEngine) {
+    execute(&mut sql_engine_with_schema, "prepare add_one as select $1 + 1;");
+    execute(&mut sql_engine_with_schema, "prepare add as select $1::integer + $2;");
+
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "execute add_one (1);"),
+        selected(
+            vec![("?column?".to_owned(), PostgreSqlType::Integer)],
+            vec![vec!["2".to_owned()]]
+        )
+    );
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "execute add (1, 2);"),
+        selected(
+            vec![("?column?".to_owned(), PostgreSqlType::Integer)],
+            vec![vec!["3".to_owned()]]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn prepare_with_enum_array_parameter(mut sql_engine_with_schema: InMemorySqlEngine) {
+    execute(
+        &mut sql_engine_with_schema,
+        "create type schema_name.mood as enum ('sad', 'happy');",
+    );
+    execute(
+        &mut sql_engine_with_schema,
+        "create table schema_name.table_name (column_m schema_name.mood[]);",
+    );
+
+    assert_eq!(
+        execute(
+            &mut sql_engine_with_schema,
+            "prepare insert_moods as insert into schema_name.table_name values ($1);"
+        ),
+        vec![Ok(QueryEvent::StatementPrepared)]
+    );
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "execute insert_moods ('{sad,happy}');"),
+        vec![Ok(QueryEvent::RecordsInserted(1))]
+    );
+    assert_eq!(
+        execute(
+            &mut sql_engine_with_schema,
+            "prepare select_moods (schema_name.mood[]) as select $1;"
+        ),
+        vec![Ok(QueryEvent::StatementPrepared)]
+    );
+}
@@ -19,8 +19,9 @@ fn select_from_nonexistent_schema(mut sql_engine: InMemorySqlEngine) {
     assert_eq!(
         sql_engine
             .execute("select * from non_existent.some_table;")
-            .expect("no system errors"),
-        Err(QueryError::schema_does_not_exist("non_existent".to_owned()))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::schema_does_not_exist("non_existent".to_owned()))]
     );
 }
 
@@ -29,8 +30,9 @@ fn select_named_columns_from_nonexistent_schema(mut sql_engine: InMemorySqlEngin
     assert_eq!(
         sql_engine
             .execute("select column_1 from schema_name.table_name;")
-            .expect("no system errors"),
-        Err(QueryError::schema_does_not_exist("schema_name".to_owned()))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::schema_does_not_exist("schema_name".to_owned()))]
     );
 }
 
@@ -39,8 +41,9 @@ fn insert_into_table_in_nonexistent_schema(mut sql_engine: InMemorySqlEngine) {
     assert_eq!(
         sql_engine
             .execute("insert into schema_name.table_name values (123);")
-            .expect("no system errors"),
-        Err(QueryError::schema_does_not_exist("schema_name".to_owned()))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::schema_does_not_exist("schema_name".to_owned()))]
     );
 }
 
@@ -49,8 +52,9 @@ fn update_records_in_table_from_non_existent_schema(mut sql_engine: InMemorySqlE
     assert_eq!(
         sql_engine
             .execute("update schema_name.table_name set column_test=789;")
-            .expect("no system errors"),
-        Err(QueryError::schema_does_not_exist("schema_name".to_owned()))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::schema_does_not_exist("schema_name".to_owned()))]
     );
 }
 
@@ -59,7 +63,8 @@ fn delete_from_table_in_nonexistent_schema(mut sql_engine: InMemorySqlEngine) {
     assert_eq!(
         sql_engine
             .execute("delete from schema_name.table_name;")
-            .expect("no system errors"),
-        Err(QueryError::schema_does_not_exist("schema_name".to_owned()))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::schema_does_not_exist("schema_name".to_owned()))]
     );
 }
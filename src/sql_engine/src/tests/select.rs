@@ -20,8 +20,13 @@ fn select_from_not_existed_table(mut sql_engine_with_schema: InMemorySqlEngine)
     assert_eq!(
         sql_engine_with_schema
             .execute("select * from schema_name.non_existent;")
-            .expect("no system errors"),
-        Err(QueryError::table_does_not_exist("schema_name.non_existent".to_owned()))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::table_does_not_exist(
+            "schema_name.non_existent".to_owned()
+        )
+        .with_schema("schema_name".to_owned())
+        .with_table("non_existent".to_owned()))]
     );
 }
 
@@ -30,8 +35,13 @@ fn select_named_columns_from_non_existent_table(mut sql_engine_with_schema: InMe
     assert_eq!(
         sql_engine_with_schema
             .execute("select column_1 from schema_name.non_existent;")
-            .expect("no system errors"),
-        Err(QueryError::table_does_not_exist("schema_name.non_existent".to_owned()))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::table_does_not_exist(
+            "schema_name.non_existent".to_owned()
+        )
+        .with_schema("schema_name".to_owned())
+        .with_table("non_existent".to_owned()))]
     );
 }
 
@@ -40,24 +50,27 @@ fn select_all_from_table_with_multiple_columns(mut sql_engine_with_schema: InMem
     sql_engine_with_schema
         .execute("create table schema_name.table_name (column_1 smallint, column_2 smallint, column_3 smallint);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("table created");
     sql_engine_with_schema
         .execute("insert into schema_name.table_name values (123, 456, 789);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("row inserted");
 
     assert_eq!(
         sql_engine_with_schema
             .execute("select * from schema_name.table_name;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsSelected((
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
             vec![
                 ("column_1".to_owned(), PostgreSqlType::SmallInt),
                 ("column_2".to_owned(), PostgreSqlType::SmallInt),
                 ("column_3".to_owned(), PostgreSqlType::SmallInt)
             ],
             vec![vec!["123".to_owned(), "456".to_owned(), "789".to_owned()]]
-        )))
+        )
     );
 }
 
@@ -66,17 +79,20 @@ fn select_not_all_columns(mut sql_engine_with_schema: InMemorySqlEngine) {
     sql_engine_with_schema
         .execute("create table schema_name.table_name (column_1 smallint, column_2 smallint, column_3 smallint);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("table created");
     sql_engine_with_schema
         .execute("insert into schema_name.table_name values (1, 4, 7), (2, 5, 8), (3, 6, 9);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("rows inserted");
 
     assert_eq!(
         sql_engine_with_schema
             .execute("select column_3, column_2 from schema_name.table_name;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsSelected((
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
             vec![
                 ("column_3".to_owned(), PostgreSqlType::SmallInt),
                 ("column_2".to_owned(), PostgreSqlType::SmallInt),
@@ -86,7 +102,7 @@ fn select_not_all_columns(mut sql_engine_with_schema: InMemorySqlEngine) {
                 vec!["8".to_owned(), "5".to_owned()],
                 vec!["9".to_owned(), "6".to_owned()],
             ]
-        )))
+        )
     );
 }
 
@@ -95,15 +111,164 @@ fn select_non_existing_columns_from_table(mut sql_engine_with_schema: InMemorySq
     sql_engine_with_schema
         .execute("create table schema_name.table_name (column_in_table smallint);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("table created");
 
     assert_eq!(
         sql_engine_with_schema
             .execute("select column_not_in_table1, column_not_in_table2 from schema_name.table_name;")
-            .expect("no system errors"),
-        Err(QueryError::column_does_not_exist(vec![
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::column_does_not_exist(vec![
             "column_not_in_table1".to_owned(),
             "column_not_in_table2".to_owned()
-        ]))
+        ]))]
+    );
+}
+
+#[rstest::rstest]
+fn select_values_in_requested_formats(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (column_si smallint, column_c char(3), column_t text);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+    sql_engine_with_schema
+        .execute("insert into schema_name.table_name values (258, 'ab', 'text');")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("row inserted");
+
+    let description = vec![
+        ("column_si".to_owned(), PostgreSqlType::SmallInt),
+        ("column_c".to_owned(), PostgreSqlType::Char(3)),
+        ("column_t".to_owned(), PostgreSqlType::Text),
+    ];
+    assert_eq!(
+        sql_engine_with_schema
+            .execute_with_formats("select * from schema_name.table_name;", vec![PostgreSqlFormat::Binary])
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![
+            Ok(QueryEvent::RowDescription(
+                description.clone(),
+                vec![PostgreSqlFormat::Binary; 3]
+            )),
+            Ok(QueryEvent::DataRow(vec![
+                Some(vec![1, 2]),
+                Some(b"ab ".to_vec()),
+                Some(b"text".to_vec())
+            ])),
+            Ok(QueryEvent::RecordsSelected(1)),
+        ]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute_with_formats(
+                "select * from schema_name.table_name;",
+                vec![PostgreSqlFormat::Binary, PostgreSqlFormat::Text]
+            )
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![
+            Ok(QueryEvent::RowDescription(
+                description,
+                vec![PostgreSqlFormat::Binary, PostgreSqlFormat::Text, PostgreSqlFormat::Text]
+            )),
+            Ok(QueryEvent::DataRow(vec![
+                Some(vec![1, 2]),
+                Some(b"ab ".to_vec()),
+                Some(b"text".to_vec())
+            ])),
+            Ok(QueryEvent::RecordsSelected(1)),
+        ]
+    );
+}
+
+#[rstest::rstest]
+fn select_expressions_of_columns(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (id integer, ratio real);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+    sql_engine_with_schema
+        .execute("insert into schema_name.table_name values (1, 1.5), (2, 2.5);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("records inserted");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select id + 1, ratio / 2 as half, * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("?column?".to_owned(), PostgreSqlType::Integer),
+                ("half".to_owned(), PostgreSqlType::Real),
+                ("id".to_owned(), PostgreSqlType::Integer),
+                ("ratio".to_owned(), PostgreSqlType::Real),
+            ],
+            vec![
+                vec!["2".to_owned(), "0.75".to_owned(), "1".to_owned(), "1.5".to_owned()],
+                vec!["3".to_owned(), "1.25".to_owned(), "2".to_owned(), "2.5".to_owned()],
+            ]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn select_real_arithmetic_with_integers(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        sql_engine
+            .execute("select cast(1.5 as real) / 2, 3 * cast(0.5 as real);")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("?column?".to_owned(), PostgreSqlType::Real),
+                ("?column?".to_owned(), PostgreSqlType::Real),
+            ],
+            vec![vec!["0.75".to_owned(), "1.5".to_owned()]]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn select_expressions_of_columns_errors(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (id integer);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+    sql_engine_with_schema
+        .execute("insert into schema_name.table_name values (1), (0);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("records inserted");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select non_existing + 1 from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::column_does_not_exist(vec!["non_existing".to_owned()]))]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select 10 / id from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![
+            Ok(QueryEvent::RowDescription(
+                vec![("?column?".to_owned(), PostgreSqlType::Integer)],
+                vec![PostgreSqlFormat::Text]
+            )),
+            Ok(QueryEvent::DataRow(vec![Some(b"10".to_vec())])),
+            Err(QueryError::division_by_zero()),
+        ]
     );
 }
@@ -0,0 +1,376 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+fn execute(sql_engine: &mut InMemorySqlEngine, sql: &str) -> Vec<QueryResult> {
+    sql_engine
+        .execute(sql)
+        .expect("no system errors")
+        .collect::<Vec<QueryResult>>()
+}
+
+#[rstest::rstest]
+fn create_and_drop_sequence(mut sql_engine_with_schema: InMemorySqlEngine) {
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "create sequence schema_name.ids;"),
+        vec![Ok(QueryEvent::SequenceCreated)]
+    );
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "create sequence schema_name.ids;"),
+        vec![Err(QueryError::sequence_already_exists("ids".to_owned()))]
+    );
+    assert_eq!(
+        execute(
+            &mut sql_engine_with_schema,
+            "create sequence if not exists schema_name.ids;"
+        ),
+        vec![
+            Ok(QueryEvent::Notice(QueryError::sequence_already_exists_skipping(
+                "ids".to_owned()
+            ))),
+            Ok(QueryEvent::SequenceCreated)
+        ]
+    );
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "drop sequence schema_name.ids;"),
+        vec![Ok(QueryEvent::SequenceDropped)]
+    );
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "drop sequence schema_name.ids;"),
+        vec![Err(QueryError::sequence_does_not_exist("schema_name.ids".to_owned()))]
+    );
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "drop sequence if exists schema_name.ids;"),
+        vec![
+            Ok(QueryEvent::Notice(QueryError::sequence_does_not_exist_skipping(
+                "schema_name.ids".to_owned()
+            ))),
+            Ok(QueryEvent::SequenceDropped)
+        ]
+    );
+}
+
+#[rstest::rstest(
+    parameters,
+    message,
+    case::zero_increment("increment 0", "INCREMENT must not be zero"),
+    case::empty_range("minvalue 5 maxvalue 5", "MINVALUE (5) must be less than MAXVALUE (5)"),
+    case::start_below_min("minvalue 5 start 1", "START value (1) cannot be less than MINVALUE (5)"),
+    case::start_above_max("increment -1 start 1", "START value (1) cannot be greater than MAXVALUE (-1)")
+)]
+fn invalid_sequence_parameters(mut sql_engine_with_schema: InMemorySqlEngine, parameters: &str, message: &str) {
+    assert_eq!(
+        execute(
+            &mut sql_engine_with_schema,
+            &format!("create sequence schema_name.ids {};", parameters)
+        ),
+        vec![Err(QueryError::invalid_sequence_parameter(message.to_owned()))]
+    );
+}
+
+#[rstest::rstest]
+fn sequence_functions(mut sql_engine_with_schema: InMemorySqlEngine) {
+    execute(
+        &mut sql_engine_with_schema,
+        "create sequence schema_name.ids increment by 10 maxvalue 25;",
+    );
+
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "select currval('schema_name.ids');"),
+        vec![Err(QueryError::sequence_current_value_not_defined("ids".to_owned()))]
+    );
+    assert_eq!(
+        execute(
+            &mut sql_engine_with_schema,
+            "select nextval('schema_name.ids'), nextval('schema_name.ids') as next;"
+        ),
+        selected(
+            vec![
+                ("nextval".to_owned(), PostgreSqlType::BigInt),
+                ("next".to_owned(), PostgreSqlType::BigInt)
+            ],
+            vec![vec!["1".to_owned(), "11".to_owned()]]
+        )
+    );
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "select currval('schema_name.ids');"),
+        selected(
+            vec![("currval".to_owned(), PostgreSqlType::BigInt)],
+            vec![vec!["11".to_owned()]]
+        )
+    );
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "select nextval('schema_name.ids');"),
+        selected(
+            vec![("nextval".to_owned(), PostgreSqlType::BigInt)],
+            vec![vec!["21".to_owned()]]
+        )
+    );
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "select nextval('schema_name.ids');"),
+        vec![Err(QueryError::sequence_max_value_reached("ids".to_owned(), 25))]
+    );
+    assert_eq!(
+        execute(
+            &mut sql_engine_with_schema,
+            "select setval('schema_name.ids', 3, false);"
+        ),
+        selected(
+            vec![("setval".to_owned(), PostgreSqlType::BigInt)],
+            vec![vec!["3".to_owned()]]
+        )
+    );
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "select nextval('schema_name.ids');"),
+        selected(
+            vec![("nextval".to_owned(), PostgreSqlType::BigInt)],
+            vec![vec!["3".to_owned()]]
+        )
+    );
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "select setval('schema_name.ids', 30);"),
+        vec![Err(QueryError::sequence_value_out_of_bounds(
+            "ids".to_owned(),
+            30,
+            1,
+            25
+        ))]
+    );
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "select nextval('schema_name.other');"),
+        vec![Err(QueryError::sequence_does_not_exist("schema_name.other".to_owned()))]
+    );
+}
+
+#[rstest::rstest]
+fn sequence_without_schema(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        execute(&mut sql_engine, "create sequence ids;"),
+        vec![Err(QueryError::schema_does_not_exist("public".to_owned()))]
+    );
+
+    execute(&mut sql_engine, "create schema public;");
+    assert_eq!(
+        execute(&mut sql_engine, "create sequence ids;"),
+        vec![Ok(QueryEvent::SequenceCreated)]
+    );
+    assert_eq!(
+        execute(&mut sql_engine, "select nextval('ids');"),
+        selected(
+            vec![("nextval".to_owned(), PostgreSqlType::BigInt)],
+            vec![vec!["1".to_owned()]]
+        )
+    );
+    assert_eq!(
+        execute(&mut sql_engine, "select nextval('public.ids');"),
+        selected(
+            vec![("nextval".to_owned(), PostgreSqlType::BigInt)],
+            vec![vec!["2".to_owned()]]
+        )
+    );
+    assert_eq!(
+        execute(&mut sql_engine, "drop sequence ids;"),
+        vec![Ok(QueryEvent::SequenceDropped)]
+    );
+}
+
+#[rstest::rstest]
+fn serial_and_identity_columns(mut sql_engine_with_schema: InMemorySqlEngine) {
+    assert_eq!(
+        execute(
+            &mut sql_engine_with_schema,
+            "create table schema_name.table_name (\
+                id serial, \
+                big_id bigserial, \
+                identity_id smallint generated by default as identity (start with 100 increment by 100), \
+                name varchar(10)\
+            );"
+        ),
+        vec![Ok(QueryEvent::TableCreated)]
+    );
+
+    assert_eq!(
+        execute(
+            &mut sql_engine_with_schema,
+            "insert into schema_name.table_name (name) values ('first'), ('second');"
+        ),
+        vec![Ok(QueryEvent::RecordsInserted(2))]
+    );
+    assert_eq!(
+        execute(
+            &mut sql_engine_with_schema,
+            "insert into schema_name.table_name (id, name) values (10, 'third');"
+        ),
+        vec![Ok(QueryEvent::RecordsInserted(1))]
+    );
+
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "select * from schema_name.table_name;"),
+        selected(
+            vec![
+                ("id".to_owned(), PostgreSqlType::Integer),
+                ("big_id".to_owned(), PostgreSqlType::BigInt),
+                ("identity_id".to_owned(), PostgreSqlType::SmallInt),
+                ("name".to_owned(), PostgreSqlType::VarChar(Some(10))),
+            ],
+            vec![
+                vec!["1".to_owned(), "1".to_owned(), "100".to_owned(), "first".to_owned()],
+                vec!["2".to_owned(), "2".to_owned(), "200".to_owned(), "second".to_owned()],
+                vec!["10".to_owned(), "3".to_owned(), "300".to_owned(), "third".to_owned()],
+            ]
+        )
+    );
+
+    // sequences of the table are dropped together with it
+    execute(&mut sql_engine_with_schema, "drop table schema_name.table_name;");
+    assert_eq!(
+        execute(
+            &mut sql_engine_with_schema,
+            "select nextval('schema_name.table_name_id_seq');"
+        ),
+        vec![Err(QueryError::sequence_does_not_exist(
+            "schema_name.table_name_id_seq".to_owned()
+        ))]
+    );
+}
+
+#[rstest::rstest]
+fn identity_column_of_non_integer_type(mut sql_engine_with_schema: InMemorySqlEngine) {
+    assert_eq!(
+        execute(
+            &mut sql_engine_with_schema,
+            "create table schema_name.table_name (id varchar(10) generated always as identity);"
+        ),
+        vec![Err(QueryError::invalid_sequence_parameter(
+            "identity column type must be smallint, integer, or bigint".to_owned()
+        ))]
+    );
+}
+
+#[rstest::rstest]
+fn explicit_value_of_generated_always_column(mut sql_engine_with_schema: InMemorySqlEngine) {
+    execute(
+        &mut sql_engine_with_schema,
+        "create table schema_name.table_name (id integer generated always as identity, name varchar(10));",
+    );
+
+    assert_eq!(
+        execute(
+            &mut sql_engine_with_schema,
+            "insert into schema_name.table_name (id, name) values (10, 'first');"
+        ),
+        vec![Err(QueryError::generated_always("id".to_owned()))]
+    );
+    assert_eq!(
+        execute(
+            &mut sql_engine_with_schema,
+            "insert into schema_name.table_name values (10, 'first');"
+        ),
+        vec![Err(QueryError::generated_always("id".to_owned()))]
+    );
+    assert_eq!(
+        execute(
+            &mut sql_engine_with_schema,
+            "insert into schema_name.table_name (name) values ('first');"
+        ),
+        vec![Ok(QueryEvent::RecordsInserted(1))]
+    );
+    assert_eq!(
+        execute(
+            &mut sql_engine_with_schema,
+            "insert into schema_name.table_name (id, name) overriding system value values (10, 'second');"
+        ),
+        vec![Ok(QueryEvent::RecordsInserted(1))]
+    );
+
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "select * from schema_name.table_name;"),
+        selected(
+            vec![
+                ("id".to_owned(), PostgreSqlType::Integer),
+                ("name".to_owned(), PostgreSqlType::VarChar(Some(10))),
+            ],
+            vec![
+                vec!["1".to_owned(), "first".to_owned()],
+                vec!["10".to_owned(), "second".to_owned()],
+            ]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn sequence_functions_of_every_selected_record(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_table(&mut sql_engine_with_schema, "name varchar(10)");
+    execute(&mut sql_engine_with_schema, "create sequence schema_name.ids;");
+    execute(
+        &mut sql_engine_with_schema,
+        "insert into schema_name.table_name values ('first'), ('second');",
+    );
+
+    assert_eq!(
+        execute(
+            &mut sql_engine_with_schema,
+            "select nextval('schema_name.ids') * 10, name from schema_name.table_name;"
+        ),
+        selected(
+            vec![
+                ("?column?".to_owned(), PostgreSqlType::BigInt),
+                ("name".to_owned(), PostgreSqlType::VarChar(Some(10))),
+            ],
+            vec![
+                vec!["10".to_owned(), "first".to_owned()],
+                vec!["20".to_owned(), "second".to_owned()],
+            ]
+        )
+    );
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "select currval('schema_name.ids');"),
+        selected(
+            vec![("currval".to_owned(), PostgreSqlType::BigInt)],
+            vec![vec!["2".to_owned()]]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn described_select_does_not_call_sequence_functions(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_table(&mut sql_engine_with_schema, "name varchar(10)");
+    execute(&mut sql_engine_with_schema, "create sequence schema_name.ids;");
+    execute(
+        &mut sql_engine_with_schema,
+        "insert into schema_name.table_name values ('first');",
+    );
+
+    sql_engine_with_schema
+        .parse(
+            "".to_owned(),
+            "select nextval('schema_name.ids') from schema_name.table_name".to_owned(),
+            vec![],
+        )
+        .expect("no system errors")
+        .for_each(drop);
+    sql_engine_with_schema
+        .describe_statement("")
+        .expect("no system errors")
+        .for_each(drop);
+
+    assert_eq!(
+        execute(&mut sql_engine_with_schema, "select nextval('schema_name.ids');"),
+        selected(
+            vec![("nextval".to_owned(), PostgreSqlType::BigInt)],
+            vec![vec!["1".to_owned()]]
+        )
+    );
+}
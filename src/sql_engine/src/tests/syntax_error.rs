@@ -0,0 +1,128 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+fn execute(sql_engine: &mut InMemorySqlEngine, sql: &str) -> Vec<QueryResult> {
+    sql_engine.execute(sql).expect("no system errors").collect()
+}
+
+fn syntax_error(message: &str, position: u32) -> Vec<QueryResult> {
+    vec![Err(QueryError::syntax_error(message.to_owned()).with_position(position))]
+}
+
+#[rstest::rstest]
+fn unknown_statement(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        execute(&mut sql_engine, "selec 1;"),
+        syntax_error("Expected an SQL statement, found: selec", 1)
+    );
+}
+
+#[rstest::rstest]
+fn unexpected_token(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        execute(&mut sql_engine, "select 1 2;"),
+        syntax_error("Expected end of statement, found: 2", 10)
+    );
+}
+
+#[rstest::rstest]
+fn unexpected_end_of_statement(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        execute(&mut sql_engine, "select 1 +;"),
+        syntax_error("Expected an expression, found: EOF", 11)
+    );
+}
+
+#[rstest::rstest]
+fn unterminated_string(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        execute(&mut sql_engine, "select 'abc;"),
+        syntax_error("Unterminated string literal at Line: 1, Column 8", 8)
+    );
+}
+
+#[rstest::rstest]
+fn error_in_second_line(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        execute(&mut sql_engine, "select *\nfrom schema_name.table_name\nwher 1;"),
+        syntax_error("Expected end of statement, found: 1", 43)
+    );
+}
+
+#[rstest::rstest]
+fn statements_are_not_executed_when_one_of_them_has_error(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        execute(&mut sql_engine, "create schema schema_name; selec 1;"),
+        syntax_error("Expected an SQL statement, found: selec", 28)
+    );
+
+    assert_eq!(
+        execute(&mut sql_engine, "create schema schema_name;"),
+        vec![Ok(QueryEvent::SchemaCreated)]
+    );
+}
+
+#[rstest::rstest]
+fn error_in_prepared_statement(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        execute(&mut sql_engine, "prepare select_one as selec 1;"),
+        syntax_error("Expected an SQL statement, found: selec", 23)
+    );
+}
+
+#[rstest::rstest]
+fn error_in_statement_of_extended_query(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        sql_engine
+            .parse("".to_owned(), "select $1 frm schema_name.table_name".to_owned(), vec![])
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        syntax_error("Expected end of statement, found: schema_name", 15)
+    );
+}
+
+#[rstest::rstest]
+fn error_after_escaped_quotes(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        execute(&mut sql_engine, "select 'it''s', \"a\"\"b\" 1;"),
+        syntax_error("Expected end of statement, found: 1", 24)
+    );
+}
+
+#[rstest::rstest]
+fn unterminated_string_after_escaped_quotes(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        execute(&mut sql_engine, "select 'it''s', 'abc;"),
+        syntax_error("Unterminated string literal at Line: 1, Column 14", 17)
+    );
+}
+
+#[rstest::rstest]
+fn error_after_comments_and_windows_line_breaks(mut sql_engine: InMemorySqlEngine) {
+    assert_eq!(
+        execute(&mut sql_engine, "select 1 -- one\r\n/* two */ 2;"),
+        syntax_error("Expected end of statement, found: 2", 28)
+    );
+}
+
+#[rstest::rstest]
+fn error_in_long_statement(mut sql_engine: InMemorySqlEngine) {
+    let values = "1, ".repeat(10_000);
+    assert_eq!(
+        execute(&mut sql_engine, &format!("select {}1 2;", values)),
+        syntax_error("Expected end of statement, found: 2", 30_010)
+    );
+}
@@ -23,8 +23,9 @@ mod schemaless {
         assert_eq!(
             sql_engine
                 .execute("create table schema_name.table_name (column_name smallint);")
-                .expect("no system errors"),
-            Err(QueryError::schema_does_not_exist("schema_name".to_owned()))
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            vec![Err(QueryError::schema_does_not_exist("schema_name".to_owned()))]
         );
     }
 
@@ -33,8 +34,9 @@ mod schemaless {
         assert_eq!(
             sql_engine
                 .execute("drop table schema_name.table_name;")
-                .expect("no system errors"),
-            Err(QueryError::schema_does_not_exist("schema_name".to_owned()))
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            vec![Err(QueryError::schema_does_not_exist("schema_name".to_owned()))]
         );
     }
 }
@@ -44,8 +46,9 @@ fn create_table(mut sql_engine_with_schema: InMemorySqlEngine) {
     assert_eq!(
         sql_engine_with_schema
             .execute("create table schema_name.table_name (column_name smallint);")
-            .expect("no system errors"),
-        Ok(QueryEvent::TableCreated)
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::TableCreated)]
     );
 }
 
@@ -54,19 +57,41 @@ fn drop_table(mut sql_engine_with_schema: InMemorySqlEngine) {
     sql_engine_with_schema
         .execute("create table schema_name.table_name (column_name smallint);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("table created");
 
     assert_eq!(
         sql_engine_with_schema
             .execute("drop table schema_name.table_name;")
-            .expect("no system errors"),
-        Ok(QueryEvent::TableDropped)
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::TableDropped)]
     );
     assert_eq!(
         sql_engine_with_schema
             .execute("create table schema_name.table_name (column_name smallint);")
-            .expect("no system errors"),
-        Ok(QueryEvent::TableCreated)
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::TableCreated)]
+    );
+}
+
+#[rstest::rstest]
+fn create_existing_table(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (column_name smallint);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("create table schema_name.table_name (column_name smallint);")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::table_already_exists("table_name".to_owned())
+            .with_schema("schema_name".to_owned())
+            .with_table("table_name".to_owned()))]
     );
 }
 
@@ -75,8 +100,13 @@ fn drop_non_existent_table(mut sql_engine_with_schema: InMemorySqlEngine) {
     assert_eq!(
         sql_engine_with_schema
             .execute("drop table schema_name.table_name;")
-            .expect("no system errors"),
-        Err(QueryError::table_does_not_exist("schema_name.table_name".to_owned()))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::table_does_not_exist(
+            "schema_name.table_name".to_owned()
+        )
+        .with_schema("schema_name".to_owned())
+        .with_table("table_name".to_owned()))]
     );
 }
 
@@ -85,12 +115,114 @@ fn create_table_with_different_types(mut sql_engine: InMemorySqlEngine) {
     sql_engine
         .execute("create schema schema_name;")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("schema created");
 
     assert_eq!(
         sql_engine
             .execute("create table schema_name.table_name (column_si smallint, column_i integer, column_bi bigint, column_c char(10), column_vc varchar(10));")
-            .expect("no system errors"),
-        Ok(QueryEvent::TableCreated)
+            .expect("no system errors").collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::TableCreated)]
     )
 }
+
+#[rstest::rstest]
+fn create_table_with_invalid_numeric_precision_or_scale(mut sql_engine_with_schema: InMemorySqlEngine) {
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("create table schema_name.table_name (column_n numeric(1001, 2));")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::invalid_numeric_precision(1001))]
+    );
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("create table schema_name.table_name (column_n decimal(5, 6));")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::invalid_numeric_scale(6, 5))]
+    );
+}
+
+#[rstest::rstest]
+fn create_table_with_unsupported_types(mut sql_engine_with_schema: InMemorySqlEngine) {
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("create table schema_name.table_name (column_r regclass);")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::type_does_not_exist("regclass".to_owned()))]
+    );
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select cast('schema_name.table_name' as regclass);")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::type_does_not_exist("regclass".to_owned()))]
+    );
+}
+
+#[rstest::rstest]
+fn create_table_with_bool_column(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (column_b bool);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+    sql_engine_with_schema
+        .execute("insert into schema_name.table_name values ('yes'::bool);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("record inserted");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![("column_b".to_owned(), PostgreSqlType::Bool)],
+            vec![vec!["t".to_owned()]]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn create_table_with_type_aliases(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute(
+            "create table schema_name.table_name \
+             (column_si int2, column_i int4, column_bi int8, column_r float4, column_d float8);",
+        )
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+    sql_engine_with_schema
+        .execute("insert into schema_name.table_name values (1, 2, 3, '1.5'::float4, 2.5::float8);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("record inserted");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("column_si".to_owned(), PostgreSqlType::SmallInt),
+                ("column_i".to_owned(), PostgreSqlType::Integer),
+                ("column_bi".to_owned(), PostgreSqlType::BigInt),
+                ("column_r".to_owned(), PostgreSqlType::Real),
+                ("column_d".to_owned(), PostgreSqlType::DoublePrecision),
+            ],
+            vec![vec![
+                "1".to_owned(),
+                "2".to_owned(),
+                "3".to_owned(),
+                "1.5".to_owned(),
+                "2.5".to_owned()
+            ]]
+        )
+    );
+}
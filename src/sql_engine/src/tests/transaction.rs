@@ -0,0 +1,653 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+use test_helpers::in_memory_backend_storage::InMemoryStorage;
+
+#[rstest::fixture]
+fn storage_with_record(
+    storage_with_table: Arc<FrontendStorage<InMemoryStorage>>,
+) -> Arc<FrontendStorage<InMemoryStorage>> {
+    let storage = storage_with_table;
+    let mut sql_engine = Handler::new(storage.clone());
+    sql_engine
+        .execute("insert into schema_name.table_name values (123);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("row inserted");
+    storage
+}
+
+fn transaction_isolation(sql_engine: &mut InMemorySqlEngine) -> Vec<QueryResult> {
+    sql_engine
+        .execute("show transaction_isolation;")
+        .expect("no system errors")
+        .collect()
+}
+
+fn isolation(value: &str) -> Vec<QueryResult> {
+    selected(
+        vec![("transaction_isolation".to_owned(), PostgreSqlType::VarChar(None))],
+        vec![vec![value.to_owned()]],
+    )
+}
+
+fn records(values: Vec<&str>) -> Vec<QueryResult> {
+    selected(
+        vec![("column_test".to_owned(), PostgreSqlType::SmallInt)],
+        values.into_iter().map(|value| vec![value.to_owned()]).collect(),
+    )
+}
+
+#[cfg(test)]
+mod isolation_level {
+    use super::*;
+
+    #[rstest::rstest]
+    fn read_committed_by_default(mut sql_engine: InMemorySqlEngine) {
+        assert_eq!(transaction_isolation(&mut sql_engine), isolation("read committed"));
+    }
+
+    #[rstest::rstest]
+    fn begin_with_isolation_level(mut sql_engine: InMemorySqlEngine) {
+        sql_engine
+            .execute("begin isolation level serializable;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("transaction started");
+
+        assert_eq!(transaction_isolation(&mut sql_engine), isolation("serializable"));
+
+        sql_engine
+            .execute("commit;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("transaction committed");
+
+        assert_eq!(transaction_isolation(&mut sql_engine), isolation("read committed"));
+    }
+
+    #[rstest::rstest]
+    fn set_transaction_isolation_level(mut sql_engine: InMemorySqlEngine) {
+        sql_engine
+            .execute("begin;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("transaction started");
+        sql_engine
+            .execute("set transaction isolation level repeatable read;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("variable set");
+
+        assert_eq!(transaction_isolation(&mut sql_engine), isolation("repeatable read"));
+    }
+
+    #[rstest::rstest]
+    fn set_transaction_isolation_variable(mut sql_engine: InMemorySqlEngine) {
+        sql_engine
+            .execute("begin;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("transaction started");
+        sql_engine
+            .execute("set transaction_isolation = 'serializable';")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("variable set");
+
+        assert_eq!(transaction_isolation(&mut sql_engine), isolation("serializable"));
+    }
+
+    #[rstest::rstest]
+    fn set_default_transaction_isolation(mut sql_engine: InMemorySqlEngine) {
+        sql_engine
+            .execute("set default_transaction_isolation to 'repeatable read';")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("variable set");
+        sql_engine
+            .execute("begin;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("transaction started");
+
+        assert_eq!(transaction_isolation(&mut sql_engine), isolation("repeatable read"));
+    }
+
+    #[rstest::rstest]
+    fn set_session_characteristics(mut sql_engine: InMemorySqlEngine) {
+        sql_engine
+            .execute("set session characteristics as transaction isolation level serializable;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("variable set");
+
+        assert_eq!(transaction_isolation(&mut sql_engine), isolation("serializable"));
+    }
+
+    #[rstest::rstest]
+    fn invalid_default_transaction_isolation(mut sql_engine: InMemorySqlEngine) {
+        assert_eq!(
+            sql_engine
+                .execute("set default_transaction_isolation = 'snapshot';")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            vec![Err(QueryError::invalid_parameter_value(
+                "default_transaction_isolation".to_owned(),
+                "snapshot".to_owned()
+            ))]
+        );
+    }
+
+    #[rstest::rstest]
+    fn set_isolation_level_after_query(storage_with_record: Arc<FrontendStorage<InMemoryStorage>>) {
+        let mut sql_engine = Handler::new(storage_with_record);
+        sql_engine
+            .execute("begin;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("transaction started");
+        sql_engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("records selected");
+
+        assert_eq!(
+            sql_engine
+                .execute("set transaction isolation level serializable;")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            vec![Err(QueryError::transaction_isolation_after_query())]
+        );
+    }
+}
+
+#[cfg(test)]
+mod guarantees {
+    use super::*;
+
+    #[rstest::rstest]
+    fn read_committed_releases_read_locks_after_statement(storage_with_record: Arc<FrontendStorage<InMemoryStorage>>) {
+        let mut reader = Handler::new(storage_with_record.clone());
+        let mut writer = Handler::new(storage_with_record);
+        reader
+            .execute("begin isolation level read committed;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("transaction started");
+        assert_eq!(
+            reader
+                .execute("select * from schema_name.table_name;")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            records(vec!["123"])
+        );
+
+        writer
+            .execute("set lock_timeout = 50;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("variable set");
+        assert_eq!(
+            writer
+                .execute("insert into schema_name.table_name values (456);")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            vec![Ok(QueryEvent::RecordsInserted(1))]
+        );
+
+        assert_eq!(
+            reader
+                .execute("select * from schema_name.table_name;")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            records(vec!["123", "456"])
+        );
+    }
+
+    #[rstest::rstest(level, case::repeatable_read("repeatable read"), case::serializable("serializable"))]
+    fn read_locks_are_held_until_end_of_transaction(
+        storage_with_record: Arc<FrontendStorage<InMemoryStorage>>,
+        level: &str,
+    ) {
+        let mut reader = Handler::new(storage_with_record.clone());
+        let mut writer = Handler::new(storage_with_record);
+        reader
+            .execute(format!("begin isolation level {};", level).as_str())
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("transaction started");
+        reader
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("records selected");
+
+        writer
+            .execute("set lock_timeout = '50ms';")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("variable set");
+        assert_eq!(
+            writer
+                .execute("insert into schema_name.table_name values (456);")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            vec![Err(QueryError::lock_timeout())]
+        );
+        assert_eq!(
+            reader
+                .execute("select * from schema_name.table_name;")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            records(vec!["123"])
+        );
+
+        reader
+            .execute("commit;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("transaction committed");
+        assert_eq!(
+            writer
+                .execute("insert into schema_name.table_name values (456);")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            vec![Ok(QueryEvent::RecordsInserted(1))]
+        );
+    }
+
+    #[rstest::rstest]
+    fn uncommitted_changes_are_not_visible(storage_with_record: Arc<FrontendStorage<InMemoryStorage>>) {
+        let mut writer = Handler::new(storage_with_record.clone());
+        let mut reader = Handler::new(storage_with_record);
+        writer
+            .execute("begin;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("transaction started");
+        writer
+            .execute("insert into schema_name.table_name values (456);")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("row inserted");
+
+        reader
+            .execute("set lock_timeout = '50ms';")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("variable set");
+        assert_eq!(
+            reader
+                .execute("select * from schema_name.table_name;")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            records(vec!["123"])
+        );
+
+        writer
+            .execute("commit;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("transaction committed");
+        assert_eq!(
+            reader
+                .execute("select * from schema_name.table_name;")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            records(vec!["123", "456"])
+        );
+    }
+
+    #[rstest::rstest]
+    fn rolled_back_changes_are_never_visible(storage_with_record: Arc<FrontendStorage<InMemoryStorage>>) {
+        let mut writer = Handler::new(storage_with_record.clone());
+        let mut reader = Handler::new(storage_with_record);
+        writer
+            .execute("begin; update schema_name.table_name set column_test = 456;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("records updated");
+        assert_eq!(
+            reader
+                .execute("select * from schema_name.table_name;")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            records(vec!["123"])
+        );
+
+        writer
+            .execute("rollback;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("transaction rolled back");
+        assert_eq!(
+            reader
+                .execute("select * from schema_name.table_name;")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            records(vec!["123"])
+        );
+    }
+
+    #[rstest::rstest]
+    fn writers_wait_for_uncommitted_changes(storage_with_record: Arc<FrontendStorage<InMemoryStorage>>) {
+        let mut first = Handler::new(storage_with_record.clone());
+        let mut second = Handler::new(storage_with_record);
+        first
+            .execute("begin; delete from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("records deleted");
+
+        second
+            .execute("set lock_timeout = '50ms';")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("variable set");
+        assert_eq!(
+            second
+                .execute("insert into schema_name.table_name values (456);")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            vec![Err(QueryError::lock_timeout())]
+        );
+    }
+
+    #[rstest::rstest]
+    fn rollback_undoes_changes(storage_with_record: Arc<FrontendStorage<InMemoryStorage>>) {
+        let mut sql_engine = Handler::new(storage_with_record);
+        sql_engine
+            .execute("begin;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("transaction started");
+        sql_engine
+            .execute("insert into schema_name.table_name values (456);")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("row inserted");
+        sql_engine
+            .execute("update schema_name.table_name set column_test = 789;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("records updated");
+        assert_eq!(
+            sql_engine
+                .execute("select * from schema_name.table_name;")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            records(vec!["789", "789"])
+        );
+
+        assert_eq!(
+            sql_engine
+                .execute("rollback;")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            vec![Ok(QueryEvent::TransactionRolledBack)]
+        );
+        assert_eq!(
+            sql_engine
+                .execute("select * from schema_name.table_name;")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            records(vec!["123"])
+        );
+    }
+
+    #[rstest::rstest]
+    fn rollback_undoes_definitions(storage_with_record: Arc<FrontendStorage<InMemoryStorage>>) {
+        let mut sql_engine = Handler::new(storage_with_record);
+        assert_eq!(
+            sql_engine
+                .execute(
+                    "begin; \
+                     create table schema_name.other_table (column_test smallint); \
+                     drop table schema_name.table_name; \
+                     rollback;"
+                )
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            vec![
+                Ok(QueryEvent::TransactionStarted),
+                Ok(QueryEvent::TableCreated),
+                Ok(QueryEvent::TableDropped),
+                Ok(QueryEvent::TransactionRolledBack)
+            ]
+        );
+
+        assert_eq!(
+            sql_engine
+                .execute("select * from schema_name.other_table;")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            vec![Err(QueryError::table_does_not_exist(
+                "schema_name.other_table".to_owned()
+            )
+            .with_schema("schema_name".to_owned())
+            .with_table("other_table".to_owned()))]
+        );
+        assert_eq!(
+            sql_engine
+                .execute("select * from schema_name.table_name;")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            records(vec!["123"])
+        );
+    }
+
+    #[rstest::rstest]
+    fn commit_keeps_changes(storage_with_record: Arc<FrontendStorage<InMemoryStorage>>) {
+        let mut sql_engine = Handler::new(storage_with_record);
+        sql_engine
+            .execute("begin;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("transaction started");
+        sql_engine
+            .execute("delete from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("records deleted");
+        sql_engine
+            .execute("commit;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("transaction committed");
+
+        assert_eq!(
+            sql_engine
+                .execute("select * from schema_name.table_name;")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            records(vec![])
+        );
+    }
+
+    #[rstest::rstest]
+    fn disconnect_rolls_back_transaction(storage_with_record: Arc<FrontendStorage<InMemoryStorage>>) {
+        let mut sql_engine = Handler::new(storage_with_record.clone());
+        sql_engine
+            .execute("begin;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("transaction started");
+        sql_engine
+            .execute("delete from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("records deleted");
+        drop(sql_engine);
+
+        let mut sql_engine = Handler::new(storage_with_record);
+        assert_eq!(
+            sql_engine
+                .execute("select * from schema_name.table_name;")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            records(vec!["123"])
+        );
+    }
+}
+
+#[rstest::rstest]
+fn table_without_schema_in_transaction(storage_with_record: Arc<FrontendStorage<InMemoryStorage>>) {
+    let mut sql_engine = Handler::new(storage_with_record);
+
+    assert_eq!(
+        sql_engine
+            .execute("begin; insert into table_name values (1);")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![
+            Ok(QueryEvent::TransactionStarted),
+            Err(QueryError::schema_does_not_exist("public".to_owned()))
+        ]
+    );
+    assert_eq!(
+        sql_engine
+            .execute("rollback;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::TransactionRolledBack)]
+    );
+}
+
+#[rstest::rstest]
+fn table_in_public_schema_is_rolled_back(mut sql_engine: InMemorySqlEngine) {
+    sql_engine
+        .execute("create schema public; create table table_name (column_test smallint);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+
+    assert_eq!(
+        sql_engine
+            .execute("begin; insert into table_name values (1); rollback;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![
+            Ok(QueryEvent::TransactionStarted),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::TransactionRolledBack)
+        ]
+    );
+    assert_eq!(
+        sql_engine
+            .execute("select * from table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        records(vec![])
+    );
+}
+
+#[cfg(test)]
+mod failed_transaction {
+    use super::*;
+    use protocol::TransactionStatus;
+
+    #[rstest::rstest]
+    fn status_of_transaction_block(mut sql_engine: InMemorySqlEngine) {
+        assert_eq!(sql_engine.transaction_status(), TransactionStatus::Idle);
+
+        sql_engine
+            .execute("begin;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("transaction started");
+        assert_eq!(sql_engine.transaction_status(), TransactionStatus::InTransaction);
+
+        sql_engine
+            .execute("commit;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("transaction committed");
+        assert_eq!(sql_engine.transaction_status(), TransactionStatus::Idle);
+    }
+
+    #[rstest::rstest]
+    fn error_outside_of_transaction_block(mut sql_engine: InMemorySqlEngine) {
+        assert_eq!(
+            sql_engine
+                .execute("select 1 / 0;")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            vec![Err(QueryError::division_by_zero())]
+        );
+
+        assert_eq!(sql_engine.transaction_status(), TransactionStatus::Idle);
+    }
+
+    #[rstest::rstest]
+    fn statements_are_rejected_after_error(mut sql_engine: InMemorySqlEngine) {
+        assert_eq!(
+            sql_engine
+                .execute("begin; select 1 / 0;")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            vec![Ok(QueryEvent::TransactionStarted), Err(QueryError::division_by_zero())]
+        );
+        assert_eq!(sql_engine.transaction_status(), TransactionStatus::Failed);
+
+        assert_eq!(
+            sql_engine
+                .execute("select 1;")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            vec![Err(QueryError::in_failed_transaction())]
+        );
+        assert_eq!(
+            sql_engine
+                .execute("rollback;")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            vec![Ok(QueryEvent::TransactionRolledBack)]
+        );
+        assert_eq!(sql_engine.transaction_status(), TransactionStatus::Idle);
+    }
+
+    #[rstest::rstest]
+    fn commit_rolls_back_failed_transaction(storage_with_record: Arc<FrontendStorage<InMemoryStorage>>) {
+        let mut sql_engine = Handler::new(storage_with_record);
+        sql_engine
+            .execute("begin; delete from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Result<Vec<QueryEvent>, QueryError>>()
+            .expect("records deleted");
+        sql_engine
+            .execute("insert into schema_name.table_name values (1 / 0);")
+            .expect("no system errors")
+            .for_each(drop);
+        assert_eq!(sql_engine.transaction_status(), TransactionStatus::Failed);
+
+        assert_eq!(
+            sql_engine
+                .execute("commit;")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            vec![Ok(QueryEvent::TransactionRolledBack)]
+        );
+        assert_eq!(
+            sql_engine
+                .execute("select * from schema_name.table_name;")
+                .expect("no system errors")
+                .collect::<Vec<QueryResult>>(),
+            records(vec!["123"])
+        );
+    }
+}
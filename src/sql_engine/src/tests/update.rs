@@ -20,8 +20,13 @@ fn update_records_in_non_existent_table(mut sql_engine_with_schema: InMemorySqlE
     assert_eq!(
         sql_engine_with_schema
             .execute("update schema_name.table_name set column_test=789;")
-            .expect("no system errors"),
-        Err(QueryError::table_does_not_exist("schema_name.table_name".to_owned()))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::table_does_not_exist(
+            "schema_name.table_name".to_owned()
+        )
+        .with_schema("schema_name".to_owned())
+        .with_table("table_name".to_owned()))]
     );
 }
 
@@ -30,39 +35,45 @@ fn update_all_records(mut sql_engine_with_schema: InMemorySqlEngine) {
     sql_engine_with_schema
         .execute("create table schema_name.table_name (column_test smallint);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("table created");
     sql_engine_with_schema
         .execute("insert into schema_name.table_name values (123);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("row inserted");
     sql_engine_with_schema
         .execute("insert into schema_name.table_name values (456);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("row inserted");
 
     assert_eq!(
         sql_engine_with_schema
             .execute("select * from schema_name.table_name;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsSelected((
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
             vec![("column_test".to_owned(), PostgreSqlType::SmallInt)],
             vec![vec!["123".to_owned()], vec!["456".to_owned()]]
-        )))
+        )
     );
     assert_eq!(
         sql_engine_with_schema
             .execute("update schema_name.table_name set column_test=789;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsUpdated(2))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsUpdated(2))]
     );
     assert_eq!(
         sql_engine_with_schema
             .execute("select * from schema_name.table_name;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsSelected((
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
             vec![("column_test".to_owned(), PostgreSqlType::SmallInt)],
             vec![vec!["789".to_owned()], vec!["789".to_owned()]]
-        )))
+        )
     );
 }
 
@@ -71,21 +82,25 @@ fn update_single_column_of_all_records(mut sql_engine_with_schema: InMemorySqlEn
     sql_engine_with_schema
         .execute("create table schema_name.table_name (col1 smallint, col2 smallint);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("table created");
     sql_engine_with_schema
         .execute("insert into schema_name.table_name values (123, 789);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("row inserted");
     sql_engine_with_schema
         .execute("insert into schema_name.table_name values (456, 789);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("row inserted");
 
     assert_eq!(
         sql_engine_with_schema
             .execute("select * from schema_name.table_name;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsSelected((
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
             vec![
                 ("col1".to_owned(), PostgreSqlType::SmallInt),
                 ("col2".to_owned(), PostgreSqlType::SmallInt),
@@ -94,19 +109,21 @@ fn update_single_column_of_all_records(mut sql_engine_with_schema: InMemorySqlEn
                 vec!["123".to_owned(), "789".to_owned()],
                 vec!["456".to_owned(), "789".to_owned()],
             ]
-        )))
+        )
     );
     assert_eq!(
         sql_engine_with_schema
             .execute("update schema_name.table_name set col2=357;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsUpdated(2))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsUpdated(2))]
     );
     assert_eq!(
         sql_engine_with_schema
             .execute("select * from schema_name.table_name;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsSelected((
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
             vec![
                 ("col1".to_owned(), PostgreSqlType::SmallInt),
                 ("col2".to_owned(), PostgreSqlType::SmallInt),
@@ -115,7 +132,7 @@ fn update_single_column_of_all_records(mut sql_engine_with_schema: InMemorySqlEn
                 vec!["123".to_owned(), "357".to_owned()],
                 vec!["456".to_owned(), "357".to_owned()],
             ]
-        )))
+        )
     );
 }
 
@@ -124,21 +141,25 @@ fn update_multiple_columns_of_all_records(mut sql_engine_with_schema: InMemorySq
     sql_engine_with_schema
         .execute("create table schema_name.table_name (col1 smallint, col2 smallint, col3 smallint);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("table created");
     sql_engine_with_schema
         .execute("insert into schema_name.table_name values (111, 222, 333);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("row inserted");
     sql_engine_with_schema
         .execute("insert into schema_name.table_name values (444, 555, 666);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("row inserted");
 
     assert_eq!(
         sql_engine_with_schema
             .execute("select * from schema_name.table_name;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsSelected((
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
             vec![
                 ("col1".to_owned(), PostgreSqlType::SmallInt),
                 ("col2".to_owned(), PostgreSqlType::SmallInt),
@@ -148,19 +169,21 @@ fn update_multiple_columns_of_all_records(mut sql_engine_with_schema: InMemorySq
                 vec!["111".to_owned(), "222".to_owned(), "333".to_owned()],
                 vec!["444".to_owned(), "555".to_owned(), "666".to_owned()],
             ]
-        )))
+        )
     );
     assert_eq!(
         sql_engine_with_schema
             .execute("update schema_name.table_name set col3=777, col1=999;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsUpdated(2))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsUpdated(2))]
     );
     assert_eq!(
         sql_engine_with_schema
             .execute("select * from schema_name.table_name;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsSelected((
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
             vec![
                 ("col1".to_owned(), PostgreSqlType::SmallInt),
                 ("col2".to_owned(), PostgreSqlType::SmallInt),
@@ -170,7 +193,7 @@ fn update_multiple_columns_of_all_records(mut sql_engine_with_schema: InMemorySq
                 vec!["999".to_owned(), "222".to_owned(), "777".to_owned()],
                 vec!["999".to_owned(), "555".to_owned(), "777".to_owned()],
             ]
-        )))
+        )
     );
 }
 
@@ -179,17 +202,20 @@ fn update_all_records_in_multiple_columns(mut sql_engine_with_schema: InMemorySq
     sql_engine_with_schema
         .execute("create table schema_name.table_name (column_1 smallint, column_2 smallint, column_3 smallint);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("table created");
     sql_engine_with_schema
         .execute("insert into schema_name.table_name values (1, 2, 3), (4, 5, 6), (7, 8, 9);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("rows inserted");
 
     assert_eq!(
         sql_engine_with_schema
             .execute("select * from schema_name.table_name;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsSelected((
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
             vec![
                 ("column_1".to_owned(), PostgreSqlType::SmallInt),
                 ("column_2".to_owned(), PostgreSqlType::SmallInt),
@@ -200,19 +226,21 @@ fn update_all_records_in_multiple_columns(mut sql_engine_with_schema: InMemorySq
                 vec!["4".to_owned(), "5".to_owned(), "6".to_owned()],
                 vec!["7".to_owned(), "8".to_owned(), "9".to_owned()]
             ]
-        )))
+        )
     );
     assert_eq!(
         sql_engine_with_schema
             .execute("update schema_name.table_name set column_1=10, column_2=-20, column_3=30;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsUpdated(3))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsUpdated(3))]
     );
     assert_eq!(
         sql_engine_with_schema
             .execute("select * from schema_name.table_name;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsSelected((
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
             vec![
                 ("column_1".to_owned(), PostgreSqlType::SmallInt),
                 ("column_2".to_owned(), PostgreSqlType::SmallInt),
@@ -223,7 +251,7 @@ fn update_all_records_in_multiple_columns(mut sql_engine_with_schema: InMemorySq
                 vec!["10".to_owned(), "-20".to_owned(), "30".to_owned()],
                 vec!["10".to_owned(), "-20".to_owned(), "30".to_owned()]
             ]
-        )))
+        )
     );
 }
 
@@ -232,8 +260,13 @@ fn update_records_in_nonexistent_table(mut sql_engine_with_schema: InMemorySqlEn
     assert_eq!(
         sql_engine_with_schema
             .execute("update schema_name.table_name set column_test=789;")
-            .expect("no system errors"),
-        Err(QueryError::table_does_not_exist("schema_name.table_name".to_owned()))
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::table_does_not_exist(
+            "schema_name.table_name".to_owned()
+        )
+        .with_schema("schema_name".to_owned())
+        .with_table("table_name".to_owned()))]
     );
 }
 
@@ -242,28 +275,222 @@ fn update_non_existent_columns_of_records(mut sql_engine_with_schema: InMemorySq
     sql_engine_with_schema
         .execute("create table schema_name.table_name (column_test smallint);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("table created");
     sql_engine_with_schema
         .execute("insert into schema_name.table_name values (123);")
         .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
         .expect("row inserted");
 
     assert_eq!(
         sql_engine_with_schema
             .execute("select * from schema_name.table_name;")
-            .expect("no system errors"),
-        Ok(QueryEvent::RecordsSelected((
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
             vec![("column_test".to_owned(), PostgreSqlType::SmallInt)],
             vec![vec!["123".to_owned()]],
-        )))
+        )
     );
     assert_eq!(
         sql_engine_with_schema
             .execute("update schema_name.table_name set col1=456, col2=789;")
-            .expect("no system errors"),
-        Err(QueryError::column_does_not_exist(vec![
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::column_does_not_exist(vec![
             "col1".to_owned(),
             "col2".to_owned()
-        ]))
+        ]))]
+    );
+}
+
+#[rstest::rstest]
+fn update_boolean_records(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (column_b boolean);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+    sql_engine_with_schema
+        .execute("insert into schema_name.table_name values (true);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("row inserted");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("update schema_name.table_name set column_b = 'no';")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsUpdated(1))]
+    );
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![("column_b".to_owned(), PostgreSqlType::Bool)],
+            vec![vec!["f".to_owned()]]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn update_values_violating_column_types(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (column_si smallint, column_v varchar(2));")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+    sql_engine_with_schema
+        .execute("insert into schema_name.table_name values (1, 'a');")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("row inserted");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("update schema_name.table_name set column_si = '-32769';")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::out_of_range("smallint".to_owned())
+            .with_column("column_si".to_owned())
+            .with_schema("schema_name".to_owned())
+            .with_table("table_name".to_owned()))]
+    );
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("update schema_name.table_name set column_v = 'abc', column_si = 'one';")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::value_too_long("character varying(2)".to_owned())
+            .with_column("column_v".to_owned())
+            .with_schema("schema_name".to_owned())
+            .with_table("table_name".to_owned()))]
+    );
+}
+
+#[rstest::rstest]
+fn update_records_with_values_of_their_columns(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (id integer, ratio real);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+    sql_engine_with_schema
+        .execute("insert into schema_name.table_name values (1, 1.5), (2, 2.5);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("records inserted");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("update schema_name.table_name set id = id + 1, ratio = ratio / id;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsUpdated(2))]
+    );
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![
+                ("id".to_owned(), PostgreSqlType::Integer),
+                ("ratio".to_owned(), PostgreSqlType::Real),
+            ],
+            vec![
+                vec!["2".to_owned(), "1.5".to_owned()],
+                vec!["3".to_owned(), "1.25".to_owned()],
+            ]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn update_records_with_values_of_their_columns_errors(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (id smallint);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+    sql_engine_with_schema
+        .execute("insert into schema_name.table_name values (1), (30000);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("records inserted");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("update schema_name.table_name set id = non_existing + 1;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::column_does_not_exist(vec!["non_existing".to_owned()]))]
+    );
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("update schema_name.table_name set id = id * 2;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Err(QueryError::out_of_range("smallint".to_owned())
+            .with_column("id".to_owned())
+            .with_schema("schema_name".to_owned())
+            .with_table("table_name".to_owned()))]
+    );
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![("id".to_owned(), PostgreSqlType::SmallInt)],
+            vec![vec!["1".to_owned()], vec!["30000".to_owned()]]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn update_records_to_null(mut sql_engine_with_schema: InMemorySqlEngine) {
+    create_table(&mut sql_engine_with_schema, "col1 smallint, col2 smallint");
+    sql_engine_with_schema
+        .execute("insert into schema_name.table_name values (1, 2), (3, 4);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("rows inserted");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("update schema_name.table_name set col1 = NULL;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsUpdated(2))]
+    );
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("update schema_name.table_name set col2 = col1;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsUpdated(2))]
+    );
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select col1, col2 from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![
+            Ok(QueryEvent::RowDescription(
+                vec![
+                    ("col1".to_owned(), PostgreSqlType::SmallInt),
+                    ("col2".to_owned(), PostgreSqlType::SmallInt),
+                ],
+                vec![PostgreSqlFormat::Text; 2]
+            )),
+            Ok(QueryEvent::DataRow(vec![None, None])),
+            Ok(QueryEvent::DataRow(vec![None, None])),
+            Ok(QueryEvent::RecordsSelected(2)),
+        ]
     );
 }
@@ -0,0 +1,160 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+use sql_types::Uuid;
+
+fn generated_uuids(results: Vec<QueryResult>) -> Vec<Uuid> {
+    results
+        .into_iter()
+        .filter_map(|result| match result {
+            Ok(QueryEvent::DataRow(values)) => values[0]
+                .as_ref()
+                .map(|value| std::str::from_utf8(value).expect("text").parse().expect("uuid")),
+            _ => None,
+        })
+        .collect()
+}
+
+#[rstest::rstest]
+fn insert_and_select_uuids(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (column_u uuid);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute(
+                "insert into schema_name.table_name values \
+                 ('a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11'), \
+                 ('{A0EEBC99-9C0B4EF8-BB6D6BB9-BD380A12}'), \
+                 ('a0eebc999c0b4ef8bb6d6bb9bd380a13');"
+            )
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(3))]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![("column_u".to_owned(), PostgreSqlType::Uuid)],
+            vec![
+                vec!["a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11".to_owned()],
+                vec!["a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a12".to_owned()],
+                vec!["a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a13".to_owned()],
+            ]
+        )
+    );
+}
+
+#[rstest::rstest]
+fn insert_generated_uuids(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (column_u uuid);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("insert into schema_name.table_name values (gen_random_uuid()), (gen_random_uuid());")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(2))]
+    );
+
+    let uuids = generated_uuids(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect(),
+    );
+    assert_eq!(uuids.len(), 2);
+    assert_ne!(uuids[0], uuids[1]);
+}
+
+#[rstest::rstest]
+fn uuid_column_default(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (id uuid default gen_random_uuid(), name varchar(10));")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("insert into schema_name.table_name (name) values ('first'), ('second');")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(2))]
+    );
+    assert_eq!(
+        sql_engine_with_schema
+            .execute(
+                "insert into schema_name.table_name (id, name) values ('a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11', 'third');"
+            )
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(1))]
+    );
+
+    let uuids = generated_uuids(
+        sql_engine_with_schema
+            .execute("select id from schema_name.table_name;")
+            .expect("no system errors")
+            .collect(),
+    );
+    assert_eq!(uuids.len(), 3);
+    assert_ne!(uuids[0], uuids[1]);
+    assert_eq!(uuids[2].to_string(), "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11");
+}
+
+#[rstest::rstest]
+fn compare_uuids(mut sql_engine_with_schema: InMemorySqlEngine) {
+    sql_engine_with_schema
+        .execute("create table schema_name.table_name (column_b boolean);")
+        .expect("no system errors")
+        .collect::<Result<Vec<QueryEvent>, QueryError>>()
+        .expect("table created");
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute(
+                "insert into schema_name.table_name values \
+                 (UUID '00000000-0000-0000-0000-000000000001' < UUID 'ffffffff-0000-0000-0000-000000000000'), \
+                 (gen_random_uuid() = gen_random_uuid());"
+            )
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        vec![Ok(QueryEvent::RecordsInserted(2))]
+    );
+
+    assert_eq!(
+        sql_engine_with_schema
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors")
+            .collect::<Vec<QueryResult>>(),
+        selected(
+            vec![("column_b".to_owned(), PostgreSqlType::Bool)],
+            vec![vec!["t".to_owned()], vec!["f".to_owned()]]
+        )
+    );
+}
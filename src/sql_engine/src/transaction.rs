@@ -0,0 +1,94 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sqlparser::ast::{TransactionIsolationLevel, TransactionMode};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use storage::lock::IsolationLevel;
+
+/// State of transaction block
+pub(crate) struct Transaction {
+    pub(crate) isolation: IsolationLevel,
+    // transaction of a query with multiple statements that ends with the
+    // query, `BEGIN` in the query makes it explicit
+    pub(crate) implicit: bool,
+    // isolation level can't be changed after transaction executed its first query
+    pub(crate) queried: bool,
+    // set by results of a statement that failed, shared with them as
+    // results are consumed after the statement is executed
+    failed: Arc<AtomicBool>,
+}
+
+impl Transaction {
+    pub(crate) fn new(isolation: IsolationLevel) -> Self {
+        Self {
+            isolation,
+            implicit: false,
+            queried: false,
+            failed: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub(crate) fn implicit(isolation: IsolationLevel) -> Self {
+        Self {
+            implicit: true,
+            ..Self::new(isolation)
+        }
+    }
+
+    /// statements of a failed transaction are rejected until its end
+    pub(crate) fn is_failed(&self) -> bool {
+        self.failed.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn failure(&self) -> Arc<AtomicBool> {
+        self.failed.clone()
+    }
+}
+
+/// Isolation level from the last `ISOLATION LEVEL` transaction mode
+pub(crate) fn isolation_from_modes(modes: &[TransactionMode]) -> Option<IsolationLevel> {
+    modes.iter().rev().find_map(|mode| match mode {
+        TransactionMode::IsolationLevel(level) => Some(match level {
+            // PostgreSQL treats READ UNCOMMITTED as READ COMMITTED
+            TransactionIsolationLevel::ReadUncommitted | TransactionIsolationLevel::ReadCommitted => {
+                IsolationLevel::ReadCommitted
+            }
+            TransactionIsolationLevel::RepeatableRead => IsolationLevel::RepeatableRead,
+            TransactionIsolationLevel::Serializable => IsolationLevel::Serializable,
+        }),
+        TransactionMode::AccessMode(_) => None,
+    })
+}
+
+/// Isolation level from value of `transaction_isolation` and
+/// `default_transaction_isolation` variables
+pub(crate) fn isolation_from_str(value: &str) -> Option<IsolationLevel> {
+    match value.trim().to_lowercase().as_str() {
+        "read uncommitted" | "read committed" => Some(IsolationLevel::ReadCommitted),
+        "repeatable read" => Some(IsolationLevel::RepeatableRead),
+        "serializable" => Some(IsolationLevel::Serializable),
+        _ => None,
+    }
+}
+
+pub(crate) fn isolation_to_str(isolation: IsolationLevel) -> &'static str {
+    match isolation {
+        IsolationLevel::ReadCommitted => "read committed",
+        IsolationLevel::RepeatableRead => "repeatable read",
+        IsolationLevel::Serializable => "serializable",
+    }
+}
@@ -0,0 +1,161 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::ConstraintError;
+
+/// Parses one dimensional array literal, e.g. `{1, "a b", NULL}`, into text
+/// of its elements, `None` is `NULL` element
+pub(crate) fn parse(value: &str) -> Result<Vec<Option<String>>, ConstraintError> {
+    let elements = value
+        .trim()
+        .strip_prefix('{')
+        .and_then(|elements| elements.strip_suffix('}'))
+        .ok_or(ConstraintError::NotAnArray)?;
+    let mut array = vec![];
+    if elements.trim().is_empty() {
+        return Ok(array);
+    }
+    let mut chars = elements.chars().peekable();
+    loop {
+        while chars.peek().filter(|c| c.is_whitespace()).is_some() {
+            chars.next();
+        }
+        let mut element = String::new();
+        // quoted or escaped element is never NULL
+        let mut literal = false;
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            literal = true;
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => element.push(chars.next().ok_or(ConstraintError::NotAnArray)?),
+                    Some(c) => element.push(c),
+                    None => return Err(ConstraintError::NotAnArray),
+                }
+            }
+            while chars.peek().filter(|c| c.is_whitespace()).is_some() {
+                chars.next();
+            }
+        } else {
+            // length of element without trailing whitespace that is not escaped
+            let mut significant = 0;
+            loop {
+                match chars.peek() {
+                    None | Some(',') => break,
+                    Some('{') | Some('}') | Some('"') => return Err(ConstraintError::NotAnArray),
+                    Some('\\') => {
+                        chars.next();
+                        element.push(chars.next().ok_or(ConstraintError::NotAnArray)?);
+                        literal = true;
+                        significant = element.len();
+                    }
+                    Some(c) => {
+                        let c = *c;
+                        chars.next();
+                        element.push(c);
+                        if !c.is_whitespace() {
+                            significant = element.len();
+                        }
+                    }
+                }
+            }
+            element.truncate(significant);
+            if element.is_empty() && !literal {
+                return Err(ConstraintError::NotAnArray);
+            }
+        }
+        if !literal && element.eq_ignore_ascii_case("NULL") {
+            array.push(None);
+        } else {
+            array.push(Some(element));
+        }
+        match chars.next() {
+            Some(',') => continue,
+            None => return Ok(array),
+            Some(_) => return Err(ConstraintError::NotAnArray),
+        }
+    }
+}
+
+/// Array literal as PostgreSQL prints it, elements are quoted when it is
+/// needed to read them back
+pub(crate) fn format<I: Iterator<Item = Option<String>>>(elements: I) -> String {
+    let elements = elements
+        .map(|element| match element {
+            None => "NULL".to_owned(),
+            Some(element) => {
+                let needs_quotes = element.is_empty()
+                    || element.eq_ignore_ascii_case("NULL")
+                    || element
+                        .chars()
+                        .any(|c| matches!(c, '{' | '}' | ',' | '"' | '\\') || c.is_whitespace());
+                if needs_quotes {
+                    format!("\"{}\"", element.replace('\\', "\\\\").replace('"', "\\\""))
+                } else {
+                    element
+                }
+            }
+        })
+        .collect::<Vec<String>>();
+    format!("{{{}}}", elements.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elements(elements: &[Option<&str>]) -> Vec<Option<String>> {
+        elements.iter().map(|element| element.map(str::to_owned)).collect()
+    }
+
+    #[rstest::rstest(
+        value,
+        expected,
+        case::empty("{}", vec![]),
+        case::numbers("{1,2,3}", vec![Some("1"), Some("2"), Some("3")]),
+        case::whitespace(" { 1 , 2 } ", vec![Some("1"), Some("2")]),
+        case::null("{NULL,null}", vec![None, None]),
+        case::quoted_null(r#"{"NULL"}"#, vec![Some("NULL")]),
+        case::quoted(r#"{"a, b","{}"," c "}"#, vec![Some("a, b"), Some("{}"), Some(" c ")]),
+        case::empty_string(r#"{""}"#, vec![Some("")]),
+        case::escapes(r#"{"a\"b\\c",d\,e}"#, vec![Some("a\"b\\c"), Some("d,e")]),
+        case::inner_space("{a b}", vec![Some("a b")])
+    )]
+    fn parse_array(value: &str, expected: Vec<Option<&str>>) {
+        assert_eq!(parse(value), Ok(elements(&expected)));
+    }
+
+    #[rstest::rstest(
+        value,
+        case::no_braces("1,2"),
+        case::missing_element("{1,,2}"),
+        case::trailing_comma("{1,}"),
+        case::unterminated_quote(r#"{"a}"#),
+        case::text_after_quote(r#"{"a"b}"#),
+        case::nested("{{1,2},{3,4}}")
+    )]
+    fn malformed(value: &str) {
+        assert_eq!(parse(value), Err(ConstraintError::NotAnArray));
+    }
+
+    #[test]
+    fn format_array() {
+        assert_eq!(
+            format(elements(&[Some("1"), None, Some(""), Some("null"), Some("a \"b\""), Some("c\\d")]).into_iter()),
+            r#"{1,NULL,"","null","a \"b\"","c\\d"}"#
+        );
+        assert_eq!(format(Vec::new().into_iter()), "{}");
+    }
+}
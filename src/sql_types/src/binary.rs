@@ -0,0 +1,261 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Values in PostgreSQL binary format as clients send them, the counterpart
+//! of `Datum::to_binary`
+use crate::{datetime::MICROS_PER_DAY, ConstraintError, Datum, Interval, SqlType, Uuid};
+use std::convert::{TryFrom, TryInto};
+
+impl SqlType {
+    /// Decodes value of the type from PostgreSQL binary format
+    pub fn from_binary(&self, bytes: &[u8]) -> Result<Datum, ConstraintError> {
+        match self {
+            Self::Bool => match bytes {
+                [0] => Ok(Datum::Bool(false)),
+                [1] => Ok(Datum::Bool(true)),
+                _ => Err(ConstraintError::InvalidBinaryFormat),
+            },
+            Self::SmallInt => Ok(Datum::Int16(i16::from_be_bytes(fixed(bytes)?))),
+            Self::Integer => Ok(Datum::Int32(i32::from_be_bytes(fixed(bytes)?))),
+            Self::BigInt => Ok(Datum::Int64(i64::from_be_bytes(fixed(bytes)?))),
+            Self::Real => Ok(Datum::Float32(f32::from_be_bytes(fixed(bytes)?))),
+            Self::DoublePrecision => Ok(Datum::Float64(f64::from_be_bytes(fixed(bytes)?))),
+            // precision and scale of the column are applied to the number
+            Self::Decimal(_) => self.constraint().validate(&numeric_text(bytes)?),
+            Self::Char(_) | Self::VarChar(_) | Self::Text | Self::Json | Self::Enum { .. } => {
+                self.constraint().validate(text(bytes)?)
+            }
+            // format version followed by text
+            Self::Jsonb => match bytes.split_first() {
+                Some((1, json)) => self.constraint().validate(text(json)?),
+                _ => Err(ConstraintError::InvalidBinaryFormat),
+            },
+            Self::Bytea => Ok(Datum::Bytea(bytes.to_vec())),
+            Self::Uuid => Ok(Datum::Uuid(Uuid::from_bytes(fixed(bytes)?))),
+            Self::Date => Ok(Datum::Date(i32::from_be_bytes(fixed(bytes)?))),
+            Self::Time => match i64::from_be_bytes(fixed(bytes)?) {
+                micros if (0..=MICROS_PER_DAY).contains(&micros) => Ok(Datum::Time(micros)),
+                _ => Err(ConstraintError::OutOfRange),
+            },
+            // PostgreSQL sends offset in seconds west of UTC
+            Self::TimeWithTimeZone => {
+                let (micros, offset) = split::<8>(bytes)?;
+                let offset = i32::from_be_bytes(fixed(offset)?);
+                Ok(Datum::TimeWithTimeZone(i64::from_be_bytes(micros), -offset))
+            }
+            Self::Timestamp => Ok(Datum::Timestamp(i64::from_be_bytes(fixed(bytes)?))),
+            Self::TimestampWithTimeZone => Ok(Datum::TimestampWithTimeZone(i64::from_be_bytes(fixed(bytes)?))),
+            // microseconds, days and months
+            Self::Interval => {
+                let (micros, rest) = split::<8>(bytes)?;
+                let (days, months) = split::<4>(rest)?;
+                Ok(Datum::Interval(Interval::new(
+                    i32::from_be_bytes(fixed(months)?),
+                    i32::from_be_bytes(days),
+                    i64::from_be_bytes(micros),
+                )))
+            }
+            Self::Array(element) => array(element, bytes),
+        }
+    }
+}
+
+fn fixed<const N: usize>(bytes: &[u8]) -> Result<[u8; N], ConstraintError> {
+    bytes.try_into().map_err(|_| ConstraintError::InvalidBinaryFormat)
+}
+
+fn split<const N: usize>(bytes: &[u8]) -> Result<([u8; N], &[u8]), ConstraintError> {
+    if bytes.len() < N {
+        return Err(ConstraintError::InvalidBinaryFormat);
+    }
+    let (head, rest) = bytes.split_at(N);
+    Ok((fixed(head)?, rest))
+}
+
+fn text(bytes: &[u8]) -> Result<&str, ConstraintError> {
+    std::str::from_utf8(bytes).map_err(|_| ConstraintError::InvalidBinaryFormat)
+}
+
+// number of base 10000 digits, weight of the first digit, sign, display scale
+// and the digits
+fn numeric_text(bytes: &[u8]) -> Result<String, ConstraintError> {
+    let (length, rest) = split::<2>(bytes)?;
+    let (weight, rest) = split::<2>(rest)?;
+    let (sign, rest) = split::<2>(rest)?;
+    let (scale, mut rest) = split::<2>(rest)?;
+    let weight = i16::from_be_bytes(weight) as i64;
+    let scale = u16::from_be_bytes(scale) as usize;
+    let mut digits = vec![];
+    for _ in 0..i16::from_be_bytes(length) {
+        let (digit, tail) = split::<2>(rest)?;
+        match i16::from_be_bytes(digit) {
+            digit @ 0..=9999 => digits.push(digit),
+            _ => return Err(ConstraintError::InvalidBinaryFormat),
+        }
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        return Err(ConstraintError::InvalidBinaryFormat);
+    }
+    let digit = |index: i64| {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| digits.get(index))
+            .copied()
+            .unwrap_or(0)
+    };
+    let mut text = match u16::from_be_bytes(sign) {
+        0x0000 => String::new(),
+        0x4000 => "-".to_owned(),
+        // NaN and infinities are not supported
+        _ => return Err(ConstraintError::InvalidBinaryFormat),
+    };
+    if weight < 0 {
+        text.push('0');
+    }
+    for index in 0..=weight {
+        if index == 0 {
+            text.push_str(&digit(index).to_string());
+        } else {
+            text.push_str(&format!("{:04}", digit(index)));
+        }
+    }
+    if scale > 0 {
+        let mut fraction = String::new();
+        let mut index = weight + 1;
+        while fraction.len() < scale {
+            fraction.push_str(&format!("{:04}", digit(index)));
+            index += 1;
+        }
+        fraction.truncate(scale);
+        text.push('.');
+        text.push_str(&fraction);
+    }
+    Ok(text)
+}
+
+// number of dimensions, flag of NULL elements, element type OID, then length
+// and lower bound of the dimension followed by elements prefixed with their
+// length, -1 for NULL
+fn array(element: &SqlType, bytes: &[u8]) -> Result<Datum, ConstraintError> {
+    let (dimensions, rest) = split::<4>(bytes)?;
+    let (_has_null, rest) = split::<4>(rest)?;
+    let (_element_oid, rest) = split::<4>(rest)?;
+    let (length, mut rest) = match i32::from_be_bytes(dimensions) {
+        0 => return Ok(Datum::Array(vec![])),
+        1 => {
+            let (length, rest) = split::<4>(rest)?;
+            let (_lower_bound, rest) = split::<4>(rest)?;
+            (i32::from_be_bytes(length), rest)
+        }
+        // multidimensional arrays are not supported
+        _ => return Err(ConstraintError::InvalidBinaryFormat),
+    };
+    let mut elements = vec![];
+    for _ in 0..length {
+        let (element_length, tail) = split::<4>(rest)?;
+        match i32::from_be_bytes(element_length) {
+            -1 => {
+                elements.push(Datum::Null);
+                rest = tail;
+            }
+            element_length if element_length >= 0 && tail.len() >= element_length as usize => {
+                let (value, tail) = tail.split_at(element_length as usize);
+                elements.push(element.from_binary(value)?);
+                rest = tail;
+            }
+            _ => return Err(ConstraintError::InvalidBinaryFormat),
+        }
+    }
+    if rest.is_empty() {
+        Ok(Datum::Array(elements))
+    } else {
+        Err(ConstraintError::InvalidBinaryFormat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Numeric;
+
+    #[rstest::rstest(
+        sql_type,
+        datum,
+        case::bool(SqlType::Bool, Datum::Bool(true)),
+        case::smallint(SqlType::SmallInt, Datum::Int16(-12)),
+        case::integer(SqlType::Integer, Datum::Int32(1_000_000)),
+        case::bigint(SqlType::BigInt, Datum::Int64(i64::MIN)),
+        case::real(SqlType::Real, Datum::Float32(1.5)),
+        case::double_precision(SqlType::DoublePrecision, Datum::Float64(-0.25)),
+        case::varchar(SqlType::VarChar(Some(10)), Datum::String("привіт".to_owned())),
+        case::bytea(SqlType::Bytea, Datum::Bytea(vec![0, 255, 10])),
+        case::uuid(SqlType::Uuid, Datum::Uuid(Uuid::from_bytes([7; 16]))),
+        case::date(SqlType::Date, Datum::Date(7336)),
+        case::time(SqlType::Time, Datum::Time(3_600_000_000)),
+        case::time_with_time_zone(SqlType::TimeWithTimeZone, Datum::TimeWithTimeZone(3_600_000_000, 3600)),
+        case::timestamp(SqlType::Timestamp, Datum::Timestamp(-1_000_000)),
+        case::timestamp_with_time_zone(SqlType::TimestampWithTimeZone, Datum::TimestampWithTimeZone(1)),
+        case::interval(SqlType::Interval, Datum::Interval(Interval::new(14, 3, 4_000_000))),
+        case::array(
+            SqlType::Array(Box::new(SqlType::Integer)),
+            Datum::Array(vec![Datum::Int32(1), Datum::Null, Datum::Int32(3)])
+        ),
+        case::empty_array(SqlType::Array(Box::new(SqlType::Text)), Datum::Array(vec![]))
+    )]
+    fn round_trip(sql_type: SqlType, datum: Datum) {
+        assert_eq!(sql_type.from_binary(&datum.to_binary().unwrap()), Ok(datum));
+    }
+
+    #[rstest::rstest(
+        sql_type,
+        value,
+        case::integer(SqlType::Decimal(None), "12345678"),
+        case::fraction(SqlType::Decimal(None), "0.0005"),
+        case::negative(SqlType::Decimal(None), "-12345.678"),
+        case::zero_with_scale(SqlType::Decimal(None), "0.00"),
+        case::precision_and_scale(SqlType::Decimal(Some((10, 3))), "10000.100")
+    )]
+    fn numeric_round_trip(sql_type: SqlType, value: &str) {
+        let numeric = sql_type.constraint().validate(value).unwrap();
+        assert_eq!(
+            sql_type
+                .from_binary(&numeric.to_binary().unwrap())
+                .map(|datum| datum.to_text()),
+            Ok(Some(value.to_owned()))
+        );
+    }
+
+    #[test]
+    fn numeric_is_validated_by_column_type() {
+        let numeric = Datum::Numeric("1234.5".parse::<Numeric>().unwrap());
+        assert_eq!(
+            SqlType::Decimal(Some((3, 0))).from_binary(&numeric.to_binary().unwrap()),
+            Err(ConstraintError::OutOfRange)
+        );
+    }
+
+    #[rstest::rstest(
+        sql_type,
+        bytes,
+        case::short_integer(SqlType::Integer, &[0, 1]),
+        case::long_bool(SqlType::Bool, &[1, 1]),
+        case::not_utf8_text(SqlType::Text, &[0xff, 0xfe]),
+        case::jsonb_version(SqlType::Jsonb, &[2, b'1']),
+        case::truncated_array(SqlType::Array(Box::new(SqlType::Integer)), &[0, 0, 0, 1, 0, 0])
+    )]
+    fn invalid_binary_format(sql_type: SqlType, bytes: &[u8]) {
+        assert_eq!(sql_type.from_binary(bytes), Err(ConstraintError::InvalidBinaryFormat));
+    }
+}
@@ -0,0 +1,154 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::ConstraintError;
+
+/// Text format of `BYTEA` values that is chosen by `bytea_output` setting
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ByteaOutput {
+    /// `\x` followed by two hexadecimal digits per byte
+    #[default]
+    Hex,
+    /// printable ASCII characters as is, other bytes as octal escapes
+    Escape,
+}
+
+impl ByteaOutput {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "hex" => Some(Self::Hex),
+            "escape" => Some(Self::Escape),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Hex => "hex",
+            Self::Escape => "escape",
+        }
+    }
+
+    pub(crate) fn format(&self, bytes: &[u8]) -> String {
+        match self {
+            Self::Hex => {
+                let mut formatted = String::with_capacity(2 + bytes.len() * 2);
+                formatted.push_str("\\x");
+                for byte in bytes {
+                    formatted.push_str(format!("{:02x}", byte).as_str());
+                }
+                formatted
+            }
+            Self::Escape => {
+                let mut formatted = String::with_capacity(bytes.len());
+                for byte in bytes {
+                    match byte {
+                        b'\\' => formatted.push_str("\\\\"),
+                        0x20..=0x7e => formatted.push(*byte as char),
+                        byte => formatted.push_str(format!("\\{:03o}", byte).as_str()),
+                    }
+                }
+                formatted
+            }
+        }
+    }
+}
+
+/// Accepts hex format, `\x` followed by pairs of hexadecimal digits that can
+/// be separated by whitespace, and escape format, where `\\` is a backslash
+/// and `\ooo` is a byte in octal, any other character is its UTF-8 bytes
+pub(crate) fn parse(value: &str) -> Result<Vec<u8>, ConstraintError> {
+    match value.strip_prefix("\\x").or_else(|| value.strip_prefix("\\X")) {
+        Some(hex) => parse_hex(hex),
+        None => parse_escape(value),
+    }
+}
+
+fn parse_hex(value: &str) -> Result<Vec<u8>, ConstraintError> {
+    let mut bytes = Vec::with_capacity(value.len() / 2);
+    let mut digits = value.chars().filter(|c| !c.is_ascii_whitespace());
+    while let Some(high) = digits.next() {
+        let low = digits.next().ok_or(ConstraintError::NotABytea)?;
+        match (high.to_digit(16), low.to_digit(16)) {
+            (Some(high), Some(low)) => bytes.push((high * 16 + low) as u8),
+            _ => return Err(ConstraintError::NotABytea),
+        }
+    }
+    Ok(bytes)
+}
+
+fn parse_escape(value: &str) -> Result<Vec<u8>, ConstraintError> {
+    let input = value.as_bytes();
+    let mut bytes = Vec::with_capacity(input.len());
+    let mut index = 0;
+    while index < input.len() {
+        if input[index] != b'\\' {
+            bytes.push(input[index]);
+            index += 1;
+        } else if input.get(index + 1) == Some(&b'\\') {
+            bytes.push(b'\\');
+            index += 2;
+        } else {
+            match input.get(index + 1..index + 4) {
+                Some([first @ b'0'..=b'3', second @ b'0'..=b'7', third @ b'0'..=b'7']) => {
+                    bytes.push((first - b'0') * 64 + (second - b'0') * 8 + (third - b'0'));
+                    index += 4;
+                }
+                _ => return Err(ConstraintError::NotABytea),
+            }
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rstest::rstest(
+        value,
+        expected,
+        case::hex("\\x00ff10", vec![0, 255, 16]),
+        case::hex_upper_case("\\XDEADBEEF", vec![0xde, 0xad, 0xbe, 0xef]),
+        case::hex_with_spaces("\\x 01 02", vec![1, 2]),
+        case::empty_hex("\\x", vec![]),
+        case::escape("a\\\\b", vec![b'a', b'\\', b'b']),
+        case::octal("\\000\\377", vec![0, 255]),
+        case::utf_8("ж", vec![0xd0, 0xb6])
+    )]
+    fn parse_bytea(value: &str, expected: Vec<u8>) {
+        assert_eq!(parse(value), Ok(expected));
+    }
+
+    #[rstest::rstest(
+        value,
+        case::odd_number_of_digits("\\x123"),
+        case::not_hex_digit("\\xzz"),
+        case::lone_backslash("a\\b"),
+        case::octal_out_of_range("\\400")
+    )]
+    fn invalid_bytea(value: &str) {
+        assert_eq!(parse(value), Err(ConstraintError::NotABytea));
+    }
+
+    #[test]
+    fn hex_output() {
+        assert_eq!(ByteaOutput::Hex.format(&[0, 171, 255]), "\\x00abff");
+    }
+
+    #[test]
+    fn escape_output() {
+        assert_eq!(ByteaOutput::Escape.format(b"a\\b\x00\n\xff"), "a\\\\b\\000\\012\\377");
+    }
+}
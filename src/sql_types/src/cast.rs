@@ -0,0 +1,293 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Casts between types as PostgreSQL defines them in `pg_cast` catalog. Every
+//! cast is allowed in some context: implicit casts are applied to operands of
+//! expressions, assignment casts to values that are stored into columns and
+//! explicit casts only by `CAST(value AS type)` and `value::type`
+use crate::{
+    datetime::{self, DATE_INFINITY, DATE_NEG_INFINITY, MICROS_PER_DAY, TIMESTAMP_INFINITY},
+    ConstraintError, Datum, Interval, SqlType,
+};
+
+/// Context of a cast from the most to the least restrictive one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CastContext {
+    Implicit,
+    Assignment,
+    Explicit,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CastError {
+    /// there is no cast between the types in the context
+    NotAllowed,
+    /// value can't be represented by the target type
+    Constraint(ConstraintError),
+}
+
+/// Converts value into the target type if there is a cast between them that
+/// is allowed in the context
+pub fn cast(datum: &Datum, target: &SqlType, context: CastContext) -> Result<Datum, CastError> {
+    if let (Datum::Array(elements), SqlType::Array(element)) = (datum, target) {
+        return elements
+            .iter()
+            .map(|datum| cast(datum, element, context))
+            .collect::<Result<Vec<Datum>, CastError>>()
+            .map(Datum::Array);
+    }
+    match cast_context(datum, target) {
+        Some(required) if required <= context => convert(datum, target, context).map_err(CastError::Constraint),
+        _ => Err(CastError::NotAllowed),
+    }
+}
+
+// the least restrictive context in which the value can be cast into the type
+fn cast_context(datum: &Datum, target: &SqlType) -> Option<CastContext> {
+    use CastContext::*;
+    let context = match (datum, target) {
+        (Datum::Null, _) => Implicit,
+        // string values are not distinguished from untyped literals that are
+        // converted by input function of any type
+        (Datum::String(_), _) => Implicit,
+        // every type has I/O conversion to string types
+        (_, SqlType::Text) | (_, SqlType::VarChar(_)) | (_, SqlType::Char(_)) => Assignment,
+        (Datum::Bool(_), SqlType::Bool) => Implicit,
+        (Datum::Bool(_), SqlType::Integer) | (Datum::Int32(_), SqlType::Bool) => Explicit,
+        (Datum::Int16(_), SqlType::SmallInt) => Implicit,
+        (Datum::Int16(_), SqlType::Integer) | (Datum::Int32(_), SqlType::Integer) => Implicit,
+        (Datum::Int16(_), SqlType::BigInt)
+        | (Datum::Int32(_), SqlType::BigInt)
+        | (Datum::Int64(_), SqlType::BigInt) => Implicit,
+        (Datum::Int32(_), SqlType::SmallInt)
+        | (Datum::Int64(_), SqlType::SmallInt)
+        | (Datum::Int64(_), SqlType::Integer) => Assignment,
+        (Datum::Int16(_), _) | (Datum::Int32(_), _) | (Datum::Int64(_), _) if is_approximate_or_numeric(target) => {
+            Implicit
+        }
+        (Datum::Float32(_), SqlType::Real) | (Datum::Float32(_), SqlType::DoublePrecision) => Implicit,
+        (Datum::Float64(_), SqlType::DoublePrecision) => Implicit,
+        (Datum::Float64(_), SqlType::Real) => Assignment,
+        (Datum::Float32(_), _) | (Datum::Float64(_), _) if is_integer(target) => Assignment,
+        (Datum::Float32(_), SqlType::Decimal(_)) | (Datum::Float64(_), SqlType::Decimal(_)) => Assignment,
+        (Datum::Numeric(_), _) if is_integer(target) => Assignment,
+        (Datum::Numeric(_), _) if is_approximate_or_numeric(target) => Implicit,
+        (Datum::Date(_), SqlType::Date)
+        | (Datum::Date(_), SqlType::Timestamp)
+        | (Datum::Date(_), SqlType::TimestampWithTimeZone) => Implicit,
+        (Datum::Timestamp(_), SqlType::Timestamp) | (Datum::Timestamp(_), SqlType::TimestampWithTimeZone) => Implicit,
+        (Datum::Timestamp(_), SqlType::Date) | (Datum::Timestamp(_), SqlType::Time) => Assignment,
+        (Datum::TimestampWithTimeZone(_), SqlType::TimestampWithTimeZone) => Implicit,
+        (Datum::TimestampWithTimeZone(_), SqlType::Timestamp)
+        | (Datum::TimestampWithTimeZone(_), SqlType::Date)
+        | (Datum::TimestampWithTimeZone(_), SqlType::Time)
+        | (Datum::TimestampWithTimeZone(_), SqlType::TimeWithTimeZone) => Assignment,
+        (Datum::Time(_), SqlType::Time)
+        | (Datum::Time(_), SqlType::TimeWithTimeZone)
+        | (Datum::Time(_), SqlType::Interval) => Implicit,
+        (Datum::TimeWithTimeZone(_, _), SqlType::TimeWithTimeZone) => Implicit,
+        (Datum::TimeWithTimeZone(_, _), SqlType::Time) => Assignment,
+        (Datum::Interval(_), SqlType::Interval) => Implicit,
+        (Datum::Interval(_), SqlType::Time) => Assignment,
+        (Datum::Json(_), SqlType::Json) | (Datum::Jsonb(_), SqlType::Jsonb) => Implicit,
+        (Datum::Json(_), SqlType::Jsonb) | (Datum::Jsonb(_), SqlType::Json) => Assignment,
+        (Datum::Bytea(_), SqlType::Bytea) | (Datum::Uuid(_), SqlType::Uuid) => Implicit,
+        (Datum::Enum(type_oid, _, _), SqlType::Enum { oid, .. }) if type_oid == oid => Implicit,
+        _ => return None,
+    };
+    Some(context)
+}
+
+fn convert(datum: &Datum, target: &SqlType, context: CastContext) -> Result<Datum, ConstraintError> {
+    let text = match (datum, target) {
+        (Datum::Null, _) => return Ok(Datum::Null),
+        (Datum::Bool(value), SqlType::Integer) => return Ok(Datum::Int32(*value as i32)),
+        (Datum::Int32(value), SqlType::Bool) => return Ok(Datum::Bool(*value != 0)),
+        // integers are rounded half to even as `rint` does
+        (Datum::Float32(_), _) | (Datum::Float64(_), _) if is_integer(target) => {
+            let value = match datum {
+                Datum::Float32(value) => *value as f64,
+                Datum::Float64(value) => *value,
+                _ => unreachable!(),
+            };
+            if !value.is_finite() {
+                return Err(ConstraintError::OutOfRange);
+            }
+            format!("{}", value.round_ties_even() as i128)
+        }
+        (Datum::Numeric(value), _) if is_integer(target) => value.round(0).to_string(),
+        // time zone is not known here, so UTC is used to convert timestamps
+        (Datum::Date(date), SqlType::Timestamp) => return Ok(Datum::Timestamp(crate::date_to_timestamp(*date))),
+        (Datum::Date(date), SqlType::TimestampWithTimeZone) => {
+            return Ok(Datum::TimestampWithTimeZone(crate::date_to_timestamp(*date)))
+        }
+        (Datum::Timestamp(timestamp), SqlType::TimestampWithTimeZone) => {
+            return Ok(Datum::TimestampWithTimeZone(*timestamp))
+        }
+        (Datum::TimestampWithTimeZone(timestamp), SqlType::Timestamp) => return Ok(Datum::Timestamp(*timestamp)),
+        (Datum::Timestamp(timestamp), SqlType::Date) | (Datum::TimestampWithTimeZone(timestamp), SqlType::Date) => {
+            return Ok(Datum::Date(timestamp_to_date(*timestamp)))
+        }
+        (Datum::Timestamp(timestamp), SqlType::Time) | (Datum::TimestampWithTimeZone(timestamp), SqlType::Time) => {
+            return time_of(*timestamp).map(Datum::Time)
+        }
+        (Datum::TimestampWithTimeZone(timestamp), SqlType::TimeWithTimeZone) => {
+            return time_of(*timestamp).map(|time| Datum::TimeWithTimeZone(time, 0))
+        }
+        (Datum::Time(time), SqlType::TimeWithTimeZone) => return Ok(Datum::TimeWithTimeZone(*time, 0)),
+        (Datum::TimeWithTimeZone(time, _), SqlType::Time) => return Ok(Datum::Time(*time)),
+        (Datum::Time(time), SqlType::Interval) => return Ok(Datum::Interval(Interval::new(0, 0, *time))),
+        (Datum::Interval(interval), SqlType::Time) => {
+            return Ok(Datum::Time(interval.micros().rem_euclid(MICROS_PER_DAY)))
+        }
+        // boolean is `true` or `false` as text rather than its output `t` or `f`
+        (Datum::Bool(value), _) if is_string(target) => value.to_string(),
+        (datum, _) => datum.to_text().unwrap_or_default(),
+    };
+    // explicit cast to string of limited length truncates the value
+    let text = match target {
+        SqlType::Char(length) | SqlType::VarChar(Some(length)) if context == CastContext::Explicit => {
+            text.chars().take(*length as usize).collect()
+        }
+        _ => text,
+    };
+    target.constraint().validate(&text)
+}
+
+fn timestamp_to_date(timestamp: i64) -> i32 {
+    match timestamp {
+        TIMESTAMP_INFINITY => DATE_INFINITY,
+        timestamp if datetime::is_infinite(timestamp) => DATE_NEG_INFINITY,
+        timestamp => timestamp.div_euclid(MICROS_PER_DAY) as i32,
+    }
+}
+
+fn time_of(timestamp: i64) -> Result<i64, ConstraintError> {
+    if datetime::is_infinite(timestamp) {
+        Err(ConstraintError::OutOfRange)
+    } else {
+        Ok(timestamp.rem_euclid(MICROS_PER_DAY))
+    }
+}
+
+fn is_integer(sql_type: &SqlType) -> bool {
+    matches!(sql_type, SqlType::SmallInt | SqlType::Integer | SqlType::BigInt)
+}
+
+fn is_approximate_or_numeric(sql_type: &SqlType) -> bool {
+    matches!(sql_type, SqlType::Real | SqlType::DoublePrecision | SqlType::Decimal(_))
+}
+
+fn is_string(sql_type: &SqlType) -> bool {
+    matches!(sql_type, SqlType::Char(_) | SqlType::VarChar(_) | SqlType::Text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rstest::rstest(
+        datum,
+        target,
+        expected,
+        case::widening_int(Datum::Int16(1), SqlType::BigInt, Datum::Int64(1)),
+        case::int_to_numeric(Datum::Int32(2), SqlType::Decimal(None), Datum::Numeric("2".parse().unwrap())),
+        case::int_to_double(Datum::Int64(3), SqlType::DoublePrecision, Datum::Float64(3.0)),
+        case::numeric_to_double(Datum::Numeric("1.5".parse().unwrap()), SqlType::DoublePrecision, Datum::Float64(1.5)),
+        case::date_to_timestamp(Datum::Date(1), SqlType::Timestamp, Datum::Timestamp(MICROS_PER_DAY)),
+        case::time_to_interval(Datum::Time(10), SqlType::Interval, Datum::Interval(Interval::new(0, 0, 10))),
+        case::untyped_string(Datum::String("42".to_owned()), SqlType::Integer, Datum::Int32(42)),
+        case::null(Datum::Null, SqlType::Uuid, Datum::Null)
+    )]
+    fn implicit_casts(datum: Datum, target: SqlType, expected: Datum) {
+        assert_eq!(cast(&datum, &target, CastContext::Implicit), Ok(expected));
+    }
+
+    #[rstest::rstest(
+        datum,
+        target,
+        expected,
+        case::narrowing_int(Datum::Int64(1), SqlType::SmallInt, Datum::Int16(1)),
+        case::float_to_int(Datum::Float64(2.5), SqlType::Integer, Datum::Int32(2)),
+        case::numeric_to_int(Datum::Numeric("2.5".parse().unwrap()), SqlType::Integer, Datum::Int32(3)),
+        case::double_to_real(Datum::Float64(1.5), SqlType::Real, Datum::Float32(1.5)),
+        case::int_to_text(Datum::Int32(42), SqlType::Text, Datum::String("42".to_owned())),
+        case::bool_to_text(Datum::Bool(true), SqlType::VarChar(None), Datum::String("true".to_owned())),
+        case::timestamp_to_date(Datum::Timestamp(MICROS_PER_DAY + 1), SqlType::Date, Datum::Date(1)),
+        case::int_array(
+            Datum::Array(vec![Datum::Int64(1), Datum::Null]),
+            SqlType::Array(Box::new(SqlType::Integer)),
+            Datum::Array(vec![Datum::Int32(1), Datum::Null])
+        )
+    )]
+    fn assignment_casts(datum: Datum, target: SqlType, expected: Datum) {
+        assert_eq!(cast(&datum, &target, CastContext::Implicit), Err(CastError::NotAllowed));
+        assert_eq!(cast(&datum, &target, CastContext::Assignment), Ok(expected));
+    }
+
+    #[rstest::rstest(
+        datum,
+        target,
+        expected,
+        case::bool_to_int(Datum::Bool(true), SqlType::Integer, Datum::Int32(1)),
+        case::int_to_bool(Datum::Int32(5), SqlType::Bool, Datum::Bool(true))
+    )]
+    fn explicit_casts(datum: Datum, target: SqlType, expected: Datum) {
+        assert_eq!(
+            cast(&datum, &target, CastContext::Assignment),
+            Err(CastError::NotAllowed)
+        );
+        assert_eq!(cast(&datum, &target, CastContext::Explicit), Ok(expected));
+    }
+
+    #[test]
+    fn no_cast() {
+        assert_eq!(
+            cast(&Datum::Bool(true), &SqlType::Date, CastContext::Explicit),
+            Err(CastError::NotAllowed)
+        );
+        assert_eq!(
+            cast(&Datum::Int64(1), &SqlType::Bool, CastContext::Explicit),
+            Err(CastError::NotAllowed)
+        );
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert_eq!(
+            cast(&Datum::Int32(40_000), &SqlType::SmallInt, CastContext::Assignment),
+            Err(CastError::Constraint(ConstraintError::OutOfRange))
+        );
+        assert_eq!(
+            cast(&Datum::Float64(f64::NAN), &SqlType::Integer, CastContext::Assignment),
+            Err(CastError::Constraint(ConstraintError::OutOfRange))
+        );
+    }
+
+    #[test]
+    fn length_of_strings() {
+        assert_eq!(
+            cast(
+                &Datum::String("abc".to_owned()),
+                &SqlType::VarChar(Some(2)),
+                CastContext::Explicit
+            ),
+            Ok(Datum::String("ab".to_owned()))
+        );
+        assert_eq!(
+            cast(&Datum::Int32(123), &SqlType::VarChar(Some(2)), CastContext::Assignment),
+            Err(CastError::Constraint(ConstraintError::ValueTooLong))
+        );
+    }
+}
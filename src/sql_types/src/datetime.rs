@@ -0,0 +1,581 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dates are stored as number of days and times as number of microseconds
+//! since 2000-01-01 00:00:00 the same way as PostgreSQL does, so that binary
+//! format of values is the same as storage format.
+
+use crate::{ConstraintError, SqlType};
+
+pub(crate) const MICROS_PER_SECOND: i64 = 1_000_000;
+pub(crate) const MICROS_PER_DAY: i64 = 86_400 * MICROS_PER_SECOND;
+// days between 1970-01-01 and 2000-01-01
+const UNIX_EPOCH_DAYS: i64 = 10_957;
+// PostgreSQL represents `infinity` and `-infinity` timestamps with these
+pub(crate) const TIMESTAMP_INFINITY: i64 = i64::MAX;
+pub(crate) const TIMESTAMP_NEG_INFINITY: i64 = i64::MIN;
+pub(crate) const DATE_INFINITY: i32 = i32::MAX;
+pub(crate) const DATE_NEG_INFINITY: i32 = i32::MIN;
+// 294276-12-31 is the last day of PostgreSQL timestamp range
+const MAX_YEAR: i64 = 294_276;
+// 4713-01-01 BC is the first day of PostgreSQL date range
+const MIN_YEAR: i64 = -4712;
+
+/// Session time zone as a fixed offset from UTC. There is no time zone
+/// database, so offsets of named time zones and their daylight saving time
+/// are unknown and only UTC is accepted by name
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeZone {
+    name: String,
+    // seconds east of UTC
+    offset: i32,
+}
+
+impl TimeZone {
+    pub fn utc() -> Self {
+        Self {
+            name: "UTC".to_owned(),
+            offset: 0,
+        }
+    }
+
+    /// Accepts UTC and its aliases, number of hours east of UTC as in
+    /// `SET TIME ZONE -8` and ISO 8601 offsets like `+03:00`
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        match value.to_lowercase().as_str() {
+            "utc" | "gmt" | "z" | "zulu" | "uct" | "universal" | "etc/utc" | "etc/gmt" => {
+                return Some(Self {
+                    name: value.to_owned(),
+                    offset: 0,
+                })
+            }
+            _ => {}
+        }
+        let offset = match value.parse::<f64>() {
+            Ok(hours) if hours.abs() < 16.0 => (hours * 3600.0).round() as i32,
+            Ok(_) => return None,
+            Err(_) => parse_offset(value)?,
+        };
+        Some(Self {
+            name: value.to_owned(),
+            offset,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Seconds east of UTC
+    pub fn offset(&self) -> i32 {
+        self.offset
+    }
+
+    /// Adds offset of the time zone to literal of time zone aware type that
+    /// does not specify its own, so that it is interpreted as local time
+    pub fn qualify(&self, sql_type: &SqlType, literal: String) -> String {
+        let has_zone = match sql_type {
+            SqlType::TimestampWithTimeZone => parse_timestamp(&literal).map(|(_, zone)| zone.is_some()),
+            SqlType::TimeWithTimeZone => parse_time_with_zone(&literal).map(|(_, zone)| zone.is_some()),
+            SqlType::Array(element) => {
+                return match crate::array::parse(&literal) {
+                    Ok(elements) => crate::array::format(
+                        elements
+                            .into_iter()
+                            .map(|element_literal| element_literal.map(|literal| self.qualify(element, literal))),
+                    ),
+                    Err(_) => literal,
+                }
+            }
+            _ => return literal,
+        };
+        match has_zone {
+            Ok(false) => literal + " " + format_offset(self.offset).as_str(),
+            _ => literal,
+        }
+    }
+}
+
+impl Default for TimeZone {
+    fn default() -> Self {
+        Self::utc()
+    }
+}
+
+pub(crate) fn parse_date(value: &str) -> Result<i32, ConstraintError> {
+    let value = value.trim();
+    match value.to_lowercase().as_str() {
+        "epoch" => return Ok(-UNIX_EPOCH_DAYS as i32),
+        "infinity" | "+infinity" => return Ok(DATE_INFINITY),
+        "-infinity" => return Ok(DATE_NEG_INFINITY),
+        _ => {}
+    }
+    let (value, bc) = strip_era(value);
+    let (date, rest) = split_date(value)?;
+    if !rest.trim().is_empty() {
+        return Err(ConstraintError::NotADateTime);
+    }
+    days(date, bc).map(|days| days as i32)
+}
+
+pub(crate) fn parse_time(value: &str) -> Result<i64, ConstraintError> {
+    match parse_time_with_zone(value)? {
+        (time, None) => Ok(time),
+        // PostgreSQL ignores time zone of TIME WITHOUT TIME ZONE values
+        (time, Some(_)) => Ok(time),
+    }
+}
+
+pub(crate) fn parse_time_with_zone(value: &str) -> Result<(i64, Option<i32>), ConstraintError> {
+    let value = value.trim();
+    let (time, rest) = split_time(value)?;
+    let zone = parse_zone(rest)?;
+    Ok((time, zone))
+}
+
+/// Microseconds since 2000-01-01 and offset from UTC if the literal has it
+pub(crate) fn parse_timestamp(value: &str) -> Result<(i64, Option<i32>), ConstraintError> {
+    let value = value.trim();
+    match value.to_lowercase().as_str() {
+        "epoch" => return Ok((-UNIX_EPOCH_DAYS * MICROS_PER_DAY, Some(0))),
+        "infinity" | "+infinity" => return Ok((TIMESTAMP_INFINITY, Some(0))),
+        "-infinity" => return Ok((TIMESTAMP_NEG_INFINITY, Some(0))),
+        _ => {}
+    }
+    let (value, bc) = strip_era(value);
+    let (date, rest) = split_date(value)?;
+    let days = days(date, bc)?;
+    let rest = rest
+        .strip_prefix('T')
+        .or_else(|| rest.strip_prefix('t'))
+        .unwrap_or(rest);
+    let rest = rest.trim_start();
+    let (time, zone) = if rest.starts_with(|c: char| c.is_ascii_digit()) {
+        let (time, rest) = split_time(rest)?;
+        (time, parse_zone(rest)?)
+    } else {
+        (0, parse_zone(rest)?)
+    };
+    let local = days * MICROS_PER_DAY + time;
+    if days > max_days() {
+        return Err(ConstraintError::DateTimeFieldOverflow);
+    }
+    Ok((local, zone))
+}
+
+/// Timestamp of midnight of the date, infinite dates are infinite timestamps
+pub fn date_to_timestamp(date: i32) -> i64 {
+    match date {
+        DATE_INFINITY => TIMESTAMP_INFINITY,
+        DATE_NEG_INFINITY => TIMESTAMP_NEG_INFINITY,
+        date => date as i64 * MICROS_PER_DAY,
+    }
+}
+
+pub(crate) fn is_infinite(timestamp: i64) -> bool {
+    timestamp == TIMESTAMP_INFINITY || timestamp == TIMESTAMP_NEG_INFINITY
+}
+
+// from 4713-01-01 BC to 294276-12-31 as literals are
+pub(crate) fn is_valid_timestamp(timestamp: i64) -> bool {
+    let days = timestamp.div_euclid(MICROS_PER_DAY);
+    days >= epoch_days_from_civil(MIN_YEAR, 1, 1) && days <= max_days()
+}
+
+pub(crate) fn civil_from_epoch_days(days: i64) -> (i64, u32, u32) {
+    civil_from_days(days + UNIX_EPOCH_DAYS)
+}
+
+pub(crate) fn epoch_days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    days_from_civil(year, month, day) - UNIX_EPOCH_DAYS
+}
+
+pub(crate) fn format_date(days: i32) -> String {
+    match days {
+        DATE_INFINITY => "infinity".to_owned(),
+        DATE_NEG_INFINITY => "-infinity".to_owned(),
+        days => {
+            let (year, month, day) = civil_from_days(days as i64 + UNIX_EPOCH_DAYS);
+            with_era(format!("{:04}-{:02}-{:02}", era_year(year), month, day), year)
+        }
+    }
+}
+
+pub(crate) fn format_time(micros: i64) -> String {
+    let seconds = micros / MICROS_PER_SECOND;
+    let fraction = micros % MICROS_PER_SECOND;
+    let mut time = format!("{:02}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if fraction != 0 {
+        time += format!(".{:06}", fraction).trim_end_matches('0');
+    }
+    time
+}
+
+pub(crate) fn format_time_with_zone(micros: i64, offset: i32) -> String {
+    format_time(micros) + format_offset(offset).as_str()
+}
+
+pub(crate) fn format_timestamp(micros: i64) -> String {
+    match micros {
+        TIMESTAMP_INFINITY => "infinity".to_owned(),
+        TIMESTAMP_NEG_INFINITY => "-infinity".to_owned(),
+        micros => {
+            let days = micros.div_euclid(MICROS_PER_DAY);
+            let (year, month, day) = civil_from_days(days + UNIX_EPOCH_DAYS);
+            with_era(
+                format!(
+                    "{:04}-{:02}-{:02} {}",
+                    era_year(year),
+                    month,
+                    day,
+                    format_time(micros.rem_euclid(MICROS_PER_DAY))
+                ),
+                year,
+            )
+        }
+    }
+}
+
+pub(crate) fn format_timestamp_with_zone(micros: i64, time_zone: &TimeZone) -> String {
+    match micros {
+        TIMESTAMP_INFINITY | TIMESTAMP_NEG_INFINITY => format_timestamp(micros),
+        micros => {
+            let local = format_timestamp(micros + time_zone.offset as i64 * MICROS_PER_SECOND);
+            // era goes after offset in PostgreSQL output
+            match local.strip_suffix(" BC") {
+                Some(local) => local.to_owned() + format_offset(time_zone.offset).as_str() + " BC",
+                None => local + format_offset(time_zone.offset).as_str(),
+            }
+        }
+    }
+}
+
+// `+03`, `-09:30` or `+05:45:30` as PostgreSQL prints offsets
+pub(crate) fn format_offset(offset: i32) -> String {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let offset = offset.abs();
+    let mut formatted = format!("{}{:02}", sign, offset / 3600);
+    if offset % 3600 != 0 {
+        formatted += format!(":{:02}", offset / 60 % 60).as_str();
+    }
+    if offset % 60 != 0 {
+        formatted += format!(":{:02}", offset % 60).as_str();
+    }
+    formatted
+}
+
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month as i64 + 9) % 12) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+pub(crate) fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        _ if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        _ => 28,
+    }
+}
+
+fn max_days() -> i64 {
+    epoch_days_from_civil(MAX_YEAR, 12, 31)
+}
+
+fn strip_era(value: &str) -> (&str, bool) {
+    let lower = value.to_lowercase();
+    if lower.ends_with(" bc") {
+        (value[..value.len() - 3].trim_end(), true)
+    } else if lower.ends_with(" ad") {
+        (value[..value.len() - 3].trim_end(), false)
+    } else {
+        (value, false)
+    }
+}
+
+// there is no year zero, 1 BC is year 0 in proleptic Gregorian calendar
+fn era_year(year: i64) -> i64 {
+    if year <= 0 {
+        1 - year
+    } else {
+        year
+    }
+}
+
+fn with_era(formatted: String, year: i64) -> String {
+    if year <= 0 {
+        formatted + " BC"
+    } else {
+        formatted
+    }
+}
+
+fn days((year, month, day): (i64, u32, u32), bc: bool) -> Result<i64, ConstraintError> {
+    if year == 0 {
+        return Err(ConstraintError::DateTimeFieldOverflow);
+    }
+    let year = if bc { 1 - year } else { year };
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return Err(ConstraintError::DateTimeFieldOverflow);
+    }
+    if !(MIN_YEAR..=MAX_YEAR).contains(&year) {
+        return Err(ConstraintError::DateTimeFieldOverflow);
+    }
+    Ok(days_from_civil(year, month, day) - UNIX_EPOCH_DAYS)
+}
+
+// `YYYY-MM-DD` followed by the rest of a literal
+fn split_date(value: &str) -> Result<((i64, u32, u32), &str), ConstraintError> {
+    let (year, rest) = split_number(value).ok_or(ConstraintError::NotADateTime)?;
+    let rest = rest.strip_prefix('-').ok_or(ConstraintError::NotADateTime)?;
+    let (month, rest) = split_number(rest).ok_or(ConstraintError::NotADateTime)?;
+    let rest = rest.strip_prefix('-').ok_or(ConstraintError::NotADateTime)?;
+    let (day, rest) = split_number(rest).ok_or(ConstraintError::NotADateTime)?;
+    if month > 12 || day > 31 {
+        return Err(ConstraintError::DateTimeFieldOverflow);
+    }
+    Ok(((year, month as u32, day as u32), rest))
+}
+
+// `HH:MM[:SS[.FFFFFF]]` followed by the rest of a literal
+fn split_time(value: &str) -> Result<(i64, &str), ConstraintError> {
+    let (hours, rest) = split_number(value).ok_or(ConstraintError::NotADateTime)?;
+    let rest = rest.strip_prefix(':').ok_or(ConstraintError::NotADateTime)?;
+    let (minutes, mut rest) = split_number(rest).ok_or(ConstraintError::NotADateTime)?;
+    let mut seconds = 0;
+    let mut micros = 0;
+    if let Some(after_colon) = rest.strip_prefix(':') {
+        let (parsed, after_seconds) = split_number(after_colon).ok_or(ConstraintError::NotADateTime)?;
+        seconds = parsed;
+        rest = after_seconds;
+        if let Some(fraction) = rest.strip_prefix('.') {
+            let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+            // fraction is rounded to microseconds
+            let padded = format!("{:0<7}", &fraction[..digits]);
+            micros = (padded[..7].parse::<i64>().unwrap() + 5) / 10;
+            rest = &fraction[digits..];
+        }
+    }
+    if minutes > 59 || seconds > 59 || hours > 24 {
+        return Err(ConstraintError::DateTimeFieldOverflow);
+    }
+    let time = ((hours * 60 + minutes) * 60 + seconds) * MICROS_PER_SECOND + micros;
+    if time > MICROS_PER_DAY {
+        return Err(ConstraintError::DateTimeFieldOverflow);
+    }
+    Ok((time, rest))
+}
+
+fn split_number(value: &str) -> Option<(i64, &str)> {
+    let digits = value.bytes().take_while(u8::is_ascii_digit).count();
+    if digits == 0 || digits > 9 {
+        return None;
+    }
+    Some((value[..digits].parse().ok()?, &value[digits..]))
+}
+
+fn parse_zone(value: &str) -> Result<Option<i32>, ConstraintError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    match value.to_lowercase().as_str() {
+        "z" | "utc" | "gmt" => Ok(Some(0)),
+        _ => parse_offset(value).map(Some).ok_or(ConstraintError::NotADateTime),
+    }
+}
+
+// `+HH`, `+HH:MM`, `+HHMM` or `+HH:MM:SS` offset east of UTC
+fn parse_offset(value: &str) -> Option<i32> {
+    let sign = match value.as_bytes().first()? {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let value = &value[1..];
+    let parts = if value.contains(':') {
+        value.split(':').map(str::to_owned).collect::<Vec<String>>()
+    } else if value.len() > 2 {
+        let (hours, minutes) = value.split_at(value.len() - 2);
+        vec![hours.to_owned(), minutes.to_owned()]
+    } else {
+        vec![value.to_owned()]
+    };
+    if parts.len() > 3 || parts.iter().any(|part| part.is_empty() || part.len() > 2) {
+        return None;
+    }
+    let mut seconds = 0;
+    for (index, part) in parts.iter().enumerate() {
+        let number = part.parse::<i32>().ok()?;
+        if index > 0 && number > 59 {
+            return None;
+        }
+        seconds += number * [3600, 60, 1][index];
+    }
+    if seconds > 15 * 3600 + 59 * 60 {
+        return None;
+    }
+    Some(sign * seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rstest::rstest(
+        value,
+        expected,
+        case::millennium("2000-01-01", 0),
+        case::leap_day("2020-02-29", 7364),
+        case::before_epoch("1999-12-31", -1),
+        case::unix_epoch("epoch", -10957)
+    )]
+    fn dates(value: &str, expected: i32) {
+        assert_eq!(parse_date(value), Ok(expected));
+        assert_eq!(parse_date(format_date(expected).as_str()), Ok(expected));
+    }
+
+    #[test]
+    fn bc_dates() {
+        let days = parse_date("0044-03-15 BC").unwrap();
+        assert_eq!(format_date(days), "0044-03-15 BC");
+    }
+
+    #[rstest::rstest(
+        value,
+        error,
+        case::not_leap_year("2019-02-29", ConstraintError::DateTimeFieldOverflow),
+        case::month("2019-13-01", ConstraintError::DateTimeFieldOverflow),
+        case::year_zero("0000-01-01", ConstraintError::DateTimeFieldOverflow),
+        case::garbage("yesterday", ConstraintError::NotADateTime),
+        case::trailing("2020-01-01 abc", ConstraintError::NotADateTime)
+    )]
+    fn invalid_dates(value: &str, error: ConstraintError) {
+        assert_eq!(parse_date(value), Err(error));
+    }
+
+    #[rstest::rstest(
+        value,
+        expected,
+        case::minutes("04:05", "04:05:00"),
+        case::seconds("04:05:06", "04:05:06"),
+        case::fraction("04:05:06.789", "04:05:06.789"),
+        case::rounded_fraction("04:05:06.0000005", "04:05:06.000001"),
+        case::end_of_day("24:00:00", "24:00:00")
+    )]
+    fn times(value: &str, expected: &str) {
+        assert_eq!(parse_time(value).map(format_time), Ok(expected.to_owned()));
+    }
+
+    #[test]
+    fn invalid_times() {
+        assert_eq!(parse_time("25:00"), Err(ConstraintError::DateTimeFieldOverflow));
+        assert_eq!(parse_time("12:60"), Err(ConstraintError::DateTimeFieldOverflow));
+        assert_eq!(parse_time("noon"), Err(ConstraintError::NotADateTime));
+    }
+
+    #[rstest::rstest(
+        value,
+        micros,
+        zone,
+        case::date_only("2000-01-02", MICROS_PER_DAY, None),
+        case::iso_separator("2000-01-01T00:00:01", MICROS_PER_SECOND, None),
+        case::utc("2000-01-01 00:00:00Z", 0, Some(0)),
+        case::offset("2000-01-01 00:00:00+03", 0, Some(3 * 3600)),
+        case::offset_with_minutes("2000-01-01 00:00:00 -09:30", 0, Some(-(9 * 3600 + 30 * 60))),
+        case::compact_offset("2000-01-01 00:00:00+0530", 0, Some(5 * 3600 + 30 * 60)),
+        case::date_with_offset("2000-01-01 +01", 0, Some(3600))
+    )]
+    fn timestamps(value: &str, micros: i64, zone: Option<i32>) {
+        assert_eq!(parse_timestamp(value), Ok((micros, zone)));
+    }
+
+    #[test]
+    fn timestamp_text() {
+        let (micros, _) = parse_timestamp("1999-12-31 23:59:59.5").unwrap();
+        assert_eq!(format_timestamp(micros), "1999-12-31 23:59:59.5");
+        assert_eq!(format_timestamp(TIMESTAMP_INFINITY), "infinity");
+    }
+
+    #[test]
+    fn timestamp_with_time_zone_text() {
+        let time_zone = TimeZone::parse("+05:30").unwrap();
+        assert_eq!(format_timestamp_with_zone(0, &time_zone), "2000-01-01 05:30:00+05:30");
+        assert_eq!(
+            format_timestamp_with_zone(0, &TimeZone::utc()),
+            "2000-01-01 00:00:00+00"
+        );
+    }
+
+    #[rstest::rstest(
+        name,
+        offset,
+        case::utc("UTC", Some(0)),
+        case::gmt("gmt", Some(0)),
+        case::hours("-8", Some(-8 * 3600)),
+        case::fractional_hours("5.5", Some(5 * 3600 + 30 * 60)),
+        case::iso_offset("+03:00", Some(3 * 3600)),
+        case::named("Europe/Berlin", None)
+    )]
+    fn time_zones(name: &str, offset: Option<i32>) {
+        assert_eq!(TimeZone::parse(name).map(|time_zone| time_zone.offset()), offset);
+    }
+
+    #[test]
+    fn qualify_literals_without_zone() {
+        let time_zone = TimeZone::parse("-8").unwrap();
+        assert_eq!(
+            time_zone.qualify(&SqlType::TimestampWithTimeZone, "2000-01-01 10:00".to_owned()),
+            "2000-01-01 10:00 -08"
+        );
+        assert_eq!(
+            time_zone.qualify(&SqlType::TimestampWithTimeZone, "2000-01-01 10:00+01".to_owned()),
+            "2000-01-01 10:00+01"
+        );
+        assert_eq!(
+            time_zone.qualify(&SqlType::Timestamp, "2000-01-01 10:00".to_owned()),
+            "2000-01-01 10:00"
+        );
+    }
+
+    #[test]
+    fn civil_calendar() {
+        for days in -800_000..800_000 {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+    }
+}
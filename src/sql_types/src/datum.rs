@@ -0,0 +1,444 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{array, datetime, ByteaOutput, Interval, Json, Numeric, TimeZone, Uuid};
+use protocol::sql_types::PostgreSqlType;
+use serde::{Deserialize, Serialize};
+
+/// Typed value of a column
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Datum {
+    Null,
+    Bool(bool),
+    Int16(i16),
+    Int32(i32),
+    Int64(i64),
+    Float32(f32),
+    Float64(f64),
+    Numeric(Numeric),
+    String(String),
+    // days since 2000-01-01
+    Date(i32),
+    // microseconds since midnight
+    Time(i64),
+    // microseconds since midnight and offset in seconds east of UTC
+    TimeWithTimeZone(i64, i32),
+    // microseconds since 2000-01-01 00:00:00
+    Timestamp(i64),
+    // microseconds since 2000-01-01 00:00:00 UTC
+    TimestampWithTimeZone(i64),
+    Interval(Interval),
+    Bytea(Vec<u8>),
+    Uuid(Uuid),
+    // text as it was given
+    Json(String),
+    Jsonb(Json),
+    // elements of one dimensional array
+    Array(Vec<Datum>),
+    // OID of enum type, position of the label in declaration order and the label
+    Enum(u32, u32, String),
+}
+
+/// Session settings that affect text format of values
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextFormat {
+    pub time_zone: TimeZone,
+    pub bytea_output: ByteaOutput,
+}
+
+impl Datum {
+    /// Value in PostgreSQL text format, `None` represents `NULL`
+    pub fn to_text(&self) -> Option<String> {
+        self.to_text_in(&TextFormat::default())
+    }
+
+    /// Value in PostgreSQL text format with `TIMESTAMP WITH TIME ZONE` values
+    /// shown in session time zone and `BYTEA` values in session output format
+    pub fn to_text_in(&self, format: &TextFormat) -> Option<String> {
+        match self {
+            Self::Null => None,
+            Self::Bool(true) => Some("t".to_owned()),
+            Self::Bool(false) => Some("f".to_owned()),
+            Self::Int16(value) => Some(value.to_string()),
+            Self::Int32(value) => Some(value.to_string()),
+            Self::Int64(value) => Some(value.to_string()),
+            Self::Float32(value) => Some(float_to_text(*value as f64, format!("{:e}", value), 6)),
+            Self::Float64(value) => Some(float_to_text(*value, format!("{:e}", value), 15)),
+            Self::Numeric(value) => Some(value.to_string()),
+            Self::String(value) => Some(value.clone()),
+            Self::Date(days) => Some(datetime::format_date(*days)),
+            Self::Time(micros) => Some(datetime::format_time(*micros)),
+            Self::TimeWithTimeZone(micros, offset) => Some(datetime::format_time_with_zone(*micros, *offset)),
+            Self::Timestamp(micros) => Some(datetime::format_timestamp(*micros)),
+            Self::TimestampWithTimeZone(micros) => {
+                Some(datetime::format_timestamp_with_zone(*micros, &format.time_zone))
+            }
+            Self::Interval(value) => Some(value.to_string()),
+            Self::Bytea(value) => Some(format.bytea_output.format(value)),
+            Self::Uuid(value) => Some(value.to_string()),
+            Self::Json(value) => Some(value.clone()),
+            Self::Jsonb(value) => Some(value.to_string()),
+            Self::Enum(_, _, label) => Some(label.clone()),
+            Self::Array(elements) => Some(array::format(elements.iter().map(|element| element.to_text_in(format)))),
+        }
+    }
+
+    /// Value in PostgreSQL binary format, `None` represents `NULL`
+    pub fn to_binary(&self) -> Option<Vec<u8>> {
+        match self {
+            Self::Null => None,
+            Self::Bool(value) => Some(vec![*value as u8]),
+            Self::Int16(value) => Some(value.to_be_bytes().to_vec()),
+            Self::Int32(value) => Some(value.to_be_bytes().to_vec()),
+            Self::Int64(value) => Some(value.to_be_bytes().to_vec()),
+            Self::Float32(value) => Some(value.to_be_bytes().to_vec()),
+            Self::Float64(value) => Some(value.to_be_bytes().to_vec()),
+            Self::Numeric(value) => Some(value.to_binary()),
+            Self::String(value) => Some(value.as_bytes().to_vec()),
+            Self::Date(days) => Some(days.to_be_bytes().to_vec()),
+            Self::Time(micros) | Self::Timestamp(micros) | Self::TimestampWithTimeZone(micros) => {
+                Some(micros.to_be_bytes().to_vec())
+            }
+            // PostgreSQL sends offset in seconds west of UTC
+            Self::TimeWithTimeZone(micros, offset) => {
+                let mut bytes = micros.to_be_bytes().to_vec();
+                bytes.extend_from_slice(&(-offset).to_be_bytes());
+                Some(bytes)
+            }
+            Self::Interval(value) => Some(value.to_binary()),
+            Self::Bytea(value) => Some(value.clone()),
+            Self::Uuid(value) => Some(value.as_bytes().to_vec()),
+            Self::Json(value) => Some(value.as_bytes().to_vec()),
+            // format version followed by text
+            Self::Jsonb(value) => {
+                let mut bytes = vec![1];
+                bytes.extend_from_slice(value.to_string().as_bytes());
+                Some(bytes)
+            }
+            Self::Enum(_, _, label) => Some(label.as_bytes().to_vec()),
+            // number of dimensions, flag of NULL elements, element type OID,
+            // then length and lower bound of the dimension followed by
+            // elements prefixed with their length, -1 for NULL
+            Self::Array(elements) => {
+                let element_oid = elements
+                    .iter()
+                    .find_map(Datum::pg_type)
+                    .unwrap_or(PostgreSqlType::Text)
+                    .pg_oid();
+                let has_null = elements.contains(&Datum::Null);
+                let mut bytes = vec![];
+                bytes.extend_from_slice(&(!elements.is_empty() as i32).to_be_bytes());
+                bytes.extend_from_slice(&(has_null as i32).to_be_bytes());
+                bytes.extend_from_slice(&element_oid.to_be_bytes());
+                if !elements.is_empty() {
+                    bytes.extend_from_slice(&(elements.len() as i32).to_be_bytes());
+                    bytes.extend_from_slice(&1i32.to_be_bytes());
+                }
+                for element in elements {
+                    match element.to_binary() {
+                        Some(element) => {
+                            bytes.extend_from_slice(&(element.len() as i32).to_be_bytes());
+                            bytes.extend_from_slice(&element);
+                        }
+                        None => bytes.extend_from_slice(&(-1i32).to_be_bytes()),
+                    }
+                }
+                Some(bytes)
+            }
+        }
+    }
+
+    /// PostgreSQL type of the value, strings are `TEXT`, `None` for `NULL`
+    pub fn pg_type(&self) -> Option<PostgreSqlType> {
+        match self {
+            Self::Null => None,
+            Self::Bool(_) => Some(PostgreSqlType::Bool),
+            Self::Int16(_) => Some(PostgreSqlType::SmallInt),
+            Self::Int32(_) => Some(PostgreSqlType::Integer),
+            Self::Int64(_) => Some(PostgreSqlType::BigInt),
+            Self::Float32(_) => Some(PostgreSqlType::Real),
+            Self::Float64(_) => Some(PostgreSqlType::DoublePrecision),
+            Self::Numeric(_) => Some(PostgreSqlType::Decimal),
+            Self::String(_) => Some(PostgreSqlType::Text),
+            Self::Date(_) => Some(PostgreSqlType::Date),
+            Self::Time(_) => Some(PostgreSqlType::Time),
+            Self::TimeWithTimeZone(_, _) => Some(PostgreSqlType::TimeWithTimeZone),
+            Self::Timestamp(_) => Some(PostgreSqlType::Timestamp),
+            Self::TimestampWithTimeZone(_) => Some(PostgreSqlType::TimestampWithTimeZone),
+            Self::Interval(_) => Some(PostgreSqlType::Interval),
+            Self::Bytea(_) => Some(PostgreSqlType::Bytea),
+            Self::Uuid(_) => Some(PostgreSqlType::Uuid),
+            Self::Json(_) => Some(PostgreSqlType::Json),
+            Self::Jsonb(_) => Some(PostgreSqlType::Jsonb),
+            Self::Enum(oid, _, _) => Some(PostgreSqlType::Enum(*oid)),
+            Self::Array(elements) => Some(PostgreSqlType::Array(Box::new(
+                elements.iter().find_map(Datum::pg_type).unwrap_or(PostgreSqlType::Text),
+            ))),
+        }
+    }
+}
+
+// PostgreSQL prints the shortest representation that reads back to the same
+// value, using exponent notation when decimal exponent is less than -4 or is
+// not less than number of significant digits of the type
+fn float_to_text(value: f64, scientific: String, max_exponent: i32) -> String {
+    if value.is_nan() {
+        return "NaN".to_owned();
+    }
+    if value.is_infinite() {
+        return if value > 0.0 { "Infinity" } else { "-Infinity" }.to_owned();
+    }
+    let (mantissa, exponent) = scientific.split_at(scientific.find('e').unwrap());
+    let exponent = exponent[1..].parse::<i32>().unwrap();
+    if exponent < -4 || exponent >= max_exponent {
+        let sign = if exponent < 0 { '-' } else { '+' };
+        return format!("{}e{}{:02}", mantissa, sign, exponent.abs());
+    }
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(mantissa) => ("-", mantissa),
+        None => ("", mantissa),
+    };
+    let digits = mantissa.replace('.', "");
+    if exponent < 0 {
+        format!("{}0.{}{}", sign, "0".repeat((-exponent - 1) as usize), digits)
+    } else if digits.len() as i32 <= exponent + 1 {
+        format!(
+            "{}{}{}",
+            sign,
+            digits,
+            "0".repeat((exponent + 1) as usize - digits.len())
+        )
+    } else {
+        let (integer, fraction) = digits.split_at((exponent + 1) as usize);
+        format!("{}{}.{}", sign, integer, fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(test)]
+    mod text_format {
+        use super::*;
+
+        #[test]
+        fn null() {
+            assert_eq!(Datum::Null.to_text(), None);
+        }
+
+        #[test]
+        fn bool() {
+            assert_eq!(Datum::Bool(true).to_text(), Some("t".to_owned()));
+            assert_eq!(Datum::Bool(false).to_text(), Some("f".to_owned()));
+        }
+
+        #[test]
+        fn ints() {
+            assert_eq!(Datum::Int16(-32768).to_text(), Some("-32768".to_owned()));
+            assert_eq!(Datum::Int32(2147483647).to_text(), Some("2147483647".to_owned()));
+            assert_eq!(
+                Datum::Int64(-9223372036854775808).to_text(),
+                Some("-9223372036854775808".to_owned())
+            );
+        }
+
+        #[test]
+        fn string() {
+            assert_eq!(Datum::String("a|b".to_owned()).to_text(), Some("a|b".to_owned()));
+        }
+
+        #[rstest::rstest(
+            value,
+            expected,
+            case::zero(0.0, "0"),
+            case::negative_zero(-0.0, "-0"),
+            case::fraction(1.5, "1.5"),
+            case::negative(-123.25, "-123.25"),
+            case::small(0.0001, "0.0001"),
+            case::smaller(0.00001, "1e-05"),
+            case::shortest_representation(0.1, "0.1"),
+            case::large(123456789012345.0, "123456789012345"),
+            case::larger(1234567890123456.0, "1.234567890123456e+15"),
+            case::huge(1e300, "1e+300"),
+            case::nan(f64::NAN, "NaN"),
+            case::infinity(f64::INFINITY, "Infinity"),
+            case::negative_infinity(f64::NEG_INFINITY, "-Infinity")
+        )]
+        fn double_precision(value: f64, expected: &str) {
+            assert_eq!(Datum::Float64(value).to_text(), Some(expected.to_owned()));
+        }
+
+        #[rstest::rstest(
+            value,
+            expected,
+            case::fraction(1.5, "1.5"),
+            case::shortest_representation(0.1, "0.1"),
+            case::large(123456.0, "123456"),
+            case::larger(1234567.0, "1.234567e+06"),
+            case::nan(f32::NAN, "NaN"),
+            case::infinity(f32::INFINITY, "Infinity")
+        )]
+        fn real(value: f32, expected: &str) {
+            assert_eq!(Datum::Float32(value).to_text(), Some(expected.to_owned()));
+        }
+
+        #[test]
+        fn date_and_time() {
+            assert_eq!(Datum::Date(0).to_text(), Some("2000-01-01".to_owned()));
+            assert_eq!(Datum::Time(3_600_000_000).to_text(), Some("01:00:00".to_owned()));
+            assert_eq!(
+                Datum::TimeWithTimeZone(3_600_000_000, -9 * 3600).to_text(),
+                Some("01:00:00-09".to_owned())
+            );
+            assert_eq!(
+                Datum::Timestamp(1_500_000).to_text(),
+                Some("2000-01-01 00:00:01.5".to_owned())
+            );
+        }
+
+        #[test]
+        fn timestamp_with_time_zone() {
+            assert_eq!(
+                Datum::TimestampWithTimeZone(0).to_text(),
+                Some("2000-01-01 00:00:00+00".to_owned())
+            );
+            assert_eq!(
+                Datum::TimestampWithTimeZone(0).to_text_in(&TextFormat {
+                    time_zone: TimeZone::parse("-3").unwrap(),
+                    ..TextFormat::default()
+                }),
+                Some("1999-12-31 21:00:00-03".to_owned())
+            );
+        }
+
+        #[test]
+        fn bytea() {
+            assert_eq!(Datum::Bytea(vec![1, 255]).to_text(), Some("\\x01ff".to_owned()));
+            assert_eq!(
+                Datum::Bytea(vec![b'a', 1]).to_text_in(&TextFormat {
+                    bytea_output: ByteaOutput::Escape,
+                    ..TextFormat::default()
+                }),
+                Some("a\\001".to_owned())
+            );
+        }
+
+        #[test]
+        fn interval() {
+            assert_eq!(
+                Datum::Interval(Interval::new(14, -3, 3_600_000_000)).to_text(),
+                Some("1 year 2 mons -3 days +01:00:00".to_owned())
+            );
+        }
+
+        #[test]
+        fn numeric() {
+            assert_eq!(
+                Datum::Numeric("-12.500".parse().unwrap()).to_text(),
+                Some("-12.500".to_owned())
+            );
+        }
+    }
+
+    #[cfg(test)]
+    mod binary_format {
+        use super::*;
+
+        #[test]
+        fn null() {
+            assert_eq!(Datum::Null.to_binary(), None);
+        }
+
+        #[test]
+        fn bool() {
+            assert_eq!(Datum::Bool(true).to_binary(), Some(vec![1]));
+            assert_eq!(Datum::Bool(false).to_binary(), Some(vec![0]));
+        }
+
+        #[test]
+        fn ints() {
+            assert_eq!(Datum::Int16(1).to_binary(), Some(vec![0, 1]));
+            assert_eq!(Datum::Int32(1).to_binary(), Some(vec![0, 0, 0, 1]));
+            assert_eq!(Datum::Int64(1).to_binary(), Some(vec![0, 0, 0, 0, 0, 0, 0, 1]));
+        }
+
+        #[test]
+        fn string() {
+            assert_eq!(Datum::String("str".to_owned()).to_binary(), Some(vec![115, 116, 114]));
+        }
+
+        #[test]
+        fn bytea() {
+            assert_eq!(Datum::Bytea(vec![0, 1]).to_binary(), Some(vec![0, 1]));
+        }
+
+        #[test]
+        fn uuid() {
+            assert_eq!(Datum::Uuid(Uuid::from_bytes([7; 16])).to_binary(), Some(vec![7; 16]));
+        }
+
+        #[test]
+        fn array() {
+            assert_eq!(
+                Datum::Array(vec![Datum::Int16(1), Datum::Null]).to_binary(),
+                Some(vec![
+                    0, 0, 0, 1, // one dimension
+                    0, 0, 0, 1, // has NULL
+                    0, 0, 0, 21, // int2 OID
+                    0, 0, 0, 2, // length
+                    0, 0, 0, 1, // lower bound
+                    0, 0, 0, 2, 0, 1, // first element
+                    255, 255, 255, 255, // NULL
+                ])
+            );
+            assert_eq!(
+                Datum::Array(vec![]).to_binary(),
+                Some(vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 25])
+            );
+        }
+
+        #[test]
+        fn jsonb() {
+            assert_eq!(
+                Datum::Jsonb("[1]".parse().unwrap()).to_binary(),
+                Some(vec![1, b'[', b'1', b']'])
+            );
+        }
+
+        #[test]
+        fn floats() {
+            assert_eq!(Datum::Float32(1.0).to_binary(), Some(vec![63, 128, 0, 0]));
+            assert_eq!(Datum::Float64(1.0).to_binary(), Some(vec![63, 240, 0, 0, 0, 0, 0, 0]));
+        }
+
+        #[test]
+        fn date_and_time() {
+            assert_eq!(Datum::Date(-1).to_binary(), Some(vec![255, 255, 255, 255]));
+            assert_eq!(Datum::Timestamp(1).to_binary(), Some(vec![0, 0, 0, 0, 0, 0, 0, 1]));
+            assert_eq!(
+                Datum::TimeWithTimeZone(1, 3600).to_binary(),
+                Some(vec![0, 0, 0, 0, 0, 0, 0, 1, 255, 255, 241, 240])
+            );
+        }
+
+        #[test]
+        fn numeric() {
+            assert_eq!(
+                Datum::Numeric("1.5".parse().unwrap()).to_binary(),
+                Some(vec![0, 2, 0, 0, 0, 0, 0, 1, 0, 1, 0x13, 0x88])
+            );
+        }
+    }
+}
@@ -0,0 +1,782 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    datetime::{self, MICROS_PER_DAY, MICROS_PER_SECOND},
+    ConstraintError,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+const MICROS_PER_MINUTE: i64 = 60 * MICROS_PER_SECOND;
+const MICROS_PER_HOUR: i64 = 60 * MICROS_PER_MINUTE;
+// PostgreSQL assumes that month has 30 days when it has to compare intervals
+// or to convert fraction of a month into days
+const DAYS_PER_MONTH: i64 = 30;
+
+/// Time span that is kept as separate number of months, days and
+/// microseconds the same way as PostgreSQL does, because neither month nor
+/// day has fixed length when added to a timestamp.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Interval {
+    months: i32,
+    days: i32,
+    micros: i64,
+}
+
+impl Interval {
+    pub fn new(months: i32, days: i32, micros: i64) -> Self {
+        Self { months, days, micros }
+    }
+
+    /// Difference of two times of day
+    pub fn between_times(left: i64, right: i64) -> Self {
+        Self::new(0, 0, left - right)
+    }
+
+    /// Difference of two timestamps, whole days of which are moved into days
+    /// field as PostgreSQL does. `None` if either of them is infinite or the
+    /// difference is too big.
+    pub fn between_timestamps(left: i64, right: i64) -> Option<Self> {
+        if datetime::is_infinite(left) || datetime::is_infinite(right) {
+            return None;
+        }
+        let micros = left.checked_sub(right)?;
+        Some(Self::new(
+            0,
+            i32::try_from(micros / MICROS_PER_DAY).ok()?,
+            micros % MICROS_PER_DAY,
+        ))
+    }
+
+    pub fn months(&self) -> i32 {
+        self.months
+    }
+
+    pub fn days(&self) -> i32 {
+        self.days
+    }
+
+    pub fn micros(&self) -> i64 {
+        self.micros
+    }
+
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(Self::new(
+            self.months.checked_add(other.months)?,
+            self.days.checked_add(other.days)?,
+            self.micros.checked_add(other.micros)?,
+        ))
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        self.checked_add(&other.checked_neg()?)
+    }
+
+    pub fn checked_neg(&self) -> Option<Self> {
+        Some(Self::new(
+            self.months.checked_neg()?,
+            self.days.checked_neg()?,
+            self.micros.checked_neg()?,
+        ))
+    }
+
+    /// Multiplies every field by the factor, fractions of months and days are
+    /// carried over to smaller fields
+    pub fn mul(&self, factor: f64) -> Option<Self> {
+        self.scale(|value| value * factor)
+    }
+
+    /// Divides every field by the divisor, fractions of months and days are
+    /// carried over to smaller fields
+    pub fn div(&self, divisor: f64) -> Option<Self> {
+        self.scale(|value| value / divisor)
+    }
+
+    // the same steps as `interval_mul` of PostgreSQL
+    fn scale(&self, scale: impl Fn(f64) -> f64) -> Option<Self> {
+        let months = scale(self.months as f64);
+        let days = scale(self.days as f64);
+        let month_remainder_days = (months - months.trunc()) * DAYS_PER_MONTH as f64;
+        let mut remainder_micros = ((days - days.trunc() + month_remainder_days - month_remainder_days.trunc())
+            * MICROS_PER_DAY as f64)
+            .round();
+        let mut result_days = to_i32(days.trunc())?;
+        if remainder_micros.abs() >= MICROS_PER_DAY as f64 {
+            let whole_days = (remainder_micros / MICROS_PER_DAY as f64).trunc();
+            result_days = result_days.checked_add(to_i32(whole_days)?)?;
+            remainder_micros -= whole_days * MICROS_PER_DAY as f64;
+        }
+        result_days = result_days.checked_add(month_remainder_days.trunc() as i32)?;
+        let micros = (scale(self.micros as f64) + remainder_micros).round();
+        Some(Self::new(to_i32(months.trunc())?, result_days, to_i64(micros).ok()?))
+    }
+
+    /// Adds the interval to timestamp in microseconds. Months are added first
+    /// keeping day of month unless it does not exist in the resulting month,
+    /// then days and then microseconds. Infinite timestamps stay infinite,
+    /// `None` if result is out of timestamp range.
+    pub fn add_to_timestamp(&self, timestamp: i64) -> Option<i64> {
+        if datetime::is_infinite(timestamp) {
+            return Some(timestamp);
+        }
+        let mut days = timestamp.div_euclid(MICROS_PER_DAY);
+        let time = timestamp.rem_euclid(MICROS_PER_DAY);
+        if self.months != 0 {
+            let (year, month, day) = datetime::civil_from_epoch_days(days);
+            let months = year * 12 + month as i64 - 1 + self.months as i64;
+            let (year, month) = (months.div_euclid(12), months.rem_euclid(12) as u32 + 1);
+            days = datetime::epoch_days_from_civil(year, month, day.min(datetime::days_in_month(year, month)));
+        }
+        let result = (days + self.days as i64)
+            .checked_mul(MICROS_PER_DAY)?
+            .checked_add(time)?
+            .checked_add(self.micros)?;
+        if datetime::is_valid_timestamp(result) {
+            Some(result)
+        } else {
+            None
+        }
+    }
+
+    /// Adds the interval to time of day, only microseconds field is taken
+    /// into account and result wraps around midnight
+    pub fn add_to_time(&self, time: i64) -> i64 {
+        (time + self.micros % MICROS_PER_DAY).rem_euclid(MICROS_PER_DAY)
+    }
+
+    /// Drops fields that are smaller than the given one as `INTERVAL '...' DAY`
+    /// does in PostgreSQL
+    pub fn truncate(&self, field: IntervalField) -> Self {
+        match field {
+            IntervalField::Year => Self::new(self.months / 12 * 12, 0, 0),
+            IntervalField::Month => Self::new(self.months, 0, 0),
+            IntervalField::Day => Self::new(self.months, self.days, 0),
+            IntervalField::Hour => Self::new(self.months, self.days, self.micros / MICROS_PER_HOUR * MICROS_PER_HOUR),
+            IntervalField::Minute => Self::new(
+                self.months,
+                self.days,
+                self.micros / MICROS_PER_MINUTE * MICROS_PER_MINUTE,
+            ),
+            IntervalField::Second => *self,
+        }
+    }
+
+    /// Parses literal that consists of the single number of given units, e.g.
+    /// `'3'` of `INTERVAL '3' DAY`
+    pub fn parse_with_field(value: &str, field: IntervalField) -> Result<Self, ConstraintError> {
+        match value.trim().parse::<f64>() {
+            Ok(number) => {
+                let mut fields = Fields::default();
+                fields.add(number, field.unit())?;
+                fields.build()
+            }
+            Err(_) => value.parse::<Self>().map(|interval| interval.truncate(field)),
+        }
+    }
+
+    /// Value in PostgreSQL binary format: microseconds, days and months
+    pub fn to_binary(&self) -> Vec<u8> {
+        let mut bytes = self.micros.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&self.days.to_be_bytes());
+        bytes.extend_from_slice(&self.months.to_be_bytes());
+        bytes
+    }
+
+    fn span(&self) -> i128 {
+        (self.months as i128 * DAYS_PER_MONTH as i128 + self.days as i128) * MICROS_PER_DAY as i128
+            + self.micros as i128
+    }
+}
+
+/// Fields of SQL standard interval qualifiers, e.g. `DAY` in
+/// `INTERVAL '3' DAY`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IntervalField {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl IntervalField {
+    fn unit(self) -> Unit {
+        match self {
+            Self::Year => Unit::Year,
+            Self::Month => Unit::Month,
+            Self::Day => Unit::Day,
+            Self::Hour => Unit::Hour,
+            Self::Minute => Unit::Minute,
+            Self::Second => Unit::Second,
+        }
+    }
+}
+
+impl FromStr for Interval {
+    type Err = ConstraintError;
+
+    /// Accepts PostgreSQL format, e.g. `1 year 2 mons -3 days 04:05:06 ago`,
+    /// SQL standard format, e.g. `1-2 3 4:05:06`, and ISO 8601 format with
+    /// designators, e.g. `P1Y2M3DT4H5M6S`, or alternative ISO 8601 format,
+    /// e.g. `P0001-02-03T04:05:06`
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        match value.strip_prefix('P').or_else(|| value.strip_prefix('p')) {
+            Some(iso) => parse_iso_8601(iso),
+            None => parse_postgres(value),
+        }
+    }
+}
+
+// PostgreSQL output style: `1 year 2 mons -3 days +04:05:06`, zero interval
+// is `00:00:00`
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut parts = vec![];
+        let mut previous_negative = false;
+        for (value, unit) in [
+            (self.months as i64 / 12, "year"),
+            (self.months as i64 % 12, "mon"),
+            (self.days as i64, "day"),
+        ]
+        .iter()
+        {
+            if *value != 0 {
+                let sign = if previous_negative && *value > 0 { "+" } else { "" };
+                let plural = if *value != 1 { "s" } else { "" };
+                parts.push(format!("{}{} {}{}", sign, value, unit, plural));
+                previous_negative = *value < 0;
+            }
+        }
+        if parts.is_empty() || self.micros != 0 {
+            let sign = if self.micros < 0 {
+                "-"
+            } else if previous_negative {
+                "+"
+            } else {
+                ""
+            };
+            let micros = self.micros.unsigned_abs();
+            let seconds = micros / MICROS_PER_SECOND as u64;
+            let mut time = format!(
+                "{}{:02}:{:02}:{:02}",
+                sign,
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60
+            );
+            let fraction = micros % MICROS_PER_SECOND as u64;
+            if fraction != 0 {
+                time += format!(".{:06}", fraction).trim_end_matches('0');
+            }
+            parts.push(time);
+        }
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+// `1 mon` and `30 days` are equal intervals as in PostgreSQL
+impl PartialEq for Interval {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Interval {}
+
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Interval {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.span().cmp(&other.span())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Unit {
+    Microsecond,
+    Millisecond,
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+    Decade,
+    Century,
+    Millennium,
+}
+
+impl Unit {
+    fn parse(unit: &str) -> Option<Self> {
+        match unit.to_lowercase().as_str() {
+            "microsecond" | "microseconds" | "us" | "usec" | "usecs" => Some(Self::Microsecond),
+            "millisecond" | "milliseconds" | "ms" | "msec" | "msecs" => Some(Self::Millisecond),
+            "second" | "seconds" | "sec" | "secs" | "s" => Some(Self::Second),
+            "minute" | "minutes" | "min" | "mins" | "m" => Some(Self::Minute),
+            "hour" | "hours" | "hr" | "hrs" | "h" => Some(Self::Hour),
+            "day" | "days" | "d" => Some(Self::Day),
+            "week" | "weeks" | "w" => Some(Self::Week),
+            "month" | "months" | "mon" | "mons" => Some(Self::Month),
+            "year" | "years" | "yr" | "yrs" | "y" => Some(Self::Year),
+            "decade" | "decades" | "dec" | "decs" => Some(Self::Decade),
+            "century" | "centuries" | "cent" | "c" => Some(Self::Century),
+            "millennium" | "millennia" | "mil" | "mils" => Some(Self::Millennium),
+            _ => None,
+        }
+    }
+
+    fn micros(self) -> Option<i64> {
+        match self {
+            Self::Microsecond => Some(1),
+            Self::Millisecond => Some(1000),
+            Self::Second => Some(MICROS_PER_SECOND),
+            Self::Minute => Some(MICROS_PER_MINUTE),
+            Self::Hour => Some(MICROS_PER_HOUR),
+            _ => None,
+        }
+    }
+}
+
+// fields are accumulated in wider types and checked for overflow when the
+// whole literal is parsed
+#[derive(Default)]
+struct Fields {
+    months: i64,
+    days: i64,
+    micros: i64,
+}
+
+impl Fields {
+    // fraction of a unit is carried over to smaller fields as PostgreSQL does,
+    // e.g. `1.5 months` is `1 mon 15 days`
+    fn add(&mut self, value: f64, unit: Unit) -> Result<(), ConstraintError> {
+        let whole = value.trunc();
+        let fraction = value - whole;
+        match unit {
+            Unit::Year | Unit::Decade | Unit::Century | Unit::Millennium => {
+                let months_per_unit = match unit {
+                    Unit::Year => 12.0,
+                    Unit::Decade => 120.0,
+                    Unit::Century => 1200.0,
+                    _ => 12000.0,
+                };
+                self.add_months(whole * months_per_unit + (fraction * months_per_unit).round())
+            }
+            Unit::Month => {
+                self.add_months(whole)?;
+                self.add_fraction_of_days(fraction * DAYS_PER_MONTH as f64)
+            }
+            Unit::Week => {
+                self.add_days(whole * 7.0)?;
+                self.add_fraction_of_days(fraction * 7.0)
+            }
+            Unit::Day => {
+                self.add_days(whole)?;
+                self.add_micros(fraction * MICROS_PER_DAY as f64)
+            }
+            unit => {
+                let micros_per_unit = unit.micros().unwrap();
+                let whole = to_i64(whole)?
+                    .checked_mul(micros_per_unit)
+                    .ok_or(ConstraintError::DateTimeFieldOverflow)?;
+                self.micros = self
+                    .micros
+                    .checked_add(whole)
+                    .ok_or(ConstraintError::DateTimeFieldOverflow)?;
+                self.add_micros(fraction * micros_per_unit as f64)
+            }
+        }
+    }
+
+    // `[-]HH:MM[:SS[.FFFFFF]]`, number of hours is not limited
+    fn add_time(&mut self, value: &str) -> Result<(), ConstraintError> {
+        let (negative, value) = split_sign(value);
+        let parts = value.split(':').collect::<Vec<&str>>();
+        // only seconds can have fraction
+        let is_number = |(index, part): (usize, &&str)| {
+            !part.is_empty()
+                && part
+                    .bytes()
+                    .all(|byte| byte.is_ascii_digit() || (index == 2 && byte == b'.'))
+        };
+        if parts.len() > 3 || !parts.iter().enumerate().all(is_number) {
+            return Err(ConstraintError::NotADateTime);
+        }
+        let number = |part: &str| part.parse::<f64>().map_err(|_| ConstraintError::NotADateTime);
+        let hours = number(parts[0])?;
+        let minutes = number(parts[1])?;
+        let seconds = if parts.len() == 3 { number(parts[2])? } else { 0.0 };
+        if minutes > 59.0 || seconds >= 60.0 {
+            return Err(ConstraintError::DateTimeFieldOverflow);
+        }
+        let sign = if negative { -1.0 } else { 1.0 };
+        self.add(sign * hours, Unit::Hour)?;
+        self.add(sign * minutes, Unit::Minute)?;
+        self.add(sign * seconds, Unit::Second)
+    }
+
+    fn add_months(&mut self, months: f64) -> Result<(), ConstraintError> {
+        self.months = self
+            .months
+            .checked_add(to_i64(months)?)
+            .ok_or(ConstraintError::DateTimeFieldOverflow)?;
+        Ok(())
+    }
+
+    fn add_days(&mut self, days: f64) -> Result<(), ConstraintError> {
+        self.days = self
+            .days
+            .checked_add(to_i64(days)?)
+            .ok_or(ConstraintError::DateTimeFieldOverflow)?;
+        Ok(())
+    }
+
+    fn add_fraction_of_days(&mut self, days: f64) -> Result<(), ConstraintError> {
+        self.add_days(days.trunc())?;
+        self.add_micros((days - days.trunc()) * MICROS_PER_DAY as f64)
+    }
+
+    fn add_micros(&mut self, micros: f64) -> Result<(), ConstraintError> {
+        self.micros = self
+            .micros
+            .checked_add(to_i64(micros.round())?)
+            .ok_or(ConstraintError::DateTimeFieldOverflow)?;
+        Ok(())
+    }
+
+    fn negate(&mut self) {
+        self.months = -self.months;
+        self.days = -self.days;
+        self.micros = -self.micros;
+    }
+
+    fn build(self) -> Result<Interval, ConstraintError> {
+        match (i32::try_from(self.months), i32::try_from(self.days)) {
+            (Ok(months), Ok(days)) => Ok(Interval::new(months, days, self.micros)),
+            _ => Err(ConstraintError::DateTimeFieldOverflow),
+        }
+    }
+}
+
+fn parse_postgres(value: &str) -> Result<Interval, ConstraintError> {
+    let mut tokens = split_tokens(value);
+    if tokens.first() == Some(&"@") {
+        tokens.remove(0);
+    }
+    let ago = tokens
+        .last()
+        .map(|token| token.eq_ignore_ascii_case("ago"))
+        .unwrap_or(false);
+    if ago {
+        tokens.pop();
+    }
+    if tokens.is_empty() {
+        return Err(ConstraintError::NotADateTime);
+    }
+    let mut fields = Fields::default();
+    let mut index = 0;
+    while index < tokens.len() {
+        let token = tokens[index];
+        if token.contains(':') {
+            fields.add_time(token)?;
+        } else if let Some((years, months)) = split_year_month(token) {
+            fields.add(years, Unit::Year)?;
+            fields.add(months, Unit::Month)?;
+        } else {
+            let number = token.parse::<f64>().map_err(|_| ConstraintError::NotADateTime)?;
+            if !number.is_finite() {
+                return Err(ConstraintError::NotADateTime);
+            }
+            // number without units is seconds unless it is followed by time
+            // as days are in SQL standard format
+            let unit = match tokens.get(index + 1) {
+                Some(next) if Unit::parse(next).is_some() => {
+                    index += 1;
+                    Unit::parse(next).unwrap()
+                }
+                Some(next) if next.contains(':') => Unit::Day,
+                _ => Unit::Second,
+            };
+            fields.add(number, unit)?;
+        }
+        index += 1;
+    }
+    if ago {
+        fields.negate();
+    }
+    fields.build()
+}
+
+// ISO 8601 with designators `P1Y2M3W4DT5H6M7.5S` or alternative format
+// `P0001-02-03T04:05:06` without leading `P`
+fn parse_iso_8601(value: &str) -> Result<Interval, ConstraintError> {
+    let (date, time) = match value.find(['T', 't']) {
+        Some(position) => (&value[..position], Some(&value[position + 1..])),
+        None => (value, None),
+    };
+    if date.is_empty() && time.map(str::is_empty).unwrap_or(true) {
+        return Err(ConstraintError::NotADateTime);
+    }
+    let mut fields = Fields::default();
+    if date.contains(|c: char| c.is_ascii_digit()) && date.chars().all(|c| c.is_ascii_digit() || c == '-') {
+        let parts = date.split('-').collect::<Vec<&str>>();
+        if parts.len() != 3 {
+            return Err(ConstraintError::NotADateTime);
+        }
+        for (part, unit) in parts.iter().zip([Unit::Year, Unit::Month, Unit::Day].iter()) {
+            let number = part.parse::<f64>().map_err(|_| ConstraintError::NotADateTime)?;
+            fields.add(number, *unit)?;
+        }
+    } else {
+        add_designated(
+            &mut fields,
+            date,
+            &[
+                ('Y', Unit::Year),
+                ('M', Unit::Month),
+                ('W', Unit::Week),
+                ('D', Unit::Day),
+            ],
+        )?;
+    }
+    match time {
+        Some(time) if time.contains(':') => fields.add_time(time)?,
+        Some(time) => add_designated(
+            &mut fields,
+            time,
+            &[('H', Unit::Hour), ('M', Unit::Minute), ('S', Unit::Second)],
+        )?,
+        None => {}
+    }
+    fields.build()
+}
+
+fn add_designated(fields: &mut Fields, mut value: &str, designators: &[(char, Unit)]) -> Result<(), ConstraintError> {
+    while !value.is_empty() {
+        let position = value
+            .find(|c: char| c.is_ascii_alphabetic())
+            .ok_or(ConstraintError::NotADateTime)?;
+        let number = value[..position]
+            .parse::<f64>()
+            .map_err(|_| ConstraintError::NotADateTime)?;
+        let designator = value[position..].chars().next().unwrap().to_ascii_uppercase();
+        let unit = designators
+            .iter()
+            .find(|(expected, _)| *expected == designator)
+            .map(|(_, unit)| *unit)
+            .ok_or(ConstraintError::NotADateTime)?;
+        fields.add(number, unit)?;
+        value = &value[position + 1..];
+    }
+    Ok(())
+}
+
+// splits whitespace separated words and numbers that are glued to their
+// units, e.g. `1day` or `-2.5hours`
+fn split_tokens(value: &str) -> Vec<&str> {
+    let mut tokens = vec![];
+    for word in value.split_whitespace() {
+        match word.find(|c: char| c.is_ascii_alphabetic()) {
+            Some(position) if position > 0 && !word.contains(':') => {
+                tokens.push(&word[..position]);
+                tokens.push(&word[position..]);
+            }
+            _ => tokens.push(word),
+        }
+    }
+    tokens
+}
+
+// SQL standard `Y-M` years and months, sign applies to both
+fn split_year_month(token: &str) -> Option<(f64, f64)> {
+    let (negative, unsigned) = split_sign(token);
+    let (years, months) = unsigned.split_at(unsigned.find('-')?);
+    let months = &months[1..];
+    let is_number = |part: &str| !part.is_empty() && part.bytes().all(|byte| byte.is_ascii_digit());
+    if !is_number(years) || !is_number(months) {
+        return None;
+    }
+    let sign = if negative { -1.0 } else { 1.0 };
+    Some((sign * years.parse::<f64>().ok()?, sign * months.parse::<f64>().ok()?))
+}
+
+fn split_sign(value: &str) -> (bool, &str) {
+    match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    }
+}
+
+fn to_i64(value: f64) -> Result<i64, ConstraintError> {
+    if value.is_finite() && value.abs() < i64::MAX as f64 {
+        Ok(value as i64)
+    } else {
+        Err(ConstraintError::DateTimeFieldOverflow)
+    }
+}
+
+fn to_i32(value: f64) -> Option<i32> {
+    if value.is_finite() && value.abs() <= i32::MAX as f64 {
+        Some(value as i32)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(value: &str) -> Interval {
+        value.parse().unwrap()
+    }
+
+    fn fields(interval: Interval) -> (i32, i32, i64) {
+        (interval.months(), interval.days(), interval.micros())
+    }
+
+    #[rstest::rstest(
+        value,
+        expected,
+        case::units("1 year 2 months 3 days 4 hours 5 minutes 6 seconds", (14, 3, 14_706_000_000)),
+        case::abbreviations("1 yr 2 mons 3 d 4 hrs 5 min 6 sec", (14, 3, 14_706_000_000)),
+        case::time("1 day 04:05:06.5", (0, 1, 14_706_500_000)),
+        case::negative_time("-01:30", (0, 0, -5_400_000_000)),
+        case::glued_units("1day 2h", (0, 1, 7_200_000_000)),
+        case::at_and_ago("@ 1 day 2 hours ago", (0, -1, -7_200_000_000)),
+        case::weeks("2 weeks", (0, 14, 0)),
+        case::fraction_of_month("1.5 months", (1, 15, 0)),
+        case::fraction_of_day("0.5 days", (0, 0, MICROS_PER_DAY / 2)),
+        case::fraction_of_year("1.5 years", (18, 0, 0)),
+        case::bare_number("90", (0, 0, 90 * MICROS_PER_SECOND)),
+        case::sql_standard("1-2 3 4:05:06", (14, 3, 14_706_000_000)),
+        case::iso_8601("P1Y2M3DT4H5M6S", (14, 3, 14_706_000_000)),
+        case::iso_8601_weeks("P2W", (0, 14, 0)),
+        case::iso_8601_time_only("PT0.5S", (0, 0, 500_000)),
+        case::iso_8601_negative("P-1D", (0, -1, 0)),
+        case::iso_8601_alternative("P0001-02-03T04:05:06", (14, 3, 14_706_000_000))
+    )]
+    fn parse(value: &str, expected: (i32, i32, i64)) {
+        assert_eq!(value.parse::<Interval>().map(fields), Ok(expected));
+    }
+
+    #[rstest::rstest(
+        value,
+        error,
+        case::empty("", ConstraintError::NotADateTime),
+        case::unknown_unit("1 fortnight", ConstraintError::NotADateTime),
+        case::garbage("soon", ConstraintError::NotADateTime),
+        case::empty_iso("P", ConstraintError::NotADateTime),
+        case::unknown_designator("P1X", ConstraintError::NotADateTime),
+        case::minutes("00:60", ConstraintError::DateTimeFieldOverflow),
+        case::too_many_days("3000000000 days", ConstraintError::DateTimeFieldOverflow)
+    )]
+    fn invalid(value: &str, error: ConstraintError) {
+        assert_eq!(value.parse::<Interval>().map(fields), Err(error));
+    }
+
+    #[rstest::rstest(
+        value,
+        expected,
+        case::zero("0 days", "00:00:00"),
+        case::all_fields("1 year 2 mons 3 days 04:05:06", "1 year 2 mons 3 days 04:05:06"),
+        case::singular("1 mon 1 day", "1 mon 1 day"),
+        case::negative_days("-1 days", "-1 days"),
+        case::mixed_signs("-1 days 1 hour", "-1 days +01:00:00"),
+        case::negative_time("1 day -1 hour", "1 day -01:00:00"),
+        case::fraction("1.5 seconds", "00:00:01.5"),
+        case::many_hours("36 hours", "36:00:00"),
+        case::negative_years("-13 months", "-1 years -1 mons")
+    )]
+    fn display(value: &str, expected: &str) {
+        assert_eq!(interval(value).to_string(), expected);
+        assert_eq!(fields(interval(expected)), fields(interval(value)));
+    }
+
+    #[test]
+    fn month_is_thirty_days_in_comparison() {
+        assert_eq!(interval("1 mon"), interval("30 days"));
+        assert!(interval("1 day") > interval("23 hours"));
+    }
+
+    #[test]
+    fn multiplication_carries_fractions() {
+        assert_eq!(interval("1 mon").mul(1.5).map(fields), Some((1, 15, 0)));
+        assert_eq!(
+            interval("1 day").div(4.0).map(fields),
+            Some((0, 0, 6 * MICROS_PER_HOUR))
+        );
+    }
+
+    #[test]
+    fn qualified_literals() {
+        assert_eq!(
+            Interval::parse_with_field("3", IntervalField::Day).map(fields),
+            Ok((0, 3, 0))
+        );
+        assert_eq!(
+            Interval::parse_with_field("1 day 2 hours 3 minutes", IntervalField::Hour).map(fields),
+            Ok((0, 1, 2 * MICROS_PER_HOUR))
+        );
+    }
+
+    #[test]
+    fn add_to_timestamp() {
+        let (january_31, _) = datetime::parse_timestamp("2001-01-31 10:00").unwrap();
+        let (february_28, _) = datetime::parse_timestamp("2001-02-28 10:00").unwrap();
+        let (march_1, _) = datetime::parse_timestamp("2001-03-01 10:00").unwrap();
+        assert_eq!(interval("1 mon").add_to_timestamp(january_31), Some(february_28));
+        assert_eq!(interval("1 mon 1 day").add_to_timestamp(january_31), Some(march_1));
+        assert_eq!(
+            interval("1 day").add_to_timestamp(datetime::TIMESTAMP_INFINITY),
+            Some(datetime::TIMESTAMP_INFINITY)
+        );
+        assert_eq!(interval("300000 years").add_to_timestamp(0), None);
+    }
+
+    #[test]
+    fn difference_of_timestamps() {
+        let (later, _) = datetime::parse_timestamp("2001-01-02 12:00").unwrap();
+        let (earlier, _) = datetime::parse_timestamp("2001-01-01 10:00").unwrap();
+        assert_eq!(
+            Interval::between_timestamps(later, earlier).map(fields),
+            Some((0, 1, 2 * MICROS_PER_HOUR))
+        );
+        assert_eq!(
+            Interval::between_timestamps(datetime::TIMESTAMP_INFINITY, earlier),
+            None
+        );
+    }
+
+    #[test]
+    fn binary_format() {
+        assert_eq!(
+            Interval::new(1, 2, 3).to_binary(),
+            vec![0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 2, 0, 0, 0, 1]
+        );
+    }
+}
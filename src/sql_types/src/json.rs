@@ -0,0 +1,532 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{ConstraintError, Numeric};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+    iter::Peekable,
+    str::{Chars, FromStr},
+};
+
+/// Parsed JSON document as `JSONB` keeps it: keys of an object are unique
+/// and sorted by length and then by bytes, numbers are exact decimals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(Numeric),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Object where the last value of a duplicated key wins
+    pub fn object(pairs: Vec<(String, Json)>) -> Self {
+        let mut object: Vec<(String, Json)> = Vec::with_capacity(pairs.len());
+        for (key, value) in pairs {
+            match object.iter_mut().find(|(existing, _value)| *existing == key) {
+                Some(pair) => pair.1 = value,
+                None => object.push((key, value)),
+            }
+        }
+        object.sort_by(|(left, _), (right, _)| compare_keys(left, right));
+        Self::Object(object)
+    }
+
+    /// Value of the key if it is an object, `->` with text operand
+    pub fn field(&self, key: &str) -> Option<&Json> {
+        match self {
+            Self::Object(pairs) => pairs
+                .iter()
+                .find(|(existing, _value)| existing == key)
+                .map(|(_key, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Element of the array, negative index counts from the end, `->` with
+    /// integer operand
+    pub fn element(&self, index: i64) -> Option<&Json> {
+        match self {
+            Self::Array(elements) => {
+                let index = if index < 0 {
+                    elements.len() as i64 + index
+                } else {
+                    index
+                };
+                if index < 0 {
+                    None
+                } else {
+                    elements.get(index as usize)
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Follows keys of objects and indexes of arrays, `#>` operator
+    pub fn path(&self, path: &[String]) -> Option<&Json> {
+        path.iter().try_fold(self, |value, step| match value {
+            Self::Object(_) => value.field(step),
+            Self::Array(_) => step.trim().parse().ok().and_then(|index| value.element(index)),
+            _ => None,
+        })
+    }
+
+    /// `@>` operator, whether all keys and values of `other` are present in
+    /// this document at the same nesting level
+    pub fn contains(&self, other: &Json) -> bool {
+        match (self, other) {
+            (Self::Object(_), Self::Object(pairs)) => pairs.iter().all(|(key, expected)| match self.field(key) {
+                Some(actual) if actual.is_scalar() && expected.is_scalar() => actual == expected,
+                Some(actual) if actual.same_container(expected) => actual.contains(expected),
+                _ => false,
+            }),
+            (Self::Array(elements), Self::Array(expected)) => expected.iter().all(|expected| {
+                elements.iter().any(|actual| {
+                    if expected.is_scalar() {
+                        actual == expected
+                    } else {
+                        actual.same_container(expected) && actual.contains(expected)
+                    }
+                })
+            }),
+            // array at top level contains its primitive elements
+            (Self::Array(elements), expected) if expected.is_scalar() => {
+                elements.iter().any(|actual| actual == expected)
+            }
+            (actual, expected) if actual.is_scalar() && expected.is_scalar() => actual == expected,
+            _ => false,
+        }
+    }
+
+    /// `?` operator, whether the string is a key of the object or an
+    /// element of the array
+    pub fn exists(&self, key: &str) -> bool {
+        match self {
+            Self::Object(_) => self.field(key).is_some(),
+            Self::Array(elements) => elements
+                .iter()
+                .any(|element| matches!(element, Self::String(value) if value == key)),
+            Self::String(value) => value == key,
+            _ => false,
+        }
+    }
+
+    /// Value as `->>` operator returns it: strings are unquoted and JSON
+    /// `null` is SQL `NULL`
+    pub fn to_text(&self) -> Option<String> {
+        match self {
+            Self::Null => None,
+            Self::String(value) => Some(value.clone()),
+            value => Some(value.to_string()),
+        }
+    }
+
+    fn is_scalar(&self) -> bool {
+        !matches!(self, Self::Array(_) | Self::Object(_))
+    }
+
+    fn same_container(&self, other: &Json) -> bool {
+        matches!(
+            (self, other),
+            (Self::Array(_), Self::Array(_)) | (Self::Object(_), Self::Object(_))
+        )
+    }
+
+    // sort order of types in PostgreSQL jsonb btree index
+    fn rank(&self) -> u8 {
+        match self {
+            Self::Null => 0,
+            Self::String(_) => 1,
+            Self::Number(_) => 2,
+            Self::Bool(_) => 3,
+            Self::Array(_) => 4,
+            Self::Object(_) => 5,
+        }
+    }
+}
+
+fn compare_keys(left: &str, right: &str) -> Ordering {
+    left.len().cmp(&right.len()).then_with(|| left.cmp(right))
+}
+
+impl PartialEq for Json {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Json {}
+
+impl PartialOrd for Json {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// containers with more items are greater, then items are compared in the
+// order they are stored
+impl Ord for Json {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Null, Self::Null) => Ordering::Equal,
+            (Self::Bool(left), Self::Bool(right)) => left.cmp(right),
+            (Self::Number(left), Self::Number(right)) => left.cmp(right),
+            (Self::String(left), Self::String(right)) => left.cmp(right),
+            (Self::Array(left), Self::Array(right)) => left.len().cmp(&right.len()).then_with(|| left.cmp(right)),
+            (Self::Object(left), Self::Object(right)) => left.len().cmp(&right.len()).then_with(|| {
+                left.iter()
+                    .zip(right.iter())
+                    .map(|((left_key, left), (right_key, right))| {
+                        compare_keys(left_key, right_key).then_with(|| left.cmp(right))
+                    })
+                    .find(|ordering| *ordering != Ordering::Equal)
+                    .unwrap_or(Ordering::Equal)
+            }),
+            (left, right) => left.rank().cmp(&right.rank()),
+        }
+    }
+}
+
+impl FromStr for Json {
+    type Err = ConstraintError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut chars = value.chars().peekable();
+        let json = parse_value(&mut chars)?;
+        skip_whitespace(&mut chars);
+        match chars.next() {
+            None => Ok(json),
+            Some(_) => Err(ConstraintError::NotAJson),
+        }
+    }
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while let Some(' ') | Some('\t') | Some('\n') | Some('\r') = chars.peek() {
+        chars.next();
+    }
+}
+
+fn parse_value(chars: &mut Peekable<Chars>) -> Result<Json, ConstraintError> {
+    skip_whitespace(chars);
+    match chars.peek() {
+        Some('{') => {
+            chars.next();
+            let mut pairs = vec![];
+            skip_whitespace(chars);
+            if chars.peek() == Some(&'}') {
+                chars.next();
+                return Ok(Json::Object(pairs));
+            }
+            loop {
+                skip_whitespace(chars);
+                if chars.next() != Some('"') {
+                    return Err(ConstraintError::NotAJson);
+                }
+                let key = parse_string(chars)?;
+                skip_whitespace(chars);
+                if chars.next() != Some(':') {
+                    return Err(ConstraintError::NotAJson);
+                }
+                pairs.push((key, parse_value(chars)?));
+                skip_whitespace(chars);
+                match chars.next() {
+                    Some(',') => continue,
+                    Some('}') => return Ok(Json::object(pairs)),
+                    _ => return Err(ConstraintError::NotAJson),
+                }
+            }
+        }
+        Some('[') => {
+            chars.next();
+            let mut elements = vec![];
+            skip_whitespace(chars);
+            if chars.peek() == Some(&']') {
+                chars.next();
+                return Ok(Json::Array(elements));
+            }
+            loop {
+                elements.push(parse_value(chars)?);
+                skip_whitespace(chars);
+                match chars.next() {
+                    Some(',') => continue,
+                    Some(']') => return Ok(Json::Array(elements)),
+                    _ => return Err(ConstraintError::NotAJson),
+                }
+            }
+        }
+        Some('"') => {
+            chars.next();
+            parse_string(chars).map(Json::String)
+        }
+        Some('-') | Some('0'..='9') => parse_number(chars),
+        Some(_) => {
+            let mut word = String::new();
+            while let Some(c) = chars.peek().filter(|c| c.is_ascii_alphabetic()) {
+                word.push(*c);
+                chars.next();
+            }
+            match word.as_str() {
+                "null" => Ok(Json::Null),
+                "true" => Ok(Json::Bool(true)),
+                "false" => Ok(Json::Bool(false)),
+                _ => Err(ConstraintError::NotAJson),
+            }
+        }
+        None => Err(ConstraintError::NotAJson),
+    }
+}
+
+// -?(0|[1-9][0-9]*)(.[0-9]+)?([eE][+-]?[0-9]+)?
+fn parse_number(chars: &mut Peekable<Chars>) -> Result<Json, ConstraintError> {
+    let mut number = String::new();
+    let take_digits = |chars: &mut Peekable<Chars>, number: &mut String| {
+        let start = number.len();
+        while let Some(digit) = chars.peek().filter(|c| c.is_ascii_digit()) {
+            number.push(*digit);
+            chars.next();
+        }
+        number.len() - start
+    };
+    if chars.peek() == Some(&'-') {
+        number.push('-');
+        chars.next();
+    }
+    let integer_start = number.len();
+    let integer_digits = take_digits(chars, &mut number);
+    if integer_digits == 0 || (integer_digits > 1 && number[integer_start..].starts_with('0')) {
+        return Err(ConstraintError::NotAJson);
+    }
+    if chars.peek() == Some(&'.') {
+        number.push('.');
+        chars.next();
+        if take_digits(chars, &mut number) == 0 {
+            return Err(ConstraintError::NotAJson);
+        }
+    }
+    if let Some(e) = chars.peek().filter(|c| **c == 'e' || **c == 'E') {
+        number.push(*e);
+        chars.next();
+        if let Some(sign) = chars.peek().filter(|c| **c == '+' || **c == '-') {
+            number.push(*sign);
+            chars.next();
+        }
+        if take_digits(chars, &mut number) == 0 {
+            return Err(ConstraintError::NotAJson);
+        }
+    }
+    number.parse().map(Json::Number).map_err(|_| ConstraintError::NotAJson)
+}
+
+// opening quote is already consumed
+fn parse_string(chars: &mut Peekable<Chars>) -> Result<String, ConstraintError> {
+    let mut value = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(value),
+            Some('\\') => match chars.next() {
+                Some('"') => value.push('"'),
+                Some('\\') => value.push('\\'),
+                Some('/') => value.push('/'),
+                Some('b') => value.push('\u{8}'),
+                Some('f') => value.push('\u{c}'),
+                Some('n') => value.push('\n'),
+                Some('r') => value.push('\r'),
+                Some('t') => value.push('\t'),
+                Some('u') => {
+                    let code = parse_code_unit(chars)?;
+                    let code = if (0xd800..0xdc00).contains(&code) {
+                        if chars.next() != Some('\\') || chars.next() != Some('u') {
+                            return Err(ConstraintError::NotAJson);
+                        }
+                        let low = parse_code_unit(chars)?;
+                        if !(0xdc00..0xe000).contains(&low) {
+                            return Err(ConstraintError::NotAJson);
+                        }
+                        0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00)
+                    } else {
+                        code
+                    };
+                    // PostgreSQL text can't contain zero byte
+                    match std::char::from_u32(code).filter(|c| *c != '\0') {
+                        Some(c) => value.push(c),
+                        None => return Err(ConstraintError::NotAJson),
+                    }
+                }
+                _ => return Err(ConstraintError::NotAJson),
+            },
+            Some(c) if c < ' ' => return Err(ConstraintError::NotAJson),
+            Some(c) => value.push(c),
+            None => return Err(ConstraintError::NotAJson),
+        }
+    }
+}
+
+fn parse_code_unit(chars: &mut Peekable<Chars>) -> Result<u32, ConstraintError> {
+    let mut code = 0;
+    for _ in 0..4 {
+        match chars.next().and_then(|c| c.to_digit(16)) {
+            Some(digit) => code = code * 16 + digit,
+            None => return Err(ConstraintError::NotAJson),
+        }
+    }
+    Ok(code)
+}
+
+fn write_string(f: &mut Formatter<'_>, value: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\u{8}' => write!(f, "\\b")?,
+            '\u{c}' => write!(f, "\\f")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if c < ' ' => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+/// Text as PostgreSQL prints `JSONB`, e.g. `{"a": 1, "b": [true, null]}`
+impl Display for Json {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => write!(f, "null"),
+            Self::Bool(value) => write!(f, "{}", value),
+            Self::Number(value) => write!(f, "{}", value),
+            Self::String(value) => write_string(f, value),
+            Self::Array(elements) => {
+                write!(f, "[")?;
+                for (index, element) in elements.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                write!(f, "]")
+            }
+            Self::Object(pairs) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in pairs.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ": {}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn json(value: &str) -> Json {
+        value.parse().expect("valid json")
+    }
+
+    #[rstest::rstest(
+        value,
+        expected,
+        case::scalar(" 1.50 ", "1.50"),
+        case::exponent("1e3", "1000"),
+        case::escapes(r#""a\"\\\/\né😀""#, r#""a\"\\/\né😀""#),
+        case::array("[1,true,null,\"x\"]", r#"[1, true, null, "x"]"#),
+        case::keys_sorted_by_length(r#"{"bb":1,"a":2,"c":3}"#, r#"{"a": 2, "c": 3, "bb": 1}"#),
+        case::last_duplicate_wins(r#"{"a":1,"a":2}"#, r#"{"a": 2}"#),
+        case::nested(r#" { "a" : [ {} , [ ] ] } "#, r#"{"a": [{}, []]}"#)
+    )]
+    fn parse_and_print(value: &str, expected: &str) {
+        assert_eq!(json(value).to_string(), expected);
+    }
+
+    #[rstest::rstest(
+        value,
+        case::empty(""),
+        case::trailing_comma("[1,]"),
+        case::leading_zero("01"),
+        case::bare_point("1."),
+        case::single_quotes("'a'"),
+        case::unquoted_key("{a:1}"),
+        case::control_character("\"a\nb\""),
+        case::lone_surrogate(r#""\ud83d""#),
+        case::zero_character(r#""\u0000""#),
+        case::trailing_garbage("{} x")
+    )]
+    fn invalid(value: &str) {
+        assert_eq!(value.parse::<Json>(), Err(ConstraintError::NotAJson));
+    }
+
+    #[test]
+    fn field_element_and_path() {
+        let document = json(r#"{"a": {"b": [10, 20, 30]}}"#);
+        assert_eq!(
+            document.field("a").and_then(|a| a.field("b")),
+            Some(&json("[10, 20, 30]"))
+        );
+        assert_eq!(document.field("b"), None);
+        assert_eq!(json("[1, 2, 3]").element(-1), Some(&json("3")));
+        assert_eq!(json("[1, 2, 3]").element(3), None);
+        assert_eq!(
+            document.path(&["a".to_owned(), "b".to_owned(), "1".to_owned()]),
+            Some(&json("20"))
+        );
+        assert_eq!(document.path(&["a".to_owned(), "x".to_owned()]), None);
+    }
+
+    #[rstest::rstest(
+        document,
+        other,
+        expected,
+        case::same_scalar("1", "1.0", true),
+        case::subset_of_object(r#"{"a": 1, "b": {"c": 2, "d": 3}}"#, r#"{"b": {"d": 3}}"#, true),
+        case::different_value(r#"{"a": 1}"#, r#"{"a": 2}"#, false),
+        case::subset_of_array("[1, [2, 3], 4]", "[[3], 1]", true),
+        case::array_contains_scalar(r#"["foo", "bar"]"#, r#""foo""#, true),
+        case::nested_array_does_not_contain_scalar(r#"{"a": [1, 2]}"#, r#"{"a": 1}"#, false),
+        case::object_is_not_array(r#"{"a": 1}"#, r#"[{"a": 1}]"#, false)
+    )]
+    fn containment(document: &str, other: &str, expected: bool) {
+        assert_eq!(json(document).contains(&json(other)), expected);
+    }
+
+    #[test]
+    fn key_exists() {
+        assert!(json(r#"{"a": 1}"#).exists("a"));
+        assert!(json(r#"["a", "b"]"#).exists("b"));
+        assert!(!json(r#"[{"a": 1}]"#).exists("a"));
+    }
+
+    #[test]
+    fn ordering_of_types() {
+        assert!(json("null") < json(r#""a""#));
+        assert!(json(r#""a""#) < json("1"));
+        assert!(json("1") < json("true"));
+        assert!(json("true") < json("[]"));
+        assert!(json("[1, 2]") < json("{}"));
+        assert!(json("[3]") < json("[1, 2]"));
+    }
+}
@@ -14,14 +14,41 @@
 
 use protocol::sql_types::PostgreSqlType;
 use serde::{Deserialize, Serialize};
-use std::convert::TryInto;
-
-#[derive(PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+use std::fmt::{self, Display, Formatter};
+
+pub use bytea::ByteaOutput;
+pub use cast::{cast, CastContext, CastError};
+pub use datetime::{date_to_timestamp, TimeZone};
+pub use datum::{Datum, TextFormat};
+pub use interval::{Interval, IntervalField};
+pub use json::Json;
+pub use numeric::Numeric;
+pub use uuid::Uuid;
+
+mod array;
+mod binary;
+mod bytea;
+mod cast;
+mod datetime;
+mod datum;
+mod interval;
+mod json;
+mod numeric;
+mod uuid;
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
 pub enum SqlType {
     Bool,
     Char(u64),
-    VarChar(u64),
-    Decimal,
+    // any length if not specified
+    VarChar(Option<u64>),
+    Text,
+    Bytea,
+    Uuid,
+    Json,
+    Jsonb,
+    // precision and scale, any number of digits if not specified
+    Decimal(Option<(u64, u64)>),
     SmallInt,
     Integer,
     BigInt,
@@ -33,37 +60,60 @@ pub enum SqlType {
     TimestampWithTimeZone,
     Date,
     Interval,
+    // one dimensional array of elements of the type
+    Array(Box<SqlType>),
+    // user defined type with labels in declaration order
+    Enum {
+        name: String,
+        oid: u32,
+        labels: Vec<String>,
+    },
 }
 
 impl SqlType {
     pub fn constraint(&self) -> Box<dyn Constraint> {
         match *self {
+            Self::Bool => Box::new(BoolSqlTypeConstraint),
             Self::Char(length) => Box::new(CharSqlTypeConstraint { length }),
             Self::VarChar(length) => Box::new(VarCharSqlTypeConstraint { length }),
+            Self::Text => Box::new(TextSqlTypeConstraint),
+            Self::Bytea => Box::new(ByteaSqlTypeConstraint),
+            Self::Uuid => Box::new(UuidSqlTypeConstraint),
+            Self::Json => Box::new(JsonSqlTypeConstraint),
+            Self::Jsonb => Box::new(JsonbSqlTypeConstraint),
             Self::SmallInt => Box::new(SmallIntTypeConstraint),
             Self::Integer => Box::new(IntegerSqlTypeConstraint),
             Self::BigInt => Box::new(BigIntTypeConstraint),
-            sql_type => unimplemented!("Type constraint for {:?} is not currently implemented", sql_type),
-        }
-    }
-
-    pub fn serializer(&self) -> Box<dyn Serializer> {
-        match *self {
-            Self::Char(_length) => Box::new(CharSqlTypeSerializer),
-            Self::VarChar(_length) => Box::new(VarCharSqlTypeSerializer),
-            Self::SmallInt => Box::new(SmallIntTypeSerializer),
-            Self::Integer => Box::new(IntegerSqlTypeSerializer),
-            Self::BigInt => Box::new(BigIntTypeSerializer),
-            sql_type => unimplemented!("Type Serializer for {:?} is not currently implemented", sql_type),
+            Self::Real => Box::new(RealSqlTypeConstraint),
+            Self::DoublePrecision => Box::new(DoublePrecisionSqlTypeConstraint),
+            Self::Decimal(precision_and_scale) => Box::new(DecimalSqlTypeConstraint { precision_and_scale }),
+            Self::Date => Box::new(DateSqlTypeConstraint),
+            Self::Time => Box::new(TimeSqlTypeConstraint),
+            Self::TimeWithTimeZone => Box::new(TimeWithTimeZoneSqlTypeConstraint),
+            Self::Timestamp => Box::new(TimestampSqlTypeConstraint),
+            Self::TimestampWithTimeZone => Box::new(TimestampWithTimeZoneSqlTypeConstraint),
+            Self::Interval => Box::new(IntervalSqlTypeConstraint),
+            Self::Array(ref element) => Box::new(ArraySqlTypeConstraint {
+                element: element.constraint(),
+            }),
+            Self::Enum { oid, ref labels, .. } => Box::new(EnumSqlTypeConstraint {
+                oid,
+                labels: labels.clone(),
+            }),
         }
     }
 
     pub fn to_pg_types(&self) -> PostgreSqlType {
         match *self {
             Self::Bool => PostgreSqlType::Bool,
-            Self::Char(_) => PostgreSqlType::Char,
-            Self::VarChar(_) => PostgreSqlType::VarChar,
-            Self::Decimal => PostgreSqlType::Decimal,
+            Self::Char(length) => PostgreSqlType::Char(length),
+            Self::VarChar(length) => PostgreSqlType::VarChar(length),
+            Self::Text => PostgreSqlType::Text,
+            Self::Bytea => PostgreSqlType::Bytea,
+            Self::Uuid => PostgreSqlType::Uuid,
+            Self::Json => PostgreSqlType::Json,
+            Self::Jsonb => PostgreSqlType::Jsonb,
+            Self::Decimal(_) => PostgreSqlType::Decimal,
             Self::SmallInt => PostgreSqlType::SmallInt,
             Self::Integer => PostgreSqlType::Integer,
             Self::BigInt => PostgreSqlType::BigInt,
@@ -75,108 +125,338 @@ impl SqlType {
             Self::TimestampWithTimeZone => PostgreSqlType::TimestampWithTimeZone,
             Self::Date => PostgreSqlType::Date,
             Self::Interval => PostgreSqlType::Interval,
+            Self::Array(ref element) => PostgreSqlType::Array(Box::new(element.to_pg_types())),
+            Self::Enum { oid, .. } => PostgreSqlType::Enum(oid),
+        }
+    }
+
+    /// Built-in type with PostgreSQL type OID, `None` if the OID is unknown
+    /// or it is OID of user defined type
+    pub fn from_pg_oid(oid: i32) -> Option<SqlType> {
+        let sql_type = match oid {
+            16 => Self::Bool,
+            17 => Self::Bytea,
+            20 => Self::BigInt,
+            21 => Self::SmallInt,
+            23 => Self::Integer,
+            25 => Self::Text,
+            114 => Self::Json,
+            700 => Self::Real,
+            701 => Self::DoublePrecision,
+            // bpchar without length does not limit its values
+            1042 | 1043 => Self::VarChar(None),
+            1082 => Self::Date,
+            1083 => Self::Time,
+            1114 => Self::Timestamp,
+            1184 => Self::TimestampWithTimeZone,
+            1186 => Self::Interval,
+            1266 => Self::TimeWithTimeZone,
+            1700 => Self::Decimal(None),
+            2950 => Self::Uuid,
+            3802 => Self::Jsonb,
+            1000 => Self::Array(Box::new(Self::Bool)),
+            1001 => Self::Array(Box::new(Self::Bytea)),
+            1005 => Self::Array(Box::new(Self::SmallInt)),
+            1007 => Self::Array(Box::new(Self::Integer)),
+            1009 => Self::Array(Box::new(Self::Text)),
+            1014 | 1015 => Self::Array(Box::new(Self::VarChar(None))),
+            1016 => Self::Array(Box::new(Self::BigInt)),
+            1021 => Self::Array(Box::new(Self::Real)),
+            1022 => Self::Array(Box::new(Self::DoublePrecision)),
+            1115 => Self::Array(Box::new(Self::Timestamp)),
+            1182 => Self::Array(Box::new(Self::Date)),
+            1183 => Self::Array(Box::new(Self::Time)),
+            1185 => Self::Array(Box::new(Self::TimestampWithTimeZone)),
+            1187 => Self::Array(Box::new(Self::Interval)),
+            1231 => Self::Array(Box::new(Self::Decimal(None))),
+            1270 => Self::Array(Box::new(Self::TimeWithTimeZone)),
+            2951 => Self::Array(Box::new(Self::Uuid)),
+            199 => Self::Array(Box::new(Self::Json)),
+            3807 => Self::Array(Box::new(Self::Jsonb)),
+            _ => return None,
+        };
+        Some(sql_type)
+    }
+}
+
+// names of types as PostgreSQL shows them in error messages
+impl Display for SqlType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool => write!(f, "boolean"),
+            Self::Char(length) => write!(f, "character({})", length),
+            Self::VarChar(Some(length)) => write!(f, "character varying({})", length),
+            Self::VarChar(None) => write!(f, "character varying"),
+            Self::Text => write!(f, "text"),
+            Self::Bytea => write!(f, "bytea"),
+            Self::Uuid => write!(f, "uuid"),
+            Self::Json => write!(f, "json"),
+            Self::Jsonb => write!(f, "jsonb"),
+            Self::Decimal(Some((precision, scale))) => write!(f, "numeric({},{})", precision, scale),
+            Self::Decimal(None) => write!(f, "numeric"),
+            Self::SmallInt => write!(f, "smallint"),
+            Self::Integer => write!(f, "integer"),
+            Self::BigInt => write!(f, "bigint"),
+            Self::Real => write!(f, "real"),
+            Self::DoublePrecision => write!(f, "double precision"),
+            Self::Time => write!(f, "time without time zone"),
+            Self::TimeWithTimeZone => write!(f, "time with time zone"),
+            Self::Timestamp => write!(f, "timestamp without time zone"),
+            Self::TimestampWithTimeZone => write!(f, "timestamp with time zone"),
+            Self::Date => write!(f, "date"),
+            Self::Interval => write!(f, "interval"),
+            Self::Array(element) => write!(f, "{}[]", element),
+            Self::Enum { name, .. } => write!(f, "{}", name),
         }
     }
 }
 
 pub trait Constraint {
-    fn validate(&self, in_value: &str) -> Result<(), ConstraintError>;
+    /// Checks that literal is a valid value of the type and converts it into
+    /// typed value
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError>;
 }
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ConstraintError {
     OutOfRange,
     NotAnInt,
+    NotABool,
+    NotAFloat,
+    NotANumeric,
+    NotADateTime,
+    NotABytea,
+    NotAUuid,
+    NotAJson,
+    NotAnArray,
+    NotAnEnumLabel,
+    DateTimeFieldOverflow,
     ValueTooLong,
+    InvalidBinaryFormat,
 }
 
-pub trait Serializer {
-    fn ser(&self, in_value: &str) -> Vec<u8>;
-
-    fn des(&self, out_value: &[u8]) -> String;
+struct BoolSqlTypeConstraint;
+
+impl Constraint for BoolSqlTypeConstraint {
+    // the same literals as PostgreSQL accepts: any unique prefix of `true`,
+    // `false`, `yes` or `no`, `on`, `off`, `1` and `0` regardless of case
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError> {
+        let value = in_value.trim().to_lowercase();
+        let matches = |literal: &str, min_len: usize| value.len() >= min_len && literal.starts_with(value.as_str());
+        if matches("true", 1) || matches("yes", 1) || matches("on", 2) || value == "1" {
+            Ok(Datum::Bool(true))
+        } else if matches("false", 1) || matches("no", 1) || matches("off", 2) || value == "0" {
+            Ok(Datum::Bool(false))
+        } else {
+            Err(ConstraintError::NotABool)
+        }
+    }
 }
 
 struct SmallIntTypeConstraint;
 
 impl Constraint for SmallIntTypeConstraint {
-    fn validate(&self, in_value: &str) -> Result<(), ConstraintError> {
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError> {
         match lexical::parse::<i16, _>(in_value) {
-            Ok(_) => Ok(()),
+            Ok(value) => Ok(Datum::Int16(value)),
             Err(e) if e.code == lexical::ErrorCode::InvalidDigit => Err(ConstraintError::NotAnInt),
             Err(_) => Err(ConstraintError::OutOfRange),
         }
     }
 }
 
-struct SmallIntTypeSerializer;
+struct IntegerSqlTypeConstraint;
 
-impl Serializer for SmallIntTypeSerializer {
-    #[allow(clippy::match_wild_err_arm)]
-    fn ser(&self, in_value: &str) -> Vec<u8> {
-        match lexical::parse::<i16, _>(in_value) {
-            Ok(parsed) => parsed.to_be_bytes().to_vec(),
-            Err(_) => unimplemented!(),
+impl Constraint for IntegerSqlTypeConstraint {
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError> {
+        match lexical::parse::<i32, _>(in_value) {
+            Ok(value) => Ok(Datum::Int32(value)),
+            Err(e) if e.code == lexical::ErrorCode::InvalidDigit => Err(ConstraintError::NotAnInt),
+            Err(_) => Err(ConstraintError::OutOfRange),
         }
     }
-
-    fn des(&self, out_value: &[u8]) -> String {
-        i16::from_be_bytes(out_value[0..2].try_into().unwrap()).to_string()
-    }
 }
 
-struct IntegerSqlTypeConstraint;
+struct BigIntTypeConstraint;
 
-impl Constraint for IntegerSqlTypeConstraint {
-    fn validate(&self, in_value: &str) -> Result<(), ConstraintError> {
-        match lexical::parse::<i32, _>(in_value) {
-            Ok(_) => Ok(()),
+impl Constraint for BigIntTypeConstraint {
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError> {
+        match lexical::parse::<i64, _>(in_value) {
+            Ok(value) => Ok(Datum::Int64(value)),
             Err(e) if e.code == lexical::ErrorCode::InvalidDigit => Err(ConstraintError::NotAnInt),
             Err(_) => Err(ConstraintError::OutOfRange),
         }
     }
 }
 
-struct IntegerSqlTypeSerializer;
+struct RealSqlTypeConstraint;
 
-impl Serializer for IntegerSqlTypeSerializer {
-    #[allow(clippy::match_wild_err_arm)]
-    fn ser(&self, in_value: &str) -> Vec<u8> {
-        match lexical::parse::<i32, _>(in_value) {
-            Ok(parsed) => parsed.to_be_bytes().to_vec(),
-            Err(_) => unimplemented!(),
+impl Constraint for RealSqlTypeConstraint {
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError> {
+        let value = parse_float(in_value)?;
+        if value.is_finite() && value.abs() > f32::MAX as f64 {
+            Err(ConstraintError::OutOfRange)
+        } else {
+            Ok(Datum::Float32(value as f32))
         }
     }
+}
 
-    fn des(&self, out_value: &[u8]) -> String {
-        i32::from_be_bytes(out_value[0..4].try_into().unwrap()).to_string()
+struct DoublePrecisionSqlTypeConstraint;
+
+impl Constraint for DoublePrecisionSqlTypeConstraint {
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError> {
+        parse_float(in_value).map(Datum::Float64)
     }
 }
 
-struct BigIntTypeConstraint;
+// accepts `NaN`, `Infinity` and `inf` spellings as PostgreSQL does, but values
+// that are too big to be represented are errors rather than infinities
+fn parse_float(in_value: &str) -> Result<f64, ConstraintError> {
+    let value = in_value.trim();
+    match value.to_lowercase().as_str() {
+        "nan" => return Ok(f64::NAN),
+        "infinity" | "+infinity" | "inf" | "+inf" => return Ok(f64::INFINITY),
+        "-infinity" | "-inf" => return Ok(f64::NEG_INFINITY),
+        _ => {}
+    }
+    match value.parse::<f64>() {
+        Ok(parsed) if parsed.is_infinite() => Err(ConstraintError::OutOfRange),
+        Ok(parsed) if parsed.is_nan() => Err(ConstraintError::NotAFloat),
+        Ok(parsed) => Ok(parsed),
+        Err(_) => Err(ConstraintError::NotAFloat),
+    }
+}
 
-impl Constraint for BigIntTypeConstraint {
-    fn validate(&self, in_value: &str) -> Result<(), ConstraintError> {
-        match lexical::parse::<i64, _>(in_value) {
-            Ok(_) => Ok(()),
-            Err(e) if e.code == lexical::ErrorCode::InvalidDigit => Err(ConstraintError::NotAnInt),
-            Err(_) => Err(ConstraintError::OutOfRange),
+struct DecimalSqlTypeConstraint {
+    precision_and_scale: Option<(u64, u64)>,
+}
+
+impl Constraint for DecimalSqlTypeConstraint {
+    // value is rounded to the scale of the column and then has to fit into its
+    // precision
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError> {
+        let value = in_value.parse::<Numeric>().map_err(|_| ConstraintError::NotANumeric)?;
+        match self.precision_and_scale {
+            None => Ok(Datum::Numeric(value)),
+            Some((precision, scale)) if value.fits(precision, scale) => Ok(Datum::Numeric(value.round(scale as u32))),
+            Some(_) => Err(ConstraintError::OutOfRange),
         }
     }
 }
 
-struct BigIntTypeSerializer;
+struct DateSqlTypeConstraint;
 
-impl Serializer for BigIntTypeSerializer {
-    #[allow(clippy::match_wild_err_arm)]
-    fn ser(&self, in_value: &str) -> Vec<u8> {
-        match lexical::parse::<i64, _>(in_value) {
-            Ok(parsed) => parsed.to_be_bytes().to_vec(),
-            Err(_) => unimplemented!(),
-        }
+impl Constraint for DateSqlTypeConstraint {
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError> {
+        datetime::parse_date(in_value).map(Datum::Date)
     }
+}
 
-    fn des(&self, out_value: &[u8]) -> String {
-        i64::from_be_bytes(out_value[0..8].try_into().unwrap()).to_string()
+struct TimeSqlTypeConstraint;
+
+impl Constraint for TimeSqlTypeConstraint {
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError> {
+        datetime::parse_time(in_value).map(Datum::Time)
+    }
+}
+
+struct TimeWithTimeZoneSqlTypeConstraint;
+
+impl Constraint for TimeWithTimeZoneSqlTypeConstraint {
+    // literals without offset are qualified with session time zone before
+    // validation, UTC is used if it was not
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError> {
+        datetime::parse_time_with_zone(in_value)
+            .map(|(time, offset)| Datum::TimeWithTimeZone(time, offset.unwrap_or_default()))
+    }
+}
+
+struct TimestampSqlTypeConstraint;
+
+impl Constraint for TimestampSqlTypeConstraint {
+    // PostgreSQL ignores offset of TIMESTAMP WITHOUT TIME ZONE literals
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError> {
+        datetime::parse_timestamp(in_value).map(|(timestamp, _offset)| Datum::Timestamp(timestamp))
+    }
+}
+
+struct TimestampWithTimeZoneSqlTypeConstraint;
+
+impl Constraint for TimestampWithTimeZoneSqlTypeConstraint {
+    // values are stored in UTC
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError> {
+        datetime::parse_timestamp(in_value).map(|(timestamp, offset)| match timestamp {
+            datetime::TIMESTAMP_INFINITY | datetime::TIMESTAMP_NEG_INFINITY => Datum::TimestampWithTimeZone(timestamp),
+            timestamp => Datum::TimestampWithTimeZone(
+                timestamp - offset.unwrap_or_default() as i64 * datetime::MICROS_PER_SECOND,
+            ),
+        })
+    }
+}
+
+struct IntervalSqlTypeConstraint;
+
+impl Constraint for IntervalSqlTypeConstraint {
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError> {
+        in_value.parse::<Interval>().map(Datum::Interval)
+    }
+}
+
+struct UuidSqlTypeConstraint;
+
+impl Constraint for UuidSqlTypeConstraint {
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError> {
+        in_value.parse::<Uuid>().map(Datum::Uuid)
+    }
+}
+
+// JSON keeps text as it was given, JSONB keeps parsed document
+struct JsonSqlTypeConstraint;
+
+impl Constraint for JsonSqlTypeConstraint {
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError> {
+        in_value.parse::<Json>().map(|_json| Datum::Json(in_value.to_owned()))
+    }
+}
+
+struct JsonbSqlTypeConstraint;
+
+impl Constraint for JsonbSqlTypeConstraint {
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError> {
+        in_value.parse::<Json>().map(Datum::Jsonb)
+    }
+}
+
+struct ArraySqlTypeConstraint {
+    element: Box<dyn Constraint>,
+}
+
+impl Constraint for ArraySqlTypeConstraint {
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError> {
+        array::parse(in_value)?
+            .into_iter()
+            .map(|element| match element {
+                Some(element) => self.element.validate(&element),
+                None => Ok(Datum::Null),
+            })
+            .collect::<Result<Vec<Datum>, ConstraintError>>()
+            .map(Datum::Array)
+    }
+}
+
+struct EnumSqlTypeConstraint {
+    oid: u32,
+    labels: Vec<String>,
+}
+
+impl Constraint for EnumSqlTypeConstraint {
+    // labels are case sensitive and surrounding whitespace is significant
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError> {
+        match self.labels.iter().position(|label| label == in_value) {
+            Some(position) => Ok(Datum::Enum(self.oid, position as u32, in_value.to_owned())),
+            None => Err(ConstraintError::NotAnEnumLabel),
+        }
     }
 }
 
@@ -185,52 +465,50 @@ struct CharSqlTypeConstraint {
 }
 
 impl Constraint for CharSqlTypeConstraint {
-    fn validate(&self, in_value: &str) -> Result<(), ConstraintError> {
+    // trailing spaces are insignificant, values are kept without them and
+    // padded to the length on output
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError> {
         let trimmed = in_value.trim_end();
-        if trimmed.len() > self.length as usize {
+        if trimmed.chars().count() > self.length as usize {
             Err(ConstraintError::ValueTooLong)
         } else {
-            Ok(())
+            Ok(Datum::String(trimmed.to_owned()))
         }
     }
 }
 
-struct CharSqlTypeSerializer;
-
-impl Serializer for CharSqlTypeSerializer {
-    fn ser(&self, in_value: &str) -> Vec<u8> {
-        in_value.trim_end().as_bytes().to_vec()
-    }
-
-    fn des(&self, out_value: &[u8]) -> String {
-        String::from_utf8(out_value.to_vec()).unwrap()
-    }
-}
-
 struct VarCharSqlTypeConstraint {
-    length: u64,
+    length: Option<u64>,
 }
 
 impl Constraint for VarCharSqlTypeConstraint {
-    fn validate(&self, in_value: &str) -> Result<(), ConstraintError> {
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError> {
+        let length = match self.length {
+            Some(length) => length,
+            None => return Ok(Datum::String(in_value.to_owned())),
+        };
         let trimmed = in_value.trim_end();
-        if trimmed.len() > self.length as usize {
+        if trimmed.chars().count() > length as usize {
             Err(ConstraintError::ValueTooLong)
         } else {
-            Ok(())
+            Ok(Datum::String(trimmed.to_owned()))
         }
     }
 }
 
-struct VarCharSqlTypeSerializer;
+struct TextSqlTypeConstraint;
 
-impl Serializer for VarCharSqlTypeSerializer {
-    fn ser(&self, in_value: &str) -> Vec<u8> {
-        in_value.trim_end().as_bytes().to_vec()
+impl Constraint for TextSqlTypeConstraint {
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError> {
+        Ok(Datum::String(in_value.to_owned()))
     }
+}
+
+struct ByteaSqlTypeConstraint;
 
-    fn des(&self, out_value: &[u8]) -> String {
-        String::from_utf8(out_value.to_vec()).unwrap()
+impl Constraint for ByteaSqlTypeConstraint {
+    fn validate(&self, in_value: &str) -> Result<Datum, ConstraintError> {
+        bytea::parse(in_value).map(Datum::Bytea)
     }
 }
 
@@ -238,6 +516,49 @@ impl Serializer for VarCharSqlTypeSerializer {
 mod tests {
     use super::*;
 
+    #[cfg(test)]
+    mod from_postgresql_type_oid {
+        use crate::SqlType;
+
+        #[rstest::rstest(
+            sql_type,
+            case::bool(SqlType::Bool),
+            case::smallint(SqlType::SmallInt),
+            case::integer(SqlType::Integer),
+            case::bigint(SqlType::BigInt),
+            case::real(SqlType::Real),
+            case::double_precision(SqlType::DoublePrecision),
+            case::numeric(SqlType::Decimal(None)),
+            case::varchar(SqlType::VarChar(None)),
+            case::text(SqlType::Text),
+            case::bytea(SqlType::Bytea),
+            case::uuid(SqlType::Uuid),
+            case::json(SqlType::Json),
+            case::jsonb(SqlType::Jsonb),
+            case::date(SqlType::Date),
+            case::time(SqlType::Time),
+            case::time_with_time_zone(SqlType::TimeWithTimeZone),
+            case::timestamp(SqlType::Timestamp),
+            case::timestamp_with_time_zone(SqlType::TimestampWithTimeZone),
+            case::interval(SqlType::Interval),
+            case::integer_array(SqlType::Array(Box::new(SqlType::Integer))),
+            case::text_array(SqlType::Array(Box::new(SqlType::Text)))
+        )]
+        fn built_in_types(sql_type: SqlType) {
+            assert_eq!(SqlType::from_pg_oid(sql_type.to_pg_types().pg_oid()), Some(sql_type));
+        }
+
+        #[test]
+        fn char_without_length() {
+            assert_eq!(SqlType::from_pg_oid(1042), Some(SqlType::VarChar(None)));
+        }
+
+        #[test]
+        fn unknown_oid() {
+            assert_eq!(SqlType::from_pg_oid(0), None);
+        }
+    }
+
     #[cfg(test)]
     mod to_postgresql_type_conversion {
         use crate::SqlType;
@@ -265,21 +586,64 @@ mod tests {
 
         #[test]
         fn char() {
-            assert_eq!(SqlType::Char(0).to_pg_types(), PostgreSqlType::Char);
-            assert_eq!(SqlType::Char(10).to_pg_types(), PostgreSqlType::Char);
-            assert_eq!(SqlType::Char(100).to_pg_types(), PostgreSqlType::Char);
+            assert_eq!(SqlType::Char(0).to_pg_types(), PostgreSqlType::Char(0));
+            assert_eq!(SqlType::Char(10).to_pg_types(), PostgreSqlType::Char(10));
+            assert_eq!(SqlType::Char(100).to_pg_types(), PostgreSqlType::Char(100));
         }
 
         #[test]
         fn var_char() {
-            assert_eq!(SqlType::VarChar(0).to_pg_types(), PostgreSqlType::VarChar);
-            assert_eq!(SqlType::VarChar(10).to_pg_types(), PostgreSqlType::VarChar);
-            assert_eq!(SqlType::VarChar(100).to_pg_types(), PostgreSqlType::VarChar);
+            assert_eq!(
+                SqlType::VarChar(Some(0)).to_pg_types(),
+                PostgreSqlType::VarChar(Some(0))
+            );
+            assert_eq!(
+                SqlType::VarChar(Some(10)).to_pg_types(),
+                PostgreSqlType::VarChar(Some(10))
+            );
+            assert_eq!(
+                SqlType::VarChar(Some(100)).to_pg_types(),
+                PostgreSqlType::VarChar(Some(100))
+            );
+            assert_eq!(SqlType::VarChar(None).to_pg_types(), PostgreSqlType::VarChar(None));
+        }
+
+        #[test]
+        fn text() {
+            assert_eq!(SqlType::Text.to_pg_types(), PostgreSqlType::Text);
+        }
+
+        #[test]
+        fn bytea() {
+            assert_eq!(SqlType::Bytea.to_pg_types(), PostgreSqlType::Bytea);
+        }
+
+        #[test]
+        fn uuid() {
+            assert_eq!(SqlType::Uuid.to_pg_types(), PostgreSqlType::Uuid);
+        }
+
+        #[test]
+        fn json() {
+            assert_eq!(SqlType::Json.to_pg_types(), PostgreSqlType::Json);
+        }
+
+        #[test]
+        fn jsonb() {
+            assert_eq!(SqlType::Jsonb.to_pg_types(), PostgreSqlType::Jsonb);
         }
 
         #[test]
         fn decimal() {
-            assert_eq!(SqlType::Decimal.to_pg_types(), PostgreSqlType::Decimal);
+            assert_eq!(SqlType::Decimal(None).to_pg_types(), PostgreSqlType::Decimal);
+        }
+
+        #[test]
+        fn array() {
+            assert_eq!(
+                SqlType::Array(Box::new(SqlType::Integer)).to_pg_types(),
+                PostgreSqlType::Array(Box::new(PostgreSqlType::Integer))
+            );
         }
 
         #[test]
@@ -330,32 +694,237 @@ mod tests {
     }
 
     #[cfg(test)]
-    mod ints {
+    mod bool {
+        use super::*;
+
+        #[rstest::fixture]
+        fn constraint() -> Box<dyn Constraint> {
+            SqlType::Bool.constraint()
+        }
+
+        #[rstest::rstest(
+            literal,
+            case::t("t"),
+            case::true_("true"),
+            case::upper_case("TRUE"),
+            case::yes("yes"),
+            case::y("y"),
+            case::on("on"),
+            case::one("1"),
+            case::with_spaces("  true ")
+        )]
+        fn true_literals(constraint: Box<dyn Constraint>, literal: &str) {
+            assert_eq!(constraint.validate(literal), Ok(Datum::Bool(true)));
+        }
+
+        #[rstest::rstest(
+            literal,
+            case::f("f"),
+            case::false_("false"),
+            case::upper_case("FALSE"),
+            case::no("no"),
+            case::n("n"),
+            case::off("off"),
+            case::of("of"),
+            case::zero("0")
+        )]
+        fn false_literals(constraint: Box<dyn Constraint>, literal: &str) {
+            assert_eq!(constraint.validate(literal), Ok(Datum::Bool(false)));
+        }
+
+        #[rstest::rstest(
+            literal,
+            case::ambiguous_o("o"),
+            case::too_long("truee"),
+            case::empty(""),
+            case::number("2"),
+            case::string("str")
+        )]
+        fn invalid_literals(constraint: Box<dyn Constraint>, literal: &str) {
+            assert_eq!(constraint.validate(literal), Err(ConstraintError::NotABool));
+        }
+    }
+
+    #[cfg(test)]
+    mod floats {
         use super::*;
 
         #[cfg(test)]
-        mod small {
+        mod real {
             use super::*;
 
-            #[cfg(test)]
-            mod serialization {
-                use super::*;
+            #[rstest::fixture]
+            fn constraint() -> Box<dyn Constraint> {
+                SqlType::Real.constraint()
+            }
 
-                #[rstest::fixture]
-                fn serializer() -> Box<dyn Serializer> {
-                    SqlType::SmallInt.serializer()
-                }
+            #[rstest::rstest]
+            fn in_range(constraint: Box<dyn Constraint>) {
+                assert_eq!(constraint.validate("1.5"), Ok(Datum::Float32(1.5)));
+                assert_eq!(constraint.validate("-3.4e38"), Ok(Datum::Float32(-3.4e38)));
+                assert_eq!(constraint.validate(" 12 "), Ok(Datum::Float32(12.0)));
+            }
 
-                #[rstest::rstest]
-                fn serialize(serializer: Box<dyn Serializer>) {
-                    assert_eq!(serializer.ser("1"), vec![0, 1])
-                }
+            #[rstest::rstest]
+            fn special_values(constraint: Box<dyn Constraint>) {
+                assert_eq!(constraint.validate("Infinity"), Ok(Datum::Float32(f32::INFINITY)));
+                assert_eq!(constraint.validate("-inf"), Ok(Datum::Float32(f32::NEG_INFINITY)));
+                assert!(matches!(constraint.validate("NaN"), Ok(Datum::Float32(value)) if value.is_nan()));
+            }
 
-                #[rstest::rstest]
-                fn deserialize(serializer: Box<dyn Serializer>) {
-                    assert_eq!(serializer.des(&[0, 1]), "1".to_owned())
-                }
+            #[rstest::rstest]
+            fn out_of_range(constraint: Box<dyn Constraint>) {
+                assert_eq!(constraint.validate("3.5e38"), Err(ConstraintError::OutOfRange));
+            }
+
+            #[rstest::rstest]
+            fn a_string(constraint: Box<dyn Constraint>) {
+                assert_eq!(constraint.validate("str"), Err(ConstraintError::NotAFloat));
             }
+        }
+
+        #[cfg(test)]
+        mod double_precision {
+            use super::*;
+
+            #[rstest::fixture]
+            fn constraint() -> Box<dyn Constraint> {
+                SqlType::DoublePrecision.constraint()
+            }
+
+            #[rstest::rstest]
+            fn in_range(constraint: Box<dyn Constraint>) {
+                assert_eq!(constraint.validate("1.5"), Ok(Datum::Float64(1.5)));
+                assert_eq!(constraint.validate("3.5e38"), Ok(Datum::Float64(3.5e38)));
+                assert_eq!(constraint.validate("-1e-300"), Ok(Datum::Float64(-1e-300)));
+            }
+
+            #[rstest::rstest]
+            fn special_values(constraint: Box<dyn Constraint>) {
+                assert_eq!(constraint.validate("infinity"), Ok(Datum::Float64(f64::INFINITY)));
+                assert_eq!(constraint.validate("-Infinity"), Ok(Datum::Float64(f64::NEG_INFINITY)));
+                assert!(matches!(constraint.validate("nan"), Ok(Datum::Float64(value)) if value.is_nan()));
+            }
+
+            #[rstest::rstest]
+            fn out_of_range(constraint: Box<dyn Constraint>) {
+                assert_eq!(constraint.validate("1e400"), Err(ConstraintError::OutOfRange));
+            }
+
+            #[rstest::rstest]
+            fn a_string(constraint: Box<dyn Constraint>) {
+                assert_eq!(constraint.validate("1.5.5"), Err(ConstraintError::NotAFloat));
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod numeric {
+        use super::*;
+
+        fn text(result: Result<Datum, ConstraintError>) -> Result<Option<String>, ConstraintError> {
+            result.map(|datum| datum.to_text())
+        }
+
+        #[test]
+        fn rounded_to_scale() {
+            let constraint = SqlType::Decimal(Some((5, 2))).constraint();
+            assert_eq!(text(constraint.validate("1.235")), Ok(Some("1.24".to_owned())));
+            assert_eq!(text(constraint.validate("-1.5")), Ok(Some("-1.50".to_owned())));
+            assert_eq!(text(constraint.validate("999.994")), Ok(Some("999.99".to_owned())));
+        }
+
+        #[test]
+        fn precision_overflow() {
+            let constraint = SqlType::Decimal(Some((5, 2))).constraint();
+            assert_eq!(constraint.validate("999.995"), Err(ConstraintError::OutOfRange));
+            assert_eq!(constraint.validate("1000"), Err(ConstraintError::OutOfRange));
+        }
+
+        #[test]
+        fn unconstrained() {
+            let constraint = SqlType::Decimal(None).constraint();
+            assert_eq!(
+                text(constraint.validate("123456789012345678901234567890.123456789")),
+                Ok(Some("123456789012345678901234567890.123456789".to_owned()))
+            );
+        }
+
+        #[test]
+        fn not_a_number() {
+            let constraint = SqlType::Decimal(None).constraint();
+            assert_eq!(constraint.validate("1.2.3"), Err(ConstraintError::NotANumeric));
+            assert_eq!(constraint.validate("abc"), Err(ConstraintError::NotANumeric));
+        }
+    }
+
+    #[cfg(test)]
+    mod date_and_time {
+        use super::*;
+
+        #[test]
+        fn date() {
+            let constraint = SqlType::Date.constraint();
+            assert_eq!(constraint.validate("2000-01-02"), Ok(Datum::Date(1)));
+            assert_eq!(
+                constraint.validate("2000-02-30"),
+                Err(ConstraintError::DateTimeFieldOverflow)
+            );
+            assert_eq!(constraint.validate("02/01/2000"), Err(ConstraintError::NotADateTime));
+        }
+
+        #[test]
+        fn time() {
+            let constraint = SqlType::Time.constraint();
+            assert_eq!(constraint.validate("00:00:01"), Ok(Datum::Time(1_000_000)));
+            assert_eq!(
+                SqlType::TimeWithTimeZone.constraint().validate("00:00:01+02"),
+                Ok(Datum::TimeWithTimeZone(1_000_000, 7200))
+            );
+        }
+
+        #[test]
+        fn timestamp_ignores_offset() {
+            assert_eq!(
+                SqlType::Timestamp.constraint().validate("2000-01-01 01:00:00+01"),
+                Ok(Datum::Timestamp(3_600_000_000))
+            );
+        }
+
+        #[test]
+        fn timestamp_with_time_zone_is_stored_in_utc() {
+            assert_eq!(
+                SqlType::TimestampWithTimeZone
+                    .constraint()
+                    .validate("2000-01-01 01:00:00+01"),
+                Ok(Datum::TimestampWithTimeZone(0))
+            );
+        }
+    }
+
+    #[cfg(test)]
+    mod interval {
+        use super::*;
+
+        #[test]
+        fn interval() {
+            let constraint = SqlType::Interval.constraint();
+            assert_eq!(
+                constraint.validate("1 day 2 hours"),
+                Ok(Datum::Interval(Interval::new(0, 1, 7_200_000_000)))
+            );
+            assert_eq!(constraint.validate("P1M"), Ok(Datum::Interval(Interval::new(1, 0, 0))));
+            assert_eq!(constraint.validate("tomorrow"), Err(ConstraintError::NotADateTime));
+        }
+    }
+
+    #[cfg(test)]
+    mod ints {
+        use super::*;
+
+        #[cfg(test)]
+        mod small {
+            use super::*;
 
             #[cfg(test)]
             mod validation {
@@ -368,9 +937,9 @@ mod tests {
 
                 #[rstest::rstest]
                 fn in_range(constraint: Box<dyn Constraint>) {
-                    assert_eq!(constraint.validate("1"), Ok(()));
-                    assert_eq!(constraint.validate("32767"), Ok(()));
-                    assert_eq!(constraint.validate("-32768"), Ok(()));
+                    assert_eq!(constraint.validate("1"), Ok(Datum::Int16(1)));
+                    assert_eq!(constraint.validate("32767"), Ok(Datum::Int16(32767)));
+                    assert_eq!(constraint.validate("-32768"), Ok(Datum::Int16(-32768)));
                 }
 
                 #[rstest::rstest]
@@ -399,26 +968,6 @@ mod tests {
         mod integer {
             use super::*;
 
-            #[cfg(test)]
-            mod serialization {
-                use super::*;
-
-                #[rstest::fixture]
-                fn serializer() -> Box<dyn Serializer> {
-                    SqlType::Integer.serializer()
-                }
-
-                #[rstest::rstest]
-                fn serialize(serializer: Box<dyn Serializer>) {
-                    assert_eq!(serializer.ser("1"), vec![0, 0, 0, 1])
-                }
-
-                #[rstest::rstest]
-                fn deserialize(serializer: Box<dyn Serializer>) {
-                    assert_eq!(serializer.des(&[0, 0, 0, 1]), "1".to_owned())
-                }
-            }
-
             #[cfg(test)]
             mod validation {
                 use super::*;
@@ -430,9 +979,9 @@ mod tests {
 
                 #[rstest::rstest]
                 fn in_range(constraint: Box<dyn Constraint>) {
-                    assert_eq!(constraint.validate("1"), Ok(()));
-                    assert_eq!(constraint.validate("-2147483648"), Ok(()));
-                    assert_eq!(constraint.validate("2147483647"), Ok(()));
+                    assert_eq!(constraint.validate("1"), Ok(Datum::Int32(1)));
+                    assert_eq!(constraint.validate("-2147483648"), Ok(Datum::Int32(-2147483648)));
+                    assert_eq!(constraint.validate("2147483647"), Ok(Datum::Int32(2147483647)));
                 }
 
                 #[rstest::rstest]
@@ -461,26 +1010,6 @@ mod tests {
         mod big_int {
             use super::*;
 
-            #[cfg(test)]
-            mod serialization {
-                use super::*;
-
-                #[rstest::fixture]
-                fn serializer() -> Box<dyn Serializer> {
-                    SqlType::BigInt.serializer()
-                }
-
-                #[rstest::rstest]
-                fn serialize(serializer: Box<dyn Serializer>) {
-                    assert_eq!(serializer.ser("1"), vec![0, 0, 0, 0, 0, 0, 0, 1])
-                }
-
-                #[rstest::rstest]
-                fn deserialize(serializer: Box<dyn Serializer>) {
-                    assert_eq!(serializer.des(&[0, 0, 0, 0, 0, 0, 0, 1]), "1".to_owned())
-                }
-            }
-
             #[cfg(test)]
             mod validation {
                 use super::*;
@@ -492,9 +1021,15 @@ mod tests {
 
                 #[rstest::rstest]
                 fn in_range(constraint: Box<dyn Constraint>) {
-                    assert_eq!(constraint.validate("1"), Ok(()));
-                    assert_eq!(constraint.validate("-9223372036854775808"), Ok(()));
-                    assert_eq!(constraint.validate("9223372036854775807"), Ok(()));
+                    assert_eq!(constraint.validate("1"), Ok(Datum::Int64(1)));
+                    assert_eq!(
+                        constraint.validate("-9223372036854775808"),
+                        Ok(Datum::Int64(-9223372036854775808))
+                    );
+                    assert_eq!(
+                        constraint.validate("9223372036854775807"),
+                        Ok(Datum::Int64(9223372036854775807))
+                    );
                 }
 
                 #[rstest::rstest]
@@ -534,26 +1069,6 @@ mod tests {
         mod chars {
             use super::*;
 
-            #[cfg(test)]
-            mod serialization {
-                use super::*;
-
-                #[rstest::fixture]
-                fn serializer() -> Box<dyn Serializer> {
-                    SqlType::Char(10).serializer()
-                }
-
-                #[rstest::rstest]
-                fn serialize(serializer: Box<dyn Serializer>) {
-                    assert_eq!(serializer.ser("str"), vec![115, 116, 114])
-                }
-
-                #[rstest::rstest]
-                fn deserialize(serializer: Box<dyn Serializer>) {
-                    assert_eq!(serializer.des(&[115, 116, 114]), "str".to_owned())
-                }
-            }
-
             #[cfg(test)]
             mod validation {
                 use super::*;
@@ -565,7 +1080,7 @@ mod tests {
 
                 #[rstest::rstest]
                 fn in_length(constraint: Box<dyn Constraint>) {
-                    assert_eq!(constraint.validate("1"), Ok(()))
+                    assert_eq!(constraint.validate("1"), Ok(Datum::String("1".to_owned())))
                 }
 
                 #[rstest::rstest]
@@ -575,32 +1090,26 @@ mod tests {
                         Err(ConstraintError::ValueTooLong)
                     )
                 }
-            }
-        }
-
-        #[cfg(test)]
-        mod var_chars {
-            use super::*;
-
-            #[cfg(test)]
-            mod serialization {
-                use super::*;
-
-                #[rstest::fixture]
-                fn serializer() -> Box<dyn Serializer> {
-                    SqlType::VarChar(10).serializer()
-                }
 
                 #[rstest::rstest]
-                fn serialize(serializer: Box<dyn Serializer>) {
-                    assert_eq!(serializer.ser("str"), vec![115, 116, 114])
+                fn length_in_characters(constraint: Box<dyn Constraint>) {
+                    assert_eq!(constraint.validate("привіт світ"), Err(ConstraintError::ValueTooLong));
+                    assert_eq!(constraint.validate("привіт"), Ok(Datum::String("привіт".to_owned())))
                 }
 
                 #[rstest::rstest]
-                fn deserialize(serializer: Box<dyn Serializer>) {
-                    assert_eq!(serializer.des(&[115, 116, 114]), "str".to_owned())
+                fn trailing_spaces_are_trimmed(constraint: Box<dyn Constraint>) {
+                    assert_eq!(
+                        constraint.validate(format!("abc{}", " ".repeat(20)).as_str()),
+                        Ok(Datum::String("abc".to_owned()))
+                    )
                 }
             }
+        }
+
+        #[cfg(test)]
+        mod var_chars {
+            use super::*;
 
             #[cfg(test)]
             mod validation {
@@ -608,12 +1117,12 @@ mod tests {
 
                 #[rstest::fixture]
                 fn constraint() -> Box<dyn Constraint> {
-                    SqlType::VarChar(10).constraint()
+                    SqlType::VarChar(Some(10)).constraint()
                 }
 
                 #[rstest::rstest]
                 fn in_length(constraint: Box<dyn Constraint>) {
-                    assert_eq!(constraint.validate("1"), Ok(()))
+                    assert_eq!(constraint.validate("1"), Ok(Datum::String("1".to_owned())))
                 }
 
                 #[rstest::rstest]
@@ -623,7 +1132,135 @@ mod tests {
                         Err(ConstraintError::ValueTooLong)
                     )
                 }
+                #[rstest::rstest]
+                fn length_in_characters(constraint: Box<dyn Constraint>) {
+                    assert_eq!(constraint.validate("привіт світ"), Err(ConstraintError::ValueTooLong));
+                    assert_eq!(
+                        constraint.validate("привіт сві"),
+                        Ok(Datum::String("привіт сві".to_owned()))
+                    )
+                }
+
+                #[rstest::rstest]
+                fn trailing_spaces_are_trimmed(constraint: Box<dyn Constraint>) {
+                    assert_eq!(
+                        constraint.validate(format!("abcdefghij{}", " ".repeat(5)).as_str()),
+                        Ok(Datum::String("abcdefghij".to_owned()))
+                    )
+                }
+            }
+
+            #[test]
+            fn without_length() {
+                let value = "1".repeat(100_000);
+                assert_eq!(
+                    SqlType::VarChar(None).constraint().validate(value.as_str()),
+                    Ok(Datum::String(value))
+                )
             }
         }
+
+        #[cfg(test)]
+        mod text {
+            use super::*;
+
+            #[test]
+            fn any_length() {
+                let value = "a ".repeat(100_000);
+                assert_eq!(
+                    SqlType::Text.constraint().validate(value.as_str()),
+                    Ok(Datum::String(value))
+                )
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod json {
+        use super::*;
+
+        #[test]
+        fn json_keeps_text() {
+            assert_eq!(
+                SqlType::Json
+                    .constraint()
+                    .validate(r#"{"b": 1,  "a": 2}"#)
+                    .map(|datum| datum.to_text()),
+                Ok(Some(r#"{"b": 1,  "a": 2}"#.to_owned()))
+            );
+        }
+
+        #[test]
+        fn jsonb_normalizes_text() {
+            assert_eq!(
+                SqlType::Jsonb
+                    .constraint()
+                    .validate(r#"{"bb": 1,  "a": 2, "a": 3}"#)
+                    .map(|datum| datum.to_text()),
+                Ok(Some(r#"{"a": 3, "bb": 1}"#.to_owned()))
+            );
+        }
+
+        #[rstest::rstest(sql_type, case::json(SqlType::Json), case::jsonb(SqlType::Jsonb))]
+        fn invalid(sql_type: SqlType) {
+            assert_eq!(sql_type.constraint().validate("{"), Err(ConstraintError::NotAJson));
+        }
+    }
+
+    #[cfg(test)]
+    mod uuid {
+        use super::*;
+
+        #[test]
+        fn canonical_text() {
+            let constraint = SqlType::Uuid.constraint();
+            assert_eq!(
+                constraint
+                    .validate("{A0EEBC99-9C0B4EF8-BB6D6BB9-BD380A11}")
+                    .map(|datum| datum.to_text()),
+                Ok(Some("a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11".to_owned()))
+            );
+            assert_eq!(constraint.validate("a0eebc99"), Err(ConstraintError::NotAUuid));
+        }
+    }
+
+    #[cfg(test)]
+    mod array {
+        use super::*;
+
+        #[test]
+        fn elements_are_validated() {
+            let constraint = SqlType::Array(Box::new(SqlType::SmallInt)).constraint();
+            assert_eq!(
+                constraint.validate("{1, NULL}"),
+                Ok(Datum::Array(vec![Datum::Int16(1), Datum::Null]))
+            );
+            assert_eq!(constraint.validate("{1, 100000}"), Err(ConstraintError::OutOfRange));
+            assert_eq!(constraint.validate("1"), Err(ConstraintError::NotAnArray));
+        }
+
+        #[test]
+        fn text_is_quoted_when_needed() {
+            assert_eq!(
+                SqlType::Array(Box::new(SqlType::Text))
+                    .constraint()
+                    .validate(r#"{a, "b c", "NULL"}"#)
+                    .map(|datum| datum.to_text()),
+                Ok(Some(r#"{a,"b c","NULL"}"#.to_owned()))
+            );
+        }
+    }
+
+    #[cfg(test)]
+    mod bytea {
+        use super::*;
+
+        #[test]
+        fn hex_and_escape_formats() {
+            let constraint = SqlType::Bytea.constraint();
+            assert_eq!(constraint.validate("\\x0102"), Ok(Datum::Bytea(vec![1, 2])));
+            assert_eq!(constraint.validate("a\\001"), Ok(Datum::Bytea(vec![b'a', 1])));
+            assert_eq!(constraint.validate("\\x0"), Err(ConstraintError::NotABytea));
+        }
     }
 }
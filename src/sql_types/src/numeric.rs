@@ -0,0 +1,626 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+// PostgreSQL keeps at least this number of significant digits in result of
+// division
+const MIN_SIGNIFICANT_DIGITS: i64 = 16;
+const MAX_DISPLAY_SCALE: i64 = 1000;
+
+/// Exact decimal number of arbitrary precision. Value is `digits * 10^-scale`,
+/// `scale` is also the number of fraction digits that are displayed, so
+/// `1.50` and `1.5` are equal but printed differently.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(into = "Vec<u8>", try_from = "Vec<u8>")]
+pub struct Numeric {
+    negative: bool,
+    // most significant digit first, without leading zeros; empty for zero
+    digits: Vec<u8>,
+    scale: u32,
+}
+
+impl Numeric {
+    fn new(negative: bool, digits: Vec<u8>, scale: u32) -> Self {
+        let digits = strip(digits);
+        Self {
+            negative: negative && !digits.is_empty(),
+            digits,
+            scale,
+        }
+    }
+
+    /// Number of displayed fraction digits
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// Number of digits before decimal point, zero has none
+    pub fn integer_digits(&self) -> usize {
+        self.digits.len().saturating_sub(self.scale as usize)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// Rounds half away from zero to the given number of fraction digits
+    pub fn round(&self, scale: u32) -> Self {
+        if scale >= self.scale {
+            return Self::new(self.negative, shift(self.digits.clone(), scale - self.scale), scale);
+        }
+        let dropped = (self.scale - scale) as usize;
+        if dropped > self.digits.len() {
+            return Self::new(false, vec![], scale);
+        }
+        let (kept, rest) = self.digits.split_at(self.digits.len() - dropped);
+        let kept = if rest[0] >= 5 {
+            add_magnitudes(kept, &[1])
+        } else {
+            kept.to_vec()
+        };
+        Self::new(self.negative, kept, scale)
+    }
+
+    /// Checks that the value rounded to `scale` has at most `precision`
+    /// significant digits
+    pub fn fits(&self, precision: u64, scale: u64) -> bool {
+        self.round(scale as u32).integer_digits() as u64 <= precision - scale
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        let (left, right, scale) = align(self, other);
+        if self.negative == other.negative {
+            return Self::new(self.negative, add_magnitudes(&left, &right), scale);
+        }
+        match compare_magnitudes(&left, &right) {
+            Ordering::Less => Self::new(other.negative, subtract_magnitudes(&right, &left), scale),
+            _ => Self::new(self.negative, subtract_magnitudes(&left, &right), scale),
+        }
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        self.add(&other.neg())
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        Self::new(
+            self.negative != other.negative,
+            multiply_magnitudes(&self.digits, &other.digits),
+            self.scale + other.scale,
+        )
+    }
+
+    /// Quotient with the same number of significant digits as PostgreSQL
+    /// produces, `None` if divisor is zero
+    pub fn div(&self, other: &Self) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        let scale = self.division_scale(other);
+        // one extra digit to round the last one
+        let extra = other.scale as i64 - self.scale as i64 + scale as i64 + 1;
+        let (quotient, _) = divide_magnitudes(&shift(self.digits.clone(), extra as u32), &other.digits);
+        Some(Self::new(self.negative != other.negative, quotient, scale + 1).round(scale))
+    }
+
+    /// Remainder of truncated division, `None` if divisor is zero
+    pub fn rem(&self, other: &Self) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        let (left, right, scale) = align(self, other);
+        let (_, remainder) = divide_magnitudes(&left, &right);
+        Some(Self::new(self.negative, remainder, scale))
+    }
+
+    pub fn neg(&self) -> Self {
+        Self::new(!self.negative, self.digits.clone(), self.scale)
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap()
+    }
+
+    // the same as `select_div_scale` of PostgreSQL that estimates weight of
+    // quotient in base 10000 digits
+    fn division_scale(&self, other: &Self) -> u32 {
+        let (left_weight, left_first) = self.first_base_digit();
+        let (right_weight, right_first) = other.first_base_digit();
+        let mut weight = left_weight - right_weight;
+        if left_first <= right_first {
+            weight -= 1;
+        }
+        (MIN_SIGNIFICANT_DIGITS - weight * 4)
+            .max(self.scale as i64)
+            .max(other.scale as i64)
+            .clamp(0, MAX_DISPLAY_SCALE) as u32
+    }
+
+    fn first_base_digit(&self) -> (i64, u32) {
+        if self.is_zero() {
+            return (0, 0);
+        }
+        // decimal exponent of the most significant digit
+        let exponent = self.digits.len() as i64 - self.scale as i64 - 1;
+        let weight = exponent.div_euclid(4);
+        let leading = (exponent - weight * 4 + 1) as usize;
+        let first = self
+            .digits
+            .iter()
+            .take(leading)
+            .fold(0, |acc, digit| acc * 10 + *digit as u32);
+        (
+            weight,
+            first * 10u32.pow((leading - leading.min(self.digits.len())) as u32),
+        )
+    }
+
+    /// Encoding that compares byte by byte in the same order as numbers do
+    pub fn to_sortable_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        if self.is_zero() {
+            bytes.push(1);
+        } else {
+            bytes.push(if self.negative { 0 } else { 2 });
+            let exponent = self.digits.len() as i64 - self.scale as i64;
+            let mut rest = ((exponent as i32 as u32) ^ 0x8000_0000).to_be_bytes().to_vec();
+            let significant = self.digits.len() - self.digits.iter().rev().take_while(|d| **d == 0).count();
+            rest.extend(self.digits[..significant].iter().map(|digit| digit + 1));
+            rest.push(0);
+            if self.negative {
+                rest.iter_mut().for_each(|byte| *byte = !*byte);
+            }
+            bytes.extend(rest);
+        }
+        bytes.extend(&self.scale.to_be_bytes());
+        bytes
+    }
+
+    pub fn from_sortable_bytes(bytes: &[u8]) -> Option<Self> {
+        let (sign, rest) = bytes.split_first()?;
+        let scale = |bytes: &[u8]| -> Option<u32> { Some(u32::from_be_bytes(<[u8; 4]>::try_from(bytes).ok()?)) };
+        match sign {
+            1 => Some(Self::new(false, vec![], scale(rest)?)),
+            0 | 2 => {
+                let negative = *sign == 0;
+                let decode = |byte: u8| if negative { !byte } else { byte };
+                let exponent = u32::from_be_bytes(<[u8; 4]>::try_from(rest.get(..4)?).ok()?);
+                let exponent = ((if negative { !exponent } else { exponent }) ^ 0x8000_0000) as i32 as i64;
+                let end = rest[4..].iter().position(|byte| decode(*byte) == 0)? + 4;
+                let mut digits = rest[4..end].iter().map(|byte| decode(*byte) - 1).collect::<Vec<u8>>();
+                let scale = scale(&rest[end + 1..])?;
+                let length = usize::try_from(exponent + scale as i64).ok()?;
+                if length < digits.len() {
+                    return None;
+                }
+                digits.resize(length, 0);
+                Some(Self::new(negative, digits, scale))
+            }
+            _ => None,
+        }
+    }
+
+    /// Value in PostgreSQL binary format: number of base 10000 digits, weight
+    /// of the first digit, sign, display scale and the digits
+    pub fn to_binary(&self) -> Vec<u8> {
+        let text = self.abs_to_string();
+        let (integer, fraction) = match text.find('.') {
+            Some(point) => (&text[..point], &text[point + 1..]),
+            None => (text.as_str(), ""),
+        };
+        let integer = "0".repeat((4 - integer.len() % 4) % 4) + integer;
+        let fraction = fraction.to_owned() + &"0".repeat((4 - fraction.len() % 4) % 4);
+        let mut weight = (integer.len() / 4) as i16 - 1;
+        let mut groups = (integer + &fraction)
+            .as_bytes()
+            .chunks(4)
+            .map(|chunk| std::str::from_utf8(chunk).unwrap().parse::<i16>().unwrap())
+            .collect::<Vec<i16>>();
+        while groups.first() == Some(&0) {
+            groups.remove(0);
+            weight -= 1;
+        }
+        while groups.last() == Some(&0) {
+            groups.pop();
+        }
+        if groups.is_empty() {
+            weight = 0;
+        }
+        let sign: u16 = if self.negative { 0x4000 } else { 0x0000 };
+        let mut bytes = vec![];
+        bytes.extend(&(groups.len() as i16).to_be_bytes());
+        bytes.extend(&weight.to_be_bytes());
+        bytes.extend(&sign.to_be_bytes());
+        bytes.extend(&(self.scale as u16).to_be_bytes());
+        for group in groups {
+            bytes.extend(&group.to_be_bytes());
+        }
+        bytes
+    }
+
+    fn abs_to_string(&self) -> String {
+        let scale = self.scale as usize;
+        let mut digits = self
+            .digits
+            .iter()
+            .map(|digit| (b'0' + digit) as char)
+            .collect::<String>();
+        if digits.len() <= scale {
+            digits = "0".repeat(scale + 1 - digits.len()) + &digits;
+        }
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+        if fraction.is_empty() {
+            integer.to_owned()
+        } else {
+            format!("{}.{}", integer, fraction)
+        }
+    }
+}
+
+impl From<i64> for Numeric {
+    fn from(value: i64) -> Self {
+        let digits = (value as i128)
+            .abs()
+            .to_string()
+            .bytes()
+            .map(|digit| digit - b'0')
+            .collect();
+        Self::new(value < 0, digits, 0)
+    }
+}
+
+impl FromStr for Numeric {
+    type Err = ();
+
+    // accepts optional sign, digits with optional decimal point and exponent
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let (negative, value) = match value.as_bytes().first() {
+            Some(b'-') => (true, &value[1..]),
+            Some(b'+') => (false, &value[1..]),
+            _ => (false, value),
+        };
+        let (mantissa, exponent) = match value.find(['e', 'E']) {
+            Some(position) => (
+                &value[..position],
+                value[position + 1..].parse::<i64>().map_err(|_| ())?,
+            ),
+            None => (value, 0),
+        };
+        let (integer, fraction) = match mantissa.find('.') {
+            Some(point) => (&mantissa[..point], &mantissa[point + 1..]),
+            None => (mantissa, ""),
+        };
+        let is_digits = |part: &str| part.bytes().all(|byte| byte.is_ascii_digit());
+        if (integer.is_empty() && fraction.is_empty()) || !is_digits(integer) || !is_digits(fraction) {
+            return Err(());
+        }
+        let digits = integer
+            .bytes()
+            .chain(fraction.bytes())
+            .map(|digit| digit - b'0')
+            .collect();
+        let scale = fraction.len() as i64 - exponent;
+        if scale < 0 {
+            Ok(Self::new(negative, shift(digits, (-scale) as u32), 0))
+        } else {
+            Ok(Self::new(negative, digits, scale as u32))
+        }
+    }
+}
+
+impl Display for Numeric {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.negative {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", self.abs_to_string())
+    }
+}
+
+impl PartialEq for Numeric {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Numeric {}
+
+impl PartialOrd for Numeric {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Numeric {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let sign = |value: &Self| match (value.negative, value.is_zero()) {
+            (true, _) => -1,
+            (false, true) => 0,
+            (false, false) => 1,
+        };
+        match sign(self).cmp(&sign(other)) {
+            Ordering::Equal => {
+                let (left, right, _) = align(self, other);
+                let ordering = compare_magnitudes(&left, &right);
+                if self.negative {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            }
+            ordering => ordering,
+        }
+    }
+}
+
+impl From<Numeric> for Vec<u8> {
+    fn from(value: Numeric) -> Self {
+        value.to_sortable_bytes()
+    }
+}
+
+impl TryFrom<Vec<u8>> for Numeric {
+    type Error = String;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        Self::from_sortable_bytes(&bytes).ok_or_else(|| "malformed numeric value".to_owned())
+    }
+}
+
+fn strip(mut digits: Vec<u8>) -> Vec<u8> {
+    let zeros = digits.iter().take_while(|digit| **digit == 0).count();
+    digits.drain(..zeros);
+    digits
+}
+
+fn shift(mut digits: Vec<u8>, zeros: u32) -> Vec<u8> {
+    if !digits.is_empty() {
+        digits.resize(digits.len() + zeros as usize, 0);
+    }
+    digits
+}
+
+fn align(left: &Numeric, right: &Numeric) -> (Vec<u8>, Vec<u8>, u32) {
+    let scale = left.scale.max(right.scale);
+    (
+        shift(left.digits.clone(), scale - left.scale),
+        shift(right.digits.clone(), scale - right.scale),
+        scale,
+    )
+}
+
+fn compare_magnitudes(left: &[u8], right: &[u8]) -> Ordering {
+    left.len().cmp(&right.len()).then_with(|| left.cmp(right))
+}
+
+fn add_magnitudes(left: &[u8], right: &[u8]) -> Vec<u8> {
+    let mut result = vec![];
+    let mut carry = 0;
+    let mut left = left.iter().rev();
+    let mut right = right.iter().rev();
+    loop {
+        let (l, r) = (left.next(), right.next());
+        if l.is_none() && r.is_none() {
+            break;
+        }
+        let sum = l.unwrap_or(&0) + r.unwrap_or(&0) + carry;
+        result.push(sum % 10);
+        carry = sum / 10;
+    }
+    if carry > 0 {
+        result.push(carry);
+    }
+    result.reverse();
+    strip(result)
+}
+
+// left has to be not less than right
+fn subtract_magnitudes(left: &[u8], right: &[u8]) -> Vec<u8> {
+    let mut result = vec![];
+    let mut borrow = 0;
+    let mut right = right.iter().rev();
+    for l in left.iter().rev() {
+        let r = right.next().unwrap_or(&0) + borrow;
+        if *l >= r {
+            result.push(l - r);
+            borrow = 0;
+        } else {
+            result.push(l + 10 - r);
+            borrow = 1;
+        }
+    }
+    result.reverse();
+    strip(result)
+}
+
+fn multiply_magnitudes(left: &[u8], right: &[u8]) -> Vec<u8> {
+    let mut result = vec![0u32; left.len() + right.len()];
+    for (i, l) in left.iter().enumerate().rev() {
+        for (j, r) in right.iter().enumerate().rev() {
+            result[i + j + 1] += *l as u32 * *r as u32;
+        }
+    }
+    for index in (1..result.len()).rev() {
+        result[index - 1] += result[index] / 10;
+        result[index] %= 10;
+    }
+    strip(result.into_iter().map(|digit| digit as u8).collect())
+}
+
+fn divide_magnitudes(dividend: &[u8], divisor: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let mut quotient = vec![];
+    let mut remainder = vec![];
+    for digit in dividend {
+        remainder.push(*digit);
+        remainder = strip(remainder);
+        let mut times = 0;
+        while compare_magnitudes(&remainder, divisor) != Ordering::Less {
+            remainder = subtract_magnitudes(&remainder, divisor);
+            times += 1;
+        }
+        quotient.push(times);
+    }
+    (strip(quotient), remainder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numeric(value: &str) -> Numeric {
+        value.parse().unwrap()
+    }
+
+    #[rstest::rstest(
+        value,
+        expected,
+        case::integer("123", "123"),
+        case::fraction("-1.50", "-1.50"),
+        case::leading_zeros("007.25", "7.25"),
+        case::no_integer_part(".5", "0.5"),
+        case::exponent("1.5e3", "1500"),
+        case::negative_exponent("15E-4", "0.0015"),
+        case::negative_zero("-0.00", "0.00")
+    )]
+    fn parse_and_display(value: &str, expected: &str) {
+        assert_eq!(numeric(value).to_string(), expected);
+    }
+
+    #[rstest::rstest(value, case::empty(""), case::point("."), case::letters("1a"), case::exponent("1e"))]
+    fn invalid(value: &str) {
+        assert_eq!(value.parse::<Numeric>(), Err(()));
+    }
+
+    #[rstest::rstest(
+        value,
+        scale,
+        expected,
+        case::half_up("1.235", 2, "1.24"),
+        case::down("1.234", 2, "1.23"),
+        case::negative_half_away_from_zero("-1.235", 2, "-1.24"),
+        case::carry("9.995", 2, "10.00"),
+        case::pad("1.5", 3, "1.500"),
+        case::to_integer("0.5", 0, "1"),
+        case::small("0.0004", 2, "0.00")
+    )]
+    fn round(value: &str, scale: u32, expected: &str) {
+        assert_eq!(numeric(value).round(scale).to_string(), expected);
+    }
+
+    #[test]
+    fn fits_precision() {
+        assert!(numeric("999.994").fits(5, 2));
+        assert!(!numeric("999.995").fits(5, 2));
+        assert!(numeric("0.12345").fits(3, 3));
+        assert!(!numeric("1").fits(3, 3));
+    }
+
+    #[rstest::rstest(
+        left,
+        right,
+        sum,
+        difference,
+        product,
+        case::positive("1.5", "2.25", "3.75", "-0.75", "3.375"),
+        case::negative("-10", "0.1", "-9.9", "-10.1", "-1.0"),
+        case::exact("0.1", "0.2", "0.3", "-0.1", "0.02")
+    )]
+    fn arithmetic(left: &str, right: &str, sum: &str, difference: &str, product: &str) {
+        let (left, right) = (numeric(left), numeric(right));
+        assert_eq!(left.add(&right).to_string(), sum);
+        assert_eq!(left.sub(&right).to_string(), difference);
+        assert_eq!(left.mul(&right).to_string(), product);
+    }
+
+    #[rstest::rstest(
+        left,
+        right,
+        expected,
+        case::repeating("1", "3", "0.33333333333333333333"),
+        case::exact("10", "4", "2.5000000000000000"),
+        case::negative("-2", "3", "-0.66666666666666666667"),
+        case::large("123456789", "3", "41152263.000000000000"),
+        case::keeps_scale("1.00000000000000000000000", "2", "0.50000000000000000000000")
+    )]
+    fn division(left: &str, right: &str, expected: &str) {
+        assert_eq!(numeric(left).div(&numeric(right)).unwrap().to_string(), expected);
+    }
+
+    #[test]
+    fn division_by_zero() {
+        assert_eq!(numeric("1").div(&numeric("0.0")), None);
+        assert_eq!(numeric("1").rem(&numeric("0")), None);
+    }
+
+    #[test]
+    fn remainder() {
+        assert_eq!(numeric("7.5").rem(&numeric("2")).unwrap().to_string(), "1.5");
+        assert_eq!(numeric("-7").rem(&numeric("3")).unwrap().to_string(), "-1");
+    }
+
+    #[test]
+    fn equal_regardless_of_scale() {
+        assert_eq!(numeric("1.50"), numeric("1.5"));
+        assert!(numeric("-2") < numeric("-1.99"));
+        assert!(numeric("0.001") > numeric("0"));
+    }
+
+    #[test]
+    fn sortable_bytes_preserve_order() {
+        let values = vec![
+            "-1000", "-999.9", "-1.23", "-1.2", "-1", "-0.05", "0", "0.00", "0.05", "0.5", "1", "1.2", "1.23", "10",
+            "999.9", "1000",
+        ];
+        let mut encoded = values
+            .iter()
+            .map(|value| numeric(value).to_sortable_bytes())
+            .collect::<Vec<Vec<u8>>>();
+        let original = encoded.clone();
+        encoded.sort();
+        assert_eq!(encoded, original);
+    }
+
+    #[rstest::rstest(
+        value,
+        case::zero("0.00"),
+        case::integer("1200"),
+        case::fraction("-12.3400"),
+        case::small("0.0005")
+    )]
+    fn sortable_bytes_round_trip(value: &str) {
+        let decoded = Numeric::from_sortable_bytes(&numeric(value).to_sortable_bytes()).unwrap();
+        assert_eq!(decoded.to_string(), value);
+    }
+
+    #[test]
+    fn binary() {
+        assert_eq!(
+            numeric("-12345.678").to_binary(),
+            vec![0, 3, 0, 1, 0x40, 0, 0, 3, 0, 1, 0x09, 0x29, 0x1a, 0x7c]
+        );
+        assert_eq!(
+            numeric("0.05").to_binary(),
+            vec![0, 1, 0xff, 0xff, 0, 0, 0, 2, 0x01, 0xf4]
+        );
+        assert_eq!(numeric("0.0").to_binary(), vec![0, 0, 0, 0, 0, 0, 0, 1]);
+    }
+}
@@ -0,0 +1,140 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::ConstraintError;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+/// Universally unique identifier, values are ordered by their bytes as
+/// PostgreSQL does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Uuid([u8; 16]);
+
+impl Uuid {
+    pub fn from_bytes(bytes: [u8; 16]) -> Self {
+        Self(bytes)
+    }
+
+    /// Randomly generated version 4 UUID as `gen_random_uuid()` returns
+    pub fn new_v4() -> Self {
+        let mut bytes = rand::random::<[u8; 16]>();
+        bytes[6] = (bytes[6] & 0x0f) | 0x40;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+}
+
+impl FromStr for Uuid {
+    type Err = ConstraintError;
+
+    /// Accepts the same forms as PostgreSQL: 32 hexadecimal digits in any
+    /// case, optionally surrounded by braces, and a hyphen is allowed after
+    /// any group of four digits, e.g. canonical
+    /// `a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11`
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let value = match value.strip_prefix('{') {
+            Some(value) => value.strip_suffix('}').ok_or(ConstraintError::NotAUuid)?,
+            None => value,
+        };
+        let digits = value.as_bytes();
+        let mut bytes = [0; 16];
+        let mut position = 0;
+        for (index, byte) in bytes.iter_mut().enumerate() {
+            let high = digits.get(position).and_then(|digit| (*digit as char).to_digit(16));
+            let low = digits.get(position + 1).and_then(|digit| (*digit as char).to_digit(16));
+            match (high, low) {
+                (Some(high), Some(low)) => *byte = (high * 16 + low) as u8,
+                _ => return Err(ConstraintError::NotAUuid),
+            }
+            position += 2;
+            if index % 2 == 1 && index < 15 && digits.get(position) == Some(&b'-') {
+                position += 1;
+            }
+        }
+        if position == digits.len() {
+            Ok(Self(bytes))
+        } else {
+            Err(ConstraintError::NotAUuid)
+        }
+    }
+}
+
+impl Display for Uuid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (index, byte) in self.0.iter().enumerate() {
+            if index == 4 || index == 6 || index == 8 || index == 10 {
+                write!(f, "-")?;
+            }
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CANONICAL: &str = "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11";
+
+    #[rstest::rstest(
+        value,
+        case::canonical("a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11"),
+        case::upper_case("A0EEBC99-9C0B-4EF8-BB6D-6BB9BD380A11"),
+        case::braces("{a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11}"),
+        case::without_hyphens("a0eebc999c0b4ef8bb6d6bb9bd380a11"),
+        case::hyphens_after_groups_of_four("a0ee-bc99-9c0b-4ef8-bb6d-6bb9-bd38-0a11"),
+        case::braces_and_other_hyphens("{a0eebc99-9c0b4ef8-bb6d6bb9-bd380a11}")
+    )]
+    fn parse(value: &str) {
+        assert_eq!(
+            value.parse::<Uuid>().map(|uuid| uuid.to_string()),
+            Ok(CANONICAL.to_owned())
+        );
+    }
+
+    #[rstest::rstest(
+        value,
+        case::empty(""),
+        case::too_short("a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a1"),
+        case::too_long("a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a111"),
+        case::not_hex("g0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11"),
+        case::misplaced_hyphen("a0e-ebc99-9c0b-4ef8-bb6d-6bb9bd380a11"),
+        case::unbalanced_brace("{a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11")
+    )]
+    fn invalid(value: &str) {
+        assert_eq!(value.parse::<Uuid>(), Err(ConstraintError::NotAUuid));
+    }
+
+    #[test]
+    fn random_uuid_is_version_4() {
+        let uuid = Uuid::new_v4();
+        assert_eq!(uuid.as_bytes()[6] >> 4, 4);
+        assert_eq!(uuid.as_bytes()[8] >> 6, 0b10);
+        assert_ne!(uuid, Uuid::new_v4());
+    }
+
+    #[test]
+    fn ordered_by_bytes() {
+        assert!(Uuid::from_bytes([0; 16]) < Uuid::from_bytes([1; 16]));
+    }
+}
@@ -13,13 +13,18 @@
 // limitations under the License.
 
 use kernel::{SystemError, SystemResult};
-use std::{collections::HashMap, fmt::Debug};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Debug,
+    ops::Bound,
+    sync::RwLock,
+};
 
 pub type Result<T, E> = std::result::Result<T, E>;
 pub type Row = (Key, Values);
 pub type Key = Vec<u8>;
 pub type Values = Vec<u8>;
-pub type ReadCursor = Box<dyn Iterator<Item = Result<Row, SystemError>>>;
+pub type ReadCursor = Box<dyn Iterator<Item = Result<Row, SystemError>> + Send>;
 
 #[derive(Debug, PartialEq)]
 pub struct NamespaceAlreadyExists;
@@ -44,25 +49,25 @@ pub enum OperationOnObjectError {
     ObjectDoesNotExist,
 }
 
-pub trait BackendStorage {
+pub trait BackendStorage: Send + Sync {
     type ErrorMapper: StorageErrorMapper;
 
     fn create_namespace_with_objects(
-        &mut self,
+        &self,
         namespace: &str,
         object_names: Vec<&str>,
     ) -> SystemResult<Result<(), NamespaceAlreadyExists>>;
 
-    fn create_namespace(&mut self, namespace: &str) -> SystemResult<Result<(), NamespaceAlreadyExists>>;
+    fn create_namespace(&self, namespace: &str) -> SystemResult<Result<(), NamespaceAlreadyExists>>;
 
-    fn drop_namespace(&mut self, namespace: &str) -> SystemResult<Result<(), NamespaceDoesNotExist>>;
+    fn drop_namespace(&self, namespace: &str) -> SystemResult<Result<(), NamespaceDoesNotExist>>;
 
-    fn create_object(&mut self, namespace: &str, object_name: &str) -> SystemResult<Result<(), CreateObjectError>>;
+    fn create_object(&self, namespace: &str, object_name: &str) -> SystemResult<Result<(), CreateObjectError>>;
 
-    fn drop_object(&mut self, namespace: &str, object_name: &str) -> SystemResult<Result<(), DropObjectError>>;
+    fn drop_object(&self, namespace: &str, object_name: &str) -> SystemResult<Result<(), DropObjectError>>;
 
     fn write(
-        &mut self,
+        &self,
         namespace: &str,
         object_name: &str,
         values: Vec<Row>,
@@ -71,13 +76,15 @@ pub trait BackendStorage {
     fn read(&self, namespace: &str, object_name: &str) -> SystemResult<Result<ReadCursor, OperationOnObjectError>>;
 
     fn delete(
-        &mut self,
+        &self,
         namespace: &str,
         object_name: &str,
         keys: Vec<Key>,
     ) -> SystemResult<Result<usize, OperationOnObjectError>>;
 
     fn is_table_exists(&self, namespace: &str, object_name: &str) -> bool;
+
+    fn is_namespace_exists(&self, namespace: &str) -> bool;
 }
 
 pub trait StorageErrorMapper {
@@ -115,16 +122,78 @@ impl StorageErrorMapper for SledErrorMapper {
     }
 }
 
+// sled iterators can't be sent between threads, so records are read in
+// batches and every batch starts right after the last read key
+struct SledCursor {
+    tree: sled::Tree,
+    last_key: Option<sled::IVec>,
+    batch: VecDeque<Result<Row, SystemError>>,
+    done: bool,
+}
+
+impl SledCursor {
+    const BATCH_SIZE: usize = 1024;
+
+    fn new(tree: sled::Tree) -> Self {
+        Self {
+            tree,
+            last_key: None,
+            batch: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    fn read_batch(&mut self) {
+        let range = match self.last_key.take() {
+            Some(key) => self.tree.range((Bound::Excluded(key), Bound::Unbounded)),
+            None => self.tree.iter(),
+        };
+        for item in range.take(Self::BATCH_SIZE) {
+            match item {
+                Ok((key, values)) => {
+                    self.batch.push_back(Ok((key.to_vec(), values.to_vec())));
+                    self.last_key = Some(key);
+                }
+                Err(error) => {
+                    self.batch.push_back(Err(SledErrorMapper::map(error)));
+                    self.done = true;
+                    return;
+                }
+            }
+        }
+        if self.batch.len() < Self::BATCH_SIZE {
+            self.done = true;
+        }
+    }
+}
+
+impl Iterator for SledCursor {
+    type Item = Result<Row, SystemError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.batch.is_empty() && !self.done {
+            self.read_batch();
+        }
+        self.batch.pop_front()
+    }
+}
+
 #[derive(Default)]
 pub struct SledBackendStorage {
-    namespaces: HashMap<String, sled::Db>,
+    namespaces: RwLock<HashMap<String, sled::Db>>,
 }
 
 impl SledBackendStorage {
-    fn new_namespace(&mut self, namespace: &str) -> SystemResult<&mut sled::Db> {
+    fn new_namespace(&self, namespace: &str) -> SystemResult<sled::Db> {
         match sled::Config::default().temporary(true).open() {
             Ok(database) => {
-                let database = self.namespaces.entry(namespace.to_owned()).or_insert(database);
+                let database = self
+                    .namespaces
+                    .write()
+                    .unwrap()
+                    .entry(namespace.to_owned())
+                    .or_insert(database)
+                    .clone();
                 Ok(database)
             }
             Err(error) => Err(SledErrorMapper::map(error)),
@@ -136,11 +205,11 @@ impl BackendStorage for SledBackendStorage {
     type ErrorMapper = SledErrorMapper;
 
     fn create_namespace_with_objects(
-        &mut self,
+        &self,
         namespace: &str,
         object_names: Vec<&str>,
     ) -> SystemResult<Result<(), NamespaceAlreadyExists>> {
-        if self.namespaces.contains_key(namespace) {
+        if self.namespaces.read().unwrap().contains_key(namespace) {
             Ok(Err(NamespaceAlreadyExists))
         } else {
             let namespace = self.new_namespace(namespace)?;
@@ -154,16 +223,16 @@ impl BackendStorage for SledBackendStorage {
         }
     }
 
-    fn create_namespace(&mut self, namespace: &str) -> SystemResult<Result<(), NamespaceAlreadyExists>> {
-        if self.namespaces.contains_key(namespace) {
+    fn create_namespace(&self, namespace: &str) -> SystemResult<Result<(), NamespaceAlreadyExists>> {
+        if self.namespaces.read().unwrap().contains_key(namespace) {
             Ok(Err(NamespaceAlreadyExists))
         } else {
             self.new_namespace(namespace).map(|_| Ok(()))
         }
     }
 
-    fn drop_namespace(&mut self, namespace: &str) -> SystemResult<Result<(), NamespaceDoesNotExist>> {
-        match self.namespaces.remove(namespace) {
+    fn drop_namespace(&self, namespace: &str) -> SystemResult<Result<(), NamespaceDoesNotExist>> {
+        match self.namespaces.write().unwrap().remove(namespace) {
             Some(namespace) => {
                 drop(namespace);
                 Ok(Ok(()))
@@ -172,8 +241,8 @@ impl BackendStorage for SledBackendStorage {
         }
     }
 
-    fn create_object(&mut self, namespace: &str, object_name: &str) -> SystemResult<Result<(), CreateObjectError>> {
-        match self.namespaces.get(namespace) {
+    fn create_object(&self, namespace: &str, object_name: &str) -> SystemResult<Result<(), CreateObjectError>> {
+        match self.namespaces.read().unwrap().get(namespace) {
             Some(namespace) => {
                 if namespace.tree_names().contains(&(object_name.into())) {
                     Ok(Err(CreateObjectError::ObjectAlreadyExists))
@@ -188,8 +257,8 @@ impl BackendStorage for SledBackendStorage {
         }
     }
 
-    fn drop_object(&mut self, namespace: &str, object_name: &str) -> SystemResult<Result<(), DropObjectError>> {
-        match self.namespaces.get(namespace) {
+    fn drop_object(&self, namespace: &str, object_name: &str) -> SystemResult<Result<(), DropObjectError>> {
+        match self.namespaces.read().unwrap().get(namespace) {
             Some(namespace) => match namespace.drop_tree(object_name.as_bytes()) {
                 Ok(true) => Ok(Ok(())),
                 Ok(false) => Ok(Err(DropObjectError::ObjectDoesNotExist)),
@@ -200,12 +269,12 @@ impl BackendStorage for SledBackendStorage {
     }
 
     fn write(
-        &mut self,
+        &self,
         namespace: &str,
         object_name: &str,
         rows: Vec<Row>,
     ) -> SystemResult<Result<usize, OperationOnObjectError>> {
-        match self.namespaces.get(namespace) {
+        match self.namespaces.read().unwrap().get(namespace) {
             Some(namespace) => {
                 if namespace.tree_names().contains(&(object_name.into())) {
                     match namespace.open_tree(object_name) {
@@ -230,14 +299,11 @@ impl BackendStorage for SledBackendStorage {
     }
 
     fn read(&self, namespace: &str, object_name: &str) -> SystemResult<Result<ReadCursor, OperationOnObjectError>> {
-        match self.namespaces.get(namespace) {
+        match self.namespaces.read().unwrap().get(namespace) {
             Some(namespace) => {
                 if namespace.tree_names().contains(&(object_name.into())) {
                     match namespace.open_tree(object_name) {
-                        Ok(object) => Ok(Ok(Box::new(object.iter().map(|item| match item {
-                            Ok((key, values)) => Ok((key.to_vec(), values.to_vec())),
-                            Err(error) => Err(Self::ErrorMapper::map(error)),
-                        })))),
+                        Ok(object) => Ok(Ok(Box::new(SledCursor::new(object)))),
                         Err(error) => Err(Self::ErrorMapper::map(error)),
                     }
                 } else {
@@ -249,12 +315,12 @@ impl BackendStorage for SledBackendStorage {
     }
 
     fn delete(
-        &mut self,
+        &self,
         namespace: &str,
         object_name: &str,
         keys: Vec<Key>,
     ) -> SystemResult<Result<usize, OperationOnObjectError>> {
-        match self.namespaces.get(namespace) {
+        match self.namespaces.read().unwrap().get(namespace) {
             Some(namespace) => {
                 if namespace.tree_names().contains(&(object_name.into())) {
                     let mut deleted = 0;
@@ -279,11 +345,15 @@ impl BackendStorage for SledBackendStorage {
     }
 
     fn is_table_exists(&self, namespace: &str, object_name: &str) -> bool {
-        match self.namespaces.get(namespace) {
+        match self.namespaces.read().unwrap().get(namespace) {
             Some(namespace) => namespace.tree_names().contains(&(object_name.into())),
             None => false,
         }
     }
+
+    fn is_namespace_exists(&self, namespace: &str) -> bool {
+        self.namespaces.read().unwrap().contains_key(namespace)
+    }
 }
 
 #[cfg(test)]
@@ -299,7 +369,7 @@ mod tests {
     }
 
     #[rstest::fixture]
-    fn with_namespace(mut storage: Storage) -> Storage {
+    fn with_namespace(storage: Storage) -> Storage {
         storage
             .create_namespace("namespace")
             .expect("no system errors")
@@ -308,7 +378,7 @@ mod tests {
     }
 
     #[rstest::fixture]
-    fn with_object(mut with_namespace: Storage) -> Storage {
+    fn with_object(with_namespace: Storage) -> Storage {
         with_namespace
             .create_object("namespace", "object_name")
             .expect("no system errors")
@@ -386,7 +456,7 @@ mod tests {
         use super::*;
 
         #[rstest::rstest]
-        fn create_namespace_with_objects(mut storage: Storage) {
+        fn create_namespace_with_objects(storage: Storage) {
             assert_eq!(
                 storage
                     .create_namespace_with_objects("namespace", vec!["object_1", "object_2"])
@@ -409,7 +479,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn create_namespace_with_objects_that_already_exists(mut with_namespace: Storage) {
+        fn create_namespace_with_objects_that_already_exists(with_namespace: Storage) {
             assert_eq!(
                 with_namespace
                     .create_namespace_with_objects("namespace", vec!["object_1", "object_2"])
@@ -419,7 +489,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn create_namespaces_with_different_names(mut storage: Storage) {
+        fn create_namespaces_with_different_names(storage: Storage) {
             assert_eq!(
                 storage.create_namespace("namespace_1").expect("namespace created"),
                 Ok(())
@@ -431,7 +501,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn create_namespace_with_existing_name(mut with_namespace: Storage) {
+        fn create_namespace_with_existing_name(with_namespace: Storage) {
             assert_eq!(
                 with_namespace.create_namespace("namespace").expect("no system errors"),
                 Err(NamespaceAlreadyExists)
@@ -439,7 +509,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn drop_namespace(mut with_namespace: Storage) {
+        fn drop_namespace(with_namespace: Storage) {
             assert_eq!(
                 with_namespace.drop_namespace("namespace").expect("namespace dropped"),
                 Ok(())
@@ -451,7 +521,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn drop_namespace_that_was_not_created(mut storage: Storage) {
+        fn drop_namespace_that_was_not_created(storage: Storage) {
             assert_eq!(
                 storage.drop_namespace("does_not_exists").expect("no system errors"),
                 Err(NamespaceDoesNotExist)
@@ -459,7 +529,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn dropping_namespace_drops_objects_in_it(mut with_namespace: Storage) {
+        fn dropping_namespace_drops_objects_in_it(with_namespace: Storage) {
             with_namespace
                 .create_object("namespace", "object_name_1")
                 .expect("no system errors")
@@ -497,7 +567,7 @@ mod tests {
         use super::*;
 
         #[rstest::rstest]
-        fn create_objects_with_different_names(mut with_namespace: Storage) {
+        fn create_objects_with_different_names(with_namespace: Storage) {
             assert_eq!(
                 with_namespace
                     .create_object("namespace", "object_name_1")
@@ -513,7 +583,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn create_object_with_the_same_name(mut with_namespace: Storage) {
+        fn create_object_with_the_same_name(with_namespace: Storage) {
             with_namespace
                 .create_object("namespace", "object_name")
                 .expect("no system errors")
@@ -528,7 +598,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn create_object_with_the_same_name_in_different_namespaces(mut storage: Storage) {
+        fn create_object_with_the_same_name_in_different_namespaces(storage: Storage) {
             storage
                 .create_namespace("namespace_1")
                 .expect("no system errors")
@@ -552,7 +622,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn create_object_in_not_existent_namespace(mut storage: Storage) {
+        fn create_object_in_not_existent_namespace(storage: Storage) {
             assert_eq!(
                 storage
                     .create_object("not_existent", "object_name")
@@ -567,7 +637,7 @@ mod tests {
         use super::*;
 
         #[rstest::rstest]
-        fn drop_object(mut with_object: Storage) {
+        fn drop_object(with_object: Storage) {
             assert_eq!(
                 with_object
                     .drop_object("namespace", "object_name")
@@ -583,7 +653,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn drop_not_created_object(mut with_namespace: Storage) {
+        fn drop_not_created_object(with_namespace: Storage) {
             assert_eq!(
                 with_namespace
                     .drop_object("namespace", "not_existed_object")
@@ -593,7 +663,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn drop_object_in_not_existent_namespace(mut storage: Storage) {
+        fn drop_object_in_not_existent_namespace(storage: Storage) {
             assert_eq!(
                 storage.drop_object("not_existent", "object").expect("no system errors"),
                 Err(DropObjectError::NamespaceDoesNotExist)
@@ -606,7 +676,7 @@ mod tests {
         use super::*;
 
         #[rstest::rstest]
-        fn insert_row_into_object(mut with_object: Storage) {
+        fn insert_row_into_object(with_object: Storage) {
             assert_eq!(
                 with_object
                     .write("namespace", "object_name", as_rows(vec![(1u8, vec!["123"])],))
@@ -624,7 +694,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn insert_many_rows_into_object(mut with_object: Storage) {
+        fn insert_many_rows_into_object(with_object: Storage) {
             with_object
                 .write("namespace", "object_name", as_rows(vec![(1u8, vec!["123"])]))
                 .expect("no system errors")
@@ -644,7 +714,26 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn insert_into_non_existent_object(mut with_namespace: Storage) {
+        fn read_rows_that_do_not_fit_into_one_batch(with_object: Storage) {
+            let rows = (0..2 * SledCursor::BATCH_SIZE as u32 + 1)
+                .map(|key| (key.to_be_bytes().to_vec(), key.to_string().into_bytes()))
+                .collect::<Vec<Row>>();
+            with_object
+                .write("namespace", "object_name", rows.clone())
+                .expect("no system errors")
+                .expect("values are written");
+
+            assert_eq!(
+                with_object
+                    .read("namespace", "object_name")
+                    .expect("no system errors")
+                    .map(|iter| iter.collect::<Vec<Result<Row, SystemError>>>()),
+                Ok(rows.into_iter().map(Ok).collect())
+            );
+        }
+
+        #[rstest::rstest]
+        fn insert_into_non_existent_object(with_namespace: Storage) {
             assert_eq!(
                 with_namespace
                     .write("namespace", "not_existed", as_rows(vec![(1u8, vec!["123"])],))
@@ -654,7 +743,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn insert_into_object_in_non_existent_namespace(mut storage: Storage) {
+        fn insert_into_object_in_non_existent_namespace(storage: Storage) {
             assert_eq!(
                 storage
                     .write("not_existed", "object", as_rows(vec![(1u8, vec!["123"])],))
@@ -686,7 +775,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn delete_some_records_from_object(mut with_object: Storage) {
+        fn delete_some_records_from_object(with_object: Storage) {
             with_object
                 .write(
                     "namespace",
@@ -713,7 +802,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn delete_from_not_existed_object(mut with_namespace: Storage) {
+        fn delete_from_not_existed_object(with_namespace: Storage) {
             assert_eq!(
                 with_namespace
                     .delete("namespace", "not_existent", vec![])
@@ -723,7 +812,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn delete_from_not_existent_namespace(mut storage: Storage) {
+        fn delete_from_not_existent_namespace(storage: Storage) {
             assert_eq!(
                 storage
                     .delete("not existent", "object", vec![])
@@ -733,7 +822,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn select_all_from_object_with_many_columns(mut with_object: Storage) {
+        fn select_all_from_object_with_many_columns(with_object: Storage) {
             with_object
                 .write("namespace", "object_name", as_rows(vec![(1u8, vec!["1", "2", "3"])]))
                 .expect("no system errors")
@@ -749,7 +838,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn insert_multiple_rows(mut with_object: Storage) {
+        fn insert_multiple_rows(with_object: Storage) {
             with_object
                 .write(
                     "namespace",
@@ -14,19 +14,60 @@
 
 use crate::{
     backend::{
-        self, BackendStorage, CreateObjectError, DropObjectError, NamespaceAlreadyExists, NamespaceDoesNotExist,
-        OperationOnObjectError, Row, SledBackendStorage,
+        self, BackendStorage, CreateObjectError, DropObjectError, Key, NamespaceAlreadyExists, NamespaceDoesNotExist,
+        OperationOnObjectError, ReadCursor, Row, SledBackendStorage, Values,
     },
-    CreateTableError, DropTableError, OperationOnTableError, Projection, SchemaAlreadyExists, SchemaDoesNotExist,
+    lock::{LockDuration, LockManager, LockMode, Resource, SessionId},
+    CreateSequenceError, CreateTableError, CreateTypeError, DropSequenceError, DropTableError, DropTypeError,
+    OperationOnTableError, Projection, SchemaAlreadyExists, SchemaDoesNotExist, SequenceError, SequenceOptions,
 };
 use kernel::{SystemError, SystemResult};
 use serde::{Deserialize, Serialize};
-use sql_types::{ConstraintError, SqlType};
-use std::collections::HashMap;
+use sql_types::{ConstraintError, Datum, SqlType};
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    ops::Bound,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+// changes of a session that are not committed yet
+#[derive(Default)]
+struct UndoLog {
+    // values that records of tables had before the session changed them for
+    // the first time, `None` for records that the session inserted
+    records: HashMap<(String, String), BTreeMap<Key, Option<Values>>>,
+    // schemas, tables, types and sequences in the order the session created
+    // or dropped them, they are undone backwards
+    definitions: Vec<Definition>,
+}
+
+// what dropped objects had is kept to be written back
+enum Definition {
+    SchemaCreated(String),
+    SchemaDropped(String),
+    TableCreated(String, String),
+    TableDropped(String, String, Vec<Row>),
+    TypeCreated(String),
+    SequenceCreated(String),
+    SystemRecordsDeleted(&'static str, Vec<Row>),
+}
 
 pub struct FrontendStorage<P: BackendStorage> {
-    key_id_generator: usize,
+    key_id_generator: AtomicUsize,
+    // keys of records that are given out next are reserved in `system.keys`,
+    // so records that were written before restart are not overwritten
+    reserved_keys: Mutex<usize>,
     persistent: P,
+    lock_manager: LockManager,
+    // the last values that sessions got from sequences, it also serializes
+    // sequence updates
+    sequence_values: Mutex<HashMap<(SessionId, String), i64>>,
+    // changes of sessions that are not committed yet, other sessions read
+    // records from them as they were before the changes
+    undo_logs: Arc<Mutex<HashMap<SessionId, UndoLog>>>,
 }
 
 impl FrontendStorage<SledBackendStorage> {
@@ -36,37 +77,573 @@ impl FrontendStorage<SledBackendStorage> {
 }
 
 impl<P: BackendStorage> FrontendStorage<P> {
-    pub fn new(mut persistent: P) -> SystemResult<Self> {
-        match persistent.create_namespace_with_objects("system", vec!["columns"])? {
-            Ok(()) => Ok(Self {
-                key_id_generator: 0,
-                persistent,
-            }),
-            Err(NamespaceAlreadyExists) => {
-                Err(SystemError::unrecoverable("system namespace already exists".to_owned()))
+    pub fn new(persistent: P) -> SystemResult<Self> {
+        // backend that has system namespace was used before restart, its
+        // tables, sequences and types are used as they are
+        let reserved_keys =
+            match persistent.create_namespace_with_objects("system", vec!["columns", "types", "sequences", "keys"])? {
+                Ok(()) => 0,
+                Err(NamespaceAlreadyExists) => system_table(persistent.read("system", "keys")?, "keys")?
+                    .collect::<SystemResult<Vec<Row>>>()?
+                    .into_iter()
+                    .find(|(key, _reserved)| key.as_slice() == RECORD_KEYS)
+                    .map(|(_key, reserved)| bincode::deserialize::<usize>(&reserved).unwrap())
+                    .unwrap_or_default(),
+            };
+        Ok(Self {
+            key_id_generator: AtomicUsize::new(reserved_keys),
+            reserved_keys: Mutex::new(reserved_keys),
+            persistent,
+            lock_manager: LockManager::default(),
+            sequence_values: Mutex::new(HashMap::new()),
+            undo_logs: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    pub fn lock_manager(&self) -> &LockManager {
+        &self.lock_manager
+    }
+
+    /// Releases everything that the session holds
+    pub fn end_session(&self, session_id: SessionId) {
+        self.sequence_values
+            .lock()
+            .unwrap()
+            .retain(|(session, _sequence), _value| *session != session_id);
+        self.undo_logs.lock().unwrap().remove(&session_id);
+        self.lock_manager.end_session(session_id);
+    }
+
+    /// Makes changes of the session visible to other sessions
+    pub fn commit(&self, session_id: SessionId) {
+        self.undo_logs.lock().unwrap().remove(&session_id);
+    }
+
+    /// Undoes schemas, tables, types and sequences that the session created
+    /// or dropped and writes back records that it changed as they were
+    /// before the changes. Rows of changed tables are locked until the end of
+    /// transaction as other sessions could have read changed records before
+    /// they are written back
+    pub fn rollback(&self, session_id: SessionId) -> SystemResult<()> {
+        let tables = match self.undo_logs.lock().unwrap().get(&session_id) {
+            Some(undo_log) => undo_log.records.keys().cloned().collect::<Vec<(String, String)>>(),
+            None => return Ok(()),
+        };
+        for (schema_name, table_name) in tables.iter() {
+            if let Err(error) = self.lock_manager.lock(
+                session_id,
+                Resource::rows(schema_name, table_name),
+                LockMode::AccessExclusive,
+                LockDuration::Transaction,
+            ) {
+                log::warn!(
+                    "session {} rolls back {}.{} without waiting for readers {:?}",
+                    session_id,
+                    schema_name,
+                    table_name,
+                    error
+                );
             }
         }
+        let undo_log = match self.undo_logs.lock().unwrap().remove(&session_id) {
+            Some(undo_log) => undo_log,
+            None => return Ok(()),
+        };
+        // dropped tables are created again before their records are written back
+        for definition in undo_log.definitions.into_iter().rev() {
+            self.undo_definition(definition)?;
+        }
+        for ((schema_name, table_name), changes) in undo_log.records {
+            let mut to_write = vec![];
+            let mut to_delete = vec![];
+            for (key, values) in changes {
+                match values {
+                    Some(values) => to_write.push((key, values)),
+                    None => to_delete.push(key),
+                }
+            }
+            // table could be created by the same transaction
+            let _ = self.persistent.delete(&schema_name, &table_name, to_delete)?;
+            let _ = self.persistent.write(&schema_name, &table_name, to_write)?;
+        }
+        Ok(())
+    }
+
+    fn undo_definition(&self, definition: Definition) -> SystemResult<()> {
+        match definition {
+            Definition::SchemaCreated(schema_name) => {
+                let _ = self.persistent.drop_namespace(&schema_name)?;
+            }
+            Definition::SchemaDropped(schema_name) => {
+                let _ = self.persistent.create_namespace(&schema_name)?;
+            }
+            Definition::TableCreated(schema_name, table_name) => {
+                let _ = self.persistent.drop_object(&schema_name, &table_name)?;
+                system_table(
+                    self.persistent
+                        .delete("system", "columns", vec![columns_key(&schema_name, &table_name)])?,
+                    "columns",
+                )?;
+            }
+            Definition::TableDropped(schema_name, table_name, records) => {
+                let _ = self.persistent.create_object(&schema_name, &table_name)?;
+                let _ = self.persistent.write(&schema_name, &table_name, records)?;
+            }
+            Definition::TypeCreated(key) => {
+                system_table(
+                    self.persistent.delete("system", "types", vec![key.into_bytes()])?,
+                    "types",
+                )?;
+            }
+            Definition::SequenceCreated(key) => self.delete_sequences(vec![key])?,
+            Definition::SystemRecordsDeleted(table_name, records) => {
+                system_table(self.persistent.write("system", table_name, records)?, table_name)?;
+            }
+        }
+        Ok(())
     }
 
-    pub fn create_schema(&mut self, schema_name: &str) -> SystemResult<Result<(), SchemaAlreadyExists>> {
+    fn log_definition(&self, session_id: SessionId, definition: Definition) {
+        self.undo_logs
+            .lock()
+            .unwrap()
+            .entry(session_id)
+            .or_default()
+            .definitions
+            .push(definition);
+    }
+
+    // remembers values of records before they are changed for the first time
+    fn log_changes(
+        &self,
+        session_id: SessionId,
+        schema_name: &str,
+        table_name: &str,
+        changes: impl Iterator<Item = (Key, Option<Values>)>,
+    ) {
+        let mut undo_logs = self.undo_logs.lock().unwrap();
+        let table_log = undo_logs
+            .entry(session_id)
+            .or_default()
+            .records
+            .entry((schema_name.to_owned(), table_name.to_owned()))
+            .or_default();
+        for (key, values) in changes {
+            table_log.entry(key).or_insert(values);
+        }
+    }
+
+    // changes that other sessions made to dropped tables can't be undone,
+    // changes of the session are written back when its tables are restored
+    fn forget_changes(&self, session_id: SessionId, dropped: impl Fn(&str, &str) -> bool) {
+        for (_session_id, undo_log) in self
+            .undo_logs
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .filter(|(session, _undo_log)| **session != session_id)
+        {
+            undo_log
+                .records
+                .retain(|(schema_name, table_name), _changes| !dropped(schema_name, table_name));
+        }
+    }
+
+    pub fn create_schema(
+        &self,
+        schema_name: &str,
+        session_id: SessionId,
+    ) -> SystemResult<Result<(), SchemaAlreadyExists>> {
         match self.persistent.create_namespace(schema_name)? {
-            Ok(()) => Ok(Ok(())),
+            Ok(()) => {
+                self.log_definition(session_id, Definition::SchemaCreated(schema_name.to_owned()));
+                Ok(Ok(()))
+            }
             Err(NamespaceAlreadyExists) => Ok(Err(SchemaAlreadyExists)),
         }
     }
 
-    pub fn drop_schema(&mut self, schema_name: &str) -> SystemResult<Result<(), SchemaDoesNotExist>> {
+    /// Drops schema with its tables, types and sequences
+    pub fn drop_schema(
+        &self,
+        schema_name: &str,
+        session_id: SessionId,
+    ) -> SystemResult<Result<(), SchemaDoesNotExist>> {
+        // tables are found by their columns as namespaces don't list objects
+        let mut tables = vec![];
+        for record in system_table(self.persistent.read("system", "columns")?, "columns")? {
+            let (key, _columns) = record?;
+            if let Some(table_name) = key
+                .strip_prefix(schema_name.as_bytes())
+                .and_then(|table_name| String::from_utf8(table_name.to_vec()).ok())
+            {
+                if let Ok(records) = self.persistent.read(schema_name, &table_name)? {
+                    tables.push((table_name, records.collect::<SystemResult<Vec<Row>>>()?));
+                }
+            }
+        }
         match self.persistent.drop_namespace(schema_name)? {
-            Ok(()) => Ok(Ok(())),
+            Ok(()) => {
+                for (table_name, records) in tables {
+                    self.log_definition(
+                        session_id,
+                        Definition::TableDropped(schema_name.to_owned(), table_name, records),
+                    );
+                }
+                let types = self
+                    .types()?
+                    .into_iter()
+                    .filter(|(key, _sql_type)| key.starts_with(&(schema_name.to_owned() + ".")))
+                    .map(|(key, sql_type)| (key.into_bytes(), bincode::serialize(&sql_type).unwrap()))
+                    .collect::<Vec<Row>>();
+                system_table(
+                    self.persistent.delete(
+                        "system",
+                        "types",
+                        types.iter().map(|(key, _sql_type)| key.clone()).collect(),
+                    )?,
+                    "types",
+                )?;
+                self.log_definition(session_id, Definition::SystemRecordsDeleted("types", types));
+                let sequences = self
+                    .sequences()?
+                    .into_iter()
+                    .filter(|(key, _sequence)| key.starts_with(&(schema_name.to_owned() + ".")))
+                    .collect();
+                self.drop_sequences(sequences, session_id)?;
+                // schema is created again before its objects are restored
+                self.log_definition(session_id, Definition::SchemaDropped(schema_name.to_owned()));
+                self.forget_changes(session_id, |schema, _table| schema == schema_name);
+                Ok(Ok(()))
+            }
             Err(NamespaceDoesNotExist) => Ok(Err(SchemaDoesNotExist)),
         }
     }
 
+    /// Creates enum type with labels in the given order. Type OID and the
+    /// next one, that is OID of array of the type, are not used by other
+    /// types
+    pub fn create_enum_type(
+        &self,
+        schema_name: &str,
+        type_name: &str,
+        labels: Vec<String>,
+        session_id: SessionId,
+    ) -> SystemResult<Result<(), CreateTypeError>> {
+        if !self.persistent.is_namespace_exists(schema_name) {
+            return Ok(Err(CreateTypeError::SchemaDoesNotExist));
+        }
+        let types = self.types()?;
+        let key = object_key(schema_name, type_name);
+        if types.iter().any(|(existing, _sql_type)| *existing == key) {
+            return Ok(Err(CreateTypeError::TypeAlreadyExists));
+        }
+        let oid = types
+            .iter()
+            .filter_map(|(_key, sql_type)| match sql_type {
+                SqlType::Enum { oid, .. } => Some(oid + 2),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(FIRST_TYPE_OID);
+        let sql_type = SqlType::Enum {
+            name: type_name.to_owned(),
+            oid,
+            labels,
+        };
+        system_table(
+            self.persistent.write(
+                "system",
+                "types",
+                vec![(key.clone().into_bytes(), bincode::serialize(&sql_type).unwrap())],
+            )?,
+            "types",
+        )?;
+        self.log_definition(session_id, Definition::TypeCreated(key));
+        Ok(Ok(()))
+    }
+
+    /// User defined type, `None` if there is no such type in the schema
+    pub fn user_type(&self, schema_name: &str, type_name: &str) -> SystemResult<Option<SqlType>> {
+        let key = object_key(schema_name, type_name);
+        Ok(self
+            .types()?
+            .into_iter()
+            .find(|(existing, _sql_type)| *existing == key)
+            .map(|(_key, sql_type)| sql_type))
+    }
+
+    /// Drops user defined type if no column has it
+    pub fn drop_type(
+        &self,
+        schema_name: &str,
+        type_name: &str,
+        session_id: SessionId,
+    ) -> SystemResult<Result<(), DropTypeError>> {
+        if !self.persistent.is_namespace_exists(schema_name) {
+            return Ok(Err(DropTypeError::SchemaDoesNotExist));
+        }
+        let sql_type = match self.user_type(schema_name, type_name)? {
+            Some(sql_type) => sql_type,
+            None => return Ok(Err(DropTypeError::TypeDoesNotExist)),
+        };
+        let columns = system_table(self.persistent.read("system", "columns")?, "columns")?;
+        for record in columns {
+            let (_table, columns) = record?;
+            let columns = bincode::deserialize::<Vec<ColumnMetadata>>(&columns).unwrap();
+            if columns.iter().any(|column| uses_type(&column.sql_type, &sql_type)) {
+                return Ok(Err(DropTypeError::DependentObjectsExist));
+            }
+        }
+        let key = object_key(schema_name, type_name).into_bytes();
+        system_table(self.persistent.delete("system", "types", vec![key.clone()])?, "types")?;
+        self.log_definition(
+            session_id,
+            Definition::SystemRecordsDeleted("types", vec![(key, bincode::serialize(&sql_type).unwrap())]),
+        );
+        Ok(Ok(()))
+    }
+
+    /// Creates sequence that starts from `options.start`. Sequence that is
+    /// owned by a table is dropped together with the table. State of
+    /// sequences is kept in `system.sequences` of the backend, so sequences
+    /// continue from their last values when storage is restarted over it
+    pub fn create_sequence(
+        &self,
+        schema_name: &str,
+        sequence_name: &str,
+        options: SequenceOptions,
+        owned_by: Option<&str>,
+        session_id: SessionId,
+    ) -> SystemResult<Result<(), CreateSequenceError>> {
+        if !self.persistent.is_namespace_exists(schema_name) {
+            return Ok(Err(CreateSequenceError::SchemaDoesNotExist));
+        }
+        let _guard = self.sequence_values.lock().unwrap();
+        let key = object_key(schema_name, sequence_name);
+        if self.sequence(&key)?.is_some() {
+            return Ok(Err(CreateSequenceError::SequenceAlreadyExists));
+        }
+        let sequence = Sequence {
+            last_value: options.start,
+            is_called: false,
+            options,
+            owned_by: owned_by.map(|table_name| object_key(schema_name, table_name)),
+        };
+        self.write_sequence(key.clone(), &sequence)?;
+        self.log_definition(session_id, Definition::SequenceCreated(key));
+        Ok(Ok(()))
+    }
+
+    pub fn drop_sequence(
+        &self,
+        schema_name: &str,
+        sequence_name: &str,
+        session_id: SessionId,
+    ) -> SystemResult<Result<(), DropSequenceError>> {
+        if !self.persistent.is_namespace_exists(schema_name) {
+            return Ok(Err(DropSequenceError::SchemaDoesNotExist));
+        }
+        let key = object_key(schema_name, sequence_name);
+        match self.sequence(&key)? {
+            Some(sequence) => self.drop_sequences(vec![(key, sequence)], session_id)?,
+            None => return Ok(Err(DropSequenceError::SequenceDoesNotExist)),
+        }
+        Ok(Ok(()))
+    }
+
+    /// Advances sequence and remembers the value as the current one of the
+    /// session. Sequences are not transactional, thus the value is never
+    /// given out twice even if the transaction is rolled back
+    pub fn next_value(
+        &self,
+        schema_name: &str,
+        sequence_name: &str,
+        session_id: SessionId,
+    ) -> SystemResult<Result<i64, SequenceError>> {
+        let mut values = self.sequence_values.lock().unwrap();
+        let key = object_key(schema_name, sequence_name);
+        let mut sequence = match self.sequence(&key)? {
+            Some(sequence) => sequence,
+            None => return Ok(Err(SequenceError::SequenceDoesNotExist)),
+        };
+        let options = &sequence.options;
+        let value = if sequence.is_called {
+            match sequence.last_value.checked_add(options.increment) {
+                Some(value) if value >= options.min_value && value <= options.max_value => value,
+                _ if options.increment > 0 => return Ok(Err(SequenceError::MaxValueReached(options.max_value))),
+                _ => return Ok(Err(SequenceError::MinValueReached(options.min_value))),
+            }
+        } else {
+            sequence.last_value
+        };
+        sequence.last_value = value;
+        sequence.is_called = true;
+        self.write_sequence(key.clone(), &sequence)?;
+        values.insert((session_id, key), value);
+        Ok(Ok(value))
+    }
+
+    /// The last value that the session got from the sequence
+    pub fn current_value(
+        &self,
+        schema_name: &str,
+        sequence_name: &str,
+        session_id: SessionId,
+    ) -> SystemResult<Result<i64, SequenceError>> {
+        let values = self.sequence_values.lock().unwrap();
+        let key = object_key(schema_name, sequence_name);
+        if self.sequence(&key)?.is_none() {
+            return Ok(Err(SequenceError::SequenceDoesNotExist));
+        }
+        match values.get(&(session_id, key)) {
+            Some(value) => Ok(Ok(*value)),
+            None => Ok(Err(SequenceError::CurrentValueNotDefined)),
+        }
+    }
+
+    /// Sets the last value of the sequence, the next value is the given one
+    /// if `is_called` is `false` and the one that follows otherwise
+    pub fn set_value(
+        &self,
+        schema_name: &str,
+        sequence_name: &str,
+        value: i64,
+        is_called: bool,
+        session_id: SessionId,
+    ) -> SystemResult<Result<i64, SequenceError>> {
+        let mut values = self.sequence_values.lock().unwrap();
+        let key = object_key(schema_name, sequence_name);
+        let mut sequence = match self.sequence(&key)? {
+            Some(sequence) => sequence,
+            None => return Ok(Err(SequenceError::SequenceDoesNotExist)),
+        };
+        if value < sequence.options.min_value || value > sequence.options.max_value {
+            return Ok(Err(SequenceError::ValueOutOfBounds(
+                sequence.options.min_value,
+                sequence.options.max_value,
+            )));
+        }
+        sequence.last_value = value;
+        sequence.is_called = is_called;
+        self.write_sequence(key.clone(), &sequence)?;
+        if is_called {
+            values.insert((session_id, key), value);
+        }
+        Ok(Ok(value))
+    }
+
+    fn sequence(&self, key: &str) -> SystemResult<Option<Sequence>> {
+        Ok(self
+            .sequences()?
+            .into_iter()
+            .find(|(existing, _sequence)| existing == key)
+            .map(|(_key, sequence)| sequence))
+    }
+
+    // qualified names of sequences and their state
+    fn sequences(&self) -> SystemResult<Vec<(String, Sequence)>> {
+        system_table(self.persistent.read("system", "sequences")?, "sequences")?
+            .map(|record| {
+                record.map(|(key, sequence)| {
+                    (
+                        String::from_utf8(key).unwrap(),
+                        bincode::deserialize::<Sequence>(&sequence).unwrap(),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    fn write_sequence(&self, key: String, sequence: &Sequence) -> SystemResult<()> {
+        system_table(
+            self.persistent.write(
+                "system",
+                "sequences",
+                vec![(key.into_bytes(), bincode::serialize(sequence).unwrap())],
+            )?,
+            "sequences",
+        )?;
+        Ok(())
+    }
+
+    fn drop_sequences(&self, sequences: Vec<(String, Sequence)>, session_id: SessionId) -> SystemResult<()> {
+        self.delete_sequences(sequences.iter().map(|(key, _sequence)| key.clone()).collect())?;
+        self.log_definition(
+            session_id,
+            Definition::SystemRecordsDeleted(
+                "sequences",
+                sequences
+                    .into_iter()
+                    .map(|(key, sequence)| (key.into_bytes(), bincode::serialize(&sequence).unwrap()))
+                    .collect(),
+            ),
+        );
+        Ok(())
+    }
+
+    // sequence that is created again is a new one for sessions
+    fn delete_sequences(&self, keys: Vec<String>) -> SystemResult<()> {
+        self.sequence_values
+            .lock()
+            .unwrap()
+            .retain(|(_session, sequence), _value| !keys.contains(sequence));
+        system_table(
+            self.persistent.delete(
+                "system",
+                "sequences",
+                keys.into_iter().map(String::into_bytes).collect(),
+            )?,
+            "sequences",
+        )?;
+        Ok(())
+    }
+
+    // qualified names of user defined types and the types
+    fn types(&self) -> SystemResult<Vec<(String, SqlType)>> {
+        system_table(self.persistent.read("system", "types")?, "types")?
+            .map(|record| {
+                record.map(|(key, sql_type)| {
+                    (
+                        String::from_utf8(key).unwrap(),
+                        bincode::deserialize::<SqlType>(&sql_type).unwrap(),
+                    )
+                })
+            })
+            .collect()
+    }
+
     pub fn create_table(
-        &mut self,
+        &self,
         schema_name: &str,
         table_name: &str,
         column_names: Vec<(String, SqlType)>,
+        session_id: SessionId,
+    ) -> SystemResult<Result<(), CreateTableError>> {
+        self.create_table_with_defaults(
+            schema_name,
+            table_name,
+            column_names
+                .into_iter()
+                .map(|(name, sql_type)| (name, sql_type, None))
+                .collect(),
+            &[],
+            session_id,
+        )
+    }
+
+    /// Creates table which columns can have default values. Default value is
+    /// kept as SQL expression that is evaluated by the engine every time a
+    /// value of the column is not provided. Values of `generated_always`
+    /// columns are taken only from their defaults unless the engine is told to
+    /// override them
+    pub fn create_table_with_defaults(
+        &self,
+        schema_name: &str,
+        table_name: &str,
+        columns: Vec<(String, SqlType, Option<String>)>,
+        generated_always: &[String],
+        session_id: SessionId,
     ) -> SystemResult<Result<(), CreateTableError>> {
         match self.persistent.create_object(schema_name, table_name)? {
             Ok(()) => self
@@ -75,17 +652,27 @@ impl<P: BackendStorage> FrontendStorage<P> {
                     "system",
                     "columns",
                     vec![(
-                        (schema_name.to_owned() + table_name).as_bytes().to_vec(),
-                        column_names
-                            .into_iter()
-                            .map(|(name, sql_type)| bincode::serialize(&ColumnMetadata { name, sql_type }).unwrap())
-                            .collect::<Vec<Vec<u8>>>()
-                            .join(&b'|')
-                            .to_vec(),
+                        columns_key(schema_name, table_name),
+                        bincode::serialize(
+                            &columns
+                                .into_iter()
+                                .map(|(name, sql_type, default)| ColumnMetadata {
+                                    generated_always: generated_always.contains(&name),
+                                    name,
+                                    sql_type,
+                                    default,
+                                })
+                                .collect::<Vec<ColumnMetadata>>(),
+                        )
+                        .unwrap(),
                     )],
                 )?
                 .map(|_| {
                     log::info!("column data is recorded");
+                    self.log_definition(
+                        session_id,
+                        Definition::TableCreated(schema_name.to_owned(), table_name.to_owned()),
+                    );
                     Ok(())
                 })
                 .map_err(|error| {
@@ -101,23 +688,41 @@ impl<P: BackendStorage> FrontendStorage<P> {
         }
     }
 
-    pub fn table_columns(&mut self, schema_name: &str, table_name: &str) -> SystemResult<Vec<(String, SqlType)>> {
+    pub fn table_columns(&self, schema_name: &str, table_name: &str) -> SystemResult<Vec<(String, SqlType)>> {
+        Ok(self
+            .columns_metadata(schema_name, table_name)?
+            .into_iter()
+            .map(|ColumnMetadata { name, sql_type, .. }| (name, sql_type))
+            .collect())
+    }
+
+    /// Names and default expressions of columns that have them
+    pub fn column_defaults(&self, schema_name: &str, table_name: &str) -> SystemResult<Vec<(String, String)>> {
+        Ok(self
+            .columns_metadata(schema_name, table_name)?
+            .into_iter()
+            .filter_map(|ColumnMetadata { name, default, .. }| default.map(|default| (name, default)))
+            .collect())
+    }
+
+    /// Names of the columns which values are always generated from defaults
+    pub fn generated_always_columns(&self, schema_name: &str, table_name: &str) -> SystemResult<Vec<String>> {
+        Ok(self
+            .columns_metadata(schema_name, table_name)?
+            .into_iter()
+            .filter(|column| column.generated_always)
+            .map(|ColumnMetadata { name, .. }| name)
+            .collect())
+    }
+
+    fn columns_metadata(&self, schema_name: &str, table_name: &str) -> SystemResult<Vec<ColumnMetadata>> {
         self.persistent
             .read("system", "columns")?
             .map(|reads| {
                 reads
                     .map(backend::Result::unwrap)
-                    .filter(|(table, _columns)| *table == (schema_name.to_owned() + table_name).as_bytes().to_vec())
-                    .map(|(_id, columns)| {
-                        columns
-                            .split(|b| *b == b'|')
-                            .filter(|v| !v.is_empty())
-                            .map(|c| {
-                                let ColumnMetadata { name, sql_type } = bincode::deserialize(c).unwrap();
-                                (name, sql_type)
-                            })
-                            .collect::<Vec<(String, SqlType)>>()
-                    })
+                    .filter(|(table, _columns)| *table == columns_key(schema_name, table_name))
+                    .map(|(_id, columns)| bincode::deserialize::<Vec<ColumnMetadata>>(&columns).unwrap())
                     .next()
                     .unwrap_or_default()
             })
@@ -131,26 +736,62 @@ impl<P: BackendStorage> FrontendStorage<P> {
             })
     }
 
-    pub fn drop_table(&mut self, schema_name: &str, table_name: &str) -> SystemResult<Result<(), DropTableError>> {
+    pub fn table_exists(&self, schema_name: &str, table_name: &str) -> bool {
+        self.persistent.is_table_exists(schema_name, table_name)
+    }
+
+    pub fn drop_table(
+        &self,
+        schema_name: &str,
+        table_name: &str,
+        session_id: SessionId,
+    ) -> SystemResult<Result<(), DropTableError>> {
+        let records = match self.persistent.read(schema_name, table_name)? {
+            Ok(records) => records.collect::<SystemResult<Vec<Row>>>()?,
+            Err(_) => vec![],
+        };
         match self.persistent.drop_object(schema_name, table_name)? {
-            Ok(()) => Ok(Ok(())),
+            // columns of dropped table do not keep their types from being dropped
+            Ok(()) => {
+                self.log_definition(
+                    session_id,
+                    Definition::TableDropped(schema_name.to_owned(), table_name.to_owned(), records),
+                );
+                let key = columns_key(schema_name, table_name);
+                let columns = system_table(self.persistent.read("system", "columns")?, "columns")?
+                    .filter(|record| matches!(record, Ok((table, _columns)) if *table == key))
+                    .collect::<SystemResult<Vec<Row>>>()?;
+                system_table(self.persistent.delete("system", "columns", vec![key])?, "columns")?;
+                self.log_definition(session_id, Definition::SystemRecordsDeleted("columns", columns));
+                // sequences of SERIAL and IDENTITY columns
+                let table_key = object_key(schema_name, table_name);
+                let sequences = self
+                    .sequences()?
+                    .into_iter()
+                    .filter(|(_key, sequence)| sequence.owned_by.as_ref() == Some(&table_key))
+                    .collect();
+                self.drop_sequences(sequences, session_id)?;
+                self.forget_changes(session_id, |schema, table| schema == schema_name && table == table_name);
+                Ok(Ok(()))
+            }
             Err(DropObjectError::ObjectDoesNotExist) => Ok(Err(DropTableError::TableDoesNotExist)),
             Err(DropObjectError::NamespaceDoesNotExist) => Ok(Err(DropTableError::SchemaDoesNotExist)),
         }
     }
 
     pub fn insert_into(
-        &mut self,
+        &self,
         schema_name: &str,
         table_name: &str,
         columns: Vec<String>,
-        rows: Vec<Vec<String>>,
+        rows: Vec<Vec<Datum>>,
+        session_id: SessionId,
     ) -> SystemResult<Result<(), OperationOnTableError>> {
         let all_columns = self.table_columns(schema_name, table_name)?;
         let index_columns = if columns.is_empty() {
             let mut index_cols = vec![];
             for (index, (name, sql_type)) in all_columns.iter().enumerate() {
-                index_cols.push((index, name.clone(), *sql_type));
+                index_cols.push((index, name.clone(), sql_type.clone()));
             }
 
             index_cols
@@ -161,7 +802,7 @@ impl<P: BackendStorage> FrontendStorage<P> {
                 let mut found = None;
                 for (index, (name, sql_type)) in all_columns.iter().enumerate() {
                     if *name == col {
-                        found = Some((index, name.clone(), *sql_type));
+                        found = Some((index, name.clone(), sql_type.clone()));
                         break;
                     }
                 }
@@ -191,54 +832,37 @@ impl<P: BackendStorage> FrontendStorage<P> {
                     return Ok(Err(OperationOnTableError::InsertTooManyExpressions));
                 }
 
-                let key = self.key_id_generator.to_be_bytes().to_vec();
-
-                // TODO: The default value or NULL should be initialized for SQL types of all columns.
-                let mut record = vec![vec![0, 0]; all_columns.len()];
-                let mut out_of_range = vec![];
-                let mut not_an_int = vec![];
-                let mut value_too_long = vec![];
-                for (item, (index, name, sql_type)) in row.iter().zip(index_columns.iter()) {
-                    match sql_type.constraint().validate(item.as_str()) {
-                        Ok(()) => {
-                            record[*index] = sql_type.serializer().ser(item.as_str());
-                        }
-                        Err(ConstraintError::OutOfRange) => {
-                            out_of_range.push((name.clone(), *sql_type));
-                        }
-                        Err(ConstraintError::NotAnInt) => {
-                            not_an_int.push((name.clone(), *sql_type));
-                        }
-                        Err(ConstraintError::ValueTooLong) => {
-                            value_too_long.push((name.clone(), *sql_type));
+                let key = self.next_key()?;
+
+                // TODO: default values of columns should be used instead of NULL
+                let mut record = vec![Datum::Null; all_columns.len()];
+                let mut violations = HashMap::new();
+                for (item, (index, name, sql_type)) in row.into_iter().zip(index_columns.iter()) {
+                    match typed(item, sql_type) {
+                        Ok(value) => {
+                            record[*index] = value;
                         }
+                        Err(error) => violations
+                            .entry(error)
+                            .or_insert_with(Vec::new)
+                            .push((name.clone(), sql_type.clone())),
                     }
                 }
-                if !out_of_range.is_empty() {
-                    errors
-                        .entry(ConstraintError::OutOfRange)
-                        .or_insert_with(Vec::new)
-                        .push(out_of_range);
-                }
-                if !not_an_int.is_empty() {
-                    errors
-                        .entry(ConstraintError::NotAnInt)
-                        .or_insert_with(Vec::new)
-                        .push(not_an_int);
-                }
-                if !value_too_long.is_empty() {
-                    errors
-                        .entry(ConstraintError::ValueTooLong)
-                        .or_insert_with(Vec::new)
-                        .push(value_too_long);
+                for (error, columns) in violations {
+                    errors.entry(error).or_insert_with(Vec::new).push(columns);
                 }
-                to_write.push((key, record.join(&b'|')));
-                self.key_id_generator += 1;
+                to_write.push((key, encode(&record)));
             }
 
             if !errors.is_empty() {
                 return Ok(Err(OperationOnTableError::ConstraintViolation(errors)));
             }
+            self.log_changes(
+                session_id,
+                schema_name,
+                table_name,
+                to_write.iter().map(|(key, _values)| (key.clone(), None)),
+            );
         }
 
         match self.persistent.write(schema_name, table_name, to_write)? {
@@ -248,27 +872,45 @@ impl<P: BackendStorage> FrontendStorage<P> {
         }
     }
 
+    fn next_key(&self) -> SystemResult<Key> {
+        let key = self.key_id_generator.fetch_add(1, Ordering::SeqCst);
+        let mut reserved_keys = self.reserved_keys.lock().unwrap();
+        if key >= *reserved_keys {
+            *reserved_keys = key + KEYS_RESERVED_AT_ONCE;
+            system_table(
+                self.persistent.write(
+                    "system",
+                    "keys",
+                    vec![(RECORD_KEYS.to_vec(), bincode::serialize(&*reserved_keys).unwrap())],
+                )?,
+                "keys",
+            )?;
+        }
+        Ok(key.to_be_bytes().to_vec())
+    }
+
     pub fn select_all_from(
-        &mut self,
+        &self,
         schema_name: &str,
         table_name: &str,
         columns: Vec<String>,
+        session_id: SessionId,
     ) -> SystemResult<Result<Projection, OperationOnTableError>> {
         let all_columns = self.table_columns(schema_name, table_name)?;
         let mut description = vec![];
         let mut column_indexes = vec![];
         let mut non_existing_columns = vec![];
-        for (i, column) in columns.iter().enumerate() {
+        for column in columns.iter() {
             let mut found = None;
             for (index, (name, sql_type)) in all_columns.iter().enumerate() {
                 if name == column {
-                    found = Some(((index, i), (name.clone(), *sql_type)));
+                    found = Some((index, (name.clone(), sql_type.clone())));
                     break;
                 }
             }
 
-            if let Some((index_pair, name_type_pair)) = found {
-                column_indexes.push(index_pair);
+            if let Some((index, name_type_pair)) = found {
+                column_indexes.push(index);
                 description.push(name_type_pair);
             } else {
                 non_existing_columns.push(column.clone());
@@ -280,20 +922,16 @@ impl<P: BackendStorage> FrontendStorage<P> {
                 if !non_existing_columns.is_empty() {
                     return Ok(Err(OperationOnTableError::ColumnDoesNotExist(non_existing_columns)));
                 }
-                read.map(backend::Result::unwrap)
-                    .map(|(_key, values)| values)
-                    .map(|bytes| {
-                        let mut values = vec![];
-                        for (i, (origin, ord)) in column_indexes.iter().enumerate() {
-                            for (index, value) in bytes.split(|b| *b == b'|').enumerate() {
-                                if index == *origin {
-                                    values.push((ord, description[i].1.serializer().des(value)))
-                                }
-                            }
-                        }
-                        values.into_iter().map(|(_, value)| value).collect()
-                    })
-                    .collect()
+                Box::new(
+                    CommittedRecords::new(
+                        read,
+                        (schema_name.to_owned(), table_name.to_owned()),
+                        session_id,
+                        self.undo_logs.clone(),
+                    )
+                    .map(|values| decode(&values))
+                    .map(move |record| column_indexes.iter().map(|index| record[*index].clone()).collect()),
+                )
             }
             Err(OperationOnObjectError::ObjectDoesNotExist) => {
                 return Ok(Err(OperationOnTableError::TableDoesNotExist))
@@ -306,106 +944,125 @@ impl<P: BackendStorage> FrontendStorage<P> {
     }
 
     pub fn update_all(
-        &mut self,
+        &self,
         schema_name: &str,
         table_name: &str,
-        rows: Vec<(String, String)>,
+        rows: Vec<(String, Datum)>,
+        session_id: SessionId,
     ) -> SystemResult<Result<usize, OperationOnTableError>> {
         let all_columns = self.table_columns(schema_name, table_name)?;
-        let mut errors = HashMap::new();
-        let mut out_of_range = vec![];
-        let mut not_an_int = vec![];
-        let mut value_too_long = vec![];
-        let mut index_value_pairs = vec![];
-        let mut non_existing_columns = vec![];
-        for (column_name, value) in rows {
-            let mut found = None;
-            for (index, (name, sql_type)) in all_columns.iter().enumerate() {
-                if *name == column_name {
-                    match sql_type.constraint().validate(value.as_str()) {
-                        Ok(()) => {
-                            found = Some((index, sql_type.serializer().ser(value.as_str())));
-                        }
-                        Err(ConstraintError::OutOfRange) => {
-                            out_of_range.push((name.clone(), *sql_type));
-                        }
-                        Err(ConstraintError::NotAnInt) => {
-                            not_an_int.push((name.clone(), *sql_type));
-                        }
-                        Err(ConstraintError::ValueTooLong) => {
-                            value_too_long.push((name.clone(), *sql_type));
-                        }
-                    }
-                    break;
-                }
-            }
-            if let Some(pair) = found {
-                index_value_pairs.push(pair);
-            } else if out_of_range.is_empty() && not_an_int.is_empty() && value_too_long.is_empty() {
-                non_existing_columns.push(column_name.clone());
-            }
-        }
-
-        if !out_of_range.is_empty() {
-            errors
-                .entry(ConstraintError::OutOfRange)
-                .or_insert_with(Vec::new)
-                .push(out_of_range);
-        }
-        if !not_an_int.is_empty() {
-            errors
-                .entry(ConstraintError::NotAnInt)
-                .or_insert_with(Vec::new)
-                .push(not_an_int);
-        }
-        if !value_too_long.is_empty() {
-            errors
-                .entry(ConstraintError::ValueTooLong)
-                .or_insert_with(Vec::new)
-                .push(value_too_long);
-        }
-
         match self.persistent.read(schema_name, table_name)? {
             Ok(reads) => {
-                if !non_existing_columns.is_empty() {
-                    return Ok(Err(OperationOnTableError::ColumnDoesNotExist(non_existing_columns)));
-                }
-                if !errors.is_empty() {
-                    return Ok(Err(OperationOnTableError::ConstraintViolation(errors)));
-                }
+                let index_value_pairs = match validate(&all_columns, rows) {
+                    Ok(index_value_pairs) => index_value_pairs,
+                    Err(error) => return Ok(Err(error)),
+                };
+                let mut changes = vec![];
                 let to_update: Vec<Row> = reads
                     .map(backend::Result::unwrap)
-                    .map(|(key, values)| {
-                        let mut values: Vec<&[u8]> = values.split(|b| *b == b'|').collect();
+                    .map(|(key, old_values)| {
+                        let mut values = decode(&old_values);
                         for (index, updated_value) in &index_value_pairs {
-                            values[*index] = updated_value;
+                            values[*index] = updated_value.clone();
                         }
+                        changes.push((key.clone(), Some(old_values)));
 
-                        (key, values.join(&b'|'))
+                        (key, encode(&values))
                     })
                     .collect();
-
-                let len = to_update.len();
-                match self.persistent.write(schema_name, table_name, to_update)? {
-                    Ok(_size) => Ok(Ok(len)),
-                    _ => unreachable!(
-                        "all errors that make code fall in here should have been handled in read operation"
-                    ),
-                }
+                self.write_updated(schema_name, table_name, to_update, changes, session_id)
             }
             Err(OperationOnObjectError::ObjectDoesNotExist) => Ok(Err(OperationOnTableError::TableDoesNotExist)),
             Err(OperationOnObjectError::NamespaceDoesNotExist) => Ok(Err(OperationOnTableError::SchemaDoesNotExist)),
         }
     }
 
+    /// Updates every record with values that are computed from its columns,
+    /// e.g. `SET id = id + 1`. Values are validated as in `update_all`, nothing
+    /// is updated if values of any record can't be computed or are invalid
+    pub fn update_all_with<E>(
+        &self,
+        schema_name: &str,
+        table_name: &str,
+        mut rows: impl FnMut(&[(String, Datum)]) -> Result<Vec<(String, Datum)>, E>,
+        session_id: SessionId,
+    ) -> SystemResult<Result<Result<usize, OperationOnTableError>, E>> {
+        let all_columns = self.table_columns(schema_name, table_name)?;
+        match self.persistent.read(schema_name, table_name)? {
+            Ok(reads) => {
+                let mut changes = vec![];
+                let mut to_update: Vec<Row> = vec![];
+                for (key, old_values) in reads.map(backend::Result::unwrap) {
+                    let mut values = decode(&old_values);
+                    let record = all_columns
+                        .iter()
+                        .map(|(name, _sql_type)| name.clone())
+                        .zip(values.iter().cloned())
+                        .collect::<Vec<(String, Datum)>>();
+                    let updated = match rows(&record) {
+                        Ok(updated) => updated,
+                        Err(error) => return Ok(Err(error)),
+                    };
+                    match validate(&all_columns, updated) {
+                        Ok(index_value_pairs) => {
+                            for (index, updated_value) in index_value_pairs {
+                                values[index] = updated_value;
+                            }
+                        }
+                        Err(error) => return Ok(Ok(Err(error))),
+                    }
+                    changes.push((key.clone(), Some(old_values)));
+                    to_update.push((key, encode(&values)));
+                }
+                Ok(Ok(self.write_updated(
+                    schema_name,
+                    table_name,
+                    to_update,
+                    changes,
+                    session_id,
+                )?))
+            }
+            Err(OperationOnObjectError::ObjectDoesNotExist) => Ok(Ok(Err(OperationOnTableError::TableDoesNotExist))),
+            Err(OperationOnObjectError::NamespaceDoesNotExist) => {
+                Ok(Ok(Err(OperationOnTableError::SchemaDoesNotExist)))
+            }
+        }
+    }
+
+    fn write_updated(
+        &self,
+        schema_name: &str,
+        table_name: &str,
+        to_update: Vec<Row>,
+        changes: Vec<(Key, Option<Values>)>,
+        session_id: SessionId,
+    ) -> SystemResult<Result<usize, OperationOnTableError>> {
+        self.log_changes(session_id, schema_name, table_name, changes.into_iter());
+
+        let len = to_update.len();
+        match self.persistent.write(schema_name, table_name, to_update)? {
+            Ok(_size) => Ok(Ok(len)),
+            _ => unreachable!("all errors that make code fall in here should have been handled in read operation"),
+        }
+    }
+
     pub fn delete_all_from(
-        &mut self,
+        &self,
         schema_name: &str,
         table_name: &str,
+        session_id: SessionId,
     ) -> SystemResult<Result<usize, OperationOnTableError>> {
         match self.persistent.read(schema_name, table_name)? {
             Ok(reads) => {
-                let keys = reads.map(backend::Result::unwrap).map(|(key, _)| key).collect();
+                let mut changes = vec![];
+                let keys = reads
+                    .map(backend::Result::unwrap)
+                    .map(|(key, values)| {
+                        changes.push((key.clone(), Some(values)));
+                        key
+                    })
+                    .collect();
+                self.log_changes(session_id, schema_name, table_name, changes.into_iter());
                 match self.persistent.delete(schema_name, table_name, keys)? {
                     Ok(len) => Ok(Ok(len)),
                     _ => unreachable!(
@@ -419,10 +1076,202 @@ impl<P: BackendStorage> FrontendStorage<P> {
     }
 }
 
+// key of the record in `system.keys` with the end of reserved keys of records
+const RECORD_KEYS: &[u8] = b"records";
+
+// keys are reserved in batches, so that the reservation is not written on
+// every insert
+const KEYS_RESERVED_AT_ONCE: usize = 1024;
+
+// PostgreSQL assigns OIDs starting from this one to user defined objects
+const FIRST_TYPE_OID: u32 = 16384;
+
+fn object_key(schema_name: &str, object_name: &str) -> String {
+    format!("{}.{}", schema_name, object_name)
+}
+
+fn columns_key(schema_name: &str, table_name: &str) -> Key {
+    (schema_name.to_owned() + table_name).into_bytes()
+}
+
+// error of system table can't be handled by a client
+fn system_table<T>(result: Result<T, OperationOnObjectError>, table_name: &str) -> SystemResult<T> {
+    result.map_err(|error| {
+        let message = format!("Can't access \"system.{}\" table because of {:?}", table_name, error);
+        log::error!("{}", message);
+        SystemError::unrecoverable(message)
+    })
+}
+
+fn uses_type(column_type: &SqlType, sql_type: &SqlType) -> bool {
+    match column_type {
+        SqlType::Array(element) => uses_type(element, sql_type),
+        column_type => column_type == sql_type,
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Sequence {
+    options: SequenceOptions,
+    last_value: i64,
+    // `nextval` returns `last_value` itself if it was not called yet
+    is_called: bool,
+    // qualified name of the table
+    owned_by: Option<String>,
+}
+
 #[derive(Serialize, Deserialize)]
 struct ColumnMetadata {
     name: String,
     sql_type: SqlType,
+    default: Option<String>,
+    generated_always: bool,
+}
+
+// records of a table that are visible to the session: records changed by
+// other sessions are read from their undo logs. Records are read in key order,
+// thus records that other sessions deleted are put between the read ones
+struct CommittedRecords {
+    records: ReadCursor,
+    table: (String, String),
+    session_id: SessionId,
+    undo_logs: Arc<Mutex<HashMap<SessionId, UndoLog>>>,
+    last_key: Option<Key>,
+    ready: VecDeque<Values>,
+    done: bool,
+}
+
+impl CommittedRecords {
+    fn new(
+        records: ReadCursor,
+        table: (String, String),
+        session_id: SessionId,
+        undo_logs: Arc<Mutex<HashMap<SessionId, UndoLog>>>,
+    ) -> Self {
+        Self {
+            records,
+            table,
+            session_id,
+            undo_logs,
+            last_key: None,
+            ready: VecDeque::new(),
+            done: false,
+        }
+    }
+}
+
+impl Iterator for CommittedRecords {
+    type Item = Values;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(values) = self.ready.pop_front() {
+                return Some(values);
+            }
+            if self.done {
+                return None;
+            }
+            let record = self.records.next().map(backend::Result::unwrap);
+            let undo_logs = self.undo_logs.lock().unwrap();
+            let changes = undo_logs
+                .iter()
+                .filter(|(session_id, _undo_log)| **session_id != self.session_id)
+                .filter_map(|(_session_id, undo_log)| undo_log.records.get(&self.table))
+                .collect::<Vec<&BTreeMap<Key, Option<Values>>>>();
+            let from = match self.last_key.take() {
+                Some(key) => Bound::Excluded(key),
+                None => Bound::Unbounded,
+            };
+            let to = match &record {
+                Some((key, _values)) => Bound::Excluded(key.clone()),
+                None => Bound::Unbounded,
+            };
+            let mut deleted = changes
+                .iter()
+                .flat_map(|table_log| table_log.range((from.clone(), to.clone())))
+                .filter_map(|(key, values)| values.clone().map(|values| (key.clone(), values)))
+                .collect::<Vec<Row>>();
+            deleted.sort();
+            self.ready.extend(deleted.into_iter().map(|(_key, values)| values));
+            match record {
+                Some((key, values)) => {
+                    match changes.iter().find_map(|table_log| table_log.get(&key)) {
+                        Some(Some(old_values)) => self.ready.push_back(old_values.clone()),
+                        // inserted by other session
+                        Some(None) => {}
+                        None => self.ready.push_back(values),
+                    }
+                    self.last_key = Some(key);
+                }
+                None => self.done = true,
+            }
+        }
+    }
+}
+
+// values of updated columns converted into types of the columns with their indexes
+fn validate(
+    all_columns: &[(String, SqlType)],
+    rows: Vec<(String, Datum)>,
+) -> Result<Vec<(usize, Datum)>, OperationOnTableError> {
+    let mut errors = HashMap::new();
+    let mut violations = HashMap::new();
+    let mut index_value_pairs = vec![];
+    let mut non_existing_columns = vec![];
+    for (column_name, value) in rows {
+        let mut found = None;
+        for (index, (name, sql_type)) in all_columns.iter().enumerate() {
+            if *name == column_name {
+                match typed(value, sql_type) {
+                    Ok(value) => {
+                        found = Some((index, value));
+                    }
+                    Err(error) => violations
+                        .entry(error)
+                        .or_insert_with(Vec::new)
+                        .push((name.clone(), sql_type.clone())),
+                }
+                break;
+            }
+        }
+        if let Some(pair) = found {
+            index_value_pairs.push(pair);
+        } else if violations.is_empty() {
+            non_existing_columns.push(column_name.clone());
+        }
+    }
+
+    for (error, columns) in violations {
+        errors.entry(error).or_insert_with(Vec::new).push(columns);
+    }
+
+    if !non_existing_columns.is_empty() {
+        return Err(OperationOnTableError::ColumnDoesNotExist(non_existing_columns));
+    }
+    if !errors.is_empty() {
+        return Err(OperationOnTableError::ConstraintViolation(errors));
+    }
+    Ok(index_value_pairs)
+}
+
+// string values are of unknown type and converted by input function of the
+// column type, values of other types are already assigned to the column, NULL
+// is kept as it is
+fn typed(value: Datum, sql_type: &SqlType) -> Result<Datum, ConstraintError> {
+    match value {
+        Datum::String(literal) => sql_type.constraint().validate(&literal),
+        value => Ok(value),
+    }
+}
+
+// every value carries its own length, so arbitrary bytes in a value can't
+// break a record apart
+fn encode(record: &[Datum]) -> Vec<u8> {
+    bincode::serialize(record).unwrap()
+}
+
+fn decode(bytes: &[u8]) -> Vec<Datum> {
+    bincode::deserialize(bytes).unwrap()
 }
 
 #[cfg(test)]
@@ -19,10 +19,18 @@ mod queries;
 #[cfg(test)]
 mod schema;
 #[cfg(test)]
+mod sequences;
+#[cfg(test)]
 mod table;
+#[cfg(test)]
+mod types;
+#[cfg(test)]
+mod undo;
 
 type PersistentStorage = FrontendStorage<SledBackendStorage>;
 
+const SESSION: SessionId = 0;
+
 #[rstest::fixture]
 fn storage() -> PersistentStorage {
     FrontendStorage::default().expect("no system errors")
@@ -30,7 +38,7 @@ fn storage() -> PersistentStorage {
 
 fn create_schema<P: backend::BackendStorage>(storage: &mut FrontendStorage<P>, schema_name: &str) {
     storage
-        .create_schema(schema_name)
+        .create_schema(schema_name, SESSION)
         .expect("no system errors")
         .expect("schema is created");
 }
@@ -49,6 +57,7 @@ fn create_table<P: backend::BackendStorage>(
                 .into_iter()
                 .map(|(name, sql_type)| (name.to_owned(), sql_type))
                 .collect::<Vec<(String, SqlType)>>(),
+            SESSION,
         )
         .expect("no system errors")
         .expect("table is created");
@@ -76,7 +85,11 @@ fn insert_into<P: backend::BackendStorage>(
             schema_name,
             table_name,
             columns.into_iter().map(ToOwned::to_owned).collect(),
-            vec![values.into_iter().map(ToOwned::to_owned).collect()],
+            vec![values
+                .into_iter()
+                .map(|value| Datum::String(value.to_owned()))
+                .collect()],
+            SESSION,
         )
         .expect("no system errors")
         .expect("values are inserted");
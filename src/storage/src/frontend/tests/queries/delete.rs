@@ -13,13 +13,13 @@
 // limitations under the License.
 
 use super::*;
-use sql_types::SqlType;
+use sql_types::{Datum, SqlType};
 
 #[rstest::rstest]
-fn delete_all_from_non_existent_schema(mut storage: PersistentStorage) {
+fn delete_all_from_non_existent_schema(storage: PersistentStorage) {
     assert_eq!(
         storage
-            .delete_all_from("non_existent", "table_name")
+            .delete_all_from("non_existent", "table_name", SESSION)
             .expect("no system errors"),
         Err(OperationOnTableError::SchemaDoesNotExist)
     );
@@ -31,7 +31,7 @@ fn delete_all_from_not_existed_table(mut storage: PersistentStorage) {
 
     assert_eq!(
         storage
-            .delete_all_from("schema_name", "table_name")
+            .delete_all_from("schema_name", "table_name", SESSION)
             .expect("no system errors"),
         Err(OperationOnTableError::TableDoesNotExist)
     );
@@ -51,7 +51,7 @@ fn delete_all_from_table(mut storage: PersistentStorage) {
 
     assert_eq!(
         storage
-            .delete_all_from("schema_name", "table_name")
+            .delete_all_from("schema_name", "table_name", SESSION)
             .expect("no system errors"),
         Ok(3)
     );
@@ -65,8 +65,9 @@ fn delete_all_from_table(mut storage: PersistentStorage) {
 
     assert_eq!(
         storage
-            .select_all_from("schema_name", "table_name", table_columns)
-            .expect("no system errors"),
+            .select_all_from("schema_name", "table_name", table_columns, SESSION)
+            .expect("no system errors")
+            .map(|(description, records)| (description, records.collect::<Vec<Vec<Datum>>>())),
         Ok((vec![("column_test".to_owned(), SqlType::SmallInt)], vec![]))
     );
 }
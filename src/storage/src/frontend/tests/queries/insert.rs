@@ -13,13 +13,19 @@
 // limitations under the License.
 
 use super::*;
-use sql_types::SqlType;
+use sql_types::{ConstraintError, Datum, SqlType};
 
 #[rstest::rstest]
-fn insert_into_non_existent_schema(mut storage: PersistentStorage) {
+fn insert_into_non_existent_schema(storage: PersistentStorage) {
     assert_eq!(
         storage
-            .insert_into("non_existent", "not_existed", vec![], vec![vec!["123".to_owned()]])
+            .insert_into(
+                "non_existent",
+                "not_existed",
+                vec![],
+                vec![vec![Datum::String("123".to_owned())]],
+                SESSION
+            )
             .expect("no system errors"),
         Err(OperationOnTableError::SchemaDoesNotExist)
     );
@@ -31,7 +37,13 @@ fn insert_into_non_existent_table(mut storage: PersistentStorage) {
 
     assert_eq!(
         storage
-            .insert_into("schema_name", "not_existed", vec![], vec![vec!["123".to_owned()]])
+            .insert_into(
+                "schema_name",
+                "not_existed",
+                vec![],
+                vec![vec![Datum::String("123".to_owned())]],
+                SESSION
+            )
             .expect("no system errors"),
         Err(OperationOnTableError::TableDoesNotExist)
     );
@@ -58,11 +70,12 @@ fn insert_many_rows_into_table(mut storage: PersistentStorage) {
 
     assert_eq!(
         storage
-            .select_all_from("schema_name", "table_name", table_columns)
-            .expect("no system errors"),
+            .select_all_from("schema_name", "table_name", table_columns, SESSION)
+            .expect("no system errors")
+            .map(|(description, records)| (description, records.collect::<Vec<Vec<Datum>>>())),
         Ok((
             vec![("column_test".to_owned(), SqlType::SmallInt)],
-            vec![vec!["123".to_owned()], vec!["456".to_owned()]]
+            vec![vec![Datum::Int16(123)], vec![Datum::Int16(456)]]
         ))
     );
 }
@@ -93,8 +106,9 @@ fn insert_multiple_values_rows(mut storage: PersistentStorage) {
 
     assert_eq!(
         storage
-            .select_all_from("schema_name", "table_name", table_columns)
-            .expect("no system errors"),
+            .select_all_from("schema_name", "table_name", table_columns, SESSION)
+            .expect("no system errors")
+            .map(|(description, records)| (description, records.collect::<Vec<Vec<Datum>>>())),
         Ok((
             vec![
                 ("column_1".to_owned(), SqlType::SmallInt),
@@ -102,9 +116,9 @@ fn insert_multiple_values_rows(mut storage: PersistentStorage) {
                 ("column_3".to_owned(), SqlType::SmallInt)
             ],
             vec![
-                vec!["1".to_owned(), "2".to_owned(), "3".to_owned()],
-                vec!["4".to_owned(), "5".to_owned(), "6".to_owned()],
-                vec!["7".to_owned(), "8".to_owned(), "9".to_owned()],
+                vec![Datum::Int16(1), Datum::Int16(2), Datum::Int16(3)],
+                vec![Datum::Int16(4), Datum::Int16(5), Datum::Int16(6)],
+                vec![Datum::Int16(7), Datum::Int16(8), Datum::Int16(9)],
             ],
         ))
     );
@@ -156,8 +170,9 @@ fn insert_named_columns(mut storage: PersistentStorage) {
 
     assert_eq!(
         storage
-            .select_all_from("schema_name", "table_name", table_columns)
-            .expect("no system errors"),
+            .select_all_from("schema_name", "table_name", table_columns, SESSION)
+            .expect("no system errors")
+            .map(|(description, records)| (description, records.collect::<Vec<Vec<Datum>>>())),
         Ok((
             vec![
                 ("column_1".to_owned(), SqlType::SmallInt),
@@ -165,9 +180,9 @@ fn insert_named_columns(mut storage: PersistentStorage) {
                 ("column_3".to_owned(), SqlType::BigInt)
             ],
             vec![
-                vec!["3".to_owned(), "2".to_owned(), "1".to_owned()],
-                vec!["6".to_owned(), "5".to_owned(), "4".to_owned()],
-                vec!["9".to_owned(), "8".to_owned(), "7".to_owned()],
+                vec![Datum::Int16(3), Datum::String("2".to_owned()), Datum::Int64(1)],
+                vec![Datum::Int16(6), Datum::String("5".to_owned()), Datum::Int64(4)],
+                vec![Datum::Int16(9), Datum::String("8".to_owned()), Datum::Int64(7)],
             ],
         ))
     );
@@ -199,7 +214,13 @@ fn insert_named_not_existed_column(mut storage: PersistentStorage) {
                 "schema_name",
                 "table_name",
                 columns,
-                vec![vec!["1".to_owned(), "2".to_owned(), "3".to_owned(), "4".to_owned()]],
+                vec![vec![
+                    Datum::String("1".to_owned()),
+                    Datum::String("2".to_owned()),
+                    Datum::String("3".to_owned()),
+                    Datum::String("4".to_owned())
+                ]],
+                SESSION,
             )
             .expect("no system errors"),
         Err(OperationOnTableError::ColumnDoesNotExist(
@@ -218,7 +239,13 @@ fn insert_row_into_table(mut storage: PersistentStorage) {
     );
     assert_eq!(
         storage
-            .insert_into("schema_name", "table_name", vec![], vec![vec!["123".to_owned()]])
+            .insert_into(
+                "schema_name",
+                "table_name",
+                vec![],
+                vec![vec![Datum::String("123".to_owned())]],
+                SESSION
+            )
             .expect("no system errors"),
         Ok(())
     );
@@ -232,11 +259,138 @@ fn insert_row_into_table(mut storage: PersistentStorage) {
 
     assert_eq!(
         storage
-            .select_all_from("schema_name", "table_name", table_columns)
-            .expect("no system errors"),
+            .select_all_from("schema_name", "table_name", table_columns, SESSION)
+            .expect("no system errors")
+            .map(|(description, records)| (description, records.collect::<Vec<Vec<Datum>>>())),
         Ok((
             vec![("column_test".to_owned(), SqlType::SmallInt)],
-            vec![vec!["123".to_owned()]]
+            vec![vec![Datum::Int16(123)]]
+        ))
+    );
+}
+
+#[rstest::rstest]
+fn records_are_not_overwritten_after_restart(mut storage: PersistentStorage) {
+    create_schema_with_table(
+        &mut storage,
+        "schema_name",
+        "table_name",
+        vec![("column_test", SqlType::SmallInt)],
+    );
+    insert_into(&mut storage, "schema_name", "table_name", vec![], vec!["123"]);
+
+    let mut storage = FrontendStorage::new(storage.persistent).expect("no system errors");
+    insert_into(&mut storage, "schema_name", "table_name", vec![], vec!["456"]);
+
+    assert_eq!(
+        storage
+            .select_all_from("schema_name", "table_name", vec!["column_test".to_owned()], SESSION)
+            .expect("no system errors")
+            .map(|(_description, records)| records.collect::<Vec<Vec<Datum>>>()),
+        Ok(vec![vec![Datum::Int16(123)], vec![Datum::Int16(456)]])
+    );
+}
+
+#[rstest::rstest]
+fn insert_typed_and_null_values(mut storage: PersistentStorage) {
+    create_schema_with_table(
+        &mut storage,
+        "schema_name",
+        "table_name",
+        vec![("column_1", SqlType::SmallInt), ("column_2", SqlType::Text)],
+    );
+    assert_eq!(
+        storage
+            .insert_into(
+                "schema_name",
+                "table_name",
+                vec![],
+                vec![
+                    vec![Datum::Int16(1), Datum::Null],
+                    vec![Datum::Null, Datum::String("abc".to_owned())]
+                ],
+                SESSION
+            )
+            .expect("no system errors"),
+        Ok(())
+    );
+
+    assert_eq!(
+        storage
+            .select_all_from(
+                "schema_name",
+                "table_name",
+                vec!["column_1".to_owned(), "column_2".to_owned()],
+                SESSION
+            )
+            .expect("no system errors")
+            .map(|(_description, records)| records.collect::<Vec<Vec<Datum>>>()),
+        Ok(vec![
+            vec![Datum::Int16(1), Datum::Null],
+            vec![Datum::Null, Datum::String("abc".to_owned())]
+        ])
+    );
+}
+
+#[rstest::rstest]
+fn insert_value_with_separator_like_bytes(mut storage: PersistentStorage) {
+    create_schema_with_table(
+        &mut storage,
+        "schema_name",
+        "table_name",
+        vec![
+            ("column_1", SqlType::VarChar(Some(10))),
+            ("column_2", SqlType::SmallInt),
+        ],
+    );
+    insert_into(&mut storage, "schema_name", "table_name", vec![], vec!["a|b", "124"]);
+
+    assert_eq!(
+        storage
+            .select_all_from(
+                "schema_name",
+                "table_name",
+                vec!["column_1".to_owned(), "column_2".to_owned()],
+                SESSION
+            )
+            .expect("no system errors")
+            .map(|(description, records)| (description, records.collect::<Vec<Vec<Datum>>>())),
+        Ok((
+            vec![
+                ("column_1".to_owned(), SqlType::VarChar(Some(10))),
+                ("column_2".to_owned(), SqlType::SmallInt)
+            ],
+            vec![vec![Datum::String("a|b".to_owned()), Datum::Int16(124)]]
+        ))
+    );
+}
+
+#[rstest::rstest]
+fn columns_without_values_are_null(mut storage: PersistentStorage) {
+    create_schema_with_table(
+        &mut storage,
+        "schema_name",
+        "table_name",
+        vec![("column_1", SqlType::SmallInt), ("column_2", SqlType::Integer)],
+    );
+    insert_into(&mut storage, "schema_name", "table_name", vec!["column_2"], vec!["1"]);
+
+    assert_eq!(
+        storage
+            .select_all_from(
+                "schema_name",
+                "table_name",
+                vec!["column_1".to_owned(), "column_2".to_owned()],
+                SESSION
+            )
+            .expect("no system errors")
+            .map(|(description, records)| (description, records.collect::<Vec<Vec<Datum>>>())),
+        Ok((
+            vec![
+                ("column_1".to_owned(), SqlType::SmallInt),
+                ("column_2".to_owned(), SqlType::Integer)
+            ],
+            vec![vec![Datum::Null, Datum::Int32(1)]]
         ))
     );
 }
@@ -262,7 +416,13 @@ fn insert_too_many_expressions(mut storage: PersistentStorage) {
                 "schema_name",
                 "table_name",
                 columns,
-                vec![vec!["1".to_owned(), "2".to_owned(), "3".to_owned(), "4".to_owned()]],
+                vec![vec![
+                    Datum::String("1".to_owned()),
+                    Datum::String("2".to_owned()),
+                    Datum::String("3".to_owned()),
+                    Datum::String("4".to_owned())
+                ]],
+                SESSION,
             )
             .expect("no system errors"),
         Err(OperationOnTableError::InsertTooManyExpressions)
@@ -277,8 +437,9 @@ fn insert_too_many_expressions(mut storage: PersistentStorage) {
 
     assert_eq!(
         storage
-            .select_all_from("schema_name", "table_name", table_columns)
-            .expect("no system errors"),
+            .select_all_from("schema_name", "table_name", table_columns, SESSION)
+            .expect("no system errors")
+            .map(|(description, records)| (description, records.collect::<Vec<Vec<Datum>>>())),
         Ok((
             vec![
                 ("column_1".to_owned(), SqlType::SmallInt),
@@ -311,7 +472,13 @@ fn insert_too_many_expressions_labeled(mut storage: PersistentStorage) {
                 "schema_name",
                 "table_name",
                 columns,
-                vec![vec!["1".to_owned(), "2".to_owned(), "3".to_owned(), "4".to_owned()]],
+                vec![vec![
+                    Datum::String("1".to_owned()),
+                    Datum::String("2".to_owned()),
+                    Datum::String("3".to_owned()),
+                    Datum::String("4".to_owned())
+                ]],
+                SESSION,
             )
             .expect("no system errors"),
         Err(OperationOnTableError::InsertTooManyExpressions)
@@ -326,8 +493,9 @@ fn insert_too_many_expressions_labeled(mut storage: PersistentStorage) {
 
     assert_eq!(
         storage
-            .select_all_from("schema_name", "table_name", table_columns)
-            .expect("no system errors"),
+            .select_all_from("schema_name", "table_name", table_columns, SESSION)
+            .expect("no system errors")
+            .map(|(description, records)| (description, records.collect::<Vec<Vec<Datum>>>())),
         Ok((
             vec![
                 ("column_1".to_owned(), SqlType::SmallInt),
@@ -364,20 +532,28 @@ mod constraints {
             &mut storage,
             "schema_name",
             "table_name",
-            vec![("column_c", SqlType::Char(10)), ("column_vc", SqlType::VarChar(10))],
+            vec![
+                ("column_c", SqlType::Char(10)),
+                ("column_vc", SqlType::VarChar(Some(10))),
+            ],
         );
         storage
     }
 
     #[rstest::rstest]
-    fn out_of_range_violation(mut storage_with_ints_table: PersistentStorage) {
+    fn out_of_range_violation(storage_with_ints_table: PersistentStorage) {
         assert_eq!(
             storage_with_ints_table
                 .insert_into(
                     "schema_name",
                     "table_name",
                     vec![],
-                    vec![vec!["-32769".to_owned(), "100".to_owned(), "100".to_owned()]],
+                    vec![vec![
+                        Datum::String("-32769".to_owned()),
+                        Datum::String("100".to_owned()),
+                        Datum::String("100".to_owned())
+                    ]],
+                    SESSION,
                 )
                 .expect("no system errors"),
             Err(constraint_violations(
@@ -388,14 +564,19 @@ mod constraints {
     }
 
     #[rstest::rstest]
-    fn not_an_int_violation(mut storage_with_ints_table: PersistentStorage) {
+    fn not_an_int_violation(storage_with_ints_table: PersistentStorage) {
         assert_eq!(
             storage_with_ints_table
                 .insert_into(
                     "schema_name",
                     "table_name",
                     vec![],
-                    vec![vec!["abc".to_owned(), "100".to_owned(), "100".to_owned()]],
+                    vec![vec![
+                        Datum::String("abc".to_owned()),
+                        Datum::String("100".to_owned()),
+                        Datum::String("100".to_owned())
+                    ]],
+                    SESSION,
                 )
                 .expect("no system errors"),
             Err(constraint_violations(
@@ -406,14 +587,18 @@ mod constraints {
     }
 
     #[rstest::rstest]
-    fn value_too_long_violation(mut storage_with_chars_table: PersistentStorage) {
+    fn value_too_long_violation(storage_with_chars_table: PersistentStorage) {
         assert_eq!(
             storage_with_chars_table
                 .insert_into(
                     "schema_name",
                     "table_name",
                     vec![],
-                    vec![vec!["12345678901".to_owned(), "100".to_owned()]],
+                    vec![vec![
+                        Datum::String("12345678901".to_owned()),
+                        Datum::String("100".to_owned())
+                    ]],
+                    SESSION,
                 )
                 .expect("no system errors"),
             Err(constraint_violations(
@@ -424,14 +609,19 @@ mod constraints {
     }
 
     #[rstest::rstest]
-    fn multiple_columns_single_row_violation(mut storage_with_ints_table: PersistentStorage) {
+    fn multiple_columns_single_row_violation(storage_with_ints_table: PersistentStorage) {
         assert_eq!(
             storage_with_ints_table
                 .insert_into(
                     "schema_name",
                     "table_name",
                     vec![],
-                    vec![vec!["-32769".to_owned(), "-2147483649".to_owned(), "100".to_owned()]],
+                    vec![vec![
+                        Datum::String("-32769".to_owned()),
+                        Datum::String("-2147483649".to_owned()),
+                        Datum::String("100".to_owned())
+                    ]],
+                    SESSION,
                 )
                 .expect("no system errors"),
             Err(constraint_violations(
@@ -445,7 +635,7 @@ mod constraints {
     }
 
     #[rstest::rstest]
-    fn multiple_columns_multiple_row_violation(mut storage_with_ints_table: PersistentStorage) {
+    fn multiple_columns_multiple_row_violation(storage_with_ints_table: PersistentStorage) {
         assert_eq!(
             storage_with_ints_table
                 .insert_into(
@@ -453,13 +643,18 @@ mod constraints {
                     "table_name",
                     vec![],
                     vec![
-                        vec!["-32769".to_owned(), "-2147483649".to_owned(), "100".to_owned()],
                         vec![
-                            "100".to_owned(),
-                            "-2147483649".to_owned(),
-                            "-9223372036854775809".to_owned()
+                            Datum::String("-32769".to_owned()),
+                            Datum::String("-2147483649".to_owned()),
+                            Datum::String("100".to_owned())
+                        ],
+                        vec![
+                            Datum::String("100".to_owned()),
+                            Datum::String("-2147483649".to_owned()),
+                            Datum::String("-9223372036854775809".to_owned())
                         ],
                     ],
+                    SESSION,
                 )
                 .expect("no system errors"),
             Err(constraint_violations(
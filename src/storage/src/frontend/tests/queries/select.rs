@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use super::*;
-use sql_types::SqlType;
+use sql_types::{Datum, SqlType};
 
 #[rstest::fixture]
 fn with_small_ints_table(mut storage: PersistentStorage) -> PersistentStorage {
@@ -31,11 +31,12 @@ fn with_small_ints_table(mut storage: PersistentStorage) -> PersistentStorage {
 }
 
 #[rstest::rstest]
-fn select_from_table_from_non_existent_schema(mut storage: PersistentStorage) {
+fn select_from_table_from_non_existent_schema(storage: PersistentStorage) {
     assert_eq!(
         storage
-            .select_all_from("non_existent", "table_name", vec![])
-            .expect("no system errors"),
+            .select_all_from("non_existent", "table_name", vec![], SESSION)
+            .expect("no system errors")
+            .map(|(description, records)| (description, records.collect::<Vec<Vec<Datum>>>())),
         Err(OperationOnTableError::SchemaDoesNotExist)
     );
 }
@@ -52,8 +53,9 @@ fn select_from_table_that_does_not_exist(mut storage: PersistentStorage) {
 
     assert_eq!(
         storage
-            .select_all_from("schema_name", "not_existed", table_columns)
-            .expect("no system errors"),
+            .select_all_from("schema_name", "not_existed", table_columns, SESSION)
+            .expect("no system errors")
+            .map(|(description, records)| (description, records.collect::<Vec<Vec<Datum>>>())),
         Err(OperationOnTableError::TableDoesNotExist)
     );
 }
@@ -77,15 +79,16 @@ fn select_all_from_table_with_many_columns(mut with_small_ints_table: Persistent
 
     assert_eq!(
         with_small_ints_table
-            .select_all_from("schema_name", "table_name", table_columns)
-            .expect("no system errors"),
+            .select_all_from("schema_name", "table_name", table_columns, SESSION)
+            .expect("no system errors")
+            .map(|(description, records)| (description, records.collect::<Vec<Vec<Datum>>>())),
         Ok((
             vec![
                 ("column_1".to_owned(), SqlType::SmallInt),
                 ("column_2".to_owned(), SqlType::SmallInt),
                 ("column_3".to_owned(), SqlType::SmallInt)
             ],
-            vec![vec!["1".to_owned(), "2".to_owned(), "3".to_owned()]]
+            vec![vec![Datum::Int16(1), Datum::Int16(2), Datum::Int16(3)]]
         ))
     );
 }
@@ -119,18 +122,20 @@ fn select_first_and_last_columns_from_table_with_multiple_columns(mut with_small
             .select_all_from(
                 "schema_name",
                 "table_name",
-                vec!["column_1".to_owned(), "column_3".to_owned()]
+                vec!["column_1".to_owned(), "column_3".to_owned()],
+                SESSION
             )
-            .expect("no system errors"),
+            .expect("no system errors")
+            .map(|(description, records)| (description, records.collect::<Vec<Vec<Datum>>>())),
         Ok((
             vec![
                 ("column_1".to_owned(), SqlType::SmallInt),
                 ("column_3".to_owned(), SqlType::SmallInt)
             ],
             vec![
-                vec!["1".to_owned(), "3".to_owned()],
-                vec!["4".to_owned(), "6".to_owned()],
-                vec!["7".to_owned(), "9".to_owned()],
+                vec![Datum::Int16(1), Datum::Int16(3)],
+                vec![Datum::Int16(4), Datum::Int16(6)],
+                vec![Datum::Int16(7), Datum::Int16(9)],
             ],
         ))
     );
@@ -165,9 +170,11 @@ fn select_all_columns_reordered_from_table_with_multiple_columns(mut with_small_
             .select_all_from(
                 "schema_name",
                 "table_name",
-                vec!["column_3".to_owned(), "column_1".to_owned(), "column_2".to_owned()]
+                vec!["column_3".to_owned(), "column_1".to_owned(), "column_2".to_owned()],
+                SESSION
             )
-            .expect("no system errors"),
+            .expect("no system errors")
+            .map(|(description, records)| (description, records.collect::<Vec<Vec<Datum>>>())),
         Ok((
             vec![
                 ("column_3".to_owned(), SqlType::SmallInt),
@@ -175,9 +182,9 @@ fn select_all_columns_reordered_from_table_with_multiple_columns(mut with_small_
                 ("column_2".to_owned(), SqlType::SmallInt)
             ],
             vec![
-                vec!["3".to_owned(), "1".to_owned(), "2".to_owned()],
-                vec!["6".to_owned(), "4".to_owned(), "5".to_owned()],
-                vec!["9".to_owned(), "7".to_owned(), "8".to_owned()],
+                vec![Datum::Int16(3), Datum::Int16(1), Datum::Int16(2)],
+                vec![Datum::Int16(6), Datum::Int16(4), Datum::Int16(5)],
+                vec![Datum::Int16(9), Datum::Int16(7), Datum::Int16(8)],
             ],
         ))
     );
@@ -218,9 +225,11 @@ fn select_with_column_name_duplication(mut with_small_ints_table: PersistentStor
                     "column_1".to_owned(),
                     "column_3".to_owned(),
                     "column_2".to_owned()
-                ]
+                ],
+                SESSION
             )
-            .expect("no system errors"),
+            .expect("no system errors")
+            .map(|(description, records)| (description, records.collect::<Vec<Vec<Datum>>>())),
         Ok((
             vec![
                 ("column_3".to_owned(), SqlType::SmallInt),
@@ -231,25 +240,25 @@ fn select_with_column_name_duplication(mut with_small_ints_table: PersistentStor
             ],
             vec![
                 vec![
-                    "3".to_owned(),
-                    "2".to_owned(),
-                    "1".to_owned(),
-                    "3".to_owned(),
-                    "2".to_owned()
+                    Datum::Int16(3),
+                    Datum::Int16(2),
+                    Datum::Int16(1),
+                    Datum::Int16(3),
+                    Datum::Int16(2)
                 ],
                 vec![
-                    "6".to_owned(),
-                    "5".to_owned(),
-                    "4".to_owned(),
-                    "6".to_owned(),
-                    "5".to_owned()
+                    Datum::Int16(6),
+                    Datum::Int16(5),
+                    Datum::Int16(4),
+                    Datum::Int16(6),
+                    Datum::Int16(5)
                 ],
                 vec![
-                    "9".to_owned(),
-                    "8".to_owned(),
-                    "7".to_owned(),
-                    "9".to_owned(),
-                    "8".to_owned()
+                    Datum::Int16(9),
+                    Datum::Int16(8),
+                    Datum::Int16(7),
+                    Datum::Int16(9),
+                    Datum::Int16(8)
                 ],
             ],
         ))
@@ -296,9 +305,11 @@ fn select_different_integer_types(mut storage: PersistentStorage) {
             .select_all_from(
                 "schema_name",
                 "table_name",
-                vec!["small_int".to_owned(), "integer".to_owned(), "big_int".to_owned()]
+                vec!["small_int".to_owned(), "integer".to_owned(), "big_int".to_owned()],
+                SESSION
             )
-            .expect("no system errors"),
+            .expect("no system errors")
+            .map(|(description, records)| (description, records.collect::<Vec<Vec<Datum>>>())),
         Ok((
             vec![
                 ("small_int".to_owned(), SqlType::SmallInt),
@@ -306,9 +317,9 @@ fn select_different_integer_types(mut storage: PersistentStorage) {
                 ("big_int".to_owned(), SqlType::BigInt),
             ],
             vec![
-                vec!["1000".to_owned(), "2000000".to_owned(), "3000000000".to_owned()],
-                vec!["4000".to_owned(), "5000000".to_owned(), "6000000000".to_owned()],
-                vec!["7000".to_owned(), "8000000".to_owned(), "9000000000".to_owned()],
+                vec![Datum::Int16(1000), Datum::Int32(2000000), Datum::Int64(3000000000)],
+                vec![Datum::Int16(4000), Datum::Int32(5000000), Datum::Int64(6000000000)],
+                vec![Datum::Int16(7000), Datum::Int32(8000000), Datum::Int64(9000000000)],
             ],
         ))
     );
@@ -320,7 +331,10 @@ fn select_different_character_strings_types(mut storage: PersistentStorage) {
         &mut storage,
         "schema_name",
         "table_name",
-        vec![("char_10", SqlType::Char(10)), ("var_char_20", SqlType::VarChar(20))],
+        vec![
+            ("char_10", SqlType::Char(10)),
+            ("var_char_20", SqlType::VarChar(Some(20))),
+        ],
     );
 
     insert_into(
@@ -350,18 +364,29 @@ fn select_different_character_strings_types(mut storage: PersistentStorage) {
             .select_all_from(
                 "schema_name",
                 "table_name",
-                vec!["char_10".to_owned(), "var_char_20".to_owned()]
+                vec!["char_10".to_owned(), "var_char_20".to_owned()],
+                SESSION
             )
-            .expect("no system errors"),
+            .expect("no system errors")
+            .map(|(description, records)| (description, records.collect::<Vec<Vec<Datum>>>())),
         Ok((
             vec![
                 ("char_10".to_owned(), SqlType::Char(10)),
-                ("var_char_20".to_owned(), SqlType::VarChar(20)),
+                ("var_char_20".to_owned(), SqlType::VarChar(Some(20))),
             ],
             vec![
-                vec!["1234567890".to_owned(), "12345678901234567890".to_owned()],
-                vec!["12345".to_owned(), "1234567890".to_owned()],
-                vec!["12345".to_owned(), "1234567890".to_owned()],
+                vec![
+                    Datum::String("1234567890".to_owned()),
+                    Datum::String("12345678901234567890".to_owned())
+                ],
+                vec![
+                    Datum::String("12345".to_owned()),
+                    Datum::String("1234567890".to_owned())
+                ],
+                vec![
+                    Datum::String("12345".to_owned()),
+                    Datum::String("1234567890".to_owned())
+                ],
             ],
         ))
     );
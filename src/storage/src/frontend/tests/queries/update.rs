@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use super::*;
-use sql_types::SqlType;
+use sql_types::{ConstraintError, Datum, SqlType};
 
 #[rstest::rstest]
 fn update_all_records(mut storage: PersistentStorage) {
@@ -33,7 +33,8 @@ fn update_all_records(mut storage: PersistentStorage) {
             .update_all(
                 "schema_name",
                 "table_name",
-                vec![("column_test".to_owned(), "567".to_owned())]
+                vec![("column_test".to_owned(), Datum::String("567".to_owned()))],
+                SESSION
             )
             .expect("no system errors"),
         Ok(3)
@@ -48,11 +49,94 @@ fn update_all_records(mut storage: PersistentStorage) {
 
     assert_eq!(
         storage
-            .select_all_from("schema_name", "table_name", table_columns)
+            .select_all_from("schema_name", "table_name", table_columns, SESSION)
+            .expect("no system errors")
+            .map(|(description, records)| (description, records.collect::<Vec<Vec<Datum>>>())),
+        Ok((
+            vec![("column_test".to_owned(), SqlType::SmallInt)],
+            vec![
+                vec![Datum::Int16(567)],
+                vec![Datum::Int16(567)],
+                vec![Datum::Int16(567)]
+            ]
+        ))
+    );
+}
+
+#[rstest::rstest]
+fn update_all_records_with_values_of_their_columns(mut storage: PersistentStorage) {
+    create_schema_with_table(
+        &mut storage,
+        "schema_name",
+        "table_name",
+        vec![("column_test", SqlType::SmallInt)],
+    );
+
+    insert_into(&mut storage, "schema_name", "table_name", vec![], vec!["123"]);
+    insert_into(&mut storage, "schema_name", "table_name", vec![], vec!["456"]);
+
+    assert_eq!(
+        storage
+            .update_all_with(
+                "schema_name",
+                "table_name",
+                |record| match record {
+                    [(name, Datum::Int16(value))] => Ok(vec![(name.clone(), Datum::Int16(value + 1))]),
+                    _ => Err(()),
+                },
+                SESSION
+            )
+            .expect("no system errors"),
+        Ok(Ok(2))
+    );
+
+    assert_eq!(
+        storage
+            .select_all_from("schema_name", "table_name", vec!["column_test".to_owned()], SESSION)
+            .expect("no system errors")
+            .map(|(description, records)| (description, records.collect::<Vec<Vec<Datum>>>())),
+        Ok((
+            vec![("column_test".to_owned(), SqlType::SmallInt)],
+            vec![vec![Datum::Int16(124)], vec![Datum::Int16(457)]]
+        ))
+    );
+}
+
+#[rstest::rstest]
+fn update_all_records_with_failed_values(mut storage: PersistentStorage) {
+    create_schema_with_table(
+        &mut storage,
+        "schema_name",
+        "table_name",
+        vec![("column_test", SqlType::SmallInt)],
+    );
+
+    insert_into(&mut storage, "schema_name", "table_name", vec![], vec!["123"]);
+    insert_into(&mut storage, "schema_name", "table_name", vec![], vec!["456"]);
+
+    assert_eq!(
+        storage
+            .update_all_with(
+                "schema_name",
+                "table_name",
+                |record| match record {
+                    [(name, Datum::Int16(123))] => Ok(vec![(name.clone(), Datum::Int16(0))]),
+                    _ => Err("failed"),
+                },
+                SESSION
+            )
             .expect("no system errors"),
+        Err("failed")
+    );
+
+    assert_eq!(
+        storage
+            .select_all_from("schema_name", "table_name", vec!["column_test".to_owned()], SESSION)
+            .expect("no system errors")
+            .map(|(description, records)| (description, records.collect::<Vec<Vec<Datum>>>())),
         Ok((
             vec![("column_test".to_owned(), SqlType::SmallInt)],
-            vec![vec!["567".to_owned()], vec!["567".to_owned()], vec!["567".to_owned()]]
+            vec![vec![Datum::Int16(123)], vec![Datum::Int16(456)]]
         ))
     );
 }
@@ -63,17 +147,17 @@ fn update_not_existed_table(mut storage: PersistentStorage) {
 
     assert_eq!(
         storage
-            .update_all("schema_name", "not_existed", vec![])
+            .update_all("schema_name", "not_existed", vec![], SESSION)
             .expect("no system errors"),
         Err(OperationOnTableError::TableDoesNotExist)
     );
 }
 
 #[rstest::rstest]
-fn update_non_existent_schema(mut storage: PersistentStorage) {
+fn update_non_existent_schema(storage: PersistentStorage) {
     assert_eq!(
         storage
-            .update_all("non_existent", "not_existed", vec![])
+            .update_all("non_existent", "not_existed", vec![], SESSION)
             .expect("no system errors"),
         Err(OperationOnTableError::SchemaDoesNotExist)
     );
@@ -104,19 +188,27 @@ mod constraints {
             &mut storage,
             "schema_name",
             "table_name",
-            vec![("column_c", SqlType::Char(10)), ("column_vc", SqlType::VarChar(10))],
+            vec![
+                ("column_c", SqlType::Char(10)),
+                ("column_vc", SqlType::VarChar(Some(10))),
+            ],
         );
         storage
     }
 
     #[rstest::rstest]
-    fn out_of_range_violation(mut storage_with_ints_table: PersistentStorage) {
+    fn out_of_range_violation(storage_with_ints_table: PersistentStorage) {
         storage_with_ints_table
             .insert_into(
                 "schema_name",
                 "table_name",
                 vec![],
-                vec![vec!["100".to_owned(), "100".to_owned(), "100".to_owned()]],
+                vec![vec![
+                    Datum::String("100".to_owned()),
+                    Datum::String("100".to_owned()),
+                    Datum::String("100".to_owned()),
+                ]],
+                SESSION,
             )
             .expect("no system errors")
             .expect("record inserted");
@@ -126,10 +218,11 @@ mod constraints {
                     "schema_name",
                     "table_name",
                     vec![
-                        ("column_si".to_owned(), "-32769".to_owned()),
-                        ("column_i".to_owned(), "100".to_owned()),
-                        ("column_bi".to_owned(), "100".to_owned())
-                    ]
+                        ("column_si".to_owned(), Datum::String("-32769".to_owned())),
+                        ("column_i".to_owned(), Datum::String("100".to_owned())),
+                        ("column_bi".to_owned(), Datum::String("100".to_owned()))
+                    ],
+                    SESSION
                 )
                 .expect("no system errors"),
             Err(constraint_violations(
@@ -140,13 +233,18 @@ mod constraints {
     }
 
     #[rstest::rstest]
-    fn not_an_int_violation(mut storage_with_ints_table: PersistentStorage) {
+    fn not_an_int_violation(storage_with_ints_table: PersistentStorage) {
         storage_with_ints_table
             .insert_into(
                 "schema_name",
                 "table_name",
                 vec![],
-                vec![vec!["100".to_owned(), "100".to_owned(), "100".to_owned()]],
+                vec![vec![
+                    Datum::String("100".to_owned()),
+                    Datum::String("100".to_owned()),
+                    Datum::String("100".to_owned()),
+                ]],
+                SESSION,
             )
             .expect("no system errors")
             .expect("record inserted");
@@ -156,10 +254,11 @@ mod constraints {
                     "schema_name",
                     "table_name",
                     vec![
-                        ("column_si".to_owned(), "abc".to_owned()),
-                        ("column_i".to_owned(), "100".to_owned()),
-                        ("column_bi".to_owned(), "100".to_owned())
-                    ]
+                        ("column_si".to_owned(), Datum::String("abc".to_owned())),
+                        ("column_i".to_owned(), Datum::String("100".to_owned())),
+                        ("column_bi".to_owned(), Datum::String("100".to_owned()))
+                    ],
+                    SESSION
                 )
                 .expect("no system errors"),
             Err(constraint_violations(
@@ -170,13 +269,14 @@ mod constraints {
     }
 
     #[rstest::rstest]
-    fn value_too_long_violation(mut storage_with_chars_table: PersistentStorage) {
+    fn value_too_long_violation(storage_with_chars_table: PersistentStorage) {
         storage_with_chars_table
             .insert_into(
                 "schema_name",
                 "table_name",
                 vec![],
-                vec![vec!["100".to_owned(), "100".to_owned()]],
+                vec![vec![Datum::String("100".to_owned()), Datum::String("100".to_owned())]],
+                SESSION,
             )
             .expect("no system errors")
             .expect("record inserted");
@@ -186,9 +286,10 @@ mod constraints {
                     "schema_name",
                     "table_name",
                     vec![
-                        ("column_c".to_owned(), "12345678901".to_owned()),
-                        ("column_vc".to_owned(), "100".to_owned())
-                    ]
+                        ("column_c".to_owned(), Datum::String("12345678901".to_owned())),
+                        ("column_vc".to_owned(), Datum::String("100".to_owned()))
+                    ],
+                    SESSION
                 )
                 .expect("no system errors"),
             Err(constraint_violations(
@@ -199,13 +300,18 @@ mod constraints {
     }
 
     #[rstest::rstest]
-    fn multiple_columns_violation(mut storage_with_ints_table: PersistentStorage) {
+    fn multiple_columns_violation(storage_with_ints_table: PersistentStorage) {
         storage_with_ints_table
             .insert_into(
                 "schema_name",
                 "table_name",
                 vec![],
-                vec![vec!["100".to_owned(), "100".to_owned(), "100".to_owned()]],
+                vec![vec![
+                    Datum::String("100".to_owned()),
+                    Datum::String("100".to_owned()),
+                    Datum::String("100".to_owned()),
+                ]],
+                SESSION,
             )
             .expect("no system errors")
             .expect("records inserted");
@@ -216,10 +322,11 @@ mod constraints {
                     "schema_name",
                     "table_name",
                     vec![
-                        ("column_si".to_owned(), "-32769".to_owned()),
-                        ("column_i".to_owned(), "-2147483649".to_owned()),
-                        ("column_bi".to_owned(), "100".to_owned())
-                    ]
+                        ("column_si".to_owned(), Datum::String("-32769".to_owned())),
+                        ("column_i".to_owned(), Datum::String("-2147483649".to_owned())),
+                        ("column_bi".to_owned(), Datum::String("100".to_owned()))
+                    ],
+                    SESSION
                 )
                 .expect("no system errors"),
             Err(constraint_violations(
@@ -16,9 +16,15 @@ use super::*;
 use sql_types::SqlType;
 
 #[rstest::rstest]
-fn create_schemas_with_different_names(mut storage: PersistentStorage) {
-    assert_eq!(storage.create_schema("schema_1").expect("no system errors"), Ok(()));
-    assert_eq!(storage.create_schema("schema_2").expect("no system errors"), Ok(()));
+fn create_schemas_with_different_names(storage: PersistentStorage) {
+    assert_eq!(
+        storage.create_schema("schema_1", SESSION).expect("no system errors"),
+        Ok(())
+    );
+    assert_eq!(
+        storage.create_schema("schema_2", SESSION).expect("no system errors"),
+        Ok(())
+    );
 }
 
 #[rstest::rstest]
@@ -26,7 +32,7 @@ fn create_schema_with_existing_name(mut storage: PersistentStorage) {
     create_schema(&mut storage, "schema_name");
 
     assert_eq!(
-        storage.create_schema("schema_name").expect("no system errors"),
+        storage.create_schema("schema_name", SESSION).expect("no system errors"),
         Err(SchemaAlreadyExists)
     );
 }
@@ -67,16 +73,24 @@ fn same_table_names_with_different_columns_in_different_schemas(mut storage: Per
 fn drop_schema(mut storage: PersistentStorage) {
     create_schema(&mut storage, "schema_name");
 
-    assert_eq!(storage.drop_schema("schema_name").expect("no system errors"), Ok(()));
-    assert_eq!(storage.create_schema("schema_name").expect("no system errors"), Ok(()));
+    assert_eq!(
+        storage.drop_schema("schema_name", SESSION).expect("no system errors"),
+        Ok(())
+    );
+    assert_eq!(
+        storage.create_schema("schema_name", SESSION).expect("no system errors"),
+        Ok(())
+    );
 }
 
 #[rstest::rstest]
 fn drop_schema_that_was_not_created() {
-    let mut storage = FrontendStorage::default().expect("no system errors");
+    let storage = FrontendStorage::default().expect("no system errors");
 
     assert_eq!(
-        storage.drop_schema("does_not_exists").expect("no system errors"),
+        storage
+            .drop_schema("does_not_exists", SESSION)
+            .expect("no system errors"),
         Err(SchemaDoesNotExist)
     );
 }
@@ -100,14 +114,21 @@ fn drop_schema_drops_tables_in_it(mut storage: PersistentStorage) {
         vec![("column_test", SqlType::SmallInt)],
     );
 
-    assert_eq!(storage.drop_schema("schema_name").expect("no system errors"), Ok(()));
-    assert_eq!(storage.create_schema("schema_name").expect("no system errors"), Ok(()));
+    assert_eq!(
+        storage.drop_schema("schema_name", SESSION).expect("no system errors"),
+        Ok(())
+    );
+    assert_eq!(
+        storage.create_schema("schema_name", SESSION).expect("no system errors"),
+        Ok(())
+    );
     assert_eq!(
         storage
             .create_table(
                 "schema_name",
                 "table_name_1",
-                vec![("column_test".to_owned(), SqlType::SmallInt)]
+                vec![("column_test".to_owned(), SqlType::SmallInt)],
+                SESSION
             )
             .expect("no system errors"),
         Ok(())
@@ -117,7 +138,8 @@ fn drop_schema_drops_tables_in_it(mut storage: PersistentStorage) {
             .create_table(
                 "schema_name",
                 "table_name_2",
-                vec![("column_test".to_owned(), SqlType::SmallInt)]
+                vec![("column_test".to_owned(), SqlType::SmallInt)],
+                SESSION
             )
             .expect("no system errors"),
         Ok(())
@@ -0,0 +1,255 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use super::*;
+
+fn options(start: i64, increment: i64, max_value: i64) -> SequenceOptions {
+    SequenceOptions {
+        start,
+        increment,
+        min_value: 1,
+        max_value,
+    }
+}
+
+fn create_sequence(storage: &mut PersistentStorage, options: SequenceOptions) {
+    storage
+        .create_sequence("schema_name", "ids", options, None, SESSION)
+        .expect("no system errors")
+        .expect("sequence is created");
+}
+
+#[rstest::rstest]
+fn create_sequence_in_non_existent_schema(storage: PersistentStorage) {
+    assert_eq!(
+        storage
+            .create_sequence("non_existent", "ids", options(1, 1, 10), None, SESSION)
+            .expect("no system errors"),
+        Err(CreateSequenceError::SchemaDoesNotExist)
+    );
+}
+
+#[rstest::rstest]
+fn create_sequence_twice(mut storage: PersistentStorage) {
+    create_schema(&mut storage, "schema_name");
+    create_sequence(&mut storage, options(1, 1, 10));
+
+    assert_eq!(
+        storage
+            .create_sequence("schema_name", "ids", options(1, 1, 10), None, SESSION)
+            .expect("no system errors"),
+        Err(CreateSequenceError::SequenceAlreadyExists)
+    );
+}
+
+#[rstest::rstest]
+fn next_values(mut storage: PersistentStorage) {
+    create_schema(&mut storage, "schema_name");
+    create_sequence(&mut storage, options(3, 3, 10));
+
+    for expected in &[3, 6, 9] {
+        assert_eq!(
+            storage.next_value("schema_name", "ids", 1).expect("no system errors"),
+            Ok(*expected)
+        );
+    }
+    assert_eq!(
+        storage.next_value("schema_name", "ids", 1).expect("no system errors"),
+        Err(SequenceError::MaxValueReached(10))
+    );
+}
+
+#[rstest::rstest]
+fn current_value_is_per_session(mut storage: PersistentStorage) {
+    create_schema(&mut storage, "schema_name");
+    create_sequence(&mut storage, options(1, 1, 10));
+
+    assert_eq!(
+        storage
+            .current_value("schema_name", "ids", 1)
+            .expect("no system errors"),
+        Err(SequenceError::CurrentValueNotDefined)
+    );
+
+    storage
+        .next_value("schema_name", "ids", 1)
+        .expect("no system errors")
+        .expect("next value");
+    storage
+        .next_value("schema_name", "ids", 2)
+        .expect("no system errors")
+        .expect("next value");
+
+    assert_eq!(
+        storage
+            .current_value("schema_name", "ids", 1)
+            .expect("no system errors"),
+        Ok(1)
+    );
+    assert_eq!(
+        storage
+            .current_value("schema_name", "ids", 2)
+            .expect("no system errors"),
+        Ok(2)
+    );
+
+    storage.end_session(2);
+    assert_eq!(
+        storage
+            .current_value("schema_name", "ids", 2)
+            .expect("no system errors"),
+        Err(SequenceError::CurrentValueNotDefined)
+    );
+}
+
+#[rstest::rstest]
+fn set_value(mut storage: PersistentStorage) {
+    create_schema(&mut storage, "schema_name");
+    create_sequence(&mut storage, options(1, 1, 10));
+
+    assert_eq!(
+        storage
+            .set_value("schema_name", "ids", 5, true, 1)
+            .expect("no system errors"),
+        Ok(5)
+    );
+    assert_eq!(
+        storage.next_value("schema_name", "ids", 1).expect("no system errors"),
+        Ok(6)
+    );
+
+    assert_eq!(
+        storage
+            .set_value("schema_name", "ids", 5, false, 1)
+            .expect("no system errors"),
+        Ok(5)
+    );
+    assert_eq!(
+        storage.next_value("schema_name", "ids", 1).expect("no system errors"),
+        Ok(5)
+    );
+
+    assert_eq!(
+        storage
+            .set_value("schema_name", "ids", 11, true, 1)
+            .expect("no system errors"),
+        Err(SequenceError::ValueOutOfBounds(1, 10))
+    );
+}
+
+#[rstest::rstest]
+fn drop_sequence(mut storage: PersistentStorage) {
+    create_schema(&mut storage, "schema_name");
+    create_sequence(&mut storage, options(1, 1, 10));
+
+    assert_eq!(
+        storage
+            .drop_sequence("schema_name", "ids", SESSION)
+            .expect("no system errors"),
+        Ok(())
+    );
+    assert_eq!(
+        storage.next_value("schema_name", "ids", 1).expect("no system errors"),
+        Err(SequenceError::SequenceDoesNotExist)
+    );
+    assert_eq!(
+        storage
+            .drop_sequence("schema_name", "ids", SESSION)
+            .expect("no system errors"),
+        Err(DropSequenceError::SequenceDoesNotExist)
+    );
+}
+
+#[rstest::rstest]
+fn sequence_is_dropped_with_owning_table(mut storage: PersistentStorage) {
+    create_schema_with_table(
+        &mut storage,
+        "schema_name",
+        "table_name",
+        vec![("id", SqlType::Integer)],
+    );
+    storage
+        .create_sequence(
+            "schema_name",
+            "table_name_id_seq",
+            options(1, 1, 10),
+            Some("table_name"),
+            SESSION,
+        )
+        .expect("no system errors")
+        .expect("sequence is created");
+
+    storage
+        .drop_table("schema_name", "table_name", SESSION)
+        .expect("no system errors")
+        .expect("table is dropped");
+
+    assert_eq!(
+        storage
+            .next_value("schema_name", "table_name_id_seq", 1)
+            .expect("no system errors"),
+        Err(SequenceError::SequenceDoesNotExist)
+    );
+}
+
+#[rstest::rstest]
+fn sequence_state_is_kept_in_backend(mut storage: PersistentStorage) {
+    create_schema(&mut storage, "schema_name");
+    create_sequence(&mut storage, options(1, 1, 10));
+    storage
+        .next_value("schema_name", "ids", 1)
+        .expect("no system errors")
+        .expect("next value");
+    storage
+        .next_value("schema_name", "ids", 1)
+        .expect("no system errors")
+        .expect("next value");
+
+    let records = storage
+        .persistent
+        .read("system", "sequences")
+        .expect("no system errors")
+        .expect("system table exists")
+        .collect::<SystemResult<Vec<Row>>>()
+        .expect("no system errors");
+    assert_eq!(records.len(), 1);
+    let (key, sequence) = &records[0];
+    let sequence = bincode::deserialize::<Sequence>(sequence).expect("sequence is decoded");
+    assert_eq!(key, b"schema_name.ids");
+    assert_eq!((sequence.last_value, sequence.is_called), (2, true));
+}
+
+#[rstest::rstest]
+fn sequence_continues_after_restart(mut storage: PersistentStorage) {
+    create_schema(&mut storage, "schema_name");
+    create_sequence(&mut storage, options(1, 1, 10));
+    storage
+        .next_value("schema_name", "ids", SESSION)
+        .expect("no system errors")
+        .expect("next value");
+
+    let storage = FrontendStorage::new(storage.persistent).expect("no system errors");
+
+    assert_eq!(
+        storage
+            .next_value("schema_name", "ids", SESSION)
+            .expect("no system errors"),
+        Ok(2)
+    );
+    assert_eq!(
+        storage
+            .current_value("schema_name", "ids", SESSION)
+            .expect("no system errors"),
+        Ok(2)
+    );
+}
@@ -24,7 +24,8 @@ fn create_tables_with_different_names(mut storage: PersistentStorage) {
             .create_table(
                 "schema_name",
                 "table_name_1",
-                vec![("column_rstest::rstest".to_owned(), SqlType::SmallInt)]
+                vec![("column_rstest::rstest".to_owned(), SqlType::SmallInt)],
+                SESSION
             )
             .expect("no system errors"),
         Ok(())
@@ -34,7 +35,8 @@ fn create_tables_with_different_names(mut storage: PersistentStorage) {
             .create_table(
                 "schema_name",
                 "table_name_2",
-                vec![("column_rstest::rstest".to_owned(), SqlType::SmallInt)]
+                vec![("column_rstest::rstest".to_owned(), SqlType::SmallInt)],
+                SESSION
             )
             .expect("no system errors"),
         Ok(())
@@ -55,7 +57,8 @@ fn create_table_with_the_same_name(mut storage: PersistentStorage) {
             .create_table(
                 "schema_name",
                 "table_name",
-                vec![("column_rstest::rstest".to_owned(), SqlType::SmallInt)]
+                vec![("column_rstest::rstest".to_owned(), SqlType::SmallInt)],
+                SESSION
             )
             .expect("no system errors"),
         Err(CreateTableError::TableAlreadyExists)
@@ -71,7 +74,8 @@ fn create_table_with_the_same_name_in_different_schemas(mut storage: PersistentS
             .create_table(
                 "schema_name_1",
                 "table_name",
-                vec![("column_rstest::rstest".to_owned(), SqlType::SmallInt)]
+                vec![("column_rstest::rstest".to_owned(), SqlType::SmallInt)],
+                SESSION
             )
             .expect("no system errors"),
         Ok(())
@@ -81,7 +85,8 @@ fn create_table_with_the_same_name_in_different_schemas(mut storage: PersistentS
             .create_table(
                 "schema_name_2",
                 "table_name",
-                vec![("column_rstest::rstest".to_owned(), SqlType::SmallInt)]
+                vec![("column_rstest::rstest".to_owned(), SqlType::SmallInt)],
+                SESSION
             )
             .expect("no system errors"),
         Ok(())
@@ -98,7 +103,7 @@ fn drop_table(mut storage: PersistentStorage) {
     );
     assert_eq!(
         storage
-            .drop_table("schema_name", "table_name")
+            .drop_table("schema_name", "table_name", SESSION)
             .expect("no system errors"),
         Ok(())
     );
@@ -107,7 +112,8 @@ fn drop_table(mut storage: PersistentStorage) {
             .create_table(
                 "schema_name",
                 "table_name",
-                vec![("column_rstest::rstest".to_owned(), SqlType::SmallInt)]
+                vec![("column_rstest::rstest".to_owned(), SqlType::SmallInt)],
+                SESSION
             )
             .expect("no system errors"),
         Ok(())
@@ -126,12 +132,71 @@ fn table_columns_on_empty_table(mut storage: PersistentStorage) {
     )
 }
 
+#[rstest::rstest]
+fn column_defaults(mut storage: PersistentStorage) {
+    create_schema(&mut storage, "schema_name");
+    storage
+        .create_table_with_defaults(
+            "schema_name",
+            "table_name",
+            vec![
+                ("column_1".to_owned(), SqlType::SmallInt, Some("1".to_owned())),
+                ("column_2".to_owned(), SqlType::SmallInt, None),
+            ],
+            &[],
+            SESSION,
+        )
+        .expect("no system errors")
+        .expect("table is created");
+
+    assert_eq!(
+        storage
+            .table_columns("schema_name", "table_name")
+            .expect("no system errors"),
+        vec![
+            ("column_1".to_owned(), SqlType::SmallInt),
+            ("column_2".to_owned(), SqlType::SmallInt)
+        ]
+    );
+    assert_eq!(
+        storage
+            .column_defaults("schema_name", "table_name")
+            .expect("no system errors"),
+        vec![("column_1".to_owned(), "1".to_owned())]
+    );
+}
+
+#[rstest::rstest]
+fn generated_always_columns(mut storage: PersistentStorage) {
+    create_schema(&mut storage, "schema_name");
+    storage
+        .create_table_with_defaults(
+            "schema_name",
+            "table_name",
+            vec![
+                ("column_1".to_owned(), SqlType::SmallInt, Some("1".to_owned())),
+                ("column_2".to_owned(), SqlType::SmallInt, Some("2".to_owned())),
+            ],
+            &["column_2".to_owned()],
+            SESSION,
+        )
+        .expect("no system errors")
+        .expect("table is created");
+
+    assert_eq!(
+        storage
+            .generated_always_columns("schema_name", "table_name")
+            .expect("no system errors"),
+        vec!["column_2".to_owned()]
+    );
+}
+
 #[rstest::rstest]
 fn drop_not_created_table(mut storage: PersistentStorage) {
     create_schema(&mut storage, "schema_name");
     assert_eq!(
         storage
-            .drop_table("schema_name", "not_existed_table")
+            .drop_table("schema_name", "not_existed_table", SESSION)
             .expect("no system errors"),
         Err(DropTableError::TableDoesNotExist)
     );
@@ -0,0 +1,145 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use super::*;
+use sql_types::SqlType;
+
+fn labels() -> Vec<String> {
+    vec!["sad".to_owned(), "ok".to_owned(), "happy".to_owned()]
+}
+
+#[rstest::rstest]
+fn create_enum_type(mut storage: PersistentStorage) {
+    create_schema(&mut storage, "schema_name");
+
+    assert_eq!(
+        storage
+            .create_enum_type("schema_name", "mood", labels(), SESSION)
+            .expect("no system errors"),
+        Ok(())
+    );
+    assert_eq!(
+        storage.user_type("schema_name", "mood").expect("no system errors"),
+        Some(SqlType::Enum {
+            name: "mood".to_owned(),
+            oid: 16384,
+            labels: labels()
+        })
+    );
+    assert_eq!(
+        storage.user_type("schema_name", "other").expect("no system errors"),
+        None
+    );
+}
+
+#[rstest::rstest]
+fn types_have_distinct_oids(mut storage: PersistentStorage) {
+    create_schema(&mut storage, "schema_name");
+    for type_name in &["mood_1", "mood_2"] {
+        storage
+            .create_enum_type("schema_name", type_name, labels(), SESSION)
+            .expect("no system errors")
+            .expect("type is created");
+    }
+
+    // OID that follows the type OID is taken by its array type
+    assert!(matches!(
+        storage.user_type("schema_name", "mood_2").expect("no system errors"),
+        Some(SqlType::Enum { oid: 16386, .. })
+    ));
+}
+
+#[rstest::rstest]
+fn create_type_with_existing_name(mut storage: PersistentStorage) {
+    create_schema(&mut storage, "schema_name");
+    storage
+        .create_enum_type("schema_name", "mood", labels(), SESSION)
+        .expect("no system errors")
+        .expect("type is created");
+
+    assert_eq!(
+        storage
+            .create_enum_type("schema_name", "mood", labels(), SESSION)
+            .expect("no system errors"),
+        Err(CreateTypeError::TypeAlreadyExists)
+    );
+}
+
+#[rstest::rstest]
+fn create_type_in_not_existent_schema(storage: PersistentStorage) {
+    assert_eq!(
+        storage
+            .create_enum_type("schema_name", "mood", labels(), SESSION)
+            .expect("no system errors"),
+        Err(CreateTypeError::SchemaDoesNotExist)
+    );
+}
+
+#[rstest::rstest]
+fn drop_type(mut storage: PersistentStorage) {
+    create_schema(&mut storage, "schema_name");
+    storage
+        .create_enum_type("schema_name", "mood", labels(), SESSION)
+        .expect("no system errors")
+        .expect("type is created");
+
+    assert_eq!(
+        storage
+            .drop_type("schema_name", "mood", SESSION)
+            .expect("no system errors"),
+        Ok(())
+    );
+    assert_eq!(
+        storage
+            .drop_type("schema_name", "mood", SESSION)
+            .expect("no system errors"),
+        Err(DropTypeError::TypeDoesNotExist)
+    );
+}
+
+#[rstest::rstest]
+fn drop_type_used_by_column(mut storage: PersistentStorage) {
+    create_schema(&mut storage, "schema_name");
+    storage
+        .create_enum_type("schema_name", "mood", labels(), SESSION)
+        .expect("no system errors")
+        .expect("type is created");
+    let mood = storage
+        .user_type("schema_name", "mood")
+        .expect("no system errors")
+        .expect("type exists");
+    create_table(
+        &mut storage,
+        "schema_name",
+        "table_name",
+        vec![("column_mood", SqlType::Array(Box::new(mood)))],
+    );
+
+    assert_eq!(
+        storage
+            .drop_type("schema_name", "mood", SESSION)
+            .expect("no system errors"),
+        Err(DropTypeError::DependentObjectsExist)
+    );
+
+    storage
+        .drop_table("schema_name", "table_name", SESSION)
+        .expect("no system errors")
+        .expect("table is dropped");
+    assert_eq!(
+        storage
+            .drop_type("schema_name", "mood", SESSION)
+            .expect("no system errors"),
+        Ok(())
+    );
+}
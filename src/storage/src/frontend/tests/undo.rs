@@ -0,0 +1,239 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+const OTHER_SESSION: SessionId = 1;
+
+#[rstest::fixture]
+fn with_records(mut storage: PersistentStorage) -> PersistentStorage {
+    create_schema_with_table(
+        &mut storage,
+        "schema_name",
+        "table_name",
+        vec![("column_test", SqlType::SmallInt)],
+    );
+    insert_into(&mut storage, "schema_name", "table_name", vec![], vec!["123"]);
+    insert_into(&mut storage, "schema_name", "table_name", vec![], vec!["456"]);
+    storage.commit(SESSION);
+    storage
+}
+
+fn change_records(storage: &PersistentStorage) {
+    storage
+        .update_all(
+            "schema_name",
+            "table_name",
+            vec![("column_test".to_owned(), Datum::String("789".to_owned()))],
+            SESSION,
+        )
+        .expect("no system errors")
+        .expect("records are updated");
+    storage
+        .insert_into(
+            "schema_name",
+            "table_name",
+            vec![],
+            vec![vec![Datum::String("1".to_owned())]],
+            SESSION,
+        )
+        .expect("no system errors")
+        .expect("record is inserted");
+}
+
+fn records(storage: &PersistentStorage, session_id: SessionId) -> Vec<Vec<Datum>> {
+    storage
+        .select_all_from("schema_name", "table_name", vec!["column_test".to_owned()], session_id)
+        .expect("no system errors")
+        .expect("records are selected")
+        .1
+        .collect()
+}
+
+#[rstest::rstest]
+fn rollback_writes_back_changed_records(with_records: PersistentStorage) {
+    change_records(&with_records);
+    with_records.rollback(SESSION).expect("no system errors");
+
+    assert_eq!(
+        records(&with_records, SESSION),
+        vec![vec![Datum::Int16(123)], vec![Datum::Int16(456)]]
+    );
+}
+
+#[rstest::rstest]
+fn rollback_writes_back_deleted_records(with_records: PersistentStorage) {
+    with_records
+        .delete_all_from("schema_name", "table_name", SESSION)
+        .expect("no system errors")
+        .expect("records are deleted");
+    with_records.rollback(SESSION).expect("no system errors");
+
+    assert_eq!(
+        records(&with_records, SESSION),
+        vec![vec![Datum::Int16(123)], vec![Datum::Int16(456)]]
+    );
+}
+
+#[rstest::rstest]
+fn other_sessions_read_committed_records(with_records: PersistentStorage) {
+    change_records(&with_records);
+
+    assert_eq!(
+        records(&with_records, SESSION),
+        vec![vec![Datum::Int16(789)], vec![Datum::Int16(789)], vec![Datum::Int16(1)]]
+    );
+    assert_eq!(
+        records(&with_records, OTHER_SESSION),
+        vec![vec![Datum::Int16(123)], vec![Datum::Int16(456)]]
+    );
+}
+
+#[rstest::rstest]
+fn other_sessions_read_deleted_records_until_commit(with_records: PersistentStorage) {
+    with_records
+        .delete_all_from("schema_name", "table_name", SESSION)
+        .expect("no system errors")
+        .expect("records are deleted");
+
+    assert_eq!(records(&with_records, SESSION), Vec::<Vec<Datum>>::new());
+    assert_eq!(
+        records(&with_records, OTHER_SESSION),
+        vec![vec![Datum::Int16(123)], vec![Datum::Int16(456)]]
+    );
+
+    with_records.commit(SESSION);
+    assert_eq!(records(&with_records, OTHER_SESSION), Vec::<Vec<Datum>>::new());
+}
+
+#[rstest::rstest]
+fn rollback_restores_dropped_table_as_it_was_before_changes(with_records: PersistentStorage) {
+    change_records(&with_records);
+    with_records
+        .drop_table("schema_name", "table_name", SESSION)
+        .expect("no system errors")
+        .expect("table is dropped");
+
+    with_records.rollback(SESSION).expect("no system errors");
+
+    assert_eq!(
+        with_records
+            .table_columns("schema_name", "table_name")
+            .expect("no system errors"),
+        vec![("column_test".to_owned(), SqlType::SmallInt)]
+    );
+    assert_eq!(
+        records(&with_records, SESSION),
+        vec![vec![Datum::Int16(123)], vec![Datum::Int16(456)]]
+    );
+}
+
+#[rstest::rstest]
+fn rollback_drops_created_objects(mut storage: PersistentStorage) {
+    create_schema_with_table(
+        &mut storage,
+        "schema_name",
+        "table_name",
+        vec![("column_test", SqlType::SmallInt)],
+    );
+    storage
+        .create_enum_type("schema_name", "mood", vec!["sad".to_owned()], SESSION)
+        .expect("no system errors")
+        .expect("type is created");
+    storage
+        .create_sequence("schema_name", "ids", sequence_options(), None, SESSION)
+        .expect("no system errors")
+        .expect("sequence is created");
+
+    storage.rollback(SESSION).expect("no system errors");
+
+    assert!(!storage.table_exists("schema_name", "table_name"));
+    assert_eq!(
+        storage
+            .table_columns("schema_name", "table_name")
+            .expect("no system errors"),
+        vec![]
+    );
+    assert_eq!(
+        storage.user_type("schema_name", "mood").expect("no system errors"),
+        None
+    );
+    assert_eq!(
+        storage.create_schema("schema_name", SESSION).expect("no system errors"),
+        Ok(())
+    );
+    assert_eq!(
+        storage
+            .create_sequence("schema_name", "ids", sequence_options(), None, SESSION)
+            .expect("no system errors"),
+        Ok(())
+    );
+}
+
+#[rstest::rstest]
+fn rollback_restores_dropped_schema(with_records: PersistentStorage) {
+    with_records.commit(SESSION);
+    with_records
+        .create_enum_type("schema_name", "mood", vec!["sad".to_owned()], SESSION)
+        .expect("no system errors")
+        .expect("type is created");
+    with_records
+        .create_sequence("schema_name", "ids", sequence_options(), None, SESSION)
+        .expect("no system errors")
+        .expect("sequence is created");
+    with_records.commit(SESSION);
+
+    with_records
+        .drop_schema("schema_name", SESSION)
+        .expect("no system errors")
+        .expect("schema is dropped");
+    with_records.rollback(SESSION).expect("no system errors");
+
+    assert_eq!(
+        records(&with_records, SESSION),
+        vec![vec![Datum::Int16(123)], vec![Datum::Int16(456)]]
+    );
+    assert!(with_records
+        .user_type("schema_name", "mood")
+        .expect("no system errors")
+        .is_some());
+    assert_eq!(
+        with_records
+            .next_value("schema_name", "ids", SESSION)
+            .expect("no system errors"),
+        Ok(1)
+    );
+}
+
+#[rstest::rstest]
+fn committed_drop_is_not_undone(with_records: PersistentStorage) {
+    with_records
+        .drop_table("schema_name", "table_name", SESSION)
+        .expect("no system errors")
+        .expect("table is dropped");
+    with_records.commit(SESSION);
+
+    with_records.rollback(SESSION).expect("no system errors");
+
+    assert!(!with_records.table_exists("schema_name", "table_name"));
+}
+
+fn sequence_options() -> SequenceOptions {
+    SequenceOptions {
+        start: 1,
+        increment: 1,
+        min_value: 1,
+        max_value: 10,
+    }
+}
@@ -16,13 +16,17 @@ extern crate kernel;
 extern crate log;
 extern crate sql_types;
 
-use sql_types::{ConstraintError, SqlType};
+use serde::{Deserialize, Serialize};
+use sql_types::{ConstraintError, Datum, SqlType};
 use std::collections::HashMap;
 
 pub mod backend;
 pub mod frontend;
+pub mod lock;
 
-pub type Projection = (Vec<(String, sql_types::SqlType)>, Vec<Vec<String>>);
+pub type Projection = (Vec<(String, sql_types::SqlType)>, Records);
+/// Records that are read from a table one by one
+pub type Records = Box<dyn Iterator<Item = Vec<Datum>> + Send>;
 
 #[derive(Debug, PartialEq)]
 pub struct SchemaAlreadyExists;
@@ -35,6 +39,53 @@ pub enum CreateTableError {
     TableAlreadyExists,
 }
 
+#[derive(Debug, PartialEq)]
+pub enum CreateTypeError {
+    SchemaDoesNotExist,
+    TypeAlreadyExists,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DropTypeError {
+    SchemaDoesNotExist,
+    TypeDoesNotExist,
+    // columns of tables have the type
+    DependentObjectsExist,
+}
+
+/// Sequence generates values from `start` that are `increment` apart and
+/// are between `min_value` and `max_value`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SequenceOptions {
+    pub start: i64,
+    pub increment: i64,
+    pub min_value: i64,
+    pub max_value: i64,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CreateSequenceError {
+    SchemaDoesNotExist,
+    SequenceAlreadyExists,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DropSequenceError {
+    SchemaDoesNotExist,
+    SequenceDoesNotExist,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SequenceError {
+    SequenceDoesNotExist,
+    MaxValueReached(i64),
+    MinValueReached(i64),
+    // session has not called `nextval` on the sequence yet
+    CurrentValueNotDefined,
+    // minimum and maximum values of the sequence
+    ValueOutOfBounds(i64, i64),
+}
+
 #[derive(Debug, PartialEq)]
 pub enum DropTableError {
     SchemaDoesNotExist,
@@ -0,0 +1,623 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Condvar, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+pub type SessionId = u64;
+
+/// Object that can be locked by a session
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Resource {
+    Schema(String),
+    Table(String, String),
+    // rows are not locked one by one, writers lock all rows of a table
+    // exclusively and readers that have to see the same rows until the end of
+    // transaction share them
+    Rows(String, String),
+}
+
+impl Resource {
+    pub fn schema(schema_name: &str) -> Resource {
+        Resource::Schema(schema_name.to_owned())
+    }
+
+    pub fn table(schema_name: &str, table_name: &str) -> Resource {
+        Resource::Table(schema_name.to_owned(), table_name.to_owned())
+    }
+
+    pub fn rows(schema_name: &str, table_name: &str) -> Resource {
+        Resource::Rows(schema_name.to_owned(), table_name.to_owned())
+    }
+}
+
+/// Table level lock modes as they are defined in PostgreSQL
+/// see https://www.postgresql.org/docs/12/explicit-locking.html#LOCKING-TABLES
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LockMode {
+    AccessShare,
+    RowShare,
+    RowExclusive,
+    ShareUpdateExclusive,
+    Share,
+    ShareRowExclusive,
+    Exclusive,
+    AccessExclusive,
+}
+
+impl LockMode {
+    fn conflicts_with(self, other: LockMode) -> bool {
+        use LockMode::*;
+        match self {
+            AccessShare => other == AccessExclusive,
+            RowShare => matches!(other, Exclusive | AccessExclusive),
+            RowExclusive => matches!(other, Share | ShareRowExclusive | Exclusive | AccessExclusive),
+            ShareUpdateExclusive => matches!(
+                other,
+                ShareUpdateExclusive | Share | ShareRowExclusive | Exclusive | AccessExclusive
+            ),
+            Share => matches!(
+                other,
+                RowExclusive | ShareUpdateExclusive | ShareRowExclusive | Exclusive | AccessExclusive
+            ),
+            ShareRowExclusive => matches!(
+                other,
+                RowExclusive | ShareUpdateExclusive | Share | ShareRowExclusive | Exclusive | AccessExclusive
+            ),
+            Exclusive => other != AccessShare,
+            AccessExclusive => true,
+        }
+    }
+}
+
+/// Defines when granted lock is released
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LockDuration {
+    /// released by `unlock_statement_locks` or `unlock_all`
+    Statement,
+    /// released only by `unlock_all`
+    Transaction,
+}
+
+/// Transaction isolation levels. Isolation is provided by strict two phase
+/// locking on table level: write locks are always held until the end of
+/// transaction, read locks are held until the end of statement in
+/// `ReadCommitted` and until the end of transaction in other levels.
+/// As whole tables are locked there are no phantoms, so `RepeatableRead`
+/// provides the same guarantees as `Serializable`.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum IsolationLevel {
+    #[default]
+    ReadCommitted,
+    RepeatableRead,
+    Serializable,
+}
+
+impl IsolationLevel {
+    pub fn read_lock_duration(self) -> LockDuration {
+        match self {
+            IsolationLevel::ReadCommitted => LockDuration::Statement,
+            IsolationLevel::RepeatableRead | IsolationLevel::Serializable => LockDuration::Transaction,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LockError {
+    Deadlock,
+    // lock is held by other session and caller does not want to wait
+    NotAvailable,
+    // lock was not granted during session lock timeout
+    Timeout,
+}
+
+#[derive(Default)]
+struct LockTable {
+    granted: HashMap<Resource, Vec<(SessionId, LockMode, LockDuration)>>,
+    waiting: HashMap<SessionId, (Resource, LockMode)>,
+    timeouts: HashMap<SessionId, Duration>,
+}
+
+impl LockTable {
+    fn blockers(&self, session_id: SessionId, resource: &Resource, mode: LockMode) -> Vec<SessionId> {
+        self.granted
+            .get(resource)
+            .map(|holders| {
+                holders
+                    .iter()
+                    .filter(|(holder, held, _)| *holder != session_id && mode.conflicts_with(*held))
+                    .map(|(holder, _, _)| *holder)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn grant(&mut self, session_id: SessionId, resource: Resource, mode: LockMode, duration: LockDuration) {
+        let holders = self.granted.entry(resource).or_default();
+        match holders
+            .iter_mut()
+            .find(|(holder, held, _)| *holder == session_id && *held == mode)
+        {
+            Some((_, _, held_for)) => {
+                if duration == LockDuration::Transaction {
+                    *held_for = LockDuration::Transaction;
+                }
+            }
+            None => holders.push((session_id, mode, duration)),
+        }
+    }
+
+    fn release(&mut self, session_id: SessionId, durations: &[LockDuration]) {
+        for holders in self.granted.values_mut() {
+            holders.retain(|(holder, _, duration)| *holder != session_id || !durations.contains(duration));
+        }
+        self.granted.retain(|_, holders| !holders.is_empty());
+    }
+
+    // walks "waits for" graph starting from sessions that block `session_id`
+    // if walk comes back to `session_id` then waiting would never end
+    fn leads_to_deadlock(&self, session_id: SessionId, blockers: Vec<SessionId>) -> bool {
+        let mut visited = HashSet::new();
+        let mut to_visit = blockers;
+        while let Some(current) = to_visit.pop() {
+            if current == session_id {
+                return true;
+            }
+            if !visited.insert(current) {
+                continue;
+            }
+            if let Some((resource, mode)) = self.waiting.get(&current) {
+                to_visit.extend(self.blockers(current, resource, *mode));
+            }
+        }
+        false
+    }
+}
+
+/// Grants locks on schemas and tables to sessions. Locks are held until session
+/// explicitly releases them with `unlock_statement_locks` or `unlock_all`
+/// depending on requested `LockDuration`.
+pub struct LockManager {
+    session_ids: AtomicU64,
+    table: Mutex<LockTable>,
+    released: Condvar,
+}
+
+impl Default for LockManager {
+    fn default() -> Self {
+        Self {
+            session_ids: AtomicU64::new(0),
+            table: Mutex::new(LockTable::default()),
+            released: Condvar::new(),
+        }
+    }
+}
+
+impl LockManager {
+    pub fn new_session(&self) -> SessionId {
+        self.session_ids.fetch_add(1, Ordering::SeqCst)
+    }
+
+    /// Sets maximum time that session waits for a lock. `None` means that
+    /// session waits until lock is granted or deadlock is detected.
+    pub fn set_lock_timeout(&self, session_id: SessionId, timeout: Option<Duration>) {
+        let mut table = self.table.lock().unwrap();
+        match timeout {
+            Some(timeout) => table.timeouts.insert(session_id, timeout),
+            None => table.timeouts.remove(&session_id),
+        };
+    }
+
+    /// Blocks current thread until lock is granted. Returns `LockError::Deadlock`
+    /// if waiting for the lock would never end and `LockError::Timeout` if lock
+    /// was not granted during session lock timeout.
+    pub fn lock(
+        &self,
+        session_id: SessionId,
+        resource: Resource,
+        mode: LockMode,
+        duration: LockDuration,
+    ) -> Result<(), LockError> {
+        let mut table = self.table.lock().unwrap();
+        let deadline = table.timeouts.get(&session_id).map(|timeout| Instant::now() + *timeout);
+        loop {
+            let blockers = table.blockers(session_id, &resource, mode);
+            if blockers.is_empty() {
+                table.waiting.remove(&session_id);
+                table.grant(session_id, resource, mode, duration);
+                return Ok(());
+            }
+            if table.leads_to_deadlock(session_id, blockers) {
+                table.waiting.remove(&session_id);
+                log::warn!("deadlock detected for session {} on {:?}", session_id, resource);
+                return Err(LockError::Deadlock);
+            }
+            table.waiting.insert(session_id, (resource.clone(), mode));
+            table = match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        table.waiting.remove(&session_id);
+                        return Err(LockError::Timeout);
+                    }
+                    self.released.wait_timeout(table, deadline - now).unwrap().0
+                }
+                None => self.released.wait(table).unwrap(),
+            };
+        }
+    }
+
+    /// Grants lock only if it is not held in conflicting mode by other sessions
+    pub fn try_lock(
+        &self,
+        session_id: SessionId,
+        resource: Resource,
+        mode: LockMode,
+        duration: LockDuration,
+    ) -> Result<(), LockError> {
+        let mut table = self.table.lock().unwrap();
+        if table.blockers(session_id, &resource, mode).is_empty() {
+            table.grant(session_id, resource, mode, duration);
+            Ok(())
+        } else {
+            Err(LockError::NotAvailable)
+        }
+    }
+
+    pub fn unlock_statement_locks(&self, session_id: SessionId) {
+        self.table
+            .lock()
+            .unwrap()
+            .release(session_id, &[LockDuration::Statement]);
+        self.released.notify_all();
+    }
+
+    pub fn unlock_all(&self, session_id: SessionId) {
+        self.table
+            .lock()
+            .unwrap()
+            .release(session_id, &[LockDuration::Statement, LockDuration::Transaction]);
+        self.released.notify_all();
+    }
+
+    pub fn end_session(&self, session_id: SessionId) {
+        self.unlock_all(session_id);
+        self.table.lock().unwrap().timeouts.remove(&session_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        sync::{mpsc, Arc},
+        thread,
+        time::Duration,
+    };
+
+    #[rstest::fixture]
+    fn lock_manager() -> Arc<LockManager> {
+        Arc::new(LockManager::default())
+    }
+
+    #[rstest::rstest]
+    fn access_share_locks_are_compatible(lock_manager: Arc<LockManager>) {
+        let first = lock_manager.new_session();
+        let second = lock_manager.new_session();
+
+        assert_eq!(
+            lock_manager.lock(
+                first,
+                Resource::table("schema", "table"),
+                LockMode::AccessShare,
+                LockDuration::Transaction
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            lock_manager.lock(
+                second,
+                Resource::table("schema", "table"),
+                LockMode::AccessShare,
+                LockDuration::Transaction
+            ),
+            Ok(())
+        );
+    }
+
+    #[rstest::rstest]
+    fn locks_on_different_tables_do_not_conflict(lock_manager: Arc<LockManager>) {
+        let first = lock_manager.new_session();
+        let second = lock_manager.new_session();
+
+        assert_eq!(
+            lock_manager.lock(
+                first,
+                Resource::table("schema", "table_1"),
+                LockMode::AccessExclusive,
+                LockDuration::Transaction
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            lock_manager.lock(
+                second,
+                Resource::table("schema", "table_2"),
+                LockMode::AccessExclusive,
+                LockDuration::Transaction
+            ),
+            Ok(())
+        );
+    }
+
+    #[rstest::rstest]
+    fn session_does_not_conflict_with_itself(lock_manager: Arc<LockManager>) {
+        let session = lock_manager.new_session();
+
+        assert_eq!(
+            lock_manager.lock(
+                session,
+                Resource::schema("schema"),
+                LockMode::AccessShare,
+                LockDuration::Transaction
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            lock_manager.lock(
+                session,
+                Resource::schema("schema"),
+                LockMode::AccessExclusive,
+                LockDuration::Transaction
+            ),
+            Ok(())
+        );
+    }
+
+    #[rstest::rstest]
+    fn lock_modes_conflicts() {
+        use LockMode::*;
+        let modes = [
+            AccessShare,
+            RowShare,
+            RowExclusive,
+            ShareUpdateExclusive,
+            Share,
+            ShareRowExclusive,
+            Exclusive,
+            AccessExclusive,
+        ];
+        // table from https://www.postgresql.org/docs/12/explicit-locking.html#TABLE-LOCK-COMPATIBILITY
+        let expected = [
+            [false, false, false, false, false, false, false, true],
+            [false, false, false, false, false, false, true, true],
+            [false, false, false, false, true, true, true, true],
+            [false, false, false, true, true, true, true, true],
+            [false, false, true, true, false, true, true, true],
+            [false, false, true, true, true, true, true, true],
+            [false, true, true, true, true, true, true, true],
+            [true, true, true, true, true, true, true, true],
+        ];
+        for (i, requested) in modes.iter().enumerate() {
+            for (j, held) in modes.iter().enumerate() {
+                assert_eq!(
+                    requested.conflicts_with(*held),
+                    expected[i][j],
+                    "{:?} vs {:?}",
+                    requested,
+                    held
+                );
+            }
+        }
+    }
+
+    #[rstest::rstest]
+    fn try_lock_does_not_wait(lock_manager: Arc<LockManager>) {
+        let first = lock_manager.new_session();
+        let second = lock_manager.new_session();
+        lock_manager
+            .lock(
+                first,
+                Resource::table("schema", "table"),
+                LockMode::Share,
+                LockDuration::Transaction,
+            )
+            .expect("lock granted");
+
+        assert_eq!(
+            lock_manager.try_lock(
+                second,
+                Resource::table("schema", "table"),
+                LockMode::Exclusive,
+                LockDuration::Transaction
+            ),
+            Err(LockError::NotAvailable)
+        );
+        assert_eq!(
+            lock_manager.try_lock(
+                second,
+                Resource::table("schema", "table"),
+                LockMode::Share,
+                LockDuration::Transaction
+            ),
+            Ok(())
+        );
+    }
+
+    #[rstest::rstest]
+    fn statement_locks_are_released_before_transaction_locks(lock_manager: Arc<LockManager>) {
+        let first = lock_manager.new_session();
+        let second = lock_manager.new_session();
+        lock_manager
+            .lock(
+                first,
+                Resource::table("schema", "table_1"),
+                LockMode::AccessShare,
+                LockDuration::Statement,
+            )
+            .expect("lock granted");
+        lock_manager
+            .lock(
+                first,
+                Resource::table("schema", "table_2"),
+                LockMode::AccessShare,
+                LockDuration::Statement,
+            )
+            .expect("lock granted");
+        lock_manager
+            .lock(
+                first,
+                Resource::table("schema", "table_2"),
+                LockMode::AccessShare,
+                LockDuration::Transaction,
+            )
+            .expect("lock granted");
+
+        lock_manager.unlock_statement_locks(first);
+
+        assert_eq!(
+            lock_manager.try_lock(
+                second,
+                Resource::table("schema", "table_1"),
+                LockMode::AccessExclusive,
+                LockDuration::Transaction
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            lock_manager.try_lock(
+                second,
+                Resource::table("schema", "table_2"),
+                LockMode::AccessExclusive,
+                LockDuration::Transaction
+            ),
+            Err(LockError::NotAvailable)
+        );
+    }
+
+    #[rstest::rstest]
+    fn lock_timeout(lock_manager: Arc<LockManager>) {
+        let first = lock_manager.new_session();
+        let second = lock_manager.new_session();
+        lock_manager.set_lock_timeout(second, Some(Duration::from_millis(50)));
+        lock_manager
+            .lock(
+                first,
+                Resource::table("schema", "table"),
+                LockMode::AccessExclusive,
+                LockDuration::Transaction,
+            )
+            .expect("lock granted");
+
+        assert_eq!(
+            lock_manager.lock(
+                second,
+                Resource::table("schema", "table"),
+                LockMode::AccessShare,
+                LockDuration::Transaction
+            ),
+            Err(LockError::Timeout)
+        );
+    }
+
+    #[rstest::rstest]
+    fn exclusive_lock_waits_until_shared_is_released(lock_manager: Arc<LockManager>) {
+        let reader = lock_manager.new_session();
+        let writer = lock_manager.new_session();
+        lock_manager
+            .lock(
+                reader,
+                Resource::table("schema", "table"),
+                LockMode::AccessShare,
+                LockDuration::Transaction,
+            )
+            .expect("lock granted");
+
+        let (sender, receiver) = mpsc::channel();
+        let manager = lock_manager.clone();
+        let handle = thread::spawn(move || {
+            let result = manager.lock(
+                writer,
+                Resource::table("schema", "table"),
+                LockMode::AccessExclusive,
+                LockDuration::Transaction,
+            );
+            sender.send(result).unwrap();
+        });
+
+        assert!(receiver.recv_timeout(Duration::from_millis(100)).is_err());
+
+        lock_manager.unlock_all(reader);
+
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok(Ok(())));
+        handle.join().unwrap();
+    }
+
+    #[rstest::rstest]
+    fn deadlock_is_detected(lock_manager: Arc<LockManager>) {
+        let first = lock_manager.new_session();
+        let second = lock_manager.new_session();
+        lock_manager
+            .lock(
+                first,
+                Resource::table("schema", "table_1"),
+                LockMode::AccessExclusive,
+                LockDuration::Transaction,
+            )
+            .expect("lock granted");
+        lock_manager
+            .lock(
+                second,
+                Resource::table("schema", "table_2"),
+                LockMode::AccessExclusive,
+                LockDuration::Transaction,
+            )
+            .expect("lock granted");
+
+        let (sender, receiver) = mpsc::channel();
+        let manager = lock_manager.clone();
+        let handle = thread::spawn(move || {
+            let result = manager.lock(
+                first,
+                Resource::table("schema", "table_2"),
+                LockMode::AccessExclusive,
+                LockDuration::Transaction,
+            );
+            sender.send(result).unwrap();
+        });
+
+        assert!(receiver.recv_timeout(Duration::from_millis(100)).is_err());
+
+        assert_eq!(
+            lock_manager.lock(
+                second,
+                Resource::table("schema", "table_1"),
+                LockMode::AccessExclusive,
+                LockDuration::Transaction
+            ),
+            Err(LockError::Deadlock)
+        );
+
+        lock_manager.unlock_all(second);
+
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok(Ok(())));
+        handle.join().unwrap();
+    }
+}
@@ -13,7 +13,10 @@
 // limitations under the License.
 
 use kernel::{SystemError, SystemResult};
-use std::collections::{BTreeMap, HashMap};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::RwLock,
+};
 use storage::backend::{
     BackendStorage, CreateObjectError, DropObjectError, Key, NamespaceAlreadyExists, NamespaceDoesNotExist,
     OperationOnObjectError, ReadCursor, Result, Row, Values,
@@ -31,22 +34,22 @@ struct Namespace {
 
 #[derive(Default)]
 pub struct InMemoryStorage {
-    namespaces: HashMap<String, Namespace>,
+    namespaces: RwLock<HashMap<String, Namespace>>,
 }
 
 impl BackendStorage for InMemoryStorage {
     type ErrorMapper = storage::backend::SledErrorMapper;
 
     fn create_namespace_with_objects(
-        &mut self,
+        &self,
         namespace: &str,
         object_names: Vec<&str>,
     ) -> SystemResult<Result<(), NamespaceAlreadyExists>> {
-        if self.namespaces.contains_key(namespace) {
+        let mut namespaces = self.namespaces.write().unwrap();
+        if namespaces.contains_key(namespace) {
             Ok(Err(NamespaceAlreadyExists))
         } else {
-            let namespace = self
-                .namespaces
+            let namespace = namespaces
                 .entry(namespace.to_owned())
                 .or_insert_with(Namespace::default);
 
@@ -59,24 +62,25 @@ impl BackendStorage for InMemoryStorage {
         }
     }
 
-    fn create_namespace(&mut self, namespace: &str) -> SystemResult<Result<(), NamespaceAlreadyExists>> {
-        if self.namespaces.contains_key(namespace) {
+    fn create_namespace(&self, namespace: &str) -> SystemResult<Result<(), NamespaceAlreadyExists>> {
+        let mut namespaces = self.namespaces.write().unwrap();
+        if namespaces.contains_key(namespace) {
             Ok(Err(NamespaceAlreadyExists))
         } else {
-            self.namespaces.insert(namespace.to_owned(), Namespace::default());
+            namespaces.insert(namespace.to_owned(), Namespace::default());
             Ok(Ok(()))
         }
     }
 
-    fn drop_namespace(&mut self, namespace: &str) -> SystemResult<Result<(), NamespaceDoesNotExist>> {
-        match self.namespaces.remove(namespace) {
+    fn drop_namespace(&self, namespace: &str) -> SystemResult<Result<(), NamespaceDoesNotExist>> {
+        match self.namespaces.write().unwrap().remove(namespace) {
             Some(_namespace) => Ok(Ok(())),
             None => Ok(Err(NamespaceDoesNotExist)),
         }
     }
 
-    fn create_object(&mut self, namespace: &str, object_name: &str) -> SystemResult<Result<(), CreateObjectError>> {
-        match self.namespaces.get_mut(namespace) {
+    fn create_object(&self, namespace: &str, object_name: &str) -> SystemResult<Result<(), CreateObjectError>> {
+        match self.namespaces.write().unwrap().get_mut(namespace) {
             Some(namespace) => {
                 if namespace.objects.contains_key(object_name) {
                     Ok(Err(CreateObjectError::ObjectAlreadyExists))
@@ -91,8 +95,8 @@ impl BackendStorage for InMemoryStorage {
         }
     }
 
-    fn drop_object(&mut self, namespace: &str, object_name: &str) -> SystemResult<Result<(), DropObjectError>> {
-        match self.namespaces.get_mut(namespace) {
+    fn drop_object(&self, namespace: &str, object_name: &str) -> SystemResult<Result<(), DropObjectError>> {
+        match self.namespaces.write().unwrap().get_mut(namespace) {
             Some(namespace) => match namespace.objects.remove(object_name) {
                 Some(_) => Ok(Ok(())),
                 None => Ok(Err(DropObjectError::ObjectDoesNotExist)),
@@ -102,12 +106,12 @@ impl BackendStorage for InMemoryStorage {
     }
 
     fn write(
-        &mut self,
+        &self,
         namespace: &str,
         object_name: &str,
         rows: Vec<(Key, Values)>,
     ) -> SystemResult<Result<usize, OperationOnObjectError>> {
-        match self.namespaces.get_mut(namespace) {
+        match self.namespaces.write().unwrap().get_mut(namespace) {
             Some(namespace) => match namespace.objects.get_mut(object_name) {
                 Some(object) => {
                     let len = rows.len();
@@ -123,7 +127,7 @@ impl BackendStorage for InMemoryStorage {
     }
 
     fn read(&self, namespace: &str, object_name: &str) -> SystemResult<Result<ReadCursor, OperationOnObjectError>> {
-        match self.namespaces.get(namespace) {
+        match self.namespaces.read().unwrap().get(namespace) {
             Some(namespace) => match namespace.objects.get(object_name) {
                 Some(object) => Ok(Ok(Box::new(
                     object
@@ -141,12 +145,12 @@ impl BackendStorage for InMemoryStorage {
     }
 
     fn delete(
-        &mut self,
+        &self,
         namespace: &str,
         object_name: &str,
         keys: Vec<Key>,
     ) -> SystemResult<Result<usize, OperationOnObjectError>> {
-        match self.namespaces.get_mut(namespace) {
+        match self.namespaces.write().unwrap().get_mut(namespace) {
             Some(namespace) => match namespace.objects.get_mut(object_name) {
                 Some(object) => {
                     object.records = object
@@ -164,11 +168,15 @@ impl BackendStorage for InMemoryStorage {
     }
 
     fn is_table_exists(&self, namespace: &str, object_name: &str) -> bool {
-        match self.namespaces.get(namespace) {
+        match self.namespaces.read().unwrap().get(namespace) {
             Some(namespace) => namespace.objects.contains_key(object_name),
             None => false,
         }
     }
+
+    fn is_namespace_exists(&self, namespace: &str) -> bool {
+        self.namespaces.read().unwrap().contains_key(namespace)
+    }
 }
 
 #[cfg(test)]
@@ -183,7 +191,7 @@ mod tests {
     }
 
     #[rstest::fixture]
-    fn with_namespace(mut storage: Storage) -> Storage {
+    fn with_namespace(storage: Storage) -> Storage {
         storage
             .create_namespace("namespace")
             .expect("no system errors")
@@ -192,7 +200,7 @@ mod tests {
     }
 
     #[rstest::fixture]
-    fn with_object(mut with_namespace: Storage) -> Storage {
+    fn with_object(with_namespace: Storage) -> Storage {
         with_namespace
             .create_object("namespace", "object_name")
             .expect("no system errors")
@@ -205,7 +213,7 @@ mod tests {
         use super::*;
 
         #[rstest::rstest]
-        fn create_namespace_with_objects(mut storage: Storage) {
+        fn create_namespace_with_objects(storage: Storage) {
             assert_eq!(
                 storage
                     .create_namespace_with_objects("namespace", vec!["object_1", "object_2"])
@@ -228,7 +236,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn create_namespace_with_objects_that_already_exists(mut with_namespace: Storage) {
+        fn create_namespace_with_objects_that_already_exists(with_namespace: Storage) {
             assert_eq!(
                 with_namespace
                     .create_namespace_with_objects("namespace", vec!["object_1", "object_2"])
@@ -238,7 +246,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn create_namespaces_with_different_names(mut storage: Storage) {
+        fn create_namespaces_with_different_names(storage: Storage) {
             assert_eq!(
                 storage.create_namespace("namespace_1").expect("namespace created"),
                 Ok(())
@@ -250,7 +258,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn create_namespace_with_existing_name(mut with_namespace: Storage) {
+        fn create_namespace_with_existing_name(with_namespace: Storage) {
             assert_eq!(
                 with_namespace.create_namespace("namespace").expect("no system errors"),
                 Err(NamespaceAlreadyExists)
@@ -258,7 +266,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn drop_namespace(mut with_namespace: Storage) {
+        fn drop_namespace(with_namespace: Storage) {
             assert_eq!(
                 with_namespace.drop_namespace("namespace").expect("namespace dropped"),
                 Ok(())
@@ -270,7 +278,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn drop_namespace_that_was_not_created(mut storage: Storage) {
+        fn drop_namespace_that_was_not_created(storage: Storage) {
             assert_eq!(
                 storage.drop_namespace("does_not_exists").expect("no system errors"),
                 Err(NamespaceDoesNotExist)
@@ -278,7 +286,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn dropping_namespace_drops_objects_in_it(mut with_namespace: Storage) {
+        fn dropping_namespace_drops_objects_in_it(with_namespace: Storage) {
             with_namespace
                 .create_object("namespace", "object_name_1")
                 .expect("no system errors")
@@ -316,7 +324,7 @@ mod tests {
         use super::*;
 
         #[rstest::rstest]
-        fn create_objects_with_different_names(mut with_namespace: Storage) {
+        fn create_objects_with_different_names(with_namespace: Storage) {
             assert_eq!(
                 with_namespace
                     .create_object("namespace", "object_name_1")
@@ -332,7 +340,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn create_object_with_the_same_name(mut with_namespace: Storage) {
+        fn create_object_with_the_same_name(with_namespace: Storage) {
             with_namespace
                 .create_object("namespace", "object_name")
                 .expect("no system errors")
@@ -347,7 +355,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn create_object_with_the_same_name_in_different_namespaces(mut storage: Storage) {
+        fn create_object_with_the_same_name_in_different_namespaces(storage: Storage) {
             storage
                 .create_namespace("namespace_1")
                 .expect("no system errors")
@@ -371,7 +379,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn create_object_in_not_existent_namespace(mut storage: Storage) {
+        fn create_object_in_not_existent_namespace(storage: Storage) {
             assert_eq!(
                 storage
                     .create_object("not_existent", "object_name")
@@ -386,7 +394,7 @@ mod tests {
         use super::*;
 
         #[rstest::rstest]
-        fn drop_object(mut with_object: Storage) {
+        fn drop_object(with_object: Storage) {
             assert_eq!(
                 with_object
                     .drop_object("namespace", "object_name")
@@ -402,7 +410,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn drop_not_created_object(mut with_namespace: Storage) {
+        fn drop_not_created_object(with_namespace: Storage) {
             assert_eq!(
                 with_namespace
                     .drop_object("namespace", "not_existed_object")
@@ -412,7 +420,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn drop_object_in_not_existent_namespace(mut storage: Storage) {
+        fn drop_object_in_not_existent_namespace(storage: Storage) {
             assert_eq!(
                 storage.drop_object("not_existent", "object").expect("no system errors"),
                 Err(DropObjectError::NamespaceDoesNotExist)
@@ -425,7 +433,7 @@ mod tests {
         use super::*;
 
         #[rstest::rstest]
-        fn insert_row_into_object(mut with_object: Storage) {
+        fn insert_row_into_object(with_object: Storage) {
             assert_eq!(
                 with_object
                     .write("namespace", "object_name", as_rows(vec![(1u8, vec!["123"])],))
@@ -443,7 +451,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn insert_many_rows_into_object(mut with_object: Storage) {
+        fn insert_many_rows_into_object(with_object: Storage) {
             with_object
                 .write("namespace", "object_name", as_rows(vec![(1u8, vec!["123"])]))
                 .expect("no system errors")
@@ -463,7 +471,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn insert_into_non_existent_object(mut with_namespace: Storage) {
+        fn insert_into_non_existent_object(with_namespace: Storage) {
             assert_eq!(
                 with_namespace
                     .write("namespace", "not_existed", as_rows(vec![(1u8, vec!["123"])],))
@@ -473,7 +481,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn insert_into_object_in_non_existent_namespace(mut storage: Storage) {
+        fn insert_into_object_in_non_existent_namespace(storage: Storage) {
             assert_eq!(
                 storage
                     .write("not_existed", "object", as_rows(vec![(1u8, vec!["123"])],))
@@ -505,7 +513,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn delete_some_records_from_object(mut with_object: Storage) {
+        fn delete_some_records_from_object(with_object: Storage) {
             with_object
                 .write(
                     "namespace",
@@ -532,7 +540,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn delete_from_not_existed_object(mut with_namespace: Storage) {
+        fn delete_from_not_existed_object(with_namespace: Storage) {
             assert_eq!(
                 with_namespace
                     .delete("namespace", "not_existent", vec![])
@@ -542,7 +550,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn delete_from_not_existent_namespace(mut storage: Storage) {
+        fn delete_from_not_existent_namespace(storage: Storage) {
             assert_eq!(
                 storage
                     .delete("not existent", "object", vec![])
@@ -552,7 +560,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn select_all_from_object_with_many_columns(mut with_object: Storage) {
+        fn select_all_from_object_with_many_columns(with_object: Storage) {
             with_object
                 .write("namespace", "object_name", as_rows(vec![(1u8, vec!["1", "2", "3"])]))
                 .expect("no system errors")
@@ -568,7 +576,7 @@ mod tests {
         }
 
         #[rstest::rstest]
-        fn insert_multiple_rows(mut with_object: Storage) {
+        fn insert_multiple_rows(with_object: Storage) {
             with_object
                 .write(
                     "namespace",